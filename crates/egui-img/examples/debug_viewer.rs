@@ -1,16 +1,19 @@
-//! Debug helper to preview `egui-img` centering and optionally write a screenshot.
+//! Debug helper to preview `egui-img` centering and optionally write a screenshot, watch a
+//! file for changes, or open each image in a window of its own.
 
 #![allow(missing_docs)]
 
 use std::{env, path::PathBuf};
 
-use egui_img::{view_image, view_image_with_screenshot};
+use egui_img::Viewer;
 use image::ImageReader;
 
 fn main() -> anyhow::Result<()> {
     let mut args = env::args().skip(1).collect::<Vec<_>>();
     if args.is_empty() {
-        anyhow::bail!("usage: debug_viewer <image_path> [--screenshot <out.png>]");
+        anyhow::bail!(
+            "usage: debug_viewer <image_path>... [--screenshot <out.png> | --watch | --windows]"
+        );
     }
 
     let mut screenshot: Option<PathBuf> = None;
@@ -21,17 +24,37 @@ fn main() -> anyhow::Result<()> {
         screenshot = Some(PathBuf::from(args.remove(idx + 1)));
         args.remove(idx);
     }
+    let watch = args
+        .iter()
+        .position(|a| a == "--watch")
+        .map(|idx| args.remove(idx));
+    if watch.is_some() && args.len() != 1 {
+        anyhow::bail!("--watch takes exactly one image path");
+    }
+    if let Some(idx) = args.iter().position(|a| a == "--windows") {
+        args.remove(idx);
+        let viewers = args
+            .iter()
+            .map(|arg| Viewer::open(&PathBuf::from(arg)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        return egui_img::show_all(viewers);
+    }
 
-    let image_path = PathBuf::from(args.remove(0));
-    let title = image_path
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("viewer")
-        .to_string();
-    let image = ImageReader::open(&image_path)?.decode()?.to_rgba8();
-
-    match screenshot {
-        Some(path) => view_image_with_screenshot(&title, image, &path),
-        None => view_image(&title, image),
+    let mut viewer = Viewer::open(&PathBuf::from(&args[0]))?;
+    for arg in &args[1..] {
+        let image_path = PathBuf::from(arg);
+        let title = image_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("viewer")
+            .to_string();
+        viewer = viewer.image(title, ImageReader::open(&image_path)?.decode()?);
+    }
+    if let Some(path) = screenshot {
+        viewer = viewer.screenshot(path);
+    }
+    if watch.is_some() {
+        viewer = viewer.watch();
     }
+    viewer.show()
 }
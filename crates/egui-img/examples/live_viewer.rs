@@ -0,0 +1,36 @@
+//! Open a viewer and refine its image in place, as a long-running renderer would.
+
+#![allow(missing_docs)]
+
+use std::{thread, time::Duration};
+
+use egui_img::Viewer;
+use image::{Rgba, RgbaImage};
+
+/// Side length of the rendered image.
+const SIZE: u32 = 512;
+
+/// A checkerboard whose cells halve in size with each `level`.
+fn render(level: u32) -> RgbaImage {
+    let cell = (SIZE >> level).max(1);
+    RgbaImage::from_fn(SIZE, SIZE, |x, y| {
+        let shade = if (x / cell + y / cell).is_multiple_of(2) {
+            40
+        } else {
+            220
+        };
+        Rgba([shade, shade, shade, 255])
+    })
+}
+
+fn main() -> anyhow::Result<()> {
+    let viewer = Viewer::new(render(0)).title("live viewer").spawn()?;
+    for level in 1..=8 {
+        thread::sleep(Duration::from_millis(500));
+        if !viewer.is_open() {
+            break;
+        }
+        viewer.update_image(render(level))?;
+    }
+    viewer.wait()
+}
@@ -0,0 +1,129 @@
+//! Viewing a single color channel, or a grayscale version, of the image.
+//!
+//! Isolated channels are shown as opaque gray levels so that, for example, the alpha channel
+//! can be inspected without blending against the background.
+
+use egui::{Color32, ColorImage};
+
+/// Which part of the image's color is shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    /// The image as it is.
+    #[default]
+    All,
+    /// Red channel as gray levels.
+    Red,
+    /// Green channel as gray levels.
+    Green,
+    /// Blue channel as gray levels.
+    Blue,
+    /// Alpha channel as gray levels, opaque white for fully opaque pixels.
+    Alpha,
+    /// Luma of the color, keeping the alpha channel.
+    Gray,
+}
+
+impl Channel {
+    /// Every view, in button order.
+    const ALL: [Self; 6] = [
+        Self::All,
+        Self::Red,
+        Self::Green,
+        Self::Blue,
+        Self::Alpha,
+        Self::Gray,
+    ];
+
+    /// Button label.
+    fn label(self) -> &'static str {
+        match self {
+            Self::All => "RGBA",
+            Self::Red => "R",
+            Self::Green => "G",
+            Self::Blue => "B",
+            Self::Alpha => "A",
+            Self::Gray => "Gray",
+        }
+    }
+
+    /// Buttons selecting the view.
+    pub fn controls(&mut self, ui: &mut egui::Ui) {
+        for channel in Self::ALL {
+            ui.selectable_value(self, channel, channel.label());
+        }
+    }
+
+    /// `image` as this view shows it.
+    pub fn apply(self, image: &ColorImage) -> ColorImage {
+        if self == Self::All {
+            return image.clone();
+        }
+        let pixels = image
+            .pixels
+            .iter()
+            .map(|color| {
+                let [r, g, b, a] = color.to_srgba_unmultiplied();
+                match self {
+                    Self::All => *color,
+                    Self::Red => Color32::from_gray(r),
+                    Self::Green => Color32::from_gray(g),
+                    Self::Blue => Color32::from_gray(b),
+                    Self::Alpha => Color32::from_gray(a),
+                    Self::Gray => {
+                        let luma =
+                            0.2126 * f32::from(r) + 0.7152 * f32::from(g) + 0.0722 * f32::from(b);
+                        let luma = luma.round() as u8;
+                        Color32::from_rgba_unmultiplied(luma, luma, luma, a)
+                    }
+                }
+            })
+            .collect();
+        ColorImage::new(image.size, pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Opaque orange, translucent blue and opaque white.
+    fn image() -> ColorImage {
+        ColorImage::new(
+            [3, 1],
+            vec![
+                Color32::from_rgb(255, 128, 0),
+                Color32::from_rgba_unmultiplied(0, 0, 255, 64),
+                Color32::WHITE,
+            ],
+        )
+    }
+
+    #[test]
+    fn all_keeps_the_image() {
+        assert_eq!(Channel::All.apply(&image()), image());
+    }
+
+    #[test]
+    fn channels_become_opaque_gray_levels() {
+        let gray = |channel: Channel| -> Vec<Color32> { channel.apply(&image()).pixels };
+        let levels = |values: [u8; 3]| values.map(Color32::from_gray).to_vec();
+        assert_eq!(gray(Channel::Red), levels([255, 0, 255]));
+        assert_eq!(gray(Channel::Green), levels([128, 0, 255]));
+        assert_eq!(gray(Channel::Blue), levels([0, 255, 255]));
+        assert_eq!(gray(Channel::Alpha), levels([255, 64, 255]));
+    }
+
+    #[test]
+    fn gray_uses_luma_and_keeps_alpha() {
+        let shown = Channel::Gray.apply(&image());
+        assert_eq!(shown.size, [3, 1]);
+        // 0.2126 * 255 + 0.7152 * 128 ≈ 145.8.
+        assert_eq!(shown.pixels[0], Color32::from_gray(146));
+        // 0.0722 * 255 ≈ 18.4.
+        assert_eq!(
+            shown.pixels[1],
+            Color32::from_rgba_unmultiplied(18, 18, 18, 64)
+        );
+        assert_eq!(shown.pixels[2], Color32::WHITE);
+    }
+}
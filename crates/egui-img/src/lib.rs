@@ -1,46 +1,237 @@
 #![warn(missing_docs)]
 
-//! Tiny helper to show an RGBA image inside an egui window.
+//! Tiny helper to show RGBA images inside an egui window.
+
+/// Single-channel and grayscale views.
+mod channel;
+/// Thumbnail for navigating large images.
+mod minimap;
+/// Pixel grid, rulers and pointer guides.
+mod overlay;
+/// Rectangular selection and crop export.
+mod selection;
+/// Exposure and gamma for deep images.
+mod tone;
+/// Reloading images from watched files.
+mod watch;
 
 use std::{
     fs::File,
     io::BufWriter,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::{self, JoinHandle},
 };
 
 use anyhow::{Result, anyhow};
 use eframe::{NativeOptions, egui};
-use egui::{Vec2, load::SizedTexture};
-use image::RgbaImage;
+use egui::{Key, Pos2, Rect, Sense, Vec2};
+use image::{DynamicImage, ImageBuffer, Rgba, Rgba32FImage, RgbaImage};
 use png::{BitDepth, ColorType, Encoder};
 
-/// Simple egui app that shows a single texture with a zoom slider.
-struct ImageViewer {
+use crate::{
+    channel::Channel,
+    minimap::Minimap,
+    overlay::Overlays,
+    selection::{Selection, crop_file_name},
+    tone::{Tone, WideImage},
+    watch::Watch,
+};
+
+/// An image the viewer can show: 8-bit, 16-bit or floating-point RGBA.
+///
+/// 8-bit and 16-bit images are taken to be gamma-encoded, floating-point images to be linear
+/// light. Deeper images get exposure and gamma controls, starting from settings that show them
+/// as intended. Every viewer function accepts any type converting into this one.
+pub enum ViewerImage {
+    /// 8 bits per channel.
+    Rgba8(RgbaImage),
+    /// 16 bits per channel.
+    Rgba16(ImageBuffer<Rgba<u16>, Vec<u16>>),
+    /// 32-bit floats per channel, where 1.0 is full intensity.
+    Rgba32F(Rgba32FImage),
+}
+
+impl From<RgbaImage> for ViewerImage {
+    fn from(image: RgbaImage) -> Self {
+        Self::Rgba8(image)
+    }
+}
+
+impl From<ImageBuffer<Rgba<u16>, Vec<u16>>> for ViewerImage {
+    fn from(image: ImageBuffer<Rgba<u16>, Vec<u16>>) -> Self {
+        Self::Rgba16(image)
+    }
+}
+
+impl From<Rgba32FImage> for ViewerImage {
+    fn from(image: Rgba32FImage) -> Self {
+        Self::Rgba32F(image)
+    }
+}
+
+impl From<DynamicImage> for ViewerImage {
+    /// Keep the precision of the decoded image, widening its color type to RGBA.
+    fn from(image: DynamicImage) -> Self {
+        match image {
+            DynamicImage::ImageRgba8(image) => Self::Rgba8(image),
+            DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_) => Self::Rgba16(image.to_rgba16()),
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+                Self::Rgba32F(image.to_rgba32f())
+            }
+            _ => Self::Rgba8(image.to_rgba8()),
+        }
+    }
+}
+
+/// Pixels of a [`ViewerImage`] as the viewer keeps them.
+enum Pixels {
+    /// 8-bit pixels, displayed as they are.
+    Display(egui::ColorImage),
+    /// Deeper pixels, displayed through a [`Tone`].
+    Wide(WideImage),
+}
+
+impl ViewerImage {
+    /// Width and height in pixels.
+    fn size(&self) -> [usize; 2] {
+        let (width, height) = match self {
+            Self::Rgba8(image) => image.dimensions(),
+            Self::Rgba16(image) => image.dimensions(),
+            Self::Rgba32F(image) => image.dimensions(),
+        };
+        [width as usize, height as usize]
+    }
+
+    /// Convert into the viewer's representation.
+    fn into_pixels(self) -> Pixels {
+        match self {
+            Self::Rgba8(image) => Pixels::Display(color_image(&image)),
+            Self::Rgba16(image) => Pixels::Wide(WideImage::from_rgba16(&image)),
+            Self::Rgba32F(image) => Pixels::Wide(WideImage::from_rgba32f(&image)),
+        }
+    }
+}
+
+/// One image of the viewer with its own zoom and pan.
+struct Picture {
+    /// Title shown in the header and on the image's tab.
+    title: String,
     /// Texture containing the displayed image.
     texture: egui::TextureHandle,
+    /// The image as given, or tone mapped from `wide`, from which the texture is built for
+    /// each channel view.
+    source: egui::ColorImage,
+    /// Full-range pixels of 16-bit and floating-point images.
+    wide: Option<WideImage>,
+    /// Exposure and gamma used to display `wide`.
+    tone: Tone,
+    /// Channel view the texture currently holds.
+    channel: Channel,
     /// Pixel dimensions of the image.
     image_size: [usize; 2],
     /// Current zoom multiplier.
     zoom: f32,
+    /// Offset of the image center from the canvas center, in points.
+    pan: Vec2,
     /// Default zoom used for reset.
     base_zoom: f32,
+    /// How the zoom follows the canvas size.
+    mode: ZoomMode,
+    /// Selected pixels, kept across zoom and pan.
+    selection: Selection,
+}
+
+/// How a picture's zoom is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ZoomMode {
+    /// Show the whole image, with padding, at the largest zoom that fits the canvas.
+    Fit,
+    /// One image pixel per screen pixel.
+    Actual,
+    /// Cover the whole canvas, cropping the image along one axis.
+    Fill,
+    /// Whatever zoom the slider, wheel or keys last set.
+    Manual,
+}
+
+impl ZoomMode {
+    /// Modes offered as buttons, with their labels and shortcuts.
+    const BUTTONS: [(Self, &'static str, Option<&'static str>); 3] = [
+        (Self::Fit, "Fit", Some("F")),
+        (Self::Actual, "1:1", Some("1")),
+        (Self::Fill, "Fill", None),
+    ];
+
+    /// Zoom for an image of `image` pixels on a canvas of `canvas` points, or `None` when the
+    /// zoom is set by hand.
+    fn zoom(self, image: Vec2, canvas: Vec2, pixels_per_point: f32) -> Option<f32> {
+        let zoom = match self {
+            Self::Fit => {
+                let room = (canvas - Vec2::splat(PADDING_PX * 2.0)).max(Vec2::splat(1.0));
+                (room / image).min_elem()
+            }
+            Self::Actual => 1.0 / pixels_per_point,
+            Self::Fill => (canvas / image).max_elem(),
+            Self::Manual => return None,
+        };
+        Some(zoom.clamp(MIN_ZOOM, MAX_ZOOM))
+    }
+}
+
+/// Simple egui app that shows one or more textures, one at a time behind a tab bar; drag to
+/// pan and scroll to zoom at the cursor.
+struct ImageViewer {
+    /// Images in tab order.
+    pictures: Vec<Picture>,
+    /// Index of the image on screen.
+    current: usize,
+    /// Overlays drawn over every image.
+    overlays: Overlays,
+    /// Channel view applied to every image.
+    channel: Channel,
     /// Optional screenshot capture state.
     screenshot: Option<ScreenshotState>,
-    /// Window title shown in the header.
-    title: String,
+    /// Replacement images for the first picture, sent by a [`ViewerHandle`].
+    updates: Option<Receiver<ViewerImage>>,
+    /// File the first picture is reloaded from when it changes.
+    watch: Option<Watch>,
+    /// Zoom settings for images that arrive after the window opens.
+    sizing: Sizing,
+}
+
+/// Channels connecting a viewer thread to its [`ViewerHandle`].
+struct Live {
+    /// Replacement images sent by the handle.
+    images: Receiver<ViewerImage>,
+    /// Receives the viewer's context once the window exists, so the handle can wake it.
+    context: Sender<egui::Context>,
 }
 
 /// Layout constants for the viewer window.
 const PADDING_PX: f32 = 24.0;
 /// Minimum window size used for the viewer.
 const MIN_WINDOW: Vec2 = Vec2::new(320.0, 240.0);
-/// Maximum window size used for the viewer.
+/// Default maximum window size used for the viewer.
 const MAX_WINDOW: Vec2 = Vec2::new(1200.0, 900.0);
 /// Estimated vertical chrome (heading + controls) reserved in the window.
 const UI_OVERHEAD_PX: f32 = 120.0;
 /// Horizontal chrome allowance (panel padding/scrollbar reserve).
 const UI_OVERHEAD_X_PX: f32 = 24.0;
+/// Smallest zoom multiplier.
+const MIN_ZOOM: f32 = 0.1;
+/// Largest zoom multiplier.
+const MAX_ZOOM: f32 = 8.0;
+/// Zoom change per point of mouse-wheel scroll, as an exponent.
+const WHEEL_ZOOM_RATE: f32 = 0.002;
+/// Zoom factor of one press of the zoom in and out keys.
+const KEY_ZOOM_STEP: f32 = 1.25;
 
 /// Tracks pending screenshot capture for the debug helper.
 #[derive(Clone)]
@@ -51,50 +242,89 @@ struct ScreenshotState {
     output_path: PathBuf,
 }
 
-/// Compute initial zoom and window size that keep a gap around the image while respecting caps.
-fn initial_view(image_size: [usize; 2]) -> (f32, Vec2) {
-    let img = Vec2::new(image_size[0] as f32, image_size[1] as f32);
-    let usable_max = Vec2::new(
-        (MAX_WINDOW.x - PADDING_PX * 2.0 - UI_OVERHEAD_X_PX).max(1.0),
-        (MAX_WINDOW.y - PADDING_PX * 2.0 - UI_OVERHEAD_PX).max(1.0),
-    );
+/// Initial zoom and window size limits, set through [`Viewer`].
+#[derive(Debug, Clone, Copy)]
+struct Sizing {
+    /// Zoom images open at, instead of the largest that fits the window.
+    zoom: Option<f32>,
+    /// Largest initial window size.
+    max_window: Vec2,
+}
 
-    let fit_zoom = (usable_max.x / img.x)
-        .min(usable_max.y / img.y)
-        .clamp(0.1, 1.0);
+impl Default for Sizing {
+    fn default() -> Self {
+        Self {
+            zoom: None,
+            max_window: MAX_WINDOW,
+        }
+    }
+}
+
+impl Sizing {
+    /// Compute initial zoom and window size that keep a gap around the image while respecting
+    /// caps.
+    fn initial_view(self, image_size: [usize; 2]) -> (f32, Vec2) {
+        let img = Vec2::new(image_size[0] as f32, image_size[1] as f32);
+        let max_window = self.max_window;
+        let usable_max = Vec2::new(
+            (max_window.x - PADDING_PX * 2.0 - UI_OVERHEAD_X_PX).max(1.0),
+            (max_window.y - PADDING_PX * 2.0 - UI_OVERHEAD_PX).max(1.0),
+        );
+
+        let zoom = self.zoom.unwrap_or_else(|| {
+            (usable_max.x / img.x)
+                .min(usable_max.y / img.y)
+                .clamp(0.1, 1.0)
+        });
 
-    let window = Vec2::new(
-        (img.x * fit_zoom + PADDING_PX * 2.0 + UI_OVERHEAD_X_PX).clamp(MIN_WINDOW.x, MAX_WINDOW.x),
-        (img.y * fit_zoom + PADDING_PX * 2.0 + UI_OVERHEAD_PX).clamp(MIN_WINDOW.y, MAX_WINDOW.y),
-    );
+        let window = Vec2::new(
+            (img.x * zoom + PADDING_PX * 2.0 + UI_OVERHEAD_X_PX).clamp(MIN_WINDOW.x, max_window.x),
+            (img.y * zoom + PADDING_PX * 2.0 + UI_OVERHEAD_PX).clamp(MIN_WINDOW.y, max_window.y),
+        );
 
-    (fit_zoom, window)
+        (zoom, window)
+    }
+
+    /// Suggest a window size that stays within the caps and fits the largest of the images.
+    fn window_size(self, image_sizes: &[[usize; 2]]) -> Vec2 {
+        image_sizes
+            .iter()
+            .map(|&size| self.initial_view(size).1)
+            .fold(MIN_WINDOW, Vec2::max)
+    }
 }
 
-impl ImageViewer {
-    /// Create an `ImageViewer` by uploading the provided `ColorImage` to a texture.
-    fn new(
-        cc: &eframe::CreationContext<'_>,
-        title: String,
-        color_image: egui::ColorImage,
-        screenshot: Option<PathBuf>,
-    ) -> Self {
+impl Picture {
+    /// Upload `image` to a texture, zoomed as `sizing` chooses.
+    fn new(ctx: &egui::Context, title: String, image: ViewerImage, sizing: Sizing) -> Self {
+        let (color_image, wide, tone) = match image.into_pixels() {
+            Pixels::Display(color_image) => (color_image, None, Tone::default()),
+            Pixels::Wide(wide) => {
+                let tone = Tone::for_image(&wide);
+                (tone.apply(&wide), Some(wide), tone)
+            }
+        };
         let image_size = color_image.size;
-        let (base_zoom, _) = initial_view(image_size);
-        let texture =
-            cc.egui_ctx
-                .load_texture(title.clone(), color_image, egui::TextureOptions::NEAREST);
+        let (base_zoom, _) = sizing.initial_view(image_size);
+        let texture = ctx.load_texture(
+            title.clone(),
+            color_image.clone(),
+            egui::TextureOptions::NEAREST,
+        );
 
         Self {
+            title,
             texture,
+            source: color_image,
+            wide,
+            tone,
+            channel: Channel::All,
             image_size,
             zoom: base_zoom,
+            pan: Vec2::ZERO,
             base_zoom,
-            screenshot: screenshot.map(|output_path| ScreenshotState {
-                requested: false,
-                output_path,
-            }),
-            title,
+            mode: ZoomMode::Manual,
+            selection: Selection::default(),
         }
     }
 
@@ -106,15 +336,301 @@ impl ImageViewer {
         )
     }
 
-    /// Render the texture into the given `ui` at `display_size`.
-    fn paint_image(&self, ui: &mut egui::Ui, display_size: Vec2) {
-        let sized_texture = SizedTexture::from_handle(&self.texture);
+    /// Set the zoom to `zoom` by hand, keeping the image point under `anchor` in place on a
+    /// canvas centered at `center`.
+    fn zoom_to(&mut self, zoom: f32, anchor: Pos2, center: Pos2) {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        let image_center = center + self.pan;
+        let image_center = anchor + (image_center - anchor) * (zoom / self.zoom);
+        self.pan = image_center - center;
+        self.zoom = zoom;
+        self.mode = ZoomMode::Manual;
+    }
+
+    /// Set the zoom to `zoom` by hand, about the canvas center.
+    fn zoom_centered(&mut self, zoom: f32) {
+        self.zoom_to(zoom, Pos2::ZERO, Pos2::ZERO);
+    }
+
+    /// Switch to `mode`, centering the image.
+    fn set_mode(&mut self, mode: ZoomMode) {
+        self.mode = mode;
+        self.pan = Vec2::ZERO;
+    }
+
+    /// Return to the zoom the picture opened with.
+    fn reset(&mut self) {
+        self.zoom = self.base_zoom;
+        self.pan = Vec2::ZERO;
+        self.mode = ZoomMode::Manual;
+    }
+
+    /// Apply shortcuts pressed this frame: F fits, 1 shows actual pixels, + and − step the
+    /// zoom, 0 resets and Escape clears the selection.
+    fn shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        self.selection.shortcuts(ctx);
+        let pressed = |keys: &[Key]| ctx.input(|i| keys.iter().any(|&key| i.key_pressed(key)));
+        if pressed(&[Key::F]) {
+            self.set_mode(ZoomMode::Fit);
+        }
+        if pressed(&[Key::Num1]) {
+            self.set_mode(ZoomMode::Actual);
+        }
+        if pressed(&[Key::Plus, Key::Equals]) {
+            self.zoom_centered(self.zoom * KEY_ZOOM_STEP);
+        }
+        if pressed(&[Key::Minus]) {
+            self.zoom_centered(self.zoom / KEY_ZOOM_STEP);
+        }
+        if pressed(&[Key::Num0]) {
+            self.reset();
+        }
+    }
+
+    /// Keep at least `PADDING_PX` of the image inside `canvas`.
+    fn clamp_pan(&mut self, canvas: Rect) {
+        let limit =
+            ((self.display_size() + canvas.size()) / 2.0 - Vec2::splat(PADDING_PX)).max(Vec2::ZERO);
+        self.pan = self.pan.clamp(-limit, limit);
+    }
+
+    /// Show `image` in place of the current image, keeping the zoom, pan and, when the new
+    /// image is as deep as the old one, the tone settings. A new size resets the zoom `sizing`
+    /// returns to.
+    fn replace(&mut self, image: ViewerImage, sizing: Sizing) {
+        let color_image = match image.into_pixels() {
+            Pixels::Display(color_image) => {
+                self.wide = None;
+                color_image
+            }
+            Pixels::Wide(wide) => {
+                if self.wide.is_none() {
+                    self.tone = Tone::for_image(&wide);
+                }
+                let color_image = self.tone.apply(&wide);
+                self.wide = Some(wide);
+                color_image
+            }
+        };
+        if color_image.size != self.image_size {
+            self.image_size = color_image.size;
+            self.base_zoom = sizing.initial_view(self.image_size).0;
+            self.selection.clear();
+        }
+        self.source = color_image;
+        self.upload();
+    }
+
+    /// Show `channel`, rebuilding the texture if it holds another view.
+    fn show_channel(&mut self, channel: Channel) {
+        if channel != self.channel {
+            self.channel = channel;
+            self.upload();
+        }
+    }
+
+    /// Rebuild the texture from the source image for the current channel view.
+    fn upload(&mut self) {
+        self.texture.set(
+            self.channel.apply(&self.source),
+            egui::TextureOptions::NEAREST,
+        );
+    }
+
+    /// Size readout, zoom slider, zoom mode buttons, reset button, overlay toggles, channel
+    /// views, tone settings for deep images and the selection's bounds and crop export.
+    fn controls(&mut self, ui: &mut egui::Ui, overlays: &mut Overlays, channel: &mut Channel) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(format!("{} × {}", self.image_size[0], self.image_size[1]));
+            let mut zoom = self.zoom;
+            let slider = egui::Slider::new(&mut zoom, MIN_ZOOM..=MAX_ZOOM)
+                .logarithmic(true)
+                .text("Zoom");
+            if ui.add(slider).changed() {
+                self.zoom_centered(zoom);
+            }
+            for (mode, label, key) in ZoomMode::BUTTONS {
+                let mut button = ui.selectable_label(self.mode == mode, label);
+                if let Some(key) = key {
+                    button = button.on_hover_text(format!("Shortcut: {key}"));
+                }
+                if button.clicked() {
+                    self.set_mode(mode);
+                }
+            }
+            if ui.button("Reset").on_hover_text("Shortcut: 0").clicked() {
+                self.reset();
+            }
+            ui.separator();
+            overlays.controls(ui);
+            ui.separator();
+            channel.controls(ui);
+            ui.separator();
+            if self.selection.region().is_some() {
+                if self.selection.controls(ui)
+                    && let Err(err) = self.save_crop()
+                {
+                    eprintln!("Failed to save crop: {err}");
+                }
+                ui.separator();
+            }
+            ui.label("Drag to pan, shift-drag to select, scroll or +/− to zoom");
+        });
+        if let Some(wide) = &self.wide
+            && ui
+                .horizontal(|ui| self.tone.controls(ui, Tone::for_image(wide)))
+                .inner
+        {
+            self.source = self.tone.apply(wide);
+            self.upload();
+        }
+    }
+
+    /// Ask for a destination and write the selected pixels of the image as given there.
+    ///
+    /// Does nothing when the dialog is cancelled or nothing is selected.
+    fn save_crop(&self) -> Result<()> {
+        let (Some(region), Some(crop)) =
+            (self.selection.region(), self.selection.crop(&self.source))
+        else {
+            return Ok(());
+        };
+        let dialog = rfd::FileDialog::new()
+            .set_file_name(crop_file_name(&self.title, region))
+            .add_filter("png", &["png"]);
+        if let Some(path) = dialog.save_file() {
+            save_color_image(&path, &crop)?;
+        }
+        Ok(())
+    }
+
+    /// Fill the rest of `ui` with the image, panned by dragging and zoomed by the mouse wheel
+    /// or a pinch around the pointer, with the selection, `overlays` and, when the image
+    /// overflows, a minimap on top. Shift-dragging selects pixels instead of panning. `debug` logs the layout for screenshot captures.
+    fn show_canvas(&mut self, ui: &mut egui::Ui, overlays: Overlays, debug: bool) {
+        let (canvas, response) = ui.allocate_exact_size(ui.available_size(), Sense::drag());
+        let center = canvas.center();
+        let image = Vec2::new(self.image_size[0] as f32, self.image_size[1] as f32);
+        if let Some(zoom) = self
+            .mode
+            .zoom(image, canvas.size(), ui.ctx().pixels_per_point())
+        {
+            self.zoom = zoom;
+        }
+        let image_rect = Rect::from_center_size(center + self.pan, self.display_size());
+        let selecting = self
+            .selection
+            .update(&response, image_rect, (self.image_size, self.zoom));
+        if response.dragged() && !selecting {
+            self.pan += response.drag_delta();
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = pinch * (scroll * WHEEL_ZOOM_RATE).exp();
+            if factor != 1.0 {
+                self.zoom_to(self.zoom * factor, pointer, center);
+            }
+        }
+        self.clamp_pan(canvas);
+        let minimap = Minimap::new(canvas, self.image_size, self.display_size());
+        if let Some(pan) = minimap
+            .as_ref()
+            .and_then(|minimap| minimap.drag(ui, self.image_size, self.zoom))
+        {
+            self.pan = pan;
+            self.clamp_pan(canvas);
+        }
+        if selecting {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+        } else if response.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+        } else if response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+        }
 
-        ui.add(
-            egui::Image::from_texture(sized_texture)
-                .texture_options(egui::TextureOptions::NEAREST)
-                .fit_to_exact_size(display_size),
+        let image_rect = Rect::from_center_size(center + self.pan, self.display_size());
+        if debug {
+            println!(
+                "[egui-img debug] canvas={:?} image={:?} zoom={:.3}",
+                canvas, image_rect, self.zoom
+            );
+        }
+        let painter = ui.painter_at(canvas);
+        painter.image(
+            self.texture.id(),
+            image_rect,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        self.selection.paint(&painter, image_rect, self.zoom);
+        overlays.paint(
+            &painter,
+            image_rect,
+            (self.image_size, self.zoom),
+            response.hover_pos(),
         );
+        if let Some(minimap) = minimap {
+            minimap.paint(&painter, self.texture.id(), (canvas, image_rect), self.zoom);
+        }
+    }
+}
+
+impl ImageViewer {
+    /// Create an `ImageViewer` by uploading each titled image to a texture, zoomed as `sizing`
+    /// chooses.
+    fn new(
+        ctx: &egui::Context,
+        images: Vec<(String, ViewerImage)>,
+        screenshot: Option<PathBuf>,
+        sizing: Sizing,
+    ) -> Self {
+        Self {
+            pictures: images
+                .into_iter()
+                .map(|(title, image)| Picture::new(ctx, title, image, sizing))
+                .collect(),
+            current: 0,
+            overlays: Overlays::default(),
+            channel: Channel::All,
+            screenshot: screenshot.map(|output_path| ScreenshotState {
+                requested: false,
+                output_path,
+            }),
+            updates: None,
+            watch: None,
+            sizing,
+        }
+    }
+
+    /// Tabs for switching images; the arrow keys step through them as well.
+    fn tab_bar(&mut self, ui: &mut egui::Ui) {
+        let count = self.pictures.len();
+        let (left, right) = ui.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowLeft),
+                i.key_pressed(egui::Key::ArrowRight),
+            )
+        });
+        if left {
+            self.current = (self.current + count - 1) % count;
+        }
+        if right {
+            self.current = (self.current + 1) % count;
+        }
+        ui.horizontal_wrapped(|ui| {
+            for (i, picture) in self.pictures.iter().enumerate() {
+                if ui
+                    .selectable_label(i == self.current, &picture.title)
+                    .clicked()
+                {
+                    self.current = i;
+                }
+            }
+        });
+        ui.separator();
     }
 
     /// Kick off and save a screenshot if configured. Returns true when capture completes.
@@ -151,176 +667,399 @@ impl ImageViewer {
         ctx.request_repaint();
         false
     }
-}
 
-impl eframe::App for ImageViewer {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let title = self.title.clone();
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if self.screenshot.is_none() {
-                ui.heading(&title);
-                ui.separator();
+    /// Take the newest image sent by a [`ViewerHandle`] or reloaded from the watched file.
+    fn poll(&mut self, ctx: &egui::Context) {
+        if let Some(image) = self.updates.as_ref().and_then(|rx| rx.try_iter().last()) {
+            self.pictures[0].replace(image, self.sizing);
+        }
+        if let Some(image) = self.watch.as_mut().and_then(|watch| watch.poll(ctx)) {
+            self.pictures[0].replace(image, self.sizing);
+        }
+    }
 
-                ui.horizontal(|ui| {
-                    ui.label(format!("{} × {}", self.image_size[0], self.image_size[1]));
-                    ui.add(
-                        egui::Slider::new(&mut self.zoom, 0.1..=8.0)
-                            .logarithmic(true)
-                            .text("Zoom"),
-                    );
-                    if ui.button("Reset").clicked() {
-                        self.zoom = self.base_zoom;
-                    }
-                });
+    /// Tabs, header, controls and canvas of the current image.
+    fn contents(&mut self, ui: &mut egui::Ui) {
+        let debug = self
+            .screenshot
+            .as_ref()
+            .is_some_and(|state| !state.requested);
+        if self.screenshot.is_none() && self.pictures.len() > 1 {
+            self.tab_bar(ui);
+        }
+        let picture = &mut self.pictures[self.current];
+        if self.screenshot.is_none() {
+            picture.shortcuts(ui.ctx());
+            self.overlays.shortcuts(ui.ctx());
+            ui.heading(&picture.title);
+            ui.separator();
+            picture.controls(ui, &mut self.overlays, &mut self.channel);
+            ui.separator();
+        }
 
-                ui.separator();
-            }
+        picture.show_channel(self.channel);
+        picture.show_canvas(ui, self.overlays, debug);
+    }
 
-            let display_size = self.display_size();
-            let padded_size = Vec2::new(
-                display_size.x + PADDING_PX * 2.0,
-                display_size.y + PADDING_PX * 2.0,
-            );
-            let available = ui.available_size();
-            let fits_without_scroll = padded_size.x <= available.x && padded_size.y <= available.y;
-
-            if fits_without_scroll {
-                if let Some(state) = &self.screenshot && !state.requested {
-                    println!(
-                        "[egui-img debug] available={:?} padded={:?} display={:?} (fits)",
-                        available, padded_size, display_size
-                    );
-                }
-                ui.allocate_ui_with_layout(
-                    available,
-                    egui::Layout::centered_and_justified(egui::Direction::TopDown),
-                    |ui| {
-                        ui.allocate_ui_with_layout(
-                            padded_size,
-                            egui::Layout::centered_and_justified(egui::Direction::TopDown),
-                            |ui| self.paint_image(ui, display_size),
-                        );
-                    },
-                );
+    /// Fill the viewport of `ctx` with the viewer.
+    fn show(&mut self, ctx: &egui::Context) {
+        self.poll(ctx);
+        egui::CentralPanel::default().show(ctx, |ui| self.contents(ui));
+        if let Some(watch) = &mut self.watch {
+            watch.show_toast(ctx);
+        }
+        let _ = self.handle_screenshot(ctx);
+    }
+}
+
+/// A viewer in a window of its own, beside the one the event loop was opened with.
+struct ExtraWindow {
+    /// Identifies the window's viewport across frames.
+    id: egui::ViewportId,
+    /// Title, size and decorations of the window.
+    builder: egui::ViewportBuilder,
+    /// The viewer drawn in the window.
+    viewer: ImageViewer,
+}
+
+impl ExtraWindow {
+    /// Draw the window, returning false once the user has closed it.
+    ///
+    /// Backends without multiple native windows show the viewer in a floating egui window
+    /// inside the main one instead.
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut open = true;
+        ctx.show_viewport_immediate(self.id, self.builder.clone(), |ctx, class| {
+            if class == egui::ViewportClass::Embedded {
+                self.viewer.poll(ctx);
+                let title = self.viewer.pictures[0].title.clone();
+                egui::Window::new(title)
+                    .id(egui::Id::new(self.id))
+                    .open(&mut open)
+                    .show(ctx, |ui| self.viewer.contents(ui));
             } else {
-                egui::ScrollArea::both()
-                    .auto_shrink([false, false])
-                    .show(ui, |ui| {
-                        let container = Vec2::new(
-                            padded_size.x.max(ui.available_width()),
-                            padded_size.y.max(ui.available_height()),
-                        );
-                        if let Some(state) = &self.screenshot && !state.requested {
-                            println!(
-                                "[egui-img debug] available={:?} padded={:?} display={:?} (scroll, container={:?})",
-                                available, padded_size, display_size, container
-                            );
-                        }
-                        ui.allocate_ui_with_layout(
-                            container,
-                            egui::Layout::centered_and_justified(egui::Direction::TopDown),
-                            |ui| self.paint_image(ui, display_size),
-                        );
-                    });
+                self.viewer.show(ctx);
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    open = false;
+                }
             }
         });
-
-        let _ = self.handle_screenshot(ctx);
+        open
     }
 }
 
-/// Suggest a window size that stays within a comfortable range for most screens.
-fn initial_window_size(image_size: [usize; 2]) -> Vec2 {
-    let (_, window) = initial_view(image_size);
-    window
+/// The viewers of one event loop: the main window and any extra windows still open.
+struct ViewerWindows {
+    /// Viewer filling the window the event loop was opened with.
+    main: ImageViewer,
+    /// Viewers in windows of their own.
+    extra: Vec<ExtraWindow>,
+}
+
+impl eframe::App for ViewerWindows {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.main.show(ctx);
+        self.extra.retain_mut(|window| window.show(ctx));
+    }
 }
 
-/// Show an RGBA image in a lightweight egui window.
+/// Title given to images that are not named.
+const DEFAULT_TITLE: &str = "image";
+
+/// Builder for a viewer window.
 ///
-/// This function blocks until the window is closed by the user.
-/// The image is uploaded with nearest‑neighbour sampling to keep pixels crisp.
-pub fn view_image(title: &str, image: RgbaImage) -> Result<()> {
-    let size = [image.width() as usize, image.height() as usize];
-    let mut color_image = Some(egui::ColorImage::from_rgba_unmultiplied(
-        size,
-        image.as_raw(),
-    ));
-    drop(image);
-    let window_title = title.to_string();
-    let app_title = window_title.clone();
-
-    let native_options = NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size(initial_window_size(size))
-            .with_title(window_title.clone()),
-        ..Default::default()
-    };
-
-    eframe::run_native(
-        &app_title,
-        native_options,
-        Box::new(move |cc| {
-            let color_image = color_image
-                .take()
-                .expect("image should only be consumed once");
-
-            Ok(Box::new(ImageViewer::new(
-                cc,
-                window_title.clone(),
-                color_image,
-                None,
-            )))
-        }),
-    )
-    .map_err(|err| anyhow!(err.to_string()))
-}
-
-/// View an image and emit a screenshot to `output` once the first frame is rendered.
+/// Start from an image with [`Viewer::new`] or a file with [`Viewer::open`], adjust the window
+/// and then [`show`](Self::show) it, blocking until it closes, or [`spawn`](Self::spawn) it on
+/// its own thread. Several viewers open side by side with [`show_all`]. Images are uploaded
+/// with nearest-neighbour sampling to keep pixels crisp.
+pub struct Viewer {
+    /// Titled images in tab order; the first also titles the window.
+    images: Vec<(String, ViewerImage)>,
+    /// Initial zoom and window size limits.
+    sizing: Sizing,
+    /// Whether the window has a title bar and borders.
+    decorations: bool,
+    /// Where to save a screenshot of the first frame, closing the window afterwards.
+    screenshot: Option<PathBuf>,
+    /// File the first image was loaded from.
+    file: Option<PathBuf>,
+    /// Whether to reload the first image when `file` changes.
+    watch: bool,
+}
+
+impl Viewer {
+    /// A viewer for `image`.
+    pub fn new(image: impl Into<ViewerImage>) -> Self {
+        Self {
+            images: vec![(DEFAULT_TITLE.to_string(), image.into())],
+            sizing: Sizing::default(),
+            decorations: true,
+            screenshot: None,
+            file: None,
+            watch: false,
+        }
+    }
+
+    /// A viewer for the image file at `path`, titled with its file name and keeping its bit
+    /// depth. Fails if the file cannot be decoded.
+    pub fn open(path: &Path) -> Result<Self> {
+        let title = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let mut viewer = Self::new(watch::load(path)?).title(title);
+        viewer.file = Some(path.to_path_buf());
+        Ok(viewer)
+    }
+
+    /// Title the window and the first image.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.images[0].0 = title.into();
+        self
+    }
+
+    /// Add another image on its own tab. With several images, a tab bar or the left and right
+    /// arrow keys switch between them; each keeps its own zoom and pan.
+    pub fn image(mut self, title: impl Into<String>, image: impl Into<ViewerImage>) -> Self {
+        self.images.push((title.into(), image.into()));
+        self
+    }
+
+    /// Open every image at `zoom` instead of the largest zoom that fits the window. This is
+    /// also the zoom the reset button returns to.
+    pub fn initial_zoom(mut self, zoom: f32) -> Self {
+        self.sizing.zoom = Some(zoom.clamp(MIN_ZOOM, MAX_ZOOM));
+        self
+    }
+
+    /// Cap the initial window size, in points; the window opens large enough for the largest
+    /// image up to this size. Defaults to 1200 × 900.
+    pub fn max_window(mut self, size: impl Into<Vec2>) -> Self {
+        self.sizing.max_window = size.into().max(MIN_WINDOW);
+        self
+    }
+
+    /// Whether the window has a title bar and borders. Defaults to true.
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Save a screenshot of the first frame to `path` as a PNG and close the window.
+    ///
+    /// This is intended for debugging layout/centering issues.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn screenshot(mut self, path: impl Into<PathBuf>) -> Self {
+        self.screenshot = Some(path.into());
+        self
+    }
+
+    /// Reload the image whenever its file changes, for viewers made with [`Viewer::open`].
+    ///
+    /// Each reload keeps the zoom and pan and is confirmed by a brief message at the bottom of
+    /// the window. A file that fails to decode, such as one caught half-written, leaves the
+    /// previous image on screen until the next change.
+    pub fn watch(mut self) -> Self {
+        self.watch = true;
+        self
+    }
+
+    /// Open the window and block until the user closes it.
+    pub fn show(self) -> Result<()> {
+        self.run(Vec::new(), None)
+    }
+
+    /// Open the window on a new thread and return a handle for replacing the first image.
+    ///
+    /// Unlike [`show`](Self::show), this returns as soon as the window is open, so a
+    /// long-running renderer can push progressively refined images with
+    /// [`ViewerHandle::update_image`]. The event loop runs off the main thread, which is only
+    /// supported on Linux and Windows.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn(self) -> Result<ViewerHandle> {
+        let (images, updates) = mpsc::channel();
+        let (context_tx, context_rx) = mpsc::channel();
+        let live = Live {
+            images: updates,
+            context: context_tx,
+        };
+        let thread = thread::Builder::new()
+            .name("egui-img viewer".to_string())
+            .spawn(move || self.run(Vec::new(), Some(live)))?;
+        match context_rx.recv() {
+            Ok(context) => Ok(ViewerHandle {
+                images,
+                context,
+                thread,
+            }),
+            // The viewer failed before opening its window; report why.
+            Err(_) => match thread.join() {
+                Ok(result) => Err(result
+                    .err()
+                    .unwrap_or_else(|| anyhow!("viewer closed early"))),
+                Err(_) => Err(anyhow!("viewer thread panicked")),
+            },
+        }
+    }
+
+    /// Title, initial size and decorations of the viewer's window.
+    fn viewport(&self) -> egui::ViewportBuilder {
+        let sizes: Vec<[usize; 2]> = self.images.iter().map(|(_, image)| image.size()).collect();
+        egui::ViewportBuilder::default()
+            .with_inner_size(self.sizing.window_size(&sizes))
+            .with_title(self.images[0].0.clone())
+            .with_decorations(self.decorations)
+    }
+
+    /// Upload the images to `ctx` and start watching the file, if asked to.
+    fn into_app(self, ctx: &egui::Context) -> ImageViewer {
+        let mut viewer = ImageViewer::new(ctx, self.images, self.screenshot, self.sizing);
+        viewer.watch = self
+            .file
+            .filter(|_| self.watch)
+            .map(|path| Watch::new(path, ctx));
+        viewer
+    }
+
+    /// Open the window, with each of `others` in a window of its own, and run them until the
+    /// first closes. With `live`, the windows may run off the main thread and the first takes
+    /// replacement images for its first picture from a [`ViewerHandle`].
+    fn run(self, others: Vec<Self>, live: Option<Live>) -> Result<()> {
+        let viewport = self.viewport();
+        let window_title = self.images[0].0.clone();
+
+        if let Some(path) = &self.screenshot {
+            println!(
+                "[egui-img debug] screenshot to {:?}, window {:?}, base_zoom {:.3}",
+                path,
+                viewport.inner_size,
+                self.sizing.initial_view(self.images[0].1.size()).0
+            );
+        }
+
+        let mut native_options = NativeOptions {
+            viewport,
+            ..Default::default()
+        };
+        if live.is_some() {
+            allow_any_thread(&mut native_options)?;
+        }
+
+        let mut pending = Some((self, others, live));
+        eframe::run_native(
+            &window_title,
+            native_options,
+            Box::new(move |cc| {
+                let (main, others, live) = pending
+                    .take()
+                    .expect("viewers should only be consumed once");
+                let mut viewer = main.into_app(&cc.egui_ctx);
+                if let Some(live) = live {
+                    live.context.send(cc.egui_ctx.clone()).ok();
+                    viewer.updates = Some(live.images);
+                }
+                let extra = others
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, other)| ExtraWindow {
+                        id: egui::ViewportId::from_hash_of(("egui-img window", i)),
+                        builder: other.viewport(),
+                        viewer: other.into_app(&cc.egui_ctx),
+                    })
+                    .collect();
+                Ok(Box::new(ViewerWindows {
+                    main: viewer,
+                    extra,
+                }))
+            }),
+        )
+        .map_err(|err| anyhow!(err.to_string()))
+    }
+}
+
+/// Open each viewer in a window of its own and block until the first one closes.
 ///
-/// This is intended for debugging layout/centering issues. The window closes after capture.
-#[cfg(not(target_arch = "wasm32"))]
-pub fn view_image_with_screenshot(title: &str, image: RgbaImage, output: &Path) -> Result<()> {
+/// Every window has its own images, zoom, overlays and controls, so renders can be compared
+/// side by side from one process. Extra windows may be closed individually; closing the first
+/// window closes them all. Fails when `viewers` is empty.
+pub fn show_all(viewers: impl IntoIterator<Item = Viewer>) -> Result<()> {
+    let mut viewers = viewers.into_iter();
+    let first = viewers
+        .next()
+        .ok_or_else(|| anyhow!("no viewers to show"))?;
+    first.run(viewers.collect(), None)
+}
+
+/// Let the event loop be created off the main thread.
+#[cfg(target_os = "linux")]
+fn allow_any_thread(options: &mut NativeOptions) -> Result<()> {
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    options.event_loop_builder = Some(Box::new(|builder| {
+        builder.with_any_thread(true);
+    }));
+    Ok(())
+}
+
+/// Let the event loop be created off the main thread.
+#[cfg(target_os = "windows")]
+fn allow_any_thread(options: &mut NativeOptions) -> Result<()> {
+    use winit::platform::windows::EventLoopBuilderExtWindows;
+
+    options.event_loop_builder = Some(Box::new(|builder| {
+        builder.with_any_thread(true);
+    }));
+    Ok(())
+}
+
+/// Other platforms, macOS in particular, only run event loops on the main thread.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn allow_any_thread(_options: &mut NativeOptions) -> Result<()> {
+    Err(anyhow!(
+        "the image viewer can only run off the main thread on Linux and Windows"
+    ))
+}
+
+/// Convert an 8-bit RGBA image into an egui `ColorImage`.
+fn color_image(image: &RgbaImage) -> egui::ColorImage {
     let size = [image.width() as usize, image.height() as usize];
-    let mut color_image = Some(egui::ColorImage::from_rgba_unmultiplied(
-        size,
-        image.as_raw(),
-    ));
-    let output_path = output.to_path_buf();
-    drop(image);
-    let window_title = title.to_string();
-    let app_title = window_title.clone();
-
-    let native_options = NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size(initial_window_size(size))
-            .with_title(window_title.clone()),
-        ..Default::default()
-    };
-
-    println!(
-        "[egui-img debug] screenshot to {:?}, window {:?}, base_zoom {:.3}",
-        output_path,
-        initial_window_size(size),
-        initial_view(size).0
-    );
-
-    eframe::run_native(
-        &app_title,
-        native_options,
-        Box::new(move |cc| {
-            let color_image = color_image
-                .take()
-                .expect("image should only be consumed once");
-
-            Ok(Box::new(ImageViewer::new(
-                cc,
-                window_title.clone(),
-                color_image,
-                Some(output_path.clone()),
-            )))
-        }),
-    )
-    .map_err(|err| anyhow!(err.to_string()))
+    egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw())
+}
+
+/// Handle to a viewer window running on its own thread, returned by [`Viewer::spawn`].
+pub struct ViewerHandle {
+    /// Replacement images for the viewer.
+    images: Sender<ViewerImage>,
+    /// The viewer's context, used to wake it when an image arrives.
+    context: egui::Context,
+    /// Thread running the viewer's event loop.
+    thread: JoinHandle<Result<()>>,
+}
+
+impl ViewerHandle {
+    /// Replace the image in the window, keeping the user's zoom and pan.
+    ///
+    /// Images sent faster than the window redraws are skipped in favour of the newest one.
+    /// Fails once the window has been closed.
+    pub fn update_image(&self, image: impl Into<ViewerImage>) -> Result<()> {
+        self.images
+            .send(image.into())
+            .map_err(|_| anyhow!("viewer window is closed"))?;
+        self.context.request_repaint();
+        Ok(())
+    }
+
+    /// Whether the window is still open.
+    pub fn is_open(&self) -> bool {
+        !self.thread.is_finished()
+    }
+
+    /// Block until the user closes the window.
+    pub fn wait(self) -> Result<()> {
+        self.thread
+            .join()
+            .map_err(|_| anyhow!("viewer thread panicked"))?
+    }
 }
 
 /// Persist an egui `ColorImage` to disk as a PNG file.
@@ -0,0 +1,88 @@
+//! Corner thumbnail of the whole image, marking the part on screen.
+//!
+//! The minimap only appears while the image overflows the canvas. Clicking or dragging on it
+//! centers the canvas on the point under the pointer.
+
+use egui::{Color32, Pos2, Rect, Sense, Stroke, TextureId, Vec2};
+
+/// Longest side of the minimap, in points.
+const SIZE: f32 = 160.0;
+/// Gap between the minimap and the canvas corner, in points.
+const MARGIN: f32 = 12.0;
+
+/// Where the minimap sits and how it scales the image.
+pub struct Minimap {
+    /// Area covered by the thumbnail.
+    rect: Rect,
+    /// Points of minimap per image pixel.
+    scale: f32,
+}
+
+impl Minimap {
+    /// The minimap in the bottom-right corner of `canvas` for an image of `image_size`
+    /// pixels drawn `display` points large, or `None` when the whole image is visible.
+    pub fn new(canvas: Rect, image_size: [usize; 2], display: Vec2) -> Option<Self> {
+        if display.x <= canvas.width() && display.y <= canvas.height() {
+            return None;
+        }
+        let image = Vec2::new(image_size[0] as f32, image_size[1] as f32);
+        let room = (canvas.size() / 3.0).min(Vec2::splat(SIZE));
+        let scale = (room / image).min_elem();
+        let size = image * scale;
+        let rect = Rect::from_min_size(canvas.max - size - Vec2::splat(MARGIN), size);
+        Some(Self { rect, scale })
+    }
+
+    /// Pan that centers the canvas on the image point under a click or drag on the minimap,
+    /// for an image shown at `zoom`.
+    pub fn drag(&self, ui: &egui::Ui, image_size: [usize; 2], zoom: f32) -> Option<Vec2> {
+        let response = ui.interact(
+            self.rect,
+            ui.id().with("egui-img minimap"),
+            Sense::click_and_drag(),
+        );
+        if response.hovered() || response.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Move);
+        }
+        if !(response.clicked() || response.dragged()) {
+            return None;
+        }
+        let pos = response.interact_pointer_pos()?;
+        let point = (self.rect.clamp(pos) - self.rect.min) / self.scale;
+        let half = Vec2::new(image_size[0] as f32, image_size[1] as f32) / 2.0;
+        Some((half - point) * zoom)
+    }
+
+    /// Draw the thumbnail from `texture` and outline the part of the image at `image_rect`,
+    /// scaled by `zoom`, that falls inside `canvas`.
+    pub fn paint(
+        &self,
+        painter: &egui::Painter,
+        texture: TextureId,
+        (canvas, image_rect): (Rect, Rect),
+        zoom: f32,
+    ) {
+        let visuals = &painter.ctx().style().visuals;
+        let frame = self.rect.expand(3.0);
+        painter.rect_filled(frame, 3.0, visuals.extreme_bg_color.gamma_multiply(0.9));
+        painter.image(
+            texture,
+            self.rect,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            Color32::WHITE,
+        );
+        painter.rect_stroke(
+            frame,
+            3.0,
+            Stroke::new(1.0, visuals.weak_text_color()),
+            egui::StrokeKind::Inside,
+        );
+
+        let to_minimap = |pos: Pos2| self.rect.min + (pos - image_rect.min) / zoom * self.scale;
+        let view =
+            Rect::from_min_max(to_minimap(canvas.min), to_minimap(canvas.max)).intersect(self.rect);
+        let color = visuals.selection.stroke.color;
+        painter.rect_filled(view, 0.0, color.gamma_multiply(0.15));
+        painter.rect_stroke(view, 0.0, Stroke::new(1.5, color), egui::StrokeKind::Inside);
+    }
+}
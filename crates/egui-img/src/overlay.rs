@@ -0,0 +1,244 @@
+//! Pixel grid, rulers and pointer guides drawn over the image.
+//!
+//! Everything is laid out in image space: grid lines sit on pixel boundaries, ruler ticks
+//! count image pixels from the top-left corner and the guides report the pixel under the
+//! pointer.
+
+use egui::{Align2, Color32, FontId, Key, Painter, Pos2, Rect, Stroke, Vec2, pos2, vec2};
+
+/// Smallest on-screen size of an image pixel, in points, at which the pixel grid is drawn.
+const GRID_MIN_CELL: f32 = 6.0;
+/// Thickness of the ruler strips, in points.
+const RULER_WIDTH: f32 = 28.0;
+/// Smallest spacing between labelled ruler ticks, in points.
+const RULER_LABEL_SPACING: f32 = 60.0;
+/// Length of labelled and unlabelled ruler ticks, in points.
+const TICK_LENGTHS: [f32; 2] = [10.0, 5.0];
+/// Size of the ruler and readout text.
+const LABEL_SIZE: f32 = 9.0;
+
+/// Which overlays are drawn.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Overlays {
+    /// Outline every pixel once they are large enough to tell apart.
+    pub grid: bool,
+    /// Rulers along the top and left edges, with guides through the pointer.
+    pub rulers: bool,
+}
+
+impl Overlays {
+    /// Toggle buttons for each overlay.
+    pub fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.grid, "Grid")
+            .on_hover_text("Pixel grid at high zoom. Shortcut: G");
+        ui.toggle_value(&mut self.rulers, "Rulers")
+            .on_hover_text("Rulers and pointer guides. Shortcut: R");
+    }
+
+    /// Apply the G and R toggles pressed this frame.
+    pub fn shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        ctx.input(|i| {
+            self.grid ^= i.key_pressed(Key::G);
+            self.rulers ^= i.key_pressed(Key::R);
+        });
+    }
+
+    /// Draw the enabled overlays for an image of `image_size` pixels shown at `image_rect`
+    /// and scaled by `zoom`, with the pointer at `pointer`.
+    pub fn paint(
+        self,
+        painter: &Painter,
+        image_rect: Rect,
+        (image_size, zoom): ([usize; 2], f32),
+        pointer: Option<Pos2>,
+    ) {
+        let space = ImageSpace {
+            rect: image_rect,
+            size: image_size,
+            zoom,
+        };
+        if self.grid && zoom >= GRID_MIN_CELL {
+            paint_grid(painter, &space);
+        }
+        if self.rulers {
+            let hovered = pointer.and_then(|p| space.pixel_at(p));
+            if let Some(pixel) = hovered {
+                paint_guides(painter, &space, pixel);
+            }
+            paint_rulers(painter, &space, hovered);
+        }
+    }
+}
+
+/// Mapping between image pixels and screen positions.
+struct ImageSpace {
+    /// Where the whole image is drawn.
+    rect: Rect,
+    /// Image size in pixels.
+    size: [usize; 2],
+    /// Points per image pixel.
+    zoom: f32,
+}
+
+impl ImageSpace {
+    /// Screen position of the pixel corner `(x, y)`.
+    fn screen(&self, x: f32, y: f32) -> Pos2 {
+        self.rect.min + vec2(x, y) * self.zoom
+    }
+
+    /// Screen coordinate along `axis` of pixel boundary `i`.
+    fn along(&self, axis: usize, i: usize) -> f32 {
+        self.rect.min[axis] + i as f32 * self.zoom
+    }
+
+    /// Pixel under the screen position `pos`, if it is on the image.
+    fn pixel_at(&self, pos: Pos2) -> Option<[usize; 2]> {
+        let p = (pos - self.rect.min) / self.zoom;
+        (p.x >= 0.0 && p.y >= 0.0 && (p.x as usize) < self.size[0] && (p.y as usize) < self.size[1])
+            .then_some([p.x as usize, p.y as usize])
+    }
+
+    /// Range of pixel boundaries along `axis` that fall inside the visible `clip` rect.
+    fn visible(&self, axis: usize, clip: Rect) -> (usize, usize) {
+        let (lo, hi, origin) = (clip.min[axis], clip.max[axis], self.rect.min[axis]);
+        let first = ((lo - origin) / self.zoom).floor().max(0.0) as usize;
+        let last = (((hi - origin) / self.zoom).ceil().max(0.0) as usize).min(self.size[axis]);
+        (first.min(last), last)
+    }
+}
+
+/// Color that stays visible over both light and dark pixels.
+fn contrast_line() -> Color32 {
+    Color32::from_rgba_unmultiplied(128, 128, 128, 110)
+}
+
+/// Lines along every visible pixel boundary.
+fn paint_grid(painter: &Painter, space: &ImageSpace) {
+    let clip = painter.clip_rect().intersect(space.rect);
+    let stroke = Stroke::new(1.0, contrast_line());
+    let (x0, x1) = space.visible(0, clip);
+    for x in x0..=x1 {
+        let x = space.along(0, x);
+        painter.line_segment([pos2(x, clip.min.y), pos2(x, clip.max.y)], stroke);
+    }
+    let (y0, y1) = space.visible(1, clip);
+    for y in y0..=y1 {
+        let y = space.along(1, y);
+        painter.line_segment([pos2(clip.min.x, y), pos2(clip.max.x, y)], stroke);
+    }
+}
+
+/// Lines through the hovered pixel and a readout of its coordinates.
+fn paint_guides(painter: &Painter, space: &ImageSpace, [x, y]: [usize; 2]) {
+    let clip = painter.clip_rect();
+    let stroke = Stroke::new(1.0, painter.ctx().style().visuals.selection.stroke.color);
+    let cell = Rect::from_min_max(
+        space.screen(x as f32, y as f32),
+        space.screen(x as f32 + 1.0, y as f32 + 1.0),
+    );
+    let center = cell.center();
+    painter.line_segment(
+        [pos2(center.x, clip.min.y), pos2(center.x, clip.max.y)],
+        stroke,
+    );
+    painter.line_segment(
+        [pos2(clip.min.x, center.y), pos2(clip.max.x, center.y)],
+        stroke,
+    );
+    painter.rect_stroke(cell, 0.0, stroke, egui::StrokeKind::Outside);
+
+    let visuals = &painter.ctx().style().visuals;
+    let text = painter.layout_no_wrap(
+        format!("{x}, {y}"),
+        FontId::monospace(LABEL_SIZE + 2.0),
+        visuals.strong_text_color(),
+    );
+    let pos = cell.right_bottom() + Vec2::splat(6.0);
+    let frame = Rect::from_min_size(pos, text.size()).expand(3.0);
+    painter.rect_filled(frame, 2.0, visuals.extreme_bg_color);
+    painter.galley(pos, text, Color32::PLACEHOLDER);
+}
+
+/// Rulers along the top and left edges of the canvas, marking the hovered pixel.
+fn paint_rulers(painter: &Painter, space: &ImageSpace, hovered: Option<[usize; 2]>) {
+    let clip = painter.clip_rect();
+    let visuals = painter.ctx().style().visuals.clone();
+    let top = Rect::from_min_max(clip.min, pos2(clip.max.x, clip.min.y + RULER_WIDTH));
+    let left = Rect::from_min_max(
+        pos2(clip.min.x, clip.min.y + RULER_WIDTH),
+        pos2(clip.min.x + RULER_WIDTH, clip.max.y),
+    );
+    let background = visuals.extreme_bg_color.gamma_multiply(0.9);
+    for strip in [top, left] {
+        painter.rect_filled(strip, 0.0, background);
+    }
+
+    let (step, minor) = ruler_steps(space.zoom);
+    let stroke = Stroke::new(1.0, visuals.weak_text_color());
+    let font = FontId::monospace(LABEL_SIZE);
+    for axis in 0..2 {
+        let strip = if axis == 0 { top } else { left };
+        let (first, last) = space.visible(axis, strip);
+        for i in (first.next_multiple_of(minor)..=last).step_by(minor) {
+            let at = space.along(axis, i);
+            let major = i % step == 0;
+            let length = TICK_LENGTHS[usize::from(!major)];
+            let (a, b, label_pos) = if axis == 0 {
+                (
+                    pos2(at, top.max.y - length),
+                    pos2(at, top.max.y),
+                    pos2(at + 2.0, top.min.y + 2.0),
+                )
+            } else {
+                (
+                    pos2(left.max.x - length, at),
+                    pos2(left.max.x, at),
+                    pos2(left.min.x + 2.0, at + 1.0),
+                )
+            };
+            if !strip.contains(a) && !strip.contains(b) {
+                continue;
+            }
+            painter.line_segment([a, b], stroke);
+            if major {
+                painter.text(
+                    label_pos,
+                    Align2::LEFT_TOP,
+                    i.to_string(),
+                    font.clone(),
+                    visuals.text_color(),
+                );
+            }
+        }
+    }
+
+    if let Some([x, y]) = hovered {
+        let mark = Stroke::new(2.0, visuals.selection.stroke.color);
+        let at = space.screen(x as f32 + 0.5, y as f32 + 0.5);
+        painter.line_segment([pos2(at.x, top.min.y), pos2(at.x, top.max.y)], mark);
+        painter.line_segment([pos2(left.min.x, at.y), pos2(left.max.x, at.y)], mark);
+    }
+}
+
+/// Pixel spacing of labelled ruler ticks and of the unlabelled ticks between them.
+///
+/// Labelled ticks step by 1, 2 or 5 times a power of ten, spaced at least
+/// [`RULER_LABEL_SPACING`] points apart at `zoom`.
+fn ruler_steps(zoom: f32) -> (usize, usize) {
+    let mut scale = 1;
+    let step = loop {
+        if let Some(step) = [1, 2, 5]
+            .into_iter()
+            .map(|m| m * scale)
+            .find(|&s| s as f32 * zoom >= RULER_LABEL_SPACING)
+        {
+            break step;
+        }
+        scale *= 10;
+    };
+    let minor = if step % 5 == 0 { step / 5 } else { step / 2 };
+    (step, minor.max(1))
+}
@@ -0,0 +1,169 @@
+//! Rectangular selection of image pixels, and cropping the image to it.
+//!
+//! The selection is kept in image pixels, so it stays on the same pixels while the view is
+//! panned and zoomed. Shift-dragging on the canvas draws a new selection; plain drags still
+//! pan.
+
+use egui::{Color32, ColorImage, Key, Painter, Pos2, Rect, Response, Stroke, vec2};
+
+/// A block of image pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    /// Top-left pixel.
+    pub min: [usize; 2],
+    /// Width and height in pixels, never zero.
+    pub size: [usize; 2],
+}
+
+impl Region {
+    /// The block spanning pixels `a` and `b`, both included.
+    fn spanning(a: [usize; 2], b: [usize; 2]) -> Self {
+        let min = [a[0].min(b[0]), a[1].min(b[1])];
+        let max = [a[0].max(b[0]), a[1].max(b[1])];
+        Self {
+            min,
+            size: [max[0] - min[0] + 1, max[1] - min[1] + 1],
+        }
+    }
+
+    /// Where the block is drawn when the image is shown at `image_rect`, scaled by `zoom`.
+    fn screen_rect(self, image_rect: Rect, zoom: f32) -> Rect {
+        let corner = |[x, y]: [usize; 2]| image_rect.min + vec2(x as f32, y as f32) * zoom;
+        Rect::from_min_max(
+            corner(self.min),
+            corner([self.min[0] + self.size[0], self.min[1] + self.size[1]]),
+        )
+    }
+}
+
+/// The current selection and the drag drawing it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Selection {
+    /// Pixel the selection drag started on, while it is in progress.
+    anchor: Option<[usize; 2]>,
+    /// Selected pixels.
+    region: Option<Region>,
+}
+
+impl Selection {
+    /// Drop the selection.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Clear the selection when Escape is pressed this frame.
+    pub fn shortcuts(&mut self, ctx: &egui::Context) {
+        if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(Key::Escape)) {
+            self.clear();
+        }
+    }
+
+    /// Follow a shift-drag on the canvas `response` over an image of `image_size` pixels
+    /// shown at `image_rect` and scaled by `zoom`. Returns whether the drag is drawing the
+    /// selection, in which case it should not pan.
+    pub fn update(
+        &mut self,
+        response: &Response,
+        image_rect: Rect,
+        (image_size, zoom): ([usize; 2], f32),
+    ) -> bool {
+        let pixel = |pos: Pos2| {
+            let p = (pos - image_rect.min) / zoom;
+            [
+                (p.x.max(0.0) as usize).min(image_size[0] - 1),
+                (p.y.max(0.0) as usize).min(image_size[1] - 1),
+            ]
+        };
+        let shift = response.ctx.input(|i| i.modifiers.shift);
+        if response.drag_started()
+            && shift
+            && let Some(pos) = response.interact_pointer_pos()
+        {
+            self.anchor = Some(pixel(pos));
+        }
+        let Some(anchor) = self.anchor else {
+            return false;
+        };
+        if let Some(pos) = response.interact_pointer_pos() {
+            self.region = Some(Region::spanning(anchor, pixel(pos)));
+        }
+        if !response.dragged() {
+            self.anchor = None;
+        }
+        true
+    }
+
+    /// Outline the selection on an image shown at `image_rect` and scaled by `zoom`.
+    pub fn paint(self, painter: &Painter, image_rect: Rect, zoom: f32) {
+        let Some(region) = self.region else {
+            return;
+        };
+        let rect = region.screen_rect(image_rect, zoom);
+        let color = painter.ctx().style().visuals.selection.stroke.color;
+        painter.rect_filled(rect, 0.0, color.gamma_multiply(0.15));
+        painter.rect_stroke(
+            rect,
+            0.0,
+            Stroke::new(1.5, color),
+            egui::StrokeKind::Outside,
+        );
+        painter.rect_stroke(
+            rect.expand(1.5),
+            0.0,
+            Stroke::new(1.0, Color32::from_black_alpha(140)),
+            egui::StrokeKind::Outside,
+        );
+    }
+
+    /// Selection bounds with buttons to save and clear it, if anything is selected. Returns
+    /// whether saving the crop was requested.
+    pub fn controls(&mut self, ui: &mut egui::Ui) -> bool {
+        let Some(region) = self.region else {
+            return false;
+        };
+        ui.monospace(format!(
+            "{}, {}  {} × {}",
+            region.min[0], region.min[1], region.size[0], region.size[1]
+        ))
+        .on_hover_text("Top-left pixel and size of the selection");
+        let save = ui
+            .button("Save crop…")
+            .on_hover_text("Write the selected pixels to a PNG")
+            .clicked();
+        if ui.button("Clear").on_hover_text("Shortcut: Esc").clicked() {
+            self.clear();
+        }
+        save
+    }
+
+    /// The selected pixels of `image`, if anything is selected.
+    pub fn crop(self, image: &ColorImage) -> Option<ColorImage> {
+        let Region { min, size } = self.region?;
+        let pixels = (min[1]..min[1] + size[1])
+            .flat_map(|y| {
+                let row = y * image.size[0] + min[0];
+                image.pixels[row..row + size[0]].iter().copied()
+            })
+            .collect();
+        Some(ColorImage::new(size, pixels))
+    }
+
+    /// Selected pixels, if any.
+    pub fn region(self) -> Option<Region> {
+        self.region
+    }
+}
+
+/// Suggested file name for a crop of `region` from the image titled `title`.
+pub fn crop_file_name(title: &str, region: Region) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let stem = stem.trim_matches('-');
+    let stem = if stem.is_empty() { "image" } else { stem };
+    format!(
+        "{stem}-crop-{}-{}-{}x{}.png",
+        region.min[0], region.min[1], region.size[0], region.size[1]
+    )
+}
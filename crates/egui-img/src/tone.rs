@@ -0,0 +1,214 @@
+//! Exposure and gamma for showing 16-bit and floating-point images on an 8-bit display.
+//!
+//! Deep images keep their full-range pixels; the displayed 8-bit image is rebuilt from them
+//! whenever the tone settings change, so detail lost to clipping at one exposure comes back
+//! at another.
+
+use egui::{ColorImage, Slider};
+use image::{ImageBuffer, Rgba, Rgba32FImage};
+
+/// Gamma applied to linear-light images so they look right on an sRGB display.
+const DISPLAY_GAMMA: f32 = 2.2;
+/// Range of the exposure slider, in stops.
+const EXPOSURE_STOPS: f32 = 8.0;
+
+/// RGBA pixels with more precision or range than the display, as floats where 1.0 is full
+/// intensity.
+pub struct WideImage {
+    /// Width and height in pixels.
+    pub size: [usize; 2],
+    /// Pixels in row-major order.
+    pixels: Vec<[f32; 4]>,
+    /// Whether the values are linear light rather than already gamma-encoded.
+    linear: bool,
+}
+
+impl WideImage {
+    /// Pixels of a 16-bit image, which like 8-bit images are taken to be gamma-encoded.
+    pub fn from_rgba16(image: &ImageBuffer<Rgba<u16>, Vec<u16>>) -> Self {
+        Self {
+            size: [image.width() as usize, image.height() as usize],
+            pixels: image
+                .pixels()
+                .map(|p| p.0.map(|v| f32::from(v) / f32::from(u16::MAX)))
+                .collect(),
+            linear: false,
+        }
+    }
+
+    /// Pixels of a floating-point image, which are taken to be linear light.
+    pub fn from_rgba32f(image: &Rgba32FImage) -> Self {
+        Self {
+            size: [image.width() as usize, image.height() as usize],
+            pixels: image.pixels().map(|p| p.0).collect(),
+            linear: true,
+        }
+    }
+}
+
+/// Mapping from wide pixel values to display values: scale by `2^exposure`, then raise to
+/// `1 / gamma`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    /// Brightness change in stops.
+    exposure: f32,
+    /// Encoding gamma applied after the exposure.
+    gamma: f32,
+}
+
+impl Default for Tone {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl Tone {
+    /// Settings that show `image` as intended: unchanged when it is already gamma-encoded,
+    /// display gamma when it is linear.
+    pub fn for_image(image: &WideImage) -> Self {
+        Self {
+            gamma: if image.linear { DISPLAY_GAMMA } else { 1.0 },
+            ..Self::default()
+        }
+    }
+
+    /// Exposure and gamma sliders with a button returning to `default`. Returns whether the
+    /// settings changed.
+    pub fn controls(&mut self, ui: &mut egui::Ui, default: Self) -> bool {
+        let mut changed = ui
+            .add(
+                Slider::new(&mut self.exposure, -EXPOSURE_STOPS..=EXPOSURE_STOPS)
+                    .step_by(0.1)
+                    .text("Exposure"),
+            )
+            .on_hover_text("Brightness in stops; each stop doubles it")
+            .changed();
+        changed |= ui
+            .add(
+                Slider::new(&mut self.gamma, 0.2..=5.0)
+                    .logarithmic(true)
+                    .text("Gamma"),
+            )
+            .changed();
+        if ui
+            .add_enabled(*self != default, egui::Button::new("Reset tone"))
+            .clicked()
+        {
+            *self = default;
+            changed = true;
+        }
+        changed
+    }
+
+    /// `image` as 8-bit pixels for display.
+    pub fn apply(self, image: &WideImage) -> ColorImage {
+        let scale = self.exposure.exp2();
+        let inverse_gamma = self.gamma.recip();
+        let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let bytes: Vec<u8> = image
+            .pixels
+            .iter()
+            .flat_map(|&[r, g, b, a]| {
+                let [r, g, b] =
+                    [r, g, b].map(|v| to_byte((v * scale).max(0.0).powf(inverse_gamma)));
+                [r, g, b, to_byte(a)]
+            })
+            .collect();
+        ColorImage::from_rgba_unmultiplied(image.size, &bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16-bit black, white and mid-grey, opaque, followed by a translucent mid-grey.
+    fn deep() -> WideImage {
+        let mid = u16::MAX / 2 + 1;
+        let values = [
+            [0, 0, 0, u16::MAX],
+            [u16::MAX; 4],
+            [mid, mid, mid, u16::MAX],
+            [mid; 4],
+        ];
+        let image = ImageBuffer::from_fn(4, 1, |x, _| Rgba(values[x as usize]));
+        WideImage::from_rgba16(&image)
+    }
+
+    fn tone(exposure: f32, gamma: f32) -> Tone {
+        Tone { exposure, gamma }
+    }
+
+    /// Red channel of every pixel, and alpha of every pixel.
+    fn red_and_alpha(image: &ColorImage) -> (Vec<u8>, Vec<u8>) {
+        let unmultiplied: Vec<[u8; 4]> = image
+            .pixels
+            .iter()
+            .map(|c| c.to_srgba_unmultiplied())
+            .collect();
+        (
+            unmultiplied[..3].iter().map(|p| p[0]).collect(),
+            image.pixels.iter().map(|c| c.a()).collect(),
+        )
+    }
+
+    #[test]
+    fn deep_images_show_unchanged_by_default() {
+        let image = deep();
+        assert_eq!(Tone::for_image(&image), Tone::default());
+        let shown = Tone::default().apply(&image);
+        assert_eq!(shown.size, [4, 1]);
+        assert_eq!(
+            red_and_alpha(&shown),
+            (vec![0, 255, 128], vec![255, 255, 255, 128])
+        );
+    }
+
+    #[test]
+    fn exposure_scales_in_stops_and_leaves_alpha() {
+        let image = deep();
+        for (exposure, white, mid) in [
+            (-2.0, 64, 32),
+            (-1.0, 128, 64),
+            (1.0, 255, 255),
+            (3.0, 255, 255),
+        ] {
+            let (red, alpha) = red_and_alpha(&tone(exposure, 1.0).apply(&image));
+            assert_eq!(red, [0, white, mid], "exposure {exposure}");
+            assert_eq!(alpha, [255, 255, 255, 128], "exposure {exposure}");
+        }
+    }
+
+    #[test]
+    fn gamma_brightens_midtones_only() {
+        let (red, _) = red_and_alpha(&tone(0.0, 2.0).apply(&deep()));
+        // √0.5 ≈ 0.707.
+        assert_eq!(red, [0, 255, 180]);
+    }
+
+    #[test]
+    fn float_images_are_linear_and_keep_their_range() {
+        let values = [[0.5, 0.5, 0.5, 1.0], [4.0, -1.0, 0.25, 0.5]];
+        let image = Rgba32FImage::from_fn(2, 1, |x, _| Rgba(values[x as usize]));
+        let image = WideImage::from_rgba32f(&image);
+        let display = Tone::for_image(&image);
+        assert_eq!(display, tone(0.0, DISPLAY_GAMMA));
+
+        let shown = display.apply(&image);
+        // 0.5^(1/2.2) ≈ 0.730.
+        assert_eq!(
+            shown.pixels[0].to_srgba_unmultiplied(),
+            [186, 186, 186, 255]
+        );
+        assert_eq!(shown.pixels[1].a(), 128);
+
+        // Highlights clipped at one exposure come back at a lower one.
+        let shown = tone(-2.0, 1.0).apply(&image);
+        assert_eq!(shown.pixels[1].a(), 128);
+        let [r, g, _, _] = shown.pixels[1].to_srgba_unmultiplied();
+        assert_eq!((r, g), (255, 0));
+    }
+}
@@ -0,0 +1,167 @@
+//! Reloading an image whenever its file changes on disk.
+//!
+//! The file's directory is watched through the platform's notification service, falling back
+//! to polling where there is none. Watching the directory rather than the file copes with
+//! programs that replace the file instead of rewriting it in place. The window repaints only
+//! when an event arrives, and the file's modification time and length decide whether it
+//! actually changed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use image::ImageReader;
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::ViewerImage;
+
+/// Seconds between checks of the file when the platform can't notify us of changes.
+const FALLBACK_POLL_SECONDS: f64 = 0.5;
+/// Seconds a reload message stays on screen.
+const TOAST_SECONDS: f64 = 1.5;
+/// Distance of the reload message from the bottom of the window, in points.
+const TOAST_MARGIN: f32 = 16.0;
+
+/// Modification time and length of a file, which change whenever it is rewritten.
+type Stamp = (SystemTime, u64);
+
+/// A short message shown at the bottom of the window.
+struct Toast {
+    /// Message text.
+    text: String,
+    /// Input time, in seconds, at which the message disappears.
+    until: f64,
+}
+
+/// An image file watched for changes, with the message confirming the last reload.
+pub struct Watch {
+    /// File being watched.
+    path: PathBuf,
+    /// Stamp of the file when it was last loaded, if it could be read.
+    stamp: Option<Stamp>,
+    /// Watcher delivering events for the file's directory, while it runs.
+    _watcher: Option<Box<dyn Watcher + Send>>,
+    /// Signalled once per event touching the file.
+    events: mpsc::Receiver<()>,
+    /// Message about the last reload, while it is shown.
+    toast: Option<Toast>,
+}
+
+impl Watch {
+    /// Watch `path`, taking its current contents as already loaded, and repaint `ctx` whenever
+    /// the file may have changed.
+    ///
+    /// When neither the platform's notifications nor polling can watch the directory, the
+    /// image simply never reloads.
+    pub fn new(path: PathBuf, ctx: &egui::Context) -> Self {
+        let (sender, events) = mpsc::channel();
+        let name = path.file_name().map(ToOwned::to_owned);
+        let ctx = ctx.clone();
+        let handler = move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let touches_file = event
+                .paths
+                .iter()
+                .any(|changed| changed.file_name() == name.as_deref());
+            if touches_file && !matches!(event.kind, EventKind::Access(_)) {
+                sender.send(()).ok();
+                ctx.request_repaint();
+            }
+        };
+        Self {
+            stamp: stamp(&path),
+            _watcher: watch_directory(&path, handler),
+            path,
+            events,
+            toast: None,
+        }
+    }
+
+    /// The file's new contents, if it changed since the last check and decodes.
+    ///
+    /// A file that fails to decode, such as one still being written, is reported and skipped;
+    /// it is loaded again on its next change.
+    pub fn poll(&mut self, ctx: &egui::Context) -> Option<ViewerImage> {
+        if self.events.try_iter().count() == 0 {
+            return None;
+        }
+        let now = ctx.input(|i| i.time);
+        let current = stamp(&self.path);
+        if current.is_none() || current == self.stamp {
+            return None;
+        }
+        self.stamp = current;
+        let (image, text) = match load(&self.path) {
+            Ok(image) => (Some(image), "Reloaded".to_string()),
+            Err(err) => (None, format!("Reload failed: {err}")),
+        };
+        self.toast = Some(Toast {
+            text,
+            until: now + TOAST_SECONDS,
+        });
+        image
+    }
+
+    /// Show the message about the last reload until it expires.
+    pub fn show_toast(&mut self, ctx: &egui::Context) {
+        let Some(toast) = &self.toast else {
+            return;
+        };
+        let remaining = toast.until - ctx.input(|i| i.time);
+        if remaining <= 0.0 {
+            self.toast = None;
+            return;
+        }
+        egui::Area::new(egui::Id::new("egui-img reload toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -TOAST_MARGIN))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| ui.label(&toast.text));
+            });
+        ctx.request_repaint_after(Duration::from_secs_f64(remaining));
+    }
+}
+
+/// Decode the image at `path`, keeping its bit depth.
+pub fn load(path: &Path) -> Result<ViewerImage> {
+    Ok(ImageReader::open(path)?.decode()?.into())
+}
+
+/// Start watching the directory holding `path` with `handler`, through the platform's
+/// notifications where it has them and by polling otherwise.
+fn watch_directory(
+    path: &Path,
+    handler: impl Fn(notify::Result<Event>) + Clone + Send + 'static,
+) -> Option<Box<dyn Watcher + Send>> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let start = |mut watcher: Box<dyn Watcher + Send>| {
+        watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+        Some(watcher)
+    };
+    let config = Config::default();
+    RecommendedWatcher::new(handler.clone(), config)
+        .ok()
+        .and_then(|watcher| start(Box::new(watcher)))
+        .or_else(|| {
+            let config = config.with_poll_interval(Duration::from_secs_f64(FALLBACK_POLL_SECONDS));
+            PollWatcher::new(handler, config)
+                .ok()
+                .and_then(|watcher| start(Box::new(watcher)))
+        })
+}
+
+/// Current stamp of the file at `path`, or `None` when it cannot be read.
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
@@ -0,0 +1,59 @@
+//! Embed the 2D and 3D curve widgets in a plain eframe app.
+//!
+//! Run with `cargo run -p scurve-gui --example embed`.
+
+#[cfg(not(target_arch = "wasm32"))]
+use scurve_gui::{CurveWidget2D, CurveWidget3D, Selected3DCurve, SelectedCurve, state::Camera};
+
+/// Two curves side by side, each owning its own selection.
+#[cfg(not(target_arch = "wasm32"))]
+struct EmbedApp {
+    /// Curve shown in the 2D widget.
+    hilbert: SelectedCurve,
+    /// Curve shown in the 3D widget.
+    zorder: Selected3DCurve,
+    /// Camera for the 3D widget.
+    camera: Camera,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl eframe::App for EmbedApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Embedded curves");
+            let size = egui::vec2(ui.available_width() / 2.0 - 8.0, ui.available_height());
+            ui.horizontal(|ui| {
+                ui.add(
+                    CurveWidget2D::new(&mut self.hilbert)
+                        .desired_size(size)
+                        .animate(true),
+                );
+                ui.add(
+                    CurveWidget3D::new(&mut self.zorder, &mut self.camera)
+                        .desired_size(size)
+                        .snake_length(10.0)
+                        .animate(true)
+                        .auto_rotate(true),
+                );
+            });
+        });
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> eframe::Result {
+    eframe::run_native(
+        "Embedded curves",
+        eframe::NativeOptions::default(),
+        Box::new(|_cc| {
+            Ok(Box::new(EmbedApp {
+                hilbert: SelectedCurve::with_name("hilbert"),
+                zorder: Selected3DCurve::with_name("zorder"),
+                camera: Camera::default(),
+            }))
+        }),
+    )
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -3,7 +3,7 @@ use std::sync::OnceLock;
 use egui::epaint::Shadow;
 use egui_commonmark::CommonMarkViewer;
 
-use crate::{APP_NAME, theme};
+use crate::{APP_NAME, theme, widgets};
 
 /// Show the modal About dialog overlay, handling open/close interactions.
 pub fn show_about_dialog(
@@ -78,6 +78,7 @@ fn show_about_area(
     center_pos: egui::Pos2,
     should_close: &mut bool,
 ) -> egui::InnerResponse<()> {
+    let palette = theme::palette(ctx);
     egui::Area::new(egui::Id::new("about_dialog"))
         .fixed_pos(center_pos)
         .order(egui::Order::Foreground)
@@ -95,17 +96,15 @@ fn show_about_area(
                     ui.vertical(|ui| {
                         ui.horizontal(|ui| {
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
-                                if ui
-                                    .add(
-                                        egui::Button::new(
-                                            egui::RichText::new("×")
-                                                .size(theme::font_size::CLOSE_BUTTON),
-                                        )
-                                        .fill(egui::Color32::TRANSPARENT)
-                                        .frame(false),
+                                let close = ui.add(
+                                    egui::Button::new(
+                                        egui::RichText::new("×")
+                                            .size(theme::font_size::CLOSE_BUTTON),
                                     )
-                                    .clicked()
-                                {
+                                    .fill(egui::Color32::TRANSPARENT)
+                                    .frame(false),
+                                );
+                                if widgets::icon_label(close, "Close about").clicked() {
                                     *should_close = true;
                                 }
                             });
@@ -124,14 +123,14 @@ fn show_about_area(
                                         ui.add(egui::Label::new(
                                             egui::RichText::new(APP_NAME)
                                                 .size(theme::font_size::HEADING_LARGE)
-                                                .color(theme::TEXT_HEADING)
+                                                .color(palette.text_heading)
                                                 .strong(),
                                         ));
                                         ui.add_space(2.0);
                                         ui.add(egui::Label::new(
                                             egui::RichText::new("Space-filling curve playground")
                                                 .size(theme::font_size::LABEL)
-                                                .color(theme::TEXT_SECONDARY),
+                                                .color(palette.text_secondary),
                                         ));
                                     });
 
@@ -144,7 +143,7 @@ fn show_about_area(
                                                     env!("CARGO_PKG_VERSION")
                                                 ))
                                                 .size(theme::font_size::VERSION)
-                                                .color(theme::TEXT_DIM),
+                                                .color(palette.text_dim),
                                             ));
                                         },
                                     );
@@ -159,7 +158,7 @@ fn show_about_area(
                                     .show(ui, |ui| {
                                         // Override visuals for readable markdown content
                                         ui.visuals_mut().override_text_color =
-                                            Some(theme::TEXT_BODY);
+                                            Some(palette.text_body);
                                         CommonMarkViewer::new().show(ui, cache, about_content());
                                     });
                             });
@@ -0,0 +1,162 @@
+//! Labelled curve positions kept across sessions.
+//!
+//! A bookmark covers a run of curve indices, or a single index when both ends match. Bookmarks
+//! are drawn as markers on the curve and on the timeline, and jumping to one moves the snake to
+//! its start. They are stored by index, so they stay put when the view changes, and bookmarks
+//! past the end of a shorter curve are simply not shown.
+
+use egui::{Align2, Color32, FontId, Pos2, Stroke};
+
+use crate::theme;
+
+/// A labelled run of curve indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    /// Text shown beside the marker.
+    pub label: String,
+    /// First bookmarked index.
+    pub start: u32,
+    /// Last bookmarked index, inclusive.
+    pub end: u32,
+    /// Marker color.
+    pub color: Color32,
+}
+
+impl Bookmark {
+    /// Whether the bookmark spans more than one index.
+    pub fn is_range(&self) -> bool {
+        self.end > self.start
+    }
+
+    /// Readout of the bookmarked indices.
+    pub fn span_label(&self) -> String {
+        if self.is_range() {
+            format!("{}–{}", self.start, self.end)
+        } else {
+            self.start.to_string()
+        }
+    }
+}
+
+/// The user's bookmarks and whether their markers are drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmarks {
+    /// Bookmarks in the order they were added.
+    pub items: Vec<Bookmark>,
+    /// Draw markers on the curve.
+    pub show_markers: bool,
+}
+
+impl Default for Bookmarks {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            show_markers: true,
+        }
+    }
+}
+
+impl Bookmarks {
+    /// Key of the bookmarks in eframe storage.
+    pub const STORAGE_KEY: &str = "bookmarks";
+
+    /// Bookmark indices `start..=end` under a default label and the next marker color.
+    pub fn add(&mut self, start: u32, end: u32) {
+        let colors = theme::bookmarks::COLORS;
+        self.items.push(Bookmark {
+            label: format!("Bookmark {}", self.items.len() + 1),
+            start: start.min(end),
+            end: start.max(end),
+            color: colors[self.items.len() % colors.len()],
+        });
+    }
+
+    /// Serialize for eframe storage: the marker toggle, then one bookmark per line as its
+    /// start, end, hex color and label.
+    pub fn to_storage(&self) -> String {
+        let mut value = self.show_markers.to_string();
+        for bookmark in &self.items {
+            let c = bookmark.color;
+            value.push_str(&format!(
+                "\n{} {} {:02x}{:02x}{:02x} {}",
+                bookmark.start,
+                bookmark.end,
+                c.r(),
+                c.g(),
+                c.b(),
+                bookmark.label.replace('\n', " ")
+            ));
+        }
+        value
+    }
+
+    /// Parse a value written by [`Self::to_storage`].
+    pub fn from_storage(value: &str) -> Option<Self> {
+        let mut lines = value.lines();
+        let show_markers = lines.next()?.parse().ok()?;
+        let items = lines
+            .map(|line| {
+                let mut parts = line.splitn(4, ' ');
+                let start = parts.next()?.parse().ok()?;
+                let end = parts.next()?.parse().ok()?;
+                let color = parts.next().filter(|part| part.len() == 6)?;
+                let [_, r, g, b] = u32::from_str_radix(color, 16).ok()?.to_be_bytes();
+                Some(Bookmark {
+                    label: parts.next().unwrap_or_default().to_string(),
+                    start,
+                    end,
+                    color: Color32::from_rgb(r, g, b),
+                })
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            items,
+            show_markers,
+        })
+    }
+}
+
+/// Draw markers for `bookmarks` over a curve whose points are at `points` on screen.
+///
+/// Ranges are traced along the curve and single indices get a ring; both are labelled at
+/// their start. Points for which `visible` is false, e.g. clipped away in 3D, are skipped.
+pub fn paint_markers(
+    painter: &egui::Painter,
+    bookmarks: &Bookmarks,
+    points: &[Pos2],
+    visible: impl Fn(usize) -> bool,
+) {
+    if !bookmarks.show_markers {
+        return;
+    }
+    let palette = theme::palette(painter.ctx());
+    let font = FontId::proportional(theme::bookmarks::LABEL_SIZE);
+    for bookmark in &bookmarks.items {
+        let start = bookmark.start as usize;
+        let Some(&anchor) = points.get(start).filter(|_| visible(start)) else {
+            continue;
+        };
+        let stroke = Stroke::new(theme::bookmarks::WIDTH, bookmark.color);
+        if bookmark.is_range() {
+            let end = (bookmark.end as usize).min(points.len() - 1);
+            for i in start..end {
+                if visible(i) && visible(i + 1) {
+                    painter.line_segment([points[i], points[i + 1]], stroke);
+                }
+            }
+        }
+        painter.circle(
+            anchor,
+            theme::bookmarks::RADIUS,
+            palette.canvas_background,
+            stroke,
+        );
+        painter.text(
+            anchor + egui::vec2(theme::bookmarks::RADIUS + 3.0, 0.0),
+            Align2::LEFT_CENTER,
+            &bookmark.label,
+            font.clone(),
+            bookmark.color,
+        );
+    }
+}
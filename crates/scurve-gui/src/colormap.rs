@@ -0,0 +1,325 @@
+//! Index-gradient colormaps for coloring curves by traversal order or structure.
+
+use std::collections::HashMap;
+
+use egui::Color32;
+
+use crate::theme;
+
+/// Viridis color stops, from first to last index.
+const VIRIDIS: &[[u8; 3]] = &[
+    [0x44, 0x01, 0x54],
+    [0x48, 0x28, 0x78],
+    [0x3e, 0x49, 0x89],
+    [0x31, 0x68, 0x8e],
+    [0x26, 0x82, 0x8e],
+    [0x1f, 0x9e, 0x89],
+    [0x35, 0xb7, 0x79],
+    [0x6e, 0xce, 0x58],
+    [0xb5, 0xde, 0x2b],
+    [0xfd, 0xe7, 0x25],
+];
+
+/// Turbo color stops, from first to last index.
+const TURBO: &[[u8; 3]] = &[
+    [0x30, 0x12, 0x3b],
+    [0x41, 0x45, 0xab],
+    [0x46, 0x75, 0xed],
+    [0x39, 0xa2, 0xfc],
+    [0x1b, 0xcf, 0xd4],
+    [0x24, 0xec, 0xa6],
+    [0x61, 0xfc, 0x6c],
+    [0xa4, 0xfc, 0x3b],
+    [0xd1, 0xe8, 0x34],
+    [0xf3, 0xc6, 0x3a],
+    [0xfe, 0x9b, 0x2d],
+    [0xf3, 0x63, 0x15],
+    [0xd9, 0x38, 0x06],
+    [0xb1, 0x19, 0x01],
+    [0x7a, 0x04, 0x03],
+];
+
+/// Magma color stops, from first to last index.
+const MAGMA: &[[u8; 3]] = &[
+    [0x00, 0x00, 0x04],
+    [0x18, 0x0f, 0x3d],
+    [0x44, 0x0f, 0x76],
+    [0x72, 0x1f, 0x81],
+    [0x9e, 0x2f, 0x7f],
+    [0xcd, 0x40, 0x71],
+    [0xf1, 0x60, 0x5d],
+    [0xfd, 0x96, 0x68],
+    [0xfe, 0xca, 0x8d],
+    [0xfc, 0xfd, 0xbf],
+];
+
+/// Plasma color stops, from first to last index.
+const PLASMA: &[[u8; 3]] = &[
+    [0x0d, 0x08, 0x87],
+    [0x46, 0x03, 0x9f],
+    [0x72, 0x01, 0xa8],
+    [0x9c, 0x17, 0x9e],
+    [0xbd, 0x37, 0x86],
+    [0xd8, 0x57, 0x6b],
+    [0xed, 0x79, 0x53],
+    [0xfb, 0x9f, 0x3a],
+    [0xfd, 0xca, 0x26],
+    [0xf0, 0xf9, 0x21],
+];
+
+/// Cividis color stops, from first to last index.
+///
+/// Cividis varies almost only in lightness and blue–yellow, so it reads the same under
+/// red–green color vision deficiencies.
+const CIVIDIS: &[[u8; 3]] = &[
+    [0x00, 0x20, 0x4d],
+    [0x00, 0x33, 0x6f],
+    [0x39, 0x48, 0x6b],
+    [0x57, 0x5c, 0x6d],
+    [0x70, 0x71, 0x73],
+    [0x8a, 0x87, 0x79],
+    [0xa6, 0x9d, 0x75],
+    [0xc4, 0xb5, 0x6c],
+    [0xe4, 0xcf, 0x5b],
+    [0xff, 0xea, 0x46],
+];
+
+/// How the main curve is colored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub enum Colormap {
+    /// A single line color for the whole curve.
+    #[default]
+    Solid,
+    /// Perceptually uniform blue-green-yellow gradient.
+    Viridis,
+    /// High-contrast rainbow gradient.
+    Turbo,
+    /// Black-purple-cream gradient.
+    Magma,
+    /// Blue-magenta-yellow gradient.
+    Plasma,
+    /// Colorblind-safe blue-grey-yellow gradient.
+    Cividis,
+    /// Linear blend between two user-chosen endpoint colors.
+    Custom,
+}
+
+impl Colormap {
+    /// Every colormap, in menu order.
+    pub const ALL: [Self; 7] = [
+        Self::Solid,
+        Self::Viridis,
+        Self::Turbo,
+        Self::Magma,
+        Self::Plasma,
+        Self::Cividis,
+        Self::Custom,
+    ];
+
+    /// Human-friendly name for menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Solid => "Solid",
+            Self::Viridis => "Viridis",
+            Self::Turbo => "Turbo",
+            Self::Magma => "Magma",
+            Self::Plasma => "Plasma",
+            Self::Cividis => "Cividis",
+            Self::Custom => "Custom",
+        }
+    }
+
+    /// Short identifier used in shared links.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Solid => "solid",
+            Self::Viridis => "viridis",
+            Self::Turbo => "turbo",
+            Self::Magma => "magma",
+            Self::Plasma => "plasma",
+            Self::Cividis => "cividis",
+            Self::Custom => "custom",
+        }
+    }
+
+    /// The colormap with the given [`Self::key`].
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|colormap| colormap.key() == key)
+    }
+
+    /// Color stops for the built-in gradients.
+    fn stops(self) -> &'static [[u8; 3]] {
+        match self {
+            Self::Solid | Self::Custom => &[],
+            Self::Viridis => VIRIDIS,
+            Self::Turbo => TURBO,
+            Self::Magma => MAGMA,
+            Self::Plasma => PLASMA,
+            Self::Cividis => CIVIDIS,
+        }
+    }
+}
+
+/// Property of each point that picks its place on the colormap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub enum ColorBy {
+    /// Position along the curve.
+    #[default]
+    Index,
+    /// Onion shell: distance from the nearest face of the grid.
+    Shell,
+    /// Quadrant or octant at a chosen recursion level, numbered in visiting order.
+    Block,
+}
+
+impl ColorBy {
+    /// Every mode, in menu order.
+    pub const ALL: [Self; 3] = [Self::Index, Self::Shell, Self::Block];
+
+    /// Human-friendly name for menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Index => "Index",
+            Self::Shell => "Shell",
+            Self::Block => "Subcube",
+        }
+    }
+
+    /// Short identifier used in shared links.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Index => "index",
+            Self::Shell => "shell",
+            Self::Block => "block",
+        }
+    }
+
+    /// The mode with the given [`Self::key`].
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.key() == key)
+    }
+}
+
+/// Groups the points of a curve into color classes for structural coloring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Classifier {
+    /// Class is the L∞ shell of the point, counted inwards from the grid faces.
+    Shell {
+        /// Side length of the grid.
+        size: u32,
+        /// Fixed trailing coordinate of a sliced curve, which also bounds the shell.
+        slice: Option<u32>,
+    },
+    /// Class is the block of `side` cells holding the point, in the order the curve enters it.
+    Block {
+        /// Side length of each block.
+        side: u32,
+    },
+}
+
+impl Classifier {
+    /// Color class of every point in `points`.
+    pub fn classes<const D: usize>(self, points: &[[u32; D]]) -> Vec<u32> {
+        match self {
+            Self::Shell { size, slice } => {
+                let max = size.saturating_sub(1);
+                points
+                    .iter()
+                    .map(|p| {
+                        p.iter()
+                            .chain(&slice)
+                            .map(|&c| c.min(max.saturating_sub(c)))
+                            .min()
+                            .unwrap_or(0)
+                    })
+                    .collect()
+            }
+            Self::Block { side } => {
+                let mut order = HashMap::new();
+                points
+                    .iter()
+                    .map(|p| {
+                        let next = order.len() as u32;
+                        *order.entry(p.map(|c| c / side)).or_insert(next)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Default endpoints for the custom gradient: the theme's curve and accent colors.
+pub const DEFAULT_ENDPOINTS: [Color32; 2] = [theme::NEON_GRID.curve, theme::NEON_GRID.accent];
+
+/// A colormap resolved for a curve of a known length.
+pub struct Gradient {
+    /// Stops interpolated along the curve.
+    stops: Vec<[u8; 3]>,
+    /// Number of points on the curve, or of color classes when `classes` is set.
+    len: usize,
+    /// Color class of each point; empty when points are colored by index.
+    classes: Vec<u32>,
+}
+
+impl Gradient {
+    /// Resolve `colormap` for a curve of `len` points; `None` means solid coloring.
+    pub fn new(colormap: Colormap, endpoints: [Color32; 2], len: usize) -> Option<Self> {
+        let stops = match colormap {
+            Colormap::Solid => return None,
+            Colormap::Custom => endpoints.map(|c| [c.r(), c.g(), c.b()]).to_vec(),
+            builtin => builtin.stops().to_vec(),
+        };
+        Some(Self {
+            stops,
+            len,
+            classes: Vec::new(),
+        })
+    }
+
+    /// Resolve the colors of the curve through `points`, grouped by `classifier` when one is
+    /// set.
+    ///
+    /// A single color cannot show structure, so classified curves fall back from the solid
+    /// colormap to Turbo, or to Cividis when `colorblind_safe` is set.
+    pub fn for_curve<const D: usize>(
+        colormap: Colormap,
+        endpoints: [Color32; 2],
+        points: &[[u32; D]],
+        classifier: Option<Classifier>,
+        colorblind_safe: bool,
+    ) -> Option<Self> {
+        let Some(classifier) = classifier else {
+            return Self::new(colormap, endpoints, points.len());
+        };
+        let classes = classifier.classes(points);
+        let len = classes.iter().max().map_or(0, |&c| c as usize + 1);
+        let colormap = match colormap {
+            Colormap::Solid if colorblind_safe => Colormap::Cividis,
+            Colormap::Solid => Colormap::Turbo,
+            other => other,
+        };
+        let mut gradient = Self::new(colormap, endpoints, len)?;
+        gradient.classes = classes;
+        Some(gradient)
+    }
+
+    /// Opaque color of the point at `index`, scaled by `brightness`.
+    pub fn color(&self, index: usize, brightness: f32) -> Color32 {
+        let key = self.classes.get(index).map_or(index, |&c| c as usize);
+        let t = if self.len > 1 {
+            key as f32 / (self.len - 1) as f32
+        } else {
+            0.0
+        };
+        let scaled = t.clamp(0.0, 1.0) * (self.stops.len() - 1) as f32;
+        let lo = scaled.floor() as usize;
+        let hi = (lo + 1).min(self.stops.len() - 1);
+        let frac = scaled - lo as f32;
+        let channel = |c: usize| {
+            let a = f32::from(self.stops[lo][c]);
+            let b = f32::from(self.stops[hi][c]);
+            ((a + (b - a) * frac) * brightness) as u8
+        };
+        Color32::from_rgb(channel(0), channel(1), channel(2))
+    }
+}
@@ -0,0 +1,196 @@
+//! Keyboard shortcuts and the command palette.
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use crate::theme;
+
+/// An action that can be triggered from the keyboard or the command palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Pause or resume all animations.
+    TogglePause,
+    /// Advance the snake by one segment.
+    Step,
+    /// Move the snake back by one segment.
+    StepBack,
+    /// Select the next curve in the curve list.
+    NextCurve,
+    /// Select the previous curve in the curve list.
+    PreviousCurve,
+    /// Show or hide the snake overlay.
+    ToggleSnake,
+    /// Show or hide the curve metadata panel.
+    ToggleDetails,
+    /// Show or hide the table comparing curve metrics.
+    ToggleMetrics,
+    /// Switch to the next pane: 2D, 3D, then the file pane.
+    SwitchPane,
+    /// Export the active pane as a PNG.
+    Screenshot,
+    /// Save a PNG of the whole window, including panels and controls.
+    WindowScreenshot,
+    /// Open the command palette.
+    OpenPalette,
+    /// Choose a file to show in the file pane.
+    OpenFile,
+    /// Choose a CSV of points to draw over the 2D pane in curve order.
+    ImportPoints,
+    /// Select a curve by registry key.
+    SelectCurve(&'static str),
+}
+
+/// Actions listed in the palette, with their shortcuts.
+///
+/// Shortcut matching ignores extra Shift, so Shift variants must precede their plain keys.
+const ACTIONS: [(Command, KeyboardShortcut); 14] = [
+    (
+        Command::TogglePause,
+        KeyboardShortcut::new(Modifiers::NONE, Key::Space),
+    ),
+    (
+        Command::Step,
+        KeyboardShortcut::new(Modifiers::NONE, Key::Period),
+    ),
+    (
+        Command::StepBack,
+        KeyboardShortcut::new(Modifiers::NONE, Key::Comma),
+    ),
+    (
+        Command::PreviousCurve,
+        KeyboardShortcut::new(Modifiers::SHIFT, Key::N),
+    ),
+    (
+        Command::NextCurve,
+        KeyboardShortcut::new(Modifiers::NONE, Key::N),
+    ),
+    (
+        Command::ToggleSnake,
+        KeyboardShortcut::new(Modifiers::NONE, Key::S),
+    ),
+    (
+        Command::ToggleDetails,
+        KeyboardShortcut::new(Modifiers::NONE, Key::I),
+    ),
+    (
+        Command::ToggleMetrics,
+        KeyboardShortcut::new(Modifiers::NONE, Key::M),
+    ),
+    (
+        Command::SwitchPane,
+        KeyboardShortcut::new(Modifiers::NONE, Key::Tab),
+    ),
+    (
+        Command::WindowScreenshot,
+        KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::S),
+    ),
+    (
+        Command::Screenshot,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::S),
+    ),
+    (
+        Command::OpenPalette,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::P),
+    ),
+    (
+        Command::ImportPoints,
+        KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::O),
+    ),
+    (
+        Command::OpenFile,
+        KeyboardShortcut::new(Modifiers::COMMAND, Key::O),
+    ),
+];
+
+impl Command {
+    /// Palette label for this command.
+    pub fn label(self) -> String {
+        match self {
+            Self::TogglePause => "Pause / resume".to_string(),
+            Self::Step => "Step snake".to_string(),
+            Self::StepBack => "Step snake back".to_string(),
+            Self::NextCurve => "Next curve".to_string(),
+            Self::PreviousCurve => "Previous curve".to_string(),
+            Self::ToggleSnake => "Toggle snake".to_string(),
+            Self::ToggleDetails => "Toggle curve details".to_string(),
+            Self::ToggleMetrics => "Toggle curve metrics".to_string(),
+            Self::SwitchPane => "Next pane".to_string(),
+            Self::Screenshot => "Export PNG".to_string(),
+            Self::WindowScreenshot => "Screenshot window".to_string(),
+            Self::OpenPalette => "Command palette".to_string(),
+            Self::OpenFile => "Open file…".to_string(),
+            Self::ImportPoints => "Import points (CSV)…".to_string(),
+            Self::SelectCurve(name) => format!("Curve: {name}"),
+        }
+    }
+}
+
+/// Return the command whose shortcut was pressed this frame, consuming the key press.
+///
+/// Unmodified shortcuts are ignored while a text field has keyboard focus.
+pub fn pressed_shortcut(ctx: &egui::Context) -> Option<Command> {
+    let typing = ctx.wants_keyboard_input();
+    ctx.input_mut(|input| {
+        ACTIONS.iter().find_map(|(command, shortcut)| {
+            let plain = shortcut.modifiers.is_none() || shortcut.modifiers == Modifiers::SHIFT;
+            (!(typing && plain) && input.consume_shortcut(shortcut)).then_some(*command)
+        })
+    })
+}
+
+/// Ctrl+P palette listing every action and curve, filtered by `query`.
+///
+/// Returns the chosen command; sets `open` to false when the palette closes.
+pub fn show_palette(
+    ctx: &egui::Context,
+    open: &mut bool,
+    query: &mut String,
+    curves: &[&'static str],
+) -> Option<Command> {
+    let needle = query.to_lowercase();
+    let entries: Vec<(Command, String)> = ACTIONS
+        .iter()
+        .map(|(command, shortcut)| (*command, Some(ctx.format_shortcut(shortcut))))
+        .filter(|(command, _)| *command != Command::OpenPalette)
+        .chain(
+            curves
+                .iter()
+                .map(|&name| (Command::SelectCurve(name), None)),
+        )
+        .map(|(command, shortcut)| {
+            let label = command.label();
+            let text = match shortcut {
+                Some(shortcut) => format!("{label}    {shortcut}"),
+                None => label.clone(),
+            };
+            (command, label, text)
+        })
+        .filter(|(_, label, _)| label.to_lowercase().contains(&needle))
+        .map(|(command, _, text)| (command, text))
+        .collect();
+
+    let mut chosen = None;
+    let response = egui::Modal::new(egui::Id::new("command_palette")).show(ctx, |ui| {
+        ui.set_width(theme::popup::PALETTE_WIDTH);
+        let edit = ui.add(egui::TextEdit::singleline(query).hint_text("Type a command or curve…"));
+        edit.request_focus();
+        if edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+            chosen = entries.first().map(|(command, _)| *command);
+        }
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .max_height(theme::popup::PALETTE_LIST_HEIGHT)
+            .show(ui, |ui| {
+                for (command, text) in &entries {
+                    if ui.selectable_label(false, text).clicked() {
+                        chosen = Some(*command);
+                    }
+                }
+            });
+    });
+
+    if chosen.is_some() || response.should_close() {
+        *open = false;
+        query.clear();
+    }
+    chosen
+}
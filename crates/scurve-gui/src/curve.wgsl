@@ -0,0 +1,38 @@
+// Walks a curve's state table, one invocation per curve index.
+//
+// Each point is packed into one word with `COORD_BITS` bits per axis, axis 0 lowest.
+
+const COORD_BITS: u32 = 10u;
+
+struct Params {
+    dimension: u32,
+    order: u32,
+    length: u32,
+    // Invocations per row of workgroups, for dispatches wider than one row.
+    row: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+// (child cell, next state) for each state and digit.
+@group(0) @binding(1) var<storage, read> transitions: array<vec2<u32>>;
+@group(0) @binding(2) var<storage, read_write> points: array<u32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x + id.y * params.row;
+    if index >= params.length {
+        return;
+    }
+    let mask = (1u << params.dimension) - 1u;
+    var state = 0u;
+    var packed = 0u;
+    for (var level = params.order; level > 0u; level--) {
+        let digit = (index >> ((level - 1u) * params.dimension)) & mask;
+        let step = transitions[(state << params.dimension) | digit];
+        for (var axis = 0u; axis < params.dimension; axis++) {
+            packed |= ((step.x >> axis) & 1u) << (axis * COORD_BITS + level - 1u);
+        }
+        state = step.y;
+    }
+    points[index] = packed;
+}
@@ -0,0 +1,257 @@
+//! Curve views packaged as egui widgets for embedding in other applications.
+//!
+//! Both widgets draw with the same code as the app's panes. Snake buffers live in egui
+//! memory, so callers only own the curve selection and, in 3D, the camera. Colors come
+//! from the palette installed by [`theme::configure_visuals`], or the default palette if
+//! none was installed.
+
+use egui::{Response, Sense, Ui, Vec2, Widget};
+
+use crate::{
+    HoverPoint, SharedSettings,
+    colormap::Colormap,
+    selection::{CurveSelection, Selected3DCurve, SelectedCurve},
+    snake::{SnakeBuffers, advance_snake_offset},
+    state::{Camera, ClipPlane, Guides},
+    theme,
+    threed::{self, View3D},
+    twod, widgets,
+};
+
+/// A 2D curve canvas with the snake overlay and hover inspector.
+///
+/// ```no_run
+/// # fn show(ui: &mut egui::Ui, curve: &mut scurve_gui::SelectedCurve) {
+/// ui.add(scurve_gui::CurveWidget2D::new(curve).snake_length(10.0).animate(true));
+/// # }
+/// ```
+pub struct CurveWidget2D<'a> {
+    /// Curve, size and snake position being shown.
+    selection: &'a mut SelectedCurve,
+    /// Drawing options shared with the 3D widget.
+    options: CurveOptions,
+}
+
+/// A rotatable 3D curve canvas with the snake overlay and hover inspector.
+///
+/// Dragging horizontally turns the camera.
+pub struct CurveWidget3D<'a> {
+    /// Curve, size, slice and snake position being shown.
+    selection: &'a mut Selected3DCurve,
+    /// Camera the curve is viewed through; updated by drags and auto-rotation.
+    camera: &'a mut Camera,
+    /// Clipping plane hiding part of the curve.
+    clip: ClipPlane,
+    /// Turn the camera at the settings' spin speed.
+    auto_rotate: bool,
+    /// Drawing options shared with the 2D widget.
+    options: CurveOptions,
+}
+
+/// Options common to both widgets.
+#[derive(Default)]
+struct CurveOptions {
+    /// Curve, snake and color settings.
+    settings: SharedSettings,
+    /// Advance the snake by the frame time each frame.
+    animate: bool,
+    /// Size to allocate, or all available space when unset.
+    desired_size: Option<Vec2>,
+}
+
+impl CurveOptions {
+    /// Allocate the widget's rect with `sense`, filling the available space by default.
+    fn allocate(&self, ui: &mut Ui, sense: Sense) -> (egui::Rect, Response) {
+        let size = self.desired_size.unwrap_or_else(|| ui.available_size());
+        ui.allocate_exact_size(size, sense)
+    }
+
+    /// Advance the snake on `selection` when animating, requesting the next frame.
+    fn animate<const D: usize>(&self, ui: &Ui, selection: &mut CurveSelection<D>) {
+        if !self.animate || !self.settings.snake_enabled {
+            return;
+        }
+        let delta = ui.input(|i| i.stable_dt);
+        selection.snake_offset = advance_snake_offset(
+            selection.snake_offset,
+            delta * self.settings.snake_speed,
+            selection.ensure_curve_length(),
+        );
+        ui.ctx().request_repaint();
+    }
+}
+
+/// Builder methods shared by both widgets.
+macro_rules! curve_options {
+    () => {
+        /// Replace all curve, snake and color settings at once.
+        pub fn settings(mut self, settings: SharedSettings) -> Self {
+            self.options.settings = settings;
+            self
+        }
+
+        /// Show or hide the snake overlay.
+        pub fn snake(mut self, enabled: bool) -> Self {
+            self.options.settings.snake_enabled = enabled;
+            self
+        }
+
+        /// Snake length as a percentage of the curve length (0–50).
+        pub fn snake_length(mut self, percent: f32) -> Self {
+            self.options.settings.snake_length = percent.clamp(0.0, 50.0);
+            self
+        }
+
+        /// Snake speed in segments per second while animating.
+        pub fn snake_speed(mut self, segments_per_second: f32) -> Self {
+            self.options.settings.snake_speed = segments_per_second;
+            self
+        }
+
+        /// Opacity of the main curve (0.0–1.0).
+        pub fn opacity(mut self, opacity: f32) -> Self {
+            self.options.settings.curve_opacity = opacity.clamp(0.0, 1.0);
+            self
+        }
+
+        /// Colormap used to color the curve by index.
+        pub fn colormap(mut self, colormap: Colormap) -> Self {
+            self.options.settings.colormap = colormap;
+            self
+        }
+
+        /// Draw segments between points that are not grid neighbours.
+        pub fn long_jumps(mut self, show: bool) -> Self {
+            self.options.settings.show_long_jumps = show;
+            self
+        }
+
+        /// Advance the snake every frame instead of leaving it where the selection put it.
+        pub fn animate(mut self, animate: bool) -> Self {
+            self.options.animate = animate;
+            self
+        }
+
+        /// Allocate exactly `size` rather than all available space.
+        pub fn desired_size(mut self, size: Vec2) -> Self {
+            self.options.desired_size = Some(size);
+            self
+        }
+    };
+}
+
+impl<'a> CurveWidget2D<'a> {
+    /// Show `selection` with the default settings.
+    pub fn new(selection: &'a mut SelectedCurve) -> Self {
+        Self {
+            selection,
+            options: CurveOptions::default(),
+        }
+    }
+
+    curve_options!();
+}
+
+impl<'a> CurveWidget3D<'a> {
+    /// Show `selection` through `camera` with the default settings.
+    pub fn new(selection: &'a mut Selected3DCurve, camera: &'a mut Camera) -> Self {
+        Self {
+            selection,
+            camera,
+            clip: ClipPlane::default(),
+            auto_rotate: false,
+            options: CurveOptions::default(),
+        }
+    }
+
+    curve_options!();
+
+    /// Hide the part of the curve on one side of `clip`.
+    pub fn clip(mut self, clip: ClipPlane) -> Self {
+        self.clip = clip;
+        self
+    }
+
+    /// Turn the camera continuously, unless the settings ask for reduced motion.
+    pub fn auto_rotate(mut self, rotate: bool) -> Self {
+        self.auto_rotate = rotate;
+        self
+    }
+}
+
+impl Widget for CurveWidget2D<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self { selection, options } = self;
+        let (rect, response) = options.allocate(ui, Sense::click());
+        options.animate(ui, selection);
+
+        let mut buffers = take_buffers(ui, response.id);
+        let (_, hovered) =
+            twod::draw_2d_canvas(ui, rect, &mut buffers, (selection, None), &options.settings);
+        ui.data_mut(|data| data.insert_temp(response.id, buffers));
+        inspect(response, hovered, &mut selection.snake_offset)
+    }
+}
+
+impl Widget for CurveWidget3D<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self {
+            selection,
+            camera,
+            clip,
+            auto_rotate,
+            options,
+        } = self;
+        let (rect, response) = options.allocate(ui, Sense::click_and_drag());
+        options.animate(ui, selection);
+
+        if response.dragged() {
+            camera.yaw += response.drag_delta().x * theme::canvas_3d::DRAG_SENSITIVITY;
+        } else if auto_rotate && !options.settings.reduced_motion {
+            let speed =
+                theme::animation::BASE_ROTATION_SPEED * (options.settings.spin_speed / 100.0);
+            camera.yaw += ui.input(|i| i.stable_dt) * speed;
+            ui.ctx().request_repaint();
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, theme::palette(ui.ctx()).canvas_background);
+        let mut buffers = take_buffers(ui, response.id);
+        let view = View3D {
+            camera: *camera,
+            clip,
+            guides: Guides::default(),
+            explode: 0.0,
+        };
+        let hovered = threed::draw_3d_space_curve(
+            &painter,
+            rect,
+            view,
+            &mut buffers,
+            &options.settings,
+            selection,
+        )
+        .and_then(|position| selection.hover_point(position));
+        if let Some(progress) = selection.generating() {
+            widgets::paint_generating(&painter, rect, progress);
+        }
+        ui.data_mut(|data| data.insert_temp(response.id, buffers));
+        inspect(response, hovered, &mut selection.snake_offset)
+    }
+}
+
+/// The snake buffers stored for widget `id`, or empty ones on first use.
+fn take_buffers(ui: &Ui, id: egui::Id) -> SnakeBuffers {
+    ui.data_mut(|data| data.remove_temp(id)).unwrap_or_default()
+}
+
+/// Show the inspector for `hovered` and jump the snake there when the widget is clicked.
+fn inspect(response: Response, hovered: Option<HoverPoint>, snake_offset: &mut f32) -> Response {
+    let Some(point) = hovered else {
+        return response;
+    };
+    if response.clicked() {
+        *snake_offset = point.position as f32;
+    }
+    response.on_hover_ui_at_pointer(|ui| widgets::point_inspector(ui, &point))
+}
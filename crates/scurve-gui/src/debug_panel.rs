@@ -0,0 +1,268 @@
+//! Developer debug panel, shown alongside the frame time overlay in dev mode.
+//!
+//! The panel lists the app state that drives animation and pane syncing, the caches behind
+//! each pane's curve, internals of the curve at the hovered point and a log of recent events.
+//! State changes are found by diffing a [`Watched`] snapshot of the app against the previous
+//! frame, so the code making a change does not have to report it.
+
+use std::{collections::VecDeque, fmt::Debug};
+
+use crate::{
+    AppState, Pane, Selected3DCurve, SelectedCurve,
+    selection::CurveSelection,
+    theme::{self, PaletteName},
+    threed::has_shells,
+    transport::LoopRegion,
+};
+
+/// App state whose changes are logged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watched {
+    /// Active pane.
+    pane: Pane,
+    /// 2D curve name and size.
+    curve_2d: (String, u32),
+    /// 3D curve name, size and 4D slice.
+    curve_3d: (String, u32, Option<u32>),
+    /// Whether playback is paused.
+    paused: bool,
+    /// Whether comparison is enabled.
+    compare: bool,
+    /// Region the snake loops over.
+    loop_region: Option<LoopRegion>,
+    /// Active palette.
+    palette: PaletteName,
+}
+
+impl Watched {
+    /// Snapshot the watched parts of the app.
+    pub fn new(
+        app_state: &AppState,
+        curve_2d: &SelectedCurve,
+        curve_3d: &Selected3DCurve,
+        palette: PaletteName,
+    ) -> Self {
+        Self {
+            pane: app_state.current_pane,
+            curve_2d: (curve_2d.name.clone(), curve_2d.size),
+            curve_3d: (curve_3d.name.clone(), curve_3d.size, curve_3d.slice),
+            paused: app_state.transport.paused,
+            compare: app_state.compare_enabled,
+            loop_region: app_state.transport.loop_region,
+            palette,
+        }
+    }
+
+    /// Describe every field that differs from `before`.
+    fn changes(&self, before: &Self) -> Vec<String> {
+        let mut changes = Vec::new();
+        note(&mut changes, "pane", &before.pane, &self.pane);
+        note(&mut changes, "2D curve", &before.curve_2d, &self.curve_2d);
+        note(&mut changes, "3D curve", &before.curve_3d, &self.curve_3d);
+        note(&mut changes, "paused", &before.paused, &self.paused);
+        note(&mut changes, "compare", &before.compare, &self.compare);
+        note(&mut changes, "loop", &before.loop_region, &self.loop_region);
+        note(&mut changes, "palette", &before.palette, &self.palette);
+        changes
+    }
+}
+
+/// Push a description of the change from `before` to `after` onto `changes`, if they differ.
+fn note<T: PartialEq + Debug>(changes: &mut Vec<String>, name: &str, before: &T, after: &T) {
+    if before != after {
+        changes.push(format!("{name}: {before:?} → {after:?}"));
+    }
+}
+
+/// Recent events, oldest first, with the input time they happened at.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    /// Logged events as `(seconds, text)`.
+    entries: VecDeque<(f64, String)>,
+    /// Snapshot from the previous frame.
+    last: Option<Watched>,
+}
+
+impl EventLog {
+    /// Record `text` at `time`, dropping the oldest entry when the log is full.
+    pub fn push(&mut self, time: f64, text: String) {
+        if self.entries.len() == theme::debug::LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((time, text));
+    }
+
+    /// Log how `now` differs from the previous frame's snapshot.
+    pub fn observe(&mut self, time: f64, now: Watched) {
+        if let Some(last) = &self.last {
+            for change in now.changes(last) {
+                self.push(time, change);
+            }
+        }
+        self.last = Some(now);
+    }
+}
+
+/// What the debug panel shows.
+pub struct DebugView<'a> {
+    /// UI state.
+    pub app_state: &'a AppState,
+    /// 2D selection.
+    pub curve_2d: &'a SelectedCurve,
+    /// 3D selection.
+    pub curve_3d: &'a Selected3DCurve,
+    /// Recent events.
+    pub events: &'a EventLog,
+}
+
+/// Show the debug panel window.
+pub fn show(ctx: &egui::Context, view: &DebugView<'_>) {
+    egui::Window::new("Debug")
+        .default_pos(ctx.viewport_rect().left_bottom() + egui::vec2(12.0, -12.0))
+        .pivot(egui::Align2::LEFT_BOTTOM)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.set_min_width(theme::debug::WIDTH);
+            egui::ScrollArea::vertical()
+                .max_height(theme::debug::MAX_HEIGHT)
+                .show(ui, |ui| {
+                    ui.collapsing("State", |ui| state_rows(ui, view.app_state));
+                    ui.collapsing("Curves", |ui| {
+                        curve_rows(ui, "2D", view.curve_2d);
+                        curve_rows(ui, "3D", view.curve_3d);
+                    });
+                    ui.collapsing("Hover", |ui| hover_rows(ui, view));
+                    egui::CollapsingHeader::new("Events")
+                        .default_open(true)
+                        .show(ui, |ui| event_rows(ui, view.events));
+                });
+        });
+}
+
+/// A two-column grid of `(name, value)` rows.
+fn rows(ui: &mut egui::Ui, id: &str, rows: &[(&str, String)]) {
+    let palette = theme::palette(ui.ctx());
+    egui::Grid::new(id)
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (name, value) in rows {
+                ui.label(egui::RichText::new(*name).color(palette.text_dim));
+                ui.monospace(value);
+                ui.end_row();
+            }
+        });
+}
+
+/// Fields of the app state that drive playback, the 3D view and the panes.
+fn state_rows(ui: &mut egui::Ui, state: &AppState) {
+    let transport = &state.transport;
+    rows(
+        ui,
+        "debug_state",
+        &[
+            ("pane", format!("{:?}", state.current_pane)),
+            ("paused", transport.paused.to_string()),
+            ("elapsed", format!("{:.2}s", transport.elapsed)),
+            ("loop", format!("{:?}", transport.loop_region)),
+            (
+                "slice playback",
+                format!("{} ({:.2}s)", transport.slice_playing, transport.slice_time),
+            ),
+            ("camera", format!("{:?}", state.camera)),
+            ("drag", format!("{:?}", state.drag_rotation)),
+            ("dragging", state.mouse_dragging.to_string()),
+            ("clip", format!("{:?}", state.clip)),
+            ("guides", format!("{:?}", state.guides)),
+            ("explode", state.explode.to_string()),
+            ("compare", state.compare_enabled.to_string()),
+            ("recording", state.recording.to_string()),
+            ("export request", format!("{:?}", state.export_request)),
+            ("canvas", format!("{:?}", state.last_canvas_rect)),
+        ],
+    );
+}
+
+/// Selection, snake and cache state of one pane's curve.
+fn curve_rows<const D: usize>(ui: &mut egui::Ui, label: &str, selection: &CurveSelection<D>) {
+    let stats = selection.geometry_stats();
+    ui.label(egui::RichText::new(label).strong());
+    rows(
+        ui,
+        &format!("debug_curve_{label}"),
+        &[
+            ("curve", selection.name.clone()),
+            ("size", selection.size.to_string()),
+            ("slice", format!("{:?}", selection.slice)),
+            ("snake offset", format!("{:.2}", selection.snake_offset)),
+            ("cached points", selection.cached_len().to_string()),
+            (
+                "mesh",
+                format!("{} vertices, {} triangles", stats.vertices, stats.triangles),
+            ),
+            (
+                "mesh cache",
+                format!(
+                    "{} hits, {} rebuilds, {} clears",
+                    stats.hits, stats.rebuilds, stats.clears
+                ),
+            ),
+        ],
+    );
+}
+
+/// The hovered point and where it sits in the structure of its curve: its shell for onion
+/// curves and its block path for power‑of‑two curves.
+fn hover_rows(ui: &mut egui::Ui, view: &DebugView<'_>) {
+    let Some(point) = &view.app_state.hovered_point else {
+        ui.label("Nothing hovered");
+        return;
+    };
+    let mut items = vec![
+        ("index", point.index.to_string()),
+        ("position", point.position.to_string()),
+        ("coords", format!("{:?}", point.coords)),
+    ];
+    let (name, shell, path) = match view.app_state.current_pane {
+        Pane::TwoD => (
+            &view.curve_2d.name,
+            view.curve_2d.shell(&point.coords),
+            view.curve_2d.block_path(&point.coords),
+        ),
+        Pane::ThreeD => (
+            &view.curve_3d.name,
+            view.curve_3d.shell(&point.coords),
+            view.curve_3d.block_path(&point.coords),
+        ),
+        Pane::File => {
+            rows(ui, "debug_hover", &items);
+            return;
+        }
+    };
+    if has_shells(name) {
+        items.push(("shell", shell.to_string()));
+    }
+    if let Some(path) = path {
+        items.push(("depth", path.len().to_string()));
+        items.push(("block path", format!("{path:?}")));
+    }
+    rows(ui, "debug_hover", &items);
+}
+
+/// Logged events, newest first.
+fn event_rows(ui: &mut egui::Ui, events: &EventLog) {
+    let palette = theme::palette(ui.ctx());
+    if events.entries.is_empty() {
+        ui.label("No events yet");
+    }
+    for (time, text) in events.entries.iter().rev() {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(format!("{time:>8.2}s"))
+                    .monospace()
+                    .color(palette.text_dim),
+            );
+            ui.monospace(text);
+        });
+    }
+}
@@ -0,0 +1,583 @@
+//! Export of the current view to image files.
+//!
+//! PNG exports are cropped from an egui viewport screenshot so they match the
+//! screen exactly; SVG exports rebuild the same geometry as vector paths.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+
+use anyhow::Result;
+use egui::{Color32, Pos2, Rect};
+#[cfg(target_arch = "wasm32")]
+use js_sys::{Array, Uint8Array};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsCast, JsValue};
+#[cfg(target_arch = "wasm32")]
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url, window};
+
+use crate::{
+    SharedSettings,
+    snake::{SnakeShading, is_adjacent_2d, is_adjacent_3d},
+    stroke::LineStyle,
+    theme,
+    twod::build_screen_points,
+};
+
+/// File formats supported by the export action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Raster image cropped from the rendered frame.
+    Png,
+    /// Vector image rebuilt from the curve geometry.
+    Svg,
+    /// Wavefront OBJ polyline of the 3D curve.
+    Obj,
+    /// Binary glTF (`.glb`) line mesh of the 3D curve.
+    Gltf,
+    /// Animated GIF produced by a recording.
+    Gif,
+}
+
+impl ExportFormat {
+    /// File extension used for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+            Self::Obj => "obj",
+            Self::Gltf => "glb",
+            Self::Gif => "gif",
+        }
+    }
+
+    /// Menu label for this format.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Png => "PNG…",
+            Self::Svg => "SVG…",
+            Self::Obj => "OBJ…",
+            Self::Gltf => "glTF…",
+            Self::Gif => "GIF…",
+        }
+    }
+
+    /// MIME type used for browser downloads.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+            Self::Obj => "model/obj",
+            Self::Gltf => "model/gltf-binary",
+            Self::Gif => "image/gif",
+        }
+    }
+}
+
+/// Everything needed to rebuild the 2D canvas as an SVG document.
+pub struct Svg2dScene<'a> {
+    /// Curve points in traversal order.
+    pub points: &'a [[u32; 2]],
+    /// Side length of the curve grid.
+    pub curve_size: u32,
+    /// Edge length of the square canvas in logical pixels.
+    pub canvas_size: f32,
+    /// Indices currently covered by the snake overlay (empty when disabled).
+    pub snake_segments: &'a [usize],
+    /// Display settings shared with the live view.
+    pub settings: &'a SharedSettings,
+}
+
+/// Render the 2D canvas as an SVG document matching the on-screen layout.
+pub fn svg_2d(scene: &Svg2dScene<'_>) -> String {
+    let size = scene.canvas_size;
+    let margin = theme::canvas_2d::MARGIN;
+    let scale = (size - margin * 2.0) / scene.curve_size.saturating_sub(1).max(1) as f32;
+    let rect = Rect::from_min_size(Pos2::ZERO, egui::vec2(size, size));
+    let screen = build_screen_points(scene.points, rect, scale, margin);
+    let show_long_jumps = scene.settings.show_long_jumps;
+    let palette = scene.settings.theme.palette();
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" \
+         viewBox=\"0 0 {size} {size}\">\n"
+    );
+    svg.push_str(&format!(
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        hex_color(palette.canvas_background)
+    ));
+
+    let line = scene.settings.line_style;
+    let line_width = line.width;
+    if scene.settings.curve_opacity > 0.0 {
+        let color = palette.curve_with_brightness(1.0, scene.settings.curve_opacity);
+        let all: Vec<usize> = (0..scene.points.len()).collect();
+        // Highlighted jumps replace the solid ones.
+        let solid_jumps = show_long_jumps && !scene.settings.highlight_jumps;
+        for run in polyline_runs(scene.points, &all, solid_jumps) {
+            write_polyline(&mut svg, &run_positions(&screen, &run), color, line);
+        }
+    }
+
+    if scene.settings.highlight_jumps {
+        let color = hex_color(palette.jump);
+        let dash = format!("{} {}", theme::jumps::DASH_LENGTH, theme::jumps::GAP_LENGTH);
+        for (i, pair) in scene.points.windows(2).enumerate() {
+            if !is_adjacent_2d(&pair[0], &pair[1]) {
+                let (a, b) = (screen[i], screen[i + 1]);
+                svg.push_str(&format!(
+                    "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{color}\" \
+                     stroke-width=\"{line_width}\" stroke-dasharray=\"{dash}\"/>\n",
+                    a.x, a.y, b.x, b.y
+                ));
+            }
+        }
+    }
+
+    if !scene.snake_segments.is_empty() {
+        let style = scene.settings.snake_style;
+        let shading = SnakeShading::new(style, scene.snake_segments, scene.points.len());
+        let snake_line = line.scaled(theme::canvas_2d::SNAKE_WIDTH_MULTIPLIER * style.width);
+        for run in polyline_runs(scene.points, scene.snake_segments, show_long_jumps) {
+            for piece in shading.split_run(&run) {
+                let color = shading.color(&palette, 1.0, piece[0]);
+                write_polyline(&mut svg, &run_positions(&screen, piece), color, snake_line);
+            }
+        }
+        if style.head_marker
+            && let Some(&head) = shading.head().and_then(|head| screen.get(head))
+        {
+            svg.push_str(&format!(
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"{}\" stroke=\"{}\" \
+                 stroke-width=\"{}\"/>\n",
+                head.x,
+                head.y,
+                snake_line.width * theme::snake_style::HEAD_RADIUS_MULTIPLIER,
+                hex_color(palette.accent),
+                hex_color(palette.text_primary),
+                theme::snake_style::HEAD_OUTLINE_WIDTH
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Split a sequence of curve indices into polylines of consecutive, drawable points.
+///
+/// Runs break where indices stop being consecutive or, unless `show_long_jumps` is set, where
+/// the curve jumps between non-adjacent cells. Single-point runs are kept so isolated cells
+/// can be drawn as half-segments like the live view does.
+fn polyline_runs(points: &[[u32; 2]], indices: &[usize], show_long_jumps: bool) -> Vec<Vec<usize>> {
+    let mut runs: Vec<Vec<usize>> = Vec::new();
+    for &i in indices.iter().filter(|&&i| i < points.len()) {
+        let extends = runs.last().and_then(|run| run.last()).is_some_and(|&prev| {
+            prev + 1 == i && (show_long_jumps || is_adjacent_2d(&points[prev], &points[i]))
+        });
+        if extends {
+            if let Some(run) = runs.last_mut() {
+                run.push(i);
+            }
+        } else {
+            runs.push(vec![i]);
+        }
+    }
+    runs
+}
+
+/// Screen positions for a run, expanding isolated points into half-segments.
+fn run_positions(screen: &[Pos2], run: &[usize]) -> Vec<Pos2> {
+    if let [i] = *run {
+        let current = screen[i];
+        let neighbour = if i + 1 < screen.len() {
+            screen[i + 1]
+        } else if i > 0 {
+            current + (current - screen[i - 1])
+        } else {
+            return Vec::new();
+        };
+        return vec![current, current + (neighbour - current) * 0.5];
+    }
+    run.iter().map(|&i| screen[i]).collect()
+}
+
+/// Append an SVG polyline element for `positions`.
+fn write_polyline(svg: &mut String, positions: &[Pos2], color: Color32, line: LineStyle) {
+    if positions.len() < 2 {
+        return;
+    }
+    let coords: Vec<String> = positions
+        .iter()
+        .map(|p| format!("{:.2},{:.2}", p.x, p.y))
+        .collect();
+    let [_, _, _, alpha] = color.to_srgba_unmultiplied();
+    svg.push_str(&format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{:.3}\" \
+         stroke-width=\"{}\" stroke-linejoin=\"{}\" stroke-miterlimit=\"{}\"/>\n",
+        coords.join(" "),
+        hex_color(color),
+        f32::from(alpha) / 255.0,
+        line.width,
+        line.join.svg_name(),
+        theme::canvas_2d::MITER_LIMIT
+    ));
+}
+
+/// Format a color as an opaque `#rrggbb` string.
+fn hex_color(color: Color32) -> String {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Curve segments drawn in the 3D view, as pairs of point indices.
+fn segments_3d(points: &[[u32; 3]], show_long_jumps: bool) -> Vec<[u32; 2]> {
+    (1..points.len())
+        .filter(|&i| show_long_jumps || is_adjacent_3d(&points[i - 1], &points[i]))
+        .map(|i| [i as u32 - 1, i as u32])
+        .collect()
+}
+
+/// Render the 3D curve as a Wavefront OBJ document in grid coordinates.
+///
+/// Consecutive drawable segments are merged into `l` polyline elements.
+pub fn obj_3d(name: &str, points: &[[u32; 3]], show_long_jumps: bool) -> String {
+    let mut obj = format!("# spacecurve export\no {name}\n");
+    for p in points {
+        obj.push_str(&format!("v {} {} {}\n", p[0], p[1], p[2]));
+    }
+
+    let mut run: Vec<u32> = Vec::new();
+    let flush = |run: &mut Vec<u32>, obj: &mut String| {
+        if run.len() >= 2 {
+            let refs: Vec<String> = run.iter().map(|i| (i + 1).to_string()).collect();
+            obj.push_str(&format!("l {}\n", refs.join(" ")));
+        }
+        run.clear();
+    };
+    for [a, b] in segments_3d(points, show_long_jumps) {
+        if run.last() != Some(&a) {
+            flush(&mut run, &mut obj);
+            run.push(a);
+        }
+        run.push(b);
+    }
+    flush(&mut run, &mut obj);
+    obj
+}
+
+/// glTF component type for 32-bit floats.
+const GLTF_FLOAT: u32 = 5126;
+/// glTF component type for 32-bit unsigned integers.
+const GLTF_UNSIGNED_INT: u32 = 5125;
+/// glTF buffer view target for vertex attributes.
+const GLTF_ARRAY_BUFFER: u32 = 34962;
+/// glTF buffer view target for indices.
+const GLTF_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Render the 3D curve as a binary glTF (`.glb`) line mesh in grid coordinates.
+pub fn glb_3d(name: &str, points: &[[u32; 3]], show_long_jumps: bool) -> Vec<u8> {
+    let segments = segments_3d(points, show_long_jumps);
+
+    let mut bin = Vec::with_capacity(points.len() * 12 + segments.len() * 8);
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in points {
+        for axis in 0..3 {
+            let v = p[axis] as f32;
+            min[axis] = min[axis].min(v);
+            max[axis] = max[axis].max(v);
+            bin.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    let positions_len = bin.len();
+    for index in segments.iter().flatten() {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    let indices_len = bin.len() - positions_len;
+    if points.is_empty() {
+        min = [0.0; 3];
+        max = [0.0; 3];
+    }
+
+    // Without drawable segments fall back to a point cloud, since glTF forbids empty accessors.
+    let (primitive, index_views, index_accessors) = if segments.is_empty() {
+        (
+            r#"{"attributes":{"POSITION":0},"mode":0}"#.to_string(),
+            String::new(),
+            String::new(),
+        )
+    } else {
+        (
+            r#"{"attributes":{"POSITION":0},"indices":1,"mode":1}"#.to_string(),
+            format!(
+                r#",{{"buffer":0,"byteOffset":{positions_len},"byteLength":{indices_len},"target":{GLTF_ELEMENT_ARRAY_BUFFER}}}"#
+            ),
+            format!(
+                r#",{{"bufferView":1,"componentType":{GLTF_UNSIGNED_INT},"count":{},"type":"SCALAR"}}"#,
+                segments.len() * 2
+            ),
+        )
+    };
+    let json = format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"spacecurve"}},"#,
+            r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0,"name":"{name}"}}],"#,
+            r#""meshes":[{{"name":"{name}","primitives":[{primitive}]}}],"#,
+            r#""buffers":[{{"byteLength":{buffer_len}}}],"#,
+            r#""bufferViews":[{{"buffer":0,"byteOffset":0,"byteLength":{positions_len},"target":{array_buffer}}}{index_views}],"#,
+            r#""accessors":[{{"bufferView":0,"componentType":{float},"count":{count},"type":"VEC3","#,
+            r#""min":[{min0},{min1},{min2}],"max":[{max0},{max1},{max2}]}}{index_accessors}]}}"#
+        ),
+        name = name,
+        primitive = primitive,
+        buffer_len = bin.len(),
+        positions_len = positions_len,
+        array_buffer = GLTF_ARRAY_BUFFER,
+        index_views = index_views,
+        float = GLTF_FLOAT,
+        count = points.len(),
+        min0 = min[0],
+        min1 = min[1],
+        min2 = min[2],
+        max0 = max[0],
+        max1 = max[1],
+        max2 = max[2],
+        index_accessors = index_accessors,
+    );
+
+    glb_container(json.into_bytes(), bin)
+}
+
+/// Wrap a glTF JSON document and binary buffer into a `.glb` container.
+fn glb_container(mut json: Vec<u8>, mut bin: Vec<u8>) -> Vec<u8> {
+    const MAGIC: u32 = 0x4654_6c67;
+    const CHUNK_JSON: u32 = 0x4e4f_534a;
+    const CHUNK_BIN: u32 = 0x004e_4942;
+
+    // Chunks must be 4-byte aligned: JSON pads with spaces, binary with zeros.
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let total = 12 + 8 + json.len() + 8 + bin.len();
+    let mut out = Vec::with_capacity(total);
+    out.extend_from_slice(&MAGIC.to_le_bytes());
+    out.extend_from_slice(&2u32.to_le_bytes());
+    out.extend_from_slice(&(total as u32).to_le_bytes());
+    out.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    out.extend_from_slice(&CHUNK_JSON.to_le_bytes());
+    out.extend_from_slice(&json);
+    out.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    out.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+    out.extend_from_slice(&bin);
+    out
+}
+
+/// Hand exported bytes to the user through a native save dialog.
+///
+/// Returns without writing anything when the dialog is cancelled.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn deliver(file_name: &str, format: ExportFormat, bytes: &[u8]) -> Result<()> {
+    let dialog = rfd::FileDialog::new()
+        .set_file_name(file_name)
+        .add_filter(format.extension(), &[format.extension()]);
+    if let Some(path) = dialog.save_file() {
+        fs::write(path, bytes)?;
+    }
+    Ok(())
+}
+
+/// Ask the user for a directory and write numbered PNG frames `{stem}-0000.png`, … into it.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn deliver_frames(stem: &str, frames: &[Vec<u8>]) -> Result<()> {
+    let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+        return Ok(());
+    };
+    for (i, bytes) in frames.iter().enumerate() {
+        fs::write(dir.join(format!("{stem}-{i:04}.png")), bytes)?;
+    }
+    Ok(())
+}
+
+/// Hand numbered PNG frames to the user as a series of browser downloads.
+#[cfg(target_arch = "wasm32")]
+pub fn deliver_frames(stem: &str, frames: &[Vec<u8>]) -> Result<()> {
+    for (i, bytes) in frames.iter().enumerate() {
+        deliver(&format!("{stem}-{i:04}.png"), ExportFormat::Png, bytes)?;
+    }
+    Ok(())
+}
+
+/// Hand exported bytes to the user as a browser download.
+#[cfg(target_arch = "wasm32")]
+pub fn deliver(file_name: &str, format: ExportFormat, bytes: &[u8]) -> Result<()> {
+    let js_err = |e: JsValue| anyhow::anyhow!("{e:?}");
+    let parts = Array::of1(&Uint8Array::from(bytes));
+    let options = BlobPropertyBag::new();
+    options.set_type(format.mime_type());
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options).map_err(js_err)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(js_err)?;
+
+    let document = window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| anyhow::anyhow!("document not available"))?;
+    let anchor = document
+        .create_element("a")
+        .map_err(js_err)?
+        .dyn_into::<HtmlAnchorElement>()
+        .map_err(|_| anyhow::anyhow!("failed to create download link"))?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    Url::revoke_object_url(&url).map_err(js_err)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    /// A 2x2 Z-order curve: one long jump between its second and third points.
+    const ZORDER_2D: [[u32; 2]; 4] = [[0, 0], [1, 0], [0, 1], [1, 1]];
+    /// A 3D walk whose final step jumps across the grid.
+    const WALK_3D: [[u32; 3]; 4] = [[0, 0, 0], [1, 0, 0], [1, 1, 0], [0, 0, 1]];
+
+    fn svg(show_long_jumps: bool, highlight_jumps: bool) -> String {
+        let settings = SharedSettings {
+            show_long_jumps,
+            highlight_jumps,
+            ..SharedSettings::default()
+        };
+        svg_2d(&Svg2dScene {
+            points: &ZORDER_2D,
+            curve_size: 2,
+            canvas_size: 100.0,
+            snake_segments: &[],
+            settings: &settings,
+        })
+    }
+
+    /// Number of `(x, y)` pairs in each polyline of `svg`.
+    fn polyline_lengths(svg: &str) -> Vec<usize> {
+        svg.lines()
+            .filter_map(|line| line.strip_prefix("<polyline points=\""))
+            .map(|rest| rest.split('"').next().unwrap().split(' ').count())
+            .collect()
+    }
+
+    #[test]
+    fn svg_has_header_background_and_closing_tag() {
+        let svg = svg(false, false);
+        let lines: Vec<&str> = svg.lines().collect();
+        assert!(lines[0].starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(lines[0].contains("width=\"100\" height=\"100\" viewBox=\"0 0 100 100\""));
+        assert!(lines[1].starts_with("<rect width=\"100%\" height=\"100%\" fill=\"#"));
+        assert_eq!(lines.last(), Some(&"</svg>"));
+        assert_eq!(svg.matches("<svg").count(), 1);
+    }
+
+    #[test]
+    fn svg_splits_polylines_at_long_jumps() {
+        assert_eq!(polyline_lengths(&svg(false, false)), [2, 2]);
+        assert_eq!(polyline_lengths(&svg(true, false)), [4]);
+        assert!(!svg(true, false).contains("<line "));
+
+        // Highlighted jumps are drawn as separate dashed lines.
+        let highlighted = svg(true, true);
+        assert_eq!(polyline_lengths(&highlighted), [2, 2]);
+        assert_eq!(highlighted.matches("<line ").count(), 1);
+        assert!(highlighted.contains("stroke-dasharray"));
+    }
+
+    #[test]
+    fn obj_lists_vertices_and_segments() {
+        let obj = obj_3d("walk", &WALK_3D, false);
+        let lines: Vec<&str> = obj.lines().collect();
+        assert_eq!(lines[..2], ["# spacecurve export", "o walk"]);
+        let vertices: Vec<&str> = lines
+            .iter()
+            .filter(|l| l.starts_with("v "))
+            .copied()
+            .collect();
+        assert_eq!(vertices, ["v 0 0 0", "v 1 0 0", "v 1 1 0", "v 0 0 1"]);
+        // The jump to the last point leaves it unconnected.
+        let segments: Vec<&str> = lines
+            .iter()
+            .filter(|l| l.starts_with("l "))
+            .copied()
+            .collect();
+        assert_eq!(segments, ["l 1 2 3"]);
+
+        let obj = obj_3d("walk", &WALK_3D, true);
+        assert_eq!(
+            obj.lines()
+                .filter(|l| l.starts_with("l "))
+                .collect::<Vec<_>>(),
+            ["l 1 2 3 4"]
+        );
+    }
+
+    /// Split a `.glb` file into its JSON document and binary chunk, checking the container.
+    fn parse_glb(glb: &[u8]) -> (Value, Vec<u8>) {
+        let word = |at: usize| u32::from_le_bytes(glb[at..at + 4].try_into().unwrap()) as usize;
+        assert_eq!(&glb[..4], b"glTF");
+        assert_eq!(word(4), 2);
+        assert_eq!(word(8), glb.len());
+
+        let json_len = word(12);
+        assert_eq!(&glb[16..20], b"JSON");
+        assert_eq!(json_len % 4, 0);
+        let json = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+
+        let bin_at = 20 + json_len;
+        let bin_len = word(bin_at);
+        assert_eq!(&glb[bin_at + 4..bin_at + 8], b"BIN\0");
+        assert_eq!(bin_at + 8 + bin_len, glb.len());
+        (json, glb[bin_at + 8..].to_vec())
+    }
+
+    #[test]
+    fn glb_holds_positions_and_line_indices() {
+        let (json, bin) = parse_glb(&glb_3d("walk", &WALK_3D, false));
+        assert_eq!(json["asset"]["version"], "2.0");
+        assert_eq!(json["nodes"][0]["name"], "walk");
+        let primitive = &json["meshes"][0]["primitives"][0];
+        assert_eq!(primitive["mode"], 1);
+        assert_eq!(primitive["indices"], 1);
+
+        let positions = &json["accessors"][0];
+        assert_eq!(positions["count"], 4);
+        assert_eq!(positions["type"], "VEC3");
+        assert_eq!(positions["min"], serde_json::json!([0, 0, 0]));
+        assert_eq!(positions["max"], serde_json::json!([1, 1, 1]));
+        let indices = &json["accessors"][1];
+        assert_eq!(indices["count"], 4);
+        assert_eq!(json["buffers"][0]["byteLength"], 4 * 12 + 4 * 4);
+
+        let words: Vec<u32> = bin
+            .chunks_exact(4)
+            .map(|w| u32::from_le_bytes(w.try_into().unwrap()))
+            .collect();
+        let floats: Vec<f32> = words[..12].iter().map(|&w| f32::from_bits(w)).collect();
+        assert_eq!(
+            floats,
+            [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+        );
+        assert_eq!(words[12..], [0, 1, 1, 2]);
+
+        let (json, _) = parse_glb(&glb_3d("walk", &WALK_3D, true));
+        assert_eq!(json["accessors"][1]["count"], 6);
+    }
+
+    #[test]
+    fn glb_without_segments_is_a_point_cloud() {
+        let (json, bin) = parse_glb(&glb_3d("dot", &[[2, 3, 4]], false));
+        let primitive = &json["meshes"][0]["primitives"][0];
+        assert_eq!(primitive["mode"], 0);
+        assert!(primitive.get("indices").is_none());
+        assert_eq!(json["accessors"].as_array().unwrap().len(), 1);
+        assert_eq!(json["bufferViews"].as_array().unwrap().len(), 1);
+        assert_eq!(bin.len(), 12);
+    }
+}
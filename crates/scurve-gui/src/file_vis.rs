@@ -0,0 +1,554 @@
+//! File visualization pane: a file's bytes laid out along a 2D curve.
+//!
+//! This is the GUI counterpart of the CLI's `vis` command. Each grid cell shows the byte
+//! sampled at its curve position, colored by byte class, so regions of similar content in
+//! the file show up as regions of the image. Files are opened through a native dialog on
+//! desktop, the browser's file picker on the web, or by dropping them on the window.
+//!
+//! A hex dump can be docked beside the image. [`ByteLayout`] maps between curve positions and
+//! byte offsets so the two stay in step: the dump follows the bytes under the image's pointer,
+//! and bytes hovered, selected or scrolled into view in the dump are marked on the image.
+
+use std::{
+    fs, mem,
+    ops::Range,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Result, bail};
+use egui::{Color32, ColorImage, Mesh, TextureHandle, TextureOptions};
+
+use crate::{
+    AppState, HoverPoint,
+    export::ExportFormat,
+    geometry,
+    hex_view::{self, HexView},
+    selection::SelectedCurve,
+    theme, widgets,
+};
+
+/// A file loaded into the pane.
+#[derive(Debug, Clone)]
+pub struct LoadedFile {
+    /// File name shown in the control bar, without directories.
+    pub name: String,
+    /// File contents; never empty.
+    pub bytes: Vec<u8>,
+}
+
+impl LoadedFile {
+    /// Wrap `bytes` read from `name`, rejecting empty files that have nothing to show.
+    pub fn new(name: String, bytes: Vec<u8>) -> Result<Self> {
+        if bytes.is_empty() {
+            bail!("{name} is empty");
+        }
+        Ok(Self { name, bytes })
+    }
+}
+
+/// A file picked by a dialog that may complete after the frame that opened it.
+type PendingFile = Arc<Mutex<Option<Result<LoadedFile>>>>;
+
+/// State of the file pane.
+pub struct FileView {
+    /// Curve and grid size the bytes are laid out along.
+    pub curve: SelectedCurve,
+    /// The file being shown, if one was opened.
+    pub file: Option<LoadedFile>,
+    /// Why the last open failed, shown until the next file loads.
+    pub error: Option<String>,
+    /// Open the file dialog at the end of the frame.
+    pub open_request: bool,
+    /// Dock a hex dump of the file beside the image.
+    pub show_hex: bool,
+    /// Selected bytes, shown in both the hex dump and the image.
+    pub selection: Option<Range<usize>>,
+    /// Scroll and drag state of the hex dump.
+    hex: HexView,
+    /// Bytes under the pointer in the image last frame.
+    image_hover: Option<Range<usize>>,
+    /// Result of the dialog in flight, filled in by the dialog itself.
+    pending: PendingFile,
+    /// Bumped on every load so the texture cache sees new contents.
+    generation: u64,
+    /// Rendered bytes and the cache key they were built for.
+    texture: Option<(u64, TextureHandle)>,
+}
+
+impl FileView {
+    /// An empty pane laying files out along `curve`.
+    pub fn with_curve(curve: &str) -> Self {
+        let mut selection = SelectedCurve::with_name(curve);
+        selection.size = theme::size_limits::DEFAULT_FILE;
+        Self {
+            curve: selection,
+            file: None,
+            error: None,
+            open_request: false,
+            show_hex: false,
+            selection: None,
+            hex: HexView::default(),
+            image_hover: None,
+            pending: PendingFile::default(),
+            generation: 0,
+            texture: None,
+        }
+    }
+
+    /// Open requested dialogs and take in picked or dropped files.
+    ///
+    /// Returns true when a new file was loaded this frame.
+    pub fn update(&mut self, ctx: &egui::Context) -> bool {
+        if mem::take(&mut self.open_request) {
+            open_dialog(ctx, &self.pending);
+        }
+        let dropped = ctx.input(|i| i.raw.dropped_files.first().cloned());
+        let result = match dropped {
+            Some(file) => Some(load_dropped(file)),
+            None => self.pending.lock().ok().and_then(|mut slot| slot.take()),
+        };
+        match result {
+            Some(Ok(file)) => {
+                self.file = Some(file);
+                self.error = None;
+                self.selection = None;
+                self.generation += 1;
+                true
+            }
+            Some(Err(err)) => {
+                self.error = Some(err.to_string());
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// File name suggested for exports: the file's name followed by the curve and size.
+    pub fn export_stem(&self) -> String {
+        let file = self.file.as_ref().map_or("file", |f| &f.name);
+        format!("{file}-{}-{}", self.curve.name, self.curve.size)
+    }
+
+    /// How the open file's bytes are spread over the current curve.
+    fn layout(&mut self) -> Option<ByteLayout> {
+        let bytes = self.file.as_ref()?.bytes.len();
+        let points = self.curve.ensure_curve_length()? as usize;
+        Some(ByteLayout { points, bytes })
+    }
+
+    /// The texture showing the file along the current curve, rebuilt when either changes.
+    fn texture(&mut self, ctx: &egui::Context) -> Option<TextureHandle> {
+        let key = geometry::cache_key((&self.curve.name, self.curve.size, self.generation));
+        if let Some((cached, texture)) = &self.texture
+            && *cached == key
+        {
+            return Some(texture.clone());
+        }
+        let bytes = &self.file.as_ref()?.bytes;
+        let size = self.curve.size;
+        let points = self.curve.ensure_cached_points()?;
+        let texture = ctx.load_texture(
+            "file_vis",
+            file_image(points, size, bytes),
+            TextureOptions::NEAREST,
+        );
+        self.texture = Some((key, texture.clone()));
+        Some(texture)
+    }
+}
+
+/// Render the file pane, including controls, the byte image and the hex dump.
+pub fn show_file_pane(
+    ui: &mut egui::Ui,
+    app_state: &mut AppState,
+    view: &mut FileView,
+    available_curves: &[&str],
+) {
+    file_controls(ui, app_state, view, available_curves);
+
+    ui.separator();
+
+    let hex = hex_panel(ui, view);
+
+    let available_rect = ui.available_rect_before_wrap();
+    let palette = theme::palette(ui.ctx());
+    ui.painter_at(available_rect)
+        .rect_filled(available_rect, 0.0, palette.canvas_background);
+    let Some(texture) = view.texture(ui.ctx()) else {
+        let hint = if view.file.is_none() {
+            "Open a file or drop one here"
+        } else {
+            "No valid curve at this size"
+        };
+        ui.painter_at(available_rect).text(
+            available_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            hint,
+            egui::FontId::proportional(theme::font_size::INFO),
+            palette.text_dim,
+        );
+        app_state.last_canvas_rect = None;
+        return;
+    };
+
+    let side = (available_rect.width().min(available_rect.height())
+        * theme::canvas_2d::SIZE_FRACTION)
+        .max(theme::canvas_2d::MIN_SIZE);
+    let image_rect = egui::Rect::from_center_size(available_rect.center(), egui::Vec2::splat(side));
+    let painter = ui.painter_at(available_rect);
+    painter.image(
+        texture.id(),
+        image_rect,
+        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+        Color32::WHITE,
+    );
+    app_state.last_canvas_rect = Some(image_rect);
+    let Some(layout) = view.layout() else {
+        return;
+    };
+
+    if let Some(hex) = &hex {
+        let visible = palette
+            .text_primary
+            .gamma_multiply(theme::hex_view::VISIBLE_OPACITY);
+        mark_bytes(
+            &painter,
+            view,
+            layout,
+            image_rect,
+            hex.visible.clone(),
+            visible,
+        );
+    }
+    if let Some(selection) = view.selection.clone() {
+        let selected = palette
+            .accent
+            .gamma_multiply(theme::hex_view::SELECTION_OPACITY);
+        mark_bytes(&painter, view, layout, image_rect, selection, selected);
+    }
+    if let Some(offset) = hex.as_ref().and_then(|h| h.hovered) {
+        mark_bytes(
+            &painter,
+            view,
+            layout,
+            image_rect,
+            offset..offset + 1,
+            palette.accent,
+        );
+    }
+
+    let response = ui.allocate_rect(available_rect, egui::Sense::click());
+    app_state.hovered_point = response
+        .hover_pos()
+        .filter(|pos| image_rect.contains(*pos))
+        .and_then(|pos| hovered_cell(view, image_rect, pos));
+    follow_image_pointer(
+        view,
+        layout,
+        &response,
+        app_state.hovered_point.as_ref(),
+        hex,
+    );
+    if let (Some(point), Some(file)) = (&app_state.hovered_point, &view.file) {
+        let offset = layout.offset(point.position);
+        response.on_hover_ui_at_pointer(|ui| {
+            widgets::point_inspector(ui, point);
+            ui.label(
+                egui::RichText::new(format!(
+                    "Offset: {offset:#x}  Byte: {:#04x}",
+                    file.bytes[offset]
+                ))
+                .size(theme::font_size::INFO)
+                .color(theme::palette(ui.ctx()).text_body),
+            );
+        });
+    }
+}
+
+/// Control bar of the file pane.
+fn file_controls(
+    ui: &mut egui::Ui,
+    app_state: &mut AppState,
+    view: &mut FileView,
+    available_curves: &[&str],
+) {
+    widgets::control_bar_frame().show(ui, |ui| {
+        ui.horizontal(|ui| {
+            if ui.button("Open…").clicked() {
+                view.open_request = true;
+            }
+            file_label(ui, view);
+
+            ui.separator();
+
+            ui.label(
+                egui::RichText::new("Curve:")
+                    .size(theme::font_size::INFO)
+                    .color(theme::palette(ui.ctx()).text_dim),
+            );
+            widgets::curve_selector_combo(
+                ui,
+                &mut view.curve.name,
+                available_curves,
+                "file_curve_selector",
+                &mut view.curve.info_open,
+                2,
+                view.curve.size,
+            );
+
+            ui.separator();
+
+            ui.label(
+                egui::RichText::new("Size:")
+                    .size(theme::font_size::INFO)
+                    .color(theme::palette(ui.ctx()).text_dim),
+            );
+            widgets::size_input(
+                ui,
+                &mut view.curve.size,
+                &view.curve.name,
+                2,
+                theme::size_limits::MAX_FILE,
+            );
+
+            ui.separator();
+
+            ui.checkbox(&mut view.show_hex, "Hex");
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                widgets::export_menu(ui, &mut app_state.export_request, &[ExportFormat::Png]);
+            });
+        });
+    });
+}
+
+/// Dock the hex dump on the right when it is enabled and a file is open.
+fn hex_panel(ui: &mut egui::Ui, view: &mut FileView) -> Option<hex_view::HexResponse> {
+    if !view.show_hex {
+        return None;
+    }
+    let file = view.file.as_ref()?;
+    let panel = egui::SidePanel::right("file_hex_view")
+        .resizable(true)
+        .default_width(theme::hex_view::DEFAULT_WIDTH);
+    Some(
+        panel
+            .show_inside(ui, |ui| {
+                hex_view::show(
+                    ui,
+                    &file.bytes,
+                    &mut view.hex,
+                    &mut view.selection,
+                    view.image_hover.as_ref(),
+                )
+            })
+            .inner,
+    )
+}
+
+/// Keep the hex dump on the bytes under the image's pointer, and select them on click.
+fn follow_image_pointer(
+    view: &mut FileView,
+    layout: ByteLayout,
+    response: &egui::Response,
+    hovered: Option<&HoverPoint>,
+    hex: Option<hex_view::HexResponse>,
+) {
+    let bytes = hovered.map(|point| layout.bytes_of(point.position..point.position + 1));
+    if let (Some(bytes), Some(hex)) = (&bytes, hex)
+        && !hex.visible.contains(&bytes.start)
+    {
+        view.hex.scroll_to = Some(bytes.start);
+    }
+    if response.clicked() {
+        view.selection.clone_from(&bytes);
+    }
+    view.image_hover = bytes;
+}
+
+/// Shade the image cells of the points showing `bytes`.
+fn mark_bytes(
+    painter: &egui::Painter,
+    view: &mut FileView,
+    layout: ByteLayout,
+    image_rect: egui::Rect,
+    bytes: Range<usize>,
+    color: Color32,
+) {
+    let cell = image_rect.width() / view.curve.size as f32;
+    let Some(points) = view.curve.ensure_cached_points() else {
+        return;
+    };
+    let mut mesh = Mesh::default();
+    let positions = layout.positions_of(bytes);
+    for [x, y] in points[positions]
+        .iter()
+        .take(theme::hex_view::MAX_MARKED_CELLS)
+    {
+        let min = image_rect.min + egui::vec2(*x as f32, *y as f32) * cell;
+        mesh.add_colored_rect(
+            egui::Rect::from_min_size(min, egui::Vec2::splat(cell)),
+            color,
+        );
+    }
+    painter.add(mesh);
+}
+
+/// Name and size of the open file, or the last open error.
+fn file_label(ui: &mut egui::Ui, view: &FileView) {
+    let palette = theme::palette(ui.ctx());
+    let (text, color) = match (&view.error, &view.file) {
+        (Some(err), _) => (err.clone(), palette.text_error),
+        (None, Some(file)) => (
+            format!("{} ({} bytes)", file.name, file.bytes.len()),
+            palette.text_body,
+        ),
+        (None, None) => ("No file".to_string(), palette.text_dim),
+    };
+    ui.label(
+        egui::RichText::new(text)
+            .size(theme::font_size::INFO)
+            .color(color),
+    );
+}
+
+/// The curve point in the cell under `pos` within `image_rect`.
+fn hovered_cell(
+    view: &mut FileView,
+    image_rect: egui::Rect,
+    pos: egui::Pos2,
+) -> Option<HoverPoint> {
+    let cell_size = image_rect.width() / view.curve.size as f32;
+    let cell = ((pos - image_rect.min) / cell_size).floor();
+    let position = view.curve.position_of([cell.x as u32, cell.y as u32])?;
+    view.curve.hover_point(position)
+}
+
+/// How a file's bytes are spread over the points of a curve.
+///
+/// Each point shows the byte [`sample_offset`] picks for it and stands for the bytes up to
+/// the next point's, so the mapping works both ways whether the file has more bytes than the
+/// curve has points or fewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteLayout {
+    /// Number of curve points.
+    pub points: usize,
+    /// Number of bytes in the file.
+    pub bytes: usize,
+}
+
+impl ByteLayout {
+    /// Offset of the byte shown at curve `position`.
+    pub fn offset(self, position: usize) -> usize {
+        sample_offset(position, self.points, self.bytes)
+    }
+
+    /// Bytes stood for by the points in `positions`.
+    pub fn bytes_of(self, positions: Range<usize>) -> Range<usize> {
+        let start = self.offset(positions.start);
+        let end = if positions.end >= self.points {
+            self.bytes
+        } else {
+            self.offset(positions.end)
+        };
+        start..end.max(start + 1)
+    }
+
+    /// Points standing for any of `bytes`.
+    pub fn positions_of(self, bytes: Range<usize>) -> Range<usize> {
+        // First point showing a byte at or after `offset`.
+        let first = |offset: usize| {
+            ((offset as u128 * self.points as u128).div_ceil(self.bytes.max(1) as u128) as usize)
+                .min(self.points)
+        };
+        let mut start = first(bytes.start);
+        // Bytes skipped by sampling belong to the point before.
+        if start == self.points || self.offset(start) > bytes.start {
+            start = start.saturating_sub(1);
+        }
+        start..first(bytes.end).max(start + 1).min(self.points)
+    }
+}
+
+/// Offset of the byte shown at curve `position` when `len` bytes are spread over `length`
+/// points, using the same integer sampling as the CLI's `vis`.
+pub fn sample_offset(position: usize, length: usize, len: usize) -> usize {
+    let offset = (position as u128 * len as u128 / length.max(1) as u128) as usize;
+    offset.min(len.saturating_sub(1))
+}
+
+/// Color of `byte` in the file image.
+pub fn byte_color(byte: u8) -> Color32 {
+    match byte {
+        0x00 => theme::byte_class::ZERO,
+        0xff => theme::byte_class::FULL,
+        b if b < 31 => theme::byte_class::CONTROL,
+        b if (32..127).contains(&b) => theme::byte_class::PRINTABLE,
+        _ => theme::byte_class::OTHER,
+    }
+}
+
+/// One texel per grid cell, colored by the byte sampled at that cell's curve position.
+fn file_image(points: &[[u32; 2]], size: u32, bytes: &[u8]) -> ColorImage {
+    let side = size as usize;
+    let mut image = ColorImage::filled([side, side], Color32::TRANSPARENT);
+    for (position, [x, y]) in points.iter().enumerate() {
+        let byte = bytes[sample_offset(position, points.len(), bytes.len())];
+        image[(*x as usize, *y as usize)] = byte_color(byte);
+    }
+    image
+}
+
+/// Read a file dropped on the window; browsers hand over the bytes, desktops a path.
+fn load_dropped(file: egui::DroppedFile) -> Result<LoadedFile> {
+    let bytes = match (file.bytes, &file.path) {
+        (Some(bytes), _) => bytes.to_vec(),
+        (None, Some(path)) => fs::read(path)?,
+        (None, None) => bail!("dropped file {} has no contents", file.name),
+    };
+    let name = match &file.path {
+        Some(path) => file_name(path),
+        None => file.name,
+    };
+    LoadedFile::new(name, bytes)
+}
+
+/// Final component of `path`, for display.
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    )
+}
+
+/// Ask the user for a file with a native dialog and read it into `pending`.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_dialog(_ctx: &egui::Context, pending: &PendingFile) {
+    let Some(path) = rfd::FileDialog::new().pick_file() else {
+        return;
+    };
+    let result = fs::read(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| LoadedFile::new(file_name(&path), bytes));
+    if let Ok(mut slot) = pending.lock() {
+        *slot = Some(result);
+    }
+}
+
+/// Show the browser's file picker; the chosen file lands in `pending` once it is read.
+#[cfg(target_arch = "wasm32")]
+fn open_dialog(ctx: &egui::Context, pending: &PendingFile) {
+    let ctx = ctx.clone();
+    let pending = pending.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        let Some(handle) = rfd::AsyncFileDialog::new().pick_file().await else {
+            return;
+        };
+        let result = LoadedFile::new(handle.file_name(), handle.read().await);
+        if let Ok(mut slot) = pending.lock() {
+            *slot = Some(result);
+        }
+        ctx.request_repaint();
+    });
+}
@@ -0,0 +1,113 @@
+//! Cached tessellation of static curve geometry.
+//!
+//! The main curve only changes when the curve, layout or styling changes, so its
+//! line shapes are tessellated once into a single batched mesh and re-submitted
+//! every frame. Only the snake overlay, and the 3D projection while the camera
+//! moves, are rebuilt per frame.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+};
+
+use egui::{Mesh, Shape, epaint::Tessellator};
+
+/// Counters describing how a [`GeometryCache`] has been used, for the debug panel.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    /// Mesh requests served from the cache.
+    pub hits: u64,
+    /// Mesh requests that had to tessellate.
+    pub rebuilds: u64,
+    /// Times the cache was cleared because the curve points changed.
+    pub clears: u64,
+    /// Vertices in the cached mesh.
+    pub vertices: usize,
+    /// Triangles in the cached mesh.
+    pub triangles: usize,
+}
+
+/// Tessellated curve mesh and adjacency flags for one curve selection.
+#[derive(Clone, Default)]
+pub struct GeometryCache {
+    /// Hash of the inputs `mesh` was built from.
+    key: Option<u64>,
+    /// Batched mesh for the main curve.
+    mesh: Arc<Mesh>,
+    /// Whether each pair of consecutive points is adjacent; empty until first requested.
+    adjacency: Vec<bool>,
+    /// Usage counters; these survive [`Self::clear`].
+    stats: CacheStats,
+}
+
+impl GeometryCache {
+    /// Drop all cached geometry, e.g. after the curve points change.
+    pub fn clear(&mut self) {
+        let mut stats = self.stats;
+        stats.clears += 1;
+        *self = Self {
+            stats,
+            ..Self::default()
+        };
+    }
+
+    /// Usage counters and the size of the cached mesh.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            vertices: self.mesh.vertices.len(),
+            triangles: self.mesh.indices.len() / 3,
+            ..self.stats
+        }
+    }
+
+    /// Return the mesh built for `key`, rebuilding it from the shapes pushed by `build` on a miss.
+    pub fn mesh(
+        &mut self,
+        ctx: &egui::Context,
+        key: u64,
+        build: impl FnOnce(&mut Vec<Shape>),
+    ) -> Arc<Mesh> {
+        // Feathering depends on the pixel density, so it is part of every key.
+        let key = cache_key((key, ctx.pixels_per_point().to_bits()));
+        if self.key != Some(key) {
+            let mut shapes = Vec::new();
+            build(&mut shapes);
+            let mut tessellator = Tessellator::new(
+                ctx.pixels_per_point(),
+                ctx.tessellation_options(|options| *options),
+                ctx.fonts(|fonts| fonts.font_image_size()),
+                Vec::new(),
+            );
+            let mut mesh = Mesh::default();
+            for shape in shapes {
+                tessellator.tessellate_shape(shape, &mut mesh);
+            }
+            self.mesh = Arc::new(mesh);
+            self.key = Some(key);
+            self.stats.rebuilds += 1;
+        } else {
+            self.stats.hits += 1;
+        }
+        Arc::clone(&self.mesh)
+    }
+
+    /// Adjacency of each consecutive pair in `points`, computed once per cache lifetime.
+    pub fn adjacency<P>(&mut self, points: &[P], adjacent: impl Fn(&P, &P) -> bool) -> &[bool] {
+        if self.adjacency.is_empty() && points.len() > 1 {
+            self.adjacency = points.windows(2).map(|w| adjacent(&w[0], &w[1])).collect();
+        }
+        &self.adjacency
+    }
+}
+
+/// Hash the inputs that determine a cached mesh.
+pub fn cache_key(parts: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    parts.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Bit patterns of a rect's corners, for use in cache keys.
+pub fn rect_bits(rect: egui::Rect) -> [u32; 4] {
+    [rect.min.x, rect.min.y, rect.max.x, rect.max.y].map(f32::to_bits)
+}
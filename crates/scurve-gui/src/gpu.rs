@@ -0,0 +1,251 @@
+//! Curve point generation in a wgpu compute shader.
+//!
+//! Hilbert and Z-order curves are walked from their [`StateTable`]s on the GPU, one
+//! invocation per index, so large grids don't stall the UI thread while every point is
+//! mapped on the CPU. The device is created on first use; without a usable adapter, or for
+//! curves with no table form, callers fall back to the CPU.
+//!
+//! Reading the points back waits for the device, so [`curve_points`] must only be called off
+//! the UI thread.
+
+use std::{
+    array,
+    sync::{OnceLock, mpsc},
+};
+
+use spacecurve::table::{self, StateTable};
+use wgpu::util::DeviceExt;
+
+use crate::theme;
+
+/// Bits per axis in a packed point; must match `COORD_BITS` in the shader.
+const COORD_BITS: u32 = 10;
+
+/// Invocations per workgroup; must match `@workgroup_size` in the shader.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Shared compute device, or `None` when no adapter could be opened.
+static GENERATOR: OnceLock<Option<Generator>> = OnceLock::new();
+
+/// A compute device with the table-walking pipeline.
+struct Generator {
+    /// Device owning buffers and the pipeline.
+    device: wgpu::Device,
+    /// Queue used to submit generation passes.
+    queue: wgpu::Queue,
+    /// Pipeline running `curve.wgsl`.
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl Generator {
+    /// Open the default adapter and build the pipeline.
+    fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .ok()?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("curve generator"),
+            required_limits: adapter.limits(),
+            ..Default::default()
+        }))
+        .ok()?;
+        let module = device.create_shader_module(wgpu::include_wgsl!("curve.wgsl"));
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("curve generator"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+        Some(Self {
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    /// Packed points of every index of `table`, or `None` if the device can't hold them.
+    fn run(&self, table: &StateTable, length: u32) -> Option<Vec<u32>> {
+        let size = u64::from(length) * 4;
+        if size > self.device.limits().max_storage_buffer_binding_size.into() {
+            return None;
+        }
+        let groups = length.div_ceil(WORKGROUP_SIZE);
+        let columns = groups.min(self.device.limits().max_compute_workgroups_per_dimension);
+        let rows = groups.div_ceil(columns);
+
+        let words = |values: &[u32]| {
+            values
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>()
+        };
+        let params = words(&[
+            table.dimension,
+            table.order,
+            length,
+            columns * WORKGROUP_SIZE,
+        ]);
+        let transitions: Vec<u32> = table
+            .transitions
+            .iter()
+            .flat_map(|&(cell, next)| [cell, next])
+            .collect();
+        let init = |label, contents: &[u8], usage| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents,
+                    usage,
+                })
+        };
+        let params = init("params", &params, wgpu::BufferUsages::UNIFORM);
+        let transitions = init(
+            "transitions",
+            &words(&transitions),
+            wgpu::BufferUsages::STORAGE,
+        );
+        let buffer = |label, usage| {
+            self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage,
+                mapped_at_creation: false,
+            })
+        };
+        let points = buffer(
+            "points",
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        );
+        let readback = buffer(
+            "readback",
+            wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        );
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("curve generator"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: transitions.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: points.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(columns, rows, 1);
+        }
+        encoder.copy_buffer_to_buffer(&points, 0, &readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        self.device.poll(wgpu::PollType::wait_indefinitely()).ok()?;
+        receiver.recv().ok()?.ok()?;
+        let packed = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        readback.unmap();
+        Some(packed)
+    }
+}
+
+/// State table and point count of curve `name` on a `dimension`-dimensional grid of side
+/// `size`, when the shader can walk it without regard to how deep the grid is.
+fn walkable(name: &str, dimension: u32, size: u32) -> Option<(StateTable, u32)> {
+    let table = table::state_table(name, dimension, size).ok()??;
+    let length = size.checked_pow(dimension)?;
+    (length >= theme::gpu::MIN_POINTS && dimension <= 3).then_some((table, length))
+}
+
+/// Points of curve `name` on a `D`-dimensional grid of side `size`, generated on the GPU.
+///
+/// Returns `None` when the CPU should generate the points instead: the curve has no state
+/// table, the grid is too small to be worth a dispatch or too deep to pack, or no device is
+/// available. Blocks until the device has finished.
+pub fn curve_points<const D: usize>(name: &str, size: u32) -> Option<Vec<[u32; D]>> {
+    let (table, length) = walkable(name, D as u32, size)?;
+    if table.order > COORD_BITS {
+        return None;
+    }
+    let generator = GENERATOR.get_or_init(Generator::new).as_ref()?;
+    let mask = (1 << COORD_BITS) - 1;
+    let packed = generator.run(&table, length)?;
+    Some(
+        packed
+            .into_iter()
+            .map(|p| array::from_fn(|axis| (p >> (axis as u32 * COORD_BITS)) & mask))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::{self, Request};
+
+    #[test]
+    fn packing_holds_every_offered_size() {
+        let largest = theme::size_limits::MAX_2D
+            .max(theme::size_limits::MAX_3D)
+            .max(theme::size_limits::MAX_FILE);
+        assert!(largest <= 1 << COORD_BITS);
+    }
+
+    /// GPU points of `name` match the CPU's, or `false` when there is no adapter to run on.
+    fn matches_cpu<const D: usize>(name: &str, size: u32) -> bool {
+        let Some(gpu) = curve_points::<D>(name, size) else {
+            return false;
+        };
+        let request = Request {
+            name: name.to_string(),
+            size,
+            slice: None,
+        };
+        let (cpu, _) = worker::generate::<D>(&request).unwrap();
+        assert_eq!(gpu, cpu, "{name} {D}D side {size}");
+        true
+    }
+
+    #[test]
+    fn points_match_the_cpu() {
+        if GENERATOR.get_or_init(Generator::new).is_none() {
+            eprintln!("no GPU adapter, skipping");
+            return;
+        }
+        assert!(matches_cpu::<2>("hilbert", theme::size_limits::MAX_2D));
+        assert!(matches_cpu::<2>("zorder", theme::size_limits::MAX_2D));
+        assert!(matches_cpu::<2>("hilbert", theme::size_limits::MAX_FILE));
+        assert!(matches_cpu::<3>("hilbert", theme::size_limits::MAX_3D));
+        assert!(matches_cpu::<3>("zorder", theme::size_limits::MAX_3D));
+    }
+
+    #[test]
+    fn small_and_tableless_curves_stay_on_the_cpu() {
+        assert!(curve_points::<2>("hilbert", 16).is_none());
+        assert!(curve_points::<2>("onion", theme::size_limits::MAX_2D).is_none());
+    }
+}
@@ -0,0 +1,267 @@
+//! Offscreen rendering of GUI scenes to images, without opening a window.
+//!
+//! The app runs against a bare egui context for a few frames and the last frame is
+//! rasterized in software, so no GPU, display server or event loop is involved. This suits
+//! documentation images and image regression tests, where the viewport screenshot used by
+//! `gui_with_screenshot` depends on a real window.
+
+use std::{array, collections::HashMap, path::Path};
+
+use anyhow::{Result, bail};
+use egui::{
+    Color32, ColorImage, ImageData, Pos2, Rect, TextureId, ViewportId,
+    epaint::{ClippedPrimitive, ImageDelta, Mesh, Primitive, Vertex},
+};
+
+use crate::{GuiOptions, ScreenshotTarget, ScurveApp, save_color_image, theme};
+
+/// Frames run before the capture is taken from the last one.
+///
+/// At 60 fps this is long enough for popups to anchor and for egui's fade-ins to finish.
+const WARMUP_FRAMES: usize = 12;
+
+/// A scene to render offscreen.
+#[derive(Debug, Clone)]
+pub struct HeadlessConfig {
+    /// Which UI state to render.
+    pub target: ScreenshotTarget,
+    /// Window size in points.
+    pub size: [f32; 2],
+    /// Pixels per point of the output (2.0 for high-DPI images).
+    pub pixels_per_point: f32,
+    /// Shared-link state applied before rendering, e.g. `curve=zorder&size=16`.
+    pub link: Option<String>,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            target: ScreenshotTarget::TwoD,
+            size: theme::window::DEFAULT_SIZE,
+            pixels_per_point: 1.0,
+            link: None,
+        }
+    }
+}
+
+/// Render `config` offscreen and return the image.
+pub fn render(config: &HeadlessConfig) -> Result<ColorImage> {
+    let [width, height] = config.size;
+    if !(width >= 1.0 && height >= 1.0 && config.pixels_per_point > 0.0) {
+        bail!(
+            "invalid headless render size {width}x{height} @ {}x",
+            config.pixels_per_point
+        );
+    }
+
+    let ctx = egui::Context::default();
+    // eframe's test constructors give the app a context and frame with no native integration.
+    let creation = eframe::CreationContext::_new_kittest(ctx.clone());
+    let mut app = ScurveApp::with_options(
+        &creation,
+        GuiOptions {
+            initial_link: config.link.clone(),
+            ..GuiOptions::default()
+        },
+    );
+    app.app_state.show_screenshot_target(config.target);
+    let mut frame = eframe::Frame::_new_kittest();
+
+    let mut textures = Textures::default();
+    let mut output = egui::FullOutput::default();
+    for index in 0..WARMUP_FRAMES {
+        // Textures freed after the previous frame are no longer referenced.
+        for id in &output.textures_delta.free {
+            textures.0.remove(id);
+        }
+        app.finish_generation();
+        output = ctx.run(raw_input(config, index), |ctx| {
+            eframe::App::update(&mut app, ctx, &mut frame);
+        });
+        for (id, delta) in &output.textures_delta.set {
+            textures.set(*id, delta);
+        }
+    }
+
+    let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+    let size = [
+        (width * config.pixels_per_point).round() as usize,
+        (height * config.pixels_per_point).round() as usize,
+    ];
+    let mut canvas = Canvas::new(size, ctx.style().visuals.panel_fill);
+    for primitive in &primitives {
+        canvas.paint(primitive, &textures, output.pixels_per_point);
+    }
+    Ok(canvas.into_image())
+}
+
+/// Render `config` offscreen and save it as a PNG.
+pub fn render_to_png(config: &HeadlessConfig, path: &Path) -> Result<()> {
+    save_color_image(path, &render(config)?)
+}
+
+/// Input for frame `index`: a fixed-size root viewport at a steady 60 fps clock.
+fn raw_input(config: &HeadlessConfig, index: usize) -> egui::RawInput {
+    let mut input = egui::RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, config.size.into())),
+        time: Some(index as f64 / 60.0),
+        predicted_dt: 1.0 / 60.0,
+        ..egui::RawInput::default()
+    };
+    input
+        .viewports
+        .entry(ViewportId::ROOT)
+        .or_default()
+        .native_pixels_per_point = Some(config.pixels_per_point);
+    input
+}
+
+/// Textures uploaded by egui, keyed by id.
+#[derive(Default)]
+struct Textures(HashMap<TextureId, ColorImage>);
+
+impl Textures {
+    /// Apply a full or partial texture upload.
+    fn set(&mut self, id: TextureId, delta: &ImageDelta) {
+        let ImageData::Color(image) = &delta.image;
+        match delta.pos {
+            None => {
+                self.0.insert(id, (**image).clone());
+            }
+            Some([left, top]) => {
+                let Some(texture) = self.0.get_mut(&id) else {
+                    return;
+                };
+                for y in 0..image.height() {
+                    for x in 0..image.width() {
+                        texture[(left + x, top + y)] = image[(x, y)];
+                    }
+                }
+            }
+        }
+    }
+
+    /// Nearest texel of texture `id` at `uv`, as premultiplied RGBA in `0..=1`.
+    ///
+    /// Nearest sampling is exact for egui's pixel-aligned glyphs and the white texel that
+    /// untextured shapes use.
+    fn sample(&self, id: TextureId, uv: Pos2) -> [f32; 4] {
+        let Some(texture) = self.0.get(&id) else {
+            return [1.0; 4];
+        };
+        let [width, height] = texture.size;
+        let x = ((uv.x * width as f32) as usize).min(width.saturating_sub(1));
+        let y = ((uv.y * height as f32) as usize).min(height.saturating_sub(1));
+        normalized(texture[(x, y)])
+    }
+}
+
+/// Software framebuffer holding premultiplied RGBA in `0..=1`.
+struct Canvas {
+    /// Width and height in pixels.
+    size: [usize; 2],
+    /// Row-major pixels.
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    /// A canvas cleared to `background`.
+    fn new(size: [usize; 2], background: Color32) -> Self {
+        Self {
+            size,
+            pixels: vec![normalized(background); size[0] * size[1]],
+        }
+    }
+
+    /// Blend one clipped primitive; paint callbacks have no software fallback and are skipped.
+    fn paint(&mut self, primitive: &ClippedPrimitive, textures: &Textures, pixels_per_point: f32) {
+        let Primitive::Mesh(mesh) = &primitive.primitive else {
+            return;
+        };
+        let clip = Rect::from_min_max(
+            (primitive.clip_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+            (primitive.clip_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+        )
+        .intersect(Rect::from_min_size(
+            Pos2::ZERO,
+            egui::vec2(self.size[0] as f32, self.size[1] as f32),
+        ));
+        if clip.is_negative() {
+            return;
+        }
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertex = |i: usize| scaled(&mesh.vertices[triangle[i] as usize], pixels_per_point);
+            self.triangle([vertex(0), vertex(1), vertex(2)], mesh, textures, clip);
+        }
+    }
+
+    /// Rasterize one triangle, sampling pixel centers inside `clip`.
+    fn triangle(&mut self, [a, b, c]: [Vertex; 3], mesh: &Mesh, textures: &Textures, clip: Rect) {
+        let area = edge(a.pos, b.pos, c.pos);
+        if area.abs() <= f32::EPSILON {
+            return;
+        }
+        let bounds = Rect::from_points(&[a.pos, b.pos, c.pos]).intersect(clip);
+        if bounds.is_negative() {
+            return;
+        }
+        let colors = [a, b, c].map(|v| normalized(v.color));
+        for y in bounds.min.y.floor() as usize..bounds.max.y.ceil() as usize {
+            for x in bounds.min.x.floor() as usize..bounds.max.x.ceil() as usize {
+                let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let weights = [
+                    edge(b.pos, c.pos, p) / area,
+                    edge(c.pos, a.pos, p) / area,
+                    edge(a.pos, b.pos, p) / area,
+                ];
+                if weights.iter().any(|w| *w < 0.0) {
+                    continue;
+                }
+                let uv = a.uv.to_vec2() * weights[0]
+                    + b.uv.to_vec2() * weights[1]
+                    + c.uv.to_vec2() * weights[2];
+                let texel = textures.sample(mesh.texture_id, uv.to_pos2());
+                let src: [f32; 4] = array::from_fn(|i| {
+                    let color = colors[0][i] * weights[0]
+                        + colors[1][i] * weights[1]
+                        + colors[2][i] * weights[2];
+                    color * texel[i]
+                });
+                let dst = &mut self.pixels[y * self.size[0] + x];
+                // Premultiplied "over" blending in gamma space, like egui's GPU backends.
+                *dst = array::from_fn(|i| src[i] + dst[i] * (1.0 - src[3]));
+            }
+        }
+    }
+
+    /// Convert the framebuffer to an egui image.
+    fn into_image(self) -> ColorImage {
+        let to_byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let pixels = self
+            .pixels
+            .into_iter()
+            .map(|[r, g, b, a]| {
+                Color32::from_rgba_premultiplied(to_byte(r), to_byte(g), to_byte(b), to_byte(a))
+            })
+            .collect();
+        ColorImage::new(self.size, pixels)
+    }
+}
+
+/// `vertex` with its position converted from points to pixels.
+fn scaled(vertex: &Vertex, pixels_per_point: f32) -> Vertex {
+    Vertex {
+        pos: (vertex.pos.to_vec2() * pixels_per_point).to_pos2(),
+        ..*vertex
+    }
+}
+
+/// Twice the signed area of triangle `a b p`.
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Premultiplied RGBA components of `color` in `0..=1`.
+fn normalized(color: Color32) -> [f32; 4] {
+    color.to_array().map(|c| f32::from(c) / 255.0)
+}
@@ -0,0 +1,218 @@
+//! Hex dump docked beside the file pane's byte image.
+//!
+//! Rows show sixteen bytes as an offset, hex pairs and their ASCII rendering. The view reports
+//! the byte under the pointer and the rows in view so the file pane can highlight their cells
+//! in the image, and takes a byte to scroll to and a range to highlight in return.
+
+use std::ops::Range;
+
+use egui::{Align2, Color32, FontFamily, FontId, Pos2, Rect, Sense, vec2};
+
+use crate::theme;
+
+/// Bytes per row.
+const ROW_BYTES: usize = 16;
+
+/// Width of the offset column, in characters.
+const OFFSET_CHARS: usize = 8;
+
+/// Scroll and drag state of the hex view.
+#[derive(Debug, Default)]
+pub struct HexView {
+    /// Byte to bring into view on the next frame.
+    pub scroll_to: Option<usize>,
+    /// Byte where the current drag selection started.
+    anchor: Option<usize>,
+}
+
+/// What the hex view showed and what the pointer did in it this frame.
+#[derive(Debug, Clone)]
+pub struct HexResponse {
+    /// Byte under the pointer.
+    pub hovered: Option<usize>,
+    /// Bytes of the rows in view.
+    pub visible: Range<usize>,
+}
+
+/// Character column of the hex pair for byte `i` of a row; the two halves of a row are split
+/// by an extra space.
+fn hex_column(i: usize) -> usize {
+    OFFSET_CHARS + 2 + 3 * i + i / (ROW_BYTES / 2)
+}
+
+/// Character column of the ASCII rendering of byte `i` of a row.
+fn ascii_column(i: usize) -> usize {
+    hex_column(ROW_BYTES) + 1 + i
+}
+
+/// Byte of a row drawn at character column `column`, in either the hex or the ASCII area.
+fn byte_at_column(column: f32) -> Option<usize> {
+    (0..ROW_BYTES).find(|&i| {
+        let hex = hex_column(i) as f32;
+        let ascii = ascii_column(i) as f32;
+        (hex..hex + 2.0).contains(&column) || (ascii..ascii + 1.0).contains(&column)
+    })
+}
+
+/// How byte `byte` reads in the ASCII column.
+fn ascii(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        char::from(byte)
+    } else {
+        '.'
+    }
+}
+
+/// Monospace character grid the dump is laid out on.
+struct Grid {
+    /// Font of every character.
+    font: FontId,
+    /// Advance of one character.
+    char_width: f32,
+    /// Height of one row of text.
+    row_height: f32,
+    /// Distance between the tops of consecutive rows.
+    row_stride: f32,
+}
+
+impl Grid {
+    /// The grid for the current style of `ui`.
+    fn new(ui: &egui::Ui) -> Self {
+        let font = FontId::new(theme::hex_view::FONT_SIZE, FontFamily::Monospace);
+        let char_width = ui.fonts_mut(|fonts| fonts.glyph_width(&font, '0'));
+        let row_height = ui.fonts_mut(|fonts| fonts.row_height(&font));
+        Self {
+            font,
+            char_width,
+            row_height,
+            row_stride: row_height + ui.spacing().item_spacing.y,
+        }
+    }
+
+    /// Top-left corner of character `column` on the row starting at `row_origin`.
+    fn at(&self, row_origin: Pos2, column: usize) -> Pos2 {
+        row_origin + vec2(column as f32 * self.char_width, 0.0)
+    }
+}
+
+/// Paint row `row` of `bytes` at `origin`, with `background` giving the fill behind each byte.
+fn paint_row(
+    painter: &egui::Painter,
+    grid: &Grid,
+    origin: Pos2,
+    (row, bytes): (usize, &[u8]),
+    background: impl Fn(usize) -> Option<Color32>,
+) {
+    let palette = theme::palette(painter.ctx());
+    let text = |column: usize, text: String, color: Color32| {
+        let pos = grid.at(origin, column);
+        painter.text(pos, Align2::LEFT_TOP, text, grid.font.clone(), color);
+    };
+    text(
+        0,
+        format!("{:0width$x}", row * ROW_BYTES, width = OFFSET_CHARS),
+        palette.text_dim,
+    );
+    let start = row * ROW_BYTES;
+    for (i, &byte) in bytes[start..(start + ROW_BYTES).min(bytes.len())]
+        .iter()
+        .enumerate()
+    {
+        if let Some(color) = background(start + i) {
+            for (column, chars) in [(hex_column(i), 2.0), (ascii_column(i), 1.0)] {
+                let size = vec2(chars * grid.char_width, grid.row_height);
+                painter.rect_filled(
+                    Rect::from_min_size(grid.at(origin, column), size),
+                    0.0,
+                    color,
+                );
+            }
+        }
+        let color = if byte == 0 {
+            palette.text_dim
+        } else {
+            palette.text_body
+        };
+        text(hex_column(i), format!("{byte:02x}"), color);
+        text(ascii_column(i), ascii(byte).to_string(), color);
+    }
+}
+
+/// Show `bytes` as a scrolling hex dump.
+///
+/// Bytes in `selection` and `highlight` get a background; clicking or dragging over bytes
+/// replaces `selection`.
+pub fn show(
+    ui: &mut egui::Ui,
+    bytes: &[u8],
+    state: &mut HexView,
+    selection: &mut Option<Range<usize>>,
+    highlight: Option<&Range<usize>>,
+) -> HexResponse {
+    let palette = theme::palette(ui.ctx());
+    let grid = Grid::new(ui);
+    let rows = bytes.len().div_ceil(ROW_BYTES);
+
+    let mut scroll = egui::ScrollArea::vertical().auto_shrink(false);
+    if let Some(offset) = state.scroll_to.take() {
+        let row = (offset / ROW_BYTES) as f32;
+        let centered = row * grid.row_stride - ui.available_height() / 2.0;
+        scroll = scroll.vertical_scroll_offset(centered.max(0.0));
+    }
+
+    let mut hovered = None;
+    let output = scroll.show_rows(ui, grid.row_height, rows, |ui, row_range| {
+        let top_left = ui.max_rect().left_top();
+        let area = Rect::from_min_size(
+            top_left,
+            vec2(
+                ascii_column(ROW_BYTES) as f32 * grid.char_width,
+                row_range.len() as f32 * grid.row_stride,
+            ),
+        );
+        let response = ui.allocate_rect(area, Sense::click_and_drag());
+        let byte_at = |pos: Pos2| {
+            let line = ((pos.y - top_left.y) / grid.row_stride).floor().max(0.0) as usize;
+            let column = byte_at_column((pos.x - top_left.x) / grid.char_width)?;
+            let offset = (row_range.start + line) * ROW_BYTES + column;
+            (offset < bytes.len()).then_some(offset)
+        };
+        hovered = response.hover_pos().and_then(byte_at);
+        if let Some(offset) = response.interact_pointer_pos().and_then(byte_at) {
+            if response.drag_started() || response.clicked() || state.anchor.is_none() {
+                state.anchor = Some(offset);
+            }
+            let anchor = state.anchor.unwrap_or(offset);
+            *selection = Some(anchor.min(offset)..anchor.max(offset) + 1);
+        }
+        if !response.is_pointer_button_down_on() {
+            state.anchor = None;
+        }
+
+        let highlighted = palette
+            .accent
+            .gamma_multiply(theme::hex_view::HIGHLIGHT_OPACITY);
+        let background = |offset: usize| {
+            if highlight.is_some_and(|r| r.contains(&offset)) || hovered == Some(offset) {
+                Some(highlighted)
+            } else {
+                selection
+                    .as_ref()
+                    .is_some_and(|r| r.contains(&offset))
+                    .then_some(palette.selection)
+            }
+        };
+        for (line, row) in row_range.clone().enumerate() {
+            let origin = top_left + vec2(0.0, line as f32 * grid.row_stride);
+            paint_row(ui.painter(), &grid, origin, (row, bytes), background);
+        }
+    });
+
+    let first_row = (output.state.offset.y / grid.row_stride).floor() as usize;
+    let last_row =
+        ((output.state.offset.y + output.inner_rect.height()) / grid.row_stride).ceil() as usize;
+    HexResponse {
+        hovered,
+        visible: (first_row * ROW_BYTES).min(bytes.len())..(last_row * ROW_BYTES).min(bytes.len()),
+    }
+}
@@ -1,6 +1,10 @@
 //! GUI application for exploring space‑filling curves using egui/eframe.
 
-use std::{fs::File, io::BufWriter, path::PathBuf, sync::Arc};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use anyhow::Result;
 use spacecurve::registry;
@@ -19,6 +23,8 @@ pub enum Pane {
     TwoD,
     /// The 3D curve visualization pane.
     ThreeD,
+    /// A file's bytes laid out along a 2D curve.
+    File,
 }
 
 /// Screenshot target specifying which UI state to capture.
@@ -45,6 +51,7 @@ pub struct ScreenshotConfig {
     pub output_path: PathBuf,
 }
 
+/// Screenshot request currently being processed.
 #[derive(Debug)]
 struct ActiveScreenshot {
     /// Destination path for the PNG output.
@@ -53,6 +60,30 @@ struct ActiveScreenshot {
     requested: bool,
 }
 
+/// Region of the viewport saved by a PNG export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PngCapture {
+    /// Only the active pane's canvas.
+    Canvas,
+    /// The whole window, including panels and controls.
+    Window,
+}
+
+/// Progress of a PNG export captured from the viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum PngExport {
+    /// No export in progress.
+    #[default]
+    Idle,
+    /// Waiting one frame so menus close before the capture.
+    Scheduled(PngCapture),
+    /// Screenshot requested; waiting for the platform to deliver it.
+    Capturing(PngCapture),
+}
+
+/// App storage key for the reduced-motion preference.
+const REDUCED_MOTION_STORAGE_KEY: &str = "reduced_motion";
+
 /// Launch configuration for the GUI.
 #[derive(Debug, Clone, Default)]
 pub struct GuiOptions {
@@ -62,55 +93,168 @@ pub struct GuiOptions {
     pub screenshot: Option<ScreenshotConfig>,
     /// Enable developer overlay (frame timing, etc.).
     pub show_dev_overlay: bool,
+    /// State from a shared link (a URL hash) applied at startup.
+    pub initial_link: Option<String>,
+    /// Start in reduced-motion mode unless the user saved a preference, e.g. when the page
+    /// reports `prefers-reduced-motion`.
+    pub reduced_motion: bool,
+    /// Renderer chosen by the web build, shown with a picker to change it.
+    pub renderer: Option<RendererStatus>,
 }
 
 /// About dialog contents and helpers.
 pub mod about;
+/// Labelled curve indices and ranges kept across sessions.
+pub mod bookmarks;
+/// Index-gradient colormaps for curve rendering.
+pub mod colormap;
+/// Keyboard shortcuts and the command palette.
+pub mod commands;
+/// Embeddable 2D and 3D curve widgets.
+pub mod curve_widget;
+/// Developer debug panel with a state inspector and event log.
+pub mod debug_panel;
+/// Export of the current view to PNG/SVG files.
+pub mod export;
+/// File visualization pane.
+pub mod file_vis;
+/// Cached tessellation of static curve geometry.
+pub mod geometry;
+/// Curve point generation in a compute shader.
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+pub mod gpu;
+/// Offscreen rendering of GUI scenes without a window.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod headless;
+/// Hex dump docked beside the file pane.
+pub mod hex_view;
+/// Registry metadata side panel.
+pub mod metadata;
+/// Locality measures of every curve, tabulated side by side.
+pub mod metrics_table;
+/// Shareable GUI state encoded in the URL hash.
+pub mod permalink;
+/// CSV point clouds drawn over the 2D pane in curve order.
+pub mod point_cloud;
+/// Recording of the active pane to GIFs or frame sequences.
+pub mod record;
+/// Renderer backend detection and choice for the web build.
+pub mod renderer;
 /// Shared selection/cache helpers for 2D and 3D panes.
 pub mod selection;
 /// Shared helpers for snake overlays.
 pub mod snake;
 /// State management logic.
 pub mod state;
-/// Centralized theme constants (colors, fonts, spacing).
+/// Anti-aliased polyline strokes for the 2D pane.
+pub mod stroke;
+/// Centralized theme constants and palettes (colors, fonts, spacing).
 pub mod theme;
 /// 3D view and interactions.
 pub mod threed;
+/// Animation playback state behind the timeline.
+pub mod transport;
+/// Shaded tube geometry for the 3D pane.
+pub mod tube;
 /// 2D view and interactions.
 pub mod twod;
 /// Reusable GUI widgets.
 pub mod widgets;
+/// Curve point generation off the UI thread.
+pub mod worker;
 
+use bookmarks::Bookmarks;
+use colormap::{ColorBy, Colormap};
+use commands::Command;
+pub use curve_widget::{CurveWidget2D, CurveWidget3D};
+use debug_panel::{DebugView, EventLog, Watched};
+use export::ExportFormat;
+use file_vis::{FileView, show_file_pane};
+use metadata::MetadataTarget;
+use metrics_table::MetricsTable;
+use permalink::LinkTarget;
+use point_cloud::PointImport;
+use record::{RecordFormat, Recorder};
+use renderer::{Backend, RendererStatus};
 pub use selection::{Selected3DCurve, SelectedCurve};
-use state::AnimationController;
+use snake::{SnakeBuffers, SnakeStyle};
+use state::{AnimationController, Camera, ClipPlane, DragRotation, Guides};
+use stroke::LineStyle;
+use theme::ThemeSettings;
 use threed::show_3d_pane;
+use transport::Transport;
+use tube::TubeStyle;
 use twod::show_2d_pane;
+use widgets::Toast;
 
 /// Settings shared between the 2D and 3D views.
+#[derive(Clone)]
 pub struct SharedSettings {
     /// Opacity of the main curve rendering (0.0–1.0).
     pub curve_opacity: f32,
+    /// Width and joins of the 2D curve strokes.
+    pub line_style: LineStyle,
+    /// Tube rendering of the 3D curve.
+    pub tube: TubeStyle,
     /// Whether to draw long-jump segments in the curve.
     pub show_long_jumps: bool,
+    /// Draw long jumps as dashed lines in a distinct color.
+    pub highlight_jumps: bool,
+    /// Recursion level of the quadrant/octant boundary overlay (0 hides it).
+    pub block_level: u32,
     /// Enable the animated snake overlay.
     pub snake_enabled: bool,
     /// Snake length as a percentage of curve length (0–50).
     pub snake_length: f32, // Percentage of curve length (0-50%)
     /// Snake speed, measured in segments per second.
     pub snake_speed: f32,
+    /// Draw a second snake trailing the first to race their coverage.
+    pub race_enabled: bool,
+    /// Distance of the second snake ahead of the first, as a percentage of curve length.
+    pub race_gap: f32,
+    /// Snake head marker, gradient, width and trail fade.
+    pub snake_style: SnakeStyle,
     /// Rotation speed of the 3D view (0–100 scale).
     pub spin_speed: f32,
+    /// Start paused, never auto-rotate and skip UI transitions.
+    pub reduced_motion: bool,
+    /// Colormap used to color the curve by index.
+    pub colormap: Colormap,
+    /// First and last colors of the custom colormap.
+    pub colormap_endpoints: [egui::Color32; 2],
+    /// Whether points take colormap colors by index, shell or block.
+    pub color_by: ColorBy,
+    /// Recursion level of the blocks used when coloring by block.
+    pub color_level: u32,
+    /// Palette and per-element color overrides.
+    pub theme: ThemeSettings,
+    /// Bookmarked indices and ranges.
+    pub bookmarks: Bookmarks,
 }
 
 impl Default for SharedSettings {
     fn default() -> Self {
         Self {
             curve_opacity: 0.35, // Default to 35% opacity
+            line_style: LineStyle::default(),
+            tube: TubeStyle::default(),
             show_long_jumps: false,
+            highlight_jumps: false,
+            block_level: 0,
             snake_enabled: true,
             snake_length: 5.0, // Default to 5% of curve length
             snake_speed: 30.0, // Default snake speed (segments per second)
-            spin_speed: 50.0,  // Default rotation speed (0-100 scale)
+            race_enabled: false,
+            race_gap: 50.0,
+            snake_style: SnakeStyle::default(),
+            spin_speed: 50.0, // Default rotation speed (0-100 scale)
+            reduced_motion: false,
+            colormap: Colormap::Solid,
+            colormap_endpoints: colormap::DEFAULT_ENDPOINTS,
+            color_by: ColorBy::Index,
+            color_level: 1,
+            theme: ThemeSettings::default(),
+            bookmarks: Bookmarks::default(),
         }
     }
 }
@@ -119,34 +263,36 @@ impl Default for SharedSettings {
 pub struct AppState {
     /// Currently selected pane.
     pub current_pane: Pane,
-    /// Accumulated animation time in seconds.
-    pub animation_time: f32,
-    /// Global pause state for animations.
-    pub paused: bool,
-    /// Current rotation angle for the 3D view (radians).
-    pub rotation_angle: f32,
+    /// Play state, loop region and slice stepping shared by all panes.
+    pub transport: Transport,
+    /// Camera orientation and projection for the 3D view.
+    pub camera: Camera,
+    /// Clipping plane hiding part of the 3D view.
+    pub clip: ClipPlane,
+    /// Axes, bounding box and grid drawn around the 3D view.
+    pub guides: Guides,
+    /// Extra spacing between onion shells in the 3D view, in grid cells (0 keeps them nested).
+    pub explode: f32,
     /// Whether the user is currently dragging in the 3D view.
     pub mouse_dragging: bool,
-    /// Last X coordinate recorded during a drag gesture.
-    pub last_mouse_x: f32,
-    /// Accumulated time used to advance the snake animation.
-    pub snake_time: f32,
-    /// Reusable buffer for 2D snake segment indices.
-    pub snake_segments_2d: Vec<usize>,
-    /// Reusable buffer for 3D snake segment indices.
-    pub snake_segments_3d: Vec<usize>,
-    /// Reusable membership mask for 2D snake lookups.
-    pub snake_mask_2d: Vec<bool>,
-    /// Reusable membership mask for 3D snake lookups.
-    pub snake_mask_3d: Vec<bool>,
-    /// Reusable inclusion mask for visible 3D snake segments.
-    pub snake_included_3d: Vec<bool>,
+    /// Pointer position and camera yaw and tilt when the current drag started.
+    pub drag_start: Option<(egui::Pos2, f32, f32)>,
+    /// Axis lock and snapping applied when dragging the 3D view.
+    pub drag_rotation: DragRotation,
+    /// Reusable snake buffers for the 2D pane.
+    pub snake_2d: SnakeBuffers,
+    /// Reusable snake buffers for the 3D pane.
+    pub snake_3d: SnakeBuffers,
     /// Whether the settings dropdown is currently open.
     pub settings_dropdown_open: bool,
     /// Persisted position for the settings dropdown to avoid frame-to-frame jitter.
     pub settings_dropdown_pos: Option<egui::Pos2>,
     /// Whether the About dialog is currently open.
     pub about_open: bool,
+    /// Whether the curve metadata side panel is shown.
+    pub metadata_open: bool,
+    /// Window comparing the locality of every curve.
+    pub metrics: MetricsTable,
     /// Smoothed frame time in milliseconds (for dev overlay).
     pub frame_time_ms: Option<f32>,
     /// Latched frame time used for the UI (updates slowly for readability).
@@ -155,31 +301,101 @@ pub struct AppState {
     pub frame_time_last_display_s: Option<f64>,
     /// Latest canvas rect for positioning overlays relative to the view.
     pub last_canvas_rect: Option<egui::Rect>,
+    /// Export requested from the UI, handled at the end of the frame.
+    pub export_request: Option<ExportFormat>,
+    /// Whether panes show a second curve side by side for comparison.
+    pub compare_enabled: bool,
+    /// Curve point under the pointer in the current frame, if any.
+    pub hovered_point: Option<HoverPoint>,
+    /// Point cloud imported from CSV and drawn over the 2D pane.
+    pub points: PointImport,
+    /// Recording requested from the UI, started at the end of the frame.
+    pub record_request: Option<RecordFormat>,
+    /// Length of new recordings in seconds.
+    pub record_seconds: f32,
+    /// Whether a recording is in progress.
+    pub recording: bool,
+    /// Whether the command palette is open.
+    pub palette_open: bool,
+    /// Filter text typed into the command palette.
+    pub palette_query: String,
+    /// Confirmation message currently on screen.
+    pub toast: Option<Toast>,
+}
+
+/// A curve point picked from screen space by the hover inspector.
+#[derive(Clone, Debug)]
+pub struct HoverPoint {
+    /// Index of the point along the curve.
+    pub index: u32,
+    /// Position of the point within the displayed points, in snake offset units.
+    pub position: usize,
+    /// Coordinates of the point, including the slice axis for 4D views.
+    pub coords: Vec<u32>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             current_pane: Pane::TwoD,
-            animation_time: 0.0,
-            paused: false,
-            rotation_angle: 0.0,
+            transport: Transport::default(),
+            camera: Camera::default(),
+            clip: ClipPlane::default(),
+            guides: Guides::default(),
+            explode: 0.0,
             mouse_dragging: false,
-            last_mouse_x: 0.0,
-            snake_time: 0.0,
-            snake_segments_2d: Vec::new(),
-            snake_segments_3d: Vec::new(),
-            snake_mask_2d: Vec::new(),
-            snake_mask_3d: Vec::new(),
-            snake_included_3d: Vec::new(),
+            drag_start: None,
+            drag_rotation: DragRotation::default(),
+            snake_2d: SnakeBuffers::default(),
+            snake_3d: SnakeBuffers::default(),
             settings_dropdown_open: false,
             settings_dropdown_pos: None,
             about_open: false,
+            metadata_open: false,
+            metrics: MetricsTable::default(),
             frame_time_ms: None,
             frame_time_display_ms: None,
             frame_time_last_display_s: None,
             last_canvas_rect: None,
+            export_request: None,
+            compare_enabled: false,
+            hovered_point: None,
+            points: PointImport::default(),
+            record_request: None,
+            record_seconds: theme::recording::DEFAULT_SECONDS,
+            recording: false,
+            palette_open: false,
+            palette_query: String::new(),
+            toast: None,
+        }
+    }
+}
+
+impl AppState {
+    /// Open the pane and popups that a capture of `target` shows, paused for a stable frame.
+    fn show_screenshot_target(&mut self, target: ScreenshotTarget) {
+        match target {
+            ScreenshotTarget::TwoD => {
+                self.current_pane = Pane::TwoD;
+            }
+            ScreenshotTarget::ThreeD => {
+                self.current_pane = Pane::ThreeD;
+            }
+            ScreenshotTarget::About => {
+                self.current_pane = Pane::TwoD;
+                self.about_open = true;
+            }
+            ScreenshotTarget::Settings => {
+                self.current_pane = Pane::TwoD;
+                self.settings_dropdown_open = true;
+            }
+            ScreenshotTarget::Settings3D => {
+                self.current_pane = Pane::ThreeD;
+                self.settings_dropdown_open = true;
+            }
         }
+        // Pause animations for consistent screenshots
+        self.transport.paused = true;
     }
 }
 
@@ -189,6 +405,12 @@ pub struct ScurveApp {
     selected_curve: SelectedCurve,
     /// 3D selection and cache state.
     selected_3d_curve: Selected3DCurve,
+    /// Second 2D curve shown in comparison mode.
+    compare_curve: SelectedCurve,
+    /// Second 3D curve shown in comparison mode.
+    compare_3d_curve: Selected3DCurve,
+    /// File pane state, including the open file.
+    file_view: FileView,
     /// Curves available for selection in this run.
     available_curves: Vec<&'static str>,
     /// Mutable app state shared across panes.
@@ -203,6 +425,19 @@ pub struct ScurveApp {
     commonmark_cache: egui_commonmark::CommonMarkCache,
     /// Whether to show developer diagnostics overlay.
     show_dev_overlay: bool,
+    /// Recent commands and state changes for the debug panel.
+    events: EventLog,
+    /// Progress of the current PNG export, if any.
+    png_export: PngExport,
+    /// In-progress recording of the active pane, if any.
+    recorder: Option<Recorder>,
+    /// Last link state written to the URL hash.
+    #[cfg(target_arch = "wasm32")]
+    last_link: String,
+    /// Renderer chosen by the web build, if any.
+    renderer: Option<RendererStatus>,
+    /// Whether the renderer bar is shown.
+    renderer_bar_open: bool,
 }
 
 impl ScurveApp {
@@ -227,9 +462,6 @@ impl ScurveApp {
 
     /// Construct a new app instance with explicit launch options.
     pub fn with_options(cc: &eframe::CreationContext<'_>, options: GuiOptions) -> Self {
-        // Configure visuals with our custom terminal theme
-        theme::configure_visuals(&cc.egui_ctx);
-
         let include_experimental = options.include_experimental_curves;
         let mut available_curves = registry::curve_names(include_experimental);
         if available_curves.is_empty() {
@@ -241,6 +473,7 @@ impl ScurveApp {
             .first()
             .copied()
             .unwrap_or(registry::CURVE_NAMES[0]);
+        let default_compare_curve = available_curves.get(1).copied().unwrap_or(default_curve);
 
         let mut app_state = AppState::default();
         let screenshot_config = options.screenshot;
@@ -251,43 +484,119 @@ impl ScurveApp {
 
         // Configure initial state based on screenshot target
         if let Some(config) = screenshot_config {
-            match config.target {
-                ScreenshotTarget::TwoD => {
-                    app_state.current_pane = Pane::TwoD;
-                }
-                ScreenshotTarget::ThreeD => {
-                    app_state.current_pane = Pane::ThreeD;
-                }
-                ScreenshotTarget::About => {
-                    app_state.current_pane = Pane::TwoD;
-                    app_state.about_open = true;
-                }
-                ScreenshotTarget::Settings => {
-                    app_state.current_pane = Pane::TwoD;
-                    app_state.settings_dropdown_open = true;
-                }
-                ScreenshotTarget::Settings3D => {
-                    app_state.current_pane = Pane::ThreeD;
-                    app_state.settings_dropdown_open = true;
-                }
+            app_state.show_screenshot_target(config.target);
+        }
+        // Screenshots always use the default camera and theme.
+        let mut shared_settings = SharedSettings {
+            reduced_motion: options.reduced_motion,
+            ..SharedSettings::default()
+        };
+        if screenshot_runtime.is_none()
+            && let Some(storage) = cc.storage
+        {
+            if let Some(camera) = storage
+                .get_string(Camera::STORAGE_KEY)
+                .and_then(|value| Camera::from_storage(&value))
+            {
+                app_state.camera = camera;
+            }
+            if let Some(theme) = storage
+                .get_string(ThemeSettings::STORAGE_KEY)
+                .and_then(|value| ThemeSettings::from_storage(&value))
+            {
+                shared_settings.theme = theme;
+            }
+            if let Some(bookmarks) = storage
+                .get_string(Bookmarks::STORAGE_KEY)
+                .and_then(|value| Bookmarks::from_storage(&value))
+            {
+                shared_settings.bookmarks = bookmarks;
             }
-            // Pause animations for consistent screenshots
-            app_state.paused = true;
+            if let Some(reduced) = storage.get_string(REDUCED_MOTION_STORAGE_KEY) {
+                shared_settings.reduced_motion = reduced == "true";
+            }
+        }
+        if shared_settings.reduced_motion {
+            app_state.transport.paused = true;
         }
 
-        Self {
+        let mut app = Self {
             selected_curve: SelectedCurve::with_name(default_curve),
             selected_3d_curve: Selected3DCurve::with_name(default_curve),
+            compare_curve: SelectedCurve::with_name(default_compare_curve),
+            compare_3d_curve: Selected3DCurve::with_name(default_compare_curve),
+            file_view: FileView::with_curve(default_curve),
             available_curves,
             app_state,
-            shared_settings: Default::default(),
+            shared_settings,
             screenshot: screenshot_runtime.take(),
             last_time: None,
             commonmark_cache: Default::default(),
             show_dev_overlay: options.show_dev_overlay,
+            events: EventLog::default(),
+            png_export: PngExport::Idle,
+            recorder: None,
+            #[cfg(target_arch = "wasm32")]
+            last_link: String::new(),
+            renderer: options.renderer,
+            renderer_bar_open: options
+                .renderer
+                .is_some_and(|status| status.notice().is_some()),
+        };
+        if app.screenshot.is_none()
+            && let Some(link) = options.initial_link
+        {
+            let available_curves = app.available_curves.clone();
+            app.link_target().apply(&link, &available_curves);
+        }
+        theme::configure_visuals(&cc.egui_ctx, &app.shared_settings.theme.palette());
+        app
+    }
+
+    /// Borrow the parts of the app captured by shared links.
+    fn link_target(&mut self) -> LinkTarget<'_> {
+        LinkTarget {
+            app_state: &mut self.app_state,
+            curve_2d: &mut self.selected_curve,
+            curve_3d: &mut self.selected_3d_curve,
+            settings: &mut self.shared_settings,
         }
     }
 
+    /// Mirror the current state into the page URL hash whenever it changes.
+    #[cfg(target_arch = "wasm32")]
+    fn sync_link(&mut self) {
+        let link = self.link_target().encode();
+        if link == self.last_link {
+            return;
+        }
+        // Replace rather than push so every tweak does not add a history entry.
+        let replaced = web_sys::window()
+            .and_then(|w| w.history().ok())
+            .map(|history| {
+                history.replace_state_with_url(
+                    &wasm_bindgen::JsValue::NULL,
+                    "",
+                    Some(&format!("#{link}")),
+                )
+            });
+        if let Some(Err(err)) = replaced {
+            eprintln!("Failed to update URL hash: {err:?}");
+        }
+        self.last_link = link;
+    }
+
+    /// Copy a link to the page with the full current state and confirm with a toast.
+    #[cfg(target_arch = "wasm32")]
+    fn copy_share_link(&mut self, ctx: &egui::Context) {
+        let href = web_sys::window()
+            .and_then(|w| w.location().href().ok())
+            .unwrap_or_default();
+        let page = href.split('#').next().unwrap_or_default();
+        ctx.copy_text(format!("{page}#{}", self.link_target().share_link()));
+        self.app_state.toast = Some(Toast::new(ctx, "Share link copied"));
+    }
+
     /// Render the top menu bar with title, tabs, and About button.
     fn show_menu_bar(&mut self, ctx: &egui::Context) {
         egui::TopBottomPanel::top("menu_bar")
@@ -305,7 +614,7 @@ impl ScurveApp {
                             egui::RichText::new(APP_NAME)
                                 .size(theme::font_size::TITLE)
                                 .strong()
-                                .color(theme::TEXT_HEADING),
+                                .color(theme::palette(ui.ctx()).text_heading),
                         )
                         .clicked()
                         && let Err(e) = webbrowser::open(APP_REPO_URL)
@@ -336,6 +645,16 @@ impl ScurveApp {
                     {
                         self.app_state.current_pane = Pane::ThreeD;
                     }
+                    ui.add_space(theme::menu_bar::TAB_SPACING);
+                    if ui
+                        .selectable_label(
+                            self.app_state.current_pane == Pane::File,
+                            egui::RichText::new("File").size(tab_text_size),
+                        )
+                        .clicked()
+                    {
+                        self.app_state.current_pane = Pane::File;
+                    }
 
                     // Right-aligned About button with padding
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -343,11 +662,80 @@ impl ScurveApp {
                         if ui.button("About").clicked() {
                             self.app_state.about_open = !self.app_state.about_open;
                         }
+                        ui.toggle_value(&mut self.app_state.metadata_open, "Details");
+                        ui.toggle_value(&mut self.app_state.metrics.open, "Metrics");
+                        if let Some(active) = self.renderer.map(|status| status.active) {
+                            let label = active.map_or("No WebGL", Backend::label);
+                            ui.toggle_value(&mut self.renderer_bar_open, label)
+                                .on_hover_text("Renderer");
+                        }
+                        #[cfg(target_arch = "wasm32")]
+                        if widgets::icon_label(ui.button("🔗"), "Copy share link").clicked() {
+                            self.copy_share_link(ctx);
+                        }
+                        if widgets::icon_label(ui.button("📷"), "Save a screenshot of the window")
+                            .clicked()
+                        {
+                            self.schedule_window_screenshot();
+                        }
                     });
                 });
             });
     }
 
+    /// Capture the whole window to a PNG once open menus have closed.
+    fn schedule_window_screenshot(&mut self) {
+        if self.png_export == PngExport::Idle {
+            self.png_export = PngExport::Scheduled(PngCapture::Window);
+        }
+    }
+
+    /// Show the registry metadata panel for the active pane's curve.
+    fn show_metadata_panel(&mut self, ctx: &egui::Context) {
+        let target = match self.app_state.current_pane {
+            Pane::TwoD => MetadataTarget {
+                continuous: self.selected_curve.is_continuous(snake::is_adjacent_2d),
+                name: &self.selected_curve.name,
+                dimension: 2,
+                size: self.selected_curve.size,
+            },
+            Pane::ThreeD => MetadataTarget {
+                continuous: self.selected_3d_curve.is_continuous(snake::is_adjacent_3d),
+                name: &self.selected_3d_curve.name,
+                dimension: self.selected_3d_curve.curve_dimensions(),
+                size: self.selected_3d_curve.size,
+            },
+            Pane::File => MetadataTarget {
+                continuous: self.file_view.curve.is_continuous(snake::is_adjacent_2d),
+                name: &self.file_view.curve.name,
+                dimension: 2,
+                size: self.file_view.curve.size,
+            },
+        };
+        metadata::show_metadata_panel(ctx, &mut self.app_state.metadata_open, &target);
+    }
+
+    /// Show the metrics window for the active pane's grid.
+    fn show_metrics_table(&mut self, ctx: &egui::Context) {
+        let dimension_3d = self.selected_3d_curve.curve_dimensions();
+        let (name, dimension, size) = match self.app_state.current_pane {
+            Pane::TwoD => (&mut self.selected_curve.name, 2, self.selected_curve.size),
+            Pane::ThreeD => (
+                &mut self.selected_3d_curve.name,
+                dimension_3d,
+                self.selected_3d_curve.size,
+            ),
+            Pane::File => (&mut self.file_view.curve.name, 2, self.file_view.curve.size),
+        };
+        metrics_table::show(
+            ctx,
+            &mut self.app_state.metrics,
+            &self.available_curves,
+            (dimension, size),
+            name,
+        );
+    }
+
     /// Handle multi-frame screenshot capture and saving to disk.
     fn handle_screenshot(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let Some(screenshot) = self.screenshot.as_mut() else {
@@ -383,6 +771,236 @@ impl ScurveApp {
         }
     }
 
+    /// Drive exports requested from the UI through to a saved file.
+    fn handle_export(&mut self, ctx: &egui::Context) {
+        match self.app_state.export_request.take() {
+            Some(ExportFormat::Svg) => self.export_svg(),
+            Some(format @ (ExportFormat::Obj | ExportFormat::Gltf)) => self.export_mesh(format),
+            // GIFs are produced by recordings rather than single-frame exports.
+            Some(ExportFormat::Gif) | None => {}
+            Some(ExportFormat::Png) => {
+                self.png_export = PngExport::Scheduled(PngCapture::Canvas);
+                ctx.request_repaint();
+                return;
+            }
+        }
+
+        match self.png_export {
+            PngExport::Idle => {}
+            PngExport::Scheduled(capture) => {
+                self.png_export = PngExport::Capturing(capture);
+                ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot(Default::default()));
+                ctx.request_repaint();
+            }
+            PngExport::Capturing(capture) => {
+                let captured = ctx.input(|input| {
+                    input.events.iter().find_map(|event| match event {
+                        // Tagged screenshots belong to an in-progress recording.
+                        egui::Event::Screenshot {
+                            image, user_data, ..
+                        } if user_data.data.is_none() => Some(image.clone()),
+                        _ => None,
+                    })
+                });
+                let Some(image) = captured else {
+                    ctx.request_repaint();
+                    return;
+                };
+                self.png_export = PngExport::Idle;
+                self.export_png(ctx, &image, capture);
+            }
+        }
+    }
+
+    /// Run shortcuts pressed this frame and commands picked from the palette.
+    fn handle_commands(&mut self, ctx: &egui::Context) {
+        let mut command = commands::pressed_shortcut(ctx);
+        if self.app_state.palette_open {
+            command = command.or(commands::show_palette(
+                ctx,
+                &mut self.app_state.palette_open,
+                &mut self.app_state.palette_query,
+                &self.available_curves,
+            ));
+        }
+        if let Some(command) = command {
+            self.run_command(command);
+        }
+    }
+
+    /// Apply a single command to the app state.
+    fn run_command(&mut self, command: Command) {
+        if self.show_dev_overlay {
+            let time = self.last_time.unwrap_or_default();
+            self.events.push(time, format!("command: {command:?}"));
+        }
+        let state = &mut self.app_state;
+        match command {
+            Command::TogglePause => state.transport.toggle(),
+            Command::Step | Command::StepBack => {
+                let delta = if command == Command::Step { 1.0 } else { -1.0 };
+                let length_2d = self.selected_curve.ensure_curve_length();
+                let length_3d = self.selected_3d_curve.ensure_curve_length();
+                let transport = &state.transport;
+                let step = |offset: &mut f32, length| {
+                    *offset = transport.advance(offset.floor(), delta, length);
+                };
+                step(&mut self.selected_curve.snake_offset, length_2d);
+                step(&mut self.selected_3d_curve.snake_offset, length_3d);
+            }
+            Command::NextCurve => self.cycle_curve(1),
+            Command::PreviousCurve => self.cycle_curve(self.available_curves.len() - 1),
+            Command::ToggleDetails => state.metadata_open = !state.metadata_open,
+            Command::ToggleMetrics => state.metrics.open = !state.metrics.open,
+            Command::ToggleSnake => {
+                self.shared_settings.snake_enabled = !self.shared_settings.snake_enabled;
+            }
+            Command::SwitchPane => {
+                state.current_pane = match state.current_pane {
+                    Pane::TwoD => Pane::ThreeD,
+                    Pane::ThreeD => Pane::File,
+                    Pane::File => Pane::TwoD,
+                };
+            }
+            Command::Screenshot => state.export_request = Some(ExportFormat::Png),
+            Command::WindowScreenshot => self.schedule_window_screenshot(),
+            Command::OpenPalette => state.palette_open = true,
+            Command::OpenFile => self.file_view.open_request = true,
+            Command::ImportPoints => self.app_state.points.open_request = true,
+            Command::SelectCurve(name) => self.active_curve_name().replace_range(.., name),
+        }
+    }
+
+    /// Name of the curve shown in the active pane.
+    fn active_curve_name(&mut self) -> &mut String {
+        match self.app_state.current_pane {
+            Pane::TwoD => &mut self.selected_curve.name,
+            Pane::ThreeD => &mut self.selected_3d_curve.name,
+            Pane::File => &mut self.file_view.curve.name,
+        }
+    }
+
+    /// Move the active pane's curve `step` entries forward in the curve list, wrapping.
+    fn cycle_curve(&mut self, step: usize) {
+        let curves = self.available_curves.clone();
+        let name = self.active_curve_name();
+        let current = curves.iter().position(|c| c == name).unwrap_or(0);
+        name.replace_range(.., curves[(current + step) % curves.len()]);
+    }
+
+    /// Start requested recordings and drive the active one to completion.
+    fn handle_recording(&mut self, ctx: &egui::Context) {
+        if let Some(format) = self.app_state.record_request.take()
+            && self.recorder.is_none()
+        {
+            let now = ctx.input(|i| i.time);
+            self.recorder = Some(Recorder::new(format, self.app_state.record_seconds, now));
+        }
+
+        let Some(recorder) = self.recorder.as_mut() else {
+            return;
+        };
+        self.app_state.recording = true;
+        if !recorder.update(ctx, self.app_state.last_canvas_rect) {
+            return;
+        }
+        self.app_state.recording = false;
+        let stem = self.export_file_name(ExportFormat::Gif);
+        let stem = stem.trim_end_matches(".gif");
+        if let Some(recorder) = self.recorder.take()
+            && let Err(err) = recorder.finish(stem)
+        {
+            eprintln!("Failed to save recording: {err}");
+        }
+    }
+
+    /// File name suggested for exports of the active pane.
+    fn export_file_name(&self, format: ExportFormat) -> String {
+        let stem = match self.app_state.current_pane {
+            Pane::TwoD => format!("{}-{}", self.selected_curve.name, self.selected_curve.size),
+            Pane::ThreeD => format!(
+                "{}-{}",
+                self.selected_3d_curve.name, self.selected_3d_curve.size
+            ),
+            Pane::File => self.file_view.export_stem(),
+        };
+        format!("{stem}.{}", format.extension())
+    }
+
+    /// Save the captured frame as a PNG, cropped to the canvas unless the whole window was asked for.
+    fn export_png(&self, ctx: &egui::Context, image: &egui::ColorImage, capture: PngCapture) {
+        let mut file_name = self.export_file_name(ExportFormat::Png);
+        let region = match (capture, self.app_state.last_canvas_rect) {
+            (PngCapture::Canvas, Some(rect)) => image.region(&rect, Some(ctx.pixels_per_point())),
+            (PngCapture::Window, _) => {
+                file_name = file_name.replace(".png", "-window.png");
+                image.clone()
+            }
+            (PngCapture::Canvas, None) => image.clone(),
+        };
+        let result = encode_png(&region)
+            .and_then(|bytes| export::deliver(&file_name, ExportFormat::Png, &bytes));
+        if let Err(err) = result {
+            eprintln!("Failed to export PNG: {err}");
+        }
+    }
+
+    /// Rebuild the 2D canvas as SVG and save it.
+    fn export_svg(&mut self) {
+        let canvas_size = self
+            .app_state
+            .last_canvas_rect
+            .map_or(theme::canvas_2d::MIN_SIZE, |rect| rect.height());
+        let curve_size = self.selected_curve.size;
+        let snake_segments: &[usize] = if self.shared_settings.snake_enabled {
+            &self.app_state.snake_2d.segments
+        } else {
+            &[]
+        };
+        let file_name = self.export_file_name(ExportFormat::Svg);
+        let Some(points) = self.selected_curve.ensure_cached_points() else {
+            return;
+        };
+        let svg = export::svg_2d(&export::Svg2dScene {
+            points,
+            curve_size,
+            canvas_size,
+            snake_segments,
+            settings: &self.shared_settings,
+        });
+        if let Err(err) = export::deliver(&file_name, ExportFormat::Svg, svg.as_bytes()) {
+            eprintln!("Failed to export SVG: {err}");
+        }
+    }
+
+    /// Wait for every curve still being generated in the background.
+    ///
+    /// Offscreen renders capture a fixed frame, so they must not catch a pane mid-generation.
+    fn finish_generation(&mut self) {
+        self.selected_curve.finish_generation();
+        self.compare_curve.finish_generation();
+        self.selected_3d_curve.finish_generation();
+        self.compare_3d_curve.finish_generation();
+        self.file_view.curve.finish_generation();
+    }
+
+    /// Serialize the 3D curve geometry as OBJ or glTF and save it.
+    fn export_mesh(&mut self, format: ExportFormat) {
+        let file_name = self.export_file_name(format);
+        let name = self.selected_3d_curve.name.clone();
+        let show_long_jumps = self.shared_settings.show_long_jumps;
+        let Some(points) = self.selected_3d_curve.ensure_cached_points() else {
+            return;
+        };
+        let bytes = match format {
+            ExportFormat::Gltf => export::glb_3d(&name, points, show_long_jumps),
+            _ => export::obj_3d(&name, points, show_long_jumps).into_bytes(),
+        };
+        if let Err(err) = export::deliver(&file_name, format, &bytes) {
+            eprintln!("Failed to export {}: {err}", format.extension());
+        }
+    }
+
     /// Smooth and store the latest frame time (ms) for dev overlay.
     fn update_frame_time(&mut self, delta_seconds: f32, now_seconds: f64) {
         const DISPLAY_INTERVAL_S: f64 = 0.25;
@@ -420,6 +1038,7 @@ impl ScurveApp {
             return;
         };
         let fps = if ms > 0.0 { 1000.0 / ms } else { 0.0 };
+        let palette = theme::palette(ctx);
 
         let pos = if let Some(rect) = self.app_state.last_canvas_rect {
             egui::pos2(rect.max.x - 12.0, rect.min.y + 12.0)
@@ -434,8 +1053,8 @@ impl ScurveApp {
             .fixed_pos(pos)
             .show(ctx, |ui| {
                 egui::Frame::new()
-                    .fill(theme::PANEL_BACKGROUND)
-                    .stroke(egui::Stroke::new(1.0, theme::BORDER))
+                    .fill(palette.panel_background)
+                    .stroke(egui::Stroke::new(1.0, palette.border))
                     .corner_radius(egui::CornerRadius::same(4))
                     .inner_margin(egui::Margin::symmetric(8, 6))
                     .show(ui, |ui| {
@@ -443,22 +1062,61 @@ impl ScurveApp {
                         ui.horizontal(|ui| {
                             ui.label(
                                 egui::RichText::new(format!("{ms:.1} ms"))
-                                    .color(theme::TEXT_PRIMARY)
+                                    .color(palette.text_primary)
                                     .size(theme::font_size::INFO),
                             );
                             ui.add_space(6.0);
                             ui.label(
                                 egui::RichText::new(format!("{fps:.1} fps"))
-                                    .color(theme::TEXT_PRIMARY)
+                                    .color(palette.text_primary)
                                     .size(theme::font_size::INFO),
                             );
                         });
                     });
             });
     }
+
+    /// Show the frame time overlay and the debug panel, logging this frame's state changes.
+    fn show_dev_tools(&mut self, ctx: &egui::Context) {
+        self.show_frame_time_overlay(ctx);
+        self.events.observe(
+            ctx.input(|i| i.time),
+            Watched::new(
+                &self.app_state,
+                &self.selected_curve,
+                &self.selected_3d_curve,
+                self.shared_settings.theme.palette,
+            ),
+        );
+        debug_panel::show(
+            ctx,
+            &DebugView {
+                app_state: &self.app_state,
+                curve_2d: &self.selected_curve,
+                curve_3d: &self.selected_3d_curve,
+                events: &self.events,
+            },
+        );
+    }
 }
 
 impl eframe::App for ScurveApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(Camera::STORAGE_KEY, self.app_state.camera.to_storage());
+        storage.set_string(
+            ThemeSettings::STORAGE_KEY,
+            self.shared_settings.theme.to_storage(),
+        );
+        storage.set_string(
+            Bookmarks::STORAGE_KEY,
+            self.shared_settings.bookmarks.to_storage(),
+        );
+        storage.set_string(
+            REDUCED_MOTION_STORAGE_KEY,
+            self.shared_settings.reduced_motion.to_string(),
+        );
+    }
+
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Compute delta time using egui input time
         let now = ctx.input(|i| i.time);
@@ -479,12 +1137,25 @@ impl eframe::App for ScurveApp {
         // Only request a repaint when there is time-based animation to show
         let needs_repaint = self.shared_settings.snake_enabled
             || (self.app_state.current_pane == Pane::ThreeD
-                && (!self.app_state.paused || self.app_state.mouse_dragging));
+                && (!self.app_state.transport.paused || self.app_state.mouse_dragging));
         if needs_repaint {
             ctx.request_repaint();
         }
 
+        let palette = self.shared_settings.theme.palette();
+        if palette != theme::palette(ctx) {
+            theme::configure_visuals(ctx, &palette);
+        }
+        theme::apply_reduced_motion(ctx, self.shared_settings.reduced_motion);
+
+        // Handle shortcuts before panels so consumed keys do not reach widgets.
+        self.handle_commands(ctx);
         self.show_menu_bar(ctx);
+        if self.renderer_bar_open
+            && let Some(status) = &self.renderer
+        {
+            renderer::show_bar(ctx, status, &mut self.renderer_bar_open);
+        }
 
         // Show About dialog if open
         if self.app_state.about_open {
@@ -495,12 +1166,20 @@ impl eframe::App for ScurveApp {
             );
         }
 
+        if self.app_state.metadata_open {
+            self.show_metadata_panel(ctx);
+        }
+        if self.app_state.metrics.open {
+            self.show_metrics_table(ctx);
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| match self.app_state.current_pane {
             Pane::TwoD => {
                 show_2d_pane(
                     ui,
                     &mut self.app_state,
                     &mut self.selected_curve,
+                    &mut self.compare_curve,
                     &self.available_curves,
                     &mut self.shared_settings,
                 );
@@ -510,10 +1189,19 @@ impl eframe::App for ScurveApp {
                     ui,
                     &mut self.app_state,
                     &mut self.selected_3d_curve,
+                    &mut self.compare_3d_curve,
                     &self.available_curves,
                     &mut self.shared_settings,
                 );
             }
+            Pane::File => {
+                show_file_pane(
+                    ui,
+                    &mut self.app_state,
+                    &mut self.file_view,
+                    &self.available_curves,
+                );
+            }
         });
 
         // Synchronize selection between panes based on the active pane
@@ -525,20 +1213,31 @@ impl eframe::App for ScurveApp {
         );
 
         self.handle_screenshot(ctx, frame);
+        self.handle_export(ctx);
+        if self.file_view.update(ctx) {
+            self.app_state.current_pane = Pane::File;
+        }
+        if self.app_state.points.update(ctx) {
+            self.app_state.current_pane = Pane::TwoD;
+        }
+        self.handle_recording(ctx);
+        #[cfg(target_arch = "wasm32")]
+        self.sync_link();
+
+        widgets::show_toast(ctx, &mut self.app_state.toast);
 
         if self.show_dev_overlay {
-            self.show_frame_time_overlay(ctx);
+            self.show_dev_tools(ctx);
         }
     }
 }
 
-/// Persist an egui `ColorImage` to disk as a PNG file.
-fn save_color_image(path: &PathBuf, image: &egui::ColorImage) -> anyhow::Result<()> {
+/// Encode an egui `ColorImage` as PNG bytes.
+fn encode_png(image: &egui::ColorImage) -> anyhow::Result<Vec<u8>> {
     use png::{BitDepth, ColorType, Encoder};
 
-    let file = File::create(path)?;
-    let buffered_file = BufWriter::new(file);
-    let mut encoder = Encoder::new(buffered_file, image.size[0] as u32, image.size[1] as u32);
+    let mut bytes = Vec::new();
+    let mut encoder = Encoder::new(&mut bytes, image.size[0] as u32, image.size[1] as u32);
     encoder.set_color(ColorType::Rgba);
     encoder.set_depth(BitDepth::Eight);
     let mut writer = encoder.write_header()?;
@@ -550,6 +1249,13 @@ fn save_color_image(path: &PathBuf, image: &egui::ColorImage) -> anyhow::Result<
     }
 
     writer.write_image_data(&data)?;
+    writer.finish()?;
+    Ok(bytes)
+}
+
+/// Persist an egui `ColorImage` to disk as a PNG file.
+fn save_color_image(path: &Path, image: &egui::ColorImage) -> anyhow::Result<()> {
+    fs::write(path, encode_png(image)?)?;
     Ok(())
 }
 
@@ -0,0 +1,146 @@
+//! Side panel describing the selected curve straight from the registry.
+//!
+//! Everything shown here is derived from the registry entry and its validators, so the
+//! panel stays in sync with the CLI's constraints without duplicating them.
+
+use spacecurve::{curve_from_name, registry};
+
+use crate::{theme, widgets};
+
+/// The curve and grid that the metadata panel describes.
+pub struct MetadataTarget<'a> {
+    /// Registry key of the curve.
+    pub name: &'a str,
+    /// Dimension the curve is built in (4 for sliced 3D views).
+    pub dimension: u32,
+    /// Side length of the grid.
+    pub size: u32,
+    /// Whether every step at the current size moves to a neighbouring cell, when known.
+    pub continuous: Option<bool>,
+}
+
+/// Right-hand panel with the curve's description, constraints, capabilities and valid sizes.
+pub fn show_metadata_panel(ctx: &egui::Context, open: &mut bool, target: &MetadataTarget<'_>) {
+    let Some(entry) = registry::find(target.name) else {
+        return;
+    };
+    egui::SidePanel::right("curve_metadata")
+        .resizable(false)
+        .exact_width(theme::popup::INFO_PANE_WIDTH)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(entry.display).heading().strong());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let close = ui.add(
+                        egui::Button::new(egui::RichText::new("×").size(theme::font_size::LABEL))
+                            .frame(false),
+                    );
+                    if widgets::icon_label(close, "Close curve details").clicked() {
+                        *open = false;
+                    }
+                });
+            });
+            ui.add(egui::Separator::default().spacing(theme::spacing::MEDIUM));
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                metadata_contents(ui, entry, target);
+            });
+        });
+}
+
+/// Body of the metadata panel.
+fn metadata_contents(ui: &mut egui::Ui, entry: &registry::CurveEntry, target: &MetadataTarget<'_>) {
+    let palette = theme::palette(ui.ctx());
+    let body = |text: String| {
+        egui::RichText::new(text)
+            .size(theme::font_size::INFO)
+            .color(palette.text_body)
+    };
+
+    detail_row(ui, "Key", entry.key);
+    widgets::section_header(ui, "Description");
+    match curve_from_name(entry.key, target.dimension, target.size) {
+        Ok(curve) => {
+            let info = curve.info().lines().map(str::trim).collect::<Vec<_>>();
+            ui.label(body(info.join(" ")));
+        }
+        Err(e) => {
+            ui.label(
+                egui::RichText::new(format!("Unavailable at this size: {e}"))
+                    .size(theme::font_size::INFO)
+                    .color(palette.text_error),
+            );
+        }
+    }
+    ui.add_space(theme::spacing::MEDIUM);
+
+    widgets::section_header(ui, "Constraints");
+    ui.label(body(entry.constraints.to_string()));
+    ui.add_space(theme::spacing::MEDIUM);
+
+    widgets::section_header(ui, "Capabilities");
+    let spec = (entry.build_spec)(target.dimension, target.size).ok();
+    let order = spec.as_ref().and_then(|spec| spec.order());
+    detail_row(ui, "Experimental", yes_no(entry.experimental));
+    detail_row(
+        ui,
+        "Power-of-two grid",
+        &order.map_or_else(|| "no".to_string(), |order| format!("yes (order {order})")),
+    );
+    detail_row(ui, "Block overlay", yes_no(order.is_some()));
+    let grid = format!("Continuous at {}^{}", target.size, target.dimension);
+    detail_row(ui, &grid, target.continuous.map_or("unknown", yes_no));
+    ui.add_space(theme::spacing::MEDIUM);
+
+    widgets::section_header(ui, "Valid sizes");
+    for dimension in 2..=4 {
+        let max = if dimension == 2 {
+            theme::size_limits::MAX_2D
+        } else {
+            theme::size_limits::MAX_3D
+        };
+        let sizes: Vec<u32> = registry::valid_sizes(entry.key, dimension, max)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&size| size >= theme::size_limits::MIN)
+            .collect();
+        detail_row(ui, &format!("{dimension}D"), &summarize_sizes(&sizes));
+    }
+}
+
+/// A dimmed label followed by its value on one line.
+fn detail_row(ui: &mut egui::Ui, label: &str, value: &str) {
+    let palette = theme::palette(ui.ctx());
+    ui.horizontal_wrapped(|ui| {
+        ui.label(
+            egui::RichText::new(format!("{label}:"))
+                .size(theme::font_size::INFO)
+                .color(palette.text_dim),
+        );
+        ui.label(
+            egui::RichText::new(value)
+                .size(theme::font_size::INFO)
+                .color(palette.text_primary),
+        );
+    });
+}
+
+/// Render a flag as "yes" or "no".
+fn yes_no(flag: bool) -> &'static str {
+    if flag { "yes" } else { "no" }
+}
+
+/// Describe a sorted list of sizes as a range when contiguous, or list them otherwise.
+fn summarize_sizes(sizes: &[u32]) -> String {
+    match sizes {
+        [] => "none".to_string(),
+        [only] => only.to_string(),
+        [first, .., last] if (last - first) as usize + 1 == sizes.len() => {
+            format!("{first}–{last}")
+        }
+        _ => sizes
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
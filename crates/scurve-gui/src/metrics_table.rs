@@ -0,0 +1,295 @@
+//! Window tabulating locality measures of every available curve on the active pane's grid.
+//!
+//! Each row measures one registered curve at the same dimension and size with
+//! [`spacecurve::analysis`], so the table answers which curve keeps neighbouring cells closest
+//! together for the grid in view. A seeded shuffle of the grid's cells is measured alongside
+//! and shown last, outside the sort, as the baseline for a curve with no locality. Rows are
+//! measured once per grid and reused until the grid or the curve list changes.
+
+use std::cmp::Ordering;
+
+use egui::RichText;
+use spacecurve::{
+    analysis::{self, Metrics, Shuffle},
+    curve_from_name, registry,
+};
+
+use crate::theme;
+
+/// A sortable column of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    /// Curve display name.
+    Curve,
+    /// Steps that leave the neighbouring cells.
+    Discontinuities,
+    /// Longest step.
+    MaxJump,
+    /// Mean step length.
+    MeanStep,
+    /// Mean index gap between neighbouring cells.
+    MeanGap,
+    /// Largest index gap between neighbouring cells.
+    MaxGap,
+}
+
+impl Column {
+    /// Every column, in display order.
+    const ALL: [Self; 6] = [
+        Self::Curve,
+        Self::Discontinuities,
+        Self::MaxJump,
+        Self::MeanStep,
+        Self::MeanGap,
+        Self::MaxGap,
+    ];
+
+    /// Header text.
+    fn label(self) -> &'static str {
+        match self {
+            Self::Curve => "Curve",
+            Self::Discontinuities => "Jumps",
+            Self::MaxJump => "Max step",
+            Self::MeanStep => "Mean step",
+            Self::MeanGap => "Mean gap",
+            Self::MaxGap => "Max gap",
+        }
+    }
+
+    /// Header tooltip explaining the measure.
+    fn description(self) -> &'static str {
+        match self {
+            Self::Curve => "Registered curve",
+            Self::Discontinuities => {
+                "Steps between consecutive indices that skip to a non-adjacent cell"
+            }
+            Self::MaxJump => "Longest distance between consecutive indices",
+            Self::MeanStep => "Mean distance between consecutive indices",
+            Self::MeanGap => "Mean index difference between adjacent cells",
+            Self::MaxGap => "Largest index difference between adjacent cells",
+        }
+    }
+
+    /// Value of this column in `metrics`, as shown in the table.
+    fn text(self, metrics: &Metrics) -> String {
+        match self {
+            Self::Curve => String::new(),
+            Self::Discontinuities => metrics.discontinuities.to_string(),
+            Self::MaxJump => format!("{:.2}", metrics.max_jump),
+            Self::MeanStep => format!("{:.3}", metrics.mean_step),
+            Self::MeanGap => format!("{:.1}", metrics.mean_neighbor_gap),
+            Self::MaxGap => metrics.max_neighbor_gap.to_string(),
+        }
+    }
+
+    /// Order two measured rows by this column.
+    fn compare(self, a: &Row, b: &Row) -> Ordering {
+        let (Ok(ma), Ok(mb)) = (&a.metrics, &b.metrics) else {
+            return a.display.cmp(b.display);
+        };
+        match self {
+            Self::Curve => a.display.cmp(b.display),
+            Self::Discontinuities => ma.discontinuities.cmp(&mb.discontinuities),
+            Self::MaxJump => ma.max_jump.total_cmp(&mb.max_jump),
+            Self::MeanStep => ma.mean_step.total_cmp(&mb.mean_step),
+            Self::MeanGap => ma.mean_neighbor_gap.total_cmp(&mb.mean_neighbor_gap),
+            Self::MaxGap => ma.max_neighbor_gap.cmp(&mb.max_neighbor_gap),
+        }
+    }
+}
+
+/// One curve's measures, or why it cannot be built on the grid.
+struct Row {
+    /// Registry key of the curve.
+    key: &'static str,
+    /// Display name of the curve.
+    display: &'static str,
+    /// Measures, or the registry's error for this grid.
+    metrics: Result<Metrics, String>,
+}
+
+/// Grid a set of rows was measured on: curve keys, dimension and size.
+type GridKey = (Vec<&'static str>, u32, u32);
+
+/// Open state, sort order and measured rows of the metrics window.
+pub struct MetricsTable {
+    /// Whether the window is shown.
+    pub open: bool,
+    /// Column the rows are sorted by.
+    sort: Column,
+    /// Sort from largest to smallest.
+    descending: bool,
+    /// Grid the rows were measured on.
+    measured: Option<GridKey>,
+    /// Measured rows, in sort order.
+    rows: Vec<Row>,
+    /// Measures of the shuffled baseline, unless the grid is too large to shuffle.
+    baseline: Option<Metrics>,
+}
+
+impl Default for MetricsTable {
+    fn default() -> Self {
+        Self {
+            open: false,
+            sort: Column::MeanGap,
+            descending: false,
+            measured: None,
+            rows: Vec::new(),
+            baseline: None,
+        }
+    }
+}
+
+impl MetricsTable {
+    /// Measure `curves` on the grid of side `size` in `dimension` dimensions, unless the rows
+    /// already describe it.
+    fn measure(&mut self, curves: &[&'static str], dimension: u32, size: u32) {
+        let key = (curves.to_vec(), dimension, size);
+        if self.measured.as_ref() == Some(&key) {
+            return;
+        }
+        self.rows = curves
+            .iter()
+            .filter_map(|&name| registry::find(name))
+            .map(|entry| Row {
+                key: entry.key,
+                display: entry.display,
+                metrics: curve_from_name(entry.key, dimension, size)
+                    .and_then(|curve| analysis::metrics(curve.as_ref()))
+                    .map_err(|err| err.to_string()),
+            })
+            .collect();
+        self.baseline = Shuffle::baseline(dimension, size)
+            .ok()
+            .and_then(|shuffle| analysis::metrics(&shuffle).ok());
+        self.measured = Some(key);
+        self.sort_rows();
+    }
+
+    /// Sort the rows by the chosen column; curves that cannot be built always come last.
+    fn sort_rows(&mut self) {
+        let (sort, descending) = (self.sort, self.descending);
+        self.rows.sort_by(|a, b| {
+            let order = sort.compare(a, b);
+            let order = if descending { order.reverse() } else { order };
+            b.metrics.is_ok().cmp(&a.metrics.is_ok()).then(order)
+        });
+    }
+
+    /// Sort by `column`, flipping the direction when it is already the sort column.
+    fn sort_by(&mut self, column: Column) {
+        if self.sort == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort = column;
+            self.descending = false;
+        }
+        self.sort_rows();
+    }
+}
+
+/// Show the metrics window for `curves` on the active pane's grid.
+///
+/// The row of the curve named `selected` is highlighted, and clicking a curve selects it.
+pub fn show(
+    ctx: &egui::Context,
+    table: &mut MetricsTable,
+    curves: &[&'static str],
+    (dimension, size): (u32, u32),
+    selected: &mut String,
+) {
+    let mut open = table.open;
+    egui::Window::new("Curve metrics")
+        .open(&mut open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            table.measure(curves, dimension, size);
+            let palette = theme::palette(ui.ctx());
+            ui.label(
+                RichText::new(format!(
+                    "{dimension}D grid of side {size}; lower is better in every column"
+                ))
+                .size(theme::font_size::INFO)
+                .color(palette.text_dim),
+            );
+            ui.add_space(theme::spacing::SMALL);
+            let highlight = table
+                .rows
+                .iter()
+                .position(|row| row.key == selected.as_str())
+                .map(|i| i + 1);
+            let mut clicked = None;
+            egui::Grid::new("curve_metrics")
+                .num_columns(Column::ALL.len())
+                .striped(true)
+                .with_row_color(move |row, _| (Some(row) == highlight).then_some(palette.selection))
+                .show(ui, |ui| {
+                    header_row(ui, table, &mut clicked);
+                    for row in &table.rows {
+                        metric_row(ui, row, selected);
+                    }
+                    if let Some(baseline) = &table.baseline {
+                        baseline_row(ui, baseline);
+                    }
+                });
+            if let Some(column) = clicked {
+                table.sort_by(column);
+            }
+        });
+    table.open = open;
+}
+
+/// Clickable column headers; the sort column shows its direction.
+fn header_row(ui: &mut egui::Ui, table: &MetricsTable, clicked: &mut Option<Column>) {
+    for column in Column::ALL {
+        let arrow = match (table.sort == column, table.descending) {
+            (false, _) => "",
+            (true, false) => " ⏶",
+            (true, true) => " ⏷",
+        };
+        let text = RichText::new(format!("{}{arrow}", column.label())).strong();
+        if ui
+            .selectable_label(table.sort == column, text)
+            .on_hover_text(column.description())
+            .clicked()
+        {
+            *clicked = Some(column);
+        }
+    }
+    ui.end_row();
+}
+
+/// The shuffled baseline's row, dimmed to set it apart from the curves.
+fn baseline_row(ui: &mut egui::Ui, metrics: &Metrics) {
+    let dim = theme::palette(ui.ctx()).text_dim;
+    ui.label(RichText::new("Shuffled").italics().color(dim))
+        .on_hover_text("Cells in a seeded random order: the baseline with no locality");
+    for column in &Column::ALL[1..] {
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(RichText::new(column.text(metrics)).monospace().color(dim));
+        });
+    }
+    ui.end_row();
+}
+
+/// One curve's row; clicking the name selects the curve.
+fn metric_row(ui: &mut egui::Ui, row: &Row, selected: &mut String) {
+    if ui.link(row.display).clicked() {
+        selected.replace_range(.., row.key);
+    }
+    match &row.metrics {
+        Ok(metrics) => {
+            for column in &Column::ALL[1..] {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.monospace(column.text(metrics));
+                });
+            }
+        }
+        Err(err) => {
+            ui.label(RichText::new("not available").color(theme::palette(ui.ctx()).text_dim))
+                .on_hover_text(err);
+        }
+    }
+    ui.end_row();
+}
@@ -0,0 +1,400 @@
+//! Shareable GUI state encoded as a URL hash, e.g. `pane=3d&curve=hilbert&size3d=8`.
+//!
+//! Decoding is lenient: unknown keys and unparsable values are ignored so old or
+//! hand-edited links still open with as much of their state as possible.
+//!
+//! The web build mirrors [`LinkTarget::encode`] into the page URL as the state changes. Links
+//! copied with the share button use [`LinkTarget::share_link`], which also pins the snake
+//! positions and the camera.
+
+use egui::Color32;
+
+use crate::{
+    AppState, Pane, Selected3DCurve, SelectedCurve, SharedSettings,
+    colormap::{ColorBy, Colormap},
+    state::Camera,
+    theme::{self, PaletteName},
+    transport::LoopRegion,
+};
+
+/// Parts of the app that a link captures and restores.
+pub struct LinkTarget<'a> {
+    /// Active pane.
+    pub app_state: &'a mut AppState,
+    /// 2D selection (curve name and size).
+    pub curve_2d: &'a mut SelectedCurve,
+    /// 3D selection (size and 4D slice).
+    pub curve_3d: &'a mut Selected3DCurve,
+    /// Snake settings and palette.
+    pub settings: &'a mut SharedSettings,
+}
+
+impl LinkTarget<'_> {
+    /// Encode the current state as `key=value` pairs joined by `&`.
+    pub fn encode(&self) -> String {
+        let pane = match self.app_state.current_pane {
+            Pane::TwoD => "2d",
+            Pane::ThreeD => "3d",
+            Pane::File => "file",
+        };
+        let mut pairs = vec![
+            format!("pane={pane}"),
+            format!("curve={}", self.curve_2d.name),
+            format!("size={}", self.curve_2d.size),
+            format!("size3d={}", self.curve_3d.size),
+            format!("dims={}", self.curve_3d.curve_dimensions()),
+        ];
+        if let Some(w) = self.curve_3d.slice {
+            pairs.push(format!("w={w}"));
+        }
+        pairs.push(format!("snake={}", u8::from(self.settings.snake_enabled)));
+        pairs.push(format!("length={}", self.settings.snake_length));
+        pairs.push(format!("speed={}", self.settings.snake_speed));
+        if self.settings.race_enabled {
+            pairs.push(format!("race={}", self.settings.race_gap));
+        }
+        if let Some(region) = self.app_state.transport.loop_region {
+            pairs.push(format!("loop={},{}", region.start, region.end));
+        }
+        pairs.push(format!("opacity={}", self.settings.curve_opacity));
+        if self.settings.block_level > 0 {
+            pairs.push(format!("blocks={}", self.settings.block_level));
+        }
+        pairs.push(format!("colormap={}", self.settings.colormap.key()));
+        if self.settings.colormap == Colormap::Custom {
+            let [start, end] = self.settings.colormap_endpoints.map(hex);
+            pairs.push(format!("ends={start},{end}"));
+        }
+        if self.settings.color_by != ColorBy::Index {
+            pairs.push(format!("by={}", self.settings.color_by.key()));
+            pairs.push(format!("level={}", self.settings.color_level));
+        }
+        pairs.push(format!("theme={}", self.settings.theme.palette.key()));
+        pairs.join("&")
+    }
+
+    /// Encode the full state for sharing: [`Self::encode`] plus the snake positions and camera.
+    ///
+    /// These change every frame during playback, so they are left out of the mirrored hash.
+    pub fn share_link(&self) -> String {
+        let camera = self.app_state.camera;
+        format!(
+            "{}&pos={}&pos3d={}&cam={:.3},{:.3},{}",
+            self.encode(),
+            self.curve_2d.snake_offset as u32,
+            self.curve_3d.snake_offset as u32,
+            camera.yaw,
+            camera.tilt,
+            u8::from(camera.orthographic)
+        )
+    }
+
+    /// Apply every recognised pair in `hash`; curves must be listed in `available_curves`.
+    pub fn apply(&mut self, hash: &str, available_curves: &[&str]) {
+        let hash = hash.trim_start_matches('#');
+        for (key, value) in hash.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "pane" => match value {
+                    "2d" => self.app_state.current_pane = Pane::TwoD,
+                    "3d" => self.app_state.current_pane = Pane::ThreeD,
+                    "file" => self.app_state.current_pane = Pane::File,
+                    _ => {}
+                },
+                "curve" if available_curves.contains(&value) => {
+                    self.curve_2d.name = value.to_string();
+                    self.curve_3d.name = value.to_string();
+                }
+                "size" => set_size(&mut self.curve_2d.size, value, theme::size_limits::MAX_2D),
+                "size3d" => set_size(&mut self.curve_3d.size, value, theme::size_limits::MAX_3D),
+                "dims" => match value {
+                    "3" => self.curve_3d.slice = None,
+                    "4" => self.curve_3d.slice = self.curve_3d.slice.or(Some(0)),
+                    _ => {}
+                },
+                "snake" => self.settings.snake_enabled = value != "0",
+                "length" => set_clamped(&mut self.settings.snake_length, value, 0.0, 50.0),
+                "speed" => set_clamped(&mut self.settings.snake_speed, value, 1.0, 200.0),
+                "race" => {
+                    self.settings.race_enabled = true;
+                    set_clamped(&mut self.settings.race_gap, value, 0.0, 100.0);
+                }
+                "theme" => {
+                    if let Some(palette) = PaletteName::from_key(value) {
+                        self.settings.theme.palette = palette;
+                    }
+                }
+                "loop" => {
+                    if let Some((start, end)) = pair(value) {
+                        self.app_state.transport.loop_region = LoopRegion::new(start, end);
+                    }
+                }
+                "opacity" => set_clamped(&mut self.settings.curve_opacity, value, 0.0, 1.0),
+                "blocks" => set_level(&mut self.settings.block_level, value),
+                "colormap" => {
+                    if let Some(colormap) = Colormap::from_key(value) {
+                        self.settings.colormap = colormap;
+                    }
+                }
+                "ends" => {
+                    if let Some((start, end)) = value.split_once(',')
+                        && let (Some(start), Some(end)) = (parse_hex(start), parse_hex(end))
+                    {
+                        self.settings.colormap_endpoints = [start, end];
+                    }
+                }
+                "by" => {
+                    if let Some(color_by) = ColorBy::from_key(value) {
+                        self.settings.color_by = color_by;
+                    }
+                }
+                "level" => set_level(&mut self.settings.color_level, value),
+                "pos" => set_offset(&mut self.curve_2d.snake_offset, value),
+                "pos3d" => set_offset(&mut self.curve_3d.snake_offset, value),
+                "cam" => {
+                    if let Some(camera) = parse_camera(value) {
+                        self.app_state.camera = camera;
+                    }
+                }
+                _ => {}
+            }
+        }
+        // The slice position is applied last so it is not reset by a later `dims` pair.
+        if let Some(w) = hash
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("w="))
+            .and_then(|value| value.parse::<u32>().ok())
+            && self.curve_3d.slice.is_some()
+        {
+            self.curve_3d.slice = Some(w.min(self.curve_3d.size.saturating_sub(1)));
+        }
+    }
+}
+
+/// Parse a side length and store it if it lies within the GUI limits.
+fn set_size(size: &mut u32, value: &str, max: u32) {
+    if let Ok(parsed) = value.parse::<u32>()
+        && (theme::size_limits::MIN..=max).contains(&parsed)
+    {
+        *size = parsed;
+    }
+}
+
+/// Parse a recursion level and store it if it lies within the block overlay's range.
+fn set_level(level: &mut u32, value: &str) {
+    if let Ok(parsed) = value.parse::<u32>()
+        && parsed <= theme::blocks::MAX_LEVEL
+    {
+        *level = parsed;
+    }
+}
+
+/// Parse a snake offset in whole segments.
+fn set_offset(offset: &mut f32, value: &str) {
+    if let Ok(parsed) = value.parse::<u32>() {
+        *offset = parsed as f32;
+    }
+}
+
+/// Parse two comma-separated floats.
+fn pair(value: &str) -> Option<(f32, f32)> {
+    let (a, b) = value.split_once(',')?;
+    Some((a.parse().ok()?, b.parse().ok()?))
+}
+
+/// Parse `yaw,tilt,orthographic` into a camera.
+fn parse_camera(value: &str) -> Option<Camera> {
+    let (angles, orthographic) = value.rsplit_once(',')?;
+    let (yaw, tilt) = pair(angles)?;
+    (yaw.is_finite() && tilt.is_finite()).then(|| Camera {
+        yaw,
+        tilt,
+        orthographic: orthographic == "1",
+    })
+}
+
+/// `color` as six hex digits.
+fn hex(color: Color32) -> String {
+    format!("{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+/// Parse six hex digits written by [`hex`].
+fn parse_hex(value: &str) -> Option<Color32> {
+    let rgb = u32::from_str_radix(value, 16)
+        .ok()
+        .filter(|_| value.len() == 6)?;
+    let [_, r, g, b] = rgb.to_be_bytes();
+    Some(Color32::from_rgb(r, g, b))
+}
+
+/// Parse a float and store it clamped to `min..=max`.
+fn set_clamped(target: &mut f32, value: &str, min: f32, max: f32) {
+    if let Ok(parsed) = value.parse::<f32>()
+        && parsed.is_finite()
+    {
+        *target = parsed.clamp(min, max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Curves the tests' links may select.
+    const CURVES: &[&str] = &["hilbert", "zorder", "onion"];
+
+    /// Owned state for a [`LinkTarget`] to borrow.
+    #[derive(Default)]
+    struct State {
+        app_state: AppState,
+        curve_2d: SelectedCurve,
+        curve_3d: Selected3DCurve,
+        settings: SharedSettings,
+    }
+
+    impl State {
+        fn target(&mut self) -> LinkTarget<'_> {
+            LinkTarget {
+                app_state: &mut self.app_state,
+                curve_2d: &mut self.curve_2d,
+                curve_3d: &mut self.curve_3d,
+                settings: &mut self.settings,
+            }
+        }
+
+        /// A fresh state with `hash` applied.
+        fn from_hash(hash: &str) -> Self {
+            let mut state = Self::default();
+            state.target().apply(hash, CURVES);
+            state
+        }
+    }
+
+    /// A state that differs from the defaults in everything a link carries.
+    fn customised() -> State {
+        let mut state = State::default();
+        state.app_state.current_pane = Pane::ThreeD;
+        state.app_state.transport.loop_region = LoopRegion::new(0.25, 0.75);
+        state.app_state.camera = Camera {
+            yaw: 1.25,
+            tilt: -0.5,
+            orthographic: true,
+        };
+        state.curve_2d.name = "zorder".to_string();
+        state.curve_2d.size = 64;
+        state.curve_2d.snake_offset = 17.0;
+        state.curve_3d.name = "zorder".to_string();
+        state.curve_3d.size = 16;
+        state.curve_3d.slice = Some(5);
+        state.curve_3d.snake_offset = 9.0;
+        let settings = &mut state.settings;
+        settings.snake_enabled = !settings.snake_enabled;
+        settings.snake_length = 12.5;
+        settings.snake_speed = 40.0;
+        settings.race_enabled = true;
+        settings.race_gap = 30.0;
+        settings.curve_opacity = 0.5;
+        settings.block_level = 2;
+        settings.colormap = Colormap::Custom;
+        settings.colormap_endpoints = [Color32::from_rgb(0x12, 0x34, 0x56), Color32::WHITE];
+        settings.color_by = ColorBy::Block;
+        settings.color_level = 3;
+        settings.theme.palette = PaletteName::Paper;
+        state
+    }
+
+    #[test]
+    fn encode_round_trips_through_apply() {
+        let mut original = customised();
+        let decoded = State::from_hash(&format!("#{}", original.target().encode()));
+
+        assert_eq!(decoded.app_state.current_pane, Pane::ThreeD);
+        assert_eq!(
+            decoded.app_state.transport.loop_region,
+            original.app_state.transport.loop_region
+        );
+        assert_eq!(decoded.curve_2d.name, "zorder");
+        assert_eq!(decoded.curve_3d.name, "zorder");
+        assert_eq!(decoded.curve_2d.size, 64);
+        assert_eq!(decoded.curve_3d.size, 16);
+        assert_eq!(decoded.curve_3d.slice, Some(5));
+        let (a, b) = (&decoded.settings, &original.settings);
+        assert_eq!(a.snake_enabled, b.snake_enabled);
+        assert_eq!(a.snake_length, b.snake_length);
+        assert_eq!(a.snake_speed, b.snake_speed);
+        assert!(a.race_enabled);
+        assert_eq!(a.race_gap, b.race_gap);
+        assert_eq!(a.curve_opacity, b.curve_opacity);
+        assert_eq!(a.block_level, 2);
+        assert_eq!(a.colormap, Colormap::Custom);
+        assert_eq!(a.colormap_endpoints, b.colormap_endpoints);
+        assert_eq!(a.color_by, ColorBy::Block);
+        assert_eq!(a.color_level, 3);
+        assert_eq!(a.theme.palette, PaletteName::Paper);
+        // The mirrored hash leaves out what changes every frame.
+        assert_eq!(decoded.curve_2d.snake_offset, 0.0);
+        assert_eq!(decoded.app_state.camera, Camera::default());
+    }
+
+    #[test]
+    fn share_links_also_restore_positions_and_camera() {
+        let mut original = customised();
+        let decoded = State::from_hash(&original.target().share_link());
+        assert_eq!(decoded.curve_2d.snake_offset, 17.0);
+        assert_eq!(decoded.curve_3d.snake_offset, 9.0);
+        assert_eq!(decoded.app_state.camera, original.app_state.camera);
+    }
+
+    #[test]
+    fn default_state_round_trips() {
+        let mut original = State::default();
+        let hash = original.target().encode();
+        let mut decoded = State::from_hash(&hash);
+        assert_eq!(decoded.target().encode(), hash);
+    }
+
+    #[test]
+    fn malformed_pairs_are_ignored() {
+        let defaults = State::default();
+        let decoded = State::from_hash(
+            "pane=4d&curve=nonesuch&size=big&size3d=&dims=7&loop=0.5&cam=1,2&ends=zzzzzz,ffffff\
+             &theme=plaid&colormap=sepia&by=mood&level=x&pos=-3&opacity=NaN&junk&=1&speed",
+        );
+        assert_eq!(
+            decoded.app_state.current_pane,
+            defaults.app_state.current_pane
+        );
+        assert_eq!(decoded.curve_2d.name, defaults.curve_2d.name);
+        assert_eq!(decoded.curve_2d.size, defaults.curve_2d.size);
+        assert_eq!(decoded.curve_3d.size, defaults.curve_3d.size);
+        assert_eq!(decoded.curve_3d.slice, None);
+        assert_eq!(decoded.app_state.transport.loop_region, None);
+        assert_eq!(decoded.app_state.camera, defaults.app_state.camera);
+        let (a, b) = (&decoded.settings, &defaults.settings);
+        assert_eq!(a.colormap_endpoints, b.colormap_endpoints);
+        assert_eq!(a.theme.palette, b.theme.palette);
+        assert_eq!(a.colormap, b.colormap);
+        assert_eq!(a.color_by, b.color_by);
+        assert_eq!(a.color_level, b.color_level);
+        assert_eq!(a.curve_opacity, b.curve_opacity);
+        assert_eq!(a.snake_speed, b.snake_speed);
+        assert_eq!(decoded.curve_2d.snake_offset, 0.0);
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped_or_rejected() {
+        let decoded = State::from_hash(&format!(
+            "size={}&size3d=1&dims=4&size3d=8&w=99&length=1e9&speed=-5&opacity=7&blocks={}&race=500",
+            theme::size_limits::MAX_2D * 2,
+            theme::blocks::MAX_LEVEL + 1,
+        ));
+        let defaults = State::default();
+        assert_eq!(decoded.curve_2d.size, defaults.curve_2d.size);
+        assert_eq!(decoded.curve_3d.size, 8);
+        assert_eq!(decoded.curve_3d.slice, Some(7));
+        assert_eq!(decoded.settings.snake_length, 50.0);
+        assert_eq!(decoded.settings.snake_speed, 1.0);
+        assert_eq!(decoded.settings.curve_opacity, 1.0);
+        assert_eq!(decoded.settings.block_level, defaults.settings.block_level);
+        assert_eq!(decoded.settings.race_gap, 100.0);
+    }
+}
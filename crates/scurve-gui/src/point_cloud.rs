@@ -0,0 +1,321 @@
+//! Point clouds imported from CSV and drawn in curve order.
+//!
+//! Sorting records by their index along a space-filling curve is the usual way to give
+//! scattered data locality. An imported cloud is snapped onto the 2D pane's grid, each point is
+//! keyed by the curve position of its cell, and the points are joined in that order, so
+//! switching curves shows how each one would sort the same data. The pane reports how long
+//! that path is next to the path through the points in file order.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result, bail};
+use egui::{Pos2, Stroke};
+
+use crate::{
+    colormap::{Colormap, Gradient},
+    selection::SelectedCurve,
+    theme::{self, Palette},
+};
+
+/// Grid cells in curve order, with the curve name and grid size they were sorted for.
+type Ordering = ((String, u32), Vec<[u32; 2]>);
+
+/// Field separators other than spaces, in order of preference.
+const DELIMITERS: [u8; 3] = [b',', b';', b'\t'];
+
+/// Points read from a CSV file.
+#[derive(Debug, Clone)]
+pub struct PointCloud {
+    /// File name shown in the menu, without directories.
+    pub name: String,
+    /// Coordinates as read, with `y` growing upwards.
+    pub points: Vec<[f64; 2]>,
+    /// Recent orderings of the cloud; two are kept so compared curves do not evict each other.
+    ordered: Vec<Ordering>,
+}
+
+impl PointCloud {
+    /// Parse CSV `text` read from `name`.
+    ///
+    /// Rows hold at least two numeric columns, of which the first two are used. Fields are
+    /// separated by the first of commas, semicolons or tabs found on the first row, or by runs
+    /// of spaces when there are none. Blank lines and lines starting with `#` are skipped, as is
+    /// a leading header row.
+    pub fn parse(name: String, text: &str) -> Result<Self> {
+        let first = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or("");
+        let delimiter = DELIMITERS
+            .into_iter()
+            .find(|d| first.as_bytes().contains(d))
+            .unwrap_or(b' ');
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .comment(Some(b'#'))
+            .delimiter(delimiter)
+            .from_reader(text.as_bytes());
+
+        let mut points = Vec::new();
+        let mut header_allowed = true;
+        for record in reader.records() {
+            let record = record.with_context(|| format!("{name} is not valid CSV"))?;
+            // Space-separated rows may hold runs of spaces, which read as empty fields.
+            let mut fields = record
+                .iter()
+                .filter(|field| !field.is_empty())
+                .map(str::parse::<f64>)
+                .peekable();
+            if fields.peek().is_none() {
+                continue;
+            }
+            match (fields.next(), fields.next()) {
+                (Some(Ok(x)), Some(Ok(y))) if x.is_finite() && y.is_finite() => {
+                    points.push([x, y]);
+                }
+                _ if header_allowed => {}
+                _ => {
+                    let line = record.position().map_or(0, csv::Position::line);
+                    bail!("{name}, line {line}: expected two numeric columns");
+                }
+            }
+            header_allowed = false;
+            if points.len() > theme::points::MAX_POINTS {
+                bail!("{name} has more than {} points", theme::points::MAX_POINTS);
+            }
+        }
+        if points.is_empty() {
+            bail!("{name} has no points");
+        }
+        Ok(Self {
+            name,
+            points,
+            ordered: Vec::new(),
+        })
+    }
+
+    /// The points snapped onto a grid of side `size`, stretched to fill it on both axes.
+    pub fn cells(&self, size: u32) -> Vec<[u32; 2]> {
+        let bounds = |axis: usize| {
+            self.points
+                .iter()
+                .map(|p| p[axis])
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                    (lo.min(v), hi.max(v))
+                })
+        };
+        let [(x0, x1), (y0, y1)] = [bounds(0), bounds(1)];
+        let last = f64::from(size.saturating_sub(1));
+        let snap = |v: f64, lo: f64, hi: f64| {
+            if hi > lo {
+                ((v - lo) / (hi - lo) * last).round() as u32
+            } else {
+                0
+            }
+        };
+        // Grid rows grow downwards, so y is flipped to keep the cloud upright.
+        self.points
+            .iter()
+            .map(|&[x, y]| [snap(x, x0, x1), last as u32 - snap(y, y0, y1)])
+            .collect()
+    }
+
+    /// The cloud's cells on the grid of `selection`, sorted by their position along its curve.
+    pub fn ordered(&mut self, selection: &mut SelectedCurve) -> Option<&[[u32; 2]]> {
+        let key = (selection.name.clone(), selection.size);
+        let cached = self.ordered.iter().position(|(k, _)| *k == key);
+        let slot = match cached {
+            Some(slot) => slot,
+            None => {
+                selection.ensure_cached_points()?;
+                let mut cells = self.cells(selection.size);
+                cells.sort_by_cached_key(|&cell| selection.position_of(cell));
+                if self.ordered.len() == 2 {
+                    self.ordered.remove(0);
+                }
+                self.ordered.push((key, cells));
+                self.ordered.len() - 1
+            }
+        };
+        Some(&self.ordered[slot].1)
+    }
+}
+
+/// Total length, in grid cells, of the path through `cells` in order.
+pub fn path_length(cells: &[[u32; 2]]) -> f64 {
+    cells
+        .windows(2)
+        .map(|pair| {
+            let [dx, dy] = [0, 1].map(|axis| f64::from(pair[0][axis].abs_diff(pair[1][axis])));
+            dx.hypot(dy)
+        })
+        .sum()
+}
+
+/// A CSV picked by a dialog that may complete after the frame that opened it.
+type PendingCloud = Arc<Mutex<Option<Result<PointCloud>>>>;
+
+/// The imported cloud, if any, and the state of importing one.
+#[derive(Default)]
+pub struct PointImport {
+    /// The cloud drawn over the 2D pane.
+    pub cloud: Option<PointCloud>,
+    /// Why the last import failed, shown until the next one succeeds.
+    pub error: Option<String>,
+    /// Open the import dialog at the end of the frame.
+    pub open_request: bool,
+    /// Result of the dialog in flight, filled in by the dialog itself.
+    pending: PendingCloud,
+}
+
+impl PointImport {
+    /// Open a requested dialog and take in the picked file.
+    ///
+    /// Returns true when a new cloud was imported this frame.
+    pub fn update(&mut self, ctx: &egui::Context) -> bool {
+        if mem::take(&mut self.open_request) {
+            open_dialog(ctx, &self.pending);
+        }
+        match self.pending.lock().ok().and_then(|mut slot| slot.take()) {
+            Some(Ok(cloud)) => {
+                self.cloud = Some(cloud);
+                self.error = None;
+                true
+            }
+            Some(Err(err)) => {
+                self.error = Some(err.to_string());
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// Parse the bytes of a picked file.
+fn load(name: String, bytes: &[u8]) -> Result<PointCloud> {
+    PointCloud::parse(name, &String::from_utf8_lossy(bytes))
+}
+
+/// Ask the user for a CSV file with a native dialog and parse it into `pending`.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_dialog(_ctx: &egui::Context, pending: &PendingCloud) {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("CSV", &["csv", "tsv", "txt"])
+        .pick_file()
+    else {
+        return;
+    };
+    let name = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    let result = fs::read(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| load(name, &bytes));
+    if let Ok(mut slot) = pending.lock() {
+        *slot = Some(result);
+    }
+}
+
+/// Show the browser's file picker; the parsed file lands in `pending` once it is read.
+#[cfg(target_arch = "wasm32")]
+fn open_dialog(ctx: &egui::Context, pending: &PendingCloud) {
+    let ctx = ctx.clone();
+    let pending = pending.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        let Some(handle) = rfd::AsyncFileDialog::new()
+            .add_filter("CSV", &["csv", "tsv", "txt"])
+            .pick_file()
+            .await
+        else {
+            return;
+        };
+        let result = load(handle.file_name(), &handle.read().await);
+        if let Ok(mut slot) = pending.lock() {
+            *slot = Some(result);
+        }
+        ctx.request_repaint();
+    });
+}
+
+/// Draw the cloud's points at `screen`, joined in curve order.
+///
+/// The path takes the pane's colormap from its first point to its last, falling back from the
+/// solid colormap like structural coloring does.
+pub fn paint_cloud(
+    painter: &egui::Painter,
+    screen: &[Pos2],
+    (colormap, endpoints): (Colormap, [egui::Color32; 2]),
+    palette: &Palette,
+) {
+    let colormap = match colormap {
+        Colormap::Solid if palette.colorblind_safe => Colormap::Cividis,
+        Colormap::Solid => Colormap::Turbo,
+        other => other,
+    };
+    let Some(gradient) = Gradient::new(colormap, endpoints, screen.len()) else {
+        return;
+    };
+    for (i, pair) in screen.windows(2).enumerate() {
+        painter.line_segment(
+            [pair[0], pair[1]],
+            Stroke::new(theme::points::PATH_WIDTH, gradient.color(i, 1.0)),
+        );
+    }
+    for &pos in screen {
+        painter.circle_filled(pos, theme::points::RADIUS, palette.text_primary);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Vec<[f64; 2]>> {
+        PointCloud::parse("points.csv".to_string(), text).map(|cloud| cloud.points)
+    }
+
+    #[test]
+    fn reads_the_first_two_columns() {
+        assert_eq!(
+            parse("1,2\n3.5, -4,9\n\"5\",6\n").unwrap(),
+            [[1.0, 2.0], [3.5, -4.0], [5.0, 6.0]]
+        );
+    }
+
+    #[test]
+    fn skips_a_header_comments_and_blank_lines() {
+        let text = "# exported points\nx,y\n\n1,2\n# halfway\n3,4\n";
+        assert_eq!(parse(text).unwrap(), [[1.0, 2.0], [3.0, 4.0]]);
+        assert_eq!(parse("1,2\n").unwrap(), [[1.0, 2.0]]);
+    }
+
+    #[test]
+    fn detects_delimiters() {
+        let expected = [[1.0, 2.0], [3.0, 4.0]];
+        assert_eq!(parse("1;2\n3;4\n").unwrap(), expected);
+        assert_eq!(parse("1\t2\n3\t4\n").unwrap(), expected);
+        assert_eq!(parse("1 2\n  3    4\n").unwrap(), expected);
+        // Commas win over spaces, which are then trimmed from fields.
+        assert_eq!(parse("x, y\n1, 2\n3 ,4\n").unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_bad_rows() {
+        let err = parse("1,2\n3,x\n").unwrap_err().to_string();
+        assert_eq!(err, "points.csv, line 2: expected two numeric columns");
+        assert!(parse("x,y\nname,value\n").is_err());
+        assert!(parse("1\n2\n").is_err());
+        assert!(parse("1,inf\n2,3\n4,NaN\n").is_err());
+        assert!(parse("x,y\n").is_err());
+        assert!(parse("# nothing\n\n").is_err());
+    }
+}
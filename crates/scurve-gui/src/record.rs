@@ -0,0 +1,215 @@
+//! Recording of the active pane to animated GIFs or PNG frame sequences.
+//!
+//! Frames are captured from viewport screenshots at a fixed rate, downscaled, and
+//! kept in memory until the recording ends; encoding happens once at the end.
+//! The GIF encoder is shared with the CLI `snake` command.
+
+use std::io::Write;
+
+use anyhow::Result;
+use egui::{ColorImage, Rect, UserData};
+use gif::{Encoder, Frame, Repeat};
+
+use crate::{
+    encode_png,
+    export::{self, ExportFormat},
+    theme,
+};
+
+/// Output produced by a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// A single looping animated GIF.
+    Gif,
+    /// One numbered PNG file per captured frame.
+    Frames,
+}
+
+impl RecordFormat {
+    /// Every recording format, in menu order.
+    pub const ALL: [Self; 2] = [Self::Gif, Self::Frames];
+
+    /// Menu label for this format.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Gif => "GIF…",
+            Self::Frames => "PNG frames…",
+        }
+    }
+}
+
+/// Looping GIF encoder writing RGBA frames of a fixed size.
+pub struct GifWriter<W: Write> {
+    /// Underlying GIF encoder.
+    encoder: Encoder<W>,
+    /// Frame width in pixels.
+    width: u16,
+    /// Frame height in pixels.
+    height: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Start an infinitely looping GIF of `width` × `height` pixels.
+    pub fn new(out: W, width: u16, height: u16) -> Result<Self> {
+        let mut encoder = Encoder::new(out, width, height, &[])?;
+        encoder.set_repeat(Repeat::Infinite)?;
+        Ok(Self {
+            encoder,
+            width,
+            height,
+        })
+    }
+
+    /// Quantize and append one RGBA frame shown for `delay` centiseconds.
+    pub fn write_rgba(&mut self, rgba: &mut [u8], delay: u16) -> Result<()> {
+        let mut frame = Frame::from_rgba_speed(self.width, self.height, rgba, 10);
+        frame.delay = delay;
+        self.encoder.write_frame(&frame)?;
+        Ok(())
+    }
+}
+
+/// Convert frames-per-second into a GIF frame delay (hundredths of a second).
+pub fn frame_delay_from_fps(fps: u16) -> u16 {
+    // GIF delays are centiseconds; clamp to at least 1cs to avoid zero-delay frames.
+    let fps = fps.max(1);
+    ((100 + (fps / 2)) / fps).max(1)
+}
+
+/// Marker attached to screenshots requested by the recorder.
+struct RecorderShot;
+
+/// An in-progress recording of the active pane.
+pub struct Recorder {
+    /// Output format written when the recording ends.
+    format: RecordFormat,
+    /// Recording length in seconds.
+    duration: f64,
+    /// Input time at which recording started.
+    started: f64,
+    /// Input time at which the next frame should be requested.
+    next_capture: f64,
+    /// Whether a screenshot has been requested but not yet delivered.
+    pending: bool,
+    /// Captured frames with their capture times.
+    frames: Vec<(f64, ColorImage)>,
+}
+
+impl Recorder {
+    /// Start recording `seconds` of video at input time `now`.
+    pub fn new(format: RecordFormat, seconds: f32, now: f64) -> Self {
+        Self {
+            format,
+            duration: f64::from(seconds),
+            started: now,
+            next_capture: now,
+            pending: false,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Collect delivered frames and request the next one, cropping to `canvas`.
+    ///
+    /// Returns `true` once the recording has run for its full duration.
+    pub fn update(&mut self, ctx: &egui::Context, canvas: Option<Rect>) -> bool {
+        let now = ctx.input(|i| i.time);
+        let captured = ctx.input(|input| {
+            input.events.iter().find_map(|event| match event {
+                egui::Event::Screenshot {
+                    image, user_data, ..
+                } if is_recorder_shot(user_data) => Some(image.clone()),
+                _ => None,
+            })
+        });
+        if let Some(image) = captured {
+            self.pending = false;
+            let region = match canvas {
+                Some(rect) => image.region(&rect, Some(ctx.pixels_per_point())),
+                None => (*image).clone(),
+            };
+            self.frames.push((now, downscale(&region)));
+        }
+
+        if self.pending {
+            ctx.request_repaint();
+            return false;
+        }
+        if now - self.started >= self.duration {
+            return true;
+        }
+        if now >= self.next_capture {
+            self.next_capture = now + 1.0 / f64::from(theme::recording::FPS);
+            self.pending = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot(UserData::new(
+                RecorderShot,
+            )));
+        }
+        ctx.request_repaint();
+        false
+    }
+
+    /// Encode the captured frames and hand them to the user, named after `stem`.
+    pub fn finish(self, stem: &str) -> Result<()> {
+        match self.format {
+            RecordFormat::Gif => {
+                let bytes = self.encode_gif()?;
+                export::deliver(&format!("{stem}.gif"), ExportFormat::Gif, &bytes)
+            }
+            RecordFormat::Frames => {
+                let frames = self
+                    .frames
+                    .iter()
+                    .map(|(_, image)| encode_png(image))
+                    .collect::<Result<Vec<_>>>()?;
+                export::deliver_frames(stem, &frames)
+            }
+        }
+    }
+
+    /// Encode frames matching the first frame's size as a GIF timed by capture times.
+    fn encode_gif(&self) -> Result<Vec<u8>> {
+        let Some((_, first)) = self.frames.first() else {
+            anyhow::bail!("no frames were captured");
+        };
+        let [width, height] = first.size;
+        let mut bytes = Vec::new();
+        let mut writer = GifWriter::new(&mut bytes, width as u16, height as u16)?;
+        let last_delay = frame_delay_from_fps(theme::recording::FPS);
+        for (i, (time, image)) in self.frames.iter().enumerate() {
+            // Frames captured after a window resize cannot join the animation.
+            if image.size != first.size {
+                continue;
+            }
+            let delay = self.frames.get(i + 1).map_or(last_delay, |(next, _)| {
+                ((next - time) * 100.0).round().max(1.0) as u16
+            });
+            let mut rgba = image.as_raw().to_vec();
+            writer.write_rgba(&mut rgba, delay)?;
+        }
+        drop(writer);
+        Ok(bytes)
+    }
+}
+
+/// Whether a screenshot was requested by the recorder rather than another capture.
+fn is_recorder_shot(user_data: &UserData) -> bool {
+    user_data
+        .data
+        .as_ref()
+        .is_some_and(|data| data.downcast_ref::<RecorderShot>().is_some())
+}
+
+/// Shrink `image` by an integer factor so neither side exceeds the recording limit.
+fn downscale(image: &ColorImage) -> ColorImage {
+    let [width, height] = image.size;
+    let max_side = theme::recording::MAX_FRAME_SIDE;
+    let step = width.max(height).div_ceil(max_side).max(1);
+    if step == 1 {
+        return image.clone();
+    }
+    let size = [width / step, height / step];
+    let pixels = (0..size[1])
+        .flat_map(|y| (0..size[0]).map(move |x| image.pixels[y * step * width + x * step]))
+        .collect();
+    ColorImage::new(size, pixels)
+}
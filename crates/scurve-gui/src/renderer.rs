@@ -0,0 +1,227 @@
+//! Renderer backend choice for the web build.
+//!
+//! Before the app starts, the browser is probed for WebGL2 and WebGL1. eframe fixes its web
+//! painter at compile time, and this build uses its WebGL painter, so WebGPU is neither
+//! offered nor probed for. The user may pin WebGL2 or WebGL1, or let the best available
+//! context win. The choice is kept in local storage, and switching reloads
+//! the page because a running app can't change its context. When the preferred backend is
+//! missing, a banner names the one used instead, so a blank canvas on an older browser
+//! comes with an explanation.
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use web_sys::{HtmlCanvasElement, window};
+
+use crate::theme;
+
+/// Local storage key holding the preferred backend.
+pub const STORAGE_KEY: &str = "scurve.renderer";
+
+/// A rendering context the web build can draw with, or the preference for one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// The best context the browser offers.
+    #[default]
+    Auto,
+    /// WebGL 2.
+    WebGl2,
+    /// WebGL 1, for browsers or drivers without WebGL 2.
+    WebGl1,
+}
+
+impl Backend {
+    /// Every choice, in the order shown in the picker.
+    pub const ALL: [Self; 3] = [Self::Auto, Self::WebGl2, Self::WebGl1];
+
+    /// Key used in local storage and the `renderer` query parameter.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::WebGl2 => "webgl2",
+            Self::WebGl1 => "webgl1",
+        }
+    }
+
+    /// Parse a [`Self::key`], ignoring case.
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|backend| backend.key().eq_ignore_ascii_case(key))
+    }
+
+    /// Human-friendly name.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "Auto",
+            Self::WebGl2 => "WebGL2",
+            Self::WebGl1 => "WebGL1",
+        }
+    }
+}
+
+/// WebGL versions the browser offers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// A WebGL 2 context can be created.
+    pub webgl2: bool,
+    /// A WebGL 1 context can be created.
+    pub webgl1: bool,
+}
+
+impl Capabilities {
+    /// Whether `backend` can be used; `Auto` needs any WebGL context.
+    pub fn supports(self, backend: Backend) -> bool {
+        match backend {
+            Backend::Auto => self.webgl2 || self.webgl1,
+            Backend::WebGl2 => self.webgl2,
+            Backend::WebGl1 => self.webgl1,
+        }
+    }
+
+    /// Comma-separated names of the versions offered, or "none".
+    pub fn summary(self) -> String {
+        let names: Vec<&str> = [(self.webgl2, "WebGL2"), (self.webgl1, "WebGL1")]
+            .into_iter()
+            .filter_map(|(offered, name)| offered.then_some(name))
+            .collect();
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
+}
+
+/// The preferred backend, and the one actually used given what the browser offers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RendererStatus {
+    /// What the browser offers.
+    pub capabilities: Capabilities,
+    /// What the user asked for.
+    pub preferred: Backend,
+    /// The context the app starts with, or `None` when WebGL is unavailable entirely.
+    pub active: Option<Backend>,
+}
+
+impl RendererStatus {
+    /// Resolve `preferred` against `capabilities`, falling back to any other WebGL context.
+    pub fn new(capabilities: Capabilities, preferred: Backend) -> Self {
+        let active = [preferred, Backend::WebGl2, Backend::WebGl1]
+            .into_iter()
+            .filter(|&backend| backend != Backend::Auto)
+            .find(|&backend| capabilities.supports(backend));
+        Self {
+            capabilities,
+            preferred,
+            active,
+        }
+    }
+
+    /// Explanation to show when the preferred backend could not be used.
+    pub fn notice(&self) -> Option<String> {
+        match self.active {
+            None => Some(format!(
+                "This browser offers no WebGL context (available: {}), so nothing can be drawn.",
+                self.capabilities.summary()
+            )),
+            Some(active) if !self.capabilities.supports(self.preferred) => Some(format!(
+                "{} is unavailable in this browser; rendering with {} instead.",
+                self.preferred.label(),
+                active.label()
+            )),
+            Some(Backend::WebGl1) if self.preferred == Backend::Auto => {
+                Some("WebGL2 is unavailable in this browser; rendering with WebGL1.".to_string())
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+/// Probe the browser for the graphics APIs it offers.
+#[cfg(target_arch = "wasm32")]
+pub fn detect() -> Capabilities {
+    let Some(window) = window() else {
+        return Capabilities::default();
+    };
+    // Each probe gets its own canvas, since a canvas keeps the first context it hands out.
+    let context = |kind: &str| {
+        window
+            .document()
+            .and_then(|document| document.create_element("canvas").ok())
+            .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok())
+            .and_then(|canvas| canvas.get_context(kind).ok().flatten())
+            .is_some()
+    };
+    Capabilities {
+        webgl2: context("webgl2"),
+        webgl1: context("webgl"),
+    }
+}
+
+/// The backend saved by an earlier [`switch_to`], if any.
+#[cfg(target_arch = "wasm32")]
+pub fn stored_preference() -> Option<Backend> {
+    let storage = window()?.local_storage().ok()??;
+    Backend::from_key(&storage.get_item(STORAGE_KEY).ok()??)
+}
+
+/// Save `backend` as the preference and reload the page to start with it.
+#[cfg(target_arch = "wasm32")]
+pub fn switch_to(backend: Backend) {
+    let Some(window) = window() else {
+        return;
+    };
+    if let Ok(Some(storage)) = window.local_storage() {
+        storage.set_item(STORAGE_KEY, backend.key()).ok();
+    }
+    window.location().reload().ok();
+}
+
+/// Native builds have a single renderer, so there is nothing to switch.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn switch_to(_backend: Backend) {}
+
+/// Bar under the menu showing the renderer in use, any fallback notice, and a picker for
+/// the preferred backend. Closing it sets `open` to false.
+pub fn show_bar(ctx: &egui::Context, status: &RendererStatus, open: &mut bool) {
+    let palette = theme::palette(ctx);
+    egui::TopBottomPanel::top("renderer_bar").show(ctx, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            if let Some(notice) = status.notice() {
+                ui.label(
+                    egui::RichText::new(notice)
+                        .size(theme::font_size::INFO)
+                        .color(palette.text_error),
+                );
+            }
+            ui.label(
+                egui::RichText::new(format!(
+                    "Browser supports: {}",
+                    status.capabilities.summary()
+                ))
+                .size(theme::font_size::INFO)
+                .color(palette.text_dim),
+            );
+            let mut preferred = status.preferred;
+            egui::ComboBox::from_label("Renderer")
+                .selected_text(preferred.label())
+                .show_ui(ui, |ui| {
+                    for backend in Backend::ALL {
+                        let enabled = status.capabilities.supports(backend);
+                        ui.add_enabled_ui(enabled, |ui| {
+                            ui.selectable_value(&mut preferred, backend, backend.label());
+                        });
+                    }
+                });
+            if preferred != status.preferred {
+                switch_to(preferred);
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("✕").on_hover_text("Hide").clicked() {
+                    *open = false;
+                }
+            });
+        });
+    });
+}
@@ -1,7 +1,31 @@
+use std::{array, mem};
+
 use spacecurve::{curve_from_name, registry};
 
-/// Shared cache and selection state for 2D/3D curve panes.
+use crate::{
+    HoverPoint,
+    colormap::{Classifier, ColorBy},
+    geometry::{CacheStats, GeometryCache},
+    theme,
+    worker::{self, PointJob, Points, Request},
+};
+
+/// Marker for grid cells without a cached point in the inverse lookup.
+const NO_POSITION: u32 = u32::MAX;
+
+/// A transition from the previously selected curve to the current one.
+///
+/// Curve index `i` travels from `from[i]` to the current curve's point `i`, so the same
+/// indices can be followed between curves of the same size.
 #[derive(Clone)]
+struct Morph<const D: usize> {
+    /// Points of the curve switched away from.
+    from: Vec<[u32; D]>,
+    /// Seconds of the transition played so far.
+    elapsed: f32,
+}
+
+/// Shared cache and selection state for 2D/3D curve panes.
 pub struct CurveSelection<const D: usize> {
     /// The selected curve name.
     pub name: String,
@@ -11,14 +35,31 @@ pub struct CurveSelection<const D: usize> {
     pub snake_offset: f32,
     /// Whether the info pane for this curve is open.
     pub info_open: bool,
+    /// Fixed value of an extra trailing axis.
+    ///
+    /// When set, the selection shows the `D`-dimensional slice of a `D + 1`-dimensional
+    /// curve, keeping the points of that slice in curve order.
+    pub slice: Option<u32>,
     /// Cache key: last curve name used to generate `cached_points`.
     cached_name: String,
     /// Cache key: last grid size used to generate `cached_points`.
     cached_size: u32,
+    /// Cache key: last slice used to generate `cached_points`.
+    cached_slice: Option<u32>,
     /// Cached integer points for the currently selected curve and size.
     cached_points: Vec<[u32; D]>,
+    /// Curve index of each entry in `cached_points`.
+    cached_indices: Vec<u32>,
+    /// Lazily built inverse of `cached_points`, mapping row-major grid cells to positions.
+    cached_positions: Vec<u32>,
     /// Cached curve length for the currently selected curve and size.
     cached_length: Option<u32>,
+    /// Tessellated geometry built from `cached_points`.
+    geometry: GeometryCache,
+    /// Transition from the last curve, set when only the curve name changed.
+    morph: Option<Morph<D>>,
+    /// Background generation of the points for the current cache keys.
+    job: Option<PointJob<D>>,
 }
 
 impl<const D: usize> Default for CurveSelection<D> {
@@ -40,72 +81,307 @@ impl<const D: usize> CurveSelection<D> {
             size: if D == 2 { 64 } else { 8 },
             snake_offset: 0.0,
             info_open: false,
+            slice: None,
             cached_name: String::new(),
             cached_size: 0,
+            cached_slice: None,
             cached_points: Vec::new(),
+            cached_indices: Vec::new(),
+            cached_positions: Vec::new(),
             cached_length: None,
+            geometry: GeometryCache::default(),
+            morph: None,
+            job: None,
         }
     }
 
-    /// Reset cached data when the selected curve or size changes.
+    /// Whether the cache keys no longer match the current selection.
+    fn cache_stale(&self) -> bool {
+        self.cached_name != self.name
+            || self.cached_size != self.size
+            || self.cached_slice != self.slice
+    }
+
+    /// Reset cached data when the selected curve, size or slice changes.
+    ///
+    /// The cache keys move to the new selection straight away, and any generation still
+    /// running for the old one is cancelled.
     fn invalidate_if_changed(&mut self) {
-        if self.cached_name != self.name || self.cached_size != self.size {
-            self.cached_points.clear();
+        if self.cache_stale() {
+            // Switching curves on the same grid can morph; any other change just snaps.
+            let same_grid = self.cached_size == self.size && self.cached_slice == self.slice;
+            let from = mem::take(&mut self.cached_points);
+            if !from.is_empty() {
+                self.morph = same_grid.then_some(Morph { from, elapsed: 0.0 });
+            } else if !same_grid {
+                self.morph = None;
+            }
+            self.cached_indices.clear();
+            self.cached_positions.clear();
             self.cached_length = None;
+            self.geometry.clear();
+            self.job = None;
+            self.cached_name = self.name.clone();
+            self.cached_size = self.size;
+            self.cached_slice = self.slice;
+        }
+    }
+
+    /// The points request for the current selection.
+    fn request(&self) -> Request {
+        Request {
+            name: self.name.clone(),
+            size: self.size,
+            slice: self.slice,
         }
     }
 
+    /// Number of curve dimensions backing the current selection.
+    pub fn curve_dimensions(&self) -> u32 {
+        D as u32 + u32::from(self.slice.is_some())
+    }
+
+    /// Side length of the sub-blocks at recursion `level` for power‑of‑two curves.
+    ///
+    /// Levels beyond the curve's order are clamped to single cells; curves that are not
+    /// built on a power‑of‑two grid return `None`.
+    pub fn block_side(&self, level: u32) -> Option<u32> {
+        let entry = registry::find(&self.name)?;
+        let spec = (entry.build_spec)(self.curve_dimensions(), self.size).ok()?;
+        spec.block_side(level.min(spec.order()?))
+    }
+
+    /// Shell of `coords`: its distance from the nearest face of the grid.
+    pub fn shell(&self, coords: &[u32]) -> u32 {
+        let max = self.size.saturating_sub(1);
+        coords
+            .iter()
+            .map(|&c| c.min(max.saturating_sub(c)))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// How points are grouped for `color_by`, with blocks taken at recursion `level`.
+    ///
+    /// Returns `None` when points are colored by index, or by block on a curve that is not
+    /// built on a power‑of‑two grid.
+    pub fn classifier(&self, color_by: ColorBy, level: u32) -> Option<Classifier> {
+        match color_by {
+            ColorBy::Index => None,
+            ColorBy::Shell => Some(Classifier::Shell {
+                size: self.size,
+                slice: self.slice,
+            }),
+            ColorBy::Block => self
+                .block_side(level)
+                .map(|side| Classifier::Block { side }),
+        }
+    }
+
+    /// Child block entered at each recursion level on the way down to `coords`.
+    ///
+    /// Each digit packs one bit per axis (x lowest), naming quadrants 0–3 in 2D and
+    /// octants 0–7 in 3D. Returns `None` for curves not built on a power‑of‑two grid.
+    pub fn block_path(&self, coords: &[u32]) -> Option<Vec<u32>> {
+        let entry = registry::find(&self.name)?;
+        let spec = (entry.build_spec)(self.curve_dimensions(), self.size).ok()?;
+        (1..=spec.order()?)
+            .map(|level| {
+                let block = spec.block_of(coords, level)?;
+                Some(
+                    block
+                        .iter()
+                        .enumerate()
+                        .map(|(axis, b)| (b & 1) << axis)
+                        .sum(),
+                )
+            })
+            .collect()
+    }
+
+    /// Whether every step of the curve moves to a cell judged `adjacent` to the previous one.
+    ///
+    /// Returns `None` for slices, whose points skip the cells outside the slice.
+    pub fn is_continuous(
+        &mut self,
+        adjacent: impl Fn(&[u32; D], &[u32; D]) -> bool,
+    ) -> Option<bool> {
+        if self.slice.is_some() {
+            return None;
+        }
+        self.ensure_cached_points()?;
+        let adjacency = self.geometry.adjacency(&self.cached_points, adjacent);
+        Some(adjacency.iter().all(|&a| a))
+    }
+
     /// Ensure the cached curve length is available for the current selection.
     pub fn ensure_curve_length(&mut self) -> Option<u32> {
         self.invalidate_if_changed();
         if let Some(len) = self.cached_length {
             return Some(len);
         }
-        if !self.cached_points.is_empty() {
-            let len = self.cached_points.len() as u32;
-            self.cached_length = Some(len);
-            return Some(len);
-        }
-        match curve_from_name(&self.name, D as u32, self.size) {
-            Ok(pattern) => {
-                let len = pattern.length();
-                self.cached_length = Some(len);
-                self.cached_name = self.name.clone();
-                self.cached_size = self.size;
-                Some(len)
+        if self.slice.is_some() {
+            // A slice's length is only known once its points are filtered, so it stays
+            // unknown while they are generated in the background.
+            if self.job.is_some() {
+                return None;
             }
-            Err(_) => None,
+            return self.ensure_cached_points().map(|pts| pts.len() as u32);
         }
+        let len = curve_from_name(&self.name, D as u32, self.size)
+            .ok()?
+            .length();
+        self.cached_length = Some(len);
+        Some(len)
+    }
+
+    /// Store freshly generated points for the current cache keys.
+    fn install(&mut self, (pts, indices): Points<D>) {
+        self.cached_length = Some(pts.len() as u32);
+        self.cached_points = pts;
+        self.cached_indices = indices;
+        self.cached_positions.clear();
+        self.geometry.clear();
     }
 
     /// Ensure the cached points are computed for the current name and size.
     /// Returns a slice of cached points if successful.
+    ///
+    /// This blocks until the points are ready, waiting on any background generation.
     pub fn ensure_cached_points(&mut self) -> Option<&[[u32; D]]> {
         self.invalidate_if_changed();
-        if self.cached_name != self.name
-            || self.cached_size != self.size
-            || self.cached_points.is_empty()
-        {
-            if let Ok(pattern) = curve_from_name(&self.name, D as u32, self.size) {
-                let mut pts = Vec::with_capacity(pattern.length() as usize);
-                for i in 0..pattern.length() {
-                    let p = pattern.point(i);
-                    let mut arr = [0u32; D];
-                    for d in 0..D {
-                        arr[d] = p[d];
-                    }
-                    pts.push(arr);
+        if self.cached_points.is_empty() {
+            let points = match self.job.take() {
+                Some(job) => job.wait(),
+                None => worker::generate(&self.request()),
+            }?;
+            self.install(points);
+        }
+        Some(&self.cached_points)
+    }
+
+    /// Like [`Self::ensure_cached_points`], but never blocks on a large curve.
+    ///
+    /// Curves of at least [`theme::worker::BACKGROUND_MIN_POINTS`] points are generated in
+    /// the background, and `None` is returned until they are ready; [`Self::generating`]
+    /// tells this apart from a curve that can't be built.
+    pub fn poll_cached_points(&mut self, ctx: &egui::Context) -> Option<&[[u32; D]]> {
+        self.invalidate_if_changed();
+        if self.cached_points.is_empty() {
+            if self.job.is_none() {
+                let length = curve_from_name(&self.name, self.curve_dimensions(), self.size)
+                    .ok()?
+                    .length();
+                if length < theme::worker::BACKGROUND_MIN_POINTS {
+                    return self.ensure_cached_points();
                 }
-                self.cached_points = pts;
-                self.cached_name = self.name.clone();
-                self.cached_size = self.size;
-                self.cached_length = Some(pattern.length());
-            } else {
-                return None;
+                self.job = PointJob::spawn(self.request(), ctx);
             }
+            let points = self.job.as_mut()?.poll()?;
+            self.job = None;
+            self.install(points);
         }
         Some(&self.cached_points)
     }
+
+    /// Block until points being generated in the background are ready.
+    pub fn finish_generation(&mut self) {
+        if self.job.is_some() {
+            self.ensure_cached_points();
+        }
+    }
+
+    /// Fraction generated of points being built in the background, if any are.
+    pub fn generating(&self) -> Option<f32> {
+        self.job.as_ref().map(PointJob::progress)
+    }
+
+    /// Number of points currently cached, without generating any.
+    pub fn cached_len(&self) -> usize {
+        self.cached_points.len()
+    }
+
+    /// Usage counters of the geometry cache.
+    pub fn geometry_stats(&self) -> CacheStats {
+        self.geometry.stats()
+    }
+
+    /// Like [`Self::poll_cached_points`], also lending the geometry cache for those points.
+    pub fn cached_points_with_geometry(
+        &mut self,
+        ctx: &egui::Context,
+    ) -> Option<(&[[u32; D]], &mut GeometryCache)> {
+        self.poll_cached_points(ctx)?;
+        Some((&self.cached_points, &mut self.geometry))
+    }
+
+    /// Advance the transition from the previous curve by `delta` seconds.
+    ///
+    /// Returns each current point's interpolated position while the transition plays, or
+    /// `None` once it has finished (or when `snap` asks to skip it) and while the new points
+    /// are still being generated.
+    pub fn advance_morph(
+        &mut self,
+        ctx: &egui::Context,
+        delta: f32,
+        duration: f32,
+        snap: bool,
+    ) -> Option<Vec<[f32; D]>> {
+        // The transition waits for the new points rather than playing towards nothing.
+        self.poll_cached_points(ctx)?;
+        let morph = self.morph.as_mut()?;
+        morph.elapsed += delta;
+        let t = morph.elapsed / duration;
+        if snap || t >= 1.0 || morph.from.len() != self.cached_points.len() {
+            self.morph = None;
+            return None;
+        }
+        // Smoothstep easing, so points start and settle gently.
+        let eased = t * t * (3.0 - 2.0 * t);
+        Some(
+            morph
+                .from
+                .iter()
+                .zip(&self.cached_points)
+                .map(|(from, to)| {
+                    array::from_fn(|axis| {
+                        let (a, b) = (from[axis] as f32, to[axis] as f32);
+                        a + (b - a) * eased
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Position within the cached points of the point occupying `cell`, if any.
+    pub fn position_of(&mut self, cell: [u32; D]) -> Option<usize> {
+        if cell.iter().any(|&c| c >= self.size) {
+            return None;
+        }
+        self.ensure_cached_points()?;
+        let side = self.size as usize;
+        let linear = |p: &[u32; D]| p.iter().rev().fold(0, |acc, &c| acc * side + c as usize);
+        if self.cached_positions.is_empty() {
+            self.cached_positions = vec![NO_POSITION; side.pow(D as u32)];
+            for (position, p) in self.cached_points.iter().enumerate() {
+                self.cached_positions[linear(p)] = position as u32;
+            }
+        }
+        let position = self.cached_positions[linear(&cell)];
+        (position != NO_POSITION).then_some(position as usize)
+    }
+
+    /// Describe the cached point at `position` for the hover inspector.
+    pub fn hover_point(&self, position: usize) -> Option<HoverPoint> {
+        let point = self.cached_points.get(position)?;
+        let mut coords = point.to_vec();
+        coords.extend(self.slice);
+        Some(HoverPoint {
+            index: self.cached_indices[position],
+            position,
+            coords,
+        })
+    }
 }
 
 /// 2D selection state.
@@ -1,3 +1,7 @@
+use egui::{Color32, epaint::CircleShape};
+
+use crate::theme::{self, Palette};
+
 // --- Adjacency helpers ---
 
 /// Check if two 2D points are adjacent (Manhattan distance <= 1).
@@ -17,7 +21,7 @@ pub fn is_adjacent_3d(a: &[u32; 3], b: &[u32; 3]) -> bool {
     dx + dy + dz <= 1
 }
 
-/// Advance the snake offset by `increment`, wrapping at `curve_length`.
+/// Advance the snake offset by `increment`, wrapping at `curve_length` in either direction.
 ///
 /// Returns the new offset value. If `curve_length` is zero or None, returns 0.0.
 pub fn advance_snake_offset(offset: f32, increment: f32, curve_length: Option<u32>) -> f32 {
@@ -29,13 +33,22 @@ pub fn advance_snake_offset(offset: f32, increment: f32, curve_length: Option<u3
         return 0.0;
     }
     let new_offset = offset + increment;
-    if new_offset >= len_f {
+    if new_offset >= len_f || new_offset < 0.0 {
         new_offset.rem_euclid(len_f)
     } else {
         new_offset
     }
 }
 
+/// Offset of the race snake, `gap_percent` of the curve ahead of `offset`.
+pub fn race_offset(offset: f32, gap_percent: f32, curve_length: u32) -> f32 {
+    if curve_length == 0 {
+        return 0.0;
+    }
+    let len = curve_length as f32;
+    (offset + gap_percent / 100.0 * len).rem_euclid(len)
+}
+
 /// Calculate which segments the snake should occupy given an offset and length percentage.
 pub fn calculate_snake_segments(
     snake_offset: f32,
@@ -52,6 +65,17 @@ pub fn calculate_snake_segments(
     segments
 }
 
+/// Buffers reused across frames while building one canvas's snake overlay.
+#[derive(Debug, Clone, Default)]
+pub struct SnakeBuffers {
+    /// Indices of the points occupied by the snake.
+    pub segments: Vec<usize>,
+    /// Membership mask over curve points for neighbour lookups.
+    pub mask: Vec<bool>,
+    /// Inclusion mask for snake segments that survive visibility rules (3D only).
+    pub included: Vec<bool>,
+}
+
 /// Fill a preallocated buffer with the indices occupied by the snake overlay.
 pub fn fill_snake_segments(
     out: &mut Vec<usize>,
@@ -105,3 +129,131 @@ pub fn snake_membership_mask<'a>(
 pub fn snake_mask_contains(mask: &[bool], idx: usize) -> bool {
     mask.get(idx).copied().unwrap_or(false)
 }
+
+// --- Appearance ---
+
+/// Appearance of the snake overlay, shared by the 2D and 3D panes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnakeStyle {
+    /// Stroke width as a multiple of each pane's default snake width.
+    pub width: f32,
+    /// Mark the leading point with a dot.
+    pub head_marker: bool,
+    /// Blend from the curve color at the tail to the snake color at the head.
+    pub gradient: bool,
+    /// Fraction of the snake, measured from the tail, that fades out (0–1).
+    pub fade: f32,
+}
+
+impl Default for SnakeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            head_marker: false,
+            gradient: false,
+            fade: 0.0,
+        }
+    }
+}
+
+impl SnakeStyle {
+    /// Whether the color varies along the snake.
+    fn is_graded(&self) -> bool {
+        self.gradient || self.fade > 0.0
+    }
+
+    /// Color at `t` along the snake (0 at the tail, 1 at the head), scaled by `brightness`.
+    pub fn color(&self, palette: &Palette, brightness: f32, t: f32) -> Color32 {
+        let head = palette.snake_with_brightness(brightness);
+        let color = if self.gradient {
+            palette.curve_opaque(brightness).lerp_to_gamma(head, t)
+        } else {
+            head
+        };
+        if t < self.fade {
+            color.gamma_multiply(t / self.fade)
+        } else {
+            color
+        }
+    }
+}
+
+/// Positions of curve indices along the current snake, used to grade its color.
+pub struct SnakeShading {
+    /// Appearance settings.
+    pub style: SnakeStyle,
+    /// Index of the tail point.
+    start: usize,
+    /// Number of points covered by the snake.
+    length: usize,
+    /// Number of points on the curve.
+    total: usize,
+}
+
+impl SnakeShading {
+    /// Shading for a snake covering `segments` (tail first) on a curve of `total` points.
+    pub fn new(style: SnakeStyle, segments: &[usize], total: usize) -> Self {
+        Self {
+            style,
+            start: segments.first().copied().unwrap_or(0),
+            length: segments.len(),
+            total,
+        }
+    }
+
+    /// Index of the leading point, if the snake is not empty.
+    pub fn head(&self) -> Option<usize> {
+        (self.length > 0 && self.total > 0).then(|| (self.start + self.length - 1) % self.total)
+    }
+
+    /// Fractional position of `idx` from the tail (0) to the head (1).
+    pub fn t(&self, idx: usize) -> f32 {
+        if self.length <= 1 || self.total == 0 {
+            return 1.0;
+        }
+        let steps = (idx + self.total - self.start % self.total) % self.total;
+        (steps as f32 / (self.length - 1) as f32).min(1.0)
+    }
+
+    /// Color band of `idx`; an ungraded snake is a single band.
+    pub fn band(&self, idx: usize) -> usize {
+        if self.style.is_graded() {
+            (self.t(idx) * (theme::snake_style::COLOR_BANDS - 1) as f32).round() as usize
+        } else {
+            0
+        }
+    }
+
+    /// Color of the point at `idx`, scaled by `brightness`.
+    pub fn color(&self, palette: &Palette, brightness: f32, idx: usize) -> Color32 {
+        self.style.color(palette, brightness, self.t(idx))
+    }
+
+    /// Split a run of curve indices into single-band pieces.
+    ///
+    /// Consecutive pieces share their boundary point so the run stays visually connected.
+    pub fn split_run<'a>(&self, run: &'a [usize]) -> Vec<&'a [usize]> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        for end in 1..run.len() {
+            if self.band(run[end]) != self.band(run[start]) {
+                pieces.push(&run[start..=end]);
+                start = end;
+            }
+        }
+        if start + 1 < run.len() || pieces.is_empty() {
+            pieces.push(&run[start..]);
+        }
+        pieces
+    }
+}
+
+/// Dot marking the snake head at `pos` for a snake stroked `width` wide.
+pub fn head_marker(palette: &Palette, pos: egui::Pos2, width: f32, brightness: f32) -> egui::Shape {
+    egui::Shape::Circle(CircleShape {
+        center: pos,
+        radius: width * theme::snake_style::HEAD_RADIUS_MULTIPLIER,
+        fill: palette.snake_with_brightness(brightness),
+        stroke: egui::Stroke::new(theme::snake_style::HEAD_OUTLINE_WIDTH, palette.text_primary),
+    })
+}
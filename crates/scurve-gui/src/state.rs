@@ -1,8 +1,195 @@
 //! State management for the GUI application.
 
-use crate::{
-    Pane, Selected3DCurve, SelectedCurve, SharedSettings, snake::advance_snake_offset, theme,
-};
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4};
+
+use egui::Vec2;
+
+use crate::{Pane, Selected3DCurve, SelectedCurve, SharedSettings, theme};
+
+/// Fixed viewpoints offered by the 3D camera menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraPreset {
+    /// Looking straight down the z axis.
+    Front,
+    /// Looking straight down the y axis.
+    Top,
+    /// Equal foreshortening of all three axes.
+    Isometric,
+}
+
+impl CameraPreset {
+    /// Every preset, in menu order.
+    pub const ALL: [Self; 3] = [Self::Front, Self::Top, Self::Isometric];
+
+    /// Menu label for this preset.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Front => "Front",
+            Self::Top => "Top",
+            Self::Isometric => "Isometric",
+        }
+    }
+
+    /// Yaw and tilt angles (radians) for this preset.
+    fn angles(self) -> (f32, f32) {
+        match self {
+            Self::Front => (0.0, 0.0),
+            Self::Top => (0.0, FRAC_PI_2),
+            Self::Isometric => (FRAC_PI_4, FRAC_1_SQRT_2.atan()),
+        }
+    }
+}
+
+/// Axis-aligned plane that hides the 3D geometry on one side of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ClipPlane {
+    /// Whether clipping is applied.
+    pub enabled: bool,
+    /// Grid axis the plane is perpendicular to (0 = x, 1 = y, 2 = z).
+    pub axis: usize,
+    /// Last grid coordinate on `axis` that stays visible.
+    pub position: u32,
+    /// Keep the cells from `position` upwards instead of up to it.
+    pub flip: bool,
+}
+
+impl ClipPlane {
+    /// Names of the axes the plane can be perpendicular to, in axis order.
+    pub const AXES: [&'static str; 3] = ["X", "Y", "Z"];
+
+    /// Whether the cell at `point` lies on the visible side of the plane.
+    pub fn keeps(&self, point: &[u32; 3]) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let c = point[self.axis.min(2)];
+        if self.flip {
+            c >= self.position
+        } else {
+            c <= self.position
+        }
+    }
+}
+
+/// Reference geometry drawn around the 3D curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Guides {
+    /// Labeled x, y and z axes from the grid's origin corner.
+    pub axes: bool,
+    /// Edges of the cube enclosing the grid.
+    pub bounds: bool,
+    /// Cell grids on the three planes meeting at the origin corner.
+    pub grid: bool,
+}
+
+/// Orientation and projection of the 3D view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// Rotation around the vertical axis, in radians; advanced by auto-rotation.
+    pub yaw: f32,
+    /// Downward tilt towards the viewer, in radians.
+    pub tilt: f32,
+    /// Use an orthographic projection instead of perspective.
+    pub orthographic: bool,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            yaw: 0.0,
+            tilt: theme::canvas_3d::CAMERA_TILT,
+            orthographic: false,
+        }
+    }
+}
+
+impl Camera {
+    /// Key under which the camera is persisted between sessions.
+    pub const STORAGE_KEY: &str = "camera_3d";
+
+    /// Point the camera from `preset`, keeping the projection mode.
+    pub fn apply(&mut self, preset: CameraPreset) {
+        (self.yaw, self.tilt) = preset.angles();
+    }
+
+    /// Serialize as `"<yaw> <tilt> <orthographic>"` for app storage.
+    pub fn to_storage(self) -> String {
+        format!("{} {} {}", self.yaw, self.tilt, self.orthographic)
+    }
+
+    /// Parse a value written by [`Self::to_storage`].
+    pub fn from_storage(value: &str) -> Option<Self> {
+        let mut parts = value.split_whitespace();
+        let camera = Self {
+            yaw: parts.next()?.parse().ok()?,
+            tilt: parts.next()?.parse().ok()?,
+            orthographic: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(camera)
+    }
+}
+
+/// Camera angles a drag in the 3D view may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AxisLock {
+    /// Horizontal drags turn the yaw and vertical drags the tilt.
+    #[default]
+    Free,
+    /// Only the yaw follows the drag.
+    Yaw,
+    /// Only the tilt follows the drag.
+    Tilt,
+}
+
+impl AxisLock {
+    /// Every lock, in menu order.
+    pub const ALL: [Self; 3] = [Self::Free, Self::Yaw, Self::Tilt];
+
+    /// Menu label for this lock.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Free => "Free",
+            Self::Yaw => "Yaw only",
+            Self::Tilt => "Tilt only",
+        }
+    }
+}
+
+/// How dragging in the 3D view turns the camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DragRotation {
+    /// Angles the drag may change.
+    pub lock: AxisLock,
+    /// Increment, in degrees, that dragged angles snap to; 0 turns snapping off.
+    pub snap_degrees: u32,
+}
+
+impl DragRotation {
+    /// Snap increments offered by the camera menu, in degrees.
+    pub const SNAP_STEPS: [u32; 3] = [0, 15, 45];
+
+    /// Point `camera` as a drag of `delta` points moves it from the angles `(yaw, tilt)` it
+    /// had when the drag started.
+    ///
+    /// Angles are worked out from the start of the drag rather than accumulated, so snapping
+    /// does not swallow small movements. The tilt stops at straight up and straight down.
+    pub fn apply(self, camera: &mut Camera, (yaw, tilt): (f32, f32), delta: Vec2) {
+        let snap = |angle: f32| match self.snap_degrees {
+            0 => angle,
+            step => {
+                let step = (step as f32).to_radians();
+                (angle / step).round() * step
+            }
+        };
+        let sensitivity = theme::canvas_3d::DRAG_SENSITIVITY;
+        if self.lock != AxisLock::Tilt {
+            camera.yaw = snap(yaw + delta.x * sensitivity);
+        }
+        if self.lock != AxisLock::Yaw {
+            camera.tilt = snap(tilt + delta.y * sensitivity).clamp(-FRAC_PI_2, FRAC_PI_2);
+        }
+    }
+}
 
 /// Logic controller for updating application state.
 pub struct AnimationController;
@@ -17,31 +204,33 @@ impl AnimationController {
         selected_3d_curve: &mut Selected3DCurve,
     ) {
         // Skip when paused or user is dragging in 3D view
-        if app_state.paused || app_state.mouse_dragging {
+        if app_state.transport.paused || app_state.mouse_dragging {
             return;
         }
 
-        app_state.animation_time += delta;
+        app_state.transport.elapsed += delta;
 
         // Convert 0-100 scale to actual rotation speed using base speed
         let actual_rotation_speed =
             theme::animation::BASE_ROTATION_SPEED * (shared_settings.spin_speed / 100.0);
-        app_state.rotation_angle += delta * actual_rotation_speed;
+        if !shared_settings.reduced_motion {
+            app_state.camera.yaw += delta * actual_rotation_speed;
+        }
 
-        // Update snake animation timing
-        app_state.snake_time += delta;
+        Self::advance_slice(delta, app_state, selected_3d_curve);
 
         // Snake animation speed from settings
         let snake_increment = delta * shared_settings.snake_speed;
 
-        // Update snake offsets for both 2D and 3D
+        // Update snake offsets for both 2D and 3D, within the loop region if one is set
         if shared_settings.snake_enabled {
-            selected_curve.snake_offset = advance_snake_offset(
+            let transport = &app_state.transport;
+            selected_curve.snake_offset = transport.advance(
                 selected_curve.snake_offset,
                 snake_increment,
                 selected_curve.ensure_curve_length(),
             );
-            selected_3d_curve.snake_offset = advance_snake_offset(
+            selected_3d_curve.snake_offset = transport.advance(
                 selected_3d_curve.snake_offset,
                 snake_increment,
                 selected_3d_curve.ensure_curve_length(),
@@ -49,6 +238,19 @@ impl AnimationController {
         }
     }
 
+    /// Step the 4D slice of the 3D view when slice animation is enabled.
+    fn advance_slice(
+        delta: f32,
+        app_state: &mut crate::AppState,
+        selected_3d_curve: &mut Selected3DCurve,
+    ) {
+        let Some(slice) = selected_3d_curve.slice.as_mut() else {
+            return;
+        };
+        let steps = app_state.transport.slice_steps(delta);
+        *slice = (*slice + steps) % selected_3d_curve.size.max(1);
+    }
+
     /// Synchronize selection between 2D and 3D panes.
     ///
     /// Propagates the selection from the active pane to the inactive pane,
@@ -78,6 +280,8 @@ impl AnimationController {
                     }
                 }
             }
+            // The file pane keeps its own curve so file layouts survive browsing.
+            Pane::File => {}
         }
     }
 }
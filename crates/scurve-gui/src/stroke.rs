@@ -0,0 +1,216 @@
+//! Anti-aliased polyline strokes with miter or round joins for the 2D pane.
+//!
+//! Each polyline becomes one continuous triangle strip whose edges fade out over one
+//! physical pixel. Segments never overlap, so translucent curves don't darken where they
+//! meet, and per-point colors blend smoothly along the line.
+
+use egui::{Color32, Mesh, Pos2, Vec2, emath::Rot2};
+
+use crate::theme;
+
+/// How two segments of a stroke meet at a corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineJoin {
+    /// Extend both edges to a sharp point, beveled beyond the miter limit.
+    #[default]
+    Miter,
+    /// Fill the outside of the corner with a circular arc.
+    Round,
+}
+
+impl LineJoin {
+    /// Every join, in menu order.
+    pub const ALL: [Self; 2] = [Self::Miter, Self::Round];
+
+    /// Menu label for this join.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Miter => "Miter",
+            Self::Round => "Round",
+        }
+    }
+
+    /// Value of the SVG `stroke-linejoin` attribute drawing the same join.
+    pub fn svg_name(self) -> &'static str {
+        match self {
+            Self::Miter => "miter",
+            Self::Round => "round",
+        }
+    }
+}
+
+/// Width and joins of the 2D curve strokes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineStyle {
+    /// Stroke width in points.
+    pub width: f32,
+    /// Corner style.
+    pub join: LineJoin,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self {
+            width: theme::canvas_2d::LINE_WIDTH,
+            join: LineJoin::Miter,
+        }
+    }
+}
+
+impl LineStyle {
+    /// This style with the width scaled by `factor`, e.g. for the snake overlay.
+    pub fn scaled(self, factor: f32) -> Self {
+        Self {
+            width: self.width * factor,
+            ..self
+        }
+    }
+
+    /// Bit pattern of the style for cache keys.
+    pub fn key(self) -> (u32, LineJoin) {
+        (self.width.to_bits(), self.join)
+    }
+}
+
+/// Offsets of one cross-section of the strip from its center point.
+///
+/// `left` and `right` point from the center to each edge, scaled so that multiplying by
+/// the half width lands on the edge (miter offsets are longer than unit length).
+struct Section {
+    /// Center of the cross-section.
+    center: Pos2,
+    /// Offset direction of the left edge.
+    left: Vec2,
+    /// Offset direction of the right edge.
+    right: Vec2,
+    /// Color of the line at this cross-section.
+    color: Color32,
+}
+
+/// Append `points` as an anti-aliased stroke to `mesh`.
+///
+/// `color` gives the color at each point, `feather` the width of the edge fade in points
+/// (one physical pixel). Lines thinner than the feather are drawn as faint hairlines.
+pub fn stroke_polyline(
+    mesh: &mut Mesh,
+    points: &[Pos2],
+    color: impl Fn(usize) -> Color32,
+    style: LineStyle,
+    feather: f32,
+) {
+    // Repeated points have no direction; keep the first of each run.
+    let kept: Vec<usize> = (0..points.len())
+        .filter(|&i| i == 0 || (points[i] - points[i - 1]).length_sq() > f32::EPSILON)
+        .collect();
+    if kept.len() < 2 {
+        return;
+    }
+    let direction = |k: usize| (points[kept[k + 1]] - points[kept[k]]).normalized();
+    let fade = (style.width / feather).min(1.0);
+
+    let mut sections = Vec::with_capacity(kept.len());
+    for (k, &i) in kept.iter().enumerate() {
+        let center = points[i];
+        let color = color(i).gamma_multiply(fade);
+        let straight = |normal: Vec2| Section {
+            center,
+            left: normal,
+            right: -normal,
+            color,
+        };
+        if k == 0 {
+            sections.push(straight(direction(0).rot90()));
+        } else if k == kept.len() - 1 {
+            sections.push(straight(direction(k - 1).rot90()));
+        } else {
+            join_sections(
+                &mut sections,
+                center,
+                [direction(k - 1).rot90(), direction(k).rot90()],
+                style.join,
+                color,
+            );
+        }
+    }
+
+    let core = (style.width - feather).max(0.0) * 0.5;
+    let outer = core + feather;
+    for (n, section) in sections.iter().enumerate() {
+        let base = mesh.vertices.len() as u32;
+        let Section {
+            center,
+            left,
+            right,
+            color,
+        } = *section;
+        mesh.colored_vertex(center + left * outer, Color32::TRANSPARENT);
+        mesh.colored_vertex(center + left * core, color);
+        mesh.colored_vertex(center + right * core, color);
+        mesh.colored_vertex(center + right * outer, Color32::TRANSPARENT);
+        if n > 0 {
+            // Quads for the left feather, the core and the right feather.
+            let previous = base - 4;
+            for lane in 0..3 {
+                let (a, b) = (previous + lane, base + lane);
+                mesh.add_triangle(a, a + 1, b);
+                mesh.add_triangle(a + 1, b + 1, b);
+            }
+        }
+    }
+}
+
+/// Push the cross-sections turning from segment normal `from` to `to`.
+///
+/// The inside of the turn stays on the miter point; the outside gets a miter, a bevel (when
+/// the miter would exceed the limit) or an arc.
+fn join_sections(
+    sections: &mut Vec<Section>,
+    center: Pos2,
+    [from, to]: [Vec2; 2],
+    join: LineJoin,
+    color: Color32,
+) {
+    let bisector = from + to;
+    // A full reversal has no miter; the inside collapses onto the center.
+    let miter = if bisector.length_sq() > f32::EPSILON {
+        let unit = bisector.normalized();
+        unit / unit.dot(from).max(1.0 / theme::canvas_2d::MITER_LIMIT)
+    } else {
+        Vec2::ZERO
+    };
+    let angle = (from.x * to.y - from.y * to.x).atan2(from.dot(to));
+    let within_limit = miter.length() < theme::canvas_2d::MITER_LIMIT - f32::EPSILON;
+    // Straight continuations need no join at all.
+    if angle.abs() <= f32::EPSILON || (join == LineJoin::Miter && within_limit) {
+        sections.push(Section {
+            center,
+            left: miter,
+            right: -miter,
+            color,
+        });
+        return;
+    }
+
+    let steps = match join {
+        LineJoin::Miter => 1,
+        LineJoin::Round => (angle.abs() / theme::canvas_2d::ROUND_JOIN_STEP)
+            .ceil()
+            .max(1.0) as usize,
+    };
+    for step in 0..=steps {
+        let outside = Rot2::from_angle(angle * step as f32 / steps as f32) * from;
+        // Normals point left of travel; a positive angle turns right, leaving the left edge
+        // outside the corner.
+        let (left, right) = if angle > 0.0 {
+            (outside, -miter)
+        } else {
+            (miter, -outside)
+        };
+        sections.push(Section {
+            center,
+            left,
+            right,
+            color,
+        });
+    }
+}
@@ -4,99 +4,500 @@
 //! spacing, and rendering parameters. Centralizing these values makes it easy
 //! to experiment with the visual presentation of the application.
 //!
-//! # Theme: "Neon Grid"
+//! # Palettes
 //!
-//! Cyberpunk-retro styling inspired by phosphor CRTs and neon signage.
-//! Electric cyan drives the curves and primary actions, while ultraviolet
-//! magenta highlights secondary affordances. Panels sit on an inky midnight
-//! background with subtle indigo strokes for a tech-noir vibe.
+//! Colors live in a [`Palette`]. The default "Neon Grid" palette is
+//! cyberpunk-retro styling inspired by phosphor CRTs and neon signage: electric
+//! cyan drives the curves and primary actions, while ultraviolet magenta
+//! highlights secondary affordances on an inky midnight background. Light
+//! "Paper" and "Projector" palettes suit embedding pages and demos, "High
+//! Contrast" favours legibility over style, and [`ThemeSettings`] layers
+//! per-element overrides on top of any palette.
 
 use egui::{Color32, FontData, FontDefinitions};
 
 // =============================================================================
-// COLORS - Neon Grid Theme
+// COLORS - Palettes
 // =============================================================================
 
-/// Inky midnight background that lets neon colors pop.
-pub const CANVAS_BACKGROUND: Color32 = Color32::from_rgb(0x06, 0x08, 0x14);
-
-/// Panel/UI background – a hair brighter than the canvas.
-pub const PANEL_BACKGROUND: Color32 = Color32::from_rgb(0x0b, 0x0f, 0x22);
-
-/// Primary/interactive color (electric cyan) used for curves and key controls.
-/// These values are scaled for depth effects.
-pub mod curve_color {
-    /// Red component.
-    pub const R: u8 = 0x1f;
-    /// Green component.
-    pub const G: u8 = 0xf2;
-    /// Blue component.
-    pub const B: u8 = 0xff;
+/// Every color used by the GUI, resolved for one palette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    /// Whether egui's dark visuals are used as the base for this palette.
+    pub dark: bool,
+    /// Whether the curve, snake and highlight colors stay distinct under deuteranopia,
+    /// protanopia and tritanopia.
+    pub colorblind_safe: bool,
+    /// Canvas background behind the curves.
+    pub canvas_background: Color32,
+    /// Panel/UI background.
+    pub panel_background: Color32,
+    /// Primary color used for curves and key controls; scaled for depth effects.
+    pub curve: Color32,
+    /// Snake/accent color for high-contrast highlights.
+    pub accent: Color32,
+    /// Color of highlighted long jumps between non-adjacent cells.
+    pub jump: Color32,
+    /// Color of the second snake in race mode.
+    pub rival: Color32,
+    /// Primary text color.
+    pub text_primary: Color32,
+    /// Secondary/muted text color.
+    pub text_secondary: Color32,
+    /// Body text color.
+    pub text_body: Color32,
+    /// Dim text color.
+    pub text_dim: Color32,
+    /// Heading color.
+    pub text_heading: Color32,
+    /// Link color.
+    pub text_link: Color32,
+    /// Error text color.
+    pub text_error: Color32,
+    /// Widget background color.
+    pub widget_background: Color32,
+    /// Widget background when hovered.
+    pub widget_hovered: Color32,
+    /// Widget background when active/pressed.
+    pub widget_active: Color32,
+    /// Toggle background for idle checkboxes.
+    pub toggle_bg: Color32,
+    /// Toggle background when checked.
+    pub toggle_bg_active: Color32,
+    /// Settings panel fill with higher opacity for legibility.
+    pub settings_panel_bg: Color32,
+    /// Selected/highlighted state fill.
+    pub selection: Color32,
+    /// Border/separator color.
+    pub border: Color32,
+    /// Slider track/rail background color.
+    pub slider_rail: Color32,
+    /// Slider filled/active portion color.
+    pub slider_fill: Color32,
+    /// Play button fill.
+    pub button_play: Color32,
+    /// Pause button fill.
+    pub button_pause: Color32,
 }
 
-/// Snake/accent color (ultraviolet magenta) for high-contrast highlights.
-pub mod accent_color {
-    /// Red component.
-    pub const R: u8 = 0xff;
-    /// Green component.
-    pub const G: u8 = 0x4d;
-    /// Blue component.
-    pub const B: u8 = 0xf6;
+/// "Neon Grid": electric cyan and ultraviolet magenta on an inky midnight background.
+pub const NEON_GRID: Palette = Palette {
+    dark: true,
+    colorblind_safe: false,
+    // Inky midnight that lets neon colors pop.
+    canvas_background: Color32::from_rgb(0x06, 0x08, 0x14),
+    // A hair brighter than the canvas.
+    panel_background: Color32::from_rgb(0x0b, 0x0f, 0x22),
+    // Electric cyan.
+    curve: Color32::from_rgb(0x1f, 0xf2, 0xff),
+    // Ultraviolet magenta.
+    accent: Color32::from_rgb(0xff, 0x4d, 0xf6),
+    // Sodium amber, distinct from both neons.
+    jump: Color32::from_rgb(0xff, 0xb0, 0x00),
+    // Acid green, the third neon.
+    rival: Color32::from_rgb(0x7d, 0xff, 0x5a),
+    // Crisp cool white.
+    text_primary: Color32::from_rgb(0xe6, 0xed, 0xff),
+    // Desaturated periwinkle.
+    text_secondary: Color32::from_rgb(0x9f, 0xb4, 0xe5),
+    // Softly cool white.
+    text_body: Color32::from_rgb(0xcb, 0xd7, 0xff),
+    // Muted dusk blue.
+    text_dim: Color32::from_rgb(0x69, 0x73, 0x92),
+    // Neon magenta.
+    text_heading: Color32::from_rgb(0xff, 0x5a, 0xf1),
+    // Sharp cyan.
+    text_link: Color32::from_rgb(0x55, 0xf0, 0xff),
+    // Warm coral.
+    text_error: Color32::from_rgb(0xff, 0x6b, 0x6b),
+    // Deep indigo.
+    widget_background: Color32::from_rgb(0x16, 0x14, 0x28),
+    // Brighter neon wash.
+    widget_hovered: Color32::from_rgb(0x23, 0x20, 0x38),
+    // Saturated ultraviolet.
+    widget_active: Color32::from_rgb(0x2f, 0x28, 0x4e),
+    toggle_bg: Color32::from_rgb(0x1c, 0x1a, 0x32),
+    toggle_bg_active: Color32::from_rgb(0x28, 0x22, 0x4b),
+    settings_panel_bg: Color32::from_rgba_premultiplied(0x16, 0x14, 0x28, 250),
+    // Muted magenta fill.
+    selection: Color32::from_rgb(0x25, 0x1e, 0x3a),
+    // Indigo stroke.
+    border: Color32::from_rgb(0x38, 0x35, 0x57),
+    slider_rail: Color32::from_rgb(0x2a, 0x27, 0x40),
+    // Cyan to mirror the curve color.
+    slider_fill: Color32::from_rgb(0x29, 0xf0, 0xff),
+    // Deep cyan.
+    button_play: Color32::from_rgb(0x0f, 0x6e, 0xa8),
+    // Ultraviolet violet for strong contrast.
+    button_pause: Color32::from_rgb(0x4a, 0x14, 0x63),
+};
+
+/// "Paper": ink blue and raspberry on warm off-white, for light embedding pages.
+pub const PAPER: Palette = Palette {
+    dark: false,
+    colorblind_safe: false,
+    canvas_background: Color32::from_rgb(0xfb, 0xfa, 0xf6),
+    panel_background: Color32::from_rgb(0xf0, 0xee, 0xe8),
+    curve: Color32::from_rgb(0x1f, 0x5f, 0xbf),
+    accent: Color32::from_rgb(0xd9, 0x36, 0x6b),
+    jump: Color32::from_rgb(0xe0, 0x7b, 0x00),
+    rival: Color32::from_rgb(0x2e, 0x8b, 0x57),
+    text_primary: Color32::from_rgb(0x1b, 0x1e, 0x28),
+    text_secondary: Color32::from_rgb(0x4a, 0x51, 0x68),
+    text_body: Color32::from_rgb(0x2b, 0x30, 0x40),
+    text_dim: Color32::from_rgb(0x8a, 0x8f, 0x9e),
+    text_heading: Color32::from_rgb(0xb0, 0x24, 0x6a),
+    text_link: Color32::from_rgb(0x1a, 0x6f, 0xb0),
+    text_error: Color32::from_rgb(0xc6, 0x28, 0x28),
+    widget_background: Color32::from_rgb(0xe4, 0xe1, 0xd8),
+    widget_hovered: Color32::from_rgb(0xd8, 0xd4, 0xc8),
+    widget_active: Color32::from_rgb(0xca, 0xc4, 0xb4),
+    toggle_bg: Color32::from_rgb(0xe6, 0xe3, 0xda),
+    toggle_bg_active: Color32::from_rgb(0xd9, 0xd0, 0xec),
+    settings_panel_bg: Color32::from_rgb(0xf0, 0xee, 0xe8),
+    selection: Color32::from_rgb(0xd9, 0xd0, 0xec),
+    border: Color32::from_rgb(0xbd, 0xb8, 0xaa),
+    slider_rail: Color32::from_rgb(0xd3, 0xcf, 0xc3),
+    slider_fill: Color32::from_rgb(0x1f, 0x5f, 0xbf),
+    button_play: Color32::from_rgb(0xc9, 0xdd, 0xf5),
+    button_pause: Color32::from_rgb(0xe8, 0xcd, 0xea),
+};
+
+/// "Projector": saturated blue and red on white, legible on washed-out projectors.
+pub const PROJECTOR: Palette = Palette {
+    dark: false,
+    colorblind_safe: false,
+    canvas_background: Color32::WHITE,
+    panel_background: Color32::from_rgb(0xf4, 0xf4, 0xf4),
+    curve: Color32::from_rgb(0x00, 0x33, 0xcc),
+    accent: Color32::from_rgb(0xe0, 0x00, 0x00),
+    jump: Color32::from_rgb(0xff, 0x8c, 0x00),
+    rival: Color32::from_rgb(0x00, 0x99, 0x33),
+    text_primary: Color32::BLACK,
+    text_secondary: Color32::from_rgb(0x22, 0x22, 0x22),
+    text_body: Color32::from_rgb(0x11, 0x11, 0x11),
+    text_dim: Color32::from_rgb(0x55, 0x55, 0x55),
+    text_heading: Color32::from_rgb(0x00, 0x33, 0xcc),
+    text_link: Color32::from_rgb(0x00, 0x33, 0xcc),
+    text_error: Color32::from_rgb(0xc0, 0x00, 0x00),
+    widget_background: Color32::from_rgb(0xe0, 0xe0, 0xe0),
+    widget_hovered: Color32::from_rgb(0xcc, 0xcc, 0xcc),
+    widget_active: Color32::from_rgb(0xb8, 0xb8, 0xb8),
+    toggle_bg: Color32::from_rgb(0xe0, 0xe0, 0xe0),
+    toggle_bg_active: Color32::from_rgb(0xc8, 0xd4, 0xff),
+    settings_panel_bg: Color32::from_rgb(0xf4, 0xf4, 0xf4),
+    selection: Color32::from_rgb(0xc8, 0xd4, 0xff),
+    border: Color32::from_rgb(0x66, 0x66, 0x66),
+    slider_rail: Color32::from_rgb(0xc8, 0xc8, 0xc8),
+    slider_fill: Color32::from_rgb(0x00, 0x33, 0xcc),
+    button_play: Color32::from_rgb(0xc8, 0xd4, 0xff),
+    button_pause: Color32::from_rgb(0xff, 0xd0, 0xd0),
+};
+
+/// "High Contrast": white, yellow and cyan on pure black, for low-vision use.
+pub const HIGH_CONTRAST: Palette = Palette {
+    dark: true,
+    colorblind_safe: false,
+    canvas_background: Color32::BLACK,
+    panel_background: Color32::BLACK,
+    curve: Color32::WHITE,
+    accent: Color32::from_rgb(0xff, 0xe0, 0x00),
+    jump: Color32::from_rgb(0x00, 0xe5, 0xff),
+    rival: Color32::from_rgb(0x33, 0xff, 0x66),
+    text_primary: Color32::WHITE,
+    text_secondary: Color32::from_rgb(0xf0, 0xf0, 0xf0),
+    text_body: Color32::WHITE,
+    text_dim: Color32::from_rgb(0xcc, 0xcc, 0xcc),
+    text_heading: Color32::from_rgb(0xff, 0xe0, 0x00),
+    text_link: Color32::from_rgb(0x00, 0xe5, 0xff),
+    text_error: Color32::from_rgb(0xff, 0x70, 0x70),
+    widget_background: Color32::from_rgb(0x1a, 0x1a, 0x1a),
+    widget_hovered: Color32::from_rgb(0x40, 0x40, 0x40),
+    widget_active: Color32::from_rgb(0x60, 0x60, 0x60),
+    toggle_bg: Color32::from_rgb(0x1a, 0x1a, 0x1a),
+    toggle_bg_active: Color32::from_rgb(0x60, 0x58, 0x00),
+    settings_panel_bg: Color32::BLACK,
+    selection: Color32::from_rgb(0x00, 0x4c, 0x99),
+    border: Color32::WHITE,
+    slider_rail: Color32::from_rgb(0x60, 0x60, 0x60),
+    slider_fill: Color32::from_rgb(0xff, 0xe0, 0x00),
+    button_play: Color32::from_rgb(0x00, 0x4c, 0x99),
+    button_pause: Color32::from_rgb(0x73, 0x00, 0x99),
+};
+
+/// "Colorblind Dark": Okabe–Ito sky blue, orange and yellow on near-black.
+///
+/// The Okabe–Ito hues stay distinguishable under red–green and blue–yellow deficiencies.
+pub const COLORBLIND_DARK: Palette = Palette {
+    dark: true,
+    colorblind_safe: true,
+    canvas_background: Color32::from_rgb(0x0b, 0x0d, 0x12),
+    panel_background: Color32::from_rgb(0x12, 0x15, 0x1c),
+    // Sky blue.
+    curve: Color32::from_rgb(0x56, 0xb4, 0xe9),
+    // Orange.
+    accent: Color32::from_rgb(0xe6, 0x9f, 0x00),
+    // Reddish purple.
+    jump: Color32::from_rgb(0xcc, 0x79, 0xa7),
+    // Yellow.
+    rival: Color32::from_rgb(0xf0, 0xe4, 0x42),
+    text_primary: Color32::from_rgb(0xee, 0xee, 0xee),
+    text_secondary: Color32::from_rgb(0xb4, 0xb8, 0xc2),
+    text_body: Color32::from_rgb(0xd8, 0xda, 0xe0),
+    text_dim: Color32::from_rgb(0x78, 0x7d, 0x8a),
+    text_heading: Color32::from_rgb(0xe6, 0x9f, 0x00),
+    text_link: Color32::from_rgb(0x56, 0xb4, 0xe9),
+    // Vermillion.
+    text_error: Color32::from_rgb(0xf0, 0x72, 0x28),
+    widget_background: Color32::from_rgb(0x1c, 0x20, 0x2a),
+    widget_hovered: Color32::from_rgb(0x28, 0x2d, 0x3a),
+    widget_active: Color32::from_rgb(0x34, 0x3b, 0x4c),
+    toggle_bg: Color32::from_rgb(0x1c, 0x20, 0x2a),
+    toggle_bg_active: Color32::from_rgb(0x1f, 0x3d, 0x55),
+    settings_panel_bg: Color32::from_rgb(0x12, 0x15, 0x1c),
+    selection: Color32::from_rgb(0x1f, 0x3d, 0x55),
+    border: Color32::from_rgb(0x4a, 0x50, 0x60),
+    slider_rail: Color32::from_rgb(0x2c, 0x31, 0x3d),
+    slider_fill: Color32::from_rgb(0x56, 0xb4, 0xe9),
+    button_play: Color32::from_rgb(0x00, 0x5a, 0x8c),
+    button_pause: Color32::from_rgb(0x7a, 0x52, 0x00),
+};
+
+/// "Colorblind Light": Okabe–Ito blue, vermillion and bluish green on white.
+pub const COLORBLIND_LIGHT: Palette = Palette {
+    dark: false,
+    colorblind_safe: true,
+    canvas_background: Color32::from_rgb(0xfa, 0xfa, 0xfa),
+    panel_background: Color32::from_rgb(0xee, 0xee, 0xee),
+    // Blue.
+    curve: Color32::from_rgb(0x00, 0x72, 0xb2),
+    // Vermillion.
+    accent: Color32::from_rgb(0xd5, 0x5e, 0x00),
+    // Reddish purple.
+    jump: Color32::from_rgb(0xcc, 0x79, 0xa7),
+    // Bluish green.
+    rival: Color32::from_rgb(0x00, 0x9e, 0x73),
+    text_primary: Color32::from_rgb(0x1a, 0x1a, 0x1a),
+    text_secondary: Color32::from_rgb(0x44, 0x44, 0x44),
+    text_body: Color32::from_rgb(0x2a, 0x2a, 0x2a),
+    text_dim: Color32::from_rgb(0x80, 0x80, 0x80),
+    text_heading: Color32::from_rgb(0x00, 0x72, 0xb2),
+    text_link: Color32::from_rgb(0x00, 0x72, 0xb2),
+    text_error: Color32::from_rgb(0xd5, 0x5e, 0x00),
+    widget_background: Color32::from_rgb(0xe0, 0xe0, 0xe0),
+    widget_hovered: Color32::from_rgb(0xd0, 0xd0, 0xd0),
+    widget_active: Color32::from_rgb(0xbe, 0xbe, 0xbe),
+    toggle_bg: Color32::from_rgb(0xe0, 0xe0, 0xe0),
+    toggle_bg_active: Color32::from_rgb(0xc6, 0xdd, 0xf0),
+    settings_panel_bg: Color32::from_rgb(0xee, 0xee, 0xee),
+    selection: Color32::from_rgb(0xc6, 0xdd, 0xf0),
+    border: Color32::from_rgb(0x8a, 0x8a, 0x8a),
+    slider_rail: Color32::from_rgb(0xcc, 0xcc, 0xcc),
+    slider_fill: Color32::from_rgb(0x00, 0x72, 0xb2),
+    button_play: Color32::from_rgb(0xc6, 0xdd, 0xf0),
+    button_pause: Color32::from_rgb(0xf5, 0xd9, 0xc2),
+};
+
+/// Named palettes offered in the settings panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PaletteName {
+    /// The dark [`NEON_GRID`] palette.
+    #[default]
+    NeonGrid,
+    /// The light [`PAPER`] palette.
+    Paper,
+    /// The high-contrast light [`PROJECTOR`] palette.
+    Projector,
+    /// The high-contrast dark [`HIGH_CONTRAST`] palette.
+    HighContrast,
+    /// The colorblind-safe dark [`COLORBLIND_DARK`] palette.
+    ColorblindDark,
+    /// The colorblind-safe light [`COLORBLIND_LIGHT`] palette.
+    ColorblindLight,
 }
 
-/// Primary text color - crisp cool white.
-pub const TEXT_PRIMARY: Color32 = Color32::from_rgb(0xe6, 0xed, 0xff);
-
-/// Secondary/muted text color - desaturated periwinkle.
-pub const TEXT_SECONDARY: Color32 = Color32::from_rgb(0x9f, 0xb4, 0xe5);
-
-/// Body text color - softly cool white.
-pub const TEXT_BODY: Color32 = Color32::from_rgb(0xcb, 0xd7, 0xff);
-
-/// Dim text color - muted dusk blue.
-pub const TEXT_DIM: Color32 = Color32::from_rgb(0x69, 0x73, 0x92);
-
-/// Heading color - neon magenta.
-pub const TEXT_HEADING: Color32 = Color32::from_rgb(0xff, 0x5a, 0xf1);
-
-/// Link color - sharp cyan.
-pub const TEXT_LINK: Color32 = Color32::from_rgb(0x55, 0xf0, 0xff);
-
-/// Widget background color - deep indigo.
-pub const WIDGET_BACKGROUND: Color32 = Color32::from_rgb(0x16, 0x14, 0x28);
-
-/// Widget background when hovered - brighter neon wash.
-pub const WIDGET_HOVERED: Color32 = Color32::from_rgb(0x23, 0x20, 0x38);
-
-/// Widget background when active/pressed - saturated ultraviolet.
-pub const WIDGET_ACTIVE: Color32 = Color32::from_rgb(0x2f, 0x28, 0x4e);
-
-/// Toggle background for idle checkboxes.
-pub const TOGGLE_BG: Color32 = Color32::from_rgb(0x1c, 0x1a, 0x32);
-
-/// Toggle background when checked.
-pub const TOGGLE_BG_ACTIVE: Color32 = Color32::from_rgb(0x28, 0x22, 0x4b);
-
-/// Settings panel fill with higher opacity for legibility.
-pub const SETTINGS_PANEL_BG: Color32 = Color32::from_rgba_premultiplied(0x16, 0x14, 0x28, 250);
-
-/// Selected/highlighted state - muted magenta fill.
-pub const SELECTION: Color32 = Color32::from_rgb(0x25, 0x1e, 0x3a);
+impl PaletteName {
+    /// Every palette, in menu order.
+    pub const ALL: [Self; 6] = [
+        Self::NeonGrid,
+        Self::Paper,
+        Self::Projector,
+        Self::HighContrast,
+        Self::ColorblindDark,
+        Self::ColorblindLight,
+    ];
+
+    /// Human-friendly name for menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::NeonGrid => "Neon Grid",
+            Self::Paper => "Paper",
+            Self::Projector => "Projector",
+            Self::HighContrast => "High Contrast",
+            Self::ColorblindDark => "Colorblind Dark",
+            Self::ColorblindLight => "Colorblind Light",
+        }
+    }
+
+    /// Short identifier used in storage and links.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::NeonGrid => "neon",
+            Self::Paper => "paper",
+            Self::Projector => "projector",
+            Self::HighContrast => "contrast",
+            Self::ColorblindDark => "cvd-dark",
+            Self::ColorblindLight => "cvd-light",
+        }
+    }
+
+    /// Look up a palette by [`Self::key`].
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|name| name.key() == key)
+    }
+
+    /// Colors of this palette.
+    pub fn palette(self) -> Palette {
+        match self {
+            Self::NeonGrid => NEON_GRID,
+            Self::Paper => PAPER,
+            Self::Projector => PROJECTOR,
+            Self::HighContrast => HIGH_CONTRAST,
+            Self::ColorblindDark => COLORBLIND_DARK,
+            Self::ColorblindLight => COLORBLIND_LIGHT,
+        }
+    }
+}
 
-/// Border/separator color - indigo stroke.
-pub const BORDER: Color32 = Color32::from_rgb(0x38, 0x35, 0x57);
+/// User theme choice: a named palette plus optional per-element overrides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ThemeSettings {
+    /// Base palette.
+    pub palette: PaletteName,
+    /// Replacement canvas background.
+    pub canvas_background: Option<Color32>,
+    /// Replacement curve color.
+    pub curve: Option<Color32>,
+    /// Replacement snake/accent color.
+    pub accent: Option<Color32>,
+}
 
-/// Slider track/rail background color.
-pub const SLIDER_RAIL: Color32 = Color32::from_rgb(0x2a, 0x27, 0x40);
+impl ThemeSettings {
+    /// Key under which the theme is persisted between sessions.
+    pub const STORAGE_KEY: &str = "theme";
+
+    /// Resolve the base palette with overrides applied.
+    pub fn palette(&self) -> Palette {
+        let mut palette = self.palette.palette();
+        if let Some(color) = self.canvas_background {
+            palette.canvas_background = color;
+        }
+        if let Some(color) = self.curve {
+            palette.curve = color;
+            palette.slider_fill = color;
+        }
+        if let Some(color) = self.accent {
+            palette.accent = color;
+        }
+        palette
+    }
+
+    /// Serialize as `"<palette> <background> <curve> <accent>"` for app storage.
+    ///
+    /// Overrides are written as `rrggbb` hex, or `-` when unset.
+    pub fn to_storage(self) -> String {
+        let color = |c: Option<Color32>| {
+            c.map_or_else(
+                || "-".to_string(),
+                |c| format!("{:02x}{:02x}{:02x}", c.r(), c.g(), c.b()),
+            )
+        };
+        format!(
+            "{} {} {} {}",
+            self.palette.key(),
+            color(self.canvas_background),
+            color(self.curve),
+            color(self.accent)
+        )
+    }
+
+    /// Parse a value written by [`Self::to_storage`].
+    pub fn from_storage(value: &str) -> Option<Self> {
+        let color = |part: &str| -> Option<Option<Color32>> {
+            if part == "-" {
+                return Some(None);
+            }
+            let rgb = u32::from_str_radix(part, 16)
+                .ok()
+                .filter(|_| part.len() == 6)?;
+            let [_, r, g, b] = rgb.to_be_bytes();
+            Some(Some(Color32::from_rgb(r, g, b)))
+        };
+        let mut parts = value.split_whitespace();
+        let theme = Self {
+            palette: PaletteName::from_key(parts.next()?)?,
+            canvas_background: color(parts.next()?)?,
+            curve: color(parts.next()?)?,
+            accent: color(parts.next()?)?,
+        };
+        parts.next().is_none().then_some(theme)
+    }
+}
 
-/// Slider filled/active portion color - cyan to mirror the curve color.
-pub const SLIDER_FILL: Color32 = Color32::from_rgb(0x29, 0xf0, 0xff);
+impl Palette {
+    /// Blend `color` towards the canvas background as `brightness` falls from 1 to 0.
+    fn shade(&self, color: Color32, brightness: f32) -> Color32 {
+        let bg = self.canvas_background;
+        let mix = |c: u8, b: u8| (f32::from(b) + (f32::from(c) - f32::from(b)) * brightness) as u8;
+        Color32::from_rgb(
+            mix(color.r(), bg.r()),
+            mix(color.g(), bg.g()),
+            mix(color.b(), bg.b()),
+        )
+    }
+
+    /// The primary curve color with brightness scaling and opacity.
+    #[inline]
+    pub fn curve_with_brightness(&self, brightness: f32, opacity: f32) -> Color32 {
+        let [r, g, b, _] = self.shade(self.curve, brightness).to_array();
+        Color32::from_rgba_unmultiplied(r, g, b, (255.0 * opacity) as u8)
+    }
+
+    /// The primary curve color with brightness scaling (opaque).
+    #[inline]
+    pub fn curve_opaque(&self, brightness: f32) -> Color32 {
+        self.shade(self.curve, brightness)
+    }
+
+    /// The snake/accent color scaled by brightness.
+    #[inline]
+    pub fn snake_with_brightness(&self, brightness: f32) -> Color32 {
+        self.shade(self.accent, brightness)
+    }
+
+    /// This palette with the rival color in the accent slot, for drawing the race snake.
+    pub fn rival_snake(&self) -> Self {
+        Self {
+            accent: self.rival,
+            ..*self
+        }
+    }
+
+    /// The highlighted long-jump color scaled by brightness.
+    #[inline]
+    pub fn jump_with_brightness(&self, brightness: f32) -> Color32 {
+        self.shade(self.jump, brightness)
+    }
+}
 
-/// Play button color - deep cyan fill that fits the neon grid palette.
-pub const BUTTON_PLAY: Color32 = Color32::from_rgb(0x0f, 0x6e, 0xa8);
+/// Memory key under which [`configure_visuals`] stores the active palette.
+const PALETTE_ID: &str = "theme_palette";
 
-/// Pause button color - ultraviolet violet fill for strong contrast.
-pub const BUTTON_PAUSE: Color32 = Color32::from_rgb(0x4a, 0x14, 0x63);
+/// The palette most recently applied with [`configure_visuals`].
+pub fn palette(ctx: &egui::Context) -> Palette {
+    ctx.data(|data| data.get_temp(egui::Id::new(PALETTE_ID)))
+        .unwrap_or(NEON_GRID)
+}
 
 /// Dimming overlay for modal backgrounds.
 pub const MODAL_DIM_ALPHA: u8 = 180;
@@ -183,6 +584,12 @@ pub mod control_bar {
 
     /// Horizontal padding for the control bar.
     pub const PADDING_HORIZONTAL: f32 = 8.0;
+
+    /// Width of the timeline track.
+    pub const SCRUBBER_WIDTH: f32 = 320.0;
+
+    /// Height of the timeline track.
+    pub const TIMELINE_HEIGHT: f32 = 16.0;
 }
 
 /// Window and dialog dimensions.
@@ -214,6 +621,12 @@ pub mod popup {
     /// Curve info pane width.
     pub const INFO_PANE_WIDTH: f32 = 320.0;
 
+    /// Width of the command palette.
+    pub const PALETTE_WIDTH: f32 = 360.0;
+
+    /// Maximum height of the command palette's result list.
+    pub const PALETTE_LIST_HEIGHT: f32 = 280.0;
+
     /// Corner radius for popup frames (small for technical look).
     pub const CORNER_RADIUS: u8 = 2;
 
@@ -242,6 +655,8 @@ pub mod shadow {
 
 /// 2D canvas rendering parameters.
 pub mod canvas_2d {
+    use std::f32::consts::PI;
+
     /// Margin inside the drawing rect.
     pub const MARGIN: f32 = 10.0;
 
@@ -251,11 +666,303 @@ pub mod canvas_2d {
     /// Minimum drawing area size.
     pub const MIN_SIZE: f32 = 200.0;
 
-    /// Line width for curve segments.
+    /// Default line width for curve segments.
     pub const LINE_WIDTH: f32 = 2.5;
 
+    /// Thinnest line width offered in the settings panel.
+    pub const MIN_LINE_WIDTH: f32 = 0.5;
+
+    /// Thickest line width offered in the settings panel.
+    pub const MAX_LINE_WIDTH: f32 = 12.0;
+
+    /// Longest miter, in half line widths, before a corner is beveled instead.
+    pub const MITER_LIMIT: f32 = 4.0;
+
+    /// Largest angle (radians) covered by one piece of a round join.
+    pub const ROUND_JOIN_STEP: f32 = PI / 8.0;
+
     /// Snake overlay width multiplier (relative to line width).
     pub const SNAKE_WIDTH_MULTIPLIER: f32 = 1.8;
+
+    /// Seconds the curve takes to morph into a newly selected curve of the same size.
+    pub const MORPH_SECONDS: f32 = 0.9;
+}
+
+/// Dashed style for highlighted long jumps.
+pub mod jumps {
+    /// Length (px) of each dash.
+    pub const DASH_LENGTH: f32 = 6.0;
+
+    /// Gap (px) between dashes.
+    pub const GAP_LENGTH: f32 = 4.0;
+}
+
+/// Snake appearance controls.
+pub mod snake_style {
+    /// Smallest snake width multiplier offered in the settings panel.
+    pub const MIN_WIDTH: f32 = 0.5;
+
+    /// Largest snake width multiplier offered in the settings panel.
+    pub const MAX_WIDTH: f32 = 3.0;
+
+    /// Head marker radius relative to the snake stroke width.
+    pub const HEAD_RADIUS_MULTIPLIER: f32 = 1.2;
+
+    /// Outline width (px) of the head marker.
+    pub const HEAD_OUTLINE_WIDTH: f32 = 1.5;
+
+    /// Number of uniformly colored pieces a graded snake is split into.
+    pub const COLOR_BANDS: usize = 24;
+}
+
+/// Quadrant/octant boundary overlay.
+pub mod blocks {
+    /// Deepest recursion level offered in the settings panel.
+    pub const MAX_LEVEL: u32 = 4;
+
+    /// Line width (px) of block boundaries.
+    pub const LINE_WIDTH: f32 = 1.0;
+}
+
+/// Axes, bounding box and reference grid of the 3D pane.
+pub mod guides {
+    use egui::Color32;
+
+    /// Colors of the x, y and z axes.
+    pub const AXIS_COLORS: [Color32; 3] = [
+        Color32::from_rgb(0xff, 0x5c, 0x5c),
+        Color32::from_rgb(0x5c, 0xe0, 0x6a),
+        Color32::from_rgb(0x5c, 0x8c, 0xff),
+    ];
+
+    /// Line width (px) of the axes.
+    pub const AXIS_WIDTH: f32 = 2.0;
+
+    /// Length of each axis relative to the side of the bounding box.
+    pub const AXIS_LENGTH: f32 = 1.1;
+
+    /// Distance beyond the end of each axis, in scene units, of its label.
+    pub const LABEL_GAP: f32 = 0.12;
+
+    /// Line width (px) of the bounding box.
+    pub const BOUNDS_WIDTH: f32 = 1.0;
+
+    /// Line width (px) of the reference grid.
+    pub const GRID_WIDTH: f32 = 0.5;
+
+    /// Opacity of the reference grid relative to the border color.
+    pub const GRID_OPACITY: f32 = 0.5;
+}
+
+/// Shaded tube rendering of the 3D curve.
+pub mod tube {
+    /// Initial tube radius, as a fraction of the distance between neighbouring cells.
+    pub const DEFAULT_RADIUS: f32 = 0.2;
+
+    /// Thinnest tube offered in the settings panel.
+    pub const MIN_RADIUS: f32 = 0.05;
+
+    /// Thickest tube offered in the settings panel; at 0.5 neighbouring tubes touch.
+    pub const MAX_RADIUS: f32 = 0.5;
+
+    /// Unit direction towards the light in view space: up, left and towards the viewer.
+    pub const LIGHT: [f32; 3] = [-0.4, -0.5, 0.768];
+
+    /// Brightness of surfaces facing away from the light.
+    pub const AMBIENT: f32 = 0.25;
+
+    /// Number of bands a segment is shaded in across its width.
+    pub const CROSS_SECTION_STEPS: u32 = 6;
+
+    /// Number of sides of each round joint.
+    pub const JOINT_SIDES: u32 = 16;
+
+    /// Number of shading rings of each round joint.
+    pub const JOINT_RINGS: u32 = 3;
+}
+
+/// Onion shell explode view.
+pub mod explode {
+    /// Largest spacing offered between neighbouring shells, in grid cells.
+    pub const MAX_SPACING: f32 = 2.0;
+}
+
+/// Limits for the free-form size inputs.
+pub mod size_limits {
+    /// Smallest accepted side length; a single cell has no segments to draw.
+    pub const MIN: u32 = 2;
+
+    /// Largest side length offered in the 2D pane.
+    pub const MAX_2D: u32 = 256;
+
+    /// Largest side length offered in the 3D pane (cubic growth keeps this small).
+    pub const MAX_3D: u32 = 32;
+
+    /// Largest side length offered in the file pane; one texel per cell keeps this cheap.
+    pub const MAX_FILE: u32 = 1024;
+
+    /// Initial side length of the file pane's curve.
+    pub const DEFAULT_FILE: u32 = 256;
+}
+
+/// Compute-shader curve generation.
+pub mod gpu {
+    /// Fewest points for which generation is handed to the GPU; smaller curves are quicker
+    /// to map on the CPU than to dispatch and read back.
+    pub const MIN_POINTS: u32 = 1 << 15;
+}
+
+/// Background generation of curve points.
+pub mod worker {
+    /// Fewest points for which generation moves off the UI thread; smaller curves are
+    /// mapped within the frame that needs them.
+    pub const BACKGROUND_MIN_POINTS: u32 = 1 << 14;
+
+    /// Indices mapped between cancellation checks, and per frame on the web.
+    pub const CHUNK_POINTS: u32 = 1 << 12;
+
+    /// Radius (px) of the spinner shown while points are generated.
+    pub const SPINNER_RADIUS: f32 = 14.0;
+
+    /// Line width (px) of the spinner arc.
+    pub const SPINNER_WIDTH: f32 = 3.0;
+
+    /// Spinner rotation speed in radians per second.
+    pub const SPINNER_SPEED: f32 = 5.0;
+
+    /// Line segments approximating the spinner arc.
+    pub const SPINNER_SEGMENTS: usize = 24;
+
+    /// Gap (px) between the spinner and its progress label.
+    pub const LABEL_GAP: f32 = 8.0;
+}
+
+/// Hex dump docked beside the file pane's image.
+pub mod hex_view {
+    /// Initial width (px) of the docked hex dump.
+    pub const DEFAULT_WIDTH: f32 = 520.0;
+
+    /// Font size of the hex dump.
+    pub const FONT_SIZE: f32 = 11.0;
+
+    /// Opacity of the accent behind bytes highlighted from the image.
+    pub const HIGHLIGHT_OPACITY: f32 = 0.4;
+
+    /// Opacity of the wash over image cells whose bytes are in view in the dump.
+    pub const VISIBLE_OPACITY: f32 = 0.2;
+
+    /// Opacity of the accent over image cells of selected bytes.
+    pub const SELECTION_OPACITY: f32 = 0.6;
+
+    /// Most image cells marked for one byte range, bounding the overlay mesh.
+    pub const MAX_MARKED_CELLS: usize = 1 << 16;
+}
+
+/// Bookmark markers on the curve and timeline.
+pub mod bookmarks {
+    use egui::Color32;
+
+    /// Colors given to new bookmarks in turn.
+    pub const COLORS: [Color32; 6] = [
+        Color32::from_rgb(230, 159, 0),
+        Color32::from_rgb(86, 180, 233),
+        Color32::from_rgb(0, 158, 115),
+        Color32::from_rgb(240, 228, 66),
+        Color32::from_rgb(204, 121, 167),
+        Color32::from_rgb(213, 94, 0),
+    ];
+
+    /// Stroke width (px) of marker rings and traced ranges.
+    pub const WIDTH: f32 = 2.5;
+
+    /// Radius (px) of the ring at a bookmark's start.
+    pub const RADIUS: f32 = 5.0;
+
+    /// Font size of marker labels.
+    pub const LABEL_SIZE: f32 = 11.0;
+
+    /// Width (px) of a single-index tick on the timeline.
+    pub const TICK_WIDTH: f32 = 2.0;
+}
+
+/// Imported point clouds drawn over the 2D pane.
+pub mod points {
+    /// Most rows read from one CSV file.
+    pub const MAX_POINTS: usize = 200_000;
+
+    /// Stroke width (px) of the path joining the points in curve order.
+    pub const PATH_WIDTH: f32 = 1.5;
+
+    /// Radius (px) of each point.
+    pub const RADIUS: f32 = 2.0;
+}
+
+/// Transient confirmation messages.
+pub mod toast {
+    /// Seconds a toast stays on screen.
+    pub const SECONDS: f64 = 2.5;
+
+    /// Gap (px) between a toast and the bottom of the window.
+    pub const BOTTOM_MARGIN: f32 = 24.0;
+}
+
+/// Developer debug panel.
+pub mod debug {
+    /// Initial width (px) of the panel.
+    pub const WIDTH: f32 = 360.0;
+
+    /// Height (px) beyond which the panel scrolls.
+    pub const MAX_HEIGHT: f32 = 480.0;
+
+    /// Events kept in the log.
+    pub const LOG_CAPACITY: usize = 200;
+}
+
+/// File pane byte-class colors, matching the CLI's `vis` images.
+pub mod byte_class {
+    use egui::Color32;
+
+    /// Zero bytes.
+    pub const ZERO: Color32 = Color32::BLACK;
+
+    /// `0xff` bytes.
+    pub const FULL: Color32 = Color32::WHITE;
+
+    /// Low ASCII control characters.
+    pub const CONTROL: Color32 = Color32::from_rgb(0x4d, 0xaf, 0x4a);
+
+    /// Printable ASCII.
+    pub const PRINTABLE: Color32 = Color32::from_rgb(0x10, 0x72, 0xb8);
+
+    /// Everything else: extended and unprintable bytes.
+    pub const OTHER: Color32 = Color32::from_rgb(0xe4, 0x1a, 0x1c);
+}
+
+/// Recording constants.
+pub mod recording {
+    /// Frames captured per second while recording.
+    pub const FPS: u16 = 15;
+
+    /// Largest frame side (px) kept in memory; bigger captures are downscaled.
+    pub const MAX_FRAME_SIDE: usize = 480;
+
+    /// Default recording length in seconds.
+    pub const DEFAULT_SECONDS: f32 = 5.0;
+
+    /// Longest recording offered in the UI, in seconds.
+    pub const MAX_SECONDS: f32 = 30.0;
+}
+
+/// Hover inspector constants.
+pub mod inspector {
+    /// Maximum screen distance (px) between the pointer and a picked 3D vertex.
+    pub const PICK_RADIUS: f32 = 8.0;
+
+    /// Radius (px) of the marker drawn on the hovered point.
+    pub const MARKER_RADIUS: f32 = 4.0;
+
+    /// Stroke width (px) of the hovered point marker.
+    pub const MARKER_STROKE: f32 = 1.5;
 }
 
 // =============================================================================
@@ -317,43 +1024,18 @@ pub mod animation {
     /// At this rate, a full 360° rotation takes approximately 18 seconds, which
     /// provides a comfortable viewing speed for examining 3D curve structure.
     pub const BASE_ROTATION_SPEED: f32 = 0.35;
+
+    /// Seconds spent on each 4D slice while animating through slices.
+    pub const SLICE_STEP_SECONDS: f32 = 0.6;
+
+    /// Duration of egui's widget and panel transitions (egui's default) unless motion is reduced.
+    pub const UI_TRANSITION_SECONDS: f32 = 6.0 / 60.0;
 }
 
 // =============================================================================
 // HELPER FUNCTIONS
 // =============================================================================
 
-/// Create the primary curve color with brightness scaling and opacity.
-#[inline]
-pub fn curve_color_with_brightness(brightness: f32, opacity: f32) -> Color32 {
-    Color32::from_rgba_unmultiplied(
-        (curve_color::R as f32 * brightness) as u8,
-        (curve_color::G as f32 * brightness) as u8,
-        (curve_color::B as f32 * brightness) as u8,
-        (255.0 * opacity) as u8,
-    )
-}
-
-/// Create the primary curve color with brightness scaling (opaque).
-#[inline]
-pub fn curve_color_opaque(brightness: f32) -> Color32 {
-    Color32::from_rgb(
-        (curve_color::R as f32 * brightness) as u8,
-        (curve_color::G as f32 * brightness) as u8,
-        (curve_color::B as f32 * brightness) as u8,
-    )
-}
-
-/// Create snake/accent color scaled by brightness.
-#[inline]
-pub fn snake_color_with_brightness(brightness: f32) -> Color32 {
-    Color32::from_rgb(
-        (accent_color::R as f32 * brightness) as u8,
-        (accent_color::G as f32 * brightness) as u8,
-        (accent_color::B as f32 * brightness) as u8,
-    )
-}
-
 /// Calculate brightness for regular curve segments (range: 0.3 to 1.0).
 ///
 /// Farther objects appear brighter to simulate depth-based atmosphere.
@@ -392,67 +1074,11 @@ pub fn normalize_depth(depth: f32) -> f32 {
 // EGUI VISUALS CONFIGURATION
 // =============================================================================
 
-/// Configure egui visuals with the terminal theme.
-pub fn configure_visuals(ctx: &egui::Context) {
-    use egui::{FontFamily, FontId, TextStyle, Visuals, epaint::Shadow};
-
-    let mut visuals = Visuals::dark();
-
-    // Window and panel backgrounds
-    visuals.window_fill = PANEL_BACKGROUND;
-    visuals.panel_fill = PANEL_BACKGROUND;
-    visuals.extreme_bg_color = CANVAS_BACKGROUND;
-    visuals.faint_bg_color = WIDGET_BACKGROUND;
-
-    // Override text color - use readable body text as default
-    visuals.override_text_color = Some(TEXT_BODY);
-
-    // Widget colors
-    visuals.widgets.noninteractive.bg_fill = WIDGET_BACKGROUND;
-    visuals.widgets.noninteractive.fg_stroke.color = TEXT_BODY;
-    visuals.widgets.noninteractive.bg_stroke.color = BORDER;
-
-    visuals.widgets.inactive.bg_fill = WIDGET_BACKGROUND;
-    visuals.widgets.inactive.fg_stroke.color = TEXT_PRIMARY;
-    visuals.widgets.inactive.bg_stroke.color = BORDER;
-
-    visuals.widgets.hovered.bg_fill = WIDGET_HOVERED;
-    visuals.widgets.hovered.fg_stroke.color = TEXT_PRIMARY;
-    visuals.widgets.hovered.bg_stroke.color = TEXT_SECONDARY;
-
-    visuals.widgets.active.bg_fill = WIDGET_ACTIVE;
-    visuals.widgets.active.fg_stroke.color = TEXT_PRIMARY;
-    visuals.widgets.active.bg_stroke.color = TEXT_PRIMARY;
-
-    visuals.widgets.open.bg_fill = WIDGET_ACTIVE;
-    visuals.widgets.open.fg_stroke.color = TEXT_PRIMARY;
-    visuals.widgets.open.bg_stroke.color = TEXT_PRIMARY;
-
-    // Selection - amber tint for contrast
-    visuals.selection.bg_fill = SELECTION;
-    visuals.selection.stroke.color = TEXT_HEADING;
-
-    // Hyperlinks - cyan for visibility
-    visuals.hyperlink_color = TEXT_LINK;
-
-    // Warning text - amber
-    visuals.warn_fg_color = TEXT_HEADING;
-
-    // Slider styling - show filled portion
-    visuals.slider_trailing_fill = true;
-
-    // Window styling - technical, minimal rounding
-    visuals.window_corner_radius = egui::CornerRadius::same(2);
-    visuals.menu_corner_radius = egui::CornerRadius::same(2);
-    visuals.window_stroke.color = BORDER;
-
-    // Popup shadow - magenta glow for a neon halo
-    visuals.popup_shadow = Shadow {
-        offset: [1, 2],
-        blur: 10,
-        spread: 0,
-        color: Color32::from_rgba_unmultiplied(0xff, 0x5a, 0xf1, 110),
-    };
+/// Configure egui visuals, fonts and text styles from `palette`.
+///
+/// The palette is also stored in the context for [`palette`] lookups.
+pub fn configure_visuals(ctx: &egui::Context, palette: &Palette) {
+    use egui::{FontFamily, FontId, TextStyle};
 
     // Register embedded fonts (Orbitron) for a neon, sci‑fi tone
     let mut fonts = FontDefinitions::default();
@@ -492,7 +1118,7 @@ pub fn configure_visuals(ctx: &egui::Context) {
     );
 
     ctx.set_fonts(fonts);
-    ctx.set_visuals(visuals);
+    ctx.set_visuals(palette_visuals(palette));
 
     // Configure text styles
     let mut style = (*ctx.style()).clone();
@@ -528,4 +1154,87 @@ pub fn configure_visuals(ctx: &egui::Context) {
     style.spacing.indent = 16.0;
 
     ctx.set_style(style);
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(PALETTE_ID), *palette));
+}
+
+/// Turn egui's widget and panel transitions off when `reduced` is set, or back on otherwise.
+pub fn apply_reduced_motion(ctx: &egui::Context, reduced: bool) {
+    let seconds = if reduced {
+        0.0
+    } else {
+        animation::UI_TRANSITION_SECONDS
+    };
+    if ctx.style().animation_time.to_bits() != seconds.to_bits() {
+        ctx.all_styles_mut(|style| style.animation_time = seconds);
+    }
+}
+
+/// egui visuals colored from `palette`.
+fn palette_visuals(palette: &Palette) -> egui::Visuals {
+    use egui::{Visuals, epaint::Shadow};
+
+    let mut visuals = if palette.dark {
+        Visuals::dark()
+    } else {
+        Visuals::light()
+    };
+
+    // Window and panel backgrounds
+    visuals.window_fill = palette.panel_background;
+    visuals.panel_fill = palette.panel_background;
+    visuals.extreme_bg_color = palette.canvas_background;
+    visuals.faint_bg_color = palette.widget_background;
+
+    // Override text color - use readable body text as default
+    visuals.override_text_color = Some(palette.text_body);
+
+    // Widget colors
+    visuals.widgets.noninteractive.bg_fill = palette.widget_background;
+    visuals.widgets.noninteractive.fg_stroke.color = palette.text_body;
+    visuals.widgets.noninteractive.bg_stroke.color = palette.border;
+
+    visuals.widgets.inactive.bg_fill = palette.widget_background;
+    visuals.widgets.inactive.fg_stroke.color = palette.text_primary;
+    visuals.widgets.inactive.bg_stroke.color = palette.border;
+
+    visuals.widgets.hovered.bg_fill = palette.widget_hovered;
+    visuals.widgets.hovered.fg_stroke.color = palette.text_primary;
+    visuals.widgets.hovered.bg_stroke.color = palette.text_secondary;
+
+    visuals.widgets.active.bg_fill = palette.widget_active;
+    visuals.widgets.active.fg_stroke.color = palette.text_primary;
+    visuals.widgets.active.bg_stroke.color = palette.text_primary;
+
+    visuals.widgets.open.bg_fill = palette.widget_active;
+    visuals.widgets.open.fg_stroke.color = palette.text_primary;
+    visuals.widgets.open.bg_stroke.color = palette.text_primary;
+
+    // Selection - amber tint for contrast
+    visuals.selection.bg_fill = palette.selection;
+    visuals.selection.stroke.color = palette.text_heading;
+
+    // Hyperlinks - cyan for visibility
+    visuals.hyperlink_color = palette.text_link;
+
+    // Warning text - amber
+    visuals.warn_fg_color = palette.text_heading;
+
+    // Slider styling - show filled portion
+    visuals.slider_trailing_fill = true;
+
+    // Window styling - technical, minimal rounding
+    visuals.window_corner_radius = egui::CornerRadius::same(2);
+    visuals.menu_corner_radius = egui::CornerRadius::same(2);
+    visuals.window_stroke.color = palette.border;
+
+    // Popup shadow - heading-colored glow for a neon halo
+    let [r, g, b, _] = palette.text_heading.to_array();
+    visuals.popup_shadow = Shadow {
+        offset: [1, 2],
+        blur: 10,
+        spread: 0,
+        color: Color32::from_rgba_unmultiplied(r, g, b, 110),
+    };
+
+    visuals
 }
@@ -1,18 +1,29 @@
+use std::array;
+
 use egui::{
     self,
     epaint::{PathShape, Stroke, Vertex},
 };
+use spacecurve::curves::onion::shell_of;
 
 // pattern_from_name used in caching method only; no direct use here
-use super::{AppState, widgets};
+use super::{AppState, SharedSettings, widgets};
 use crate::{
+    bookmarks,
+    colormap::{Classifier, Gradient},
+    export::ExportFormat,
+    geometry,
     selection::Selected3DCurve,
-    snake::{fill_snake_segments, is_adjacent_3d, snake_mask_contains, snake_membership_mask},
+    snake::{
+        SnakeBuffers, SnakeShading, fill_snake_segments, head_marker, is_adjacent_3d, race_offset,
+        snake_mask_contains, snake_membership_mask,
+    },
+    state::{Camera, ClipPlane, Guides},
     theme::{
-        self, canvas_3d::CAP_SHORTEN_FACTOR, curve_color_opaque, isolated_point_brightness,
+        self, Palette, canvas_3d::CAP_SHORTEN_FACTOR, isolated_point_brightness,
         isolated_point_line_width, segment_brightness, segment_line_width,
-        snake_color_with_brightness,
     },
+    tube::{self, TubePoint},
 };
 
 /// Number of depth buckets for O(N) "sorting".
@@ -29,7 +40,7 @@ const NUM_DEPTH_BINS: usize = 128;
 /// `egui`'s immediate mode painter handles thousands of individual line calls poorly,
 /// as each one adds overhead. By manually pushing vertices to a single `Mesh`, we
 /// reduce the overhead to essentially zero.
-fn add_segment_to_mesh(
+pub fn add_segment_to_mesh(
     mesh: &mut egui::Mesh,
     a: egui::Pos2,
     b: egui::Pos2,
@@ -118,259 +129,856 @@ struct SnakeDraw {
 }
 
 /// Render the 3D pane, including controls and the curve canvas.
+///
+/// When comparison is enabled, `compare_3d_curve` is drawn beside `selected_3d_curve` with the
+/// same size, slice, rotation and snake offset.
 pub fn show_3d_pane(
     ui: &mut egui::Ui,
     app_state: &mut AppState,
     selected_3d_curve: &mut Selected3DCurve,
+    compare_3d_curve: &mut Selected3DCurve,
     available_curves: &[&str],
-    shared_settings: &mut crate::SharedSettings,
+    shared_settings: &mut SharedSettings,
 ) {
     // Repaints are requested conditionally from the app loop
 
     // Secondary control bar with lighter visual weight
-    egui::Frame::new()
-        .inner_margin(egui::Margin {
-            left: theme::control_bar::PADDING_HORIZONTAL as i8,
-            right: theme::control_bar::PADDING_HORIZONTAL as i8,
-            top: theme::control_bar::PADDING_VERTICAL as i8,
-            bottom: theme::control_bar::PADDING_VERTICAL as i8,
-        })
-        .show(ui, |ui| {
-            ui.horizontal(|ui| {
-                // Use smaller, dimmer text for control labels
-                ui.label(
-                    egui::RichText::new("Curve:")
-                        .size(theme::font_size::INFO)
-                        .color(theme::TEXT_DIM),
-                );
-                widgets::curve_selector_combo(
-                    ui,
-                    &mut selected_3d_curve.name,
-                    available_curves,
-                    "3d_curve_selector",
-                    &mut selected_3d_curve.info_open,
-                    3,
-                    selected_3d_curve.size,
-                );
+    widgets::control_bar_frame().show(ui, |ui| {
+        ui.horizontal(|ui| {
+            // Use smaller, dimmer text for control labels
+            ui.label(
+                egui::RichText::new("Curve:")
+                    .size(theme::font_size::INFO)
+                    .color(theme::palette(ui.ctx()).text_dim),
+            );
+            let curve_dimensions = selected_3d_curve.curve_dimensions();
+            widgets::curve_selector_combo(
+                ui,
+                &mut selected_3d_curve.name,
+                available_curves,
+                "3d_curve_selector",
+                &mut selected_3d_curve.info_open,
+                curve_dimensions,
+                selected_3d_curve.size,
+            );
+
+            ui.separator();
+
+            ui.label(
+                egui::RichText::new("Size:")
+                    .size(theme::font_size::INFO)
+                    .color(theme::palette(ui.ctx()).text_dim),
+            );
+            widgets::size_input(
+                ui,
+                &mut selected_3d_curve.size,
+                &selected_3d_curve.name,
+                curve_dimensions,
+                theme::size_limits::MAX_3D,
+            );
+
+            ui.separator();
+
+            widgets::slice_controls(
+                ui,
+                &mut selected_3d_curve.slice,
+                selected_3d_curve.size,
+                &mut app_state.transport.slice_playing,
+            );
 
+            ui.separator();
+
+            if has_shells(&selected_3d_curve.name) {
+                widgets::explode_slider(ui, &mut app_state.explode);
                 ui.separator();
+            }
 
-                ui.label(
-                    egui::RichText::new("Size:")
-                        .size(theme::font_size::INFO)
-                        .color(theme::TEXT_DIM),
-                );
-                widgets::size_selector_3d(ui, &mut selected_3d_curve.size, "3d_size_selector");
-
-                // Add pause button and settings on the right side of the controls
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    widgets::settings_dropdown(
-                        ui,
-                        &mut app_state.settings_dropdown_open,
-                        &mut app_state.settings_dropdown_pos,
-                        shared_settings,
-                        true, // Include spin speed for 3D view
-                    );
-                    ui.add_space(theme::spacing::SMALL);
-                    widgets::pause_play_button(ui, &mut app_state.paused);
-                });
+            let compare_dimensions = selected_3d_curve.curve_dimensions();
+            widgets::compare_controls(
+                ui,
+                &mut app_state.compare_enabled,
+                compare_3d_curve,
+                available_curves,
+                "3d_compare_curve_selector",
+                compare_dimensions,
+            );
+
+            // Add pause button and settings on the right side of the controls
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                pane_tools(ui, app_state, selected_3d_curve.size, shared_settings);
             });
         });
+    });
+
+    if shared_settings.snake_enabled {
+        widgets::timeline(
+            ui,
+            selected_3d_curve,
+            &mut app_state.transport,
+            shared_settings,
+        );
+    }
 
     ui.separator();
 
     let available_rect = ui.available_rect_before_wrap();
     app_state.last_canvas_rect = Some(available_rect);
-    let bg = theme::CANVAS_BACKGROUND;
+    let bg = theme::palette(ui.ctx()).canvas_background;
     let painter = ui.painter_at(available_rect);
     painter.rect_filled(available_rect, 0.0, bg);
 
-    // Draw 3D space-filling curve using 2D painting, using cached points
-    // Capture values that will be needed while we hold a borrow during caching
-    let curve_size = selected_3d_curve.size;
-    let snake_offset = selected_3d_curve.snake_offset;
-    if let Some(points3d) = selected_3d_curve.ensure_cached_points() {
-        draw_3d_space_curve(
-            &painter,
-            available_rect,
-            app_state,
-            shared_settings,
-            points3d,
-            curve_size,
-            snake_offset,
-        );
-    }
+    app_state.hovered_point = None;
+    draw_panes(
+        &painter,
+        available_rect,
+        app_state,
+        shared_settings,
+        selected_3d_curve,
+        compare_3d_curve,
+    );
 
     // Handle mouse interaction for manual rotation control
+    handle_pointer(ui, available_rect, app_state, selected_3d_curve);
+}
+
+/// Right-aligned 3D pane buttons: settings, export, recording, colors, clipping, camera and
+/// playback.
+fn pane_tools(
+    ui: &mut egui::Ui,
+    app_state: &mut AppState,
+    size: u32,
+    shared_settings: &mut SharedSettings,
+) {
+    widgets::settings_dropdown(
+        ui,
+        &mut app_state.settings_dropdown_open,
+        &mut app_state.settings_dropdown_pos,
+        shared_settings,
+        true, // Include spin speed for 3D view
+    );
+    ui.add_space(theme::spacing::SMALL);
+    widgets::export_menu(
+        ui,
+        &mut app_state.export_request,
+        &[ExportFormat::Png, ExportFormat::Obj, ExportFormat::Gltf],
+    );
+    ui.add_space(theme::spacing::SMALL);
+    widgets::record_menu(
+        ui,
+        &mut app_state.record_request,
+        &mut app_state.record_seconds,
+        app_state.recording,
+    );
+    ui.add_space(theme::spacing::SMALL);
+    widgets::colormap_menu(ui, shared_settings);
+    ui.add_space(theme::spacing::SMALL);
+    widgets::clip_menu(ui, &mut app_state.clip, size);
+    ui.add_space(theme::spacing::SMALL);
+    widgets::camera_menu(
+        ui,
+        &mut app_state.camera,
+        &mut app_state.drag_rotation,
+        &mut app_state.guides,
+        &mut app_state.transport.paused,
+    );
+    ui.add_space(theme::spacing::SMALL);
+    widgets::pause_play_button(ui, &mut app_state.transport.paused);
+}
+
+/// Handle drag rotation, click-to-jump for the snake and the hover inspector tooltip.
+fn handle_pointer(
+    ui: &mut egui::Ui,
+    available_rect: egui::Rect,
+    app_state: &mut AppState,
+    selected_3d_curve: &mut Selected3DCurve,
+) {
     let response = ui.allocate_rect(available_rect, egui::Sense::click_and_drag());
 
     if response.hovered() && ui.input(|i| i.pointer.primary_down()) {
+        let pointer = response.interact_pointer_pos().unwrap_or_default();
         // Mouse button is down - pause rotation immediately
         if !app_state.mouse_dragging {
             app_state.mouse_dragging = true;
-            app_state.last_mouse_x = response.interact_pointer_pos().unwrap_or_default().x;
+            let camera = app_state.camera;
+            app_state.drag_start = Some((pointer, camera.yaw, camera.tilt));
         }
 
-        // If dragging, apply manual rotation
-        if response.dragged() {
-            let current_mouse_x = response.interact_pointer_pos().unwrap_or_default().x;
-            let delta_x = current_mouse_x - app_state.last_mouse_x;
-
-            // Apply manual rotation (scale the mouse movement)
-            app_state.rotation_angle += delta_x * theme::canvas_3d::DRAG_SENSITIVITY;
-            app_state.last_mouse_x = current_mouse_x;
+        // If dragging, turn the camera from where it was when the drag started
+        if response.dragged()
+            && let Some((origin, yaw, tilt)) = app_state.drag_start
+        {
+            app_state
+                .drag_rotation
+                .apply(&mut app_state.camera, (yaw, tilt), pointer - origin);
         }
     } else if app_state.mouse_dragging {
         // Mouse button released - resume automatic rotation
         app_state.mouse_dragging = false;
     }
+
+    if let Some(point) = &app_state.hovered_point {
+        // Clicking a vertex jumps the snake there instead of waiting for the animation.
+        if response.clicked() {
+            selected_3d_curve.snake_offset = point.position as f32;
+        }
+        response.on_hover_ui_at_pointer(|ui| widgets::point_inspector(ui, point));
+    }
 }
 
-/// Render the 3D curve and overlays into the given rect.
-fn draw_3d_space_curve(
+/// Draw the primary curve into `rect`, or both curves side by side when comparing.
+fn draw_panes(
     painter: &egui::Painter,
     rect: egui::Rect,
     app_state: &mut AppState,
-    shared_settings: &crate::SharedSettings,
-    original_curve_points: &[[u32; 3]],
-    curve_size: u32,
-    snake_offset: f32,
+    shared_settings: &SharedSettings,
+    selected_3d_curve: &mut Selected3DCurve,
+    compare_3d_curve: &mut Selected3DCurve,
 ) {
-    let center = rect.center();
+    if app_state.compare_enabled {
+        compare_3d_curve.size = selected_3d_curve.size;
+        compare_3d_curve.slice = selected_3d_curve.slice;
+        compare_3d_curve.snake_offset = selected_3d_curve.snake_offset;
+        let [left, right] = widgets::split_columns(rect);
+        draw_selection(painter, left, app_state, shared_settings, selected_3d_curve);
+        draw_selection(painter, right, app_state, shared_settings, compare_3d_curve);
+    } else {
+        draw_selection(painter, rect, app_state, shared_settings, selected_3d_curve);
+    }
+}
+
+/// Draw the cached points of `selection` into `rect`, if the curve can be constructed.
+fn draw_selection(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    app_state: &mut AppState,
+    shared_settings: &SharedSettings,
+    selection: &mut Selected3DCurve,
+) {
+    let view = View3D {
+        camera: app_state.camera,
+        clip: app_state.clip,
+        guides: app_state.guides,
+        explode: if has_shells(&selection.name) {
+            app_state.explode
+        } else {
+            0.0
+        },
+    };
+    let buffers = &mut app_state.snake_3d;
+    let hovered = draw_3d_space_curve(painter, rect, view, buffers, shared_settings, selection);
+    if let Some(progress) = selection.generating() {
+        widgets::paint_generating(painter, rect, progress);
+    }
+    if let Some(point) = hovered.and_then(|position| selection.hover_point(position)) {
+        app_state.hovered_point = Some(point);
+    }
+}
+
+/// Camera and clipping plane a 3D canvas is drawn with.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct View3D {
+    /// Orientation and projection.
+    pub camera: Camera,
+    /// Clipping plane hiding part of the curve.
+    pub clip: ClipPlane,
+    /// Reference geometry drawn behind the curve.
+    pub guides: Guides,
+    /// Spacing added between neighbouring L∞ shells, in grid cells.
+    pub explode: f32,
+}
+
+/// Whether `name` is a curve traversed shell by shell, so the explode view applies.
+pub fn has_shells(name: &str) -> bool {
+    matches!(name, "onion" | "hairyonion")
+}
+
+/// A curve projected to screen space for one frame.
+struct ProjectedCurve<'a> {
+    /// Integer grid points in curve order.
+    original: &'a [[u32; 3]],
+    /// Rotated points in normalized scene space; `z` is depth.
+    pts3d: Vec<[f32; 3]>,
+    /// Screen positions of `pts3d`.
+    pts2d: Vec<egui::Pos2>,
+    /// Whether each consecutive pair of points is adjacent.
+    connected: Vec<bool>,
+    /// Whether each segment's start and end caps are exposed.
+    shorten_caps: Vec<(bool, bool)>,
+    /// Whether each point lies on the kept side of the clipping plane.
+    visible: Vec<bool>,
+    /// Screen distance between neighbouring cells at the scene center, in points.
+    cell: f32,
+    /// Whether points were projected orthographically.
+    orthographic: bool,
+}
+
+impl ProjectedCurve<'_> {
+    /// Whether both endpoints of segment `i` survive clipping.
+    fn segment_visible(&self, i: usize) -> bool {
+        self.visible[i] && self.visible[i + 1]
+    }
+}
+
+/// Render the 3D curve and overlays into the given rect.
+///
+/// The projected curve is re-tessellated only when the camera, layout or styling changes;
+/// the snake overlay is rebuilt every frame. Returns the position of the vertex under the
+/// pointer, if any.
+pub fn draw_3d_space_curve(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    view: View3D,
+    buffers: &mut SnakeBuffers,
+    shared_settings: &SharedSettings,
+    selection: &mut Selected3DCurve,
+) -> Option<usize> {
+    // Capture values that will be needed while we hold a borrow during caching
+    let curve_size = selection.size;
+    let snake_offset = selection.snake_offset;
+    let block_side = match shared_settings.block_level {
+        0 => None,
+        level => selection.block_side(level),
+    };
+    let classifier = selection.classifier(shared_settings.color_by, shared_settings.color_level);
+    let (original, geometry) = selection.cached_points_with_geometry(painter.ctx())?;
+    if original.is_empty() {
+        return None;
+    }
+
     let margin = theme::canvas_3d::MARGIN;
     let available_width = rect.width() - margin * 2.0;
     let available_height = rect.height() - margin * 2.0;
     let scale = (available_width.min(available_height) * theme::canvas_3d::SCALE_FACTOR)
         .max(theme::canvas_3d::MIN_SCALE);
-
-    if original_curve_points.is_empty() {
-        return;
-    }
-
-    let rotation_y = app_state.rotation_angle;
-    let rotation_x = theme::canvas_3d::CAMERA_TILT;
-    let (curve_points_3d, curve_points_2d) = project_points(
-        original_curve_points,
-        curve_size,
-        rotation_x,
-        rotation_y,
-        center,
-        scale,
-    );
-
-    let connected = compute_connected(original_curve_points);
+    let View3D {
+        camera,
+        clip,
+        guides,
+        explode,
+    } = view;
+    let center = rect.center();
+    // Scale of the un-exploded grid, which shrinks to keep an exploded curve in view.
+    let grid_scale = scale / explode_fit(curve_size, explode);
+    let (pts3d, pts2d) = project_points(original, curve_size, explode, camera, center, scale);
+    let connected = geometry.adjacency(original, is_adjacent_3d).to_vec();
     let shorten_caps = compute_shorten_caps(&connected);
-    let segments_with_depth = build_segment_depths(
-        &curve_points_3d,
-        &connected,
-        shared_settings.show_long_jumps,
-    );
-    // Sorted by depth binning inside draw_curve_segments
+    let visible = original.iter().map(|p| clip.keeps(p)).collect();
+    let curve = ProjectedCurve {
+        original,
+        pts3d,
+        pts2d,
+        connected,
+        shorten_caps,
+        visible,
+        cell: grid_scale * 2.0 / (curve_size - 1) as f32,
+        orthographic: camera.orthographic,
+    };
 
-    draw_curve_segments(
+    let palette = theme::palette(painter.ctx());
+    let key = geometry::cache_key((
+        geometry::rect_bits(rect),
+        [camera.yaw, camera.tilt].map(f32::to_bits),
+        camera.orthographic,
+        clip,
+        explode.to_bits(),
+        shared_settings.curve_opacity.to_bits(),
+        shared_settings.tube.key(),
+        shared_settings.colormap,
+        shared_settings.colormap_endpoints,
+        (
+            shared_settings.show_long_jumps,
+            shared_settings.highlight_jumps,
+            block_side,
+            classifier,
+        ),
+        (
+            palette.curve,
+            palette.jump,
+            palette.border,
+            palette.canvas_background,
+        ),
+    ));
+    let mesh = geometry.mesh(painter.ctx(), key, |shapes| {
+        if let Some(side) = block_side {
+            block_lattice_shapes(
+                shapes,
+                palette.border,
+                camera,
+                center,
+                grid_scale,
+                curve_size,
+                side,
+            );
+        }
+        curve_shapes(shapes, &palette, shared_settings, &curve, classifier);
+    });
+    draw_guides(
         painter,
-        &curve_points_2d,
-        &segments_with_depth,
-        &shorten_caps,
-        shared_settings.curve_opacity,
+        guides,
+        (camera, center, grid_scale),
+        curve_size,
+        &palette,
     );
+    painter.add(egui::Shape::Mesh(mesh));
 
-    if shared_settings.snake_enabled && curve_points_2d.len() > 1 {
-        fill_snake_segments(
-            &mut app_state.snake_segments_3d,
+    if shared_settings.snake_enabled && curve.pts2d.len() > 1 {
+        draw_snakes(
+            painter,
+            buffers,
+            shared_settings,
+            &palette,
+            &curve,
             snake_offset,
-            shared_settings.snake_length,
-            original_curve_points.len() as u32,
         );
-        let snake_segments = &app_state.snake_segments_3d;
+    }
+    bookmarks::paint_markers(painter, &shared_settings.bookmarks, &curve.pts2d, |i| {
+        curve.visible[i]
+    });
 
-        let snake_mask: &[bool] = if shared_settings.show_long_jumps {
-            &[]
-        } else {
-            snake_membership_mask(
-                snake_segments,
-                curve_points_2d.len(),
-                &mut app_state.snake_mask_3d,
-            )
-        };
-        let snake_included = snake_included_mask(
-            snake_segments,
-            &connected,
-            shared_settings.show_long_jumps,
-            &mut app_state.snake_included_3d,
+    hover_vertex(painter, rect, &curve)
+}
+
+/// Push the static curve: depth-binned segments plus isolated-node half-segments.
+///
+/// Points are colored by the classes of `classifier` when it is set.
+fn curve_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    palette: &Palette,
+    shared_settings: &SharedSettings,
+    curve: &ProjectedCurve<'_>,
+    classifier: Option<Classifier>,
+) {
+    let gradient = Gradient::for_curve(
+        shared_settings.colormap,
+        shared_settings.colormap_endpoints,
+        curve.original,
+        classifier,
+        palette.colorblind_safe,
+    );
+    // Highlighted jumps replace the solid ones.
+    let segments_with_depth = build_segment_depths(
+        curve,
+        shared_settings.show_long_jumps && !shared_settings.highlight_jumps,
+    );
+    if shared_settings.tube.enabled {
+        tube_shapes(
+            shapes,
+            palette,
+            shared_settings,
+            curve,
+            &segments_with_depth,
+            gradient.as_ref(),
         );
-        let draws = collect_snake_draws(
-            &curve_points_2d,
-            &curve_points_3d,
-            &connected,
-            snake_included,
-            &shorten_caps,
+    } else {
+        // Sorted by depth binning inside curve_segment_shapes
+        curve_segment_shapes(
+            shapes,
+            palette,
+            &curve.pts2d,
+            &segments_with_depth,
+            &curve.shorten_caps,
+            shared_settings.curve_opacity,
+            gradient.as_ref(),
         );
-        // Sorted by depth binning inside draw_snake_draws
-        draw_snake_draws(painter, &draws);
-
         if !shared_settings.show_long_jumps {
-            draw_isolated_snake_points(
-                painter,
-                original_curve_points,
-                &curve_points_2d,
-                &curve_points_3d,
-                snake_segments,
-                snake_mask,
-            );
+            isolated_point_shapes(shapes, palette, curve, gradient.as_ref());
         }
     }
+    if shared_settings.highlight_jumps {
+        jump_shapes(shapes, palette, curve);
+    }
+}
+
+/// Push the curve as solid shaded tubes, colored like the line rendering but always opaque.
+fn tube_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    palette: &Palette,
+    shared_settings: &SharedSettings,
+    curve: &ProjectedCurve<'_>,
+    segments_with_depth: &[(usize, f32)],
+    gradient: Option<&Gradient>,
+) {
+    let radius = shared_settings.tube.radius * curve.cell;
+    let points: Vec<TubePoint> = curve
+        .pts2d
+        .iter()
+        .zip(&curve.pts3d)
+        .map(|(&pos, p)| TubePoint {
+            pos,
+            depth: p[2],
+            radius: radius * perspective_scale(p[2], curve.orthographic),
+        })
+        .collect();
+    let segments: Vec<usize> = segments_with_depth.iter().map(|&(i, _)| i).collect();
+    let color_at =
+        |i: usize| gradient.map_or_else(|| palette.curve_opaque(1.0), |g| g.color(i, 1.0));
+    let mesh = tube::tube_mesh(&points, &segments, &curve.visible, &color_at);
+    shapes.push(egui::Shape::Mesh(mesh.into()));
+}
+
+/// Push dashed, depth-shaded lines for consecutive points that are not grid neighbours.
+fn jump_shapes(shapes: &mut Vec<egui::Shape>, palette: &Palette, curve: &ProjectedCurve<'_>) {
+    for (i, _) in curve
+        .connected
+        .iter()
+        .enumerate()
+        .filter(|&(i, c)| !*c && curve.segment_visible(i))
+    {
+        let depth = (curve.pts3d[i][2] + curve.pts3d[i + 1][2]) / 2.0;
+        let brightness = segment_brightness(depth);
+        let stroke = Stroke::new(
+            segment_line_width(brightness),
+            palette.jump_with_brightness(brightness),
+        );
+        shapes.extend(egui::Shape::dashed_line(
+            &[curve.pts2d[i], curve.pts2d[i + 1]],
+            stroke,
+            theme::jumps::DASH_LENGTH,
+            theme::jumps::GAP_LENGTH,
+        ));
+    }
+}
+
+/// Draw one snake starting at `snake_offset` in the accent color of `palette`.
+fn draw_snake(
+    painter: &egui::Painter,
+    buffers: &mut SnakeBuffers,
+    shared_settings: &SharedSettings,
+    palette: &Palette,
+    curve: &ProjectedCurve<'_>,
+    snake_offset: f32,
+) {
+    fill_snake_segments(
+        &mut buffers.segments,
+        snake_offset,
+        shared_settings.snake_length,
+        curve.original.len() as u32,
+    );
+    let snake_segments = &buffers.segments;
+    let shading = SnakeShading::new(
+        shared_settings.snake_style,
+        snake_segments,
+        curve.original.len(),
+    );
+
+    let snake_mask: &[bool] = if shared_settings.show_long_jumps {
+        &[]
+    } else {
+        snake_membership_mask(snake_segments, curve.pts2d.len(), &mut buffers.mask)
+    };
+    let snake_included = snake_included_mask(
+        snake_segments,
+        curve,
+        shared_settings.show_long_jumps,
+        &mut buffers.included,
+    );
+    let draws = collect_snake_draws(
+        palette,
+        &shading,
+        &curve.pts2d,
+        &curve.pts3d,
+        &curve.connected,
+        snake_included,
+        &curve.shorten_caps,
+    );
+    // Sorted by depth binning inside draw_snake_draws
+    draw_snake_draws(painter, &draws);
 
     if !shared_settings.show_long_jumps {
-        draw_isolated_points(
+        draw_isolated_snake_points(
             painter,
-            original_curve_points,
-            &curve_points_2d,
-            &curve_points_3d,
+            palette,
+            curve,
+            snake_segments,
+            snake_mask,
+            &shading,
         );
     }
+
+    if shading.style.head_marker
+        && let Some(head) = shading.head()
+        && curve.visible.get(head).copied().unwrap_or(false)
+    {
+        let brightness = segment_brightness(curve.pts3d[head][2]);
+        let width = segment_line_width(brightness) * shading.style.width;
+        painter.add(head_marker(palette, curve.pts2d[head], width, brightness));
+    }
+}
+
+/// Pick the vertex under the pointer, unless the user is dragging, and mark it on the canvas.
+fn hover_vertex(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    curve: &ProjectedCurve<'_>,
+) -> Option<usize> {
+    let pts2d = &curve.pts2d;
+    let pointer = painter
+        .ctx()
+        .pointer_hover_pos()
+        .filter(|pos| rect.contains(*pos))
+        .filter(|_| !painter.ctx().input(|i| i.pointer.is_decidedly_dragging()))?;
+    let hovered = pick_vertex(pts2d, &curve.pts3d, &curve.visible, pointer)?;
+    painter.circle_stroke(
+        pts2d[hovered],
+        theme::inspector::MARKER_RADIUS,
+        Stroke::new(
+            theme::inspector::MARKER_STROKE,
+            theme::palette(painter.ctx()).text_primary,
+        ),
+    );
+    Some(hovered)
+}
+
+/// Find the unclipped vertex nearest the viewer among those within pick range of `pointer`.
+fn pick_vertex(
+    pts2d: &[egui::Pos2],
+    pts3d: &[[f32; 3]],
+    visible: &[bool],
+    pointer: egui::Pos2,
+) -> Option<usize> {
+    let radius_sq = theme::inspector::PICK_RADIUS * theme::inspector::PICK_RADIUS;
+    pts2d
+        .iter()
+        .enumerate()
+        .filter(|(i, p)| visible[*i] && p.distance_sq(pointer) <= radius_sq)
+        .max_by(|(a, _), (b, _)| pts3d[*a][2].total_cmp(&pts3d[*b][2]))
+        .map(|(i, _)| i)
 }
 
 /// Project integer 3D curve points into rotated 3D coordinates and 2D screen positions.
+///
+/// A non-zero `explode` spreads the points' L∞ shells apart before projection.
 fn project_points(
     original: &[[u32; 3]],
     curve_size: u32,
-    rotation_x: f32,
-    rotation_y: f32,
+    explode: f32,
+    camera: Camera,
     center: egui::Pos2,
     scale: f32,
 ) -> (Vec<[f32; 3]>, Vec<egui::Pos2>) {
     let mut pts3d = Vec::with_capacity(original.len());
     let mut pts2d = Vec::with_capacity(original.len());
     for p in original.iter() {
-        let x = (p[0] as f32 / (curve_size - 1) as f32) * 2.0 - 1.0;
-        let y = (p[1] as f32 / (curve_size - 1) as f32) * 2.0 - 1.0;
-        let z = (p[2] as f32 / (curve_size - 1) as f32) * 2.0 - 1.0;
-        let x_rot = x * rotation_y.cos() + z * rotation_y.sin();
-        let z_rot = -x * rotation_y.sin() + z * rotation_y.cos();
-        let y_tilt = y * rotation_x.cos() - z_rot * rotation_x.sin();
-        let z_tilt = y * rotation_x.sin() + z_rot * rotation_x.cos();
-        pts3d.push([x_rot, y_tilt, z_tilt]);
-        let depth = theme::canvas_3d::PERSPECTIVE_DISTANCE - z_tilt;
-        let perspective_scale = theme::canvas_3d::PERSPECTIVE_DISTANCE / depth;
-        let screen_x = center.x + x_rot * scale * perspective_scale;
-        let screen_y = center.y - y_tilt * scale * perspective_scale;
-        pts2d.push(egui::Pos2::new(screen_x, screen_y));
+        let normalized = if explode > 0.0 {
+            explode_point(p, curve_size, explode)
+        } else {
+            p.map(|c| (c as f32 / (curve_size - 1) as f32) * 2.0 - 1.0)
+        };
+        let (rotated, screen) = project(normalized, camera, center, scale);
+        pts3d.push(rotated);
+        pts2d.push(screen);
     }
     (pts3d, pts2d)
 }
 
-/// Compute whether successive 3D points are adjacent (Manhattan distance <= 1).
-fn compute_connected(original: &[[u32; 3]]) -> Vec<bool> {
-    if original.len() < 2 {
-        return Vec::new();
+/// Normalize `p` after pushing it out of its L∞ shell's faces by `explode` cells per shell nested
+/// inside.
+///
+/// Each face of a shell moves along its outward axis, so the outer shells open up and reveal the
+/// ones they enclose. The scene is then shrunk to keep the outermost faces inside `[-1, 1]`.
+fn explode_point(p: &[u32; 3], curve_size: u32, explode: f32) -> [f32; 3] {
+    let max = curve_size - 1;
+    let cell = 2.0 / max as f32;
+    let shell = shell_of(p, curve_size);
+    let offset = explode * (max / 2 - shell) as f32 * cell;
+    let fit = explode_fit(curve_size, explode);
+    array::from_fn(|axis| {
+        let c = p[axis];
+        let push = if c == shell {
+            -offset
+        } else if c == max - shell {
+            offset
+        } else {
+            0.0
+        };
+        (c as f32 * cell - 1.0 + push) / fit
+    })
+}
+
+/// Factor by which an exploded scene of side `curve_size` is shrunk to fit the canvas.
+fn explode_fit(curve_size: u32, explode: f32) -> f32 {
+    let max = curve_size - 1;
+    1.0 + explode * (max / 2) as f32 * 2.0 / max as f32
+}
+
+/// Rotate a point in normalized scene space (`[-1, 1]` per axis) and project it to the screen.
+fn project(
+    [x, y, z]: [f32; 3],
+    camera: Camera,
+    center: egui::Pos2,
+    scale: f32,
+) -> ([f32; 3], egui::Pos2) {
+    let rotation_x = camera.tilt;
+    let rotation_y = camera.yaw;
+    let x_rot = x * rotation_y.cos() + z * rotation_y.sin();
+    let z_rot = -x * rotation_y.sin() + z * rotation_y.cos();
+    let y_tilt = y * rotation_x.cos() - z_rot * rotation_x.sin();
+    let z_tilt = y * rotation_x.sin() + z_rot * rotation_x.cos();
+    let perspective_scale = perspective_scale(z_tilt, camera.orthographic);
+    let screen_x = center.x + x_rot * scale * perspective_scale;
+    let screen_y = center.y - y_tilt * scale * perspective_scale;
+    ([x_rot, y_tilt, z_tilt], egui::Pos2::new(screen_x, screen_y))
+}
+
+/// Screen magnification of geometry at depth `z` relative to the scene center.
+fn perspective_scale(z: f32, orthographic: bool) -> f32 {
+    if orthographic {
+        1.0
+    } else {
+        theme::canvas_3d::PERSPECTIVE_DISTANCE / (theme::canvas_3d::PERSPECTIVE_DISTANCE - z)
     }
-    let last_seg_idx = original.len() - 2;
-    let mut connected = Vec::with_capacity(last_seg_idx + 1);
-    for i in 0..=last_seg_idx {
-        connected.push(is_adjacent_3d(&original[i], &original[i + 1]));
+}
+
+/// Push the edges of every block of `side` cells, forming a lattice around the curve.
+///
+/// Lattice planes fall halfway between neighbouring cells, so the outermost edges frame
+/// the whole grid.
+fn block_lattice_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    color: egui::Color32,
+    camera: Camera,
+    center: egui::Pos2,
+    scale: f32,
+    curve_size: u32,
+    side: u32,
+) {
+    let stroke = Stroke::new(theme::blocks::LINE_WIDTH, color);
+    let normalize = |cell: u32| ((cell as f32 - 0.5) / (curve_size - 1) as f32) * 2.0 - 1.0;
+    let planes: Vec<f32> = (0..=curve_size)
+        .step_by(side as usize)
+        .map(normalize)
+        .collect();
+    let (start, end) = (normalize(0), normalize(curve_size));
+    for axis in 0..3 {
+        for &u in &planes {
+            for &v in &planes {
+                let endpoint = |t: f32| {
+                    let mut p = [0.0; 3];
+                    p[axis] = t;
+                    p[(axis + 1) % 3] = u;
+                    p[(axis + 2) % 3] = v;
+                    project(p, camera, center, scale).1
+                };
+                shapes.push(egui::Shape::line_segment(
+                    [endpoint(start), endpoint(end)],
+                    stroke,
+                ));
+            }
+        }
+    }
+}
+
+/// Draw the snake at `snake_offset`, preceded by its rival when racing is enabled.
+fn draw_snakes(
+    painter: &egui::Painter,
+    buffers: &mut SnakeBuffers,
+    shared_settings: &SharedSettings,
+    palette: &Palette,
+    curve: &ProjectedCurve<'_>,
+    snake_offset: f32,
+) {
+    // The race snake goes first so the shared buffers end up holding the main snake.
+    if shared_settings.race_enabled {
+        let length = curve.original.len() as u32;
+        let race_offset = race_offset(snake_offset, shared_settings.race_gap, length);
+        let rival = palette.rival_snake();
+        draw_snake(
+            painter,
+            buffers,
+            shared_settings,
+            &rival,
+            curve,
+            race_offset,
+        );
+    }
+    draw_snake(
+        painter,
+        buffers,
+        shared_settings,
+        palette,
+        curve,
+        snake_offset,
+    );
+}
+
+/// Draw the enabled `guides` around a grid of side `curve_size`.
+///
+/// `scene` holds the camera, the screen position of the scene center and the scale the
+/// curve was projected with. Guides frame the cells, so they lie half a cell outside the
+/// outermost cell centers.
+fn draw_guides(
+    painter: &egui::Painter,
+    guides: Guides,
+    (camera, center, scale): (Camera, egui::Pos2, f32),
+    curve_size: u32,
+    palette: &Palette,
+) {
+    let cell = 2.0 / (curve_size - 1) as f32;
+    let extent = 1.0 + cell / 2.0;
+    let to_screen = |p: [f32; 3]| project(p, camera, center, scale).1;
+    let line = |a: [f32; 3], b: [f32; 3], stroke: Stroke| {
+        painter.line_segment([to_screen(a), to_screen(b)], stroke);
+    };
+
+    if guides.grid {
+        let stroke = Stroke::new(
+            theme::guides::GRID_WIDTH,
+            palette.border.gamma_multiply(theme::guides::GRID_OPACITY),
+        );
+        // Each plane holds the origin corner and is perpendicular to `normal`.
+        for normal in 0..3 {
+            let first = (normal + 1) % 3;
+            let second = (normal + 2) % 3;
+            for step in 0..=curve_size {
+                let offset = -extent + step as f32 * cell;
+                for (along, across) in [(first, second), (second, first)] {
+                    let mut start = [-extent; 3];
+                    start[across] = offset;
+                    let mut end = start;
+                    end[along] = extent;
+                    line(start, end, stroke);
+                }
+            }
+        }
+    }
+
+    if guides.bounds {
+        let stroke = Stroke::new(theme::guides::BOUNDS_WIDTH, palette.border);
+        for axis in 0..3 {
+            for corner in 0..4 {
+                let mut a = [0.0; 3];
+                a[axis] = -extent;
+                a[(axis + 1) % 3] = if corner & 1 == 0 { -extent } else { extent };
+                a[(axis + 2) % 3] = if corner & 2 == 0 { -extent } else { extent };
+                let mut b = a;
+                b[axis] = extent;
+                line(a, b, stroke);
+            }
+        }
+    }
+
+    if guides.axes {
+        let origin = [-extent; 3];
+        let length = 2.0 * extent * theme::guides::AXIS_LENGTH;
+        for (axis, (name, color)) in ClipPlane::AXES
+            .iter()
+            .zip(theme::guides::AXIS_COLORS)
+            .enumerate()
+        {
+            let mut end = origin;
+            end[axis] += length;
+            line(origin, end, Stroke::new(theme::guides::AXIS_WIDTH, color));
+            let mut label = end;
+            label[axis] += theme::guides::LABEL_GAP;
+            painter.text(
+                to_screen(label),
+                egui::Align2::CENTER_CENTER,
+                *name,
+                egui::FontId::proportional(theme::font_size::INFO),
+                color,
+            );
+        }
     }
-    connected
 }
 
 /// For each segment, decide whether to shorten start/end caps at exposed ends.
@@ -389,37 +997,37 @@ fn compute_shorten_caps(connected: &[bool]) -> Vec<(bool, bool)> {
 }
 
 /// Build a list of segment indices with their average depth for painter sorting.
-fn build_segment_depths(
-    pts3d: &[[f32; 3]],
-    connected: &[bool],
-    show_long_jumps: bool,
-) -> Vec<(usize, f32)> {
+fn build_segment_depths(curve: &ProjectedCurve<'_>, show_long_jumps: bool) -> Vec<(usize, f32)> {
+    let (pts3d, connected) = (&curve.pts3d, &curve.connected);
     let mut segs = Vec::with_capacity(connected.len());
     for i in 0..connected.len() {
         let start_depth = pts3d[i][2];
         let end_depth = pts3d[i + 1][2];
         let avg_depth = (start_depth + end_depth) / 2.0;
-        if show_long_jumps || connected[i] {
+        if (show_long_jumps || connected[i]) && curve.segment_visible(i) {
             segs.push((i, avg_depth));
         }
     }
     segs
 }
 
-/// Draw depth‑sorted curve segments using depth binning.
+/// Push depth‑sorted curve segments using depth binning.
 ///
 /// This function implements the core optimization:
 /// 1. **Binning**: Distribute segments into `NUM_DEPTH_BINS` buckets based on depth.
 /// 2. **Batching**: For each bin, generate a single `egui::Mesh` containing all segments.
 ///
 /// This reduces the number of draw calls from O(N) (e.g., 32,000) to O(BINS) (128),
-/// providing a massive performance boost.
-fn draw_curve_segments(
-    painter: &egui::Painter,
+/// providing a massive performance boost. With a `gradient`, segments are colored by index
+/// while keeping their bin's depth shading.
+fn curve_segment_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    palette: &Palette,
     pts2d: &[egui::Pos2],
     segments_with_depth: &[(usize, f32)],
     shorten_caps: &[(bool, bool)],
     opacity: f32,
+    gradient: Option<&Gradient>,
 ) {
     if opacity <= 0.0 {
         return;
@@ -444,7 +1052,7 @@ fn draw_curve_segments(
             + normalized_depth * (theme::canvas_3d::DEPTH_MAX - theme::canvas_3d::DEPTH_MIN);
         let brightness = theme::segment_brightness(depth);
         let line_width = theme::segment_line_width(brightness);
-        let color = theme::curve_color_with_brightness(brightness, opacity);
+        let color = palette.curve_with_brightness(brightness, opacity);
         // Stroke not needed for mesh, just width and color
 
         let mut mesh = egui::Mesh::default();
@@ -458,14 +1066,14 @@ fn draw_curve_segments(
                 start_pos,
                 end_pos,
                 line_width,
-                color,
+                gradient.map_or(color, |g| g.color(i, brightness).gamma_multiply(opacity)),
                 shorten_start,
                 shorten_end,
             );
         }
 
         if !mesh.vertices.is_empty() {
-            painter.add(egui::Shape::Mesh(mesh.into()));
+            shapes.push(egui::Shape::Mesh(mesh.into()));
         }
     }
 }
@@ -473,10 +1081,11 @@ fn draw_curve_segments(
 /// Build a membership mask for snake segments that should be included given visibility rules.
 fn snake_included_mask<'a>(
     snake_segments: &[usize],
-    connected: &[bool],
+    curve: &ProjectedCurve<'_>,
     show_long_jumps: bool,
     scratch: &'a mut Vec<bool>,
 ) -> &'a [bool] {
+    let connected = &curve.connected;
     let len = connected.len();
     if scratch.len() < len {
         scratch.resize(len, false);
@@ -485,7 +1094,7 @@ fn snake_included_mask<'a>(
     }
 
     for &i in snake_segments {
-        if i < len && (show_long_jumps || connected[i]) {
+        if i < len && (show_long_jumps || connected[i]) && curve.segment_visible(i) {
             scratch[i] = true;
         }
     }
@@ -495,6 +1104,8 @@ fn snake_included_mask<'a>(
 
 /// Turn included snake segments into depth‑sortable draw primitives.
 fn collect_snake_draws(
+    palette: &Palette,
+    shading: &SnakeShading,
     pts2d: &[egui::Pos2],
     pts3d: &[[f32; 3]],
     connected: &[bool],
@@ -509,7 +1120,11 @@ fn collect_snake_draws(
             let mut pts: Vec<egui::Pos2> = Vec::new();
             pts.push(pts2d[i]);
             let mut j = i;
-            while j < nsegs && snake_mask_contains(snake_included, j) && connected[j] {
+            while j < nsegs
+                && snake_mask_contains(snake_included, j)
+                && connected[j]
+                && shading.band(j) == shading.band(i)
+            {
                 pts.push(pts2d[j + 1]);
                 j += 1;
             }
@@ -523,8 +1138,8 @@ fn collect_snake_draws(
             let brightness = segment_brightness(avg_depth);
             draws.push(SnakeDraw {
                 depth: avg_depth,
-                width: segment_line_width(brightness),
-                color: snake_color_with_brightness(brightness),
+                width: segment_line_width(brightness) * shading.style.width,
+                color: shading.color(palette, brightness, i),
                 points: pts,
                 shorten: None,
             });
@@ -535,8 +1150,8 @@ fn collect_snake_draws(
                 let brightness = segment_brightness(avg_depth);
                 draws.push(SnakeDraw {
                     depth: avg_depth,
-                    width: segment_line_width(brightness),
-                    color: snake_color_with_brightness(brightness),
+                    width: segment_line_width(brightness) * shading.style.width,
+                    color: shading.color(palette, brightness, i),
                     points: vec![pts2d[i], pts2d[i + 1]],
                     shorten: Some(shorten_caps[i]),
                 });
@@ -549,7 +1164,7 @@ fn collect_snake_draws(
 
 /// Render snake primitives with proper cap handling using depth binning.
 ///
-/// Similar to `draw_curve_segments`, this batches the snake segments into meshes
+/// Similar to `curve_segment_shapes`, this batches the snake segments into meshes
 /// to minimize draw calls. Continuous polyline paths (length >= 3) are still drawn
 /// as paths because they are already efficient, but isolated segments are batched.
 fn draw_snake_draws(painter: &egui::Painter, draws: &[SnakeDraw]) {
@@ -595,15 +1210,16 @@ fn draw_snake_draws(painter: &egui::Painter, draws: &[SnakeDraw]) {
 /// Draw half‑segments for isolated snake nodes when long jumps are hidden.
 fn draw_isolated_snake_points(
     painter: &egui::Painter,
-    original: &[[u32; 3]],
-    pts2d: &[egui::Pos2],
-    pts3d: &[[f32; 3]],
+    palette: &Palette,
+    curve: &ProjectedCurve<'_>,
     snake_segments: &[usize],
     snake_mask: &[bool],
+    shading: &SnakeShading,
 ) {
+    let (original, pts2d, pts3d) = (curve.original, &curve.pts2d, &curve.pts3d);
     let mut isolated = Vec::new();
     for &idx in snake_segments {
-        if idx < original.len() {
+        if idx < original.len() && curve.visible[idx] {
             let has_adjacent_prev = idx > 0
                 && snake_mask_contains(snake_mask, idx - 1)
                 && is_adjacent_3d(&original[idx - 1], &original[idx]);
@@ -634,21 +1250,22 @@ fn draw_isolated_snake_points(
             continue;
         };
         let brightness = isolated_point_brightness(*depth);
-        let line_width = isolated_point_line_width(brightness);
-        let color = snake_color_with_brightness(brightness);
+        let line_width = isolated_point_line_width(brightness) * shading.style.width;
+        let color = shading.color(palette, brightness, *i);
         painter.line_segment([current_pos, segment_end], Stroke::new(line_width, color));
     }
 }
 
-/// Draw half‑segments for isolated curve nodes when long jumps are hidden.
-fn draw_isolated_points(
-    painter: &egui::Painter,
-    original: &[[u32; 3]],
-    pts2d: &[egui::Pos2],
-    pts3d: &[[f32; 3]],
+/// Push half‑segments for isolated curve nodes when long jumps are hidden.
+fn isolated_point_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    palette: &Palette,
+    curve: &ProjectedCurve<'_>,
+    gradient: Option<&Gradient>,
 ) {
+    let (original, pts2d, pts3d) = (curve.original, &curve.pts2d, &curve.pts3d);
     let mut iso = Vec::new();
-    for i in 0..original.len() {
+    for i in (0..original.len()).filter(|&i| curve.visible[i]) {
         let has_adjacent_prev = i > 0 && is_adjacent_3d(&original[i - 1], &original[i]);
         let has_adjacent_next =
             i < original.len() - 1 && is_adjacent_3d(&original[i], &original[i + 1]);
@@ -676,7 +1293,13 @@ fn draw_isolated_points(
         };
         let brightness = isolated_point_brightness(*depth);
         let line_width = isolated_point_line_width(brightness);
-        let color = curve_color_opaque(brightness);
-        painter.line_segment([current_pos, segment_end], Stroke::new(line_width, color));
+        let color = gradient.map_or_else(
+            || palette.curve_opaque(brightness),
+            |g| g.color(*i, brightness),
+        );
+        shapes.push(egui::Shape::line_segment(
+            [current_pos, segment_end],
+            Stroke::new(line_width, color),
+        ));
     }
 }
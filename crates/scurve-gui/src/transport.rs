@@ -0,0 +1,86 @@
+//! Playback state for the GUI's animations.
+//!
+//! The transport owns everything the timeline controls: whether playback runs, how long it
+//! has run, the part of the curve the snake loops over and the 4D slice stepper. Snake
+//! offsets themselves stay on the curve selections, since each pane's curve has its own
+//! length.
+
+use crate::{snake::advance_snake_offset, theme};
+
+/// Part of the curve the snake loops over, as fractions of the curve length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopRegion {
+    /// Start of the region, in `0.0..1.0`.
+    pub start: f32,
+    /// End of the region, in `start..=1.0`.
+    pub end: f32,
+}
+
+impl LoopRegion {
+    /// The region between two fractions in either order, or `None` when it is empty.
+    pub fn new(a: f32, b: f32) -> Option<Self> {
+        let (start, end) = (a.min(b).clamp(0.0, 1.0), a.max(b).clamp(0.0, 1.0));
+        (end - start > f32::EPSILON).then_some(Self { start, end })
+    }
+
+    /// Start and end offsets of the region on a curve of `length` points.
+    ///
+    /// The region always covers at least one point, so short curves keep playing.
+    pub fn offsets(self, length: u32) -> (f32, f32) {
+        let length = length as f32;
+        let start = (self.start * length).floor().min(length - 1.0).max(0.0);
+        let end = (self.end * length).ceil().max(start + 1.0);
+        (start, end)
+    }
+}
+
+/// Play state, elapsed time, loop region and slice stepping.
+#[derive(Debug, Clone, Default)]
+pub struct Transport {
+    /// Whether playback is paused.
+    pub paused: bool,
+    /// Seconds of playback so far, excluding pauses.
+    pub elapsed: f32,
+    /// Region the snake loops over, or the whole curve when unset.
+    pub loop_region: Option<LoopRegion>,
+    /// Whether the 3D view steps through 4D slices automatically.
+    pub slice_playing: bool,
+    /// Time accumulated towards the next automatic slice step.
+    pub slice_time: f32,
+}
+
+impl Transport {
+    /// Pause a playing transport or resume a paused one.
+    pub fn toggle(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Move `offset` by `delta` segments on a curve of `length` points.
+    ///
+    /// Playback wraps within the loop region. An offset outside the region, e.g. after
+    /// seeking, is brought back to its start.
+    pub fn advance(&self, offset: f32, delta: f32, length: Option<u32>) -> f32 {
+        let (Some(region), Some(length)) = (self.loop_region, length.filter(|&l| l > 0)) else {
+            return advance_snake_offset(offset, delta, length);
+        };
+        let (start, end) = region.offsets(length);
+        if !(start..end).contains(&offset) {
+            return start;
+        }
+        start + (offset + delta - start).rem_euclid(end - start)
+    }
+
+    /// Accumulate `delta` seconds of slice playback and return how many steps to take.
+    pub fn slice_steps(&mut self, delta: f32) -> u32 {
+        if !self.slice_playing {
+            return 0;
+        }
+        self.slice_time += delta;
+        let mut steps = 0;
+        while self.slice_time >= theme::animation::SLICE_STEP_SECONDS {
+            self.slice_time -= theme::animation::SLICE_STEP_SECONDS;
+            steps += 1;
+        }
+        steps
+    }
+}
@@ -0,0 +1,178 @@
+//! Shaded tube geometry for the 3D pane.
+//!
+//! Tubes are drawn as screen-space impostors: each segment is a quad spanning the tube's
+//! projected diameter, shaded across its width as a lit cylinder, and each vertex gets a
+//! shaded disc so segments meet in round joints. Primitives are painted back to front, so
+//! nearer tubes cover farther ones without a depth buffer.
+
+use std::f32::consts::TAU;
+
+use egui::{Color32, Mesh, Pos2, Vec2, epaint::Vertex};
+
+use crate::theme;
+
+/// Whether the 3D curve is drawn as tubes, and how thick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TubeStyle {
+    /// Draw tubes instead of lines.
+    pub enabled: bool,
+    /// Tube radius as a fraction of the distance between neighbouring cells.
+    pub radius: f32,
+}
+
+impl Default for TubeStyle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: theme::tube::DEFAULT_RADIUS,
+        }
+    }
+}
+
+impl TubeStyle {
+    /// Bit pattern of the style for cache keys.
+    pub fn key(self) -> (bool, u32) {
+        (self.enabled, self.radius.to_bits())
+    }
+}
+
+/// One curve vertex as seen by the camera.
+#[derive(Debug, Clone, Copy)]
+pub struct TubePoint {
+    /// Screen position.
+    pub pos: Pos2,
+    /// Depth; larger values are nearer the viewer.
+    pub depth: f32,
+    /// Projected tube radius at this point, in points.
+    pub radius: f32,
+}
+
+/// A primitive queued for back-to-front painting.
+enum Piece {
+    /// Segment from point `i` to point `i + 1`.
+    Segment(usize),
+    /// Round joint at point `i`.
+    Joint(usize),
+}
+
+/// Build the tube mesh for `points`.
+///
+/// `segments` lists the segments to draw by their start index, and `joints` marks the points
+/// that get a round joint; isolated points show up as lone spheres. `color_at` gives the unlit
+/// color of each point.
+pub fn tube_mesh(
+    points: &[TubePoint],
+    segments: &[usize],
+    joints: &[bool],
+    color_at: &dyn Fn(usize) -> Color32,
+) -> Mesh {
+    let mut pieces: Vec<(f32, Piece)> = segments
+        .iter()
+        .map(|&i| {
+            (
+                (points[i].depth + points[i + 1].depth) / 2.0,
+                Piece::Segment(i),
+            )
+        })
+        .chain(
+            joints
+                .iter()
+                .enumerate()
+                .filter(|(_, joint)| **joint)
+                .map(|(i, _)| (points[i].depth, Piece::Joint(i))),
+        )
+        .collect();
+    pieces.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut mesh = Mesh::default();
+    for (_, piece) in pieces {
+        match piece {
+            Piece::Segment(i) => {
+                add_segment(&mut mesh, points[i], points[i + 1], color_at(i));
+            }
+            Piece::Joint(i) => add_joint(&mut mesh, points[i], color_at(i)),
+        }
+    }
+    mesh
+}
+
+/// Lit brightness of a surface whose normal, in view space, is `(x, y, z)`.
+///
+/// Screen `y` grows downwards; `z` points at the viewer.
+fn lighting(x: f32, y: f32, z: f32) -> f32 {
+    let [lx, ly, lz] = theme::tube::LIGHT;
+    let diffuse = (x * lx + y * ly + z * lz).max(0.0);
+    theme::tube::AMBIENT + (1.0 - theme::tube::AMBIENT) * diffuse
+}
+
+/// `color` scaled by `brightness`, keeping its alpha.
+fn shade(color: Color32, brightness: f32) -> Color32 {
+    let [r, g, b, a] = color.to_array();
+    let scale = |c: u8| (f32::from(c) * brightness).min(255.0) as u8;
+    Color32::from_rgba_premultiplied(scale(r), scale(g), scale(b), a)
+}
+
+/// Push a quad from `a` to `b` shaded across its width like a cylinder.
+fn add_segment(mesh: &mut Mesh, a: TubePoint, b: TubePoint, color: Color32) {
+    let along = b.pos - a.pos;
+    if along.length_sq() <= f32::EPSILON {
+        return;
+    }
+    let normal = along.normalized().rot90();
+    let rows = theme::tube::CROSS_SECTION_STEPS;
+    let base = mesh.vertices.len() as u32;
+    for step in 0..=rows {
+        // Position across the tube, from one edge (-1) to the other (1).
+        let s = step as f32 / rows as f32 * 2.0 - 1.0;
+        let brightness = lighting(s * normal.x, s * normal.y, (1.0 - s * s).sqrt());
+        let color = shade(color, brightness);
+        for end in [a, b] {
+            mesh.vertices.push(Vertex {
+                pos: end.pos + normal * (s * end.radius),
+                uv: Pos2::ZERO,
+                color,
+            });
+        }
+    }
+    for step in 0..rows {
+        let row = base + step * 2;
+        mesh.add_triangle(row, row + 1, row + 3);
+        mesh.add_triangle(row, row + 3, row + 2);
+    }
+}
+
+/// Push a disc at `point` shaded like the visible half of a sphere.
+fn add_joint(mesh: &mut Mesh, point: TubePoint, color: Color32) {
+    let sides = theme::tube::JOINT_SIDES;
+    let rings = theme::tube::JOINT_RINGS;
+    let center = mesh.vertices.len() as u32;
+    mesh.vertices.push(Vertex {
+        pos: point.pos,
+        uv: Pos2::ZERO,
+        color: shade(color, lighting(0.0, 0.0, 1.0)),
+    });
+    for ring in 1..=rings {
+        let r = ring as f32 / rings as f32;
+        let z = (1.0 - r * r).sqrt();
+        for side in 0..sides {
+            let dir = Vec2::angled(side as f32 / sides as f32 * TAU);
+            mesh.vertices.push(Vertex {
+                pos: point.pos + dir * (r * point.radius),
+                uv: Pos2::ZERO,
+                color: shade(color, lighting(dir.x * r, dir.y * r, z)),
+            });
+        }
+    }
+    let at = |ring: u32, side: u32| center + 1 + (ring - 1) * sides + side % sides;
+    for side in 0..sides {
+        mesh.add_triangle(center, at(1, side), at(1, side + 1));
+        for ring in 2..=rings {
+            mesh.add_triangle(at(ring - 1, side), at(ring, side), at(ring, side + 1));
+            mesh.add_triangle(
+                at(ring - 1, side),
+                at(ring, side + 1),
+                at(ring - 1, side + 1),
+            );
+        }
+    }
+}
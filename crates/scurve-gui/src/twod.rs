@@ -1,100 +1,219 @@
-use egui::{
-    self,
-    epaint::{PathShape, Stroke},
-};
+use std::ops::Range;
+
+use egui::{self, epaint::Stroke};
 
 use super::widgets;
 use crate::{
-    AppState,
+    AppState, HoverPoint, SharedSettings, bookmarks,
+    colormap::{Classifier, Gradient},
+    export::ExportFormat,
+    geometry,
+    point_cloud::{self, PointCloud},
     selection::SelectedCurve,
-    snake::{fill_snake_segments, is_adjacent_2d, snake_mask_contains, snake_membership_mask},
-    theme,
+    snake::{
+        SnakeBuffers, SnakeShading, SnakeStyle, fill_snake_segments, head_marker, is_adjacent_2d,
+        race_offset, snake_mask_contains, snake_membership_mask,
+    },
+    stroke::{LineStyle, stroke_polyline},
+    theme::{self, Palette},
 };
 
 /// Render the 2D pane, including controls and the curve canvas.
+///
+/// When comparison is enabled, `compare_curve` is drawn beside `selected_curve` with the
+/// same size and snake offset.
 pub fn show_2d_pane(
     ui: &mut egui::Ui,
     app_state: &mut AppState,
     selected_curve: &mut SelectedCurve,
+    compare_curve: &mut SelectedCurve,
     available_curves: &[&str],
-    shared_settings: &mut crate::SharedSettings,
+    shared_settings: &mut SharedSettings,
 ) {
     // Secondary control bar with lighter visual weight
-    egui::Frame::new()
-        .inner_margin(egui::Margin {
-            left: theme::control_bar::PADDING_HORIZONTAL as i8,
-            right: theme::control_bar::PADDING_HORIZONTAL as i8,
-            top: theme::control_bar::PADDING_VERTICAL as i8,
-            bottom: theme::control_bar::PADDING_VERTICAL as i8,
-        })
-        .show(ui, |ui| {
-            ui.horizontal(|ui| {
-                // Use smaller, dimmer text for control labels
-                ui.label(
-                    egui::RichText::new("Curve:")
-                        .size(theme::font_size::INFO)
-                        .color(theme::TEXT_DIM),
+    widgets::control_bar_frame().show(ui, |ui| {
+        ui.horizontal(|ui| {
+            // Use smaller, dimmer text for control labels
+            ui.label(
+                egui::RichText::new("Curve:")
+                    .size(theme::font_size::INFO)
+                    .color(theme::palette(ui.ctx()).text_dim),
+            );
+            widgets::curve_selector_combo(
+                ui,
+                &mut selected_curve.name,
+                available_curves,
+                "curve_selector",
+                &mut selected_curve.info_open,
+                2,
+                selected_curve.size,
+            );
+
+            ui.separator();
+
+            ui.label(
+                egui::RichText::new("Size:")
+                    .size(theme::font_size::INFO)
+                    .color(theme::palette(ui.ctx()).text_dim),
+            );
+            widgets::size_input(
+                ui,
+                &mut selected_curve.size,
+                &selected_curve.name,
+                2,
+                theme::size_limits::MAX_2D,
+            );
+
+            ui.separator();
+
+            widgets::compare_controls(
+                ui,
+                &mut app_state.compare_enabled,
+                compare_curve,
+                available_curves,
+                "compare_curve_selector",
+                2,
+            );
+
+            // Push pause and settings buttons to the far right
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                widgets::settings_dropdown(
+                    ui,
+                    &mut app_state.settings_dropdown_open,
+                    &mut app_state.settings_dropdown_pos,
+                    shared_settings,
+                    false,
                 );
-                widgets::curve_selector_combo(
+                ui.add_space(theme::spacing::SMALL);
+                widgets::export_menu(
                     ui,
-                    &mut selected_curve.name,
-                    available_curves,
-                    "curve_selector",
-                    &mut selected_curve.info_open,
-                    2,
-                    selected_curve.size,
+                    &mut app_state.export_request,
+                    &[ExportFormat::Png, ExportFormat::Svg],
                 );
-
-                ui.separator();
-
-                ui.label(
-                    egui::RichText::new("Size:")
-                        .size(theme::font_size::INFO)
-                        .color(theme::TEXT_DIM),
+                ui.add_space(theme::spacing::SMALL);
+                widgets::record_menu(
+                    ui,
+                    &mut app_state.record_request,
+                    &mut app_state.record_seconds,
+                    app_state.recording,
                 );
-                widgets::size_selector_2d(ui, &mut selected_curve.size, "size_selector");
-
-                // Push pause and settings buttons to the far right
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    widgets::settings_dropdown(
-                        ui,
-                        &mut app_state.settings_dropdown_open,
-                        &mut app_state.settings_dropdown_pos,
-                        shared_settings,
-                        false,
-                    );
-                    ui.add_space(theme::spacing::SMALL);
-                    widgets::pause_play_button(ui, &mut app_state.paused);
-                });
+                ui.add_space(theme::spacing::SMALL);
+                widgets::points_menu(ui, &mut app_state.points, selected_curve);
+                ui.add_space(theme::spacing::SMALL);
+                widgets::colormap_menu(ui, shared_settings);
+                ui.add_space(theme::spacing::SMALL);
+                widgets::pause_play_button(ui, &mut app_state.transport.paused);
             });
         });
+    });
+
+    if shared_settings.snake_enabled {
+        widgets::timeline(
+            ui,
+            selected_curve,
+            &mut app_state.transport,
+            shared_settings,
+        );
+    }
 
     ui.separator();
 
-    draw_2d_canvas(ui, app_state, selected_curve, shared_settings);
+    let available_rect = ui.available_rect_before_wrap();
+    let (canvas_rect, hovered) = draw_canvases(
+        ui,
+        available_rect,
+        app_state,
+        (selected_curve, compare_curve),
+        shared_settings,
+    );
+    app_state.hovered_point = hovered;
+    app_state.last_canvas_rect = Some(canvas_rect);
+
+    let response = ui.allocate_rect(available_rect, egui::Sense::click());
+    if let Some(point) = &app_state.hovered_point {
+        // Clicking a point jumps the snake there instead of waiting for the animation.
+        if response.clicked() {
+            selected_curve.snake_offset = point.position as f32;
+        }
+        response.on_hover_ui_at_pointer(|ui| widgets::point_inspector(ui, point));
+    }
 }
 
-/// Render the 2D drawing canvas and overlays.
-fn draw_2d_canvas(
-    ui: &mut egui::Ui,
+/// Draw the selected curve, and the compared curve beside it when comparison is enabled.
+///
+/// Returns the rect covering the drawn curves and the curve point under the pointer.
+fn draw_canvases(
+    ui: &egui::Ui,
+    available_rect: egui::Rect,
     app_state: &mut AppState,
-    selected_curve: &mut SelectedCurve,
-    shared_settings: &crate::SharedSettings,
-) {
-    let bg = theme::CANVAS_BACKGROUND;
-    let available_rect = ui.available_rect_before_wrap();
+    (selected_curve, compare_curve): (&mut SelectedCurve, &mut SelectedCurve),
+    shared_settings: &SharedSettings,
+) -> (egui::Rect, Option<HoverPoint>) {
+    let buffers = &mut app_state.snake_2d;
+    let mut cloud = app_state.points.cloud.as_mut();
+    if app_state.compare_enabled {
+        compare_curve.size = selected_curve.size;
+        compare_curve.snake_offset = selected_curve.snake_offset;
+        let [left, right] = widgets::split_columns(available_rect);
+        let (left, left_hover) = draw_2d_canvas(
+            ui,
+            left,
+            buffers,
+            (selected_curve, cloud.as_deref_mut()),
+            shared_settings,
+        );
+        let (right, right_hover) =
+            draw_2d_canvas(ui, right, buffers, (compare_curve, cloud), shared_settings);
+        (left.union(right), left_hover.or(right_hover))
+    } else {
+        draw_2d_canvas(
+            ui,
+            available_rect,
+            buffers,
+            (selected_curve, cloud),
+            shared_settings,
+        )
+    }
+}
+
+/// Render the 2D drawing canvas and overlays centered in `available_rect`.
+///
+/// An imported point cloud, when given, is drawn over the curve in its order.
+///
+/// Returns the square rect the curve was drawn into and the curve point under the pointer.
+pub fn draw_2d_canvas(
+    ui: &egui::Ui,
+    available_rect: egui::Rect,
+    buffers: &mut SnakeBuffers,
+    (selected_curve, cloud): (&mut SelectedCurve, Option<&mut PointCloud>),
+    shared_settings: &SharedSettings,
+) -> (egui::Rect, Option<HoverPoint>) {
+    let palette = theme::palette(ui.ctx());
+    let bg = palette.canvas_background;
     let drawing_size = (available_rect.width().min(available_rect.height())
         * theme::canvas_2d::SIZE_FRACTION)
         .max(theme::canvas_2d::MIN_SIZE);
     let drawing_rect =
         egui::Rect::from_center_size(available_rect.center(), egui::Vec2::splat(drawing_size));
-    app_state.last_canvas_rect = Some(drawing_rect);
     let painter = ui.painter_at(available_rect);
     painter.rect_filled(available_rect, 0.0, bg);
 
     let curve_size = selected_curve.size;
     let snake_offset = selected_curve.snake_offset;
-    if let Some(curve_points) = selected_curve.ensure_cached_points() {
+    let structure = structure_overlays(selected_curve, shared_settings);
+    let morph = selected_curve.advance_morph(
+        ui.ctx(),
+        ui.input(|i| i.stable_dt),
+        theme::canvas_2d::MORPH_SECONDS,
+        shared_settings.reduced_motion,
+    );
+    let morphing = morph.is_some();
+    if morphing {
+        ui.ctx().request_repaint();
+    }
+    let cloud_cells = cloud.and_then(|cloud| cloud.ordered(selected_curve));
+    let mut hovered = None;
+    if let Some((curve_points, geometry)) = selected_curve.cached_points_with_geometry(ui.ctx()) {
         let painter = ui.painter_at(drawing_rect);
         painter.rect_filled(drawing_rect, 5.0, bg);
 
@@ -102,62 +221,214 @@ fn draw_2d_canvas(
         let inner_size = drawing_size - margin * 2.0;
         let scale = inner_size / (curve_size - 1) as f32;
 
-        let screen_points = build_screen_points(curve_points, drawing_rect, scale, margin);
+        let screen_points = match &morph {
+            Some(points) => points
+                .iter()
+                .map(|&p| screen_point(p, drawing_rect, scale, margin))
+                .collect(),
+            None => build_screen_points(curve_points, drawing_rect, scale, margin),
+        };
 
-        let line_color = theme::curve_color_with_brightness(1.0, shared_settings.curve_opacity);
-        let line_width = theme::canvas_2d::LINE_WIDTH;
-
-        if shared_settings.curve_opacity > 0.0 && screen_points.len() > 1 {
-            draw_main_curve_segments(
-                &painter,
-                curve_points,
-                &screen_points,
-                line_width,
-                line_color,
-                shared_settings.show_long_jumps,
-            );
+        if screen_points.len() > 1 {
+            let build = |shapes: &mut Vec<egui::Shape>| {
+                let origin = drawing_rect.min + egui::Vec2::splat(margin);
+                curve_shapes(
+                    shapes,
+                    ui.ctx(),
+                    shared_settings,
+                    curve_points,
+                    &screen_points,
+                    (origin, scale, curve_size),
+                    structure,
+                );
+            };
+            if morphing {
+                // Morph frames never repeat, so they bypass the geometry cache.
+                let mut shapes = Vec::new();
+                build(&mut shapes);
+                painter.extend(shapes);
+            } else {
+                let key = geometry::cache_key((
+                    geometry::rect_bits(drawing_rect),
+                    shared_settings.curve_opacity.to_bits(),
+                    shared_settings.colormap,
+                    shared_settings.colormap_endpoints,
+                    (
+                        shared_settings.show_long_jumps,
+                        shared_settings.highlight_jumps,
+                    ),
+                    (structure, palette.curve, palette.jump, palette.border),
+                    shared_settings.line_style.key(),
+                ));
+                painter.add(egui::Shape::Mesh(geometry.mesh(ui.ctx(), key, build)));
+            }
         }
 
         if shared_settings.snake_enabled && curve_points.len() > 1 {
-            fill_snake_segments(
-                &mut app_state.snake_segments_2d,
+            draw_snakes(
+                &painter,
+                buffers,
+                shared_settings,
+                &palette,
+                (curve_points, &screen_points),
                 snake_offset,
-                shared_settings.snake_length,
-                curve_points.len() as u32,
             );
-            let snake_segments = &app_state.snake_segments_2d;
-
-            let snake_mask: &[bool] = if shared_settings.show_long_jumps {
-                &[]
-            } else {
-                snake_membership_mask(
-                    snake_segments,
-                    curve_points.len(),
-                    &mut app_state.snake_mask_2d,
-                )
-            };
-
-            let snake_color = theme::snake_color_with_brightness(1.0);
-            let snake_width = line_width * theme::canvas_2d::SNAKE_WIDTH_MULTIPLIER;
-            let snake_stroke = Stroke::new(snake_width, snake_color);
-
-            draw_snake_overlay(
+        }
+        bookmarks::paint_markers(&painter, &shared_settings.bookmarks, &screen_points, |_| {
+            true
+        });
+        if let Some(cells) = cloud_cells {
+            point_cloud::paint_cloud(
                 &painter,
-                curve_points,
-                &screen_points,
-                snake_segments,
-                snake_mask,
-                snake_stroke,
-                shared_settings.show_long_jumps,
+                &build_screen_points(cells, drawing_rect, scale, margin),
+                (shared_settings.colormap, shared_settings.colormap_endpoints),
+                &palette,
             );
         }
+
+        hovered = inspect_hover(ui, drawing_rect, scale, selected_curve);
+    } else if let Some(progress) = selected_curve.generating() {
+        let painter = ui.painter_at(drawing_rect);
+        painter.rect_filled(drawing_rect, 5.0, bg);
+        widgets::paint_generating(&painter, drawing_rect, progress);
+    }
+
+    (drawing_rect, hovered)
+}
+
+/// Block side of the boundary overlay and the classifier for structural coloring, when the
+/// settings ask for them and the selected curve supports them.
+fn structure_overlays(
+    selected_curve: &SelectedCurve,
+    shared_settings: &SharedSettings,
+) -> (Option<u32>, Option<Classifier>) {
+    let block_side = match shared_settings.block_level {
+        0 => None,
+        level => selected_curve.block_side(level),
+    };
+    let classifier =
+        selected_curve.classifier(shared_settings.color_by, shared_settings.color_level);
+    (block_side, classifier)
+}
+
+/// Draw the snake starting at `snake_offset`, and the race snake when racing is enabled.
+fn draw_snakes(
+    painter: &egui::Painter,
+    buffers: &mut SnakeBuffers,
+    shared_settings: &SharedSettings,
+    palette: &Palette,
+    (curve_points, screen_points): (&[[u32; 2]], &[egui::Pos2]),
+    snake_offset: f32,
+) {
+    let length = curve_points.len() as u32;
+    // The race snake goes first so the shared buffers end up holding the main snake.
+    if shared_settings.race_enabled {
+        let race_offset = race_offset(snake_offset, shared_settings.race_gap, length);
+        let rival = palette.rival_snake();
+        draw_snake(
+            painter,
+            buffers,
+            shared_settings,
+            &rival,
+            curve_points,
+            screen_points,
+            race_offset,
+        );
+    }
+    draw_snake(
+        painter,
+        buffers,
+        shared_settings,
+        palette,
+        curve_points,
+        screen_points,
+        snake_offset,
+    );
+}
+
+/// Draw one snake starting at `snake_offset` in the accent color of `palette`.
+fn draw_snake(
+    painter: &egui::Painter,
+    buffers: &mut SnakeBuffers,
+    shared_settings: &SharedSettings,
+    palette: &Palette,
+    curve_points: &[[u32; 2]],
+    screen_points: &[egui::Pos2],
+    snake_offset: f32,
+) {
+    fill_snake_segments(
+        &mut buffers.segments,
+        snake_offset,
+        shared_settings.snake_length,
+        curve_points.len() as u32,
+    );
+    let snake_segments = &buffers.segments;
+
+    let snake_mask: &[bool] = if shared_settings.show_long_jumps {
+        &[]
+    } else {
+        snake_membership_mask(snake_segments, curve_points.len(), &mut buffers.mask)
+    };
+
+    let brush = SnakeBrush::new(
+        palette,
+        shared_settings.snake_style,
+        snake_segments,
+        curve_points.len(),
+        shared_settings.line_style,
+        feather(painter.ctx()),
+    );
+
+    draw_snake_overlay(
+        painter,
+        curve_points,
+        screen_points,
+        snake_segments,
+        snake_mask,
+        &brush,
+        shared_settings.show_long_jumps,
+    );
+}
+
+/// Pick the curve point in the grid cell under the pointer and mark it on the canvas.
+fn inspect_hover(
+    ui: &egui::Ui,
+    drawing_rect: egui::Rect,
+    scale: f32,
+    selected_curve: &mut SelectedCurve,
+) -> Option<HoverPoint> {
+    let pos = ui.ctx().pointer_hover_pos()?;
+    if !drawing_rect.contains(pos) {
+        return None;
     }
+    let cell = pick_cell(pos, drawing_rect, scale, theme::canvas_2d::MARGIN)?;
+    let position = selected_curve.position_of(cell)?;
+    let screen = build_screen_points(&[cell], drawing_rect, scale, theme::canvas_2d::MARGIN)[0];
+    ui.painter_at(drawing_rect).circle_stroke(
+        screen,
+        theme::inspector::MARKER_RADIUS,
+        Stroke::new(
+            theme::inspector::MARKER_STROKE,
+            theme::palette(ui.ctx()).text_primary,
+        ),
+    );
+    selected_curve.hover_point(position)
+}
 
-    ui.allocate_rect(drawing_rect, egui::Sense::hover());
+/// Map a screen position back to the nearest grid cell; the inverse of `build_screen_points`.
+fn pick_cell(
+    pos: egui::Pos2,
+    drawing_rect: egui::Rect,
+    scale: f32,
+    margin: f32,
+) -> Option<[u32; 2]> {
+    let x = ((pos.x - drawing_rect.min.x - margin) / scale).round();
+    let y = ((pos.y - drawing_rect.min.y - margin) / scale).round();
+    (x >= 0.0 && y >= 0.0).then_some([x as u32, y as u32])
 }
 
 /// Convert integer curve points to screen positions within the drawing rect.
-fn build_screen_points(
+pub fn build_screen_points(
     curve_points: &[[u32; 2]],
     drawing_rect: egui::Rect,
     scale: f32,
@@ -165,74 +436,240 @@ fn build_screen_points(
 ) -> Vec<egui::Pos2> {
     curve_points
         .iter()
-        .map(|p| egui::Pos2 {
-            x: drawing_rect.min.x + margin + p[0] as f32 * scale,
-            y: drawing_rect.min.y + margin + p[1] as f32 * scale,
-        })
+        .map(|p| screen_point(p.map(|c| c as f32), drawing_rect, scale, margin))
         .collect()
 }
 
-/// Draw the main curve segments and half‑segments for isolated nodes.
-fn draw_main_curve_segments(
-    painter: &egui::Painter,
+/// Screen position of a possibly fractional grid point within the drawing rect.
+fn screen_point(p: [f32; 2], drawing_rect: egui::Rect, scale: f32, margin: f32) -> egui::Pos2 {
+    drawing_rect.min + egui::Vec2::splat(margin) + egui::vec2(p[0], p[1]) * scale
+}
+
+/// Push the block boundaries, main curve and highlighted jumps.
+///
+/// `grid` holds the screen position of cell `(0, 0)`, the distance between cells and the
+/// grid size; boundaries are drawn between blocks of `block_side` cells when it is set, and
+/// points are colored by the classes of `classifier` when it is set.
+fn curve_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    ctx: &egui::Context,
+    shared_settings: &SharedSettings,
+    curve_points: &[[u32; 2]],
+    screen_points: &[egui::Pos2],
+    (origin, scale, curve_size): (egui::Pos2, f32, u32),
+    (block_side, classifier): (Option<u32>, Option<Classifier>),
+) {
+    let palette = theme::palette(ctx);
+    if let Some(side) = block_side {
+        block_boundary_shapes(shapes, origin, scale, curve_size, side, palette.border);
+    }
+    let highlight_jumps = shared_settings.highlight_jumps;
+    if shared_settings.curve_opacity > 0.0 {
+        main_curve_shapes(
+            shapes,
+            curve_points,
+            screen_points,
+            &point_colors(shared_settings, &palette, curve_points, classifier),
+            // Highlighted jumps replace the solid ones.
+            shared_settings.show_long_jumps && !highlight_jumps,
+            shared_settings.line_style,
+            feather(ctx),
+        );
+    }
+    if highlight_jumps {
+        jump_shapes(
+            shapes,
+            curve_points,
+            screen_points,
+            Stroke::new(shared_settings.line_style.width, palette.jump),
+        );
+    }
+}
+
+/// Width in points of one physical pixel, over which stroke edges fade out.
+fn feather(ctx: &egui::Context) -> f32 {
+    1.0 / ctx.pixels_per_point()
+}
+
+/// Color of each curve point: the palette's curve color, or the colormap's when one is set
+/// or the points are grouped by `classifier`.
+fn point_colors(
+    settings: &SharedSettings,
+    palette: &Palette,
+    curve_points: &[[u32; 2]],
+    classifier: Option<Classifier>,
+) -> impl Fn(usize) -> egui::Color32 {
+    let gradient = Gradient::for_curve(
+        settings.colormap,
+        settings.colormap_endpoints,
+        curve_points,
+        classifier,
+        palette.colorblind_safe,
+    );
+    let line_color = palette.curve_with_brightness(1.0, settings.curve_opacity);
+    let opacity = settings.curve_opacity;
+    move |i| {
+        gradient
+            .as_ref()
+            .map_or(line_color, |g| g.color(i, 1.0).gamma_multiply(opacity))
+    }
+}
+
+/// Push the main curve as anti-aliased strokes, with half‑segments for isolated nodes.
+///
+/// Each point takes the color `color_at` gives its index.
+fn main_curve_shapes(
+    shapes: &mut Vec<egui::Shape>,
     curve_points: &[[u32; 2]],
     screen_points: &[egui::Pos2],
-    line_width: f32,
-    line_color: egui::Color32,
+    color_at: &dyn Fn(usize) -> egui::Color32,
     show_long_jumps: bool,
+    line: LineStyle,
+    feather: f32,
 ) {
-    if show_long_jumps {
-        painter.add(PathShape::line(
-            screen_points.to_vec(),
-            Stroke::new(line_width, line_color),
+    let mut mesh = egui::Mesh::default();
+    for run in connected_runs(curve_points, show_long_jumps) {
+        let start = run.start;
+        if run.len() > 1 {
+            let points = &screen_points[run];
+            stroke_polyline(&mut mesh, points, |k| color_at(start + k), line, feather);
+        } else if let Some(half) = half_segment(screen_points, start) {
+            stroke_polyline(&mut mesh, &half, |_| color_at(start), line, feather);
+        }
+    }
+    shapes.push(egui::Shape::Mesh(mesh.into()));
+}
+
+/// Split the curve into maximal runs of points joined by drawn segments.
+///
+/// Runs break at long jumps unless `show_long_jumps` is set; isolated points form runs of
+/// one.
+fn connected_runs(curve_points: &[[u32; 2]], show_long_jumps: bool) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for i in 1..=curve_points.len() {
+        let joined = i < curve_points.len()
+            && (show_long_jumps || is_adjacent_2d(&curve_points[i - 1], &curve_points[i]));
+        if !joined {
+            runs.push(start..i);
+            start = i;
+        }
+    }
+    runs
+}
+
+/// Half of the segment leaving point `i`, or entering it for the last point, so isolated
+/// points stay visible.
+fn half_segment(screen_points: &[egui::Pos2], i: usize) -> Option<[egui::Pos2; 2]> {
+    let current = screen_points[i];
+    let end = if let Some(&next) = screen_points.get(i + 1) {
+        current + (next - current) * 0.5
+    } else if i > 0 {
+        current + (current - screen_points[i - 1]) * 0.5
+    } else {
+        return None;
+    };
+    Some([current, end])
+}
+
+/// Push the internal boundaries between blocks of `side` cells.
+///
+/// `origin` is the screen position of cell `(0, 0)` and `scale` the distance between cells;
+/// boundaries fall halfway between neighbouring cells.
+fn block_boundary_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    origin: egui::Pos2,
+    scale: f32,
+    curve_size: u32,
+    side: u32,
+    color: egui::Color32,
+) {
+    let stroke = Stroke::new(theme::blocks::LINE_WIDTH, color);
+    let at = |cell: f32| (cell - 0.5) * scale;
+    let (start, end) = (at(0.0), at(curve_size as f32));
+    for boundary in (side..curve_size).step_by(side as usize) {
+        let offset = at(boundary as f32);
+        shapes.push(egui::Shape::line_segment(
+            [
+                origin + egui::vec2(offset, start),
+                origin + egui::vec2(offset, end),
+            ],
+            stroke,
+        ));
+        shapes.push(egui::Shape::line_segment(
+            [
+                origin + egui::vec2(start, offset),
+                origin + egui::vec2(end, offset),
+            ],
+            stroke,
         ));
-        return;
     }
+}
 
-    let mut run: Vec<egui::Pos2> = Vec::new();
-    let stroke = Stroke::new(line_width, line_color);
+/// Push dashed lines with `stroke` for consecutive points that are not grid neighbours.
+fn jump_shapes(
+    shapes: &mut Vec<egui::Shape>,
+    curve_points: &[[u32; 2]],
+    screen_points: &[egui::Pos2],
+    stroke: Stroke,
+) {
     for i in 0..curve_points.len() - 1 {
-        if is_adjacent_2d(&curve_points[i], &curve_points[i + 1]) {
-            if run.is_empty() {
-                run.push(screen_points[i]);
-            }
-            run.push(screen_points[i + 1]);
-        } else if !run.is_empty() {
-            if run.len() >= 2 {
-                painter.add(PathShape::line(run.clone(), stroke));
-            }
-            run.clear();
+        if !is_adjacent_2d(&curve_points[i], &curve_points[i + 1]) {
+            shapes.extend(egui::Shape::dashed_line(
+                &[screen_points[i], screen_points[i + 1]],
+                stroke,
+                theme::jumps::DASH_LENGTH,
+                theme::jumps::GAP_LENGTH,
+            ));
         }
     }
-    if !run.is_empty() && run.len() >= 2 {
-        painter.add(PathShape::line(run, stroke));
+}
+
+/// Stroke settings for one snake overlay.
+struct SnakeBrush<'a> {
+    /// Palette the snake colors are drawn from.
+    palette: &'a Palette,
+    /// Per-point color grading and appearance.
+    shading: SnakeShading,
+    /// Stroke width and joins.
+    line: LineStyle,
+    /// Edge fade width in points.
+    feather: f32,
+}
+
+impl<'a> SnakeBrush<'a> {
+    /// Brush for a snake covering `segments` on a curve of `total` points drawn with `line`.
+    fn new(
+        palette: &'a Palette,
+        style: SnakeStyle,
+        segments: &[usize],
+        total: usize,
+        line: LineStyle,
+        feather: f32,
+    ) -> Self {
+        Self {
+            palette,
+            shading: SnakeShading::new(style, segments, total),
+            line: line.scaled(theme::canvas_2d::SNAKE_WIDTH_MULTIPLIER * style.width),
+            feather,
+        }
     }
 
-    for i in 0..curve_points.len() {
-        let has_adjacent_prev = i > 0 && is_adjacent_2d(&curve_points[i - 1], &curve_points[i]);
-        let has_adjacent_next =
-            i < curve_points.len() - 1 && is_adjacent_2d(&curve_points[i], &curve_points[i + 1]);
-        if !has_adjacent_prev && !has_adjacent_next {
-            let current_pos = screen_points[i];
-            let segment_end = if i == curve_points.len() - 1 && i > 0 {
-                let prev_pos = screen_points[i - 1];
-                egui::Pos2 {
-                    x: current_pos.x + (current_pos.x - prev_pos.x) * 0.5,
-                    y: current_pos.y + (current_pos.y - prev_pos.y) * 0.5,
-                }
-            } else if i < curve_points.len() - 1 {
-                let next_pos = screen_points[i + 1];
-                egui::Pos2 {
-                    x: current_pos.x + (next_pos.x - current_pos.x) * 0.5,
-                    y: current_pos.y + (next_pos.y - current_pos.y) * 0.5,
-                }
-            } else {
-                continue;
-            };
-            painter.line_segment(
-                [current_pos, segment_end],
-                Stroke::new(line_width, line_color),
-            );
+    /// Stroke `points` in a single color.
+    fn stroke(&self, painter: &egui::Painter, points: &[egui::Pos2], color: egui::Color32) {
+        let mut mesh = egui::Mesh::default();
+        stroke_polyline(&mut mesh, points, |_| color, self.line, self.feather);
+        painter.add(mesh);
+    }
+
+    /// Stroke a run of curve indices, split into uniformly colored pieces.
+    fn stroke_run(&self, painter: &egui::Painter, screen_points: &[egui::Pos2], run: &[usize]) {
+        for piece in self.shading.split_run(run) {
+            if piece.len() >= 2 {
+                let color = self.shading.color(self.palette, 1.0, piece[0]);
+                let points: Vec<egui::Pos2> = piece.iter().map(|&i| screen_points[i]).collect();
+                self.stroke(painter, &points, color);
+            }
         }
     }
 }
@@ -244,23 +681,21 @@ fn draw_snake_overlay(
     screen_points: &[egui::Pos2],
     snake_segments: &[usize],
     snake_mask: &[bool],
-    snake_stroke: Stroke,
+    brush: &SnakeBrush<'_>,
     show_long_jumps: bool,
 ) {
     if show_long_jumps {
-        let mut snake_path = Vec::new();
-        for &i in snake_segments {
-            if i < screen_points.len() {
-                snake_path.push(screen_points[i]);
-            }
-        }
-        if snake_path.len() >= 2 {
-            painter.add(PathShape::line(snake_path, snake_stroke));
-        }
+        let run: Vec<usize> = snake_segments
+            .iter()
+            .copied()
+            .filter(|&i| i < screen_points.len())
+            .collect();
+        brush.stroke_run(painter, screen_points, &run);
+        draw_snake_head(painter, screen_points, brush);
         return;
     }
 
-    let mut current_run: Vec<egui::Pos2> = Vec::new();
+    let mut current_run: Vec<usize> = Vec::new();
     for &i in snake_segments {
         if i >= curve_points.len() {
             continue;
@@ -274,20 +709,16 @@ fn draw_snake_overlay(
         if !has_prev && !has_next {
             // Isolated point handled below
         } else if !has_prev {
-            current_run = vec![screen_points[i]];
+            current_run = vec![i];
         } else if has_prev && !current_run.is_empty() {
-            current_run.push(screen_points[i]);
+            current_run.push(i);
             if !has_next {
-                if current_run.len() >= 2 {
-                    painter.add(PathShape::line(current_run.clone(), snake_stroke));
-                }
+                brush.stroke_run(painter, screen_points, &current_run);
                 current_run.clear();
             }
         }
     }
-    if current_run.len() >= 2 {
-        painter.add(PathShape::line(current_run, snake_stroke));
-    }
+    brush.stroke_run(painter, screen_points, &current_run);
 
     for &i in snake_segments {
         if i >= curve_points.len() {
@@ -299,24 +730,24 @@ fn draw_snake_overlay(
         let has_next = i < curve_points.len() - 1
             && snake_mask_contains(snake_mask, i + 1)
             && is_adjacent_2d(&curve_points[i], &curve_points[i + 1]);
-        if !has_prev && !has_next {
-            let current_pos = screen_points[i];
-            let segment_end = if i == curve_points.len() - 1 && i > 0 {
-                let prev_pos = screen_points[i - 1];
-                egui::Pos2 {
-                    x: current_pos.x + (current_pos.x - prev_pos.x) * 0.5,
-                    y: current_pos.y + (current_pos.y - prev_pos.y) * 0.5,
-                }
-            } else if i < curve_points.len() - 1 {
-                let next_pos = screen_points[i + 1];
-                egui::Pos2 {
-                    x: current_pos.x + (next_pos.x - current_pos.x) * 0.5,
-                    y: current_pos.y + (next_pos.y - current_pos.y) * 0.5,
-                }
-            } else {
-                continue;
-            };
-            painter.line_segment([current_pos, segment_end], snake_stroke);
+        if !has_prev
+            && !has_next
+            && let Some(half) = half_segment(screen_points, i)
+        {
+            brush.stroke(painter, &half, brush.shading.color(brush.palette, 1.0, i));
         }
     }
+    draw_snake_head(painter, screen_points, brush);
+}
+
+/// Mark the snake head when the style asks for it.
+fn draw_snake_head(painter: &egui::Painter, screen_points: &[egui::Pos2], brush: &SnakeBrush<'_>) {
+    if brush.shading.style.head_marker
+        && let Some(&pos) = brush
+            .shading
+            .head()
+            .and_then(|head| screen_points.get(head))
+    {
+        painter.add(head_marker(brush.palette, pos, brush.line.width, 1.0));
+    }
 }
@@ -1,5 +1,9 @@
 //! Web entrypoint and glue for the spacecurve GUI compiled to WebAssembly.
 #[cfg(target_arch = "wasm32")]
+use eframe::WebGlContextOption;
+#[cfg(target_arch = "wasm32")]
+use scurve_gui::renderer::{self, Backend, RendererStatus};
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsCast;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -15,7 +19,14 @@ pub async fn run() {
     let dev_mode = query_flag("dev");
     let include_experimental = dev_mode || query_flag("experimental");
 
-    let web_options = eframe::WebOptions::default();
+    let renderer = RendererStatus::new(
+        renderer::detect(),
+        query_param("renderer")
+            .and_then(|key| Backend::from_key(&key))
+            .or_else(renderer::stored_preference)
+            .unwrap_or_default(),
+    );
+
     // Obtain the canvas element by id from the DOM.
     let document = window()
         .and_then(|w| w.document())
@@ -26,54 +37,111 @@ pub async fn run() {
         .dyn_into::<HtmlCanvasElement>()
         .expect("element is not a canvas");
 
+    // Without any WebGL context eframe can't start; explain instead of leaving a blank canvas.
+    let Some(active) = renderer.active else {
+        show_failure(&canvas, &renderer.notice().unwrap_or_default());
+        return;
+    };
+    let web_options = eframe::WebOptions {
+        webgl_context_option: match active {
+            Backend::WebGl1 => WebGlContextOption::WebGl1,
+            _ => WebGlContextOption::WebGl2,
+        },
+        ..Default::default()
+    };
+
     let gui_options = scurve_gui::GuiOptions {
         include_experimental_curves: include_experimental,
         show_dev_overlay: dev_mode,
+        initial_link: url_hash(),
+        reduced_motion: prefers_reduced_motion(),
+        renderer: Some(renderer),
         ..Default::default()
     };
 
-    eframe::WebRunner::new()
+    let started = eframe::WebRunner::new()
         .start(
-            canvas,
+            canvas.clone(),
             web_options,
             Box::new(move |cc| {
                 Ok(Box::new(scurve_gui::ScurveApp::with_options(
                     cc,
-                    gui_options.clone(),
+                    gui_options,
                 )))
             }),
         )
-        .await
-        .expect("failed to start eframe web app");
+        .await;
+    if let Err(err) = started {
+        let reason = err.as_string().unwrap_or_else(|| format!("{err:?}"));
+        show_failure(
+            &canvas,
+            &format!(
+                "The {} renderer failed to start: {reason}. Browser supports: {}. \
+                 Try another renderer with ?renderer=webgl1 or ?renderer=webgl2.",
+                active.label(),
+                renderer.capabilities.summary()
+            ),
+        );
+    }
 }
 
+/// Hide `canvas` and show `message` in its place.
 #[cfg(target_arch = "wasm32")]
-fn query_flag(param: &str) -> bool {
-    let search = window()
-        .map(|w| w.location())
-        .and_then(|loc| loc.search().ok())
-        .unwrap_or_default();
+fn show_failure(canvas: &HtmlCanvasElement, message: &str) {
+    canvas.set_attribute("hidden", "").ok();
+    let Some(notice) = window()
+        .and_then(|w| w.document())
+        .and_then(|document| document.create_element("p").ok())
+    else {
+        return;
+    };
+    notice.set_text_content(Some(message));
+    canvas.after_with_node_1(&notice).ok();
+}
 
-    if search.is_empty() {
-        return false;
-    }
+/// Return the page's URL hash without the leading `#`, if it is non-empty.
+#[cfg(target_arch = "wasm32")]
+fn url_hash() -> Option<String> {
+    let hash = window()?.location().hash().ok()?;
+    let hash = hash.trim_start_matches('#');
+    (!hash.is_empty()).then(|| hash.to_string())
+}
 
-    let Ok(params) = UrlSearchParams::new_with_str(&search) else {
-        return false;
-    };
+/// Whether the browser asks pages to minimise non-essential motion.
+#[cfg(target_arch = "wasm32")]
+fn prefers_reduced_motion() -> bool {
+    window()
+        .and_then(|w| {
+            w.match_media("(prefers-reduced-motion: reduce)")
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|query| query.matches())
+}
 
+/// The value of query parameter `param`, which is empty when it is set without a value.
+#[cfg(target_arch = "wasm32")]
+fn query_param(param: &str) -> Option<String> {
+    let search = window()?.location().search().ok()?;
+    if search.is_empty() {
+        return None;
+    }
+    let params = UrlSearchParams::new_with_str(&search).ok()?;
     if !params.has(param) {
-        return false;
+        return None;
     }
+    Some(params.get(param).unwrap_or_default())
+}
 
-    match params.get(param).as_deref() {
-        None => true,
-        Some(v) if v.is_empty() => true,
-        Some(v) => matches!(
+/// Whether the query string sets `param` to a truthy value (or sets it without a value).
+#[cfg(target_arch = "wasm32")]
+fn query_flag(param: &str) -> bool {
+    query_param(param).is_some_and(|v| {
+        matches!(
             v.to_ascii_lowercase().as_str(),
-            "1" | "true" | "yes" | "y" | "on"
-        ),
-    }
+            "" | "1" | "true" | "yes" | "y" | "on"
+        )
+    })
 }
 
 // Provide a no-op main for non-wasm targets so the bin compiles in workspace builds
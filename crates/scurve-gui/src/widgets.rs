@@ -1,21 +1,89 @@
+use std::{f32::consts::TAU, ops::RangeInclusive, time::Duration};
+
 use egui::{
     self, Response, Slider,
     epaint::{Shadow, Stroke},
 };
-use spacecurve::curve_from_name;
+use spacecurve::{
+    curve_from_name,
+    registry::{self, CurveFilter},
+};
+
+use crate::{
+    HoverPoint,
+    bookmarks::Bookmarks,
+    colormap::{ColorBy, Colormap},
+    export::ExportFormat,
+    point_cloud::{PointImport, path_length},
+    record::RecordFormat,
+    selection::{CurveSelection, SelectedCurve},
+    state::{AxisLock, Camera, CameraPreset, ClipPlane, DragRotation, Guides},
+    stroke::{LineJoin, LineStyle},
+    theme::{self, PaletteName, ThemeSettings},
+    transport::{LoopRegion, Transport},
+    tube::TubeStyle,
+};
+
+/// Name an icon-only button for screen readers and show the same name as its tooltip.
+pub fn icon_label(response: Response, label: &str) -> Response {
+    let enabled = response.enabled();
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, enabled, label));
+    response.on_hover_text(label)
+}
+
+/// A short confirmation shown at the bottom of the window until `until`.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    /// Message text.
+    pub text: String,
+    /// Input time, in seconds, at which the toast disappears.
+    pub until: f64,
+}
+
+impl Toast {
+    /// A toast showing `text` for the default duration from now.
+    pub fn new(ctx: &egui::Context, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            until: ctx.input(|i| i.time) + theme::toast::SECONDS,
+        }
+    }
+}
 
-use crate::theme;
+/// Show `toast` above everything else, clearing it once it has expired.
+pub fn show_toast(ctx: &egui::Context, toast: &mut Option<Toast>) {
+    let Some(current) = toast else {
+        return;
+    };
+    let remaining = current.until - ctx.input(|i| i.time);
+    if remaining <= 0.0 {
+        *toast = None;
+        return;
+    }
+    egui::Area::new(egui::Id::new("toast"))
+        .anchor(
+            egui::Align2::CENTER_BOTTOM,
+            egui::vec2(0.0, -theme::toast::BOTTOM_MARGIN),
+        )
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| ui.label(&current.text));
+        });
+    ctx.request_repaint_after(Duration::from_secs_f64(remaining));
+}
 
 /// Add a slider with themed rail and fill colors for better visibility.
 pub fn themed_slider(ui: &mut egui::Ui, slider: Slider<'_>) -> Response {
+    let palette = theme::palette(ui.ctx());
     // Override widget visuals for the slider
     let old_noninteractive_bg = ui.visuals().widgets.noninteractive.bg_fill;
     let old_inactive_bg = ui.visuals().widgets.inactive.bg_fill;
     let old_inactive_fg = ui.visuals().widgets.inactive.fg_stroke.color;
 
-    ui.visuals_mut().widgets.noninteractive.bg_fill = theme::SLIDER_RAIL;
-    ui.visuals_mut().widgets.inactive.bg_fill = theme::SLIDER_RAIL;
-    ui.visuals_mut().widgets.inactive.fg_stroke.color = theme::SLIDER_FILL;
+    ui.visuals_mut().widgets.noninteractive.bg_fill = palette.slider_rail;
+    ui.visuals_mut().widgets.inactive.bg_fill = palette.slider_rail;
+    ui.visuals_mut().widgets.inactive.fg_stroke.color = palette.slider_fill;
 
     let response = ui.add(slider);
 
@@ -29,15 +97,16 @@ pub fn themed_slider(ui: &mut egui::Ui, slider: Slider<'_>) -> Response {
 
 /// Checkbox with a distinct neon-backed card for better contrast.
 pub fn neon_checkbox(ui: &mut egui::Ui, checked: &mut bool, label: &str) -> Response {
+    let palette = theme::palette(ui.ctx());
     let fill = if *checked {
-        theme::TOGGLE_BG_ACTIVE
+        palette.toggle_bg_active
     } else {
-        theme::TOGGLE_BG
+        palette.toggle_bg
     };
 
     egui::Frame::new()
         .fill(fill)
-        .stroke(Stroke::new(1.0, theme::BORDER))
+        .stroke(Stroke::new(1.0, palette.border))
         .inner_margin(egui::Margin::symmetric(8, 6))
         .corner_radius(egui::CornerRadius::same(3))
         .show(ui, |ui| ui.checkbox(checked, label))
@@ -45,15 +114,42 @@ pub fn neon_checkbox(ui: &mut egui::Ui, checked: &mut bool, label: &str) -> Resp
 }
 
 /// Minimal heading used inside settings sections.
-fn section_header(ui: &mut egui::Ui, title: &str) {
+pub fn section_header(ui: &mut egui::Ui, title: &str) {
     ui.label(
         egui::RichText::new(title)
             .strong()
-            .color(theme::TEXT_HEADING)
+            .color(theme::palette(ui.ctx()).text_heading)
             .size(theme::font_size::LABEL),
     );
 }
 
+/// Spinner and percentage painted over `rect` while a curve's points are generated.
+pub fn paint_generating(painter: &egui::Painter, rect: egui::Rect, progress: f32) {
+    let palette = theme::palette(painter.ctx());
+    let center = rect.center();
+    let radius = theme::worker::SPINNER_RADIUS;
+    let start = painter.ctx().input(|i| i.time) as f32 * theme::worker::SPINNER_SPEED;
+    let segments = theme::worker::SPINNER_SEGMENTS;
+    let arc = (0..=segments)
+        .map(|i| {
+            let angle = start + i as f32 / segments as f32 * TAU * 0.75;
+            center + radius * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        arc,
+        Stroke::new(theme::worker::SPINNER_WIDTH, palette.accent),
+    ));
+    painter.text(
+        center + egui::vec2(0.0, radius + theme::worker::LABEL_GAP),
+        egui::Align2::CENTER_TOP,
+        format!("Generating… {:.0}%", progress * 100.0),
+        egui::FontId::proportional(theme::font_size::INFO),
+        palette.text_dim,
+    );
+    painter.ctx().request_repaint();
+}
+
 /// Slider row with aligned label and themed slider control.
 fn slider_row(ui: &mut egui::Ui, label: &str, slider: Slider<'_>) -> Response {
     const LABEL_WIDTH: f32 = 74.0;
@@ -63,7 +159,7 @@ fn slider_row(ui: &mut egui::Ui, label: &str, slider: Slider<'_>) -> Response {
             [LABEL_WIDTH, 0.0],
             egui::Label::new(
                 egui::RichText::new(label)
-                    .color(theme::TEXT_BODY)
+                    .color(theme::palette(ui.ctx()).text_body)
                     .size(theme::font_size::LABEL),
             ),
         );
@@ -88,7 +184,7 @@ fn slider_row_with_value(
             [LABEL_WIDTH, 0.0],
             egui::Label::new(
                 egui::RichText::new(label)
-                    .color(theme::TEXT_BODY)
+                    .color(theme::palette(ui.ctx()).text_body)
                     .size(theme::font_size::LABEL),
             ),
         );
@@ -102,7 +198,7 @@ fn slider_row_with_value(
             egui::Label::new(
                 egui::RichText::new(value.into())
                     .monospace()
-                    .color(theme::TEXT_SECONDARY),
+                    .color(theme::palette(ui.ctx()).text_secondary),
             ),
         );
 
@@ -150,7 +246,7 @@ pub fn curve_selector_combo(
     let combo_response = egui::ComboBox::from_id_salt(id_salt)
         .selected_text(&*curve_name)
         .show_ui(ui, |ui| {
-            for &name in available_curves {
+            for name in curves_for_dimension(available_curves, dim) {
                 if ui
                     .selectable_value(curve_name, name.to_string(), name)
                     .clicked()
@@ -161,14 +257,17 @@ pub fn curve_selector_combo(
         });
 
     // Info button with better styling
-    let info_button = ui.add(
-        egui::Button::new("ℹ")
-            .min_size(egui::vec2(20.0, 20.0))
-            .fill(if *info_open {
-                ui.visuals().widgets.hovered.bg_fill
-            } else {
-                egui::Color32::TRANSPARENT
-            }),
+    let info_button = icon_label(
+        ui.add(
+            egui::Button::new("ℹ")
+                .min_size(egui::vec2(20.0, 20.0))
+                .fill(if *info_open {
+                    ui.visuals().widgets.hovered.bg_fill
+                } else {
+                    egui::Color32::TRANSPARENT
+                }),
+        ),
+        "Curve info",
     );
     if info_button.clicked() {
         *info_open = !*info_open;
@@ -191,6 +290,20 @@ pub fn curve_selector_combo(
     }
 }
 
+/// The curves of `available_curves` that can be built with `dim` axes, in order.
+fn curves_for_dimension<'a>(available_curves: &[&'a str], dim: u32) -> Vec<&'a str> {
+    let fits = registry::filter(CurveFilter {
+        dimension: Some(dim),
+        include_experimental: true,
+        ..CurveFilter::default()
+    });
+    available_curves
+        .iter()
+        .copied()
+        .filter(|name| fits.iter().any(|entry| entry.key == *name))
+        .collect()
+}
+
 /// Arguments for the curve info pane helper.
 struct InfoPaneArgs<'a> {
     /// Unique salt used for UI ids tied to this selector.
@@ -199,7 +312,7 @@ struct InfoPaneArgs<'a> {
     info_open: &'a mut bool,
     /// Currently selected curve name.
     curve_name: &'a str,
-    /// Dimensionality of the curve (2 or 3).
+    /// Dimensionality of the curve (2, 3, or 4 for sliced views).
     dim: u32,
     /// Grid size used when querying pattern info.
     size: u32,
@@ -283,14 +396,12 @@ fn render_info_popup_contents(
         ui.horizontal(|ui| {
             ui.label(egui::RichText::new(curve.name()).heading().strong());
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                if ui
-                    .add(
-                        egui::Button::new(egui::RichText::new("×").size(theme::font_size::LABEL))
-                            .fill(egui::Color32::TRANSPARENT)
-                            .frame(false),
-                    )
-                    .clicked()
-                {
+                let close = ui.add(
+                    egui::Button::new(egui::RichText::new("×").size(theme::font_size::LABEL))
+                        .fill(egui::Color32::TRANSPARENT)
+                        .frame(false),
+                );
+                if icon_label(close, "Close curve info").clicked() {
                     *info_open = false;
                 }
             });
@@ -317,14 +428,12 @@ fn render_info_popup_contents(
         ui.horizontal(|ui| {
             ui.label(egui::RichText::new("Curve Info").heading().strong());
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                if ui
-                    .add(
-                        egui::Button::new(egui::RichText::new("×").size(theme::font_size::LABEL))
-                            .fill(egui::Color32::TRANSPARENT)
-                            .frame(false),
-                    )
-                    .clicked()
-                {
+                let close = ui.add(
+                    egui::Button::new(egui::RichText::new("×").size(theme::font_size::LABEL))
+                        .fill(egui::Color32::TRANSPARENT)
+                        .frame(false),
+                );
+                if icon_label(close, "Close curve info").clicked() {
                     *info_open = false;
                 }
             });
@@ -340,49 +449,436 @@ fn render_info_popup_contents(
     }
 }
 
-/// Common size selector widget for 2D curves
-pub fn size_selector_2d(ui: &mut egui::Ui, size: &mut u32, id_salt: &str) {
-    egui::ComboBox::from_id_salt(id_salt)
-        .selected_text(format!("{size}×{size}"))
-        .show_ui(ui, |ui| {
-            for &s in &[4, 8, 16, 32, 64, 128] {
-                ui.selectable_value(size, s, format!("{s}×{s}"));
+/// Free-form side length input, validated live against the curve registry.
+///
+/// When the curve rejects the size, the error is shown inline together with buttons for the
+/// nearest valid sizes below and above the requested one.
+pub fn size_input(ui: &mut egui::Ui, size: &mut u32, curve_name: &str, dimension: u32, max: u32) {
+    ui.add(egui::DragValue::new(size).range(theme::size_limits::MIN..=max));
+
+    let Err(err) = registry::validate(curve_name, dimension, *size) else {
+        return;
+    };
+    ui.label(
+        egui::RichText::new(err.to_string())
+            .size(theme::font_size::INFO)
+            .color(theme::palette(ui.ctx()).text_error),
+    );
+    let suggestions = nearest_valid_sizes(curve_name, dimension, *size, max);
+    if suggestions.iter().all(Option::is_none) {
+        ui.label(
+            egui::RichText::new(format!("no valid size in {dimension}D"))
+                .size(theme::font_size::INFO)
+                .color(theme::palette(ui.ctx()).text_dim),
+        );
+    }
+    for suggestion in suggestions.into_iter().flatten() {
+        if ui.small_button(format!("→ {suggestion}")).clicked() {
+            *size = suggestion;
+        }
+    }
+}
+
+/// Closest sizes below and above `size` that the registry accepts for this curve.
+fn nearest_valid_sizes(curve_name: &str, dimension: u32, size: u32, max: u32) -> [Option<u32>; 2] {
+    let sizes = registry::valid_sizes(curve_name, dimension, max).unwrap_or_default();
+    [
+        sizes
+            .iter()
+            .rev()
+            .find(|&&s| (theme::size_limits::MIN..size).contains(&s))
+            .copied(),
+        sizes.iter().find(|&&s| s > size).copied(),
+    ]
+}
+
+/// Dimension input for the 3D pane; choosing four dimensions views the curve as 3D slices.
+pub fn slice_controls(ui: &mut egui::Ui, slice: &mut Option<u32>, size: u32, animating: &mut bool) {
+    let mut dimension = 3 + u32::from(slice.is_some());
+    ui.label(
+        egui::RichText::new("Dims:")
+            .size(theme::font_size::INFO)
+            .color(theme::palette(ui.ctx()).text_dim),
+    );
+    if ui
+        .add(egui::DragValue::new(&mut dimension).range(3..=4))
+        .changed()
+    {
+        *slice = (dimension == 4).then_some(0);
+    }
+
+    let Some(w) = slice.as_mut() else {
+        *animating = false;
+        return;
+    };
+    let max = size.saturating_sub(1);
+    *w = (*w).min(max);
+    ui.add(egui::Slider::new(w, 0..=max).text("w"));
+    ui.toggle_value(animating, "Sweep");
+}
+
+/// Toggle for side-by-side comparison plus the selector for the second curve.
+pub fn compare_controls<const D: usize>(
+    ui: &mut egui::Ui,
+    enabled: &mut bool,
+    compare_curve: &mut CurveSelection<D>,
+    available_curves: &[&str],
+    id_salt: &str,
+    dim: u32,
+) {
+    ui.toggle_value(enabled, "Compare");
+    if !*enabled {
+        return;
+    }
+    ui.label(
+        egui::RichText::new("vs")
+            .size(theme::font_size::INFO)
+            .color(theme::palette(ui.ctx()).text_dim),
+    );
+    curve_selector_combo(
+        ui,
+        &mut compare_curve.name,
+        available_curves,
+        id_salt,
+        &mut compare_curve.info_open,
+        dim,
+        compare_curve.size,
+    );
+}
+
+/// Padded frame shared by the pane control bars.
+pub fn control_bar_frame() -> egui::Frame {
+    egui::Frame::new().inner_margin(egui::Margin {
+        left: theme::control_bar::PADDING_HORIZONTAL as i8,
+        right: theme::control_bar::PADDING_HORIZONTAL as i8,
+        top: theme::control_bar::PADDING_VERTICAL as i8,
+        bottom: theme::control_bar::PADDING_VERTICAL as i8,
+    })
+}
+
+/// Timeline of the snake offset over the whole curve, with stepping, speed and loop controls.
+///
+/// Clicking or dragging the track seeks; Shift-dragging selects the loop region. Bookmarks
+/// are marked on the track and listed in a menu for jumping back to them. While
+/// paused the bar also describes the point at the snake offset: its curve index,
+/// coordinates, shell and, for power‑of‑two grids, the path of quadrants or octants that
+/// leads to it.
+pub fn timeline<const D: usize>(
+    ui: &mut egui::Ui,
+    selection: &mut CurveSelection<D>,
+    transport: &mut Transport,
+    shared: &mut crate::SharedSettings,
+) {
+    let Some(length) = selection
+        .poll_cached_points(ui.ctx())
+        .map(<[[u32; D]]>::len)
+        .filter(|&len| len > 0)
+    else {
+        return;
+    };
+    let current = (selection.snake_offset as usize).min(length - 1);
+    let mut position = current;
+    let step = |position: usize, delta: isize| {
+        (position as isize + delta).rem_euclid(length as isize) as usize
+    };
+    let snake_points = (shared.snake_length / 100.0 * length as f32) as usize;
+
+    control_bar_frame().show(ui, |ui| {
+        ui.horizontal(|ui| {
+            for (label, delta) in [("−10", -10), ("−1", -1)] {
+                if ui.small_button(label).clicked() {
+                    position = step(position, delta);
+                }
+            }
+            timeline_track(
+                ui,
+                (length, snake_points),
+                &mut position,
+                transport,
+                &shared.bookmarks,
+            );
+            for (label, delta) in [("+1", 1), ("+10", 10)] {
+                if ui.small_button(label).clicked() {
+                    position = step(position, delta);
+                }
+            }
+            ui.separator();
+            ui.add(
+                egui::DragValue::new(&mut shared.snake_speed)
+                    .range(1.0..=200.0)
+                    .speed(0.5)
+                    .suffix(" seg/s"),
+            )
+            .on_hover_text("Playback speed");
+            loop_controls(ui, transport);
+            bookmarks_menu(ui, &mut shared.bookmarks, length, &mut position, transport);
+            if transport.paused
+                && let Some(point) = selection.hover_point(position)
+            {
+                ui.separator();
+                step_details(ui, selection, &point);
             }
         });
+    });
+
+    if position != current {
+        selection.snake_offset = position as f32;
+    }
 }
 
-/// Common size selector widget for 3D curves
-pub fn size_selector_3d(ui: &mut egui::Ui, size: &mut u32, id_salt: &str) {
-    egui::ComboBox::from_id_salt(id_salt)
-        .selected_text(format!("{size}×{size}×{size}"))
-        .show_ui(ui, |ui| {
-            for &s in &[4, 8, 16, 32] {
-                // Smaller max size for 3D due to cubic growth
-                ui.selectable_value(size, s, format!("{s}×{s}×{s}"));
+/// Timeline track: the loop region, bookmarks, the snake's extent and a playhead at
+/// `position`.
+///
+/// `length` and `snake_points` are the lengths of the curve and the snake, in points.
+fn timeline_track(
+    ui: &mut egui::Ui,
+    (length, snake_points): (usize, usize),
+    position: &mut usize,
+    transport: &mut Transport,
+    bookmarks: &Bookmarks,
+) {
+    let palette = theme::palette(ui.ctx());
+    let size = egui::vec2(
+        theme::control_bar::SCRUBBER_WIDTH,
+        theme::control_bar::TIMELINE_HEIGHT,
+    );
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+    let fraction_at = |x: f32| ((x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+    let x_at = |fraction: f32| rect.left() + fraction * rect.width();
+
+    // The anchor of a Shift-drag lives in egui memory until the drag ends.
+    let anchor_id = response.id.with("loop_anchor");
+    if let Some(pointer) = response.interact_pointer_pos() {
+        let fraction = fraction_at(pointer.x);
+        if response.drag_started() && ui.input(|i| i.modifiers.shift) {
+            ui.data_mut(|data| data.insert_temp(anchor_id, fraction));
+        }
+        match ui.data(|data| data.get_temp::<f32>(anchor_id)) {
+            Some(anchor) => transport.loop_region = LoopRegion::new(anchor, fraction),
+            None => *position = ((fraction * length as f32) as usize).min(length - 1),
+        }
+    }
+    if response.drag_stopped() {
+        ui.data_mut(|data| data.remove::<f32>(anchor_id));
+    }
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 3.0, palette.slider_rail);
+    if let Some(region) = transport.loop_region {
+        let span =
+            egui::Rect::from_x_y_ranges(x_at(region.start)..=x_at(region.end), rect.y_range());
+        painter.rect_filled(span, 3.0, palette.selection);
+    }
+    for bookmark in bookmarks
+        .items
+        .iter()
+        .filter(|b| (b.start as usize) < length)
+    {
+        let from = x_at(bookmark.start as f32 / length as f32);
+        let to = x_at((bookmark.end as usize + 1).min(length) as f32 / length as f32)
+            .max(from + theme::bookmarks::TICK_WIDTH);
+        let tick = egui::Rect::from_x_y_ranges(from..=to, rect.top()..=rect.top() + 4.0);
+        painter.rect_filled(tick, 0.0, bookmark.color);
+    }
+    // The snake may wrap past the end of the curve back to its start.
+    let start = *position as f32 / length as f32;
+    let end = (*position + snake_points) as f32 / length as f32;
+    let snake = palette.accent.gamma_multiply(0.6);
+    for (from, to) in [(start, end.min(1.0)), (0.0, end - 1.0)] {
+        if to > from {
+            let span =
+                egui::Rect::from_x_y_ranges(x_at(from)..=x_at(to), rect.y_range().shrink(3.0));
+            painter.rect_filled(span, 2.0, snake);
+        }
+    }
+    let head = x_at(start);
+    painter.line_segment(
+        [
+            egui::pos2(head, rect.top()),
+            egui::pos2(head, rect.bottom()),
+        ],
+        Stroke::new(2.0, palette.slider_fill),
+    );
+
+    let value = *position as f64;
+    let enabled = response.enabled();
+    response.widget_info(|| egui::WidgetInfo::slider(enabled, value, "Timeline"));
+    response.on_hover_text("Click to seek, Shift-drag to loop a region");
+}
+
+/// Menu of bookmarks on a curve of `length` points.
+///
+/// New bookmarks cover the loop region when one is set and the point at `position`
+/// otherwise. Going to a bookmark pauses playback at its start and loops over it when it is
+/// a range.
+fn bookmarks_menu(
+    ui: &mut egui::Ui,
+    bookmarks: &mut Bookmarks,
+    length: usize,
+    position: &mut usize,
+    transport: &mut Transport,
+) {
+    ui.menu_button("Bookmarks", |ui| {
+        if ui.button("Add").clicked() {
+            match transport.loop_region {
+                Some(region) => {
+                    let (start, end) = region.offsets(length as u32);
+                    bookmarks.add(start as u32, end as u32 - 1);
+                }
+                None => bookmarks.add(*position as u32, *position as u32),
             }
-        });
+        }
+        ui.checkbox(&mut bookmarks.show_markers, "Show markers");
+        if bookmarks.items.is_empty() {
+            return;
+        }
+        ui.separator();
+        let mut removed = None;
+        for (i, bookmark) in bookmarks.items.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba(&mut bookmark.color);
+                ui.add(egui::TextEdit::singleline(&mut bookmark.label).desired_width(120.0));
+                ui.label(bookmark.span_label());
+                let reachable = (bookmark.start as usize) < length;
+                if ui.add_enabled(reachable, egui::Button::new("Go")).clicked() {
+                    *position = bookmark.start as usize;
+                    transport.paused = true;
+                    transport.loop_region = if bookmark.is_range() {
+                        LoopRegion::new(
+                            bookmark.start as f32 / length as f32,
+                            (bookmark.end as usize + 1).min(length) as f32 / length as f32,
+                        )
+                    } else {
+                        None
+                    };
+                }
+                if icon_label(ui.small_button("×"), "Delete bookmark").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = removed {
+            bookmarks.items.remove(i);
+        }
+    });
+}
+
+/// Readout of the loop region with a button clearing it, or a hint on making one.
+fn loop_controls(ui: &mut egui::Ui, transport: &mut Transport) {
+    let palette = theme::palette(ui.ctx());
+    let Some(region) = transport.loop_region else {
+        ui.label(
+            egui::RichText::new("Shift-drag to loop")
+                .size(theme::font_size::INFO)
+                .color(palette.text_dim),
+        );
+        return;
+    };
+    ui.label(
+        egui::RichText::new(format!(
+            "Loop {:.0}–{:.0}%",
+            region.start * 100.0,
+            region.end * 100.0
+        ))
+        .size(theme::font_size::INFO)
+        .color(palette.text_body),
+    );
+    if icon_label(ui.small_button("×"), "Clear loop").clicked() {
+        transport.loop_region = None;
+    }
+}
+
+/// Index, coordinates, shell and block path of the stepped-to point.
+fn step_details<const D: usize>(
+    ui: &mut egui::Ui,
+    selection: &CurveSelection<D>,
+    point: &HoverPoint,
+) {
+    let palette = theme::palette(ui.ctx());
+    let coords = point
+        .coords
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut details = vec![
+        format!("Point: ({coords})"),
+        format!("Shell: {}", selection.shell(&point.coords)),
+    ];
+    if let Some(path) = selection.block_path(&point.coords)
+        && !path.is_empty()
+    {
+        let blocks = match selection.curve_dimensions() {
+            2 => "Quadrants",
+            3 => "Octants",
+            _ => "Orthants",
+        };
+        let digits = path.iter().map(u32::to_string).collect::<Vec<_>>();
+        details.push(format!("{blocks}: {}", digits.join(" › ")));
+    }
+    ui.label(
+        egui::RichText::new(format!("Index: {}", point.index))
+            .size(theme::font_size::INFO)
+            .color(palette.text_primary),
+    );
+    for detail in details {
+        ui.label(
+            egui::RichText::new(detail)
+                .size(theme::font_size::INFO)
+                .color(palette.text_body),
+        );
+    }
+}
+
+/// Tooltip body describing the curve point under the pointer.
+pub fn point_inspector(ui: &mut egui::Ui, point: &HoverPoint) {
+    let coords = point
+        .coords
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    ui.label(
+        egui::RichText::new(format!("Index: {}", point.index))
+            .size(theme::font_size::INFO)
+            .color(theme::palette(ui.ctx()).text_primary),
+    );
+    ui.label(
+        egui::RichText::new(format!("Point: ({coords})"))
+            .size(theme::font_size::INFO)
+            .color(theme::palette(ui.ctx()).text_body),
+    );
+}
+
+/// Split `rect` into equal left and right columns.
+pub fn split_columns(rect: egui::Rect) -> [egui::Rect; 2] {
+    let mid = rect.center().x;
+    [
+        egui::Rect::from_min_max(rect.min, egui::pos2(mid, rect.max.y)),
+        egui::Rect::from_min_max(egui::pos2(mid, rect.min.y), rect.max),
+    ]
 }
 
 /// Common pause/play button widget
 pub fn pause_play_button(ui: &mut egui::Ui, paused: &mut bool) -> bool {
+    let palette = theme::palette(ui.ctx());
     let (fill, border, glyph) = if *paused {
-        (theme::BUTTON_PLAY, theme::TEXT_LINK, "▶")
+        (palette.button_play, palette.text_link, "▶")
     } else {
-        (theme::BUTTON_PAUSE, theme::TEXT_HEADING, "⏸")
+        (palette.button_pause, palette.text_heading, "⏸")
     };
 
-    let clicked = ui
-        .add(
-            egui::Button::new(
-                egui::RichText::new(glyph)
-                    .color(theme::TEXT_PRIMARY)
-                    .size(theme::font_size::TITLE),
-            )
-            .min_size(egui::vec2(34.0, 28.0))
-            .fill(fill)
-            .stroke(Stroke::new(1.5, border)),
+    let response = ui.add(
+        egui::Button::new(
+            egui::RichText::new(glyph)
+                .color(palette.text_primary)
+                .size(theme::font_size::TITLE),
         )
-        .clicked();
+        .min_size(egui::vec2(34.0, 28.0))
+        .fill(fill)
+        .stroke(Stroke::new(1.5, border)),
+    );
+    let clicked = icon_label(response, if *paused { "Play" } else { "Pause" }).clicked();
 
     if clicked {
         *paused = !*paused;
@@ -391,6 +887,277 @@ pub fn pause_play_button(ui: &mut egui::Ui, paused: &mut bool) -> bool {
     clicked
 }
 
+/// Export menu offering downloads of the current view in each of `formats`.
+pub fn export_menu(
+    ui: &mut egui::Ui,
+    request: &mut Option<ExportFormat>,
+    formats: &[ExportFormat],
+) {
+    ui.menu_button("Export", |ui| {
+        for &format in formats {
+            if ui.button(format.label()).clicked() {
+                *request = Some(format);
+                ui.close();
+            }
+        }
+    });
+}
+
+/// Record menu capturing the active pane for a chosen number of seconds.
+///
+/// While a recording runs, the menu is replaced by a recording indicator.
+pub fn record_menu(
+    ui: &mut egui::Ui,
+    request: &mut Option<RecordFormat>,
+    seconds: &mut f32,
+    recording: bool,
+) {
+    if recording {
+        ui.label(
+            egui::RichText::new("● REC")
+                .size(theme::font_size::INFO)
+                .color(theme::palette(ui.ctx()).text_error),
+        );
+        return;
+    }
+    ui.menu_button("Record", |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Length");
+            ui.add(
+                egui::DragValue::new(seconds)
+                    .range(1.0..=theme::recording::MAX_SECONDS)
+                    .suffix(" s"),
+            );
+        });
+        for format in RecordFormat::ALL {
+            if ui.button(format.label()).clicked() {
+                *request = Some(format);
+                ui.close();
+            }
+        }
+    });
+}
+
+/// 3D camera menu with view presets, angle entry, drag locking and snapping, projection
+/// toggle, reset and reference guides.
+///
+/// Choosing a preset or typing an angle pauses auto-rotation so the view stays put; reset
+/// resumes it.
+pub fn camera_menu(
+    ui: &mut egui::Ui,
+    camera: &mut Camera,
+    rotation: &mut DragRotation,
+    guides: &mut Guides,
+    paused: &mut bool,
+) {
+    ui.menu_button("View", |ui| {
+        for preset in CameraPreset::ALL {
+            if ui.button(preset.label()).clicked() {
+                camera.apply(preset);
+                *paused = true;
+                ui.close();
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.label("Yaw");
+            let mut yaw = camera.yaw.to_degrees().rem_euclid(360.0);
+            if angle_field(ui, &mut yaw, 0.0..=360.0) {
+                camera.yaw = yaw.to_radians();
+                *paused = true;
+            }
+            ui.label("Tilt");
+            let mut tilt = camera.tilt.to_degrees();
+            if angle_field(ui, &mut tilt, -90.0..=90.0) {
+                camera.tilt = tilt.to_radians();
+                *paused = true;
+            }
+        });
+        ui.separator();
+        ui.label("Drag");
+        ui.horizontal(|ui| {
+            for lock in AxisLock::ALL {
+                ui.radio_value(&mut rotation.lock, lock, lock.label());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Snap");
+            for step in DragRotation::SNAP_STEPS {
+                let label = match step {
+                    0 => "Off".to_string(),
+                    step => format!("{step}°"),
+                };
+                ui.radio_value(&mut rotation.snap_degrees, step, label);
+            }
+        });
+        ui.separator();
+        ui.checkbox(&mut camera.orthographic, "Orthographic");
+        if ui.button("Reset").clicked() {
+            *camera = Camera::default();
+            *paused = false;
+            ui.close();
+        }
+        ui.separator();
+        ui.checkbox(&mut guides.axes, "Axes");
+        ui.checkbox(&mut guides.bounds, "Bounding box");
+        ui.checkbox(&mut guides.grid, "Grid planes");
+    });
+}
+
+/// Degree entry for a camera angle; returns true when the user changed it.
+fn angle_field(ui: &mut egui::Ui, degrees: &mut f32, range: RangeInclusive<f32>) -> bool {
+    ui.add(
+        egui::DragValue::new(degrees)
+            .range(range)
+            .speed(1.0)
+            .max_decimals(1)
+            .suffix("°"),
+    )
+    .changed()
+}
+
+/// 3D clipping plane menu: axis, position along it and which side stays visible.
+pub fn clip_menu(ui: &mut egui::Ui, clip: &mut ClipPlane, size: u32) {
+    ui.menu_button("Clip", |ui| {
+        ui.checkbox(&mut clip.enabled, "Clip geometry");
+        ui.add_enabled_ui(clip.enabled, |ui| {
+            ui.horizontal(|ui| {
+                for (axis, name) in ClipPlane::AXES.iter().enumerate() {
+                    ui.radio_value(&mut clip.axis, axis, *name);
+                }
+            });
+            let max = size.saturating_sub(1);
+            clip.position = clip.position.min(max);
+            ui.add(egui::Slider::new(&mut clip.position, 0..=max).text("Position"));
+            ui.checkbox(&mut clip.flip, "Keep the far side");
+        });
+    });
+}
+
+/// Slider spreading the L∞ shells of onion curves apart in the 3D view.
+pub fn explode_slider(ui: &mut egui::Ui, explode: &mut f32) {
+    ui.label(
+        egui::RichText::new("Explode:")
+            .size(theme::font_size::INFO)
+            .color(theme::palette(ui.ctx()).text_dim),
+    );
+    ui.add(egui::Slider::new(explode, 0.0..=theme::explode::MAX_SPACING).fixed_decimals(1))
+        .on_hover_text("Move each shell outward so its traversal is visible");
+}
+
+/// Colormap picker with endpoint editors for the custom map, plus the property points are
+/// colored by.
+pub fn colormap_menu(ui: &mut egui::Ui, shared: &mut crate::SharedSettings) {
+    ui.menu_button("Color", |ui| {
+        ui.horizontal(|ui| {
+            ui.label("By");
+            for color_by in ColorBy::ALL {
+                ui.radio_value(&mut shared.color_by, color_by, color_by.label());
+            }
+        });
+        if shared.color_by == ColorBy::Block {
+            ui.add(
+                egui::Slider::new(&mut shared.color_level, 1..=theme::blocks::MAX_LEVEL)
+                    .text("Level"),
+            );
+        }
+        ui.separator();
+        for colormap in Colormap::ALL {
+            ui.radio_value(&mut shared.colormap, colormap, colormap.label());
+        }
+        if shared.colormap == Colormap::Custom {
+            ui.separator();
+            let [start, end] = &mut shared.colormap_endpoints;
+            ui.horizontal(|ui| {
+                ui.label("Start");
+                ui.color_edit_button_srgba(start);
+            });
+            ui.horizontal(|ui| {
+                ui.label("End");
+                ui.color_edit_button_srgba(end);
+            });
+        }
+    });
+}
+
+/// Menu importing a CSV point cloud to draw over the 2D pane in curve order.
+///
+/// Once a cloud is loaded the menu compares the length of the path through its points in the
+/// order of `selection`'s curve with the path in file order.
+pub fn points_menu(ui: &mut egui::Ui, import: &mut PointImport, selection: &mut SelectedCurve) {
+    ui.menu_button("Points", |ui| {
+        let palette = theme::palette(ui.ctx());
+        if ui.button("Import CSV…").clicked() {
+            import.open_request = true;
+            ui.close();
+        }
+        if let Some(err) = &import.error {
+            ui.colored_label(palette.text_error, err);
+        }
+        let Some(cloud) = &mut import.cloud else {
+            return;
+        };
+        ui.separator();
+        ui.label(format!("{}: {} points", cloud.name, cloud.points.len()));
+        let file_order = path_length(&cloud.cells(selection.size));
+        if let Some(curve_order) = cloud.ordered(selection).map(path_length) {
+            ui.label(
+                egui::RichText::new(format!(
+                    "Path {curve_order:.0} cells in curve order, {file_order:.0} in file order"
+                ))
+                .size(theme::font_size::INFO)
+                .color(palette.text_dim),
+            );
+        }
+        if ui.button("Clear").clicked() {
+            import.cloud = None;
+        }
+    });
+}
+
+/// Palette picker with optional per-element color overrides.
+fn theme_section(ui: &mut egui::Ui, settings: &mut ThemeSettings) {
+    const LABEL_WIDTH: f32 = 74.0;
+
+    section_header(ui, "Theme");
+    egui::ComboBox::from_id_salt("theme_palette")
+        .selected_text(settings.palette.label())
+        .show_ui(ui, |ui| {
+            for name in PaletteName::ALL {
+                ui.selectable_value(&mut settings.palette, name, name.label());
+            }
+        });
+
+    let base = settings.palette.palette();
+    let overrides = [
+        (
+            "Background",
+            &mut settings.canvas_background,
+            base.canvas_background,
+        ),
+        ("Curve", &mut settings.curve, base.curve),
+        ("Snake", &mut settings.accent, base.accent),
+    ];
+    for (label, color, default) in overrides {
+        ui.horizontal(|ui| {
+            ui.add_sized(
+                [LABEL_WIDTH, 0.0],
+                egui::Label::new(
+                    egui::RichText::new(label)
+                        .color(theme::palette(ui.ctx()).text_body)
+                        .size(theme::font_size::LABEL),
+                ),
+            );
+            let mut value = color.unwrap_or(default);
+            if ui.color_edit_button_srgba(&mut value).changed() {
+                *color = Some(value);
+            }
+            if color.is_some() && ui.small_button("Reset").clicked() {
+                *color = None;
+            }
+        });
+    }
+}
+
 /// Render the settings panel content (called from within the dropdown frame).
 fn settings_panel_content(
     ui: &mut egui::Ui,
@@ -434,11 +1201,102 @@ fn settings_panel_content(
         };
     }
 
+    // Stroke width and joins only apply to the 2D pane, tubes only to the 3D pane.
+    if show_spin_speed {
+        tube_controls(ui, &mut shared.tube);
+    } else {
+        line_controls(ui, &mut shared.line_style);
+    }
+
     neon_checkbox(ui, &mut shared.show_long_jumps, "Long jumps");
+    neon_checkbox(ui, &mut shared.highlight_jumps, "Highlight jumps");
+
+    let block_level = shared.block_level;
+    slider_row_with_value(
+        ui,
+        "Blocks",
+        egui::Slider::new(&mut shared.block_level, 0..=theme::blocks::MAX_LEVEL),
+        if block_level == 0 {
+            "off".to_string()
+        } else {
+            format!("level {block_level}")
+        },
+    );
+
+    ui.add_space(theme::spacing::MEDIUM - 2.0);
+    ui.add(egui::Separator::default().spacing(theme::spacing::SMALL));
+
+    snake_section(ui, shared);
 
     ui.add_space(theme::spacing::MEDIUM - 2.0);
     ui.add(egui::Separator::default().spacing(theme::spacing::SMALL));
+    theme_section(ui, &mut shared.theme);
+    neon_checkbox(ui, &mut shared.reduced_motion, "Reduce motion");
+
+    if show_spin_speed {
+        ui.add_space(theme::spacing::MEDIUM - 2.0);
+        ui.add(egui::Separator::default().spacing(theme::spacing::SMALL));
+        section_header(ui, "3D rotation");
+        let spin_value = shared.spin_speed;
+        slider_row_with_value(
+            ui,
+            "Speed",
+            egui::Slider::new(&mut shared.spin_speed, 0.0..=100.0).step_by(1.0),
+            format!("{:>5.0}%", spin_value.round()),
+        );
+    }
+}
+
+/// Width slider and join choice for the 2D curve strokes.
+fn line_controls(ui: &mut egui::Ui, line: &mut LineStyle) {
+    const LABEL_WIDTH: f32 = 74.0;
+
+    let width_value = line.width;
+    slider_row_with_value(
+        ui,
+        "Line width",
+        egui::Slider::new(
+            &mut line.width,
+            theme::canvas_2d::MIN_LINE_WIDTH..=theme::canvas_2d::MAX_LINE_WIDTH,
+        )
+        .step_by(0.5),
+        format!("{width_value:>5.1} px"),
+    );
+    ui.horizontal(|ui| {
+        ui.add_sized(
+            [LABEL_WIDTH, 0.0],
+            egui::Label::new(
+                egui::RichText::new("Joins")
+                    .color(theme::palette(ui.ctx()).text_body)
+                    .size(theme::font_size::LABEL),
+            ),
+        );
+        for join in LineJoin::ALL {
+            ui.selectable_value(&mut line.join, join, join.label());
+        }
+    });
+}
 
+/// Tube toggle and radius for the 3D curve.
+fn tube_controls(ui: &mut egui::Ui, tube: &mut TubeStyle) {
+    neon_checkbox(ui, &mut tube.enabled, "Tubes");
+    if tube.enabled {
+        let radius_value = tube.radius;
+        slider_row_with_value(
+            ui,
+            "Radius",
+            egui::Slider::new(
+                &mut tube.radius,
+                theme::tube::MIN_RADIUS..=theme::tube::MAX_RADIUS,
+            )
+            .step_by(0.05),
+            format!("{radius_value:>5.2}"),
+        );
+    }
+}
+
+/// Snake toggle, timing and appearance controls.
+fn snake_section(ui: &mut egui::Ui, shared: &mut crate::SharedSettings) {
     section_header(ui, "Snake");
 
     neon_checkbox(ui, &mut shared.snake_enabled, "Enable snake overlay");
@@ -450,24 +1308,37 @@ fn settings_panel_content(
         egui::Slider::new(&mut shared.snake_length, 0.0..=50.0).step_by(0.5),
         format!("{:>6.1}%", snake_length_value),
     );
-    let snake_value = shared.snake_speed;
+
+    let style = &mut shared.snake_style;
+    let width_value = style.width;
     slider_row_with_value(
         ui,
-        "Speed",
-        egui::Slider::new(&mut shared.snake_speed, 1.0..=200.0).step_by(1.0),
-        format!("{:>6.0} seg/s", snake_value.round()),
+        "Width",
+        egui::Slider::new(
+            &mut style.width,
+            theme::snake_style::MIN_WIDTH..=theme::snake_style::MAX_WIDTH,
+        )
+        .step_by(0.1),
+        format!("{width_value:>5.1}×"),
     );
+    let fade_value = style.fade;
+    slider_row_with_value(
+        ui,
+        "Fade",
+        egui::Slider::new(&mut style.fade, 0.0..=1.0).step_by(0.05),
+        format!("{:>5.0}%", fade_value * 100.0),
+    );
+    neon_checkbox(ui, &mut style.gradient, "Gradient");
+    neon_checkbox(ui, &mut style.head_marker, "Head marker");
 
-    if show_spin_speed {
-        ui.add_space(theme::spacing::MEDIUM - 2.0);
-        ui.add(egui::Separator::default().spacing(theme::spacing::SMALL));
-        section_header(ui, "3D rotation");
-        let spin_value = shared.spin_speed;
+    neon_checkbox(ui, &mut shared.race_enabled, "Race a second snake");
+    if shared.race_enabled {
+        let gap_value = shared.race_gap;
         slider_row_with_value(
             ui,
-            "Speed",
-            egui::Slider::new(&mut shared.spin_speed, 0.0..=100.0).step_by(1.0),
-            format!("{:>5.0}%", spin_value.round()),
+            "Gap",
+            egui::Slider::new(&mut shared.race_gap, 0.0..=100.0).step_by(0.5),
+            format!("{gap_value:>6.1}%"),
         );
     }
 }
@@ -482,7 +1353,7 @@ pub fn settings_dropdown(
     shared: &mut crate::SharedSettings,
     show_spin_speed: bool,
 ) {
-    let button_response = ui.button("⚙");
+    let button_response = icon_label(ui.button("⚙"), "Settings");
     if button_response.clicked() {
         *settings_open = !*settings_open;
         if *settings_open {
@@ -504,6 +1375,8 @@ pub fn settings_dropdown(
         )
     });
 
+    let palette = theme::palette(ui.ctx());
+    let [accent_r, accent_g, accent_b, _] = palette.accent.to_array();
     let area_response = egui::Area::new(egui::Id::new("settings_dropdown"))
         .movable(false)
         .order(egui::Order::Foreground)
@@ -512,8 +1385,8 @@ pub fn settings_dropdown(
         .fixed_pos(*anchor_pos)
         .show(ui.ctx(), |ui| {
             egui::Frame::new()
-                .fill(theme::SETTINGS_PANEL_BG)
-                .stroke(Stroke::new(1.0, theme::BORDER))
+                .fill(palette.settings_panel_bg)
+                .stroke(Stroke::new(1.0, palette.border))
                 .inner_margin(egui::Margin::same(theme::popup::SETTINGS_MARGIN))
                 .corner_radius(egui::CornerRadius::same(theme::popup::CORNER_RADIUS))
                 .shadow(Shadow {
@@ -521,9 +1394,9 @@ pub fn settings_dropdown(
                     blur: theme::shadow::BLUR,
                     spread: theme::shadow::SPREAD,
                     color: egui::Color32::from_rgba_unmultiplied(
-                        theme::accent_color::R,
-                        theme::accent_color::G,
-                        theme::accent_color::B,
+                        accent_r,
+                        accent_g,
+                        accent_b,
                         theme::POPUP_SHADOW_ALPHA,
                     ),
                 })
@@ -542,7 +1415,8 @@ pub fn settings_dropdown(
     {
         let inside_dropdown = area_response.response.rect.contains(pos);
         let inside_button = button_response.rect.contains(pos);
-        if !inside_dropdown && !inside_button {
+        // Color pickers open as popups outside the dropdown; keep it open while they are in use.
+        if !inside_dropdown && !inside_button && !egui::Popup::is_any_open(ui.ctx()) {
             *settings_open = false;
             *settings_pos = None;
         }
@@ -0,0 +1,265 @@
+//! Curve point generation off the UI thread.
+//!
+//! Mapping every index of a large curve takes long enough to freeze the interface when done
+//! inside a frame. A [`PointJob`] generates a selection's points on a worker thread, or on
+//! the web, which has no threads, a slice at a time across frames. Dropping a job cancels
+//! it, so changing the curve quickly never waits for stale work.
+//!
+//! With the `gpu` feature, a worker first asks the GPU for the points. Reading them back
+//! waits for the device, so this only ever happens on the worker thread; [`generate`] always
+//! maps points on the CPU.
+
+#[cfg(target_arch = "wasm32")]
+use std::mem;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use spacecurve::{SpaceCurve, curve_from_name};
+
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+use crate::gpu;
+use crate::theme;
+
+/// Points in curve order, with the curve index of each.
+pub type Points<const D: usize> = (Vec<[u32; D]>, Vec<u32>);
+
+/// The curve whose points a job generates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// Registry name of the curve.
+    pub name: String,
+    /// Side length of the grid per axis.
+    pub size: u32,
+    /// Fixed value of an extra trailing axis, keeping only that slice of the curve.
+    pub slice: Option<u32>,
+}
+
+/// Incremental CPU generation of a request's points.
+struct Generator<const D: usize> {
+    /// The curve being walked.
+    curve: Box<dyn SpaceCurve>,
+    /// Fixed trailing coordinate of a sliced curve.
+    slice: Option<u32>,
+    /// Next curve index to map.
+    next: u32,
+    /// Points generated so far.
+    points: Points<D>,
+}
+
+impl<const D: usize> Generator<D> {
+    /// Start generating `request`, or `None` if its curve can't be constructed.
+    fn new(request: &Request) -> Option<Self> {
+        let dimension = D as u32 + u32::from(request.slice.is_some());
+        let curve = curve_from_name(&request.name, dimension, request.size).ok()?;
+        let capacity = if request.slice.is_some() {
+            curve.length() / request.size
+        } else {
+            curve.length()
+        } as usize;
+        Some(Self {
+            curve,
+            slice: request.slice,
+            next: 0,
+            points: (Vec::with_capacity(capacity), Vec::with_capacity(capacity)),
+        })
+    }
+
+    /// Map up to `budget` more indices, returning whether the curve is finished.
+    fn advance(&mut self, budget: u32) -> bool {
+        let end = self.next.saturating_add(budget).min(self.curve.length());
+        let (pts, indices) = &mut self.points;
+        for (i, p) in (self.next..end).zip(self.curve.iter_range(self.next..end)) {
+            if self.slice.is_some_and(|w| p[D] != w) {
+                continue;
+            }
+            let mut arr = [0u32; D];
+            arr.copy_from_slice(&p[..D]);
+            pts.push(arr);
+            indices.push(i);
+        }
+        self.next = end;
+        self.next == self.curve.length()
+    }
+}
+
+/// Points of `request` generated on the GPU, when it can take the whole curve.
+#[cfg(all(feature = "gpu", not(target_arch = "wasm32")))]
+fn gpu_points<const D: usize>(request: &Request) -> Option<Points<D>> {
+    if request.slice.is_some() {
+        return None;
+    }
+    let pts = gpu::curve_points::<D>(&request.name, request.size)?;
+    let indices = (0..pts.len() as u32).collect();
+    Some((pts, indices))
+}
+
+/// Without the `gpu` feature every curve is generated on the CPU.
+#[cfg(all(not(feature = "gpu"), not(target_arch = "wasm32")))]
+fn gpu_points<const D: usize>(_request: &Request) -> Option<Points<D>> {
+    None
+}
+
+/// Generate every point of `request` on the calling thread, on the CPU.
+pub fn generate<const D: usize>(request: &Request) -> Option<Points<D>> {
+    let mut generator = Generator::<D>::new(request)?;
+    generator.advance(u32::MAX);
+    Some(generator.points)
+}
+
+/// Points of one request being generated in the background.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct PointJob<const D: usize> {
+    /// The request being generated.
+    request: Request,
+    /// Total number of curve indices to map.
+    total: u32,
+    /// Indices mapped so far, updated by the worker.
+    done: Arc<AtomicU32>,
+    /// Set to ask the worker to stop early.
+    cancel: Arc<AtomicBool>,
+    /// Delivers the finished points.
+    result: mpsc::Receiver<Points<D>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<const D: usize> PointJob<D> {
+    /// Start generating `request` on a worker thread, or `None` if its curve can't be
+    /// constructed. The worker asks `ctx` for a repaint once the points are ready.
+    pub fn spawn(request: Request, ctx: &egui::Context) -> Option<Self> {
+        let mut generator = Generator::<D>::new(&request)?;
+        let total = generator.curve.length();
+        let done = Arc::new(AtomicU32::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, result) = mpsc::channel();
+        let worker = WorkerState {
+            done: Arc::clone(&done),
+            cancel: Arc::clone(&cancel),
+            ctx: ctx.clone(),
+        };
+        let gpu_request = request.clone();
+        thread::spawn(move || {
+            if let Some(points) = gpu_points(&gpu_request) {
+                worker.finish(&sender, points);
+                return;
+            }
+            while !generator.advance(theme::worker::CHUNK_POINTS) {
+                if worker.cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+                worker.done.store(generator.next, Ordering::Relaxed);
+            }
+            worker.finish(&sender, generator.points);
+        });
+        Some(Self {
+            request,
+            total,
+            done,
+            cancel,
+            result,
+        })
+    }
+
+    /// The finished points, once the worker has delivered them.
+    pub fn poll(&mut self) -> Option<Points<D>> {
+        self.result.try_recv().ok()
+    }
+
+    /// Block until the worker has finished, returning its points.
+    pub fn wait(self) -> Option<Points<D>> {
+        self.result.recv().ok()
+    }
+
+    /// Fraction of the curve the worker has generated, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.done.load(Ordering::Relaxed) as f32 / self.total.max(1) as f32
+    }
+
+    /// The request being generated.
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<const D: usize> Drop for PointJob<D> {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Handles a worker thread reports through.
+#[cfg(not(target_arch = "wasm32"))]
+struct WorkerState {
+    /// Indices mapped so far.
+    done: Arc<AtomicU32>,
+    /// Set when the job has been dropped.
+    cancel: Arc<AtomicBool>,
+    /// Context repainted when the points are ready.
+    ctx: egui::Context,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WorkerState {
+    /// Deliver `points` and wake the UI to show them.
+    fn finish<const D: usize>(&self, sender: &mpsc::Sender<Points<D>>, points: Points<D>) {
+        if sender.send(points).is_ok() {
+            self.ctx.request_repaint();
+        }
+    }
+}
+
+/// Points of one request being generated a slice per frame.
+#[cfg(target_arch = "wasm32")]
+pub struct PointJob<const D: usize> {
+    /// The request being generated.
+    request: Request,
+    /// Generation state, advanced by each poll.
+    generator: Generator<D>,
+    /// Context repainted while work remains.
+    ctx: egui::Context,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl<const D: usize> PointJob<D> {
+    /// Start generating `request`, or `None` if its curve can't be constructed.
+    pub fn spawn(request: Request, ctx: &egui::Context) -> Option<Self> {
+        let generator = Generator::new(&request)?;
+        Some(Self {
+            request,
+            generator,
+            ctx: ctx.clone(),
+        })
+    }
+
+    /// Generate the next slice, returning the points once the last one is done.
+    pub fn poll(&mut self) -> Option<Points<D>> {
+        if self.generator.advance(theme::worker::CHUNK_POINTS) {
+            return Some(mem::take(&mut self.generator.points));
+        }
+        self.ctx.request_repaint();
+        None
+    }
+
+    /// Generate everything that remains, returning the points.
+    pub fn wait(mut self) -> Option<Points<D>> {
+        self.generator.advance(u32::MAX);
+        Some(self.generator.points)
+    }
+
+    /// Fraction of the curve generated so far, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.generator.next as f32 / self.generator.curve.length().max(1) as f32
+    }
+
+    /// The request being generated.
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+}
@@ -0,0 +1,274 @@
+//! Bulk `vis` rendering of a directory for the `batch` subcommand.
+//!
+//! Every regular file directly inside the input directory is rendered with the same `vis`
+//! settings, several files at a time. The output directory receives a full-size PNG per file,
+//! a smaller copy of each under [`THUMBS`], and an [`INDEX`] page laying the thumbnails out as
+//! a gallery that links to the full images. A file that fails to render is listed in the
+//! gallery with the reason instead of stopping the run.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    result::Result as StdResult,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use anyhow::{Result, bail};
+use image::imageops;
+
+use crate::cmd::{self, VisMode, VisOverlays};
+
+/// File name of the gallery page.
+pub const INDEX: &str = "index.html";
+/// Directory, inside the output directory, holding the thumbnails.
+pub const THUMBS: &str = "thumbs";
+
+/// Settings shared by every file of a batch.
+pub struct BatchOptions<'a> {
+    /// Width and height of the full-size images.
+    pub width: u32,
+    /// Registry name of the curve laying out the bytes.
+    pub pattern: &'a str,
+    /// How bytes are colored.
+    pub mode: VisMode,
+    /// Overlays drawn on every image.
+    pub overlays: &'a VisOverlays,
+    /// Largest width and height of a thumbnail.
+    pub thumbnail: u32,
+    /// Number of files rendered at once.
+    pub jobs: usize,
+}
+
+/// How one file of a batch fared.
+pub struct BatchEntry {
+    /// File name of the input.
+    pub name: String,
+    /// Length of the input in bytes.
+    pub bytes: u64,
+    /// File name of the rendered image, the same in the output and thumbnail directories,
+    /// or why the input could not be rendered.
+    pub outcome: StdResult<String, String>,
+}
+
+/// Render every file in `dir` into `out`, returning the entries in file name order.
+///
+/// The gallery page is not written; pass the entries to [`write_gallery`] for that. Fails
+/// when `dir` can't be read or holds no files, or `out` can't be created.
+pub fn batch(dir: &Path, out: &Path, options: &BatchOptions<'_>) -> Result<Vec<BatchEntry>> {
+    let files = list_files(dir)?;
+    if files.is_empty() {
+        bail!("no files to render in {}", dir.display());
+    }
+    fs::create_dir_all(out.join(THUMBS))?;
+
+    let next = AtomicUsize::new(0);
+    let mut entries: Vec<BatchEntry> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..options.jobs.clamp(1, files.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    while let Some(path) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        done.push(render_entry(path, out, options));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("batch worker panicked"))
+            .collect()
+    });
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+/// Regular files directly inside `dir`, in name order.
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Render `path` into `out`, recording any failure in the entry.
+fn render_entry(path: &Path, out: &Path, options: &BatchOptions<'_>) -> BatchEntry {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    BatchEntry {
+        bytes: fs::metadata(path).map_or(0, |meta| meta.len()),
+        outcome: render_file(path, &name, out, options).map_err(|err| err.to_string()),
+        name,
+    }
+}
+
+/// Render `path` and save its full-size image and thumbnail, returning their file name.
+fn render_file(path: &Path, name: &str, out: &Path, options: &BatchOptions<'_>) -> Result<String> {
+    let render = cmd::vis(
+        path,
+        options.width,
+        options.pattern,
+        options.mode,
+        options.overlays,
+    )?;
+    let image_name = format!("{name}.png");
+    render.image.save(out.join(&image_name))?;
+    let side = options.thumbnail.min(options.width);
+    imageops::thumbnail(&render.image, side, side).save(out.join(THUMBS).join(&image_name))?;
+    Ok(image_name)
+}
+
+/// Write the gallery page for `entries`, headed by `title`, with thumbnails `thumbnail`
+/// points wide.
+pub fn write_gallery(
+    entries: &[BatchEntry],
+    title: &str,
+    thumbnail: u32,
+    out: &mut impl Write,
+) -> Result<()> {
+    let title = escape_html(title);
+    let rendered = entries.iter().filter(|entry| entry.outcome.is_ok()).count();
+    writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2em; background: #f4f4f4; }}\n\
+         .grid {{ display: flex; flex-wrap: wrap; gap: 1em; }}\n\
+         figure {{ margin: 0; width: {thumbnail}px; }}\n\
+         figure img {{ display: block; width: {thumbnail}px; height: {thumbnail}px; }}\n\
+         figcaption {{ font-size: 0.8em; overflow-wrap: anywhere; }}\n\
+         .error {{ height: {thumbnail}px; background: #fdd; color: #900; font-size: 0.8em; \
+         overflow: auto; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n<p>{rendered} of {} files rendered</p>\n\
+         <div class=\"grid\">",
+        entries.len()
+    )?;
+    for entry in entries {
+        let name = escape_html(&entry.name);
+        match &entry.outcome {
+            Ok(image) => {
+                let href = escape_html(&escape_url(image));
+                writeln!(
+                    out,
+                    "<figure><a href=\"{href}\"><img src=\"{THUMBS}/{href}\" alt=\"{name}\" \
+                     loading=\"lazy\"></a><figcaption>{name}<br>{} bytes</figcaption></figure>",
+                    entry.bytes
+                )?;
+            }
+            Err(reason) => writeln!(
+                out,
+                "<figure><div class=\"error\">{}</div><figcaption>{name}<br>{} bytes\
+                 </figcaption></figure>",
+                escape_html(reason),
+                entry.bytes
+            )?,
+        }
+    }
+    writeln!(out, "</div>\n</body>\n</html>")?;
+    Ok(())
+}
+
+/// Escape the characters with special meaning in HTML text and attribute values.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode every byte of `path` outside the URL unreserved set.
+fn escape_url(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            escaped.push(char::from(byte));
+        } else {
+            escaped.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn escapes_names_for_html_and_urls() {
+        assert_eq!(escape_html("a<b>&\"c'"), "a&lt;b&gt;&amp;&quot;c&#39;");
+        assert_eq!(escape_url("my file#1.bin.png"), "my%20file%231.bin.png");
+        assert_eq!(escape_url("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn renders_every_file_and_records_failures() {
+        let input = tempdir().expect("input dir");
+        let out = tempdir().expect("output dir");
+        fs::write(input.path().join("b.bin"), (0..=255).collect::<Vec<u8>>()).expect("write");
+        fs::write(input.path().join("a.bin"), b"hello").expect("write");
+        fs::write(input.path().join("empty"), b"").expect("write");
+        fs::create_dir(input.path().join("nested")).expect("mkdir");
+
+        let overlays = VisOverlays::default();
+        let options = BatchOptions {
+            width: 32,
+            pattern: "hilbert",
+            mode: VisMode::Bytes,
+            overlays: &overlays,
+            thumbnail: 16,
+            jobs: 2,
+        };
+        let entries = batch(input.path(), out.path(), &options).expect("batch");
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["a.bin", "b.bin", "empty"]);
+        assert_eq!(entries[1].bytes, 256);
+        assert!(entries[2].outcome.is_err());
+
+        let thumb = image::open(out.path().join(THUMBS).join("a.bin.png")).expect("thumb");
+        assert_eq!((thumb.width(), thumb.height()), (16, 16));
+        let full = image::open(out.path().join("b.bin.png")).expect("image");
+        assert_eq!((full.width(), full.height()), (32, 32));
+
+        let mut html = Vec::new();
+        write_gallery(&entries, "samples", 16, &mut html).expect("gallery");
+        let html = String::from_utf8(html).expect("utf-8");
+        assert!(html.contains("<a href=\"a.bin.png\"><img src=\"thumbs/a.bin.png\""));
+        assert!(html.contains("2 of 3 files rendered"));
+        assert!(html.contains("input file is empty"));
+    }
+
+    #[test]
+    fn rejects_directories_without_files() {
+        let input = tempdir().expect("input dir");
+        let out = tempdir().expect("output dir");
+        let overlays = VisOverlays::default();
+        let options = BatchOptions {
+            width: 32,
+            pattern: "hilbert",
+            mode: VisMode::Bytes,
+            overlays: &overlays,
+            thumbnail: 16,
+            jobs: 1,
+        };
+        assert!(batch(input.path(), out.path(), &options).is_err());
+    }
+}
@@ -0,0 +1,348 @@
+//! Content-addressed cache of rendered outputs.
+//!
+//! A render is keyed by a hash of everything that can change its output: the command, every
+//! option, the contents of every input file, the output format and the tool's version. A
+//! cached entry holds the output file exactly as written, plus whatever the command printed,
+//! so replaying a hit is indistinguishable from rendering again. Entries are never
+//! invalidated in place; a changed input or option simply hashes to a new key.
+//!
+//! The hash is BLAKE3, so a crafted input file can't be made to share another render's key
+//! and replay the wrong output. Options are fed as their JSON encoding, and every key
+//! starts with [`KEY_FORMAT`], so a change to how keys are built can never collide with old
+//! entries.
+
+use std::{
+    fmt::{self, Display},
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::Result;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+/// Version of the key encoding, bumped whenever the parts fed to a key change shape.
+const KEY_FORMAT: u32 = 2;
+/// Suffix of the file holding an entry's printed output.
+const REPORT_SUFFIX: &str = "json";
+
+/// Hash identifying one render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key(blake3::Hash);
+
+impl Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_hex())
+    }
+}
+
+/// Accumulates the parts of a render into a [`Key`].
+#[derive(Debug, Clone)]
+pub struct KeyBuilder(blake3::Hasher);
+
+impl KeyBuilder {
+    /// Start a key for `command`, tied to this version of the tool.
+    pub fn new(command: &str) -> Self {
+        let mut builder = Self(blake3::Hasher::new());
+        builder.feed(&KEY_FORMAT.to_le_bytes());
+        builder.bytes(env!("CARGO_PKG_VERSION").as_bytes());
+        builder.bytes(command.as_bytes());
+        builder
+    }
+
+    /// Feed `data`, preceded by its length so adjacent parts can't run together.
+    fn bytes(&mut self, data: &[u8]) {
+        self.feed(&(data.len() as u64).to_le_bytes());
+        self.feed(data);
+    }
+
+    /// Feed raw bytes.
+    fn feed(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    /// Add an option called `name` with the given value, encoded as JSON.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` has no JSON encoding, such as a map with non-string keys.
+    pub fn option(mut self, name: &str, value: impl Serialize) -> Self {
+        self.bytes(name.as_bytes());
+        let encoded = serde_json::to_vec(&value).expect("cache key options encode as JSON");
+        self.bytes(&encoded);
+        self
+    }
+
+    /// Add the contents of the file at `path`.
+    pub fn file(mut self, path: &Path) -> Result<Self> {
+        let mut file = File::open(path)?;
+        self.feed(&file.metadata()?.len().to_le_bytes());
+        io::copy(&mut file, &mut self.0)?;
+        Ok(self)
+    }
+
+    /// The finished key.
+    pub fn finish(&self) -> Key {
+        Key(self.0.finalize())
+    }
+}
+
+/// Text a command printed while rendering, replayed on a cache hit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    /// Lines written to standard output.
+    pub stdout: String,
+    /// Lines written to standard error.
+    pub stderr: String,
+}
+
+impl Report {
+    /// Record a line for standard output.
+    pub fn out(&mut self, line: impl Display) {
+        self.stdout.push_str(&format!("{line}\n"));
+    }
+
+    /// Record a line for standard error.
+    pub fn err(&mut self, line: impl Display) {
+        self.stderr.push_str(&format!("{line}\n"));
+    }
+
+    /// Print the recorded lines.
+    pub fn print(&self) {
+        print!("{}", self.stdout);
+        eprint!("{}", self.stderr);
+    }
+}
+
+/// A stored render.
+pub struct Hit {
+    /// The output file as it was written.
+    pub path: PathBuf,
+    /// What the command printed.
+    pub report: Report,
+}
+
+/// Number of files and bytes removed by [`Cache::clear`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cleared {
+    /// Files removed.
+    pub files: u64,
+    /// Bytes freed.
+    pub bytes: u64,
+}
+
+/// A directory of stored renders.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    /// Directory holding the entries.
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// A cache kept in `dir`, created on first store.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The platform's per-user cache directory for scurve.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|base| base.join("scurve"))
+    }
+
+    /// Path of the output file for `key`, which is written as `extension`.
+    fn output_path(&self, key: Key, extension: &str) -> PathBuf {
+        self.dir.join(format!("{key}.{extension}"))
+    }
+
+    /// The stored render for `key`, whose output has `extension`, if there is a complete one.
+    pub fn lookup(&self, key: Key, extension: &str) -> Option<Hit> {
+        let path = self.output_path(key, extension);
+        let report = fs::read(self.output_path(key, REPORT_SUFFIX)).ok()?;
+        let report = serde_json::from_slice(&report).ok()?;
+        path.is_file().then_some(Hit { path, report })
+    }
+
+    /// Store a copy of the output file at `output`, whose extension is `extension`.
+    pub fn store(&self, key: Key, extension: &str, output: &Path, report: &Report) -> Result<()> {
+        self.write(key, extension, |path| {
+            fs::copy(output, path)?;
+            Ok(())
+        })?;
+        self.write_report(key, report)
+    }
+
+    /// Store `image` as a PNG.
+    pub fn store_image(&self, key: Key, image: &RgbaImage, report: &Report) -> Result<()> {
+        self.write(key, "png", |path| {
+            image.save_with_format(path, image::ImageFormat::Png)?;
+            Ok(())
+        })?;
+        self.write_report(key, report)
+    }
+
+    /// Store what the command printed. Written last, so its presence marks a complete entry.
+    fn write_report(&self, key: Key, report: &Report) -> Result<()> {
+        self.write(key, REPORT_SUFFIX, |path| {
+            fs::write(path, serde_json::to_vec(report)?)?;
+            Ok(())
+        })
+    }
+
+    /// Write the entry file for `key` with `extension` through `write`, renaming it into place
+    /// so that concurrent readers never see a partial file.
+    fn write(
+        &self,
+        key: Key,
+        extension: &str,
+        write: impl FnOnce(&Path) -> Result<()>,
+    ) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let temporary = self
+            .dir
+            .join(format!(".{key}.{}.{extension}", process::id()));
+        if let Err(err) = write(&temporary) {
+            fs::remove_file(&temporary).ok();
+            return Err(err);
+        }
+        fs::rename(&temporary, self.output_path(key, extension))?;
+        Ok(())
+    }
+
+    /// Remove every entry. A cache that was never created is already clear.
+    ///
+    /// Only files named like entries, or like the temporaries they are written through, are
+    /// removed, so pointing the cache at a directory holding anything else leaves it alone.
+    pub fn clear(&self) -> Result<Cleared> {
+        let mut cleared = Cleared::default();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(cleared),
+            Err(err) => return Err(err.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let written = entry.file_name().to_str().is_some_and(is_entry_name);
+            if written && entry.file_type()?.is_file() {
+                cleared.bytes += entry.metadata()?.len();
+                fs::remove_file(entry.path())?;
+                cleared.files += 1;
+            }
+        }
+        Ok(cleared)
+    }
+}
+
+/// Whether `name` is an entry file, `<key>.<extension>`, or a temporary the cache writes one
+/// through, `.<key>.<process>.<extension>`.
+fn is_entry_name(name: &str) -> bool {
+    let (name, temporary) = match name.strip_prefix('.') {
+        Some(rest) => (rest, true),
+        None => (name, false),
+    };
+    let parts: Vec<&str> = name.split('.').collect();
+    let Some((key, rest)) = parts.split_first() else {
+        return false;
+    };
+    let is_key = key.len() == blake3::OUT_LEN * 2
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    let is_extension =
+        |ext: &str| !ext.is_empty() && ext.bytes().all(|b| b.is_ascii_alphanumeric());
+    is_key
+        && match (temporary, rest) {
+            (false, [ext]) => is_extension(ext),
+            (true, [process, ext]) => {
+                !process.is_empty()
+                    && process.bytes().all(|b| b.is_ascii_digit())
+                    && is_extension(ext)
+            }
+            _ => false,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn keys_change_with_every_part() {
+        let td = tempdir().unwrap();
+        let (a, b) = (td.path().join("a"), td.path().join("b"));
+        fs::write(&a, b"one").unwrap();
+        fs::write(&b, b"two").unwrap();
+
+        let key = |command: &str, width: u32, file: &Path| {
+            KeyBuilder::new(command)
+                .option("width", width)
+                .file(file)
+                .unwrap()
+                .finish()
+        };
+        let base = key("vis", 256, &a);
+        assert_eq!(base, key("vis", 256, &a));
+        assert_ne!(base, key("map", 256, &a));
+        assert_ne!(base, key("vis", 512, &a));
+        assert_ne!(base, key("vis", 256, &b));
+        // Length prefixes keep parts from running together.
+        assert_ne!(
+            KeyBuilder::new("x").option("ab", "c").finish(),
+            KeyBuilder::new("x").option("a", "bc").finish()
+        );
+        assert_ne!(
+            KeyBuilder::new("x").option("chunk", None::<u32>).finish(),
+            KeyBuilder::new("x").option("chunk", 0).finish()
+        );
+        assert_eq!(base.to_string().len(), 64);
+    }
+
+    #[test]
+    fn stores_replays_and_clears_entries() {
+        let td = tempdir().unwrap();
+        let cache = Cache::new(td.path().join("cache"));
+        let key = KeyBuilder::new("test").finish();
+        assert!(cache.lookup(key, "png").is_none());
+        assert_eq!(cache.clear().unwrap(), Cleared::default());
+
+        let mut report = Report::default();
+        report.out("legend");
+        report.err("warning");
+        let image = RgbaImage::from_pixel(4, 4, Rgba([1, 2, 3, 255]));
+        cache.store_image(key, &image, &report).unwrap();
+        let hit = cache.lookup(key, "png").expect("hit");
+        assert_eq!(hit.report, report);
+        assert_eq!(image::open(&hit.path).unwrap().to_rgba8(), image);
+        assert!(cache.lookup(key, "gif").is_none());
+
+        let output = td.path().join("out.gif");
+        fs::write(&output, b"GIF89a").unwrap();
+        cache.store(key, "gif", &output, &report).unwrap();
+        let hit = cache.lookup(key, "gif").expect("hit");
+        assert_eq!(fs::read(hit.path).unwrap(), b"GIF89a");
+
+        // Files the cache didn't write survive a clear.
+        let dir = td.path().join("cache");
+        let foreign = [
+            "notes.txt".to_string(),
+            format!("{key}.png.bak"),
+            format!("x{}.png", &key.to_string()[1..]),
+            format!(".{key}.tmp.png"),
+        ];
+        for name in &foreign {
+            fs::write(dir.join(name), b"keep").unwrap();
+        }
+        fs::write(dir.join(format!(".{key}.123.png")), b"partial").unwrap();
+
+        let cleared = cache.clear().unwrap();
+        assert_eq!(cleared.files, 4);
+        assert!(cache.lookup(key, "png").is_none());
+        for name in &foreign {
+            assert!(dir.join(name).is_file(), "{name} was removed");
+        }
+    }
+}
@@ -3,14 +3,31 @@
 //! These functions implement the top‑level subcommands and write the resulting
 //! images to disk.
 
-use std::{fs::File, ops::Range, path::Path};
+use std::{
+    f64::consts::{FRAC_PI_4, TAU},
+    fs::File,
+    io::Write,
+    ops::Range,
+    path::Path,
+};
 
 use anyhow::{Result, anyhow, bail};
-use gif::{Encoder, Frame, Repeat};
-use spacecurve::{curve_from_name, registry};
-
-use crate::map::{
-    MapPalette, StrokeOptions, draw_chunk_overlay, render_chunk_image, render_map_image,
+use scurve_gui::{
+    colormap::{Colormap, DEFAULT_ENDPOINTS, Gradient},
+    record::{GifWriter, frame_delay_from_fps},
+};
+use serde::Serialize;
+use spacecurve::{SpaceCurve, curve_from_name, curves::onion::shell_of, registry};
+
+use crate::{
+    executable,
+    map::{
+        MapPalette, OrbitView, Projection, StrokeOptions, draw_chunk_overlay, draw_steps,
+        render_chunk_image, render_map_image, render_orbit_image, render_projected_image,
+    },
+    sections::{self, Section},
+    segment::{self, Region},
+    series, wav,
 };
 
 /// Black color for 0x00.
@@ -48,10 +65,68 @@ fn mmap_readonly(file: &File) -> Result<memmap2::Mmap> {
     Ok(map)
 }
 
+/// Construct the curve registered as `name` for rendering.
+///
+/// Rendering walks points by `u32` index, so curves with more points than that are rejected
+/// here rather than part way through.
+pub fn render_curve(name: &str, dimensions: u32, side: u32) -> Result<Box<dyn SpaceCurve>> {
+    let curve = curve_from_name(name, dimensions, side)?;
+    curve.try_length()?;
+    Ok(curve)
+}
+
+/// How `vis` colors each byte.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, clap::ValueEnum)]
+pub enum VisMode {
+    /// Color by byte class: zero, 0xff, control, printable or other.
+    #[default]
+    Bytes,
+    /// Color by the executable section containing the byte.
+    Sections,
+}
+
+/// How `audio` colors each window of samples.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum AudioMode {
+    /// Loudness of the window, through the chosen colormap.
+    #[default]
+    Amplitude,
+    /// Low, mid and high band energy of the window as red, green and blue.
+    Bands,
+}
+
+/// Overlays drawn on a `vis` image.
+#[derive(Default)]
+pub struct VisOverlays {
+    /// Outline regions of similar entropy and flag anomalous ones.
+    pub regions: bool,
+    /// Named byte ranges to outline and label, drawn over the regions.
+    pub sections: Vec<Section>,
+}
+
+/// Result of rendering a `vis` image.
+pub struct VisRender {
+    /// The rendered image buffer.
+    pub image: image::RgbaImage,
+    /// Regions of similar entropy in file order; empty unless requested.
+    pub regions: Vec<Region>,
+    /// Names of sections that lie wholly past the end of the file and were not drawn.
+    pub skipped_sections: Vec<String>,
+    /// Executable sections and their colors in [`VisMode::Sections`]; empty otherwise.
+    pub legend: Vec<Section>,
+}
+
 /// Visualize a file by mapping each byte through a space‑filling curve.
 ///
-/// The returned image is square with the requested `width`.
-pub fn vis(input: &Path, width: u32, pattern_name: &str) -> Result<image::RgbaImage> {
+/// The returned image is square with the requested `width`, colored according to `mode`, with
+/// `overlays` drawn on top.
+pub fn vis(
+    input: &Path,
+    width: u32,
+    pattern_name: &str,
+    mode: VisMode,
+    overlays: &VisOverlays,
+) -> Result<VisRender> {
     let file = File::open(input)?;
     let mmap = mmap_readonly(&file)?;
 
@@ -59,22 +134,288 @@ pub fn vis(input: &Path, width: u32, pattern_name: &str) -> Result<image::RgbaIm
         bail!("input file is empty");
     }
 
-    let pattern = curve_from_name(pattern_name, 2, width)?;
+    let pattern = render_curve(pattern_name, 2, width)?;
+    let (mut image, legend) = match mode {
+        VisMode::Bytes => (
+            render_vis(&mmap, pattern.as_ref(), width, |_, byte| {
+                byte_to_color(byte)
+            }),
+            Vec::new(),
+        ),
+        VisMode::Sections => {
+            let legend = executable::sections(&mmap)?;
+            let image = render_vis(&mmap, pattern.as_ref(), width, |offset, byte| {
+                executable::byte_color(&legend, offset, byte)
+            });
+            (image, legend)
+        }
+    };
+    let regions = if overlays.regions {
+        let regions = segment::segment(&mmap, segment::BLOCK_BYTES);
+        segment::draw_regions(&mut image, pattern.as_ref(), mmap.len(), &regions);
+        regions
+    } else {
+        Vec::new()
+    };
+    let skipped_sections =
+        sections::draw_sections(&mut image, pattern.as_ref(), mmap.len(), &overlays.sections)
+            .into_iter()
+            .map(|section| section.name.clone())
+            .collect();
+    Ok(VisRender {
+        image,
+        regions,
+        skipped_sections,
+        legend,
+    })
+}
 
+/// Color each pixel of a `width×width` image by the byte of `data` that `pattern` places there,
+/// passing `color` the byte's offset and value.
+fn render_vis(
+    data: &[u8],
+    pattern: &dyn SpaceCurve,
+    width: u32,
+    color: impl Fn(usize, u8) -> image::Rgba<u8>,
+) -> image::RgbaImage {
     let mut imgbuf = image::ImageBuffer::new(width, width);
 
     let plen = pattern.length() as u128;
-    let mlen = mmap.len() as u128;
-    for i in 0..pattern.length() {
-        let p = pattern.point(i);
+    let mlen = data.len() as u128;
+    for (i, p) in (0..).zip(pattern.iter()) {
         // Integer scaling avoids float rounding that could produce idx == mlen.
-        let idx = ((i as u128) * mlen / plen) as usize;
-        let byte = mmap[idx.min(mmap.len() - 1)];
-        imgbuf.put_pixel(p[0], p[1], byte_to_color(byte));
+        let idx = ((i as u128) * mlen / plen).min(mlen - 1) as usize;
+        imgbuf.put_pixel(p[0], p[1], color(idx, data[idx]));
+    }
+    imgbuf
+}
+
+/// Visualize a WAV file by laying its samples along a space-filling curve.
+///
+/// The audio is cut into one window per pixel of a square `width` image. In
+/// [`AudioMode::Amplitude`] each pixel shows the window's loudness through `colormap`; in
+/// [`AudioMode::Bands`] its red, green and blue channels show the low, mid and high band
+/// energies. Levels use a decibel scale relative to the loudest window.
+pub fn audio(
+    input: &Path,
+    width: u32,
+    pattern_name: &str,
+    mode: AudioMode,
+    colormap: Colormap,
+) -> Result<image::RgbaImage> {
+    let audio = wav::load(input)?;
+    let pattern = render_curve(pattern_name, 2, width)?;
+    let windows = pattern.length() as usize;
+    let peak = |levels: &mut dyn Iterator<Item = f32>| levels.fold(0.0, f32::max);
+    let colors: Vec<image::Rgba<u8>> = match mode {
+        AudioMode::Amplitude => {
+            let gradient = Gradient::new(colormap, DEFAULT_ENDPOINTS, 256)
+                .ok_or_else(|| anyhow!("audio needs a gradient colormap"))?;
+            let levels = wav::loudness(&audio.samples, windows);
+            let loudest = peak(&mut levels.iter().copied());
+            levels
+                .iter()
+                .map(|&level| {
+                    let t = wav::decibel_scale(level, loudest);
+                    let color = gradient.color((t * 255.0).round() as usize, 1.0);
+                    image::Rgba([color.r(), color.g(), color.b(), 0xff])
+                })
+                .collect()
+        }
+        AudioMode::Bands => {
+            let bands = wav::band_energies(&audio, windows);
+            // Each band is scaled to its own peak so quiet treble still shows.
+            let loudest = [0, 1, 2].map(|band| peak(&mut bands.iter().map(|b| b[band])));
+            bands
+                .iter()
+                .map(|levels| {
+                    let [red, green, blue] = [0, 1, 2].map(|band| {
+                        (wav::decibel_scale(levels[band], loudest[band]) * 255.0).round() as u8
+                    });
+                    image::Rgba([red, green, blue, 0xff])
+                })
+                .collect()
+        }
+    };
+
+    let mut imgbuf = image::ImageBuffer::new(width, width);
+    for (i, color) in colors.into_iter().enumerate() {
+        let p = pattern.point(i as u32);
+        imgbuf.put_pixel(p[0], p[1], color);
+    }
+    Ok(imgbuf)
+}
+
+/// Target width in pixels of a `csv2map` image; cells are scaled up by a whole factor
+/// towards it.
+const CSV2MAP_WIDTH: u32 = 512;
+/// Color of `csv2map` cells that hold no value.
+const COLOR_EMPTY: image::Rgba<u8> = image::Rgba([0x20, 0x20, 0x20, 0xff]);
+
+/// Plot the numbers in column `value_col` of a CSV file as a heatmap along a curve.
+///
+/// Values are placed at their row order among the data rows, or at the curve index given by
+/// `index_col`, and colored through `colormap` from the smallest value to the largest. The
+/// curve is the smallest `pattern_name` grid that holds every index, and each cell is drawn
+/// as a square block so small series are still legible.
+pub fn csv2map(
+    input: &Path,
+    value_col: usize,
+    index_col: Option<usize>,
+    pattern_name: &str,
+    colormap: Colormap,
+) -> Result<image::RgbaImage> {
+    let samples = series::load(input, value_col, index_col)?;
+    let cells = samples
+        .iter()
+        .map(|s| u64::from(s.index) + 1)
+        .max()
+        .unwrap_or(1);
+    let mut requested = cells.isqrt();
+    if requested * requested < cells {
+        requested += 1;
+    }
+    let (side, _) = resolve_curve_dimension(pattern_name, 2, u32::try_from(requested)?)?;
+    let pattern = render_curve(pattern_name, 2, side)?;
+
+    let gradient = Gradient::new(colormap, DEFAULT_ENDPOINTS, 256)
+        .ok_or_else(|| anyhow!("csv2map needs a gradient colormap"))?;
+    let (min, max) = samples.iter().fold((f64::MAX, f64::MIN), |(lo, hi), s| {
+        (lo.min(s.value), hi.max(s.value))
+    });
+    let cell = (CSV2MAP_WIDTH / side).max(1);
+    let mut imgbuf = image::ImageBuffer::from_pixel(side * cell, side * cell, COLOR_EMPTY);
+    for sample in &samples {
+        let t = if max > min {
+            (sample.value - min) / (max - min)
+        } else {
+            0.5
+        };
+        let color = gradient.color((t * 255.0).round() as usize, 1.0);
+        let color = image::Rgba([color.r(), color.g(), color.b(), 0xff]);
+        let p = pattern.point(sample.index);
+        for y in 0..cell {
+            for x in 0..cell {
+                imgbuf.put_pixel(p[0] * cell + x, p[1] * cell + y, color);
+            }
+        }
+    }
+    Ok(imgbuf)
+}
+
+/// Positions of the pixels of a `width×height` image in the order `pattern_name` visits them.
+///
+/// The curve covers the smallest square it supports that holds the whole image; points that
+/// fall outside the image are skipped, so every pixel appears exactly once.
+fn curve_order(pattern_name: &str, width: u32, height: u32) -> Result<Vec<(u32, u32)>> {
+    let (side, _) = resolve_curve_dimension(pattern_name, 2, width.max(height))?;
+    let pattern = render_curve(pattern_name, 2, side)?;
+    Ok(pattern
+        .iter_range(0..pattern.length())
+        .filter(|p| p[0] < width && p[1] < height)
+        .map(|p| (p[0], p[1]))
+        .collect())
+}
+
+/// Flatten the image at `input` into a one-pixel-tall strip following `pattern_name`.
+///
+/// Neighbouring pixels in the strip are neighbours in the image, so 1D filters run over the
+/// strip keep much of the image's 2D locality.
+pub fn unravel(input: &Path, pattern_name: &str) -> Result<image::RgbaImage> {
+    let img = image::open(input)?.to_rgba8();
+    let order = curve_order(pattern_name, img.width(), img.height())?;
+    Ok(image::ImageBuffer::from_fn(
+        order.len() as u32,
+        1,
+        |x, _| {
+            let (px, py) = order[x as usize];
+            *img.get_pixel(px, py)
+        },
+    ))
+}
+
+/// Rebuild a `size` image from a strip written by [`unravel`] with the same `pattern_name`.
+///
+/// Without `size` the strip must hold a square image.
+pub fn reravel(
+    input: &Path,
+    pattern_name: &str,
+    size: Option<[u32; 2]>,
+) -> Result<image::RgbaImage> {
+    let strip = image::open(input)?.to_rgba8();
+    if strip.height() != 1 {
+        bail!("strip must be one pixel tall, not {}", strip.height());
+    }
+    let len = strip.width();
+    let [width, height] = match size {
+        Some(size) => size,
+        None => {
+            let side = u64::from(len).isqrt() as u32;
+            if side * side != len {
+                bail!("strip of {len} pixels is not a square image; pass --size WIDTHxHEIGHT");
+            }
+            [side, side]
+        }
+    };
+    if u64::from(width) * u64::from(height) != u64::from(len) {
+        bail!("strip of {len} pixels does not fill a {width}x{height} image");
+    }
+
+    let order = curve_order(pattern_name, width, height)?;
+    let mut imgbuf = image::ImageBuffer::new(width, height);
+    for (x, &(px, py)) in order.iter().enumerate() {
+        imgbuf.put_pixel(px, py, *strip.get_pixel(x as u32, 0));
     }
     Ok(imgbuf)
 }
 
+/// Number of bytes shown on each `hexmap` row.
+const HEXMAP_ROW_BYTES: usize = 16;
+
+/// Write a hexdump of `input` annotated with the curve coordinates used by `vis`.
+///
+/// Each row lists the byte offset, the pixel at which the row's first byte appears in a
+/// `width×width` `vis` image laid out along `pattern_name`, the bytes in hex, and their
+/// printable ASCII.
+pub fn hexmap(input: &Path, width: u32, pattern_name: &str, out: &mut impl Write) -> Result<()> {
+    let file = File::open(input)?;
+    let mmap = mmap_readonly(&file)?;
+
+    if mmap.is_empty() {
+        bail!("input file is empty");
+    }
+
+    let pattern = render_curve(pattern_name, 2, width)?;
+
+    let plen = pattern.length() as u128;
+    let mlen = mmap.len() as u128;
+    for (row, bytes) in mmap.chunks(HEXMAP_ROW_BYTES).enumerate() {
+        let offset = row * HEXMAP_ROW_BYTES;
+        // Inverse of the `vis` sampling: the first curve index showing this byte.
+        let index = (offset as u128 * plen).div_ceil(mlen).min(plen - 1) as u32;
+        let p = pattern.point(index);
+
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        let ascii: String = bytes
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    char::from(b)
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        writeln!(
+            out,
+            "{offset:08x}  {:>11}  {:<47}  |{ascii}|",
+            format!("({}, {})", p[0], p[1]),
+            hex.join(" ")
+        )?;
+    }
+    Ok(())
+}
+
 /// Result of rendering a map image.
 pub struct MapRender {
     /// The rendered image buffer.
@@ -85,6 +426,24 @@ pub struct MapRender {
     pub adjusted: bool,
 }
 
+/// Parameters controlling map rendering.
+pub struct MapOptions<'a> {
+    /// Output image size in pixels.
+    pub size: u32,
+    /// Requested logical curve dimension (side length).
+    pub curve_dimension: u32,
+    /// Number of curve axes; curves with more than two axes are flattened by `projection`.
+    pub dimensions: u32,
+    /// Pattern name for the curve.
+    pub pattern_name: &'a str,
+    /// Optional [start, end) offsets limiting which part of the curve is drawn.
+    pub chunk: Option<Range<u32>>,
+    /// Stroke rendering options.
+    pub stroke: StrokeOptions,
+    /// Mapping from curve axes onto the image plane.
+    pub projection: Projection,
+}
+
 /// Result of rendering a snake animation.
 pub struct SnakeRender {
     /// Actual curve dimension (side length) used for the grid.
@@ -99,6 +458,8 @@ pub struct SnakeOptions<'a> {
     pub size: u32,
     /// Requested logical curve dimension (side length).
     pub curve_dimension: u32,
+    /// Number of curve axes: 2 draws a flat map, 3 a rotating 3D view.
+    pub dimensions: u32,
     /// Pattern name for the curve.
     pub pattern_name: &'a str,
     /// Segment range to animate.
@@ -113,80 +474,122 @@ pub struct SnakeOptions<'a> {
     pub full_curve: Option<image::Rgba<u8>>,
 }
 
-/// Find the smallest curve dimension ≥ `requested_side` that satisfies the pattern constraints.
-fn resolve_curve_dimension(pattern_name: &str, requested_side: u32) -> Result<(u32, bool)> {
-    const DIMENSION: u32 = 2;
+/// Parameters controlling onion-peeling animation rendering.
+#[derive(Clone, Copy)]
+pub struct PeelOptions<'a> {
+    /// Output image size in pixels.
+    pub size: u32,
+    /// Requested logical curve dimension (side length).
+    pub curve_dimension: u32,
+    /// Pattern name for the curve.
+    pub pattern_name: &'a str,
+    /// Frames per second for the GIF.
+    pub fps: u16,
+    /// Stroke styling used for the drawn shells.
+    pub stroke: StrokeOptions,
+    /// Output GIF path.
+    pub output: &'a Path,
+    /// Optional color for rendering the full curve beneath the shells.
+    pub full_curve: Option<image::Rgba<u8>>,
+}
+
+/// Parameters controlling the animated `allrgb` reveal.
+#[derive(Clone, Copy)]
+pub struct AllrgbAnimation<'a> {
+    /// Output frame size in pixels; must divide the 4096-pixel image side.
+    pub size: u32,
+    /// Number of frames the reveal is spread over.
+    pub frames: u32,
+    /// Frames per second for the GIF.
+    pub fps: u16,
+    /// Output GIF path.
+    pub output: &'a Path,
+}
 
+/// Find the smallest curve side ≥ `requested_side` that satisfies the pattern constraints for a
+/// curve with `dimensions` axes.
+fn resolve_curve_dimension(
+    pattern_name: &str,
+    dimensions: u32,
+    requested_side: u32,
+) -> Result<(u32, bool)> {
     if requested_side == 0 {
         bail!("curve dimension must be >= 1");
     }
 
-    let initial_validation = registry::validate(pattern_name, DIMENSION, requested_side);
-    if initial_validation.is_ok() {
-        return Ok((requested_side, false));
+    if let Some(side) = registry::size_at_least(pattern_name, dimensions, requested_side)? {
+        return Ok((side, side != requested_side));
     }
+    let reason = registry::validate(pattern_name, dimensions, requested_side)
+        .err()
+        .map_or_else(String::new, |err| format!(": {err}"));
+    bail!("could not find a valid curve dimension >= {requested_side} for '{pattern_name}'{reason}")
+}
 
-    let mut last_err = initial_validation.unwrap_err();
-
-    let mut candidate = requested_side
-        .checked_next_power_of_two()
-        .and_then(|p| {
-            if p > requested_side {
-                Some(p)
-            } else {
-                p.checked_mul(2)
-            }
-        })
-        .ok_or_else(|| {
-            anyhow!(
-                "could not find a valid curve dimension >= {} for '{}': {}",
-                requested_side,
-                pattern_name,
-                last_err
-            )
-        })?;
-
-    while candidate > requested_side {
-        match registry::validate(pattern_name, DIMENSION, candidate) {
-            Ok(()) => return Ok((candidate, true)),
-            Err(err) => {
-                last_err = err;
-                candidate = match candidate.checked_mul(2) {
-                    Some(next) if next > candidate => next,
-                    _ => break,
-                };
-            }
+/// Check that `projection` is usable for a curve with `dimensions` axes of length `side`.
+fn validate_projection(projection: &Projection, dimensions: u32, side: u32) -> Result<()> {
+    let axis_count = dimensions as usize;
+    let [x_axis, y_axis] = projection.axes;
+    if x_axis >= axis_count || y_axis >= axis_count {
+        bail!(
+            "projection axes {},{} out of range for a {}-dimensional curve",
+            x_axis,
+            y_axis,
+            dimensions
+        );
+    }
+    if x_axis == y_axis {
+        bail!("projection axes must be distinct");
+    }
+    for &(axis, value) in &projection.slice {
+        if axis >= axis_count {
+            bail!(
+                "slice axis {} out of range for a {}-dimensional curve",
+                axis,
+                dimensions
+            );
+        }
+        if projection.axes.contains(&axis) {
+            bail!("slice axis {} is also a projection axis", axis);
+        }
+        if value >= side {
+            bail!(
+                "slice value {} for axis {} exceeds curve side {}",
+                value,
+                axis,
+                side
+            );
         }
     }
-
-    Err(anyhow!(
-        "could not find a valid curve dimension >= {} for '{}': {}",
-        requested_side,
-        pattern_name,
-        last_err
-    ))
+    Ok(())
 }
 
 /// Render a map of a curve using a requested grid dimension.
 ///
-/// - `size`: Output image width/height in pixels.
-/// - `curve_dimension`: Requested side length for the curve grid (renders `dimension×dimension` points).
-/// - `pattern_name`: Curve name.
-/// - `chunk`: Optional [start, end) offsets limiting which part of the curve is drawn.
-/// - `stroke`: Stroke rendering options.
-pub fn map(
-    size: u32,
-    curve_dimension: u32,
-    pattern_name: &str,
-    chunk: Option<Range<u32>>,
-    stroke: StrokeOptions,
-) -> Result<MapRender> {
+/// Curves with more than two axes are flattened onto the image plane through the options'
+/// projection.
+pub fn map(options: MapOptions<'_>) -> Result<MapRender> {
+    let MapOptions {
+        size,
+        curve_dimension,
+        dimensions,
+        pattern_name,
+        chunk,
+        stroke,
+        projection,
+    } = options;
+
     if stroke.line_width == 0 {
         bail!("line width must be >= 1");
     }
 
-    let (side, adjusted) = resolve_curve_dimension(pattern_name, curve_dimension)?;
-    let pattern = curve_from_name(pattern_name, 2, side)?;
+    if dimensions < 2 {
+        bail!("map requires a curve with at least two dimensions");
+    }
+
+    let (side, adjusted) = resolve_curve_dimension(pattern_name, dimensions, curve_dimension)?;
+    validate_projection(&projection, dimensions, side)?;
+    let pattern = render_curve(pattern_name, dimensions, side)?;
     let length = pattern.length();
     let chunk = chunk.unwrap_or(0..length);
 
@@ -203,7 +606,7 @@ pub fn map(
         );
     }
 
-    let imgbuf = render_map_image(size, side, chunk, stroke, &*pattern);
+    let imgbuf = render_projected_image(size, side, chunk, stroke, &projection, &*pattern);
     Ok(MapRender {
         image: imgbuf,
         side,
@@ -212,10 +615,14 @@ pub fn map(
 }
 
 /// Generate an animated snake GIF where a chunk of the curve marches across all offsets.
+///
+/// Three-dimensional curves are drawn in a 3D view that makes one full turn over the course
+/// of the animation.
 pub fn snake(options: SnakeOptions<'_>) -> Result<SnakeRender> {
     let SnakeOptions {
         size,
         curve_dimension,
+        dimensions,
         pattern_name,
         chunk,
         fps,
@@ -232,8 +639,12 @@ pub fn snake(options: SnakeOptions<'_>) -> Result<SnakeRender> {
         bail!("size {} exceeds GIF limits ({}).", size, u16::MAX);
     }
 
-    let (side, adjusted) = resolve_curve_dimension(pattern_name, curve_dimension)?;
-    let pattern = curve_from_name(pattern_name, 2, side)?;
+    if !(2..=3).contains(&dimensions) {
+        bail!("snake supports 2 or 3 dimensions, not {dimensions}");
+    }
+
+    let (side, adjusted) = resolve_curve_dimension(pattern_name, dimensions, curve_dimension)?;
+    let pattern = render_curve(pattern_name, dimensions, side)?;
     let length = pattern.length();
 
     if chunk.start >= chunk.end {
@@ -254,13 +665,11 @@ pub fn snake(options: SnakeOptions<'_>) -> Result<SnakeRender> {
         bail!("chunk must span at least two points for animation");
     }
 
-    let mut file = File::create(output)?;
-    let mut encoder = Encoder::new(&mut file, size as u16, size as u16, &[])?;
-    encoder.set_repeat(Repeat::Infinite)?;
+    let mut writer = GifWriter::new(File::create(output)?, size as u16, size as u16)?;
 
     let frame_delay = frame_delay_from_fps(fps);
 
-    let base_frame = full_curve.map(|foreground| {
+    let base_frame = full_curve.filter(|_| dimensions == 2).map(|foreground| {
         let palette = StrokeOptions {
             palette: MapPalette {
                 foreground,
@@ -273,6 +682,18 @@ pub fn snake(options: SnakeOptions<'_>) -> Result<SnakeRender> {
 
     for offset in 0..length {
         let start = (chunk.start + offset) % length;
+        if dimensions == 3 {
+            let view = OrbitView {
+                size,
+                side,
+                // Start from a corner so the first frame already shows depth.
+                yaw: FRAC_PI_4 + TAU * f64::from(offset) / f64::from(length),
+            };
+            let frame_image =
+                render_orbit_image(view, start, chunk_len, stroke, full_curve, &*pattern);
+            writer.write_rgba(&mut frame_image.into_raw(), frame_delay)?;
+            continue;
+        }
         let mut frame_image = base_frame
             .clone()
             .unwrap_or_else(|| render_chunk_image(size, side, start, chunk_len, stroke, &*pattern));
@@ -290,19 +711,66 @@ pub fn snake(options: SnakeOptions<'_>) -> Result<SnakeRender> {
         }
 
         let mut raw = frame_image.into_raw();
-        let mut frame = Frame::from_rgba_speed(size as u16, size as u16, &mut raw, 10);
-        frame.delay = frame_delay;
-        encoder.write_frame(&frame)?;
+        writer.write_rgba(&mut raw, frame_delay)?;
     }
 
     Ok(SnakeRender { side, adjusted })
 }
 
-/// Convert frames-per-second into a GIF frame delay (hundredths of a second).
-fn frame_delay_from_fps(fps: u16) -> u16 {
-    // GIF delays are centiseconds; clamp to at least 1cs to avoid zero-delay frames.
-    let fps = fps.max(1);
-    ((100 + (fps / 2)) / fps).max(1)
+/// Generate an animated GIF that draws the curve one L∞ shell per frame, from the outer
+/// surface inwards.
+///
+/// A step between two points is drawn with the inner of the two shells it touches, so steps
+/// that jump between shells appear once both shells are shown. Shells are those of the grid,
+/// so any curve can be peeled; for the onion curves each frame adds one contiguous run.
+pub fn peel(options: PeelOptions<'_>) -> Result<SnakeRender> {
+    let PeelOptions {
+        size,
+        curve_dimension,
+        pattern_name,
+        fps,
+        stroke,
+        output,
+        full_curve,
+    } = options;
+
+    if size > u16::MAX as u32 {
+        bail!("size {} exceeds GIF limits ({}).", size, u16::MAX);
+    }
+
+    let (side, adjusted) = resolve_curve_dimension(pattern_name, 2, curve_dimension)?;
+    let pattern = render_curve(pattern_name, 2, side)?;
+
+    let mut steps_by_shell = vec![Vec::new(); side.div_ceil(2) as usize];
+    let mut prev = shell_of(&pattern.point(0), side);
+    for idx in 1..pattern.length() {
+        let shell = shell_of(&pattern.point(idx), side);
+        steps_by_shell[prev.max(shell) as usize].push(idx - 1);
+        prev = shell;
+    }
+
+    let mut writer = GifWriter::new(File::create(output)?, size as u16, size as u16)?;
+    let frame_delay = frame_delay_from_fps(fps);
+    let mut frame = match full_curve {
+        Some(foreground) => {
+            let palette = StrokeOptions {
+                palette: MapPalette {
+                    foreground,
+                    background: stroke.palette.background,
+                },
+                ..stroke
+            };
+            render_map_image(size, side, 0..pattern.length(), palette, &*pattern)
+        }
+        None => image::ImageBuffer::from_pixel(size, size, stroke.palette.background),
+    };
+    for steps in &steps_by_shell {
+        draw_steps(&mut frame, size, side, steps, stroke, &*pattern);
+        let mut raw = frame.clone().into_raw();
+        writer.write_rgba(&mut raw, frame_delay)?;
+    }
+
+    Ok(SnakeRender { side, adjusted })
 }
 
 /// Generate a 4096×4096 image containing every RGB color exactly once.
@@ -311,9 +779,9 @@ fn frame_delay_from_fps(fps: u16) -> u16 {
 /// walking `colormap_name` in RGB space.
 pub fn allrgb(pattern_name: &str, colormap_name: &str) -> Result<image::RgbaImage> {
     let width = 4096;
-    let pattern = curve_from_name(pattern_name, 2, width)?;
+    let pattern = render_curve(pattern_name, 2, width)?;
     let mut imgbuf: image::RgbaImage = image::ImageBuffer::new(width, width);
-    let colormap = curve_from_name(colormap_name, 3, 256)?;
+    let colormap = render_curve(colormap_name, 3, 256)?;
 
     let mut pb = pbr::ProgressBar::new(4096);
     pb.format("╢▌▌░╟");
@@ -334,3 +802,126 @@ pub fn allrgb(pattern_name: &str, colormap_name: &str) -> Result<image::RgbaImag
     pb.finish();
     Ok(imgbuf)
 }
+
+/// Write an animated GIF that builds the `allrgb` image up in curve order.
+///
+/// Each frame shows the pixels filled so far, so the animation follows the colormap curve
+/// through the RGB cube while showing where each color lands in the 2D layout. Frames are
+/// scaled down to `animation.size` by averaging, with unfilled pixels counted as black.
+pub fn allrgb_animation(
+    pattern_name: &str,
+    colormap_name: &str,
+    animation: AllrgbAnimation<'_>,
+) -> Result<()> {
+    let AllrgbAnimation {
+        size,
+        frames,
+        fps,
+        output,
+    } = animation;
+    let width = 4096;
+    if size == 0 || width % size != 0 {
+        bail!("animation size must divide {width}, not {size}");
+    }
+    if frames == 0 {
+        bail!("animation needs at least one frame");
+    }
+
+    let pattern = render_curve(pattern_name, 2, width)?;
+    let colormap = render_curve(colormap_name, 3, 256)?;
+    let mut writer = GifWriter::new(File::create(output)?, size as u16, size as u16)?;
+    let frame_delay = frame_delay_from_fps(fps);
+
+    let mut pb = pbr::ProgressBar::new(u64::from(frames));
+    pb.format("╢▌▌░╟");
+    reveal_frames(&*pattern, &*colormap, size, frames, |frame| {
+        pb.inc();
+        writer.write_rgba(&mut frame.into_raw(), frame_delay)?;
+        Ok(())
+    })?;
+    pb.finish();
+    Ok(())
+}
+
+/// Fill a square layout along `pattern` with the colors of `colormap`, emitting `frames`
+/// evenly spaced `size×size` snapshots of the partly filled image.
+fn reveal_frames(
+    pattern: &dyn SpaceCurve,
+    colormap: &dyn SpaceCurve,
+    size: u32,
+    frames: u32,
+    mut emit: impl FnMut(image::RgbaImage) -> Result<()>,
+) -> Result<()> {
+    let length = pattern.length();
+    let width = u32::try_from(u64::from(length).isqrt()).unwrap_or(u32::MAX);
+    let cell = (width / size).max(1);
+    let area = u64::from(cell) * u64::from(cell);
+    let mut sums = vec![[0u64; 3]; (size * size) as usize];
+
+    let mut next = 0;
+    for frame in 1..=frames {
+        let end = (u64::from(length) * u64::from(frame) / u64::from(frames)) as u32;
+        for (p, c) in pattern
+            .iter_range(next..end)
+            .zip(colormap.iter_range(next..end))
+        {
+            let sum = &mut sums[((p[1] / cell) * size + p[0] / cell) as usize];
+            for channel in 0..3 {
+                sum[channel] += u64::from(c[channel]);
+            }
+        }
+        next = end;
+
+        let image = image::ImageBuffer::from_fn(size, size, |x, y| {
+            let [red, green, blue] = sums[(y * size + x) as usize].map(|v| (v / area) as u8);
+            image::Rgba([red, green, blue, 255])
+        });
+        emit(image)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_order_visits_every_pixel_once() {
+        for (width, height) in [(8, 8), (5, 3), (1, 7)] {
+            let mut order = curve_order("hilbert", width, height).unwrap();
+            assert_eq!(order.len(), (width * height) as usize);
+            order.sort_unstable();
+            order.dedup();
+            assert_eq!(order.len(), (width * height) as usize);
+        }
+    }
+
+    #[test]
+    fn render_curve_rejects_curves_beyond_u32() {
+        assert_eq!(
+            render_curve("hilbert", 2, 1 << 15).unwrap().length(),
+            1 << 30
+        );
+        let err = render_curve("hilbert", 2, 1 << 16).unwrap_err();
+        assert!(err.to_string().contains("u32"), "{err}");
+    }
+
+    #[test]
+    fn reveal_frames_fill_the_image_in_order() {
+        let pattern = curve_from_name("hilbert", 2, 8).unwrap();
+        let colormap = curve_from_name("zorder", 3, 4).unwrap();
+        let mut frames = Vec::new();
+        reveal_frames(&*pattern, &*colormap, 4, 4, |frame| {
+            frames.push(frame);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(frames.len(), 4);
+        let lit = |image: &image::RgbaImage| image.pixels().filter(|p| p.0[..3] != [0; 3]).count();
+        let counts: Vec<_> = frames.iter().map(lit).collect();
+        assert!(counts.windows(2).all(|w| w[0] <= w[1]), "{counts:?}");
+        assert!(counts[0] < counts[3]);
+        assert!(frames.iter().all(|f| f.dimensions() == (4, 4)));
+    }
+}
@@ -0,0 +1,368 @@
+//! Cross-curve comparison for the `compare` subcommand.
+//!
+//! Every registered curve that can be built on the requested grid is measured with
+//! [`spacecurve::analysis::metrics`] and ranked by the mean index gap between neighbouring
+//! cells, the measure the GUI's metrics table sorts by first; ties fall to the largest gap and
+//! then to the number of jumps. Lower is better for every column. A seeded shuffle of the
+//! grid's cells follows the ranking, unranked, as the baseline for a curve with no locality.
+//! The ranking is written as an aligned table, CSV or JSON, and can be accompanied by one image
+//! tiling every curve in rank order.
+
+use std::io::Write;
+
+use ab_glyph::PxScale;
+use anyhow::Result;
+use image::{Rgba, RgbaImage, imageops};
+use serde::Serialize;
+use spacecurve::{
+    SpaceCurve,
+    analysis::{self, Shuffle},
+    registry::{self, CurveFilter},
+};
+
+use crate::{
+    cmd::render_curve,
+    map::{MapPalette, Projection, StrokeOptions, render_projected_image},
+    sections::{label, label_font},
+};
+
+/// Color of the caption boxes on comparison tiles.
+const COLOR_CAPTION: Rgba<u8> = Rgba([0x30, 0x30, 0x30, 0xff]);
+/// Stroke used for the comparison tiles, matching the `map` defaults.
+const TILE_STROKE: StrokeOptions = StrokeOptions {
+    line_width: 1,
+    long_edges: true,
+    palette: MapPalette {
+        foreground: Rgba([0x80, 0x80, 0xff, 0xff]),
+        background: Rgba([0xff, 0xff, 0xff, 0xff]),
+    },
+};
+
+/// How the ranking is written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Aligned, human-readable columns.
+    #[default]
+    Text,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// A JSON array with one object per curve.
+    Json,
+}
+
+/// One ranked curve and its measures.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Entry {
+    /// Position in the ranking, from 1, or 0 for the unranked baseline.
+    pub rank: usize,
+    /// Registry key of the curve.
+    pub key: &'static str,
+    /// Display name of the curve.
+    pub name: &'static str,
+    /// Steps between consecutive indices that skip to a non-adjacent cell.
+    pub discontinuities: u32,
+    /// Longest distance between consecutive indices.
+    pub max_jump: f64,
+    /// Mean distance between consecutive indices.
+    pub mean_step: f64,
+    /// Mean index difference between adjacent cells.
+    pub mean_neighbor_gap: f64,
+    /// Largest index difference between adjacent cells.
+    pub max_neighbor_gap: u32,
+    /// Mean over cells of the smallest index difference to an adjacent cell.
+    pub nearest_neighbor_gap: f64,
+}
+
+/// Ranking of the curves that fit a grid, and the curves that do not.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Number of curve axes.
+    pub dimensions: u32,
+    /// Side length of every axis.
+    pub side: u32,
+    /// Measured curves, best first.
+    pub entries: Vec<Entry>,
+    /// The shuffled baseline, unless the grid has too many cells to shuffle.
+    pub baseline: Option<Entry>,
+    /// Registry keys of curves that cannot be built on the grid, with the reason.
+    pub skipped: Vec<(&'static str, String)>,
+}
+
+/// Measure and rank every registered curve passing `filter` on a grid of `dimensions` axes of
+/// `side` cells.
+///
+/// Curves that pass but cannot be built on the grid are listed in [`Comparison::skipped`].
+pub fn compare(dimensions: u32, side: u32, filter: CurveFilter) -> Comparison {
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in registry::filter(filter) {
+        let measured = render_curve(entry.key, dimensions, side)
+            .and_then(|curve| Entry::measure(entry.key, entry.display, curve.as_ref()));
+        match measured {
+            Ok(measured) => entries.push(measured),
+            Err(e) => skipped.push((entry.key, e.to_string())),
+        }
+    }
+    entries.sort_by(|a, b| {
+        a.mean_neighbor_gap
+            .total_cmp(&b.mean_neighbor_gap)
+            .then(a.max_neighbor_gap.cmp(&b.max_neighbor_gap))
+            .then(a.discontinuities.cmp(&b.discontinuities))
+            .then(a.key.cmp(b.key))
+    });
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.rank = i + 1;
+    }
+    let baseline = Shuffle::baseline(dimensions, side)
+        .ok()
+        .and_then(|shuffle| Entry::measure(BASELINE_KEY, "Shuffled (baseline)", &shuffle).ok());
+    Comparison {
+        dimensions,
+        side,
+        entries,
+        baseline,
+        skipped,
+    }
+}
+
+/// Key reported for the shuffled baseline.
+const BASELINE_KEY: &str = "shuffled";
+
+impl Entry {
+    /// Measure `curve`, leaving the rank unset.
+    fn measure(key: &'static str, name: &'static str, curve: &dyn SpaceCurve) -> Result<Self> {
+        let metrics = analysis::metrics(curve)?;
+        Ok(Self {
+            rank: 0,
+            key,
+            name,
+            discontinuities: metrics.discontinuities,
+            max_jump: metrics.max_jump,
+            mean_step: metrics.mean_step,
+            mean_neighbor_gap: metrics.mean_neighbor_gap,
+            max_neighbor_gap: metrics.max_neighbor_gap,
+            nearest_neighbor_gap: metrics.nearest_neighbor_gap.mean,
+        })
+    }
+}
+
+/// Write the ranking in `comparison` to `out` as `format`.
+pub fn write(comparison: &Comparison, format: Format, out: &mut impl Write) -> Result<()> {
+    match format {
+        Format::Text => {
+            writeln!(
+                out,
+                "{:>4}  {:<20} {:>6} {:>9} {:>9} {:>10} {:>9} {:>11}",
+                "rank",
+                "curve",
+                "jumps",
+                "max step",
+                "mean step",
+                "mean gap",
+                "max gap",
+                "nearest gap"
+            )?;
+            for e in comparison.entries.iter().chain(&comparison.baseline) {
+                let rank = if e.rank == 0 {
+                    "-".to_string()
+                } else {
+                    e.rank.to_string()
+                };
+                writeln!(
+                    out,
+                    "{:>4}  {:<20} {:>6} {:>9.2} {:>9.3} {:>10.1} {:>9} {:>11.2}",
+                    rank,
+                    e.name,
+                    e.discontinuities,
+                    e.max_jump,
+                    e.mean_step,
+                    e.mean_neighbor_gap,
+                    e.max_neighbor_gap,
+                    e.nearest_neighbor_gap
+                )?;
+            }
+        }
+        Format::Csv => {
+            writeln!(
+                out,
+                "rank,key,name,discontinuities,max_jump,mean_step,mean_neighbor_gap,\
+                 max_neighbor_gap,nearest_neighbor_gap"
+            )?;
+            for e in comparison.entries.iter().chain(&comparison.baseline) {
+                let rank = if e.rank == 0 {
+                    String::new()
+                } else {
+                    e.rank.to_string()
+                };
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    rank,
+                    e.key,
+                    e.name,
+                    e.discontinuities,
+                    e.max_jump,
+                    e.mean_step,
+                    e.mean_neighbor_gap,
+                    e.max_neighbor_gap,
+                    e.nearest_neighbor_gap
+                )?;
+            }
+        }
+        Format::Json => {
+            let rows: Vec<&Entry> = comparison
+                .entries
+                .iter()
+                .chain(&comparison.baseline)
+                .collect();
+            serde_json::to_writer_pretty(&mut *out, &rows)?;
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Tile a `tile×tile` map of every ranked curve into one image, best first, left to right.
+///
+/// Curves with more than two axes are flattened onto their first two. Each tile is captioned
+/// with its rank and curve name.
+pub fn render(comparison: &Comparison, tile: u32) -> Result<RgbaImage> {
+    let count = comparison.entries.len().max(1) as u32;
+    let columns = (1..=count).find(|c| c * c >= count).unwrap_or(count);
+    let rows = count.div_ceil(columns);
+    let mut image =
+        RgbaImage::from_pixel(columns * tile, rows * tile, TILE_STROKE.palette.background);
+    let font = label_font();
+    let scale = PxScale::from((tile as f32 / 16.0).clamp(10.0, 20.0));
+    for (i, entry) in comparison.entries.iter().enumerate() {
+        let curve = render_curve(entry.key, comparison.dimensions, comparison.side)?;
+        let mut map = render_projected_image(
+            tile,
+            comparison.side,
+            0..curve.length(),
+            TILE_STROKE,
+            &Projection::default(),
+            curve.as_ref(),
+        );
+        let caption = format!("{}. {}", entry.rank, entry.name);
+        label(
+            &mut map,
+            &font,
+            scale,
+            (tile / 2, 0),
+            &caption,
+            COLOR_CAPTION,
+        );
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        imageops::replace(
+            &mut image,
+            &map,
+            i64::from(column * tile),
+            i64::from(row * tile),
+        );
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_curves_that_fit_the_grid() {
+        let comparison = compare(2, 8, CurveFilter::default());
+        let ranks: Vec<_> = comparison.entries.iter().map(|e| e.rank).collect();
+        assert_eq!(ranks, (1..=comparison.entries.len()).collect::<Vec<_>>());
+        assert!(
+            comparison
+                .entries
+                .windows(2)
+                .all(|w| w[0].mean_neighbor_gap <= w[1].mean_neighbor_gap)
+        );
+        assert!(comparison.entries.iter().any(|e| e.key == "hilbert"));
+        assert!(comparison.entries.iter().all(|e| e.key != "hairyonion"));
+        // Only curves on power-of-three grids can't be built at size 8.
+        let skipped: Vec<_> = comparison.skipped.iter().map(|(key, _)| *key).collect();
+        assert_eq!(skipped, ["peano"]);
+    }
+
+    #[test]
+    fn skips_curves_that_do_not_fit() {
+        let comparison = compare(2, 6, experimental());
+        let skipped: Vec<_> = comparison.skipped.iter().map(|(key, _)| *key).collect();
+        assert!(skipped.contains(&"hilbert"));
+        assert!(comparison.entries.iter().any(|e| e.key == "onion"));
+        assert!(comparison.entries.iter().any(|e| e.key == "hairyonion"));
+    }
+
+    /// A filter passing every curve, experimental ones included.
+    fn experimental() -> CurveFilter {
+        CurveFilter {
+            include_experimental: true,
+            ..CurveFilter::default()
+        }
+    }
+
+    #[test]
+    fn ranks_only_continuous_curves_when_asked() {
+        let comparison = compare(
+            2,
+            8,
+            CurveFilter {
+                continuous: true,
+                ..experimental()
+            },
+        );
+        let mut keys: Vec<_> = comparison.entries.iter().map(|e| e.key).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["hairyonion", "hilbert", "moore", "scan"]);
+        let skipped: Vec<_> = comparison.skipped.iter().map(|(key, _)| *key).collect();
+        assert_eq!(skipped, ["peano"]);
+    }
+
+    #[test]
+    fn writes_every_format() {
+        let comparison = compare(2, 4, CurveFilter::default());
+        for format in [Format::Text, Format::Csv, Format::Json] {
+            let mut out = Vec::new();
+            write(&comparison, format, &mut out).unwrap();
+            let text = String::from_utf8(out).unwrap();
+            assert!(text.contains("Hilbert"), "{format:?}: {text}");
+        }
+        let mut out = Vec::new();
+        write(&comparison, Format::Json, &mut out).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            parsed.as_array().unwrap().len(),
+            comparison.entries.len() + 1
+        );
+    }
+
+    #[test]
+    fn baseline_trails_every_curve() {
+        let comparison = compare(2, 8, CurveFilter::default());
+        let baseline = comparison.baseline.as_ref().expect("baseline");
+        assert_eq!((baseline.rank, baseline.key), (0, BASELINE_KEY));
+        assert!(
+            comparison
+                .entries
+                .iter()
+                .all(|e| e.mean_neighbor_gap < baseline.mean_neighbor_gap)
+        );
+        let mut out = Vec::new();
+        write(&comparison, Format::Csv, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(
+            text.lines().last().unwrap().starts_with(",shuffled,"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn tiles_every_curve() {
+        let comparison = compare(2, 4, CurveFilter::default());
+        let image = render(&comparison, 64).unwrap();
+        let columns = (1..).find(|c| c * c >= comparison.entries.len()).unwrap() as u32;
+        assert_eq!(image.width(), columns * 64);
+    }
+}
@@ -0,0 +1,169 @@
+//! Section layout of ELF, PE and Mach-O executables for `vis --mode sections`.
+//!
+//! The headers are parsed with the `object` crate and every section stored in the file becomes
+//! a [`Section`], each given its own hue. Files without section tables fall back to their
+//! segments. Bytes before the first section are reported as the headers.
+
+use anyhow::{Result, anyhow};
+use image::Rgba;
+use object::{Object, ObjectSection, ObjectSegment};
+
+use crate::sections::Section;
+
+/// Hue step between consecutive sections, the golden angle, so neighbours contrast.
+const HUE_STEP: f32 = 137.508;
+/// Lowest value of any channel of a section color.
+const CHANNEL_FLOOR: f32 = 64.0;
+/// Color of the bytes before the first section.
+const COLOR_HEADERS: Rgba<u8> = Rgba([0xd0, 0xd0, 0xd0, 0xff]);
+/// Color of bytes outside every section.
+const COLOR_UNMAPPED: Rgba<u8> = Rgba([0x30, 0x30, 0x30, 0xff]);
+
+/// Sections of the executable in `data`, sorted by offset, with the headers first.
+///
+/// Fails when `data` is not a recognised executable or object file.
+pub fn sections(data: &[u8]) -> Result<Vec<Section>> {
+    let file = object::File::parse(data)
+        .map_err(|e| anyhow!("not a recognised ELF, PE or Mach-O file: {e}"))?;
+    let mut ranges: Vec<(String, u64, u64)> = file
+        .sections()
+        .filter_map(|section| {
+            let (offset, size) = section.file_range()?;
+            let name = section.name().ok().filter(|name| !name.is_empty())?;
+            let name = match section.segment_name() {
+                Ok(Some(segment)) => format!("{segment},{name}"),
+                _ => name.to_string(),
+            };
+            Some((name, offset, size))
+        })
+        .collect();
+    if ranges.is_empty() {
+        ranges = file
+            .segments()
+            .enumerate()
+            .map(|(i, segment)| {
+                let (offset, size) = segment.file_range();
+                let name = match segment.name() {
+                    Ok(Some(name)) if !name.is_empty() => name.to_string(),
+                    _ => format!("segment {i}"),
+                };
+                (name, offset, size)
+            })
+            .collect();
+    }
+    ranges.retain(|&(_, _, size)| size > 0);
+    ranges.sort_by_key(|&(_, offset, _)| offset);
+
+    let clamp = |value: u64| usize::try_from(value).unwrap_or(usize::MAX).min(data.len());
+    let mut sections = Vec::with_capacity(ranges.len() + 1);
+    if let Some(&(_, first, _)) = ranges.first()
+        && first > 0
+    {
+        sections.push(Section {
+            name: "headers".to_string(),
+            range: 0..clamp(first),
+            color: COLOR_HEADERS,
+        });
+    }
+    for (i, (name, offset, size)) in ranges.into_iter().enumerate() {
+        sections.push(Section {
+            name,
+            range: clamp(offset)..clamp(offset.saturating_add(size)),
+            color: hue(i as f32 * HUE_STEP),
+        });
+    }
+    Ok(sections)
+}
+
+/// Color of the byte at `offset` with value `byte`, given `sections` sorted by offset.
+///
+/// Zero bytes are drawn at half brightness so padding stands out within a section.
+pub fn byte_color(sections: &[Section], offset: usize, byte: u8) -> Rgba<u8> {
+    // Later sections win where ranges overlap, matching the legend order.
+    let candidates = sections.partition_point(|section| section.range.start <= offset);
+    let color = sections[..candidates]
+        .iter()
+        .rev()
+        .find(|section| section.range.contains(&offset))
+        .map_or(COLOR_UNMAPPED, |section| section.color);
+    if byte == 0 {
+        let [red, green, blue, alpha] = color.0;
+        Rgba([red / 2, green / 2, blue / 2, alpha])
+    } else {
+        color
+    }
+}
+
+/// A saturated color of the given hue, in degrees.
+fn hue(degrees: f32) -> Rgba<u8> {
+    let sector = degrees.rem_euclid(360.0) / 60.0;
+    let rising = sector.fract();
+    let (red, green, blue) = match sector as u32 {
+        0 => (1.0, rising, 0.0),
+        1 => (1.0 - rising, 1.0, 0.0),
+        2 => (0.0, 1.0, rising),
+        3 => (0.0, 1.0 - rising, 1.0),
+        4 => (rising, 0.0, 1.0),
+        _ => (1.0, 0.0, 1.0 - rising),
+    };
+    // Keep every hue bright enough to read against the unmapped background.
+    let channel = |value: f32| (CHANNEL_FLOOR + value * (255.0 - CHANNEL_FLOOR)).round() as u8;
+    Rgba([channel(red), channel(green), channel(blue), 0xff])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn parses_the_running_executable() {
+        let data = fs::read(env::current_exe().unwrap()).unwrap();
+        let sections = sections(&data).unwrap();
+        assert!(sections.len() > 2);
+        assert!(
+            sections
+                .windows(2)
+                .all(|w| w[0].range.start <= w[1].range.start)
+        );
+        assert!(sections.iter().all(|s| s.range.end <= data.len()));
+        assert!(sections.iter().any(|s| s.name.contains("text")));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(sections(b"plain text, not an executable").is_err());
+    }
+
+    #[test]
+    fn colors_bytes_by_section() {
+        let red = Rgba([0xff, 0, 0, 0xff]);
+        let blue = Rgba([0, 0, 0xff, 0xff]);
+        let sections = [
+            Section {
+                name: "a".to_string(),
+                range: 0..10,
+                color: red,
+            },
+            Section {
+                name: "b".to_string(),
+                range: 20..30,
+                color: blue,
+            },
+        ];
+        assert_eq!(byte_color(&sections, 5, 1), red);
+        assert_eq!(byte_color(&sections, 15, 1), COLOR_UNMAPPED);
+        assert_eq!(byte_color(&sections, 25, 1), blue);
+        assert_eq!(byte_color(&sections, 25, 0), Rgba([0, 0, 0x7f, 0xff]));
+        assert_eq!(byte_color(&sections, 40, 1), COLOR_UNMAPPED);
+    }
+
+    #[test]
+    fn hues_are_distinct_and_bright() {
+        let colors: Vec<_> = (0..6).map(|i| hue(i as f32 * 60.0)).collect();
+        assert_eq!(colors[0], Rgba([0xff, 0x40, 0x40, 0xff]));
+        assert_eq!(colors[2], Rgba([0x40, 0xff, 0x40, 0xff]));
+        assert_eq!(colors[4], Rgba([0x40, 0x40, 0xff, 0xff]));
+    }
+}
@@ -3,10 +3,26 @@
 //! This crate exposes helpers used by the `scurve` binary as a tiny library so
 //! they can be reused from other binaries (for example, the GUI).
 
+/// Bulk `vis` rendering of a directory into a gallery.
+pub mod batch;
+/// Content-addressed cache of rendered outputs.
+pub mod cache;
 /// Commands for generating images from inputs and patterns.
 pub mod cmd;
+/// Cross-curve metric ranking for `compare`.
+pub mod compare;
+/// Section layout of executables for coloring `vis` images.
+pub mod executable;
 /// Helpers to render maps and drawing primitives.
 pub mod map;
+/// Named byte ranges overlaid on `vis` images.
+pub mod sections;
+/// Entropy segmentation and anomaly flagging for `vis`.
+pub mod segment;
+/// Numeric series read from CSV files for `csv2map`.
+pub mod series;
+/// WAV decoding and loudness analysis for `audio` images.
+pub mod wav;
 
 // Re-export command functionality for potential library use.
 pub use cmd::*;
@@ -5,27 +5,54 @@
 
 use std::{
     fmt::Display,
+    fs::{self, File},
+    io::{self, BufWriter},
+    num::NonZeroUsize,
     ops::Range,
     path::{Path, PathBuf},
     process,
     str::FromStr,
+    thread,
 };
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::{Parser, Subcommand};
-use colornames::Color;
 use image::{Rgba, RgbaImage};
-use spacecurve::registry;
+use scurve_gui::{
+    colormap::Colormap,
+    headless::{self, HeadlessConfig},
+};
+use serde::Serialize;
+use spacecurve::registry::{self, CurveFilter};
 
+/// Bulk `vis` rendering of a directory into a gallery.
+mod batch;
+/// Content-addressed cache of rendered outputs.
+mod cache;
 /// CLI command implementations.
 mod cmd;
+/// Cross-curve metric ranking for `compare`.
+mod compare;
+/// Section layout of executables for coloring `vis` images.
+mod executable;
 /// Rendering helpers shared by the CLI.
 mod map;
-
-use crate::map::MapPalette;
+/// Named byte ranges overlaid on `vis` images.
+mod sections;
+/// Entropy segmentation and anomaly flagging for `vis`.
+mod segment;
+/// Numeric series read from CSV files for `csv2map`.
+mod series;
+/// WAV decoding and loudness analysis for `audio` images.
+mod wav;
+
+use crate::{
+    cache::{Cache, Key, KeyBuilder, Report},
+    map::{MapPalette, Projection},
+};
 
 /// Half-open range of curve offsets parsed from `--chunk`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize)]
 struct ChunkOffsets {
     /// Inclusive start offset for rendering.
     start: u32,
@@ -67,81 +94,70 @@ impl FromStr for ChunkOffsets {
     }
 }
 
-/// Validate a curve name against the known set.
+/// Resolve a curve name or alias to its canonical key.
 fn parse_curve_name(s: &str) -> Result<String, String> {
-    if registry::CURVE_NAMES.contains(&s) {
-        Ok(s.to_string())
-    } else {
-        Err(format!(
-            "Invalid curve name '{}'. Valid options: {}",
-            s,
-            registry::CURVE_NAMES.join(", ")
-        ))
-    }
+    registry::lookup(s)
+        .map(|entry| entry.key.to_string())
+        .map_err(|err| format!("{err}. Valid options: {}", registry::CURVE_NAMES.join(", ")))
 }
 
-/// Parse a named or hex color into an `Rgba` value (alpha defaults to 0xff).
-///
-/// Supports CSS color names via `colornames`, short/long hex (RGB/RRGGBB),
-/// and optional alpha (RGBA/RRGGBBAA) with or without a leading `#`.
-fn parse_rgba_color(input: &str) -> Result<Rgba<u8>, String> {
-    fn parse_hex_rgba(hex: &str) -> Option<Rgba<u8>> {
-        use std::ops::Range;
-
-        let raw = hex.trim_start_matches('#');
-        if !raw.as_bytes().iter().all(|b| b.is_ascii_hexdigit()) {
-            return None;
-        }
+/// Parse the key of a built-in gradient colormap.
+fn parse_colormap(s: &str) -> Result<Colormap, String> {
+    match Colormap::from_key(s) {
+        Some(Colormap::Solid | Colormap::Custom) | None => Err(format!(
+            "Invalid colormap '{s}'. Valid options: viridis, turbo, magma, plasma, cividis"
+        )),
+        Some(colormap) => Ok(colormap),
+    }
+}
 
-        let from_pair =
-            |range: Range<usize>| -> Option<u8> { u8::from_str_radix(&raw[range], 16).ok() };
-        let from_nibble = |idx: usize| -> Option<u8> {
-            u8::from_str_radix(&raw[idx..idx + 1], 16)
-                .ok()
-                .map(|v| v * 17)
-        };
+/// Parse a projection axis pair in `X,Y` form.
+fn parse_axes(s: &str) -> Result<[usize; 2], String> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| "axes must be in X,Y form".to_string())?;
+    let parse_axis = |axis: &str| -> Result<usize, String> {
+        axis.trim()
+            .parse::<usize>()
+            .map_err(|_| format!("invalid axis '{axis}': expected a non-negative integer"))
+    };
+    Ok([parse_axis(x)?, parse_axis(y)?])
+}
 
-        match raw.len() {
-            3 => Some(Rgba([
-                from_nibble(0)?,
-                from_nibble(1)?,
-                from_nibble(2)?,
-                0xff,
-            ])),
-            4 => Some(Rgba([
-                from_nibble(0)?,
-                from_nibble(1)?,
-                from_nibble(2)?,
-                from_nibble(3)?,
-            ])),
-            6 => Some(Rgba([
-                from_pair(0..2)?,
-                from_pair(2..4)?,
-                from_pair(4..6)?,
-                0xff,
-            ])),
-            8 => Some(Rgba([
-                from_pair(0..2)?,
-                from_pair(2..4)?,
-                from_pair(4..6)?,
-                from_pair(6..8)?,
-            ])),
-            _ => None,
-        }
-    }
+/// Parse a window size in `WIDTHxHEIGHT` form.
+fn parse_window_size(s: &str) -> Result<[f32; 2], String> {
+    parse_image_size(s).map(|size| size.map(|side| side as f32))
+}
 
-    let trimmed = input.trim();
-    if let Some(rgba) = parse_hex_rgba(trimmed) {
-        return Ok(rgba);
-    }
+/// Parse an image size in `WIDTHxHEIGHT` form.
+fn parse_image_size(s: &str) -> Result<[u32; 2], String> {
+    let (width, height) = s
+        .split_once('x')
+        .ok_or_else(|| "size must be in WIDTHxHEIGHT form".to_string())?;
+    let parse_side = |side: &str| -> Result<u32, String> {
+        side.trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|&side| side > 0)
+            .ok_or_else(|| format!("invalid size '{side}': expected a positive integer"))
+    };
+    Ok([parse_side(width)?, parse_side(height)?])
+}
 
-    let color: Color = trimmed.try_into().map_err(|_| {
-        format!(
-            "invalid color '{input}': use a named color or hex (RGB/RRGGBB with optional alpha, leading '#' optional)"
-        )
-    })?;
-    let (red, green, blue) = color.rgb();
-    Ok(Rgba([red, green, blue, 0xff]))
+/// Parse a slice constraint in `AXIS=VALUE` form.
+fn parse_slice(s: &str) -> Result<(usize, u32), String> {
+    let (axis, value) = s
+        .split_once('=')
+        .ok_or_else(|| "slice must be in AXIS=VALUE form".to_string())?;
+    let axis = axis
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("invalid slice axis '{axis}': expected a non-negative integer"))?;
+    let value = value
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("invalid slice value '{value}': expected a non-negative integer"))?;
+    Ok((axis, value))
 }
 
 #[derive(Parser)]
@@ -153,11 +169,47 @@ struct Cli {
     #[arg(short, action = clap::ArgAction::Count, help = "Sets the level of verbosity")]
     v: u8,
 
+    /// Reuse identical earlier renders of `vis`, `map` and `allrgb`.
+    #[arg(
+        long,
+        global = true,
+        help = "Reuse identical earlier renders of vis, map and allrgb"
+    )]
+    cache: bool,
+
+    /// Render from scratch, overriding `--cache`.
+    #[arg(
+        long,
+        global = true,
+        help = "Render from scratch even when the cache is enabled"
+    )]
+    no_cache: bool,
+
+    /// Directory holding cached renders, instead of the per-user cache directory.
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Keep cached renders in DIR instead of the user cache directory"
+    )]
+    cache_dir: Option<PathBuf>,
+
     /// Command to execute.
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Operations of the `cache` subcommand.
+#[derive(Subcommand)]
+enum CacheAction {
+    #[command(about = "Remove every cached render")]
+    /// Delete every entry in the cache directory.
+    Clear,
+    #[command(about = "Print the cache directory")]
+    /// Print where cached renders are kept.
+    Dir,
+}
+
 /// Screenshot target for the GUI.
 #[derive(Clone, Copy, Debug, clap::ValueEnum)]
 enum ScreenshotPane {
@@ -176,6 +228,401 @@ enum ScreenshotPane {
     Settings3D,
 }
 
+/// Options for the `audio` subcommand.
+#[derive(Clone, Debug, clap::Args)]
+struct AudioArgs {
+    #[arg(short = 'p', help = &format!("Pattern name (options: {})", registry::CURVE_NAMES.join(", ")), value_parser = parse_curve_name)]
+    /// Optional pattern name (defaults to `hilbert`).
+    pattern: Option<String>,
+
+    #[arg(short = 'w', help = "Image width")]
+    /// Output image width/height in pixels.
+    width: Option<u32>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        help = "Color windows by loudness, or by low/mid/high band energy as red/green/blue"
+    )]
+    /// How windows of samples are colored.
+    mode: cmd::AudioMode,
+
+    #[arg(
+        long,
+        default_value = "magma",
+        value_parser = parse_colormap,
+        help = "Colormap for --mode amplitude (viridis, turbo, magma, plasma, cividis)"
+    )]
+    /// Gradient used for loudness.
+    colormap: Colormap,
+
+    #[arg(help = "WAV file to visualise")]
+    /// Input audio file.
+    input: PathBuf,
+
+    #[arg(help = "Optional output file path; opens a viewer when omitted")]
+    /// Optional output file path (launches a viewer when not provided).
+    output: Option<PathBuf>,
+}
+
+/// Stroke styling flags shared by `map` and `snake`.
+#[derive(Clone, Copy, Debug, clap::Args)]
+struct StrokeArgs {
+    #[arg(
+        short = 'w',
+        long = "line-width",
+        value_name = "PIXELS",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Line width in pixels for the curve stroke"
+    )]
+    /// Stroke width for the rendered curve.
+    line_width: u32,
+
+    #[arg(
+        long = "fg",
+        visible_alias = "foreground",
+        value_parser = map::parse_rgba_color,
+        default_value = "#8080ff",
+        value_name = "HEX",
+        help = "Foreground color (name or hex; RGB/RRGGBB with optional alpha, '#' optional)"
+    )]
+    /// Stroke color for the curve.
+    foreground: Rgba<u8>,
+
+    #[arg(
+        long = "bg",
+        visible_alias = "background",
+        value_parser = map::parse_rgba_color,
+        default_value = "#ffffff",
+        value_name = "HEX",
+        help = "Background color (name or hex; RGB/RRGGBB with optional alpha, '#' optional)"
+    )]
+    /// Background color for the map.
+    background: Rgba<u8>,
+
+    #[arg(
+        long = "long",
+        default_value_t = false,
+        help = "Include long edges (segments longer than 1 unit)"
+    )]
+    /// Render long edges between non-adjacent points.
+    long_edges: bool,
+}
+
+impl StrokeArgs {
+    /// Stroke options for rendering.
+    fn options(self) -> map::StrokeOptions {
+        map::StrokeOptions {
+            line_width: self.line_width,
+            long_edges: self.long_edges,
+            palette: MapPalette {
+                foreground: self.foreground,
+                background: self.background,
+            },
+        }
+    }
+}
+
+/// Options for the `list-curves` subcommand.
+#[derive(Clone, Debug, clap::Args)]
+struct ListCurvesArgs {
+    #[arg(
+        short = 'd',
+        long = "dims",
+        value_name = "N",
+        help = "Only list curves that support this many dimensions"
+    )]
+    /// Number of dimensions the listed curves must support.
+    dims: Option<u32>,
+
+    #[arg(
+        short = 's',
+        long = "size",
+        value_name = "SIDE",
+        help = "Only list curves that support this side length"
+    )]
+    /// Side length the listed curves must support.
+    size: Option<u32>,
+
+    #[arg(
+        long = "continuous",
+        help = "Only list curves that always step between adjacent cells"
+    )]
+    /// Only list continuous curves.
+    continuous: bool,
+}
+
+/// Options for the `compare` subcommand.
+#[derive(Clone, Debug, clap::Args)]
+struct CompareArgs {
+    #[arg(
+        short = 'd',
+        long = "dims",
+        value_name = "N",
+        default_value_t = 2,
+        value_parser = clap::value_parser!(u32).range(2..),
+        help = "Number of curve dimensions"
+    )]
+    /// Number of curve dimensions.
+    dims: u32,
+
+    #[arg(
+        short = 's',
+        long = "size",
+        value_name = "SIDE",
+        default_value_t = 16,
+        help = "Side length of every curve axis"
+    )]
+    /// Side length of the curve grid.
+    size: u32,
+
+    #[arg(
+        long = "format",
+        value_enum,
+        default_value = "text",
+        help = "Format of the ranking written to stdout"
+    )]
+    /// Output format for the ranking.
+    format: compare::Format,
+
+    #[arg(
+        long = "experimental",
+        help = "Include experimental curves (e.g. Hairy Onion)"
+    )]
+    /// Include experimental curves in the comparison.
+    experimental: bool,
+
+    #[arg(
+        long = "continuous",
+        help = "Only rank curves that always step between adjacent cells"
+    )]
+    /// Restrict the comparison to continuous curves.
+    continuous: bool,
+
+    #[arg(
+        long = "image",
+        value_name = "PATH",
+        help = "Also write an image tiling every ranked curve"
+    )]
+    /// Optional path for the combined comparison image.
+    image: Option<PathBuf>,
+
+    #[arg(
+        long = "tile",
+        value_name = "PX",
+        default_value_t = 256,
+        value_parser = clap::value_parser!(u32).range(16..),
+        help = "Size in pixels of each curve in the comparison image"
+    )]
+    /// Tile size for the comparison image.
+    tile: u32,
+}
+
+/// Options for the `csv2map` subcommand.
+#[derive(Clone, Debug, clap::Args)]
+struct Csv2mapArgs {
+    #[arg(short = 'p', help = &format!("Pattern name (options: {})", registry::CURVE_NAMES.join(", ")), value_parser = parse_curve_name)]
+    /// Optional pattern name (defaults to `hilbert`).
+    pattern: Option<String>,
+
+    #[arg(
+        long = "value-col",
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Column holding the values, counting from 1"
+    )]
+    /// One-based column of the plotted values.
+    value_col: u64,
+
+    #[arg(
+        long = "index-col",
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Column holding each row's curve index, counting from 1 (defaults to row order)"
+    )]
+    /// Optional one-based column of curve indices.
+    index_col: Option<u64>,
+
+    #[arg(
+        long,
+        default_value = "viridis",
+        value_parser = parse_colormap,
+        help = "Colormap for the values (viridis, turbo, magma, plasma, cividis)"
+    )]
+    /// Gradient used for the values.
+    colormap: Colormap,
+
+    #[arg(help = "CSV file to plot")]
+    /// Input CSV file.
+    input: PathBuf,
+
+    #[arg(help = "Optional output file path; opens a viewer when omitted")]
+    /// Optional output file path (launches a viewer when not provided).
+    output: Option<PathBuf>,
+}
+
+/// Options for the `batch` subcommand.
+#[derive(Clone, Debug, clap::Args)]
+struct BatchArgs {
+    #[arg(short = 'p', help = &format!("Pattern name (options: {})", registry::CURVE_NAMES.join(", ")), value_parser = parse_curve_name)]
+    /// Optional pattern name (defaults to `hilbert`).
+    pattern: Option<String>,
+
+    #[arg(short = 'w', help = "Image width")]
+    /// Output image width/height in pixels.
+    width: Option<u32>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        help = "Color bytes by value class, or by the ELF/PE/Mach-O section holding them"
+    )]
+    /// How bytes are colored.
+    mode: cmd::VisMode,
+
+    #[arg(long, help = "Outline regions of similar entropy in every image")]
+    /// Outline regions of similar entropy, flagging anomalies.
+    regions: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "TOML or JSON file of named byte ranges to outline and label"
+    )]
+    /// Optional sections file whose byte ranges are drawn over every image.
+    sections: Option<PathBuf>,
+
+    #[arg(
+        long = "thumb",
+        value_name = "PX",
+        default_value_t = 128,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Largest thumbnail width in the gallery"
+    )]
+    /// Thumbnail size in pixels.
+    thumb: u32,
+
+    #[arg(
+        short = 'j',
+        long = "jobs",
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Files rendered at once (defaults to the number of CPUs)"
+    )]
+    /// Optional number of files rendered in parallel.
+    jobs: Option<u64>,
+
+    #[arg(
+        long = "out",
+        value_name = "DIR",
+        help = "Directory for the images and index.html"
+    )]
+    /// Output directory for the gallery.
+    out: PathBuf,
+
+    #[arg(help = "Directory of files to visualise")]
+    /// Input directory.
+    input: PathBuf,
+}
+
+/// Options shared by the `unravel` and `reravel` subcommands.
+#[derive(Clone, Debug, clap::Args)]
+struct RavelArgs {
+    #[arg(short = 'p', help = &format!("Pattern name (options: {})", registry::CURVE_NAMES.join(", ")), value_parser = parse_curve_name)]
+    /// Optional pattern name (defaults to `hilbert`).
+    pattern: Option<String>,
+
+    #[arg(help = "Input image")]
+    /// Input image or strip.
+    input: PathBuf,
+
+    #[arg(help = "Optional output file path; opens a viewer when omitted")]
+    /// Optional output file path (launches a viewer when not provided).
+    output: Option<PathBuf>,
+}
+
+/// Options for writing `allrgb` as an animation instead of a still image.
+#[derive(Clone, Copy, Debug, Serialize, clap::Args)]
+struct AnimateArgs {
+    #[arg(
+        long = "animate",
+        default_value_t = false,
+        requires = "output",
+        help = "Write an animated GIF that fills the image in curve order"
+    )]
+    /// Emit a GIF revealing the image in colormap-curve order instead of a still image.
+    animate: bool,
+
+    #[arg(
+        short = 's',
+        long = "size",
+        default_value_t = 512,
+        requires = "animate",
+        help = "Animation frame size in pixels (must divide 4096)"
+    )]
+    /// Frame size for the animation.
+    size: u32,
+
+    #[arg(
+        long = "frames",
+        default_value_t = 64,
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "animate",
+        help = "Number of frames in the animation"
+    )]
+    /// Number of animation frames.
+    frames: u32,
+
+    #[arg(
+        long = "fps",
+        default_value_t = 20,
+        value_parser = clap::value_parser!(u16).range(1..=120),
+        requires = "animate",
+        help = "Frames per second for the animated GIF"
+    )]
+    /// Frames per second for the animation (1-120).
+    fps: u16,
+}
+
+/// Options for rendering a screenshot offscreen instead of through a window.
+#[derive(Clone, Debug, clap::Args)]
+struct OffscreenArgs {
+    #[arg(
+        long = "headless",
+        help = "Render offscreen in software instead of opening a window"
+    )]
+    /// Render offscreen without a window.
+    headless: bool,
+
+    #[arg(
+        long = "size",
+        default_value = "800x600",
+        value_parser = parse_window_size,
+        help = "Window size in points for --headless, as WIDTHxHEIGHT"
+    )]
+    /// Window size in points.
+    size: [f32; 2],
+
+    #[arg(
+        long = "scale",
+        default_value_t = 1.0,
+        help = "Pixels per point for --headless (2 for high-DPI images)"
+    )]
+    /// Pixels per point of the output.
+    scale: f32,
+
+    #[arg(
+        long = "link",
+        help = "Shared-link state to apply for --headless, e.g. 'curve=zorder&size=16'"
+    )]
+    /// Shared-link state applied before rendering.
+    link: Option<String>,
+}
+
 #[derive(Subcommand)]
 /// Subcommands supported by the `scurve` tool.
 enum Commands {
@@ -195,54 +642,46 @@ enum Commands {
         /// Side length of the curve grid (SIDE×SIDE points).
         curve_dimension: Option<u32>,
 
-        #[arg(
-            short = 'w',
-            long = "line-width",
-            value_name = "PIXELS",
-            default_value_t = 1,
-            value_parser = clap::value_parser!(u32).range(1..),
-            help = "Line width in pixels for the curve stroke"
-        )]
-        /// Stroke width for the rendered curve.
-        line_width: u32,
+        #[command(flatten)]
+        /// Stroke width, colors and long-edge handling.
+        stroke: StrokeArgs,
 
         #[arg(
-            long = "fg",
-            visible_alias = "foreground",
-            value_parser = parse_rgba_color,
-            default_value = "#8080ff",
-            value_name = "HEX",
-            help = "Foreground color (name or hex; RGB/RRGGBB with optional alpha, '#' optional)"
+            long = "chunk",
+            value_name = "START:END",
+            help = "Draw only the curve segment from START (inclusive) to END (exclusive)"
         )]
-        /// Stroke color for the curve.
-        foreground: Rgba<u8>,
+        /// Optional start/end offsets (START:END) for the rendered curve segment.
+        chunk: Option<ChunkOffsets>,
 
         #[arg(
-            long = "bg",
-            visible_alias = "background",
-            value_parser = parse_rgba_color,
-            default_value = "#ffffff",
-            value_name = "HEX",
-            help = "Background color (name or hex; RGB/RRGGBB with optional alpha, '#' optional)"
+            long = "dims",
+            value_name = "N",
+            default_value_t = 2,
+            value_parser = clap::value_parser!(u32).range(2..),
+            help = "Number of curve dimensions; curves with more than two are projected to 2D"
         )]
-        /// Background color for the map.
-        background: Rgba<u8>,
+        /// Number of curve dimensions.
+        dims: u32,
 
         #[arg(
-            long = "long",
-            default_value_t = false,
-            help = "Include long edges (segments longer than 1 unit)"
+            long = "axes",
+            value_name = "X,Y",
+            default_value = "0,1",
+            value_parser = parse_axes,
+            help = "Curve axes drawn along the image x and y directions"
         )]
-        /// Render long edges between non-adjacent points.
-        long_edges: bool,
+        /// Curve axes projected onto the image plane.
+        axes: [usize; 2],
 
         #[arg(
-            long = "chunk",
-            value_name = "START:END",
-            help = "Draw only the curve segment from START (inclusive) to END (exclusive)"
+            long = "slice",
+            value_name = "AXIS=VALUE",
+            value_parser = parse_slice,
+            help = "Fix AXIS to VALUE and draw only curve steps inside that slice (repeatable)"
         )]
-        /// Optional start/end offsets (START:END) for the rendered curve segment.
-        chunk: Option<ChunkOffsets>,
+        /// Fixed axis values selecting a slice through the remaining axes.
+        slice: Vec<(usize, u32)>,
 
         #[arg(help = &format!("Pattern name (options: {})", registry::CURVE_NAMES.join(", ")), value_parser = parse_curve_name)]
         /// Pattern name.
@@ -269,64 +708,47 @@ enum Commands {
         /// Side length of the curve grid (SIDE×SIDE points).
         curve_dimension: Option<u32>,
 
-        #[arg(
-            short = 'w',
-            long = "line-width",
-            value_name = "PIXELS",
-            default_value_t = 1,
-            value_parser = clap::value_parser!(u32).range(1..),
-            help = "Line width in pixels for the curve stroke"
-        )]
-        /// Stroke width for the rendered curve.
-        line_width: u32,
-
-        #[arg(
-            long = "fg",
-            visible_alias = "foreground",
-            value_parser = parse_rgba_color,
-            default_value = "#8080ff",
-            value_name = "HEX",
-            help = "Foreground color (name or hex; RGB/RRGGBB with optional alpha, '#' optional)"
-        )]
-        /// Stroke color for the curve.
-        foreground: Rgba<u8>,
-
-        #[arg(
-            long = "bg",
-            visible_alias = "background",
-            value_parser = parse_rgba_color,
-            default_value = "#ffffff",
-            value_name = "HEX",
-            help = "Background color (name or hex; RGB/RRGGBB with optional alpha, '#' optional)"
-        )]
-        /// Background color for the map.
-        background: Rgba<u8>,
+        #[command(flatten)]
+        /// Stroke width, colors and long-edge handling.
+        stroke: StrokeArgs,
 
         #[arg(
             long = "full",
             value_name = "COLOR",
-            value_parser = parse_rgba_color,
+            value_parser = map::parse_rgba_color,
             help = "Draw the full curve in COLOR beneath the animated snake"
         )]
         /// Optional full-curve color to render behind the snake overlay.
         full: Option<Rgba<u8>>,
 
-        #[arg(
-            long = "long",
-            default_value_t = false,
-            help = "Include long edges (segments longer than 1 unit)"
-        )]
-        /// Render long edges between non-adjacent points.
-        long_edges: bool,
-
         #[arg(
             long = "chunk",
             value_name = "START:END",
-            required = true,
+            required_unless_present = "peel",
+            conflicts_with = "peel",
             help = "Chunk to animate (START inclusive, END exclusive)"
         )]
-        /// Mandatory start/end offsets (START:END) for the animated segment.
-        chunk: ChunkOffsets,
+        /// Start/end offsets (START:END) for the animated segment; required unless peeling.
+        chunk: Option<ChunkOffsets>,
+
+        #[arg(
+            long = "dims",
+            value_name = "N",
+            default_value_t = 2,
+            value_parser = clap::value_parser!(u32).range(2..=3),
+            help = "Number of curve dimensions; 3 draws the snake on a rotating 3D view"
+        )]
+        /// Number of curve dimensions.
+        dims: u32,
+
+        #[arg(
+            long = "peel",
+            default_value_t = false,
+            conflicts_with = "dims",
+            help = "Draw the curve one L∞ shell per frame, from the outside in, instead of a chunk"
+        )]
+        /// Animate the curve shell by shell (onion peeling) instead of a moving chunk.
+        peel: bool,
 
         #[arg(
             long = "fps",
@@ -359,6 +781,10 @@ enum Commands {
         /// Pattern name for pixel layout.
         pattern: String,
 
+        #[command(flatten)]
+        /// Settings for the animated reveal.
+        animation: AnimateArgs,
+
         #[arg(help = "Optional output file path; opens a viewer when omitted")]
         /// Optional output file path (launches a viewer when not provided).
         output: Option<PathBuf>,
@@ -375,6 +801,30 @@ enum Commands {
         /// Output image width/height in pixels.
         width: Option<u32>,
 
+        #[arg(
+            long,
+            value_enum,
+            default_value_t,
+            help = "Color bytes by value class, or by the ELF/PE/Mach-O section holding them"
+        )]
+        /// How bytes are colored.
+        mode: cmd::VisMode,
+
+        #[arg(
+            long,
+            help = "Segment the file by entropy, outline the regions and print their offsets"
+        )]
+        /// Outline regions of similar entropy and list them, flagging anomalies.
+        regions: bool,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "TOML or JSON file of named byte ranges to outline and label"
+        )]
+        /// Optional sections file whose byte ranges are drawn over the image.
+        sections: Option<PathBuf>,
+
         #[arg(help = "File to visualise")]
         /// Input file to visualise.
         input: PathBuf,
@@ -384,6 +834,78 @@ enum Commands {
         output: Option<PathBuf>,
     },
 
+    #[command(about = "Visualise every file in a directory into an HTML gallery")]
+    /// Run `vis` over a directory and write a thumbnail gallery.
+    Batch {
+        #[command(flatten)]
+        /// Input directory, output directory and rendering options.
+        args: BatchArgs,
+    },
+
+    #[command(about = "Visualise a WAV file's loudness along a curve")]
+    /// Visualise audio loudness or band energy using a space‑filling curve.
+    Audio {
+        #[command(flatten)]
+        /// Input, output and rendering options.
+        args: AudioArgs,
+    },
+
+    #[command(about = "Plot a numeric CSV column as a heatmap along a curve")]
+    /// Plot numeric CSV data along a space‑filling curve.
+    Csv2map {
+        #[command(flatten)]
+        /// Column, curve and output options.
+        args: Csv2mapArgs,
+    },
+
+    #[command(about = "Rank every curve that fits a grid by locality metrics")]
+    /// Measure all registered curves for one shape and print a ranked table.
+    Compare {
+        #[command(flatten)]
+        /// Shape, output format and image options.
+        args: CompareArgs,
+    },
+
+    #[command(about = "Flatten an image into a one-pixel-tall strip along a curve")]
+    /// Flatten an image's pixels into a strip in curve order.
+    Unravel {
+        #[command(flatten)]
+        /// Pattern, input and output options.
+        args: RavelArgs,
+    },
+
+    #[command(about = "Rebuild an image from a strip written by unravel")]
+    /// Rebuild a 2D image from a strip in curve order.
+    Reravel {
+        #[command(flatten)]
+        /// Pattern, input and output options.
+        args: RavelArgs,
+
+        #[arg(
+            long = "size",
+            value_parser = parse_image_size,
+            help = "Size of the original image as WIDTHxHEIGHT (defaults to square)"
+        )]
+        /// Dimensions of the rebuilt image.
+        size: Option<[u32; 2]>,
+    },
+
+    #[command(about = "Hexdump a file annotated with its vis curve coordinates")]
+    /// Print a hexdump whose rows are annotated with the matching `vis` pixel coordinates.
+    Hexmap {
+        #[arg(short = 'p', help = &format!("Pattern name (options: {})", registry::CURVE_NAMES.join(", ")), value_parser = parse_curve_name)]
+        /// Optional pattern name (defaults to `hilbert`).
+        pattern: Option<String>,
+
+        #[arg(short = 'w', help = "Width of the corresponding vis image")]
+        /// Width/height of the `vis` image the coordinates refer to.
+        width: Option<u32>,
+
+        #[arg(help = "File to dump")]
+        /// Input file to dump.
+        input: PathBuf,
+    },
+
     #[command(about = "Open GUI window")]
     /// Launch the interactive GUI.
     Gui {
@@ -395,7 +917,9 @@ enum Commands {
         dev: bool,
     },
 
-    #[command(about = "Take a screenshot of the GUI (requires --features screenshot)")]
+    #[command(
+        about = "Take a screenshot of the GUI (requires --features screenshot unless --headless)"
+    )]
     /// Capture a screenshot of a specific GUI pane.
     Screenshot {
         #[arg(
@@ -408,28 +932,47 @@ enum Commands {
         /// Which pane to capture.
         pane: ScreenshotPane,
 
+        #[command(flatten)]
+        /// Offscreen rendering options.
+        offscreen: OffscreenArgs,
+
         #[arg(help = "Output PNG file path")]
         /// Output file path for the screenshot.
         output: PathBuf,
     },
 
+    #[command(about = "Manage the render cache")]
+    /// Inspect or empty the render cache.
+    Cache {
+        #[command(subcommand)]
+        /// Cache operation.
+        action: CacheAction,
+    },
+
     #[command(
         name = "list-curves",
         about = "List supported curve names and constraints"
     )]
     /// List supported curves and their constraints.
-    ListCurves,
+    ListCurves {
+        #[command(flatten)]
+        /// Capabilities the listed curves must have.
+        args: ListCurvesArgs,
+    },
+}
+
+/// Exit with an error message when `result` failed.
+fn exit_on_err<E: Display>(result: Result<(), E>) {
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(1);
+    }
 }
 
 /// Print a success message or exit with an error.
 fn report_ok<E: Display>(result: Result<(), E>, ok_msg: &str) {
-    match result {
-        Ok(()) => println!("{ok_msg}"),
-        Err(e) => {
-            eprintln!("{e}");
-            process::exit(1);
-        }
-    }
+    exit_on_err(result);
+    println!("{ok_msg}");
 }
 
 /// Save an image to disk or show it in an egui viewer when no path is given.
@@ -438,51 +981,421 @@ fn deliver_image(image: RgbaImage, output: Option<&Path>, window_title: &str) ->
         image.save(path)?;
     } else {
         println!("No output file provided; opening viewer (close the window to finish)...");
-        egui_img::view_image(window_title, image)?;
+        egui_img::Viewer::new(image).title(window_title).show()?;
     }
 
     Ok(())
 }
 
-/// Handle the `vis` subcommand.
-fn handle_vis(
-    input: &Path,
-    output: Option<&Path>,
-    width: Option<u32>,
-    pattern: Option<&str>,
+/// Extension of `output` as recorded in the cache; images for the viewer are cached as PNGs.
+fn output_extension(output: Option<&Path>) -> String {
+    output.and_then(Path::extension).map_or_else(
+        || "png".to_string(),
+        |ext| ext.to_string_lossy().to_lowercase(),
+    )
+}
+
+/// The cache and finished key for a render written as `extension`, when caching is enabled.
+fn cache_entry<'c>(
+    cache: Option<&'c Cache>,
+    key: impl FnOnce() -> Result<KeyBuilder>,
+    extension: &str,
+) -> Result<Option<(&'c Cache, Key)>> {
+    cache
+        .map(|cache| Ok((cache, key()?.option("format", extension).finish())))
+        .transpose()
+}
+
+/// Warn when a render could not be stored; the render itself has already succeeded.
+fn warn_unstored(stored: Result<()>) {
+    if let Err(err) = stored {
+        eprintln!("Warning: could not store the render in the cache: {err}");
+    }
+}
+
+/// Deliver an image like [`deliver_image`], replaying the render stored under `key` when the
+/// cache has one and storing a fresh render otherwise. `render` returns the image and what
+/// the command prints alongside it.
+fn deliver_cached(
+    cache: Option<&Cache>,
+    key: impl FnOnce() -> Result<KeyBuilder>,
+    (output, window_title): (Option<&Path>, &str),
+    render: impl FnOnce() -> Result<(RgbaImage, Report)>,
 ) -> Result<()> {
+    let extension = output_extension(output);
+    let Some((cache, key)) = cache_entry(cache, key, &extension)? else {
+        let (image, report) = render()?;
+        report.print();
+        return deliver_image(image, output, window_title);
+    };
+    if let Some(hit) = cache.lookup(key, &extension) {
+        hit.report.print();
+        return match output {
+            Some(path) => {
+                fs::copy(&hit.path, path)?;
+                Ok(())
+            }
+            None => deliver_image(image::open(&hit.path)?.to_rgba8(), None, window_title),
+        };
+    }
+    let (image, report) = render()?;
+    report.print();
+    match output {
+        Some(path) => {
+            image.save(path)?;
+            warn_unstored(cache.store(key, &extension, path, &report));
+            Ok(())
+        }
+        None => {
+            warn_unstored(cache.store_image(key, &image, &report));
+            deliver_image(image, None, window_title)
+        }
+    }
+}
+
+/// Write `output` with `render`, or copy the render stored under `key` when the cache has one.
+fn write_cached(
+    cache: Option<&Cache>,
+    key: impl FnOnce() -> Result<KeyBuilder>,
+    output: &Path,
+    render: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let extension = output_extension(Some(output));
+    let Some((cache, key)) = cache_entry(cache, key, &extension)? else {
+        return render();
+    };
+    if let Some(hit) = cache.lookup(key, &extension) {
+        hit.report.print();
+        fs::copy(&hit.path, output)?;
+        return Ok(());
+    }
+    render()?;
+    warn_unstored(cache.store(key, &extension, output, &Report::default()));
+    Ok(())
+}
+
+/// The directory named by `--cache-dir`, or else the per-user cache directory.
+fn cache_dir(cli: &Cli) -> Option<PathBuf> {
+    cli.cache_dir.clone().or_else(Cache::default_dir)
+}
+
+/// Handle the `cache` subcommand.
+fn handle_cache(dir: Option<PathBuf>, action: &CacheAction) -> Result<()> {
+    let Some(dir) = dir else {
+        bail!("no cache directory; pass --cache-dir to choose one");
+    };
+    match action {
+        CacheAction::Clear => {
+            let cleared = Cache::new(&dir).clear()?;
+            println!(
+                "Removed {} files ({} bytes) from {}",
+                cleared.files,
+                cleared.bytes,
+                dir.display()
+            );
+        }
+        CacheAction::Dir => println!("{}", dir.display()),
+    }
+    Ok(())
+}
+
+/// The render cache, when `--cache` enables it and `--no-cache` doesn't override it.
+fn render_cache(cli: &Cli) -> Option<Cache> {
+    if cli.no_cache || !cli.cache {
+        return None;
+    }
+    let dir = cache_dir(cli);
+    if dir.is_none() {
+        eprintln!("Warning: no cache directory; pass --cache-dir to enable the cache");
+    }
+    dir.map(Cache::new)
+}
+
+/// Parameters supplied by the CLI for the `vis` subcommand.
+#[derive(Clone, Copy)]
+struct VisInput<'a> {
+    /// File to visualise.
+    file: &'a Path,
+    /// Optional output path (opens a viewer when `None`).
+    output: Option<&'a Path>,
+    /// Requested image width (defaults to 256 when `None`).
+    width: Option<u32>,
+    /// Curve pattern name (defaults to `hilbert` when `None`).
+    pattern: Option<&'a str>,
+    /// How bytes are colored.
+    mode: cmd::VisMode,
+    /// Whether to outline regions of similar entropy.
+    regions: bool,
+    /// Optional sections file to overlay.
+    sections: Option<&'a Path>,
+    /// Render cache, when enabled.
+    cache: Option<&'a Cache>,
+}
+
+/// Handle the `vis` subcommand.
+fn handle_vis(input: VisInput<'_>) -> Result<()> {
+    let width = input.width.unwrap_or(256);
+    let pattern_name = input.pattern.unwrap_or("hilbert");
+    let overlays = cmd::VisOverlays {
+        regions: input.regions,
+        sections: input
+            .sections
+            .map(sections::load)
+            .transpose()?
+            .unwrap_or_default(),
+    };
+    let key = || {
+        let key = KeyBuilder::new("vis")
+            .option("width", width)
+            .option("pattern", pattern_name)
+            .option("mode", input.mode)
+            .option("regions", input.regions)
+            .file(input.file)?;
+        match input.sections {
+            Some(path) => key.file(path),
+            None => Ok(key),
+        }
+    };
+    let title = format!("vis: {pattern_name}");
+    deliver_cached(input.cache, key, (input.output, &title), || {
+        let render = cmd::vis(input.file, width, pattern_name, input.mode, &overlays)?;
+        let mut report = Report::default();
+        for section in &render.legend {
+            let [red, green, blue, _] = section.color.0;
+            report.out(format!(
+                "{:08x}-{:08x}  #{red:02x}{green:02x}{blue:02x}  {}",
+                section.range.start, section.range.end, section.name
+            ));
+        }
+        for region in &render.regions {
+            report.out(format!(
+                "{:08x}-{:08x}  {:.2} bits/byte{}",
+                region.range.start,
+                region.range.end,
+                region.entropy,
+                if region.anomalous { "  anomaly" } else { "" }
+            ));
+        }
+        for name in &render.skipped_sections {
+            report.err(format!(
+                "Warning: section '{name}' lies past the end of the file"
+            ));
+        }
+        Ok((render.image, report))
+    })
+}
+
+/// Handle the `batch` subcommand.
+fn handle_batch(args: &BatchArgs) -> Result<()> {
+    let pattern = args.pattern.as_deref().unwrap_or("hilbert");
+    let overlays = cmd::VisOverlays {
+        regions: args.regions,
+        sections: args
+            .sections
+            .as_deref()
+            .map(sections::load)
+            .transpose()?
+            .unwrap_or_default(),
+    };
+    let jobs = match args.jobs {
+        Some(jobs) => usize::try_from(jobs)?,
+        None => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+    };
+    let options = batch::BatchOptions {
+        width: args.width.unwrap_or(256),
+        pattern,
+        mode: args.mode,
+        overlays: &overlays,
+        thumbnail: args.thumb,
+        jobs,
+    };
+    let entries = batch::batch(&args.input, &args.out, &options)?;
+    let index = args.out.join(batch::INDEX);
+    let title = format!("{} ({pattern})", args.input.display());
+    batch::write_gallery(
+        &entries,
+        &title,
+        args.thumb.min(options.width),
+        &mut BufWriter::new(File::create(&index)?),
+    )?;
+
+    let mut rendered = 0;
+    for entry in &entries {
+        match &entry.outcome {
+            Ok(_) => rendered += 1,
+            Err(reason) => eprintln!("Warning: {}: {reason}", entry.name),
+        }
+    }
+    if rendered == 0 {
+        bail!("no file in {} could be rendered", args.input.display());
+    }
+    println!(
+        "Rendered {rendered} of {} files; gallery written to {}",
+        entries.len(),
+        index.display()
+    );
+    Ok(())
+}
+
+/// Handle the `audio` subcommand.
+fn handle_audio(args: &AudioArgs) -> Result<()> {
+    let width = args.width.unwrap_or(256);
+    let pattern_name = args.pattern.as_deref().unwrap_or("hilbert");
+    let image = cmd::audio(&args.input, width, pattern_name, args.mode, args.colormap)?;
+    deliver_image(
+        image,
+        args.output.as_deref(),
+        &format!("audio: {pattern_name}"),
+    )
+}
+
+/// Handle the `csv2map` subcommand.
+fn handle_csv2map(args: &Csv2mapArgs) -> Result<()> {
+    let pattern_name = args.pattern.as_deref().unwrap_or("hilbert");
+    let image = cmd::csv2map(
+        &args.input,
+        usize::try_from(args.value_col)?,
+        args.index_col.map(usize::try_from).transpose()?,
+        pattern_name,
+        args.colormap,
+    )?;
+    deliver_image(
+        image,
+        args.output.as_deref(),
+        &format!("csv2map: {pattern_name}"),
+    )
+}
+
+/// Handle the `compare` subcommand.
+fn handle_compare(args: &CompareArgs) -> Result<()> {
+    let comparison = compare::compare(
+        args.dims,
+        args.size,
+        CurveFilter {
+            include_experimental: args.experimental,
+            continuous: args.continuous,
+            ..CurveFilter::default()
+        },
+    );
+    for (key, reason) in &comparison.skipped {
+        eprintln!("skipping {key}: {reason}");
+    }
+    if comparison.entries.is_empty() {
+        bail!(
+            "no curve supports {} dimensions of size {}",
+            args.dims,
+            args.size
+        );
+    }
+    compare::write(&comparison, args.format, &mut io::stdout().lock())?;
+    if let Some(path) = &args.image {
+        compare::render(&comparison, args.tile)?.save(path)?;
+    }
+    Ok(())
+}
+
+/// Handle the `unravel` subcommand.
+fn handle_unravel(args: &RavelArgs) -> Result<()> {
+    let pattern_name = args.pattern.as_deref().unwrap_or("hilbert");
+    let strip = cmd::unravel(&args.input, pattern_name)?;
+    deliver_image(
+        strip,
+        args.output.as_deref(),
+        &format!("unravel: {pattern_name}"),
+    )
+}
+
+/// Handle the `reravel` subcommand.
+fn handle_reravel(args: &RavelArgs, size: Option<[u32; 2]>) -> Result<()> {
+    let pattern_name = args.pattern.as_deref().unwrap_or("hilbert");
+    let image = cmd::reravel(&args.input, pattern_name, size)?;
+    deliver_image(
+        image,
+        args.output.as_deref(),
+        &format!("reravel: {pattern_name}"),
+    )
+}
+
+/// Handle the `hexmap` subcommand.
+fn handle_hexmap(input: &Path, width: Option<u32>, pattern: Option<&str>) -> Result<()> {
     let width = width.unwrap_or(256);
     let pattern_name = pattern.unwrap_or("hilbert");
-    let image = cmd::vis(input, width, pattern_name)?;
-    deliver_image(image, output, &format!("vis: {pattern_name}"))
+    let mut out = io::stdout().lock();
+    cmd::hexmap(input, width, pattern_name, &mut out)
 }
 
-/// Handle the `map` subcommand.
-fn handle_map(
+/// Parameters supplied by the CLI for the `map` subcommand.
+struct MapInput<'a> {
+    /// Requested output size in pixels (defaults to 512 when `None`).
     size: Option<u32>,
+    /// Requested curve dimension (defaults to 16 when `None`).
     curve_dimension: Option<u32>,
-    pattern: &str,
-    output: Option<&Path>,
+    /// Number of curve axes.
+    dimensions: u32,
+    /// Curve pattern name.
+    pattern: &'a str,
+    /// Optional output path (opens a viewer when `None`).
+    output: Option<&'a Path>,
+    /// Optional offset range limiting the rendered segment.
     chunk: Option<ChunkOffsets>,
+    /// Stroke styling options.
     stroke: map::StrokeOptions,
-) -> Result<()> {
-    let size = size.unwrap_or(512);
-    // Default keeps behaviour similar to the previous 16×16 grid (256 points).
-    let requested_dimension = curve_dimension.unwrap_or(16);
-    let render = cmd::map(
+    /// Mapping from curve axes onto the image plane.
+    projection: Projection,
+    /// Render cache, when enabled.
+    cache: Option<&'a Cache>,
+}
+
+/// Handle the `map` subcommand.
+fn handle_map(input: MapInput<'_>) -> Result<()> {
+    let MapInput {
         size,
-        requested_dimension,
+        curve_dimension,
+        dimensions,
         pattern,
-        chunk.map(ChunkOffsets::into_range),
+        output,
+        chunk,
         stroke,
-    )?;
-    if render.adjusted {
-        eprintln!(
-            "Requested curve dimension {} is not valid for pattern '{}'; using {} instead.",
-            requested_dimension, pattern, render.side
-        );
-    }
-    deliver_image(render.image, output, &format!("map: {pattern}"))
+        projection,
+        cache,
+    } = input;
+
+    let size = size.unwrap_or(512);
+    // Default keeps behaviour similar to the previous 16×16 grid (256 points).
+    let requested_dimension = curve_dimension.unwrap_or(16);
+    let key = || {
+        Ok(KeyBuilder::new("map")
+            .option("size", size)
+            .option("curve_dimension", requested_dimension)
+            .option("dimensions", dimensions)
+            .option("pattern", pattern)
+            .option("chunk", chunk)
+            .option("line_width", stroke.line_width)
+            .option("long_edges", stroke.long_edges)
+            .option("foreground", stroke.palette.foreground.0)
+            .option("background", stroke.palette.background.0)
+            .option("projection", &projection))
+    };
+    let title = format!("map: {pattern}");
+    deliver_cached(cache, key, (output, &title), || {
+        let render = cmd::map(cmd::MapOptions {
+            size,
+            curve_dimension: requested_dimension,
+            dimensions,
+            pattern_name: pattern,
+            chunk: chunk.map(ChunkOffsets::into_range),
+            stroke,
+            projection: projection.clone(),
+        })?;
+        let mut report = Report::default();
+        if render.adjusted {
+            report.err(format!(
+                "Requested curve dimension {} is not valid for pattern '{}'; using {} instead.",
+                requested_dimension, pattern, render.side
+            ));
+        }
+        Ok((render.image, report))
+    })
 }
 
 /// Parameters supplied by the CLI for the `snake` subcommand.
@@ -492,10 +1405,14 @@ struct SnakeInput<'a> {
     size: Option<u32>,
     /// Requested curve dimension (defaults to 16 when `None`).
     curve_dimension: Option<u32>,
+    /// Number of curve axes.
+    dimensions: u32,
     /// Curve pattern name.
     pattern: &'a str,
-    /// Offset range for the animated segment.
-    chunk: ChunkOffsets,
+    /// Offset range for the animated segment (unused when peeling).
+    chunk: Option<ChunkOffsets>,
+    /// Whether to animate shell by shell instead of a moving chunk.
+    peel: bool,
     /// Destination GIF path.
     output: &'a Path,
     /// Frames per second.
@@ -511,8 +1428,10 @@ fn handle_snake(input: SnakeInput<'_>) -> Result<()> {
     let SnakeInput {
         size,
         curve_dimension,
+        dimensions,
         pattern,
         chunk,
+        peel,
         output,
         fps,
         stroke,
@@ -521,16 +1440,32 @@ fn handle_snake(input: SnakeInput<'_>) -> Result<()> {
 
     let size = size.unwrap_or(512);
     let requested_dimension = curve_dimension.unwrap_or(16);
-    let render = cmd::snake(cmd::SnakeOptions {
-        size,
-        curve_dimension: requested_dimension,
-        pattern_name: pattern,
-        chunk: chunk.into_range(),
-        fps,
-        stroke,
-        output,
-        full_curve,
-    })?;
+    let render = if peel {
+        cmd::peel(cmd::PeelOptions {
+            size,
+            curve_dimension: requested_dimension,
+            pattern_name: pattern,
+            fps,
+            stroke,
+            output,
+            full_curve,
+        })?
+    } else {
+        let Some(chunk) = chunk else {
+            bail!("--chunk is required unless --peel is given");
+        };
+        cmd::snake(cmd::SnakeOptions {
+            size,
+            curve_dimension: requested_dimension,
+            dimensions,
+            pattern_name: pattern,
+            chunk: chunk.into_range(),
+            fps,
+            stroke,
+            output,
+            full_curve,
+        })?
+    };
 
     if render.adjusted {
         eprintln!(
@@ -542,10 +1477,41 @@ fn handle_snake(input: SnakeInput<'_>) -> Result<()> {
 }
 
 /// Handle the `allrgb` subcommand.
-fn handle_allrgb(pattern: &str, colormap: Option<&str>, output: Option<&Path>) -> Result<()> {
+fn handle_allrgb(
+    pattern: &str,
+    colormap: Option<&str>,
+    output: Option<&Path>,
+    animation: AnimateArgs,
+    cache: Option<&Cache>,
+) -> Result<()> {
     let colormap = colormap.unwrap_or(pattern);
-    let image = cmd::allrgb(pattern, colormap)?;
-    deliver_image(image, output, &format!("allrgb: {pattern}/{colormap}"))
+    let key = || {
+        Ok(KeyBuilder::new("allrgb")
+            .option("pattern", pattern)
+            .option("colormap", colormap)
+            .option("animation", animation))
+    };
+    if animation.animate {
+        let Some(output) = output else {
+            bail!("--animate needs an output path");
+        };
+        return write_cached(cache, key, output, || {
+            cmd::allrgb_animation(
+                pattern,
+                colormap,
+                cmd::AllrgbAnimation {
+                    size: animation.size,
+                    frames: animation.frames,
+                    fps: animation.fps,
+                    output,
+                },
+            )
+        });
+    }
+    let title = format!("allrgb: {pattern}/{colormap}");
+    deliver_cached(cache, key, (output, &title), || {
+        Ok((cmd::allrgb(pattern, colormap)?, Report::default()))
+    })
 }
 
 /// Handle the `gui` subcommand.
@@ -560,21 +1526,42 @@ fn handle_gui(dev: bool) {
     );
 }
 
-#[cfg(feature = "screenshot")]
-/// Handle the `screenshot` subcommand when the feature is enabled.
-fn handle_screenshot(pane: ScreenshotPane, output: PathBuf) {
-    use scurve_gui::{ScreenshotConfig, ScreenshotTarget};
+/// Map the CLI pane choice onto the GUI's screenshot target.
+fn screenshot_target(pane: ScreenshotPane) -> scurve_gui::ScreenshotTarget {
+    use scurve_gui::ScreenshotTarget;
 
-    let target = match pane {
+    match pane {
         ScreenshotPane::TwoD => ScreenshotTarget::TwoD,
         ScreenshotPane::ThreeD => ScreenshotTarget::ThreeD,
         ScreenshotPane::About => ScreenshotTarget::About,
         ScreenshotPane::Settings => ScreenshotTarget::Settings,
         ScreenshotPane::Settings3D => ScreenshotTarget::Settings3D,
+    }
+}
+
+/// Handle the `screenshot` subcommand, rendering offscreen when `--headless` is set.
+fn handle_screenshot(pane: ScreenshotPane, offscreen: OffscreenArgs, output: PathBuf) {
+    if !offscreen.headless {
+        handle_window_screenshot(pane, output);
+        return;
+    }
+    let config = HeadlessConfig {
+        target: screenshot_target(pane),
+        size: offscreen.size,
+        pixels_per_point: offscreen.scale,
+        link: offscreen.link,
     };
+    report_ok(
+        headless::render_to_png(&config, &output),
+        "Screenshot saved!",
+    );
+}
 
-    let config = ScreenshotConfig {
-        target,
+#[cfg(feature = "screenshot")]
+/// Capture a screenshot through a real window when the feature is enabled.
+fn handle_window_screenshot(pane: ScreenshotPane, output: PathBuf) {
+    let config = scurve_gui::ScreenshotConfig {
+        target: screenshot_target(pane),
         output_path: output,
     };
 
@@ -585,16 +1572,23 @@ fn handle_screenshot(pane: ScreenshotPane, output: PathBuf) {
 }
 
 #[cfg(not(feature = "screenshot"))]
-/// Handle the `screenshot` subcommand when the feature is disabled.
-fn handle_screenshot(_pane: ScreenshotPane, _output: PathBuf) {
-    eprintln!("Screenshot feature not enabled. Rebuild with: cargo build --features screenshot",);
+/// Report that window screenshots need the `screenshot` feature.
+fn handle_window_screenshot(_pane: ScreenshotPane, _output: PathBuf) {
+    eprintln!(
+        "Screenshot feature not enabled. Rebuild with: cargo build --features screenshot, or pass --headless",
+    );
     process::exit(1);
 }
 
 /// Handle the `list-curves` subcommand.
-fn handle_list_curves() {
+fn handle_list_curves(args: &ListCurvesArgs) {
     println!("Supported curves (key — display — constraints):");
-    for entry in registry::REGISTRY {
+    for entry in registry::filter(CurveFilter {
+        dimension: args.dims,
+        size: args.size,
+        include_experimental: true,
+        continuous: args.continuous,
+    }) {
         println!(
             "- {} — {} — {}",
             entry.key, entry.display, entry.constraints
@@ -602,96 +1596,133 @@ fn handle_list_curves() {
     }
 }
 
-fn main() {
-    let cli = Cli::parse();
-
-    match cli.command {
+/// Run a command whose output goes through the render cache.
+fn run_cached(command: Commands, cache: Option<&Cache>) {
+    match command {
         Commands::Vis {
             input,
             output,
             width,
+            mode,
+            regions,
+            sections,
             pattern,
         } => report_ok(
-            handle_vis(&input, output.as_deref(), width, pattern.as_deref()),
+            handle_vis(VisInput {
+                file: &input,
+                output: output.as_deref(),
+                width,
+                pattern: pattern.as_deref(),
+                mode,
+                regions,
+                sections: sections.as_deref(),
+                cache,
+            }),
             "OK!",
         ),
         Commands::Map {
             pattern,
             size,
             curve_dimension,
-            line_width,
+            stroke,
             output,
-            foreground,
-            background,
             chunk,
-            long_edges,
+            dims,
+            axes,
+            slice,
         } => report_ok(
-            handle_map(
+            handle_map(MapInput {
                 size,
                 curve_dimension,
-                &pattern,
-                output.as_deref(),
+                dimensions: dims,
+                pattern: &pattern,
+                output: output.as_deref(),
                 chunk,
-                map::StrokeOptions {
-                    line_width,
-                    long_edges,
-                    palette: MapPalette {
-                        foreground,
-                        background,
-                    },
-                },
-            ),
+                stroke: stroke.options(),
+                projection: Projection { axes, slice },
+                cache,
+            }),
             "OK!",
         ),
         Commands::Allrgb {
             pattern,
             colormap,
+            animation,
             output,
         } => report_ok(
-            handle_allrgb(&pattern, colormap.as_deref(), output.as_deref()),
+            handle_allrgb(
+                &pattern,
+                colormap.as_deref(),
+                output.as_deref(),
+                animation,
+                cache,
+            ),
             "OK!",
         ),
+        _ => unreachable!("only vis, map and allrgb renders are cached"),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let cache = render_cache(&cli);
+    let cache_dir = cache_dir(&cli);
+
+    match cli.command {
+        command @ (Commands::Vis { .. } | Commands::Map { .. } | Commands::Allrgb { .. }) => {
+            run_cached(command, cache.as_ref());
+        }
+        Commands::Batch { args } => exit_on_err(handle_batch(&args)),
+        Commands::Audio { args } => report_ok(handle_audio(&args), "OK!"),
+        Commands::Csv2map { args } => report_ok(handle_csv2map(&args), "OK!"),
+        Commands::Compare { args } => exit_on_err(handle_compare(&args)),
+        Commands::Unravel { args } => report_ok(handle_unravel(&args), "OK!"),
+        Commands::Reravel { args, size } => report_ok(handle_reravel(&args, size), "OK!"),
+        Commands::Hexmap {
+            input,
+            width,
+            pattern,
+        } => exit_on_err(handle_hexmap(&input, width, pattern.as_deref())),
         Commands::Snake {
             pattern,
             size,
             curve_dimension,
-            line_width,
+            stroke,
             output,
-            foreground,
-            background,
             chunk,
+            dims,
+            peel,
             fps,
-            long_edges,
             full,
         } => report_ok(
             handle_snake(SnakeInput {
                 size,
                 curve_dimension,
+                dimensions: dims,
                 pattern: &pattern,
                 chunk,
+                peel,
                 output: &output,
                 fps,
-                stroke: map::StrokeOptions {
-                    line_width,
-                    long_edges,
-                    palette: MapPalette {
-                        foreground,
-                        background,
-                    },
-                },
+                stroke: stroke.options(),
                 full_curve: full,
             }),
             "Saved snake GIF!",
         ),
         Commands::Gui { dev } => handle_gui(dev),
-        Commands::Screenshot { pane, output } => handle_screenshot(pane, output),
-        Commands::ListCurves => handle_list_curves(),
+        Commands::Screenshot {
+            pane,
+            offscreen,
+            output,
+        } => handle_screenshot(pane, offscreen, output),
+        Commands::ListCurves { args } => handle_list_curves(&args),
+        Commands::Cache { action } => exit_on_err(handle_cache(cache_dir, &action)),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ChunkOffsets;
+    use super::{ChunkOffsets, parse_axes, parse_slice};
 
     #[test]
     fn parses_chunk_offsets() {
@@ -699,6 +1730,14 @@ mod tests {
         assert_eq!(chunk.into_range(), 1..5);
     }
 
+    #[test]
+    fn parses_projection_arguments() {
+        assert_eq!(parse_axes("0,2").unwrap(), [0, 2]);
+        assert_eq!(parse_slice("3=7").unwrap(), (3, 7));
+        assert!(parse_axes("1").is_err());
+        assert!(parse_slice("a=1").is_err());
+    }
+
     #[test]
     fn rejects_invalid_chunks() {
         assert!("5:1".parse::<ChunkOffsets>().is_err());
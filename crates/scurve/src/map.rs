@@ -3,10 +3,76 @@
 //! This module includes small drawing primitives and the function that renders
 //! a sampled map for a given space‑filling curve.
 
-use std::ops::Range;
+use std::{array, f64::consts::PI, ops::Range};
 
+use colornames::Color;
 use image::{Rgba, RgbaImage};
-use spacecurve::SpaceCurve;
+use serde::Serialize;
+use spacecurve::{SpaceCurve, point::Point};
+
+/// Parse a named or hex color into an `Rgba` value (alpha defaults to 0xff).
+///
+/// Supports CSS color names via `colornames`, short/long hex (RGB/RRGGBB),
+/// and optional alpha (RGBA/RRGGBBAA) with or without a leading `#`.
+pub fn parse_rgba_color(input: &str) -> Result<Rgba<u8>, String> {
+    fn parse_hex_rgba(hex: &str) -> Option<Rgba<u8>> {
+        use std::ops::Range;
+
+        let raw = hex.trim_start_matches('#');
+        if !raw.as_bytes().iter().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let from_pair =
+            |range: Range<usize>| -> Option<u8> { u8::from_str_radix(&raw[range], 16).ok() };
+        let from_nibble = |idx: usize| -> Option<u8> {
+            u8::from_str_radix(&raw[idx..idx + 1], 16)
+                .ok()
+                .map(|v| v * 17)
+        };
+
+        match raw.len() {
+            3 => Some(Rgba([
+                from_nibble(0)?,
+                from_nibble(1)?,
+                from_nibble(2)?,
+                0xff,
+            ])),
+            4 => Some(Rgba([
+                from_nibble(0)?,
+                from_nibble(1)?,
+                from_nibble(2)?,
+                from_nibble(3)?,
+            ])),
+            6 => Some(Rgba([
+                from_pair(0..2)?,
+                from_pair(2..4)?,
+                from_pair(4..6)?,
+                0xff,
+            ])),
+            8 => Some(Rgba([
+                from_pair(0..2)?,
+                from_pair(2..4)?,
+                from_pair(4..6)?,
+                from_pair(6..8)?,
+            ])),
+            _ => None,
+        }
+    }
+
+    let trimmed = input.trim();
+    if let Some(rgba) = parse_hex_rgba(trimmed) {
+        return Ok(rgba);
+    }
+
+    let color: Color = trimmed.try_into().map_err(|_| {
+        format!(
+            "invalid color '{input}': use a named color or hex (RGB/RRGGBB with optional alpha, leading '#' optional)"
+        )
+    })?;
+    let (red, green, blue) = color.rgb();
+    Ok(Rgba([red, green, blue, 0xff]))
+}
 
 /// Colors used when rendering a map image.
 #[derive(Clone, Copy, Debug)]
@@ -28,6 +94,40 @@ pub struct StrokeOptions {
     pub palette: MapPalette,
 }
 
+/// Mapping from the points of an N-dimensional curve onto the 2D image plane.
+///
+/// Two curve axes are drawn along the image x and y directions. Every other axis is either
+/// flattened (its coordinate is ignored) or fixed by a slice entry, in which case only curve
+/// steps whose endpoints both lie inside the slice are drawn.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Projection {
+    /// Curve axes drawn along the image x and y directions.
+    pub axes: [usize; 2],
+    /// Fixed `(axis, value)` pairs selecting a slice through the remaining axes.
+    pub slice: Vec<(usize, u32)>,
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self {
+            axes: [0, 1],
+            slice: Vec::new(),
+        }
+    }
+}
+
+impl Projection {
+    /// Whether `p` lies inside the slice selected by this projection.
+    fn contains(&self, p: &Point) -> bool {
+        self.slice.iter().all(|&(axis, value)| p[axis] == value)
+    }
+
+    /// Project `p` onto the image plane.
+    fn project(&self, p: &Point) -> [u32; 2] {
+        [p[self.axes[0]], p[self.axes[1]]]
+    }
+}
+
 /// Convert a map coordinate to image space.
 fn scale(v: u32, margin: u32, side: u32, innerw: f64) -> f64 {
     if side <= 1 {
@@ -118,6 +218,62 @@ pub fn render_map_image(
     )
 }
 
+/// Pixel geometry shared by every segment drawn into one image.
+#[derive(Clone, Copy, Debug)]
+struct Canvas {
+    /// Margin in pixels around the drawable area.
+    margin: u32,
+    /// Logical grid side length.
+    side: u32,
+    /// Width of the drawable area in pixels.
+    innerw: f64,
+}
+
+impl Canvas {
+    /// Compute the canvas geometry for a `size×size` image of a `side×side` grid.
+    fn new(size: u32, side: u32, stroke: StrokeOptions) -> Self {
+        let margin = 10_u32.saturating_add(stroke.line_width.max(1) / 2);
+        let innerw = f64::from(size.saturating_sub(margin.saturating_mul(2))).max(1.0);
+        Self {
+            margin,
+            side,
+            innerw,
+        }
+    }
+
+    /// Convert a grid coordinate pair to pixel coordinates.
+    fn to_pixel(self, p: [u32; 2]) -> (i64, i64) {
+        (
+            scale(p[0], self.margin, self.side, self.innerw).round() as i64,
+            scale(p[1], self.margin, self.side, self.innerw).round() as i64,
+        )
+    }
+}
+
+/// Draw one curve step between two grid coordinates, honoring long-edge handling.
+fn draw_segment(
+    img: &mut RgbaImage,
+    canvas: Canvas,
+    from: [u32; 2],
+    to: [u32; 2],
+    stroke: StrokeOptions,
+) {
+    if !stroke.long_edges && from[0].abs_diff(to[0]) + from[1].abs_diff(to[1]) > 1 {
+        return;
+    }
+    let (x0, y0) = canvas.to_pixel(from);
+    let (x1, y1) = canvas.to_pixel(to);
+    draw_line(
+        img,
+        x0,
+        y0,
+        x1,
+        y1,
+        stroke.palette.foreground,
+        stroke.line_width.max(1),
+    );
+}
+
 /// Draw a contiguous curve segment starting at `start` with `len` points into `img`.
 ///
 /// The segment wraps around the curve when `start + len` exceeds the curve length. Styling and
@@ -132,9 +288,7 @@ fn draw_chunk(
     stroke: StrokeOptions,
     pattern: &dyn SpaceCurve,
 ) {
-    let stroke_width = stroke.line_width.max(1);
-    let margin = 10_u32.saturating_add(stroke_width / 2);
-    let innerw = f64::from(size.saturating_sub(margin.saturating_mul(2))).max(1.0);
+    let canvas = Canvas::new(size, side, stroke);
 
     let total_points = pattern.length();
     let len = len.min(total_points);
@@ -145,27 +299,32 @@ fn draw_chunk(
         return;
     }
 
-    let mut prev = pattern.point(start % total_points);
-    for step in 1..len {
-        let idx = (start + step) % total_points;
-        let next = pattern.point(idx);
-        if !stroke.long_edges {
-            let dx = (prev[0] as i64 - next[0] as i64).abs();
-            let dy = (prev[1] as i64 - next[1] as i64).abs();
-            if dx + dy > 1 {
-                prev = next;
-                continue;
-            }
-        }
-        let x0 = scale(prev[0], margin, side, innerw).round() as i64;
-        let y0 = scale(prev[1], margin, side, innerw).round() as i64;
-        let x1 = scale(next[0], margin, side, innerw).round() as i64;
-        let y1 = scale(next[1], margin, side, innerw).round() as i64;
-        draw_line(img, x0, y0, x1, y1, stroke.palette.foreground, stroke_width);
+    let mut points = wrapping_range(pattern, start, len);
+    let Some(mut prev) = points.next() else {
+        return;
+    };
+    for next in points {
+        draw_segment(img, canvas, [prev[0], prev[1]], [next[0], next[1]], stroke);
         prev = next;
     }
 }
 
+/// Points of the `len` curve indices from `start`, wrapping past the end of the curve.
+///
+/// `len` must not exceed the curve length.
+fn wrapping_range(
+    pattern: &dyn SpaceCurve,
+    start: u32,
+    len: u32,
+) -> impl Iterator<Item = Point> + '_ {
+    let start = start % pattern.length();
+    pattern
+        .iter()
+        .skip(start as usize)
+        .chain(pattern.iter())
+        .take(len as usize)
+}
+
 /// Render a square image showing a contiguous curve segment starting at `start` with `len` points.
 ///
 /// The segment wraps around the curve when `start + len` exceeds the curve length. Styling and
@@ -185,6 +344,187 @@ pub fn render_chunk_image(
     imgbuf
 }
 
+/// Render a square image of an N-dimensional `pattern` flattened through `projection`.
+///
+/// `side` is the logical side length of every curve axis and `chunk` limits the drawn curve
+/// indices using half-open offsets `[start, end)`.
+pub fn render_projected_image(
+    size: u32,
+    side: u32,
+    chunk: Range<u32>,
+    stroke: StrokeOptions,
+    projection: &Projection,
+    pattern: &dyn SpaceCurve,
+) -> RgbaImage {
+    let mut imgbuf: RgbaImage =
+        image::ImageBuffer::from_pixel(size, size, stroke.palette.background);
+    let canvas = Canvas::new(size, side, stroke);
+
+    let mut prev: Option<Point> = None;
+    for next in pattern.iter_range(chunk) {
+        let inside = projection.contains(&next);
+        if inside && let Some(prev) = &prev {
+            draw_segment(
+                &mut imgbuf,
+                canvas,
+                projection.project(prev),
+                projection.project(&next),
+                stroke,
+            );
+        }
+        prev = inside.then_some(next);
+    }
+    imgbuf
+}
+
+/// Downward tilt of the 3D view, matching the GUI's default camera.
+const ORBIT_TILT: f64 = PI / 6.0;
+/// Fraction of the way towards the background color that the farthest segments are faded.
+const ORBIT_FAR_FADE: f64 = 0.6;
+
+/// Orthographic view of a `side³` curve rendered into a `size×size` image.
+///
+/// The cube is turned `yaw` radians about curve axis 2, which points up the image, and then
+/// tilted towards the viewer.
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitView {
+    /// Output image size in pixels.
+    pub size: u32,
+    /// Logical side length of every curve axis.
+    pub side: u32,
+    /// Rotation about the vertical axis, in radians.
+    pub yaw: f64,
+}
+
+impl OrbitView {
+    /// Radius of the sphere enclosing the centred cube, in grid units.
+    fn radius(self) -> f64 {
+        (f64::from(self.side.saturating_sub(1)) / 2.0 * 3_f64.sqrt()).max(0.5)
+    }
+
+    /// Rotate grid point `p` about the cube centre, giving `[across, up, depth]` where larger
+    /// depths lie farther from the viewer.
+    fn rotate(self, p: &Point) -> [f64; 3] {
+        let half = f64::from(self.side.saturating_sub(1)) / 2.0;
+        let [x, y, z] = [0, 1, 2].map(|axis| f64::from(p[axis]) - half);
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_tilt, cos_tilt) = ORBIT_TILT.sin_cos();
+        let across = x * cos_yaw - y * sin_yaw;
+        let depth = x * sin_yaw + y * cos_yaw;
+        [
+            across,
+            z * cos_tilt - depth * sin_tilt,
+            z * sin_tilt + depth * cos_tilt,
+        ]
+    }
+
+    /// Convert rotated `[across, up]` coordinates to pixel coordinates.
+    fn to_pixel(self, rotated: [f64; 3], stroke: StrokeOptions) -> (i64, i64) {
+        let canvas = Canvas::new(self.size, self.side, stroke);
+        let center = f64::from(self.size) / 2.0;
+        let scale = canvas.innerw / 2.0 / self.radius();
+        (
+            (center + rotated[0] * scale).round() as i64,
+            (center - rotated[1] * scale).round() as i64,
+        )
+    }
+}
+
+/// Blend `from` towards `to` by `amount` in `[0, 1]`.
+fn mix(from: Rgba<u8>, to: Rgba<u8>, amount: f64) -> Rgba<u8> {
+    Rgba(array::from_fn(|i| {
+        let (a, b) = (f64::from(from.0[i]), f64::from(to.0[i]));
+        (a + (b - a) * amount).round() as u8
+    }))
+}
+
+/// Draw `len` points of a 3D `pattern` starting at `start` as seen through `view`.
+///
+/// The segment wraps around the curve like [`render_chunk_image`]. Steps are painted from the
+/// back to the front and faded towards the background with depth so the shape reads as solid.
+fn draw_orbit_chunk(
+    img: &mut RgbaImage,
+    view: OrbitView,
+    start: u32,
+    len: u32,
+    stroke: StrokeOptions,
+    pattern: &dyn SpaceCurve,
+) {
+    let total_points = pattern.length();
+    let len = len.min(total_points);
+    if len < 2 {
+        return;
+    }
+
+    let mut steps = Vec::with_capacity(len as usize - 1);
+    let mut points = wrapping_range(pattern, start, len);
+    let Some(mut prev) = points.next() else {
+        return;
+    };
+    for next in points {
+        let distance: u32 = (0..3).map(|axis| prev[axis].abs_diff(next[axis])).sum();
+        if stroke.long_edges || distance <= 1 {
+            steps.push((view.rotate(&prev), view.rotate(&next)));
+        }
+        prev = next;
+    }
+    steps.sort_by(|a, b| (b.0[2] + b.1[2]).total_cmp(&(a.0[2] + a.1[2])));
+
+    let radius = view.radius();
+    for (from, to) in steps {
+        let depth = ((from[2] + to[2]) / 2.0 / radius).clamp(-1.0, 1.0);
+        let fade = (depth + 1.0) / 2.0 * ORBIT_FAR_FADE;
+        let color = mix(stroke.palette.foreground, stroke.palette.background, fade);
+        let (x0, y0) = view.to_pixel(from, stroke);
+        let (x1, y1) = view.to_pixel(to, stroke);
+        draw_line(img, x0, y0, x1, y1, color, stroke.line_width.max(1));
+    }
+}
+
+/// Render one frame of a 3D snake: a curve segment of `len` points starting at `start`, seen
+/// through `view`, optionally above the whole curve drawn in `full_curve`.
+pub fn render_orbit_image(
+    view: OrbitView,
+    start: u32,
+    len: u32,
+    stroke: StrokeOptions,
+    full_curve: Option<Rgba<u8>>,
+    pattern: &dyn SpaceCurve,
+) -> RgbaImage {
+    let mut imgbuf: RgbaImage =
+        image::ImageBuffer::from_pixel(view.size, view.size, stroke.palette.background);
+    if let Some(foreground) = full_curve {
+        let full = StrokeOptions {
+            palette: MapPalette {
+                foreground,
+                ..stroke.palette
+            },
+            ..stroke
+        };
+        draw_orbit_chunk(&mut imgbuf, view, 0, pattern.length(), full, pattern);
+    }
+    draw_orbit_chunk(&mut imgbuf, view, start, len, stroke, pattern);
+    imgbuf
+}
+
+/// Draw the curve steps from each index in `steps` to the index after it onto an existing
+/// image, leaving the rest of the image untouched.
+pub fn draw_steps(
+    img: &mut RgbaImage,
+    size: u32,
+    side: u32,
+    steps: &[u32],
+    stroke: StrokeOptions,
+    pattern: &dyn SpaceCurve,
+) {
+    let canvas = Canvas::new(size, side, stroke);
+    for &idx in steps {
+        let from = pattern.point(idx);
+        let to = pattern.point(idx + 1);
+        draw_segment(img, canvas, [from[0], from[1]], [to[0], to[1]], stroke);
+    }
+}
+
 /// Draw a curve segment onto an existing image without clearing it first.
 pub fn draw_chunk_overlay(
     img: &mut RgbaImage,
@@ -218,6 +558,12 @@ mod tests {
                 .collect();
             Self { points }
         }
+
+        fn from_points(points: Vec<Vec<u32>>) -> Self {
+            Self {
+                points: points.into_iter().map(Point::new).collect(),
+            }
+        }
     }
 
     impl SpaceCurve for StubPattern {
@@ -245,7 +591,7 @@ mod tests {
         }
 
         fn dimensions(&self) -> u32 {
-            2
+            self.points[0].dimension()
         }
     }
 
@@ -290,6 +636,75 @@ mod tests {
         assert_eq!(wrapped.get_pixel(22, 10), &stroke.palette.foreground);
     }
 
+    #[test]
+    fn draw_steps_adds_only_selected_steps() {
+        let pattern = StubPattern::new(vec![[0, 0], [1, 0], [1, 1]]);
+
+        let stroke = StrokeOptions {
+            line_width: 1,
+            long_edges: true,
+            palette: MapPalette {
+                foreground: Rgba([4, 5, 6, 255]),
+                background: Rgba([0, 0, 0, 0]),
+            },
+        };
+
+        let mut image = RgbaImage::from_pixel(32, 32, stroke.palette.background);
+        draw_steps(&mut image, 32, 2, &[1], stroke, &pattern);
+        assert_eq!(image.get_pixel(16, 10), &stroke.palette.background);
+        assert_eq!(image.get_pixel(22, 16), &stroke.palette.foreground);
+
+        draw_steps(&mut image, 32, 2, &[0], stroke, &pattern);
+        assert_eq!(image.get_pixel(16, 10), &stroke.palette.foreground);
+    }
+
+    #[test]
+    fn orbit_view_turns_about_the_vertical_axis() {
+        let view = OrbitView {
+            size: 64,
+            side: 3,
+            yaw: 0.0,
+        };
+        let [across, up, depth] = view.rotate(&Point::new(vec![2, 1, 1]));
+        assert!((across - 1.0).abs() < 1e-9 && up.abs() < 1e-9 && depth.abs() < 1e-9);
+
+        let turned = OrbitView {
+            yaw: PI / 2.0,
+            ..view
+        };
+        let [across, _, depth] = turned.rotate(&Point::new(vec![2, 1, 1]));
+        assert!(across.abs() < 1e-9);
+        assert!(depth > 0.5);
+
+        let [_, up, _] = view.rotate(&Point::new(vec![1, 1, 2]));
+        assert!(up > 0.5, "curve axis 2 points up the image");
+    }
+
+    #[test]
+    fn orbit_image_fades_far_segments() {
+        let pattern = StubPattern::from_points(vec![vec![0, 0, 0], vec![1, 0, 0], vec![1, 1, 0]]);
+        let stroke = StrokeOptions {
+            line_width: 1,
+            long_edges: false,
+            palette: MapPalette {
+                foreground: Rgba([0, 0, 0, 255]),
+                background: Rgba([255, 255, 255, 255]),
+            },
+        };
+        let view = OrbitView {
+            size: 64,
+            side: 2,
+            yaw: 0.0,
+        };
+
+        let image = render_orbit_image(view, 0, 3, stroke, None, &pattern);
+        let mut shades: Vec<_> = image.pixels().map(|p| p.0[0]).collect();
+        shades.sort_unstable();
+        shades.dedup();
+        assert!(shades.len() >= 3, "near, far and background shades");
+        assert!(!shades.contains(&0), "no segment sits at the very front");
+    }
+
     #[test]
     fn render_skips_long_edges_by_default() {
         let pattern = StubPattern::new(vec![[0, 0], [2, 0]]);
@@ -315,4 +730,55 @@ mod tests {
         let mid_pixel_long = with_long.get_pixel(32, 10);
         assert_eq!(mid_pixel_long, &stroke_short.palette.foreground);
     }
+
+    #[test]
+    fn projection_selects_axes() {
+        let pattern = StubPattern::from_points(vec![vec![0, 0, 0], vec![0, 0, 1], vec![1, 0, 1]]);
+        let stroke = StrokeOptions {
+            line_width: 1,
+            long_edges: false,
+            palette: MapPalette {
+                foreground: Rgba([4, 5, 6, 255]),
+                background: Rgba([0, 0, 0, 0]),
+            },
+        };
+        let projection = Projection {
+            axes: [0, 2],
+            slice: Vec::new(),
+        };
+
+        let image = render_projected_image(32, 2, 0..3, stroke, &projection, &pattern);
+
+        // Steps along axis 2 become vertical, steps along axis 0 horizontal.
+        assert_eq!(image.get_pixel(10, 16), &stroke.palette.foreground);
+        assert_eq!(image.get_pixel(16, 22), &stroke.palette.foreground);
+        assert_eq!(image.get_pixel(22, 10), &stroke.palette.background);
+    }
+
+    #[test]
+    fn projection_slice_skips_steps_outside_slice() {
+        let pattern = StubPattern::from_points(vec![
+            vec![0, 0, 0],
+            vec![1, 0, 0],
+            vec![1, 0, 1],
+            vec![1, 1, 1],
+        ]);
+        let stroke = StrokeOptions {
+            line_width: 1,
+            long_edges: true,
+            palette: MapPalette {
+                foreground: Rgba([4, 5, 6, 255]),
+                background: Rgba([0, 0, 0, 0]),
+            },
+        };
+        let projection = Projection {
+            axes: [0, 1],
+            slice: vec![(2, 0)],
+        };
+
+        let image = render_projected_image(32, 2, 0..4, stroke, &projection, &pattern);
+
+        assert_eq!(image.get_pixel(16, 10), &stroke.palette.foreground);
+        assert_eq!(image.get_pixel(22, 16), &stroke.palette.background);
+    }
 }
@@ -0,0 +1,374 @@
+//! Named byte ranges overlaid on `vis` images.
+//!
+//! Sections come from a TOML or JSON file listing a name and byte range for each entry, such
+//! as the headers and segments reported by a disassembler or format parser:
+//!
+//! ```toml
+//! [[sections]]
+//! name = "header"
+//! start = 0
+//! size = 0x200
+//!
+//! [[sections]]
+//! name = ".text"
+//! start = 0x1000
+//! end = 0x8000
+//! color = "orange"
+//! ```
+//!
+//! Each section is outlined where its bytes fall on the curve and labelled near its middle.
+
+use std::{fs, ops::Range, path::Path};
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use anyhow::{Context, Result, anyhow, bail};
+use image::{Rgba, RgbaImage};
+use serde::Deserialize;
+use spacecurve::SpaceCurve;
+
+use crate::map::parse_rgba_color;
+
+/// Colors assigned in turn to sections that do not name one.
+const PALETTE: [Rgba<u8>; 6] = [
+    Rgba([0xff, 0xd7, 0x00, 0xff]),
+    Rgba([0x00, 0xe5, 0xff, 0xff]),
+    Rgba([0xff, 0x40, 0xff, 0xff]),
+    Rgba([0x76, 0xff, 0x03, 0xff]),
+    Rgba([0xff, 0x91, 0x00, 0xff]),
+    Rgba([0xff, 0xff, 0xff, 0xff]),
+];
+/// Padding between a label's text and the edge of its box, in pixels.
+const LABEL_PADDING: u32 = 2;
+
+/// Layout of a sections file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SectionsFile {
+    /// The listed sections.
+    sections: Vec<SectionEntry>,
+}
+
+/// One section as written in a sections file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SectionEntry {
+    /// Label drawn on the image.
+    name: String,
+    /// First byte offset of the section.
+    start: u64,
+    /// Offset one past the last byte; exclusive with `size`.
+    end: Option<u64>,
+    /// Length of the section in bytes; exclusive with `end`.
+    size: Option<u64>,
+    /// Outline color, named or hex; defaults to the next palette color.
+    color: Option<String>,
+}
+
+/// A named byte range to outline on a `vis` image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// Label drawn on the image.
+    pub name: String,
+    /// Byte offsets covered by the section.
+    pub range: Range<usize>,
+    /// Outline and label color.
+    pub color: Rgba<u8>,
+}
+
+/// Read the sections listed in the TOML or JSON file at `path`, chosen by its extension.
+pub fn load(path: &Path) -> Result<Vec<Section>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("cannot read sections file {}", path.display()))?;
+    let file: SectionsFile = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&text)?,
+        Some("toml") => toml::from_str(&text)?,
+        _ => bail!(
+            "sections file {} must have a .toml or .json extension",
+            path.display()
+        ),
+    };
+    file.sections
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| section(entry, PALETTE[i % PALETTE.len()]))
+        .collect()
+}
+
+/// Validate a parsed entry, giving it `fallback` when it names no color.
+fn section(entry: SectionEntry, fallback: Rgba<u8>) -> Result<Section> {
+    let end = match (entry.end, entry.size) {
+        (Some(end), None) => end,
+        (None, Some(size)) => entry.start.saturating_add(size),
+        _ => bail!("section '{}' needs exactly one of end or size", entry.name),
+    };
+    if end <= entry.start {
+        bail!("section '{}' is empty", entry.name);
+    }
+    let color = match &entry.color {
+        Some(color) => {
+            parse_rgba_color(color).map_err(|e| anyhow!("section '{}': {e}", entry.name))?
+        }
+        None => fallback,
+    };
+    let offset = |value: u64| usize::try_from(value).unwrap_or(usize::MAX);
+    Ok(Section {
+        range: offset(entry.start)..offset(end),
+        name: entry.name,
+        color,
+    })
+}
+
+/// Outline and label `sections` on a `vis` image of `data_len` bytes laid out along `pattern`.
+///
+/// A pixel belongs to a section when any byte it stands for does, so sections smaller than a
+/// pixel still show. Sections are drawn in order, later ones on top. Sections lying wholly
+/// past the end of the data are skipped and returned so the caller can report them.
+pub fn draw_sections<'a>(
+    image: &mut RgbaImage,
+    pattern: &dyn SpaceCurve,
+    data_len: usize,
+    sections: &'a [Section],
+) -> Vec<&'a Section> {
+    let font = label_font();
+    let scale = PxScale::from((image.width() as f32 / 24.0).clamp(10.0, 24.0));
+    let mut skipped = Vec::new();
+    for section in sections {
+        let mask = coverage(
+            image.width(),
+            image.height(),
+            pattern,
+            data_len,
+            &section.range,
+        );
+        match label_anchor(image.width(), &mask) {
+            Some(anchor) => {
+                outline(image, &mask, section.color);
+                label(image, &font, scale, anchor, &section.name, section.color);
+            }
+            None => skipped.push(section),
+        }
+    }
+    skipped
+}
+
+/// Pixels showing at least one byte of `range`, in row-major order.
+fn coverage(
+    width: u32,
+    height: u32,
+    pattern: &dyn SpaceCurve,
+    data_len: usize,
+    range: &Range<usize>,
+) -> Vec<bool> {
+    let mut mask = vec![false; (width * height) as usize];
+    let plen = u128::from(pattern.length());
+    let offset = |i: u32| (u128::from(i) * data_len as u128 / plen) as usize;
+    for i in 0..pattern.length() {
+        // Same sampling as `vis`: pixel `i` shows bytes from its own offset up to the next
+        // pixel's, and always at least its own byte.
+        let first = offset(i);
+        let last = offset(i + 1).max(first + 1);
+        if first < range.end && range.start < last {
+            let p = pattern.point(i);
+            mask[(p[1] * width + p[0]) as usize] = true;
+        }
+    }
+    mask
+}
+
+/// Recolor the pixels of `mask` that touch a pixel outside it or the image edge.
+fn outline(image: &mut RgbaImage, mask: &[bool], color: Rgba<u8>) {
+    let (width, height) = image.dimensions();
+    let inside = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && x < i64::from(width)
+            && y < i64::from(height)
+            && mask[(y as u32 * width + x as u32) as usize]
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let (sx, sy) = (i64::from(x), i64::from(y));
+            if inside(sx, sy)
+                && [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                    .iter()
+                    .any(|(dx, dy)| !inside(sx + dx, sy + dy))
+            {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+/// The pixel of `mask` nearest its centroid, where the label goes, or `None` when the mask is
+/// empty.
+fn label_anchor(width: u32, mask: &[bool]) -> Option<(u32, u32)> {
+    let pixels = || {
+        mask.iter()
+            .enumerate()
+            .filter(|&(_, &set)| set)
+            .map(|(i, _)| (i as u32 % width, i as u32 / width))
+    };
+    let (mut sx, mut sy, mut n) = (0.0, 0.0, 0.0);
+    for (x, y) in pixels() {
+        sx += f64::from(x);
+        sy += f64::from(y);
+        n += 1.0;
+    }
+    let (cx, cy) = (sx / n, sy / n);
+    pixels().min_by(|a, b| {
+        let distance =
+            |(x, y): (u32, u32)| (f64::from(x) - cx).powi(2) + (f64::from(y) - cy).powi(2);
+        distance(*a).total_cmp(&distance(*b))
+    })
+}
+
+/// The font labels are drawn in.
+pub fn label_font() -> FontRef<'static> {
+    FontRef::try_from_slice(epaint_default_fonts::HACK_REGULAR).expect("bundled font parses")
+}
+
+/// Draw `text` in a box of `color` centered on `anchor`, kept inside the image.
+pub fn label(
+    image: &mut RgbaImage,
+    font: &FontRef<'_>,
+    scale: PxScale,
+    anchor: (u32, u32),
+    text: &str,
+    color: Rgba<u8>,
+) {
+    let scaled = font.as_scaled(scale);
+    let text_width: f32 = text
+        .chars()
+        .map(|c| scaled.h_advance(font.glyph_id(c)))
+        .sum();
+    let box_width = text_width.ceil() as u32 + 2 * LABEL_PADDING;
+    let box_height = scaled.height().ceil() as u32 + 2 * LABEL_PADDING;
+    let (width, height) = image.dimensions();
+    let left = anchor
+        .0
+        .saturating_sub(box_width / 2)
+        .min(width.saturating_sub(box_width));
+    let top = anchor
+        .1
+        .saturating_sub(box_height / 2)
+        .min(height.saturating_sub(box_height));
+
+    for y in top..(top + box_height).min(height) {
+        for x in left..(left + box_width).min(width) {
+            image.put_pixel(x, y, color);
+        }
+    }
+
+    let [red, green, blue, _] = color.0.map(f32::from);
+    let luma = 0.299 * red + 0.587 * green + 0.114 * blue;
+    let ink: u8 = if luma > 128.0 { 0x00 } else { 0xff };
+    let mut caret = point(
+        (left + LABEL_PADDING) as f32,
+        (top + LABEL_PADDING) as f32 + scaled.ascent(),
+    );
+    for c in text.chars() {
+        let glyph = font.glyph_id(c).with_scale_and_position(scale, caret);
+        caret.x += scaled.h_advance(glyph.id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let x = bounds.min.x as i64 + i64::from(gx);
+            let y = bounds.min.y as i64 + i64::from(gy);
+            if x < 0 || y < 0 || x >= i64::from(width) || y >= i64::from(height) {
+                return;
+            }
+            let pixel = image.get_pixel_mut(x as u32, y as u32);
+            for channel in &mut pixel.0[..3] {
+                let blended = f32::from(*channel) * (1.0 - coverage) + f32::from(ink) * coverage;
+                *channel = blended.round() as u8;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spacecurve::curve_from_name;
+
+    use super::*;
+
+    fn entry(name: &str, start: u64, end: Option<u64>, size: Option<u64>) -> SectionEntry {
+        SectionEntry {
+            name: name.to_string(),
+            start,
+            end,
+            size,
+            color: None,
+        }
+    }
+
+    #[test]
+    fn parses_toml_and_json() {
+        let toml: SectionsFile = toml::from_str(
+            "[[sections]]\nname = \"header\"\nstart = 0\nsize = 0x200\ncolor = \"red\"\n",
+        )
+        .unwrap();
+        let json: SectionsFile = serde_json::from_str(
+            r#"{"sections": [{"name": "header", "start": 0, "end": 512, "color": "red"}]}"#,
+        )
+        .unwrap();
+        for file in [toml, json] {
+            let parsed: Vec<_> = file
+                .sections
+                .into_iter()
+                .map(|e| section(e, PALETTE[0]).unwrap())
+                .collect();
+            assert_eq!(
+                parsed,
+                [Section {
+                    name: "header".to_string(),
+                    range: 0..512,
+                    color: Rgba([0xff, 0, 0, 0xff]),
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_ranges() {
+        assert!(section(entry("a", 0, None, None), PALETTE[0]).is_err());
+        assert!(section(entry("a", 0, Some(4), Some(4)), PALETTE[0]).is_err());
+        assert!(section(entry("a", 8, Some(8), None), PALETTE[0]).is_err());
+    }
+
+    #[test]
+    fn tiny_sections_cover_a_pixel() {
+        let pattern = curve_from_name("hilbert", 2, 4).unwrap();
+        // 1600 bytes on 16 pixels: each pixel stands for 100 bytes.
+        let mask = coverage(4, 4, pattern.as_ref(), 1600, &(250..251));
+        assert_eq!(mask.iter().filter(|&&m| m).count(), 1);
+        let p = pattern.point(2);
+        assert!(mask[(p[1] * 4 + p[0]) as usize]);
+        let spanning = coverage(4, 4, pattern.as_ref(), 1600, &(150..450));
+        assert_eq!(spanning.iter().filter(|&&m| m).count(), 4);
+    }
+
+    #[test]
+    fn draws_and_reports_sections_past_the_end() {
+        let pattern = curve_from_name("hilbert", 2, 64).unwrap();
+        let sections = [
+            Section {
+                name: "code".to_string(),
+                range: 0..2048,
+                color: PALETTE[0],
+            },
+            Section {
+                name: "ghost".to_string(),
+                range: 9000..9100,
+                color: PALETTE[1],
+            },
+        ];
+        let mut image = RgbaImage::new(64, 64);
+        let skipped = draw_sections(&mut image, pattern.as_ref(), 4096, &sections);
+        assert_eq!(skipped, [&sections[1]]);
+        assert!(image.pixels().any(|p| *p == PALETTE[0]));
+        assert!(!image.pixels().any(|p| *p == PALETTE[1]));
+    }
+}
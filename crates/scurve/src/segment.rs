@@ -0,0 +1,209 @@
+//! Entropy segmentation of files shown by `vis`.
+//!
+//! The input is cut into fixed-size blocks and the Shannon entropy of each block is measured.
+//! Consecutive blocks with similar entropy are merged into regions, and a region is flagged as
+//! anomalous when its entropy lies far from the file's typical (median) block entropy, as
+//! happens with a compressed or encrypted blob embedded in text or code.
+
+use std::ops::Range;
+
+use image::{Rgba, RgbaImage};
+use spacecurve::SpaceCurve;
+
+/// Bytes per entropy block.
+pub const BLOCK_BYTES: usize = 1024;
+/// Difference in bits per byte from a region's mean entropy that starts a new region.
+const SPLIT_BITS: f64 = 1.0;
+/// Difference in bits per byte from the median block entropy that marks a region anomalous.
+const ANOMALY_BITS: f64 = 2.0;
+/// Outline color for ordinary region boundaries.
+const COLOR_BOUNDARY: Rgba<u8> = Rgba([0xff, 0xd7, 0x00, 0xff]);
+/// Outline color for anomalous regions.
+const COLOR_ANOMALY: Rgba<u8> = Rgba([0xff, 0x00, 0xff, 0xff]);
+
+/// A run of bytes with similar entropy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    /// Byte offsets covered by the region.
+    pub range: Range<usize>,
+    /// Mean entropy of the region's blocks, in bits per byte.
+    pub entropy: f64,
+    /// Whether the region's entropy stands out sharply from the rest of the file.
+    pub anomalous: bool,
+}
+
+/// Shannon entropy of `bytes`, in bits per byte.
+fn entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0u32; 256];
+    for &byte in bytes {
+        counts[byte as usize] += 1;
+    }
+    let total = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = f64::from(count) / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Split `data` into regions of similar entropy, measured over blocks of `block` bytes.
+pub fn segment(data: &[u8], block: usize) -> Vec<Region> {
+    let mut regions: Vec<Region> = Vec::new();
+    let mut entropies = Vec::new();
+    // Running sum of block entropies and block count for the open region.
+    let mut sum = 0.0;
+    let mut blocks = 0.0;
+    for (i, chunk) in data.chunks(block.max(1)).enumerate() {
+        let start = i * block.max(1);
+        let bits = entropy(chunk);
+        entropies.push(bits);
+        match regions.last_mut() {
+            Some(region) if (bits - sum / blocks).abs() <= SPLIT_BITS => {
+                region.range.end = start + chunk.len();
+                sum += bits;
+                blocks += 1.0;
+                region.entropy = sum / blocks;
+            }
+            _ => {
+                regions.push(Region {
+                    range: start..start + chunk.len(),
+                    entropy: bits,
+                    anomalous: false,
+                });
+                sum = bits;
+                blocks = 1.0;
+            }
+        }
+    }
+    entropies.sort_by(f64::total_cmp);
+    if let Some(&median) = entropies.get(entropies.len() / 2) {
+        for region in &mut regions {
+            region.anomalous = (region.entropy - median).abs() > ANOMALY_BITS;
+        }
+    }
+    regions
+}
+
+/// Outline `regions` on a `vis` image of `data_len` bytes laid out along `pattern`.
+///
+/// Pixels at the edge of a region are recolored, so every boundary between regions is drawn
+/// along the curve's path through the image. Anomalous regions get a distinct outline.
+pub fn draw_regions(
+    image: &mut RgbaImage,
+    pattern: &dyn SpaceCurve,
+    data_len: usize,
+    regions: &[Region],
+) {
+    let (width, height) = image.dimensions();
+    let mut labels = vec![0usize; (width * height) as usize];
+    let plen = u128::from(pattern.length());
+    let mut region = 0;
+    for (i, p) in (0u32..).zip(pattern.iter()) {
+        // Same sampling as `vis`, so offsets only grow and the regions can be walked in order.
+        let offset = (u128::from(i) * data_len as u128 / plen) as usize;
+        while region + 1 < regions.len() && offset >= regions[region].range.end {
+            region += 1;
+        }
+        labels[(p[1] * width + p[0]) as usize] = region;
+    }
+
+    let label = |x: u32, y: u32| labels[(y * width + x) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let own = label(x, y);
+            let neighbours = [
+                (x > 0).then(|| label(x - 1, y)),
+                (x + 1 < width).then(|| label(x + 1, y)),
+                (y > 0).then(|| label(x, y - 1)),
+                (y + 1 < height).then(|| label(x, y + 1)),
+            ];
+            if neighbours.iter().flatten().any(|&other| other != own) {
+                let color = if regions[own].anomalous {
+                    COLOR_ANOMALY
+                } else {
+                    COLOR_BOUNDARY
+                };
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spacecurve::curve_from_name;
+
+    use super::*;
+
+    /// Deterministic high-entropy bytes.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    /// Low-entropy text-like bytes.
+    fn text(len: usize) -> Vec<u8> {
+        b"the quick brown fox "
+            .iter()
+            .copied()
+            .cycle()
+            .take(len)
+            .collect()
+    }
+
+    #[test]
+    fn entropy_extremes() {
+        assert_eq!(entropy(&[7; 64]), 0.0);
+        let all: Vec<u8> = (0..=255).collect();
+        assert!((entropy(&all) - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn uniform_input_is_one_ordinary_region() {
+        let regions = segment(&text(8 * BLOCK_BYTES), BLOCK_BYTES);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].range, 0..8 * BLOCK_BYTES);
+        assert!(!regions[0].anomalous);
+    }
+
+    #[test]
+    fn embedded_blob_is_flagged() {
+        let mut data = text(4 * BLOCK_BYTES);
+        data.extend(noise(2 * BLOCK_BYTES));
+        data.extend(text(4 * BLOCK_BYTES + 100));
+        let regions = segment(&data, BLOCK_BYTES);
+        let ranges: Vec<_> = regions.iter().map(|r| r.range.clone()).collect();
+        assert_eq!(
+            ranges,
+            [
+                0..4 * BLOCK_BYTES,
+                4 * BLOCK_BYTES..6 * BLOCK_BYTES,
+                6 * BLOCK_BYTES..data.len()
+            ]
+        );
+        let flags: Vec<_> = regions.iter().map(|r| r.anomalous).collect();
+        assert_eq!(flags, [false, true, false]);
+    }
+
+    #[test]
+    fn boundaries_are_drawn_between_regions() {
+        let mut data = text(2 * BLOCK_BYTES);
+        data.extend(noise(2 * BLOCK_BYTES));
+        let regions = segment(&data, BLOCK_BYTES);
+        let pattern = curve_from_name("hilbert", 2, 16).unwrap();
+        let mut image = RgbaImage::new(16, 16);
+        draw_regions(&mut image, pattern.as_ref(), data.len(), &regions);
+        let outlined = image.pixels().filter(|&&p| p != Rgba([0; 4])).count();
+        assert!(outlined > 0 && outlined < 256);
+    }
+}
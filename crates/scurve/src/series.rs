@@ -0,0 +1,120 @@
+//! Numeric series read from CSV files for `csv2map`.
+//!
+//! Each record contributes one value, taken from a chosen column, placed at a curve index that
+//! is either the record's position among the data rows or an explicit index column. Columns
+//! are numbered from 1, like `cut` and `awk`. A first row whose value does not parse as a
+//! number is taken to be a header.
+
+use std::{io::Read, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+/// One value of a series and the curve index it is drawn at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Curve index of the value.
+    pub index: u32,
+    /// The value itself.
+    pub value: f64,
+}
+
+/// Read the series in column `value_col` of the CSV file at `path`.
+///
+/// Values are placed by `index_col` when given, and by row order otherwise. Rows with an empty
+/// value are skipped, though they still take up a place in row order.
+pub fn load(path: &Path, value_col: usize, index_col: Option<usize>) -> Result<Vec<Sample>> {
+    parse(reader().from_path(path)?, value_col, index_col)
+}
+
+/// CSV settings: headers are detected by [`parse`], rows may differ in length and fields are
+/// trimmed.
+fn reader() -> csv::ReaderBuilder {
+    let mut builder = csv::ReaderBuilder::new();
+    builder
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All);
+    builder
+}
+
+/// Read the series from CSV `reader`; see [`load`].
+fn parse<R: Read>(
+    mut reader: csv::Reader<R>,
+    value_col: usize,
+    index_col: Option<usize>,
+) -> Result<Vec<Sample>> {
+    if value_col == 0 || index_col == Some(0) {
+        bail!("columns are numbered from 1");
+    }
+    let mut samples = Vec::new();
+    let mut row: u32 = 0;
+    for (line, record) in reader.records().enumerate() {
+        let record = record?;
+        let field = |col: usize| record.get(col - 1).unwrap_or("");
+        let raw = field(value_col);
+        let value = match raw.parse::<f64>() {
+            Ok(value) if value.is_finite() => Some(value),
+            _ if raw.is_empty() => None,
+            // A first row that is not numeric names the columns.
+            _ if line == 0 => continue,
+            _ => bail!("line {}: value '{raw}' is not a number", line + 1),
+        };
+        let index = match index_col {
+            Some(col) => field(col)
+                .parse::<u32>()
+                .with_context(|| format!("line {}: bad index '{}'", line + 1, field(col)))?,
+            None => row,
+        };
+        row = row.checked_add(1).ok_or_else(|| anyhow!("too many rows"))?;
+        if let Some(value) = value {
+            samples.push(Sample { index, value });
+        }
+    }
+    if samples.is_empty() {
+        bail!("no numeric values in column {value_col}");
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse `text` as CSV with the same settings as [`load`].
+    fn parse_str(text: &str, value_col: usize, index_col: Option<usize>) -> Result<Vec<Sample>> {
+        parse(reader().from_reader(text.as_bytes()), value_col, index_col)
+    }
+
+    #[test]
+    fn reads_values_in_row_order() {
+        let samples = parse_str("time,temp\n1,20.5\n2,\n3,-4e1\n", 2, None).unwrap();
+        assert_eq!(
+            samples,
+            [
+                Sample {
+                    index: 0,
+                    value: 20.5
+                },
+                Sample {
+                    index: 2,
+                    value: -40.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_explicit_indices() {
+        let samples = parse_str("7, 1.5\n3,\"2\"\n", 2, Some(1)).unwrap();
+        let indices: Vec<_> = samples.iter().map(|s| s.index).collect();
+        assert_eq!(indices, [7, 3]);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse_str("1\nx\n", 1, None).is_err());
+        assert!(parse_str("a,b\n", 2, None).is_err());
+        assert!(parse_str("1,2\n", 0, None).is_err());
+        assert!(parse_str("-1,2\n", 2, Some(1)).is_err());
+    }
+}
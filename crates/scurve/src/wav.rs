@@ -0,0 +1,180 @@
+//! WAV decoding and analysis for the `audio` subcommand.
+//!
+//! WAV files are decoded with `hound` and mixed down to mono. The signal is cut into one window
+//! per pixel, and each window is summarised by its loudness or by its energy in three frequency
+//! bands. Levels are measured in decibels so quiet passages stay visible next to loud ones.
+
+use std::{f32::consts::TAU, ops::Range, path::Path};
+
+use anyhow::{Result, bail};
+use hound::{SampleFormat, WavReader};
+
+/// Quietest level shown, in decibels below the loudest window; anything quieter maps to zero.
+const FLOOR_DB: f32 = 60.0;
+/// Upper edge of the low band, in hertz.
+const LOW_HZ: f32 = 250.0;
+/// Lower edge of the high band, in hertz.
+const HIGH_HZ: f32 = 4000.0;
+
+/// Decoded mono audio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audio {
+    /// Samples in `[-1, 1]`, averaged across channels.
+    pub samples: Vec<f32>,
+    /// Sample rate in hertz.
+    pub rate: u32,
+}
+
+/// Decode the WAV file at `path` and mix it down to mono.
+pub fn load(path: &Path) -> Result<Audio> {
+    let reader = WavReader::open(path)?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+        SampleFormat::Int => {
+            let scale = 1.0 / (1_u64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|sample| sample.map(|value| value as f32 * scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+    let channels = usize::from(spec.channels.max(1));
+    let samples: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    if samples.is_empty() {
+        bail!("audio file has no samples");
+    }
+    Ok(Audio {
+        samples,
+        rate: spec.sample_rate,
+    })
+}
+
+/// Sample range of window `i` when `len` samples are cut into `windows` windows.
+///
+/// Windows are never empty: with fewer samples than windows, neighbouring windows share a
+/// sample.
+fn window(i: usize, windows: usize, len: usize) -> Range<usize> {
+    let at = |i: usize| (i as u128 * len as u128 / windows as u128) as usize;
+    let start = at(i).min(len - 1);
+    start..at(i + 1).clamp(start + 1, len)
+}
+
+/// Root mean square of `values`.
+fn rms(values: impl ExactSizeIterator<Item = f32>) -> f32 {
+    let count = values.len().max(1) as f32;
+    (values.map(|v| v * v).sum::<f32>() / count).sqrt()
+}
+
+/// Root-mean-square amplitude of each of `windows` windows of `samples`.
+pub fn loudness(samples: &[f32], windows: usize) -> Vec<f32> {
+    (0..windows)
+        .map(|i| rms(samples[window(i, windows, samples.len())].iter().copied()))
+        .collect()
+}
+
+/// Root-mean-square amplitude of the low, mid and high bands of each of `windows` windows.
+///
+/// The bands are split with one-pole filters at [`LOW_HZ`] and [`HIGH_HZ`], which is coarse
+/// but cheap and needs no transform.
+pub fn band_energies(audio: &Audio, windows: usize) -> Vec<[f32; 3]> {
+    let coefficient = |hz: f32| 1.0 - (-TAU * hz / audio.rate.max(1) as f32).exp();
+    let (low_k, high_k) = (coefficient(LOW_HZ), coefficient(HIGH_HZ));
+    let (mut low, mut below_high) = (0.0, 0.0);
+    let bands: Vec<[f32; 3]> = audio
+        .samples
+        .iter()
+        .map(|&x| {
+            low += low_k * (x - low);
+            below_high += high_k * (x - below_high);
+            [low, below_high - low, x - below_high]
+        })
+        .collect();
+    (0..windows)
+        .map(|i| {
+            let range = window(i, windows, bands.len());
+            [0, 1, 2].map(|band| rms(bands[range.clone()].iter().map(|b| b[band])))
+        })
+        .collect()
+}
+
+/// Position of `level` on a decibel scale from [`FLOOR_DB`] below `peak` (0) up to `peak` (1).
+pub fn decibel_scale(level: f32, peak: f32) -> f32 {
+    if level <= 0.0 || peak <= 0.0 {
+        return 0.0;
+    }
+    (1.0 + 20.0 * (level / peak).log10() / FLOOR_DB).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One second of a sine wave at `hz`, sampled at `rate`.
+    fn tone(hz: f32, rate: u32) -> Vec<f32> {
+        (0..rate)
+            .map(|i| (TAU * hz * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn windows_cover_every_sample() {
+        let ranges: Vec<_> = (0..4).map(|i| window(i, 4, 10)).collect();
+        assert_eq!(ranges, [0..2, 2..5, 5..7, 7..10]);
+        let sparse: Vec<_> = (0..4).map(|i| window(i, 4, 2)).collect();
+        assert_eq!(sparse, [0..1, 0..1, 1..2, 1..2]);
+    }
+
+    #[test]
+    fn loudness_tracks_amplitude() {
+        let mut samples = vec![0.0; 8000];
+        samples.extend(tone(440.0, 8000).iter().map(|s| s * 0.5));
+        let levels = loudness(&samples, 2);
+        assert!(levels[0] < 0.1);
+        assert!((levels[1] - 0.5 / 2_f32.sqrt()).abs() < 0.05);
+    }
+
+    #[test]
+    fn bands_separate_low_and_high_tones() {
+        let rate = 44_100;
+        let mut samples = tone(60.0, rate);
+        samples.extend(tone(10_000.0, rate));
+        let bands = band_energies(&Audio { samples, rate }, 2);
+        assert!(bands[0][0] > bands[0][2] * 4.0, "{bands:?}");
+        assert!(bands[1][2] > bands[1][0] * 4.0, "{bands:?}");
+    }
+
+    #[test]
+    fn decibel_scale_spans_the_floor() {
+        assert_eq!(decibel_scale(1.0, 1.0), 1.0);
+        assert!((decibel_scale(0.1, 1.0) - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(decibel_scale(1e-6, 1.0), 0.0);
+        assert_eq!(decibel_scale(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn loads_wav_files_as_mono() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..10 {
+            writer.write_sample(i16::MAX).unwrap();
+            writer.write_sample(0_i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let audio = load(&path).unwrap();
+        assert_eq!(audio.rate, 8000);
+        assert_eq!(audio.samples.len(), 10);
+        assert!(audio.samples.iter().all(|&s| (s - 0.5).abs() < 1e-3));
+    }
+}
@@ -6,7 +6,7 @@ use std::{fs, fs::File, io::Write, path::PathBuf, process::Command};
 
 use assert_cmd::{
     assert::{Assert, OutputAssertExt},
-    cargo::CommandCargoExt,
+    cargo::{CommandCargoExt, cargo_bin},
 };
 use colornames::Color;
 use image::DynamicImage;
@@ -296,6 +296,52 @@ fn map_with_various_dimensions() {
     }
 }
 
+#[test]
+fn map_projects_high_dimensional_curves() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("map4d.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("map")
+        .arg("-s")
+        .arg("128")
+        .arg("-d")
+        .arg("4")
+        .arg("--dims")
+        .arg("4")
+        .arg("--axes")
+        .arg("0,2")
+        .arg("--slice")
+        .arg("1=0")
+        .arg("--slice")
+        .arg("3=1")
+        .arg("hilbert")
+        .arg(&output);
+    cmd.assert().success();
+
+    let img = read_image(&output);
+    assert_eq!(img.width(), 128);
+    assert_eq!(img.height(), 128);
+}
+
+#[test]
+fn map_rejects_invalid_projection() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("map.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("map")
+        .arg("-d")
+        .arg("4")
+        .arg("--dims")
+        .arg("3")
+        .arg("--axes")
+        .arg("0,3")
+        .arg("hilbert")
+        .arg(&output);
+    cmd.assert().failure();
+}
+
 #[test]
 fn map_with_scan_pattern() {
     let td = tempdir().expect("tmp");
@@ -507,6 +553,88 @@ fn snake_renders_full_curve_when_requested() {
     );
 }
 
+#[test]
+#[allow(deprecated)]
+fn snake_peels_one_shell_per_frame() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("peel.gif");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("snake")
+        .arg("-s")
+        .arg("32")
+        .arg("-d")
+        .arg("8")
+        .arg("--peel")
+        .arg("onion")
+        .arg(&output);
+    cmd.assert().success();
+
+    let mut decoder = gif::DecodeOptions::new();
+    decoder.set_color_output(gif::ColorOutput::RGBA);
+    let mut reader = decoder
+        .read_info(File::open(&output).expect("open gif"))
+        .expect("read gif");
+    let mut frames = 0;
+    while reader.read_next_frame().expect("frame").is_some() {
+        frames += 1;
+    }
+    assert_eq!(frames, 4, "an 8×8 grid has four shells");
+}
+
+#[test]
+#[allow(deprecated)]
+fn snake_requires_chunk_or_peel() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("none.gif");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("snake").arg("hilbert").arg(&output);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("snake")
+        .arg("--peel")
+        .arg("--chunk")
+        .arg("0:4")
+        .arg("hilbert")
+        .arg(&output);
+    cmd.assert().failure();
+}
+
+#[test]
+#[allow(deprecated)]
+fn snake_renders_rotating_3d_view() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("snake_3d.gif");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("snake")
+        .arg("-s")
+        .arg("48")
+        .arg("-d")
+        .arg("2")
+        .arg("--dims")
+        .arg("3")
+        .arg("--chunk")
+        .arg("0:3")
+        .arg("hilbert")
+        .arg(&output);
+    cmd.assert().success();
+
+    let mut decoder = gif::DecodeOptions::new();
+    decoder.set_color_output(gif::ColorOutput::RGBA);
+    let mut reader = decoder
+        .read_info(File::open(&output).expect("open gif"))
+        .expect("read gif");
+    let mut frames = Vec::new();
+    while let Some(frame) = reader.read_next_frame().expect("frame") {
+        frames.push(frame.buffer.to_vec());
+    }
+    assert_eq!(frames.len(), 8, "one frame per point of a 2×2×2 curve");
+    assert_ne!(frames[0], frames[1], "the view turns between frames");
+}
+
 // ============================================================================
 // ALLRGB command tests
 // ============================================================================
@@ -526,6 +654,452 @@ fn allrgb_produces_correct_dimensions() {
     assert_eq!(img.height(), 4096);
 }
 
+#[test]
+#[ignore = "slow: walks all 4096x4096 pixels; run with --ignored"]
+fn allrgb_animation_has_requested_frames() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("allrgb.gif");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("allrgb")
+        .arg("--animate")
+        .arg("--frames")
+        .arg("3")
+        .arg("-s")
+        .arg("64")
+        .arg("hilbert")
+        .arg(&output);
+    cmd.assert().success();
+
+    let mut decoder = gif::DecodeOptions::new();
+    decoder.set_color_output(gif::ColorOutput::RGBA);
+    let mut reader = decoder
+        .read_info(File::open(&output).expect("open gif"))
+        .expect("read gif");
+    assert_eq!(reader.width(), 64);
+    let mut frames = 0;
+    while reader.read_next_frame().expect("frame").is_some() {
+        frames += 1;
+    }
+    assert_eq!(frames, 3);
+}
+
+#[test]
+fn allrgb_animation_rejects_bad_options() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("allrgb.gif");
+
+    // Animation needs an output file, and its size must divide 4096.
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("allrgb").arg("--animate").arg("hilbert");
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("allrgb")
+        .arg("--animate")
+        .arg("-s")
+        .arg("100")
+        .arg("hilbert")
+        .arg(&output);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("allrgb")
+        .arg("--frames")
+        .arg("4")
+        .arg("hilbert")
+        .arg(&output);
+    cmd.assert().failure();
+}
+
+// ============================================================================
+// AUDIO command tests
+// ============================================================================
+
+/// Write a mono 16-bit WAV file that is silent for its first half and a tone for the second.
+fn write_half_tone_wav(path: &PathBuf) {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 8000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).expect("create wav");
+    for i in 0..8000 {
+        let sample = if i < 4000 {
+            0.0
+        } else {
+            (f64::from(i) * 0.3).sin() * 16_000.0
+        };
+        writer.write_sample(sample as i16).expect("write sample");
+    }
+    writer.finalize().expect("finalize wav");
+}
+
+#[test]
+fn audio_maps_loudness_along_the_curve() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("tone.wav");
+    write_half_tone_wav(&input);
+
+    for mode in ["amplitude", "bands"] {
+        let output = td.path().join(format!("{mode}.png"));
+        let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+        cmd.arg("audio")
+            .arg("-w")
+            .arg("16")
+            .arg("--mode")
+            .arg(mode)
+            .arg(&input)
+            .arg(&output);
+        cmd.assert().success();
+
+        let img = read_image(&output).to_rgba8();
+        assert_eq!(img.dimensions(), (16, 16));
+        // The curve starts in the silent half, which takes exactly half of the pixels.
+        let silent = *img.get_pixel(0, 0);
+        let quiet = img.pixels().filter(|&&p| p == silent).count();
+        assert_eq!(quiet, 128, "{mode}");
+    }
+}
+
+#[test]
+fn audio_rejects_non_wav_input() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("data.bin");
+    write_bytes(&input, b"not a wav file");
+    let output = td.path().join("out.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("audio").arg(&input).arg(&output);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("audio")
+        .arg("--colormap")
+        .arg("solid")
+        .arg(&input)
+        .arg(&output);
+    cmd.assert().failure();
+}
+
+// ============================================================================
+// CSV2MAP command tests
+// ============================================================================
+
+#[test]
+fn csv2map_plots_a_column_along_the_curve() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("data.csv");
+    let mut csv = String::from("row,value\n");
+    for i in 0..16 {
+        csv.push_str(&format!("{i},{}\n", if i < 8 { 0 } else { 10 }));
+    }
+    fs::write(&input, csv).expect("write csv");
+    let output = td.path().join("out.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("csv2map")
+        .arg("--value-col")
+        .arg("2")
+        .arg(&input)
+        .arg(&output);
+    cmd.assert().success();
+
+    let img = read_image(&output).to_rgba8();
+    // Sixteen rows fit a 4×4 grid, with each cell scaled up to 128 pixels.
+    assert_eq!(img.dimensions(), (512, 512));
+    let low = *img.get_pixel(0, 0);
+    let low_cells = img.pixels().filter(|&&p| p == low).count();
+    assert_eq!(low_cells, 8 * 128 * 128);
+}
+
+#[test]
+fn csv2map_rejects_non_numeric_columns() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("data.csv");
+    fs::write(&input, "name,value\nfirst,1\nsecond,2\n").expect("write csv");
+    let output = td.path().join("out.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("csv2map")
+        .arg("--value-col")
+        .arg("1")
+        .arg(&input)
+        .arg(&output);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("csv2map")
+        .arg("--value-col")
+        .arg("0")
+        .arg(&input)
+        .arg(&output);
+    cmd.assert().failure();
+}
+
+// ============================================================================
+// COMPARE command tests
+// ============================================================================
+
+#[test]
+fn compare_ranks_curves_in_every_format() {
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("compare").arg("-d").arg("2").arg("-s").arg("8");
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let text = String::from_utf8(stdout).expect("utf8 output");
+    assert!(text.starts_with("rank"), "{text}");
+    assert!(text.contains("Hilbert"), "{text}");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("compare")
+        .arg("-s")
+        .arg("8")
+        .arg("--format")
+        .arg("csv");
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let text = String::from_utf8(stdout).expect("utf8 output");
+    let mut lines = text.lines();
+    assert!(lines.next().expect("header").starts_with("rank,key,name"));
+    assert!(lines.next().expect("first row").starts_with("1,"));
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("compare")
+        .arg("-s")
+        .arg("8")
+        .arg("--format")
+        .arg("json");
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let rows: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(rows[0]["rank"], 1);
+}
+
+#[test]
+fn compare_skips_curves_that_do_not_fit() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("compare.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("compare")
+        .arg("-s")
+        .arg("6")
+        .arg("--tile")
+        .arg("32")
+        .arg("--image")
+        .arg(&output);
+    let assert = cmd.assert().success();
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).expect("utf8");
+    assert!(stderr.contains("skipping hilbert"), "{stderr}");
+
+    // Scan and Onion take a side of 6, one row of two 32-pixel tiles.
+    let img = read_image(&output).to_rgba8();
+    assert_eq!(img.dimensions(), (64, 32));
+}
+
+#[test]
+fn compare_fails_when_no_curve_fits() {
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("compare").arg("-s").arg("0");
+    cmd.assert().failure();
+}
+
+// ============================================================================
+// BATCH command tests
+// ============================================================================
+
+#[test]
+fn batch_renders_a_directory_into_a_gallery() {
+    let td = tempdir().expect("tmp");
+    let samples = td.path().join("samples");
+    let gallery = td.path().join("gallery");
+    fs::create_dir(&samples).expect("mkdir");
+    write_bytes(&samples.join("one.bin"), &[0x41; 300]);
+    write_bytes(&samples.join("two.bin"), &(0..=255).collect::<Vec<u8>>());
+    write_bytes(&samples.join("empty.bin"), &[]);
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("batch")
+        .arg("-w")
+        .arg("64")
+        .arg("--thumb")
+        .arg("32")
+        .arg("--out")
+        .arg(&gallery)
+        .arg(&samples);
+    let assert = cmd.assert().success();
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).expect("utf8");
+    assert!(stderr.contains("empty.bin"), "{stderr}");
+
+    assert_eq!(read_image(&gallery.join("one.bin.png")).width(), 64);
+    assert_eq!(read_image(&gallery.join("thumbs/two.bin.png")).width(), 32);
+    let index = fs::read_to_string(gallery.join("index.html")).expect("index");
+    assert!(index.contains("href=\"one.bin.png\""), "{index}");
+    assert!(index.contains("2 of 3 files rendered"), "{index}");
+}
+
+#[test]
+fn batch_fails_when_nothing_renders() {
+    let td = tempdir().expect("tmp");
+    let samples = td.path().join("samples");
+    fs::create_dir(&samples).expect("mkdir");
+    write_bytes(&samples.join("empty.bin"), &[]);
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("batch")
+        .arg("--out")
+        .arg(td.path().join("gallery"))
+        .arg(&samples);
+    cmd.assert().failure();
+}
+
+// ============================================================================
+// Render cache tests
+// ============================================================================
+
+fn cached_scurve(cache_dir: &PathBuf) -> Command {
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("--cache-dir").arg(cache_dir);
+    cmd
+}
+
+#[test]
+fn cache_replays_renders_until_disabled_or_cleared() {
+    let td = tempdir().expect("tmp");
+    let cache_dir = td.path().join("cache");
+    let input = td.path().join("input.bin");
+    let output = td.path().join("out.png");
+    write_bytes(&input, &(0..=255).collect::<Vec<u8>>());
+    let vis = |extra: &[&str]| {
+        let mut cmd = cached_scurve(&cache_dir);
+        cmd.args(extra)
+            .arg("vis")
+            .arg("-w")
+            .arg("32")
+            .arg(&input)
+            .arg(&output);
+        cmd.assert().success();
+        read_image(&output).to_rgba8()
+    };
+
+    let rendered = vis(&["--cache"]);
+    let stored: Vec<PathBuf> = fs::read_dir(&cache_dir)
+        .expect("cache dir")
+        .map(|entry| entry.expect("entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .collect();
+    assert_eq!(stored.len(), 1);
+
+    // Swap the stored image for a marker to tell replays from fresh renders.
+    let marker = image::RgbaImage::from_pixel(32, 32, image::Rgba([1, 2, 3, 255]));
+    marker.save(&stored[0]).expect("save marker");
+    assert_eq!(vis(&["--cache"]), marker);
+    assert_eq!(vis(&["--cache", "--no-cache"]), rendered);
+    assert_eq!(vis(&[]), rendered);
+
+    let mut clear = cached_scurve(&cache_dir);
+    clear.arg("cache").arg("clear");
+    let stdout = clear.assert().success().get_output().stdout.clone();
+    assert!(
+        String::from_utf8(stdout)
+            .expect("utf8")
+            .starts_with("Removed 2 files")
+    );
+    assert_eq!(vis(&["--cache"]), rendered);
+}
+
+#[test]
+fn cache_keys_on_input_contents_and_options() {
+    let td = tempdir().expect("tmp");
+    let cache_dir = td.path().join("cache");
+    let input = td.path().join("input.bin");
+    let output = td.path().join("out.png");
+    let run = |width: &str| {
+        let mut cmd = cached_scurve(&cache_dir);
+        cmd.arg("--cache")
+            .arg("vis")
+            .arg("-w")
+            .arg(width)
+            .arg(&input)
+            .arg(&output);
+        cmd.assert().success();
+    };
+    write_bytes(&input, &[0; 64]);
+    run("16");
+    run("16");
+    run("32");
+    write_bytes(&input, &[0xff; 64]);
+    run("16");
+    assert_eq!(fs::read_dir(&cache_dir).expect("cache dir").count(), 6);
+}
+
+// ============================================================================
+// UNRAVEL / RERAVEL command tests
+// ============================================================================
+
+#[test]
+fn unravel_and_reravel_round_trip() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("input.png");
+    let strip = td.path().join("strip.png");
+    let rebuilt = td.path().join("rebuilt.png");
+    let original = image::RgbaImage::from_fn(12, 7, |x, y| {
+        image::Rgba([(x * 20) as u8, (y * 30) as u8, ((x + y) * 7) as u8, 255])
+    });
+    original.save(&input).expect("save input");
+
+    for pattern in ["hilbert", "zorder", "onion"] {
+        let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+        cmd.arg("unravel")
+            .arg("-p")
+            .arg(pattern)
+            .arg(&input)
+            .arg(&strip);
+        cmd.assert().success();
+        assert_eq!(read_image(&strip).to_rgba8().dimensions(), (84, 1));
+
+        let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+        cmd.arg("reravel")
+            .arg("-p")
+            .arg(pattern)
+            .arg("--size")
+            .arg("12x7")
+            .arg(&strip)
+            .arg(&rebuilt);
+        cmd.assert().success();
+        assert_eq!(read_image(&rebuilt).to_rgba8(), original, "{pattern}");
+    }
+}
+
+#[test]
+fn reravel_needs_a_size_for_non_square_strips() {
+    let td = tempdir().expect("tmp");
+    let strip = td.path().join("strip.png");
+    image::RgbaImage::new(10, 1)
+        .save(&strip)
+        .expect("save strip");
+    let output = td.path().join("out.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("reravel").arg(&strip).arg(&output);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("reravel")
+        .arg("--size")
+        .arg("3x3")
+        .arg(&strip)
+        .arg(&output);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("reravel")
+        .arg("--size")
+        .arg("5x2")
+        .arg(&strip)
+        .arg(&output);
+    cmd.assert().success();
+}
+
 // ============================================================================
 // Error handling tests
 // ============================================================================
@@ -622,3 +1196,222 @@ fn allrgb_rejects_invalid_colormap() {
         .arg(&output);
     cmd.assert().failure();
 }
+
+#[test]
+fn vis_regions_lists_and_outlines_anomalies() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("mixed.bin");
+    let mut bytes = vec![b'a'; 8192];
+    // A block of every byte value is as high-entropy as a block can be.
+    bytes.extend((0..4096).map(|i| i as u8));
+    bytes.extend(vec![b'a'; 8192]);
+    write_bytes(&input, &bytes);
+    let output = td.path().join("regions.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("vis")
+        .arg("--regions")
+        .arg("-w")
+        .arg("32")
+        .arg(&input)
+        .arg(&output);
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let text = String::from_utf8(stdout).expect("utf8 output");
+
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 4, "three regions then OK: {text}");
+    assert!(lines[0].starts_with("00000000-00002000"));
+    assert!(lines[1].starts_with("00002000-00003000"));
+    assert!(lines[1].ends_with("anomaly"));
+    assert!(!lines[2].ends_with("anomaly"));
+
+    let img = read_image(&output).to_rgba8();
+    assert!(img.pixels().any(|p| p.0 == [0xff, 0x00, 0xff, 0xff]));
+}
+
+#[test]
+fn vis_outlines_sections_from_file() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("input.bin");
+    write_bytes(&input, &vec![0x41u8; 4096]);
+    let sections = td.path().join("sections.json");
+    fs::write(
+        &sections,
+        r#"{"sections": [
+            {"name": "header", "start": 0, "size": 512, "color": "lime"},
+            {"name": "beyond", "start": 8192, "end": 9000}
+        ]}"#,
+    )
+    .expect("write sections");
+    let output = td.path().join("sections.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("vis")
+        .arg("--sections")
+        .arg(&sections)
+        .arg("-w")
+        .arg("64")
+        .arg(&input)
+        .arg(&output);
+    let stderr = cmd.assert().success().get_output().stderr.clone();
+    let warnings = String::from_utf8(stderr).expect("utf8 output");
+    assert!(warnings.contains("'beyond'"), "{warnings}");
+
+    let img = read_image(&output).to_rgba8();
+    let lime = rgba_from_name("lime");
+    assert!(img.pixels().any(|p| p.0 == lime));
+}
+
+#[test]
+fn vis_rejects_unknown_sections_format() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("input.bin");
+    write_bytes(&input, &[1, 2, 3]);
+    let sections = td.path().join("sections.txt");
+    fs::write(&sections, "header 0 512").expect("write sections");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("vis")
+        .arg("--sections")
+        .arg(&sections)
+        .arg(&input)
+        .arg(td.path().join("out.png"));
+    cmd.assert().failure();
+}
+
+#[test]
+fn vis_colors_executable_sections() {
+    let td = tempdir().expect("tmp");
+    let exe = cargo_bin("scurve");
+    let output = td.path().join("sections.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("vis")
+        .arg("--mode")
+        .arg("sections")
+        .arg("-w")
+        .arg("64")
+        .arg(&exe)
+        .arg(&output);
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let text = String::from_utf8(stdout).expect("utf8 output");
+    assert!(
+        text.contains("text"),
+        "legend lists the code section: {text}"
+    );
+
+    let img = read_image(&output);
+    assert_eq!((img.width(), img.height()), (64, 64));
+}
+
+#[test]
+fn vis_sections_mode_rejects_non_executables() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("input.bin");
+    write_bytes(&input, b"not an executable at all");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("vis")
+        .arg("--mode")
+        .arg("sections")
+        .arg(&input)
+        .arg(td.path().join("out.png"));
+    cmd.assert().failure();
+}
+
+// ============================================================================
+// HEXMAP command tests
+// ============================================================================
+
+#[test]
+fn hexmap_annotates_rows_with_coordinates() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("input.bin");
+    let bytes: Vec<u8> = (0u8..32).collect();
+    write_bytes(&input, &bytes);
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("hexmap").arg("-w").arg("8").arg(&input);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let text = String::from_utf8(output).expect("utf8 output");
+
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("00000000"));
+    assert!(lines[0].contains("(0, 0)"));
+    assert!(lines[0].contains("00 01 02 03"));
+    assert!(lines[1].starts_with("00000010"));
+}
+
+#[test]
+fn hexmap_rejects_empty_file() {
+    let td = tempdir().expect("tmp");
+    let input = td.path().join("empty.bin");
+    write_bytes(&input, &[]);
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("hexmap").arg(&input);
+    cmd.assert().failure();
+}
+
+// ============================================================================
+// SCREENSHOT command tests
+// ============================================================================
+
+#[test]
+fn headless_screenshot_renders_without_a_window() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("headless.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("screenshot")
+        .arg("--headless")
+        .arg("-p")
+        .arg("3d")
+        .arg("--size")
+        .arg("320x240")
+        .arg("--scale")
+        .arg("2")
+        .arg("--link")
+        .arg("curve=zorder&size3d=4")
+        .arg(&output);
+    cmd.assert().success();
+
+    let img = read_image(&output).to_rgba8();
+    assert_eq!(img.dimensions(), (640, 480));
+    let first = img.get_pixel(0, 0);
+    assert!(img.pixels().any(|p| p != first), "render is not blank");
+}
+
+#[test]
+fn headless_screenshot_rejects_bad_size() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("headless.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("screenshot")
+        .arg("--headless")
+        .arg("--size")
+        .arg("0x240")
+        .arg(&output);
+    cmd.assert().failure();
+    assert!(!output.exists());
+}
+
+#[test]
+#[allow(deprecated)]
+fn map_accepts_aliases_and_suggests_names() {
+    let td = tempdir().expect("tmp");
+    let output = td.path().join("map.png");
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("map").arg("-d").arg("8").arg("Morton").arg(&output);
+    cmd.assert().success();
+    assert!(output.exists());
+
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.arg("map").arg("hilbret").arg(&output);
+    let assert = cmd.assert().failure();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(stderr.contains("did you mean \"hilbert\""), "{stderr}");
+}
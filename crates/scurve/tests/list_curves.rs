@@ -14,3 +14,18 @@ fn list_curves_prints_expected_entries() {
     assert!(stdout.contains("hilbert"));
     assert!(stdout.contains("Z-order (Morton)"));
 }
+
+#[test]
+#[allow(deprecated)]
+fn list_curves_filters_by_capability() {
+    let mut cmd = Command::cargo_bin("scurve").expect("binary exists");
+    cmd.args(["list-curves", "--dims", "3", "--size", "8", "--continuous"]);
+    let assert = cmd.assert().success();
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let keys: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("- "))
+        .filter_map(|line| line.split(' ').next())
+        .collect();
+    assert_eq!(keys, ["hilbert", "scan", "hairyonion"]);
+}
@@ -3,7 +3,7 @@
 use std::hint::black_box;
 
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
-use spacecurve::curve_from_name;
+use spacecurve::{curve_from_name, hilbert2d, hilbert3d};
 
 /// Benchmark configurations: (curve_name, dimension, size).
 /// For power-of-two curves (hilbert, zorder, hcurve, gray): size must be power of 2.
@@ -109,6 +109,57 @@ fn bench_hilbert_2d_vs_nd(c: &mut Criterion) {
     group.finish();
 }
 
+/// Compare the table-driven `hilbert2d`/`hilbert3d` free functions with the trait path.
+fn bench_hilbert_fast_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hilbert_fast_path");
+
+    for order in [4, 8, 15] {
+        let curve = curve_from_name("hilbert", 2, 1 << order).expect("hilbert 2d");
+        let midpoint = curve.length() / 2;
+        let pt = curve.point(midpoint);
+        let (x, y) = (pt[0], pt[1]);
+
+        group.bench_function(
+            BenchmarkId::new("trait_point", format!("2d-{order}")),
+            |b| b.iter(|| curve.point(black_box(midpoint))),
+        );
+        group.bench_function(BenchmarkId::new("fast_point", format!("2d-{order}")), |b| {
+            b.iter(|| hilbert2d::point(black_box(u64::from(midpoint)), order))
+        });
+        group.bench_function(
+            BenchmarkId::new("trait_index", format!("2d-{order}")),
+            |b| b.iter(|| curve.index(black_box(&pt))),
+        );
+        group.bench_function(BenchmarkId::new("fast_index", format!("2d-{order}")), |b| {
+            b.iter(|| hilbert2d::index(black_box(x), black_box(y), order))
+        });
+    }
+
+    for order in [3, 6, 10] {
+        let curve = curve_from_name("hilbert", 3, 1 << order).expect("hilbert 3d");
+        let midpoint = curve.length() / 2;
+        let pt = curve.point(midpoint);
+        let (x, y, z) = (pt[0], pt[1], pt[2]);
+
+        group.bench_function(
+            BenchmarkId::new("trait_point", format!("3d-{order}")),
+            |b| b.iter(|| curve.point(black_box(midpoint))),
+        );
+        group.bench_function(BenchmarkId::new("fast_point", format!("3d-{order}")), |b| {
+            b.iter(|| hilbert3d::point(black_box(u64::from(midpoint)), order))
+        });
+        group.bench_function(
+            BenchmarkId::new("trait_index", format!("3d-{order}")),
+            |b| b.iter(|| curve.index(black_box(&pt))),
+        );
+        group.bench_function(BenchmarkId::new("fast_index", format!("3d-{order}")), |b| {
+            b.iter(|| hilbert3d::index(black_box(x), black_box(y), black_box(z), order))
+        });
+    }
+
+    group.finish();
+}
+
 /// Benchmark scaling behavior: how performance changes with curve size.
 fn bench_scaling(c: &mut Criterion) {
     let mut group = c.benchmark_group("scaling");
@@ -142,6 +193,7 @@ mod bench_defs {
         bench_point,
         bench_index,
         bench_hilbert_2d_vs_nd,
+        bench_hilbert_fast_path,
         bench_scaling
     );
 }
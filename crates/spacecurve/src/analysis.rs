@@ -0,0 +1,864 @@
+//! Locality and continuity measures for comparing curves on the same grid.
+//!
+//! A curve is judged from both directions: how far apart consecutive indices land on the grid
+//! (steps and discontinuities), and how far apart along the curve the indices of neighbouring
+//! cells are (neighbour gaps). The nearest-neighbour gap keeps only each cell's closest
+//! neighbour along the curve, answering how far apart neighbours usually land rather than how
+//! far the worst pair does; it is exactly 1 for any curve that enters or leaves every cell by a
+//! unit step, and grows only where both steps around a cell jump. Lower is better for every
+//! measure.
+//!
+//! [`dilation`] estimates the standard theoretical measures over arbitrary pairs of indices
+//! rather than neighbours: how much farther apart points land than their index distance allows,
+//! and the reverse. [`axis_sweeps`] looks at one axis at a time, for workloads that read slabs
+//! of cells sharing one coordinate, such as time-major storage. [`spectra`] treats each
+//! coordinate as a signal over the index and reports where its energy lies in frequency, a
+//! signature for comparing curve families.
+//!
+//! [`Shuffle`] visits the cells of a grid in a seeded random order. It keeps no locality at all,
+//! so its measures are the baseline real curves should beat by a wide margin. It is not a
+//! registered curve and only exists for these measures.
+
+use rustfft::{FftPlanner, num_complex::Complex};
+
+use crate::{
+    error::{self, Error},
+    point::Point,
+    spacecurve::SpaceCurve,
+    spec::GridSpec,
+};
+
+/// Locality and continuity measures of one curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// Number of points on the curve.
+    pub length: u32,
+    /// Steps between consecutive indices that do not move to a face-adjacent cell.
+    pub discontinuities: u32,
+    /// Longest Euclidean distance between consecutive indices.
+    pub max_jump: f64,
+    /// Mean Euclidean distance between consecutive indices.
+    pub mean_step: f64,
+    /// Mean difference in index between face-adjacent cells.
+    pub mean_neighbor_gap: f64,
+    /// Largest difference in index between face-adjacent cells.
+    pub max_neighbor_gap: u32,
+    /// For each cell, the smallest difference in index to any face-adjacent cell.
+    pub nearest_neighbor_gap: Spread,
+}
+
+/// Distribution of a per-cell measure over every cell that has one.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Spread {
+    /// Mean value.
+    pub mean: f64,
+    /// Median value.
+    pub p50: u32,
+    /// Value that 90% of cells do not exceed.
+    pub p90: u32,
+    /// Value that 99% of cells do not exceed.
+    pub p99: u32,
+    /// Largest value.
+    pub max: u32,
+}
+
+impl Spread {
+    /// Summarise `values`, using nearest-rank percentiles; all zero when there are none.
+    fn of(mut values: Vec<u32>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_unstable();
+        let total: u64 = values.iter().map(|&v| u64::from(v)).sum();
+        let rank = |p: usize| values[(values.len() * p).div_ceil(100).max(1) - 1];
+        Self {
+            mean: mean(total as f64, values.len() as u64),
+            p50: rank(50),
+            p90: rank(90),
+            p99: rank(99),
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+/// Index differences between face-adjacent cells.
+struct NeighborGaps {
+    /// Sum of the differences over every neighbouring pair.
+    total: u64,
+    /// Number of neighbouring pairs.
+    pairs: u64,
+    /// Largest difference.
+    max: u32,
+    /// Smallest difference from each cell to any neighbour, for cells that have one.
+    nearest: Vec<u32>,
+}
+
+/// Measure `curve` by walking every index once.
+///
+/// Fails when the curve has more than `u32::MAX` points.
+pub fn metrics(curve: &dyn SpaceCurve) -> error::Result<Metrics> {
+    let length = curve.try_length()?;
+    let points: Vec<Point> = (0..length).map(|i| curve.point(i)).collect();
+
+    let mut discontinuities = 0;
+    let mut max_jump: f64 = 0.0;
+    let mut total_step = 0.0;
+    for pair in points.windows(2) {
+        let distance = pair[0].distance(&pair[1]);
+        let manhattan: u32 = pair[0]
+            .iter()
+            .zip(pair[1].iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .sum();
+        if manhattan != 1 {
+            discontinuities += 1;
+        }
+        max_jump = max_jump.max(distance);
+        total_step += distance;
+    }
+    let steps = points.len().saturating_sub(1);
+
+    let gaps = neighbor_gaps(&points, &curve.sides());
+    Ok(Metrics {
+        length,
+        discontinuities,
+        max_jump,
+        mean_step: mean(total_step, steps as u64),
+        mean_neighbor_gap: mean(gaps.total as f64, gaps.pairs),
+        max_neighbor_gap: gaps.max,
+        nearest_neighbor_gap: Spread::of(gaps.nearest),
+    })
+}
+
+/// Index differences between face-adjacent cells of a grid with `sides`, where `points[i]` is
+/// the cell at index `i`.
+fn neighbor_gaps(points: &[Point], sides: &[u32]) -> NeighborGaps {
+    let mut strides = Vec::with_capacity(sides.len());
+    let mut cells = 1;
+    for &side in sides {
+        strides.push(cells);
+        cells *= side as usize;
+    }
+    let linear =
+        |p: &Point| -> usize { p.iter().zip(&strides).map(|(&c, s)| c as usize * s).sum() };
+
+    let mut index_of = vec![u32::MAX; cells];
+    for (i, p) in points.iter().enumerate() {
+        index_of[linear(p)] = i as u32;
+    }
+
+    let (mut total, mut pairs, mut max) = (0, 0, 0);
+    let mut nearest = vec![u32::MAX; points.len()];
+    for (i, p) in points.iter().enumerate() {
+        let cell = linear(p);
+        for (axis, stride) in strides.iter().enumerate() {
+            if p[axis] + 1 == sides[axis] {
+                continue;
+            }
+            let j = index_of[cell + stride];
+            if j == u32::MAX {
+                continue;
+            }
+            let gap = j.abs_diff(i as u32);
+            total += u64::from(gap);
+            pairs += 1;
+            max = max.max(gap);
+            nearest[i] = nearest[i].min(gap);
+            nearest[j as usize] = nearest[j as usize].min(gap);
+        }
+    }
+    nearest.retain(|&gap| gap != u32::MAX);
+    NeighborGaps {
+        total,
+        pairs,
+        max,
+        nearest,
+    }
+}
+
+/// Number of dominant frequencies reported per axis.
+const DOMINANT_FREQUENCIES: usize = 4;
+
+/// Dilation of a curve over pairs of indices, measured exhaustively or on a sample.
+///
+/// For indices `i` and `j` with points `p` and `q` on a `D`-dimensional grid, the spatial
+/// ratio is `|p - q|^D / |i - j|` and the index ratio its inverse. A curve filling space at
+/// the best possible rate keeps both bounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Dilation {
+    /// Number of index pairs measured.
+    pub pairs: u64,
+    /// Whether every pair was measured, so the maxima are exact rather than lower bounds.
+    pub exhaustive: bool,
+    /// Largest spatial ratio: the dilation factor.
+    pub max_spatial: f64,
+    /// Mean spatial ratio.
+    pub mean_spatial: f64,
+    /// Largest index ratio.
+    pub max_index: f64,
+    /// Mean index ratio.
+    pub mean_index: f64,
+    /// Dimension of the grid.
+    pub dimension: u32,
+}
+
+impl Dilation {
+    /// Hölder constant of the curve: the smallest `C` with `|p - q| <= C |i - j|^(1/D)` over
+    /// the measured pairs.
+    pub fn holder_constant(&self) -> f64 {
+        if self.dimension == 0 {
+            return 0.0;
+        }
+        self.max_spatial.powf(1.0 / f64::from(self.dimension))
+    }
+}
+
+/// Estimate the dilation of `curve`, measuring every pair of indices when there are at most
+/// `budget` of them and `budget` sampled pairs otherwise.
+///
+/// Sampled pairs start at a uniformly chosen index and are spaced by offsets drawn evenly on a
+/// logarithmic scale, because the worst spatial ratios come from nearby indices that uniform
+/// pairs almost never hit. Sampled means therefore weight every scale of separation equally.
+/// Sampling is seeded, so repeated calls agree. Fails when the curve has more than `u32::MAX`
+/// points.
+pub fn dilation(curve: &dyn SpaceCurve, budget: u64) -> error::Result<Dilation> {
+    let length = curve.try_length()?;
+    let dimension = curve.dimensions();
+    let all_pairs = u64::from(length) * u64::from(length.saturating_sub(1)) / 2;
+    let exhaustive = all_pairs <= budget;
+
+    let mut result = Dilation {
+        exhaustive,
+        dimension,
+        ..Dilation::default()
+    };
+    let (mut total_spatial, mut total_index) = (0.0, 0.0);
+    let mut measure = |a: &Point, b: &Point, gap: u32| {
+        let spatial = a.distance(b).powi(dimension as i32);
+        let index = f64::from(gap);
+        result.pairs += 1;
+        result.max_spatial = result.max_spatial.max(spatial / index);
+        result.max_index = result.max_index.max(index / spatial);
+        total_spatial += spatial / index;
+        total_index += index / spatial;
+    };
+    if exhaustive {
+        let points: Vec<Point> = (0..length).map(|i| curve.point(i)).collect();
+        for (i, a) in points.iter().enumerate() {
+            for (gap, b) in points[i + 1..].iter().enumerate() {
+                measure(a, b, gap as u32 + 1);
+            }
+        }
+    } else {
+        // Sampled pairs look points up one at a time, so large curves are never held in memory.
+        let mut rng = SplitMix64(0x5eed_cafe);
+        let span = (f64::from(length) - 1.0).ln();
+        for _ in 0..budget {
+            let offset = ((rng.unit() * span).exp() as u32).clamp(1, length - 1);
+            let i = (rng.next() % u64::from(length - offset)) as u32;
+            measure(&curve.point(i), &curve.point(i + offset), offset);
+        }
+    }
+    result.mean_spatial = mean(total_spatial, result.pairs);
+    result.mean_index = mean(total_index, result.pairs);
+    Ok(result)
+}
+
+/// How a curve sweeps one axis of its grid.
+///
+/// A slab is the set of cells sharing one coordinate on the axis. A curve suits workloads that
+/// read whole slabs when it covers each slab in few contiguous index runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisSweep {
+    /// Steps between consecutive indices that change this coordinate.
+    pub moves: u32,
+    /// Times the direction along this axis flips, counting only steps that move along it.
+    pub reversals: u32,
+    /// Contiguous index runs covering each slab, by coordinate.
+    pub slab_runs: Vec<u32>,
+    /// Mean of `slab_runs`.
+    pub mean_slab_runs: f64,
+    /// Mean fraction of the curve between the first and last index of a slab, both included.
+    pub mean_slab_span: f64,
+}
+
+/// Sweep statistics for every axis of `curve`, in axis order.
+///
+/// Fails when the curve has more than `u32::MAX` points.
+pub fn axis_sweeps(curve: &dyn SpaceCurve) -> error::Result<Vec<AxisSweep>> {
+    let length = curve.try_length()?;
+    let points: Vec<Point> = (0..length).map(|i| curve.point(i)).collect();
+    Ok((0..curve.dimensions() as usize)
+        .map(|axis| axis_sweep(&points, axis))
+        .collect())
+}
+
+/// Sweep statistics along `axis`, where `points[i]` is the cell at index `i`.
+fn axis_sweep(points: &[Point], axis: usize) -> AxisSweep {
+    let size = points
+        .iter()
+        .map(|p| p[axis] as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let (mut moves, mut reversals) = (0, 0);
+    let mut heading = None;
+    for pair in points.windows(2) {
+        let (from, to) = (pair[0][axis], pair[1][axis]);
+        if from == to {
+            continue;
+        }
+        moves += 1;
+        let forward = to > from;
+        if heading.is_some_and(|h| h != forward) {
+            reversals += 1;
+        }
+        heading = Some(forward);
+    }
+
+    let mut slab_runs = vec![0; size];
+    let mut spans = vec![(usize::MAX, 0); size];
+    for (i, p) in points.iter().enumerate() {
+        let slab = p[axis] as usize;
+        if i == 0 || points[i - 1][axis] != p[axis] {
+            slab_runs[slab] += 1;
+        }
+        let (first, last) = &mut spans[slab];
+        *first = (*first).min(i);
+        *last = i;
+    }
+    let total_runs: u64 = slab_runs.iter().map(|&r| u64::from(r)).sum();
+    let total_span: usize = spans
+        .iter()
+        .filter(|(first, _)| *first != usize::MAX)
+        .map(|(first, last)| last - first + 1)
+        .sum();
+    AxisSweep {
+        moves,
+        reversals,
+        mean_slab_runs: mean(total_runs as f64, size as u64),
+        mean_slab_span: mean(total_span as f64, size as u64) / points.len().max(1) as f64,
+        slab_runs,
+    }
+}
+
+/// Frequency content of one coordinate sequence along a curve.
+///
+/// Frequency `f` completes `f` cycles over the whole curve. The constant part of the sequence
+/// is removed, so only how the coordinate varies counts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    /// Share of the signal's energy at each frequency from 1 to half the curve length, at
+    /// position `f - 1`; sums to 1 unless the coordinate never changes.
+    pub energy: Vec<f64>,
+    /// Up to four frequencies holding the most energy, strongest first.
+    pub dominant: Vec<usize>,
+    /// Least-squares slope of log energy against log frequency. More negative means energy
+    /// falls off faster, so the coordinate varies more smoothly along the curve.
+    pub decay: f64,
+}
+
+/// Spectrum of every coordinate sequence of `curve`, in axis order.
+///
+/// Each coordinate sequence goes through a fast transform, taking `O(n log n)` time for any
+/// curve length `n`. Fails when the curve has more than `u32::MAX` points.
+pub fn spectra(curve: &dyn SpaceCurve) -> error::Result<Vec<Spectrum>> {
+    let length = curve.try_length()?;
+    let points: Vec<Point> = (0..length).map(|i| curve.point(i)).collect();
+    Ok((0..curve.dimensions() as usize)
+        .map(|axis| {
+            let signal: Vec<f64> = points.iter().map(|p| f64::from(p[axis])).collect();
+            spectrum(&signal)
+        })
+        .collect())
+}
+
+/// Spectrum of `signal` with its mean removed.
+fn spectrum(signal: &[f64]) -> Spectrum {
+    let mean = mean(signal.iter().sum(), signal.len() as u64);
+    let centered: Vec<f64> = signal.iter().map(|v| v - mean).collect();
+    let mut energy = power_spectrum(&centered);
+    let total: f64 = energy.iter().sum();
+    if total > 0.0 {
+        energy.iter_mut().for_each(|e| *e /= total);
+    }
+
+    let mut ranked: Vec<usize> = (1..=energy.len())
+        .filter(|&f| energy[f - 1] > 0.0)
+        .collect();
+    ranked.sort_by(|&a, &b| energy[b - 1].total_cmp(&energy[a - 1]).then(a.cmp(&b)));
+    ranked.truncate(DOMINANT_FREQUENCIES);
+
+    Spectrum {
+        decay: decay(&energy),
+        dominant: ranked,
+        energy,
+    }
+}
+
+/// Squared magnitude of the discrete Fourier transform of `signal` at frequencies 1 to
+/// `n / 2`, at position `f - 1`. The rest mirror these for a real signal, and frequency 0 is
+/// its sum.
+fn power_spectrum(signal: &[f64]) -> Vec<f64> {
+    let n = signal.len();
+    let mut buffer: Vec<Complex<f64>> = signal.iter().map(|&v| Complex::new(v, 0.0)).collect();
+    FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+    buffer
+        .iter()
+        .skip(1)
+        .take(n / 2)
+        .map(Complex::norm_sqr)
+        .collect()
+}
+
+/// Least-squares slope of `ln(energy[f - 1])` against `ln(f)` over frequencies with energy.
+fn decay(energy: &[f64]) -> f64 {
+    let samples: Vec<(f64, f64)> = energy
+        .iter()
+        .enumerate()
+        .filter(|&(_, &e)| e > 0.0)
+        .map(|(i, e)| (((i + 1) as f64).ln(), e.ln()))
+        .collect();
+    let n = samples.len() as f64;
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let (sx, sy) = samples
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    let (mx, my) = (sx / n, sy / n);
+    let (cov, var) = samples.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+        (cov + (x - mx) * (y - my), var + (x - mx) * (x - mx))
+    });
+    if var == 0.0 { 0.0 } else { cov / var }
+}
+
+/// Seed of [`Shuffle::baseline`], shared by every report so their baselines agree.
+pub const BASELINE_SEED: u64 = 0x5eed_ba5e;
+
+/// Most cells a [`Shuffle`] may have, since it keeps the whole order in memory.
+pub const SHUFFLE_MAX_LENGTH: u32 = 1 << 24;
+
+/// A seeded random bijection between indices and cells, the locality baseline for reports.
+///
+/// The same dimension, size and seed always give the same order.
+#[derive(Debug, Clone)]
+pub struct Shuffle {
+    /// Number of axes.
+    dimension: u32,
+    /// Side length of every axis.
+    size: u32,
+    /// Linear cell number visited at each index, with axis 0 varying fastest.
+    cells: Vec<u32>,
+    /// Index at which each linear cell number is visited.
+    indices: Vec<u32>,
+}
+
+impl Shuffle {
+    /// Shuffle the cells of a grid of `dimension` axes of `size` cells with `seed`.
+    ///
+    /// Fails for grids a curve couldn't have, or with more than [`SHUFFLE_MAX_LENGTH`] cells.
+    pub fn new(dimension: u32, size: u32, seed: u64) -> error::Result<Self> {
+        let length = GridSpec::new(dimension, size)?.length();
+        if length > SHUFFLE_MAX_LENGTH {
+            return Err(Error::Size(format!(
+                "shuffle of {length} cells exceeds the limit of {SHUFFLE_MAX_LENGTH}"
+            )));
+        }
+        let mut cells: Vec<u32> = (0..length).collect();
+        let mut rng = SplitMix64(seed);
+        for i in (1..cells.len()).rev() {
+            cells.swap(i, (rng.next() % (i as u64 + 1)) as usize);
+        }
+        let mut indices = vec![0; cells.len()];
+        for (index, &cell) in (0..).zip(&cells) {
+            indices[cell as usize] = index;
+        }
+        Ok(Self {
+            dimension,
+            size,
+            cells,
+            indices,
+        })
+    }
+
+    /// The shuffle every report uses as its baseline, seeded with [`BASELINE_SEED`].
+    pub fn baseline(dimension: u32, size: u32) -> error::Result<Self> {
+        Self::new(dimension, size, BASELINE_SEED)
+    }
+}
+
+impl SpaceCurve for Shuffle {
+    fn name(&self) -> &'static str {
+        "Shuffled"
+    }
+
+    fn info(&self) -> &'static str {
+        "Seeded random order of the cells, with no locality.\n\
+        A worst-case baseline for locality measures, not a usable curve."
+    }
+
+    fn index(&self, p: &Point) -> u32 {
+        let cell = p
+            .iter()
+            .rev()
+            .fold(0, |cell, &coordinate| cell * self.size + coordinate);
+        self.indices[cell as usize]
+    }
+
+    fn point(&self, index: u32) -> Point {
+        let mut cell = self.cells[index as usize];
+        let coordinates: Vec<u32> = (0..self.dimension)
+            .map(|_| {
+                let coordinate = cell % self.size;
+                cell /= self.size;
+                coordinate
+            })
+            .collect();
+        Point::new_with_dimension(self.dimension, coordinates)
+    }
+
+    fn length(&self) -> u32 {
+        self.cells.len() as u32
+    }
+
+    fn dimensions(&self) -> u32 {
+        self.dimension
+    }
+}
+
+/// Small seeded generator for reproducible sampling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Next 64 random bits.
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// `total / count`, or zero when there is nothing to average.
+fn mean(total: f64, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+    use crate::{curve_from_name, curve_from_name_rect};
+
+    fn measure(name: &str, dimension: u32, size: u32) -> Metrics {
+        metrics(curve_from_name(name, dimension, size).unwrap().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn hilbert_is_continuous() {
+        let m = measure("hilbert", 2, 8);
+        assert_eq!(m.length, 64);
+        assert_eq!(m.discontinuities, 0);
+        assert_eq!(m.max_jump, 1.0);
+        assert_eq!(m.mean_step, 1.0);
+    }
+
+    #[test]
+    fn zorder_jumps() {
+        let m = measure("zorder", 2, 4);
+        assert!(m.discontinuities > 0);
+        assert!(m.max_jump > 1.0);
+        assert!(m.mean_step > 1.0);
+    }
+
+    #[test]
+    fn scan_neighbor_gaps() {
+        // A boustrophedon on a 2x2 grid visits (0,0), (1,0), (1,1), (0,1): three neighbour
+        // pairs are consecutive and the fourth closes the loop three indices apart.
+        let m = measure("scan", 2, 2);
+        assert_eq!(m.discontinuities, 0);
+        assert_eq!(m.max_neighbor_gap, 3);
+        assert_eq!(m.mean_neighbor_gap, 1.5);
+    }
+
+    #[test]
+    fn rectangular_neighbor_gaps() {
+        // A 3x5 boustrophedon: rows of three are consecutive, and the cells one row apart are
+        // separated by what remains of one row plus the start of the next.
+        let curve = curve_from_name_rect("scan", &[3, 5]).unwrap();
+        let m = metrics(curve.as_ref()).unwrap();
+        let (mut total, mut pairs, mut max) = (0, 0, 0);
+        for i in 0..curve.length() {
+            let p = curve.point(i);
+            for axis in 0..2 {
+                let mut q = p.to_vec();
+                q[axis] += 1;
+                if q[axis] < curve.sides()[axis] {
+                    let q = Point::new(q);
+                    let gap = curve.index(&q).abs_diff(i);
+                    (total, pairs, max) = (total + gap, pairs + 1, max.max(gap));
+                }
+            }
+        }
+        assert_eq!(m.max_neighbor_gap, max);
+        assert_eq!(m.mean_neighbor_gap, f64::from(total) / f64::from(pairs));
+        assert_eq!(m.max_neighbor_gap, 5);
+    }
+
+    #[test]
+    fn continuous_curves_have_adjacent_neighbors() {
+        // Every cell of a continuous curve is next to the cell before or after it.
+        let n = measure("hilbert", 2, 16).nearest_neighbor_gap;
+        assert_eq!((n.mean, n.p50, n.p90, n.p99, n.max), (1.0, 1, 1, 1, 1));
+    }
+
+    #[test]
+    fn zorder_nearest_neighbor_gap() {
+        // Z-order jumps between every pair of cells, but indices 2k and 2k+1 always share an
+        // edge, so each cell still has a neighbour one index away.
+        let n = measure("zorder", 3, 8).nearest_neighbor_gap;
+        assert_eq!((n.mean, n.max), (1.0, 1));
+    }
+
+    /// A line of four cells visited in the order 0, 2, 1, 3.
+    #[derive(Debug)]
+    struct Shuffled;
+
+    impl Shuffled {
+        const ORDER: [u32; 4] = [0, 2, 1, 3];
+    }
+
+    impl SpaceCurve for Shuffled {
+        fn name(&self) -> &'static str {
+            "Shuffled"
+        }
+
+        fn info(&self) -> &'static str {
+            "Test curve"
+        }
+
+        fn index(&self, p: &Point) -> u32 {
+            Self::ORDER.iter().position(|&c| c == p[0]).unwrap() as u32
+        }
+
+        fn point(&self, index: u32) -> Point {
+            Point::new(vec![Self::ORDER[index as usize]])
+        }
+
+        fn length(&self) -> u32 {
+            4
+        }
+
+        fn dimensions(&self) -> u32 {
+            1
+        }
+    }
+
+    #[test]
+    fn jumps_on_both_sides_widen_nearest_gap() {
+        // Cells 0 and 3 are entered and left by jumps; their only neighbours, cells 1 and 2,
+        // are two indices away. Cells 1 and 2 are consecutive.
+        let n = metrics(&Shuffled).unwrap().nearest_neighbor_gap;
+        assert_eq!((n.mean, n.p50, n.p90, n.max), (1.5, 1, 2, 2));
+    }
+
+    #[test]
+    fn spread_percentiles() {
+        let s = Spread::of((1..=100).collect());
+        assert_eq!(
+            (s.mean, s.p50, s.p90, s.p99, s.max),
+            (50.5, 50, 90, 99, 100)
+        );
+        assert_eq!(Spread::of(Vec::new()), Spread::default());
+    }
+
+    #[test]
+    fn scan_dilation_exhaustive() {
+        // A 2x2 boustrophedon: indices 0 and 3 are neighbours three apart, the worst index
+        // ratio, while no pair lands farther apart than its index distance allows.
+        let d = dilation(curve_from_name("scan", 2, 2).unwrap().as_ref(), 100).unwrap();
+        assert!(d.exhaustive);
+        assert_eq!(d.pairs, 6);
+        assert!((d.max_spatial - 1.0).abs() < 1e-9);
+        assert!((d.max_index - 3.0).abs() < 1e-9);
+        assert!((d.holder_constant() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn dilation_sampling_is_reproducible_and_bounded() {
+        let curve = curve_from_name("hilbert", 2, 32).unwrap();
+        let exact = dilation(curve.as_ref(), u64::MAX).unwrap();
+        let sampled = dilation(curve.as_ref(), 20_000).unwrap();
+        assert!(exact.exhaustive);
+        assert!(!sampled.exhaustive);
+        assert_eq!(sampled.pairs, 20_000);
+        assert_eq!(sampled, dilation(curve.as_ref(), 20_000).unwrap());
+        assert!(sampled.max_spatial <= exact.max_spatial);
+        assert!(sampled.max_spatial > exact.max_spatial * 0.5);
+        // The Hilbert curve's dilation factor is known to be 6 in the limit of fine grids.
+        assert!(exact.max_spatial > 4.0 && exact.max_spatial <= 6.0);
+    }
+
+    #[test]
+    fn hilbert_dilates_less_than_zorder() {
+        let hilbert = dilation(
+            curve_from_name("hilbert", 2, 16).unwrap().as_ref(),
+            u64::MAX,
+        )
+        .unwrap();
+        let zorder =
+            dilation(curve_from_name("zorder", 2, 16).unwrap().as_ref(), u64::MAX).unwrap();
+        assert!(hilbert.max_spatial < zorder.max_spatial);
+    }
+
+    #[test]
+    fn scan_sweeps_rows_then_columns() {
+        // The boustrophedon runs along x, flipping direction on every row, and climbs y once.
+        let sweeps = axis_sweeps(curve_from_name("scan", 2, 4).unwrap().as_ref()).unwrap();
+        let [x, y] = sweeps.as_slice() else {
+            panic!("expected two axes");
+        };
+        assert_eq!((x.moves, x.reversals), (12, 3));
+        assert_eq!((y.moves, y.reversals), (3, 0));
+        // Each row is one run. Every row crosses every column, but the turns at the ends of
+        // the rows join two crossings of an end column into one run.
+        assert_eq!(y.slab_runs, vec![1, 1, 1, 1]);
+        assert_eq!(x.slab_runs, vec![3, 4, 4, 2]);
+        assert_eq!(x.mean_slab_runs, 3.25);
+        assert_eq!(y.mean_slab_span, 0.25);
+        assert_eq!(x.mean_slab_span, (16.0 + 14.0 + 12.0 + 10.0) / 4.0 / 16.0);
+    }
+
+    #[test]
+    fn hilbert_sweeps_axes_alike() {
+        let sweeps = axis_sweeps(curve_from_name("hilbert", 2, 16).unwrap().as_ref()).unwrap();
+        assert_eq!(sweeps.len(), 2);
+        assert_eq!(sweeps[0].moves + sweeps[1].moves, 255);
+        for sweep in &sweeps {
+            assert_eq!(sweep.slab_runs.len(), 16);
+            assert!(sweep.mean_slab_runs > 1.0);
+        }
+    }
+
+    #[test]
+    fn power_spectrum_matches_direct_transform() {
+        for n in [1, 2, 15, 16, 25] {
+            let signal: Vec<f64> = (0..n).map(|i| f64::from((i * 7 + 3) % 11)).collect();
+            let power = power_spectrum(&signal);
+            assert_eq!(power.len(), n as usize / 2);
+            for (f, fast) in (1..).zip(&power) {
+                let (re, im) = (0..n).fold((0.0, 0.0), |(re, im), k| {
+                    let angle = TAU * f64::from(f * k % n) / f64::from(n);
+                    let v = signal[k as usize];
+                    (re + v * angle.cos(), im - v * angle.sin())
+                });
+                let direct = re * re + im * im;
+                assert!(
+                    (fast - direct).abs() < 1e-6,
+                    "n {n}, f {f}: {fast} != {direct}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scan_spectrum_peaks() {
+        // On an 8x8 boustrophedon, x sweeps back and forth once every two rows: 4 cycles over
+        // the 64 points. y climbs once, a single slow cycle.
+        let spectra = spectra(curve_from_name("scan", 2, 8).unwrap().as_ref()).unwrap();
+        assert_eq!(spectra[0].dominant[0], 4);
+        assert_eq!(spectra[1].dominant[0], 1);
+        for spectrum in &spectra {
+            assert_eq!(spectrum.energy.len(), 32);
+            assert!((spectrum.energy.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(spectrum.dominant.len() <= 4);
+            assert!(spectrum.decay < 0.0);
+        }
+    }
+
+    #[test]
+    fn onion_circles_where_hilbert_drifts() {
+        // Hilbert's coordinates drift across the grid once, while the onion circles its shells
+        // several times, moving the strongest frequency up.
+        let hilbert = spectra(curve_from_name("hilbert", 2, 16).unwrap().as_ref()).unwrap();
+        let onion = spectra(curve_from_name("onion", 2, 16).unwrap().as_ref()).unwrap();
+        assert_eq!(hilbert[0].dominant[0], 1);
+        assert!(onion[0].dominant[0] > 2);
+    }
+
+    #[test]
+    fn spectra_of_odd_lengths() {
+        let spectra = spectra(curve_from_name("onion", 2, 5).unwrap().as_ref()).unwrap();
+        assert_eq!(spectra.len(), 2);
+        assert_eq!(spectra[0].energy.len(), 12);
+        assert!((spectra[0].energy.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shuffle_is_a_seeded_bijection() {
+        let shuffle = Shuffle::baseline(3, 5).unwrap();
+        let mut seen = [false; 125];
+        for i in 0..shuffle.length() {
+            let p = shuffle.point(i);
+            assert_eq!(shuffle.index(&p), i);
+            let cell = (p[0] + 5 * p[1] + 25 * p[2]) as usize;
+            assert!(!seen[cell]);
+            seen[cell] = true;
+        }
+        let again = Shuffle::baseline(3, 5).unwrap();
+        let other = Shuffle::new(3, 5, BASELINE_SEED + 1).unwrap();
+        let order = |s: &Shuffle| (0..s.length()).map(|i| s.point(i)).collect::<Vec<_>>();
+        assert_eq!(order(&shuffle), order(&again));
+        assert_ne!(order(&shuffle), order(&other));
+        assert!(Shuffle::baseline(2, 1 << 13).is_err());
+        assert!(Shuffle::baseline(0, 4).is_err());
+    }
+
+    #[test]
+    fn shuffle_is_worse_than_every_curve() {
+        let baseline = metrics(&Shuffle::baseline(2, 16).unwrap()).unwrap();
+        for name in ["hilbert", "zorder", "scan", "gray"] {
+            let m = measure(name, 2, 16);
+            assert!(m.mean_neighbor_gap < baseline.mean_neighbor_gap, "{name}");
+            assert!(m.discontinuities < baseline.discontinuities, "{name}");
+        }
+    }
+
+    #[test]
+    fn single_point() {
+        let m = measure("scan", 1, 1);
+        assert_eq!(m.length, 1);
+        assert_eq!(m.discontinuities, 0);
+        assert_eq!(m.mean_step, 0.0);
+        assert_eq!(m.mean_neighbor_gap, 0.0);
+        assert_eq!(m.nearest_neighbor_gap, Spread::default());
+        let d = dilation(curve_from_name("scan", 1, 1).unwrap().as_ref(), 10).unwrap();
+        assert_eq!((d.pairs, d.max_spatial), (0, 0.0));
+        let sweeps = axis_sweeps(curve_from_name("scan", 1, 1).unwrap().as_ref()).unwrap();
+        assert_eq!(sweeps[0].slab_runs, vec![1]);
+        assert_eq!((sweeps[0].moves, sweeps[0].mean_slab_span), (0, 1.0));
+        let spectra = spectra(curve_from_name("scan", 1, 1).unwrap().as_ref()).unwrap();
+        assert!(spectra[0].energy.is_empty() && spectra[0].dominant.is_empty());
+    }
+
+    #[test]
+    fn rejects_wide_curves() {
+        let wide = curve_from_name("hilbert", 2, 1 << 16).unwrap();
+        let wide = wide.as_ref();
+        assert!(matches!(metrics(wide), Err(Error::Size(_))));
+        assert!(matches!(dilation(wide, 10), Err(Error::Size(_))));
+        assert!(matches!(axis_sweeps(wide), Err(Error::Size(_))));
+        assert!(matches!(spectra(wide), Err(Error::Size(_))));
+    }
+}
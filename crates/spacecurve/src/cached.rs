@@ -0,0 +1,299 @@
+//! Memoising recent conversions of curves with expensive mappings.
+//!
+//! Rendering and analysis tend to query points near the ones they just asked about, so a small
+//! cache in front of a recursive mapping such as the N-D onion answers most lookups without
+//! recomputing them. [`Cached`] keeps one least-recently-used cache for each direction behind
+//! a mutex, so the wrapper stays `Send + Sync` like every other curve.
+
+use std::{
+    num::NonZeroUsize,
+    ops::Range,
+    sync::{Mutex, MutexGuard},
+};
+
+use lru::LruCache;
+
+use crate::{error, point::Point, spacecurve::SpaceCurve};
+
+/// How often a [`Cached`] curve found a conversion in its caches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from a cache.
+    pub hits: u64,
+    /// Lookups passed on to the wrapped curve.
+    pub misses: u64,
+}
+
+/// The mutable state of a [`Cached`] curve.
+#[derive(Debug)]
+struct Caches {
+    /// Recent index-to-point conversions, or `None` when nothing is kept.
+    points: Option<LruCache<u32, Point>>,
+    /// Recent point-to-index conversions, or `None` when nothing is kept.
+    indices: Option<LruCache<Point, u32>>,
+    /// Hit and miss counts since construction or the last clear.
+    stats: CacheStats,
+}
+
+impl Caches {
+    /// The cached point at `index`, counting the lookup.
+    fn point(&mut self, index: u32) -> Option<Point> {
+        let found = self
+            .points
+            .as_mut()
+            .and_then(|cache| cache.get(&index).cloned());
+        self.count(found.is_some());
+        found
+    }
+
+    /// The cached index of `point`, counting the lookup.
+    fn index(&mut self, point: &Point) -> Option<u32> {
+        let found = self
+            .indices
+            .as_mut()
+            .and_then(|cache| cache.get(point).copied());
+        self.count(found.is_some());
+        found
+    }
+
+    /// Remember that `index` maps to `point`.
+    fn store_point(&mut self, index: u32, point: &Point) {
+        if let Some(cache) = &mut self.points {
+            cache.put(index, point.clone());
+        }
+    }
+
+    /// Remember that `point` maps to `index`.
+    fn store_index(&mut self, point: &Point, index: u32) {
+        if let Some(cache) = &mut self.indices {
+            cache.put(point.clone(), index);
+        }
+    }
+
+    /// Record a lookup as a hit or a miss.
+    fn count(&mut self, hit: bool) {
+        if hit {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+    }
+}
+
+/// A curve remembering its most recent index↔point conversions.
+#[derive(Debug)]
+pub struct Cached<C> {
+    /// The wrapped curve.
+    curve: C,
+    /// Caches for both directions.
+    caches: Mutex<Caches>,
+}
+
+impl<C: SpaceCurve> Cached<C> {
+    /// Wrap `curve`, remembering up to `capacity` conversions in each direction.
+    pub fn new(curve: C, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity);
+        Self {
+            curve,
+            caches: Mutex::new(Caches {
+                points: capacity.map(LruCache::new),
+                indices: capacity.map(LruCache::new),
+                stats: CacheStats::default(),
+            }),
+        }
+    }
+
+    /// The wrapped curve.
+    pub fn inner(&self) -> &C {
+        &self.curve
+    }
+
+    /// Unwrap the curve, discarding the caches.
+    pub fn into_inner(self) -> C {
+        self.curve
+    }
+
+    /// Hit and miss counts since construction or the last [`Self::clear`].
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Forget every cached conversion and reset the counts.
+    pub fn clear(&self) {
+        let mut caches = self.lock();
+        if let Some(cache) = &mut caches.points {
+            cache.clear();
+        }
+        if let Some(cache) = &mut caches.indices {
+            cache.clear();
+        }
+        caches.stats = CacheStats::default();
+    }
+
+    /// The caches, recovered even if another thread panicked while holding them.
+    fn lock(&self) -> MutexGuard<'_, Caches> {
+        self.caches
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<C: SpaceCurve> SpaceCurve for Cached<C> {
+    fn name(&self) -> &'static str {
+        self.curve.name()
+    }
+
+    fn info(&self) -> &'static str {
+        self.curve.info()
+    }
+
+    fn index(&self, p: &Point) -> u32 {
+        if let Some(index) = self.lock().index(p) {
+            return index;
+        }
+        // Computed without holding the lock, so other threads are not held up.
+        let index = self.curve.index(p);
+        self.lock().store_index(p, index);
+        index
+    }
+
+    fn point(&self, index: u32) -> Point {
+        if let Some(point) = self.lock().point(index) {
+            return point;
+        }
+        let point = self.curve.point(index);
+        self.lock().store_point(index, &point);
+        point
+    }
+
+    fn length(&self) -> u32 {
+        self.curve.length()
+    }
+
+    fn dimensions(&self) -> u32 {
+        self.curve.dimensions()
+    }
+
+    // The caches are keyed by `u32` indices, so 64-bit lookups go straight to the curve.
+    fn index_u64(&self, p: &Point) -> u64 {
+        self.curve.index_u64(p)
+    }
+
+    fn point_u64(&self, index: u64) -> error::Result<Point> {
+        self.curve.point_u64(index)
+    }
+
+    fn length_u64(&self) -> u64 {
+        self.curve.length_u64()
+    }
+
+    fn sides(&self) -> Vec<u32> {
+        self.curve.sides()
+    }
+
+    fn nested_blocks(&self) -> bool {
+        self.curve.nested_blocks()
+    }
+
+    fn next_point(&self, p: &Point) -> Option<Point> {
+        self.curve.next_point(p)
+    }
+
+    fn prev_point(&self, p: &Point) -> Option<Point> {
+        self.curve.prev_point(p)
+    }
+
+    // Sequential walks and bulk conversions would only flush the cache, so they go straight
+    // to the curve.
+    fn index_many_into(&self, points: &[Point], out: &mut [u32]) {
+        self.curve.index_many_into(points, out);
+    }
+
+    fn point_many_into(&self, indices: &[u32], out: &mut [Point]) {
+        self.curve.point_many_into(indices, out);
+    }
+
+    fn iter_range(&self, range: Range<u32>) -> Box<dyn Iterator<Item = Point> + '_> {
+        self.curve.iter_range(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::onion::OnionCurve;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cached = Cached::new(OnionCurve::new(2, 8).unwrap(), 2);
+        cached.point(1);
+        cached.point(2);
+        cached.point(1);
+        cached.point(3);
+        let misses = cached.stats().misses;
+        cached.point(1);
+        cached.point(3);
+        assert_eq!(cached.stats().misses, misses);
+        cached.point(2);
+        assert_eq!(cached.stats().misses, misses + 1);
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let cached = Cached::new(OnionCurve::new(2, 8).unwrap(), 0);
+        for _ in 0..2 {
+            cached.index(&cached.point(5));
+        }
+        assert_eq!(cached.stats(), CacheStats { hits: 0, misses: 4 });
+    }
+
+    #[test]
+    fn answers_match_wrapped_curve() {
+        let curve = OnionCurve::new(3, 6).unwrap();
+        let cached = Cached::new(OnionCurve::new(3, 6).unwrap(), 16);
+        for pass in 0..2 {
+            for index in 0..curve.length() {
+                let point = curve.point(index);
+                assert_eq!(cached.point(index), point, "pass {pass}");
+                assert_eq!(cached.index(&point), index, "pass {pass}");
+            }
+        }
+    }
+
+    #[test]
+    fn batches_bypass_the_caches() {
+        let cached = Cached::new(OnionCurve::new(2, 8).unwrap(), 4);
+        let points = cached.point_many(&[3, 1, 4]);
+        assert_eq!(
+            points,
+            [
+                cached.inner().point(3),
+                cached.inner().point(1),
+                cached.inner().point(4)
+            ]
+        );
+        assert_eq!(cached.index_many(&points), [3, 1, 4]);
+        assert_eq!(cached.stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn repeated_queries_hit() {
+        let cached = Cached::new(OnionCurve::new(2, 8).unwrap(), 4);
+        for _ in 0..3 {
+            for index in 0..4 {
+                cached.index(&cached.point(index));
+            }
+        }
+        assert_eq!(
+            cached.stats(),
+            CacheStats {
+                hits: 16,
+                misses: 8
+            }
+        );
+        cached.clear();
+        assert_eq!(cached.stats(), CacheStats::default());
+        cached.point(0);
+        assert_eq!(cached.stats().misses, 1);
+    }
+}
@@ -0,0 +1,172 @@
+//! Indices that remember which curve they belong to.
+//!
+//! A bare `u32` from a Hilbert curve compares happily with one from a Z-order curve, or from a
+//! Hilbert curve on a different grid, and nothing notices. A [`CurveIndex`] carries the
+//! [`fingerprint`] of its curve next to the index, so it cannot be confused with a plain
+//! integer at compile time, and indices loaded from storage can be checked against the curve
+//! they are about to be used with.
+//!
+//! The byte form is the fingerprint followed by the index, both big-endian, so sorting the
+//! bytes (as key-value stores do) sorts by curve and then in curve order, matching [`Ord`].
+
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use crate::{
+    error::{Error, Result},
+    fingerprint::fingerprint,
+    spacecurve::SpaceCurve,
+};
+
+/// An index on one curve, tagged with that curve's [`fingerprint`].
+///
+/// Indices order by fingerprint first, so a collection mixing curves groups each curve's
+/// indices together, in curve order. Use [`CurveIndex::try_cmp`] to compare indices that must
+/// come from the same curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CurveIndex {
+    /// Fingerprint of the curve the index belongs to.
+    fingerprint: u64,
+    /// Position along the curve.
+    index: u32,
+}
+
+impl CurveIndex {
+    /// Length of the byte form.
+    pub const BYTES: usize = 12;
+
+    /// Tag `index` with the curve `fingerprint`.
+    pub fn new(fingerprint: u64, index: u32) -> Self {
+        Self { fingerprint, index }
+    }
+
+    /// Tag `index` with the fingerprint of `curve`.
+    ///
+    /// This walks the whole curve; when tagging many indices, compute the fingerprint once and
+    /// use [`CurveIndex::new`]. Fails as [`fingerprint`] does.
+    pub fn of(curve: &dyn SpaceCurve, index: u32) -> Result<Self> {
+        Ok(Self::new(fingerprint(curve)?, index))
+    }
+
+    /// Fingerprint of the curve the index belongs to.
+    pub fn fingerprint(self) -> u64 {
+        self.fingerprint
+    }
+
+    /// The raw index, without checking which curve it belongs to.
+    pub fn index(self) -> u32 {
+        self.index
+    }
+
+    /// The raw index, provided it belongs to the curve with `fingerprint`.
+    pub fn index_on(self, fingerprint: u64) -> Result<u32> {
+        if self.fingerprint == fingerprint {
+            Ok(self.index)
+        } else {
+            Err(Error::Mismatch(format!(
+                "index {self} belongs to curve {:016x}, not {fingerprint:016x}",
+                self.fingerprint
+            )))
+        }
+    }
+
+    /// Order two indices along their curve, failing if they belong to different curves.
+    pub fn try_cmp(self, other: Self) -> Result<Ordering> {
+        Ok(self.index.cmp(&other.index_on(self.fingerprint)?))
+    }
+
+    /// The byte form: fingerprint then index, both big-endian.
+    pub fn to_bytes(self) -> [u8; Self::BYTES] {
+        let mut bytes = [0; Self::BYTES];
+        bytes[..8].copy_from_slice(&self.fingerprint.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.index.to_be_bytes());
+        bytes
+    }
+
+    /// Read an index from its byte form.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; Self::BYTES] = bytes.try_into().map_err(|_| {
+            Error::Size(format!(
+                "curve index needs {} bytes, got {}",
+                Self::BYTES,
+                bytes.len()
+            ))
+        })?;
+        let (fingerprint, index) = bytes.split_at(8);
+        Ok(Self {
+            fingerprint: u64::from_be_bytes(fingerprint.try_into().expect("8 bytes")),
+            index: u32::from_be_bytes(index.try_into().expect("4 bytes")),
+        })
+    }
+}
+
+/// Formats as `index@fingerprint`, with the fingerprint in 16 hex digits.
+impl fmt::Display for CurveIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{:016x}", self.index, self.fingerprint)
+    }
+}
+
+/// Parses the [`Display`](fmt::Display) form, `index@fingerprint`.
+impl FromStr for CurveIndex {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bad = || Error::Other(format!("expected INDEX@FINGERPRINT, got '{s}'"));
+        let (index, fingerprint) = s.split_once('@').ok_or_else(bad)?;
+        Ok(Self {
+            fingerprint: u64::from_str_radix(fingerprint, 16).map_err(|_| bad())?,
+            index: index.parse().map_err(|_| bad())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve_from_name;
+
+    #[test]
+    fn rejects_indices_from_other_curves() {
+        let hilbert = curve_from_name("hilbert", 2, 8).unwrap();
+        let zorder = curve_from_name("zorder", 2, 8).unwrap();
+        let (h, z) = (
+            fingerprint(hilbert.as_ref()).unwrap(),
+            fingerprint(zorder.as_ref()).unwrap(),
+        );
+
+        let a = CurveIndex::new(h, 3);
+        assert_eq!(a.index_on(h).unwrap(), 3);
+        assert!(a.index_on(z).is_err());
+        assert_eq!(a.try_cmp(CurveIndex::new(h, 9)).unwrap(), Ordering::Less);
+        assert!(a.try_cmp(CurveIndex::new(z, 9)).is_err());
+        assert_eq!(CurveIndex::of(hilbert.as_ref(), 3).unwrap(), a);
+    }
+
+    #[test]
+    fn byte_order_matches_ord() {
+        let mut indices = vec![
+            CurveIndex::new(2, 0),
+            CurveIndex::new(1, 0x100),
+            CurveIndex::new(1, 0xff),
+            CurveIndex::new(u64::MAX, 7),
+        ];
+        let mut bytes: Vec<_> = indices.iter().map(|i| i.to_bytes()).collect();
+        indices.sort();
+        bytes.sort();
+        let decoded: Vec<_> = bytes
+            .iter()
+            .map(|b| CurveIndex::from_bytes(b).unwrap())
+            .collect();
+        assert_eq!(decoded, indices);
+        assert!(CurveIndex::from_bytes(&[0; 11]).is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let index = CurveIndex::new(0xdead_beef, 42);
+        assert_eq!(index.to_string(), "42@00000000deadbeef");
+        assert_eq!(index.to_string().parse::<CurveIndex>().unwrap(), index);
+        assert!("42".parse::<CurveIndex>().is_err());
+        assert!("x@1".parse::<CurveIndex>().is_err());
+    }
+}
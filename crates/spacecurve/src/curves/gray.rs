@@ -1,4 +1,9 @@
-use crate::{error, ops, point::Point, spacecurve::SpaceCurve, spec::GridSpec};
+use crate::{
+    error, ops,
+    point::Point,
+    spacecurve::SpaceCurve,
+    spec::{GridSpec, INDEX_BITS},
+};
 
 /// Gray-code based hypercube traversal (BRGC).
 #[derive(Debug)]
@@ -21,7 +26,7 @@ impl Gray {
     /// the hypercube.
     pub fn from_dimensions(dimension: u32, size: u32) -> error::Result<Self> {
         let spec = GridSpec::power_of_two(dimension, size)?;
-        spec.require_index_bits_lt(32)?;
+        spec.require_index_bits_lt(INDEX_BITS)?;
 
         Ok(Self {
             dimension: spec.dimension(),
@@ -74,6 +79,16 @@ impl SpaceCurve for Gray {
         debug_assert!(binary_index < self.length, "index conversion overflowed");
         binary_index
     }
+
+    fn next_point(&self, p: &Point) -> Option<Point> {
+        let mut next = p.clone();
+        ops::gray_step(&mut next.0, self.bits_per_axis, true).then_some(next)
+    }
+
+    fn prev_point(&self, p: &Point) -> Option<Point> {
+        let mut prev = p.clone();
+        ops::gray_step(&mut prev.0, self.bits_per_axis, false).then_some(prev)
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,5 @@
 use crate::{
-    curves::onion::{onion_index_2d, onion_point_2d},
+    curves::onion::{onion_index_2d, onion_point_2d, shell_of},
     error,
     point::Point,
     spacecurve::SpaceCurve,
@@ -29,6 +29,31 @@ impl HairyOnionCurve {
             length: spec.length(),
         })
     }
+
+    /// Number of L∞ shells, from the outer surface to the centre.
+    pub fn shells(&self) -> u32 {
+        self.side_length.div_ceil(2)
+    }
+
+    /// Shell holding the point at `index`, counted from the outside.
+    pub fn shell_of_index(&self, index: u32) -> u32 {
+        self.shell_of_point(&self.point(index))
+    }
+
+    /// Shell holding `p`, counted from the outside.
+    pub fn shell_of_point(&self, p: &Point) -> u32 {
+        shell_of(p, self.side_length)
+    }
+
+    /// Points of shell `level` in curve order.
+    ///
+    /// Above two dimensions the tiled spirals cross between shells, so unlike the Onion curve a
+    /// shell is not one contiguous index range and this walks the whole curve.
+    pub fn shell_points(&self, level: u32) -> impl Iterator<Item = Point> + '_ {
+        debug_assert!(level < self.shells(), "shell out of bounds");
+        self.iter_range(0..self.length)
+            .filter(move |p| self.shell_of_point(p) == level)
+    }
 }
 
 impl SpaceCurve for HairyOnionCurve {
@@ -177,6 +202,21 @@ mod tests {
         assert_eq!(c.length(), 9);
     }
 
+    #[test]
+    fn shell_points_partition_the_grid() {
+        for (dim, size) in [(2, 5), (3, 4), (3, 5)] {
+            let curve = HairyOnionCurve::new(dim, size).unwrap();
+            let mut seen = 0;
+            for level in 0..curve.shells() {
+                for p in curve.shell_points(level) {
+                    assert_eq!(curve.shell_of_index(curve.index(&p)), level);
+                    seen += 1;
+                }
+            }
+            assert_eq!(seen, curve.length());
+        }
+    }
+
     #[test]
     fn roundtrip_dims_2_to_4_sizes_upto_8() {
         for dim in 2..=4 {
@@ -1,10 +1,13 @@
+use std::ops::Range;
+
 use smallvec::SmallVec;
 
 use crate::{
     curves::{hilbert2, hilbertn},
     error, point,
-    spacecurve::SpaceCurve,
-    spec::GridSpec,
+    spacecurve::{SpaceCurve, out_of_range},
+    spec::{GridSpec, INDEX_BITS, WIDE_INDEX_BITS},
+    table::{StateTable, Walk},
 };
 
 /// Internal dispatcher selecting the 2D or N-D Hilbert core.
@@ -18,15 +21,31 @@ enum HilbertImpl {
 
 impl HilbertImpl {
     /// Compute a Hilbert index using the chosen implementation.
-    fn index(&self, dimension: u32, order: u32, point: &[u32]) -> u32 {
+    fn index(&self, dimension: u32, order: u32, point: &[u32]) -> u64 {
         match self {
             Self::TwoD => hilbert2::hilbert_index(order, point),
             Self::Nd => hilbertn::hilbert_index(dimension, order, point),
         }
     }
 
+    /// Advance the state machine by one `word`, returning the child cell and next state.
+    fn step(&self, dimension: u32, state: (u32, u32), word: u32) -> (u32, (u32, u32)) {
+        match self {
+            Self::TwoD => hilbert2::step(state, word),
+            Self::Nd => hilbertn::step(dimension, state, word),
+        }
+    }
+
+    /// Invert one state machine step, returning the `word` that visits `label` and the next state.
+    fn unstep(&self, dimension: u32, state: (u32, u32), label: u32) -> (u32, (u32, u32)) {
+        match self {
+            Self::TwoD => hilbert2::unstep(state, label),
+            Self::Nd => hilbertn::unstep(dimension, state, label),
+        }
+    }
+
     /// Compute coordinates from an index using the chosen implementation.
-    fn point(&self, dimension: u32, order: u32, index: u32) -> SmallVec<[u32; 4]> {
+    fn point(&self, dimension: u32, order: u32, index: u64) -> SmallVec<[u32; 4]> {
         match self {
             Self::TwoD => hilbert2::hilbert_point(order, index),
             Self::Nd => hilbertn::hilbert_point(dimension, order, index),
@@ -44,7 +63,7 @@ pub struct Hilbert {
     pub dimension: u32,
     /// Cached total number of points (`2^(order * dimension)`), computed once
     /// at construction with checked math to avoid overflow in debug/release.
-    length: u32,
+    length: u64,
     /// Chooses between the 2D fast path and the generic N-D logic.
     mapper: HilbertImpl,
 }
@@ -55,18 +74,100 @@ impl Hilbert {
     /// a power of two (`size == 2^order`) or the result is an error.
     pub fn from_dimensions(dimension: u32, size: u32) -> error::Result<Self> {
         let spec = GridSpec::power_of_two(dimension, size)?;
-        spec.require_index_bits_lt(32)?;
+        spec.require_index_bits_lt(INDEX_BITS)?;
+        Ok(Self::from_spec(&spec))
+    }
 
-        Ok(Self {
+    /// Construct a Hilbert curve addressed with 64-bit indices, allowing grids of up to 63
+    /// index bits, such as order 20 in three dimensions.
+    ///
+    /// Each level of the curve names its cell with a `u32` word, so the dimension must be
+    /// below 32. Use [`SpaceCurve::index_u64`] and [`SpaceCurve::point_u64`] to reach the
+    /// whole grid.
+    pub fn from_dimensions_u64(dimension: u32, size: u32) -> error::Result<Self> {
+        let spec = GridSpec::power_of_two_u64(dimension, size)?;
+        if dimension >= u32::BITS {
+            return Err(error::Error::Shape("dimension must be < 32".to_string()));
+        }
+        spec.require_index_bits_lt(WIDE_INDEX_BITS)?;
+        Ok(Self::from_spec(&spec))
+    }
+
+    /// Build the curve for a validated power-of-two `spec`.
+    fn from_spec(spec: &GridSpec) -> Self {
+        Self {
             dimension: spec.dimension(),
             order: spec.order().unwrap(),
-            length: spec.length(),
+            length: spec.length_u64(),
             mapper: if spec.dimension() == 2 {
                 HilbertImpl::TwoD
             } else {
                 HilbertImpl::Nd
             },
-        })
+        }
+    }
+
+    /// The state table walking this curve.
+    pub fn state_table(&self) -> StateTable {
+        StateTable::explore(self.dimension, self.order, (0, 0), self.cell_step())
+    }
+
+    /// The point one step forward or back along the curve from `p`.
+    ///
+    /// One pass down the levels recovers each level's word and sub-curve state. Only the levels
+    /// from the lowest word that can move downwards are then walked again, which for all but
+    /// one in `2^dimension` steps is just the last level.
+    fn adjacent(&self, p: &point::Point, forward: bool) -> Option<point::Point> {
+        let dimension = self.dimension;
+        let last = (1u32 << dimension) - 1;
+        let mut path: SmallVec<[(u32, (u32, u32)); 16]> = SmallVec::new();
+        let mut state = (0, 0);
+        for level in (0..self.order).rev() {
+            // Labels put coordinate 0 in the most significant bit.
+            let label = (0..dimension)
+                .map(|axis| ((p[axis as usize] >> level) & 1) << (dimension - 1 - axis))
+                .sum();
+            let (word, next) = self.mapper.unstep(dimension, state, label);
+            path.push((word, state));
+            state = next;
+        }
+
+        let (limit, restart) = if forward { (last, 0) } else { (0, last) };
+        let pivot = path.iter().rposition(|&(word, _)| word != limit)?;
+        let (word, mut state) = path[pivot];
+        let mut word = if forward { word + 1 } else { word - 1 };
+        let mut coords = p.0.clone();
+        for level in (0..self.order - pivot as u32).rev() {
+            let (label, next) = self.mapper.step(dimension, state, word);
+            for (axis, coord) in coords.iter_mut().enumerate() {
+                let bit = (label >> (dimension - 1 - axis as u32)) & 1;
+                *coord = (*coord & !(1 << level)) | (bit << level);
+            }
+            state = next;
+            word = restart;
+        }
+        Some(point::Point::new_with_dimension(dimension, coords))
+    }
+
+    /// The point at `index`, which must be below the curve's length.
+    fn locate(&self, index: u64) -> point::Point {
+        point::Point::new_with_dimension(
+            self.dimension,
+            self.mapper.point(self.dimension, self.order, index),
+        )
+    }
+
+    /// One level of the state machine, naming the child cell with coordinate `c` at bit `c`.
+    fn cell_step(&self) -> impl Fn((u32, u32), u32) -> (u32, (u32, u32)) + '_ {
+        let dimension = self.dimension;
+        move |state, word| {
+            let (label, next) = self.mapper.step(dimension, state, word);
+            // Steps put coordinate 0 in the most significant bit of the label.
+            let cell = (0..dimension)
+                .map(|axis| ((label >> (dimension - 1 - axis)) & 1) << axis)
+                .sum();
+            (cell, next)
+        }
     }
 }
 
@@ -81,27 +182,58 @@ impl SpaceCurve for Hilbert {
         image storage, and indexing; typically clusters better than Z-order."
     }
     fn length(&self) -> u32 {
-        self.length
+        u32::try_from(self.length).expect("curve has more than u32::MAX points; use length_u64")
     }
     fn dimensions(&self) -> u32 {
         self.dimension
     }
+    fn nested_blocks(&self) -> bool {
+        true
+    }
     fn index(&self, p: &point::Point) -> u32 {
+        u32::try_from(self.index_u64(p)).expect("index exceeds u32; use index_u64")
+    }
+    fn point(&self, index: u32) -> point::Point {
+        debug_assert!(u64::from(index) < self.length, "index out of bounds");
+        self.locate(u64::from(index))
+    }
+    fn index_u64(&self, p: &point::Point) -> u64 {
         debug_assert_eq!(p.len(), self.dimension as usize, "point dimension mismatch");
-        let side = 1u32 << self.order;
+        let side = 1u64 << self.order;
         debug_assert!(
-            p.iter().all(|&c| c < side),
+            p.iter().all(|&c| u64::from(c) < side),
             "point coordinate out of bounds"
         );
         self.mapper.index(self.dimension, self.order, p)
     }
-    fn point(&self, index: u32) -> point::Point {
-        let len = self.length;
-        debug_assert!(index < len, "index out of bounds");
-        point::Point::new_with_dimension(
+    fn point_u64(&self, index: u64) -> error::Result<point::Point> {
+        if index >= self.length {
+            return Err(out_of_range(index, self.length));
+        }
+        Ok(self.locate(index))
+    }
+    fn length_u64(&self) -> u64 {
+        self.length
+    }
+    fn next_point(&self, p: &point::Point) -> Option<point::Point> {
+        self.adjacent(p, true)
+    }
+    fn prev_point(&self, p: &point::Point) -> Option<point::Point> {
+        self.adjacent(p, false)
+    }
+    fn iter_range(&self, range: Range<u32>) -> Box<dyn Iterator<Item = point::Point> + '_> {
+        debug_assert!(u64::from(range.end) <= self.length, "range out of bounds");
+        // The table walk decomposes `u32` indices, so curves beyond 32 bits look points up.
+        if self.length > u64::from(u32::MAX) {
+            return Box::new(range.map(|index| self.point(index)));
+        }
+        Box::new(Walk::new(
             self.dimension,
-            self.mapper.point(self.dimension, self.order, index % len),
-        )
+            self.order,
+            (0, 0),
+            range,
+            self.cell_step(),
+        ))
     }
 }
 
@@ -130,4 +262,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn iter_range_matches_point() -> error::Result<()> {
+        for (dimension, size) in [(2, 32), (3, 8), (4, 4)] {
+            let h = Hilbert::from_dimensions(dimension, size)?;
+            for range in [0..h.length(), 7..h.length() / 2 + 1, 9..9] {
+                let walked: Vec<_> = h.iter_range(range.clone()).collect();
+                let looked_up: Vec<_> = range.map(|i| h.point(i)).collect();
+                assert_eq!(walked, looked_up, "{dimension}D size {size}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn wide_indices_reach_beyond_u32() -> error::Result<()> {
+        let h = Hilbert::from_dimensions_u64(3, 1 << 20)?;
+        assert_eq!(h.length_u64(), 1 << 60);
+        for index in [0, 1 << 40, 0x0123_4567_89ab_cdef, (1 << 60) - 2] {
+            let p = h.point_u64(index)?;
+            assert_eq!(h.index_u64(&p), index);
+            assert_eq!(h.next_point(&p), Some(h.point_u64(index + 1)?));
+        }
+        assert!(h.point_u64(1 << 60).is_err());
+        let walked: Vec<_> = h.iter_range(5..9).collect();
+        let looked_up: Vec<_> = (5..9).map(|i| h.point(i)).collect();
+        assert_eq!(walked, looked_up);
+
+        // Small grids give the same curve either way.
+        let (narrow, wide) = (
+            Hilbert::from_dimensions(3, 8)?,
+            Hilbert::from_dimensions_u64(3, 8)?,
+        );
+        for i in 0..narrow.length() {
+            assert_eq!(narrow.point(i), wide.point_u64(u64::from(i))?);
+        }
+
+        assert!(Hilbert::from_dimensions_u64(2, 1 << 31).is_ok());
+        assert!(Hilbert::from_dimensions_u64(4, 1 << 16).is_err());
+        assert!(Hilbert::from_dimensions_u64(31, 2).is_ok());
+        assert!(Hilbert::from_dimensions_u64(32, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn u32_boundary() -> error::Result<()> {
+        // 2D order 16 has one point more than `u32::MAX`, but its last index still fits.
+        let h = Hilbert::from_dimensions_u64(2, 1 << 16)?;
+        assert_eq!(h.length_u64(), 1 << 32);
+        let last = h.point(u32::MAX);
+        assert_eq!(h.point_u64(u64::from(u32::MAX))?, last);
+        assert_eq!(h.index(&last), u32::MAX);
+        assert_eq!(h.next_point(&last), None);
+        assert!(h.point_u64(1 << 32).is_err());
+
+        // One more bit puts indices past `u32::MAX`, reachable only through the u64 methods.
+        let h = Hilbert::from_dimensions_u64(3, 1 << 11)?;
+        let p = h.point_u64(1 << 32)?;
+        assert_eq!(h.index_u64(&p), 1 << 32);
+        assert_eq!(h.prev_point(&p), Some(h.point(u32::MAX)));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "use length_u64")]
+    fn length_panics_beyond_u32() {
+        Hilbert::from_dimensions_u64(2, 1 << 16).unwrap().length();
+    }
+
+    #[test]
+    #[should_panic(expected = "use index_u64")]
+    fn index_panics_beyond_u32() {
+        let h = Hilbert::from_dimensions_u64(3, 1 << 11).unwrap();
+        let p = h.point_u64(1 << 32).unwrap();
+        h.index(&p);
+    }
 }
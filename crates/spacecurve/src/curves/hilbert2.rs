@@ -2,49 +2,71 @@ use smallvec::{SmallVec, smallvec};
 
 use super::hilbert_common::{gray2, rot2};
 
-/// 2D Hilbert index for a point `p` at a given `order`.
-pub fn hilbert_index(order: u32, point: &[u32]) -> u32 {
+/// 2D Hilbert index for a point `p` at a given `order`, for orders up to 32.
+pub fn hilbert_index(order: u32, point: &[u32]) -> u64 {
     let mut index_acc = 0;
-    let mut entry_state = 0;
-    let mut direction_state = 0;
+    let mut state = (0, 0);
     for step in 0..order {
         let bit_offset = order - step - 1;
         let a_bit = (point[1] >> bit_offset) & 1;
         let b_bit = (point[0] >> bit_offset) & 1;
-        let label: u32 = (a_bit | b_bit << 1) ^ entry_state;
-        let word = match direction_state {
-            0 => gray2(rot2(label)),
-            _ => gray2(label),
-        };
-        if word == 3 {
-            entry_state = 3 - entry_state;
-        }
-        index_acc = (index_acc << 2) | word;
-        if word == 0 || word == 3 {
-            direction_state ^= 1;
-        }
+        let (word, next) = unstep(state, a_bit | b_bit << 1);
+        index_acc = (index_acc << 2) | u64::from(word);
+        state = next;
     }
     index_acc
 }
 
-/// 2D Hilbert point for a given `order` and `index`.
-pub fn hilbert_point(order: u32, index: u32) -> SmallVec<[u32; 4]> {
+/// Inverse of [`step`]: the word that visits quadrant `label` in `state`, and the state of
+/// that quadrant's sub-curve.
+pub fn unstep(state: (u32, u32), label: u32) -> (u32, (u32, u32)) {
+    let (mut entry_state, mut direction_state) = state;
+    let label = label ^ entry_state;
+    let word = match direction_state {
+        0 => gray2(rot2(label)),
+        _ => gray2(label),
+    };
+    if word == 3 {
+        entry_state = 3 - entry_state;
+    }
+    if word == 0 || word == 3 {
+        direction_state ^= 1;
+    }
+    (word, (entry_state, direction_state))
+}
+
+/// One level of the 2D Hilbert state machine.
+///
+/// Maps the 2-bit `word` read in state `(entry, direction)` to the quadrant it visits, with
+/// x in bit 1 and y in bit 0, and returns the state of that quadrant's sub-curve.
+pub fn step(state: (u32, u32), word: u32) -> (u32, (u32, u32)) {
+    let (mut entry_state, mut direction_state) = state;
+    let label = match direction_state {
+        0 => rot2(gray2(word)) ^ entry_state,
+        _ => gray2(word) ^ entry_state,
+    };
+    if word == 3 {
+        entry_state = 3 - entry_state;
+    }
+    if word == 0 || word == 3 {
+        direction_state ^= 1;
+    }
+    (label, (entry_state, direction_state))
+}
+
+/// 2D Hilbert point for a given `order` and `index`, for orders up to 32.
+pub fn hilbert_point(order: u32, index: u64) -> SmallVec<[u32; 4]> {
     let hwidth = order * 2;
-    let mut entry_state = 0;
-    let mut direction_state = 0;
+    let mut state = (0, 0);
     // Use 32-bit coordinate masks to avoid artificial 16-bit limits.
     let mut x_coord: u32 = 0;
     let mut y_coord: u32 = 0;
-    for step in 0..order {
+    for level in 0..order {
         // Extract 2 bits from the index
-        let word = (index >> (hwidth - (step * 2) - 2)) & 3;
-
-        let label = match direction_state {
-            0 => rot2(gray2(word)) ^ entry_state,
-            _ => gray2(word) ^ entry_state,
-        };
+        let word = ((index >> (hwidth - (level * 2) - 2)) & 3) as u32;
+        let (label, next) = step(state, word);
 
-        let bit_mask: u32 = 1 << (order - step - 1);
+        let bit_mask: u32 = 1 << (order - level - 1);
 
         if (label & 2) != 0 {
             x_coord |= bit_mask;
@@ -52,13 +74,7 @@ pub fn hilbert_point(order: u32, index: u32) -> SmallVec<[u32; 4]> {
         if (label & 1) != 0 {
             y_coord |= bit_mask;
         }
-
-        if word == 3 {
-            entry_state = 3 - entry_state;
-        }
-        if word == 0 || word == 3 {
-            direction_state ^= 1;
-        }
+        state = next;
     }
     smallvec![x_coord, y_coord]
 }
@@ -86,10 +102,18 @@ mod tests {
         assert!(hilbert_point(3, 45).as_slice() == [5, 6]);
     }
 
+    #[test]
+    fn round_trips_beyond_u32_indices() {
+        for (order, index) in [(20, 0x12_3456_789a), (32, u64::MAX), (32, 1 << 63)] {
+            let p = hilbert_point(order, index);
+            assert_eq!(hilbert_index(order, &p), index, "order {order}");
+        }
+    }
+
     #[test]
     fn test_symmetry() {
         for m in 2u32..5u32 {
-            for i in 0u32..2u32.pow(2 * m) {
+            for i in 0u64..1 << (2 * m) {
                 let p = hilbert_point(m, i);
                 let r = hilbert_index(m, &p);
                 assert!(i == r);
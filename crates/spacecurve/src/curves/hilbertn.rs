@@ -39,36 +39,50 @@ fn entry(x: u32) -> u32 {
     }
 }
 
-/// N‑D Hilbert: compute point coordinates for `index`.
-pub fn hilbert_point(dimension: u32, order: u32, index: u32) -> SmallVec<[u32; 4]> {
-    let hwidth = order * dimension;
-    let mut entry_state = 0;
-    let mut direction_state = 0;
+/// One level of the N‑D Hilbert state machine.
+///
+/// Maps the `dimension`-bit `word` read in state `(entry, direction)` to the child cell it
+/// visits, with coordinate 0 in the most significant bit, and returns the state of that
+/// cell's sub-curve.
+pub fn step(dimension: u32, state: (u32, u32), word: u32) -> (u32, (u32, u32)) {
+    let (entry_state, direction_state) = state;
+    let label = itransform(entry_state, direction_state, dimension, ops::graycode(word));
+    let entry_state = entry_state ^ lrot(entry(word), direction_state + 1, dimension);
+    let direction_state = (direction_state + direction(word, dimension) + 1) % dimension;
+    (label, (entry_state, direction_state))
+}
+
+/// N‑D Hilbert: compute point coordinates for `index`, for `order * dimension` up to 64.
+pub fn hilbert_point(dimension: u32, order: u32, index: u64) -> SmallVec<[u32; 4]> {
+    let mask = u64::from(bitmask(dimension));
+    let mut state = (0, 0);
     let mut point = smallvec![0; dimension as usize];
     for order_idx in 0..order {
-        let word = bitrange(
-            index,
-            hwidth,
-            order_idx * dimension,
-            order_idx * dimension + dimension,
-        );
-        let mut label = ops::graycode(word);
-        label = itransform(entry_state, direction_state, dimension, label);
+        let word = ((index >> ((order - order_idx - 1) * dimension)) & mask) as u32;
+        let (label, next) = step(dimension, state, word);
         for coord in 0..dimension {
             let bit_val = bitrange(label, dimension, coord, coord + 1);
             point[coord as usize] = setbit(point[coord as usize], order, order_idx, bit_val);
         }
-        entry_state ^= lrot(entry(word), direction_state + 1, dimension);
-        direction_state = (direction_state + direction(word, dimension) + 1) % dimension;
+        state = next;
     }
     point
 }
 
-/// N‑D Hilbert: compute linear index for `point`.
-pub fn hilbert_index(dimension: u32, order: u32, point: &[u32]) -> u32 {
+/// Inverse of [`step`]: the word that visits child cell `label` in `state`, and the state of
+/// that cell's sub-curve.
+pub fn unstep(dimension: u32, state: (u32, u32), label: u32) -> (u32, (u32, u32)) {
+    let (entry_state, direction_state) = state;
+    let word = ops::igraycode(transform(entry_state, direction_state, dimension, label));
+    let entry_state = entry_state ^ lrot(entry(word), direction_state + 1, dimension);
+    let direction_state = (direction_state + direction(word, dimension) + 1) % dimension;
+    (word, (entry_state, direction_state))
+}
+
+/// N‑D Hilbert: compute linear index for `point`, for `order * dimension` up to 64.
+pub fn hilbert_index(dimension: u32, order: u32, point: &[u32]) -> u64 {
     let mut index_acc = 0;
-    let mut entry_state = 0;
-    let mut direction_state = 0;
+    let mut state = (0, 0);
     for order_idx in 0..order {
         let mut label = 0;
         for coord in 0..dimension {
@@ -80,12 +94,9 @@ pub fn hilbert_index(dimension: u32, order: u32, point: &[u32]) -> u32 {
             );
             label |= bit_val << coord;
         }
-        label = transform(entry_state, direction_state, dimension, label);
-
-        let word = ops::igraycode(label);
-        entry_state ^= lrot(entry(word), direction_state + 1, dimension);
-        direction_state = (direction_state + direction(word, dimension) + 1) % dimension;
-        index_acc = (index_acc << dimension) | word;
+        let (word, next) = unstep(dimension, state, label);
+        index_acc = (index_acc << dimension) | u64::from(word);
+        state = next;
     }
     index_acc
 }
@@ -98,13 +109,30 @@ mod tests {
     fn test_hilbert() {
         let m = 3;
         for n in 2..5 {
-            for i in 0..2u32.pow(n * m) {
+            for i in 0..1u64 << (n * m) {
                 let v = hilbert_point(n, m, i);
                 assert_eq!(i, hilbert_index(n, m, &v));
             }
         }
     }
 
+    #[test]
+    fn round_trips_beyond_u32_indices() {
+        for (dimension, order) in [(3, 21), (4, 16), (8, 8)] {
+            let bits = dimension * order;
+            for index in [
+                0,
+                1u64 << (bits - 1),
+                u64::MAX >> (64 - bits),
+                0x0123_4567_89ab,
+            ] {
+                let index = index & (u64::MAX >> (64 - bits));
+                let p = hilbert_point(dimension, order, index);
+                assert_eq!(hilbert_index(dimension, order, &p), index, "{dimension}D");
+            }
+        }
+    }
+
     #[test]
     fn test_transform() {
         // These values are from the example on p 18 of Hamilton
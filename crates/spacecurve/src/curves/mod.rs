@@ -14,9 +14,15 @@ mod hilbert2;
 mod hilbert_common;
 /// Internal N-D Hilbert helpers.
 mod hilbertn;
+/// Moore curve: a closed loop of 2D Hilbert curves.
+pub mod moore;
 /// Onion curve family operating on L∞ shells (single consolidated module).
 pub mod onion;
+/// Peano's base-3 curve for power-of-three grids.
+pub mod peano;
 /// Simple serpentine scan (boustrophedon) traversal.
 pub mod scan;
+/// Sierpinski-Knopp curve from recursive triangle bisection.
+pub mod sierpinski;
 /// Z-order (Morton) bit-interleaving.
 pub mod zorder;
@@ -0,0 +1,152 @@
+use smallvec::smallvec;
+
+use super::hilbert2;
+use crate::{
+    error::{self, Error},
+    point::Point,
+    spacecurve::SpaceCurve,
+    spec::{GridSpec, INDEX_BITS},
+};
+
+/// The Moore curve: a closed variant of the 2D Hilbert curve.
+///
+/// The grid is split into quadrants visited in Hilbert's U order, each holding a Hilbert
+/// curve of one order lower whose ends lie on the edge shared with the neighbouring half.
+/// The curve starts and finishes on either side of the bottom centre, so its last point is
+/// adjacent to its first and the walk can loop.
+#[derive(Debug)]
+pub struct Moore {
+    /// Order of the curve (`size == 2^order`).
+    order: u32,
+    /// Side length per dimension.
+    size: u32,
+    /// Cached total number of points in the curve.
+    length: u32,
+}
+
+impl Moore {
+    /// Construct a 2D Moore curve with the given side length.
+    ///
+    /// The dimension must be 2 and the size a power of two of at least 2.
+    pub fn from_dimensions(dimension: u32, size: u32) -> error::Result<Self> {
+        if dimension != 2 {
+            return Err(Error::Shape(format!(
+                "Moore curves are 2-dimensional, got dimension {dimension}"
+            )));
+        }
+        let spec = GridSpec::power_of_two(dimension, size)?;
+        spec.require_index_bits_lt(INDEX_BITS)?;
+        if size < 2 {
+            return Err(Error::Size(
+                "Moore curves need a size of at least 2".to_string(),
+            ));
+        }
+        Ok(Self {
+            order: spec.order().unwrap(),
+            size,
+            length: spec.length(),
+        })
+    }
+
+    /// Side length of one quadrant.
+    fn half(&self) -> u32 {
+        self.size / 2
+    }
+}
+
+impl SpaceCurve for Moore {
+    fn name(&self) -> &'static str {
+        "Moore"
+    }
+
+    fn info(&self) -> &'static str {
+        "Four Hilbert curves joined into a closed loop.\n\
+        Keeps Hilbert's locality, and the last cell is adjacent\n\
+        to the first, so walks and animations can cycle seamlessly."
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+
+    fn dimensions(&self) -> u32 {
+        2
+    }
+
+    // Each quadrant is a Hilbert curve starting at a multiple of its length.
+    fn nested_blocks(&self) -> bool {
+        true
+    }
+
+    fn point(&self, index: u32) -> Point {
+        debug_assert!(index < self.length, "index out of range");
+        let half = self.half();
+        let quadrant_len = self.length / 4;
+        let quadrant = index / quadrant_len;
+        let local = hilbert2::hilbert_point(self.order - 1, u64::from(index % quadrant_len));
+        // Hilbert sub-curves run from (0, 0) to (0, half - 1); the left quadrants mirror them
+        // in x and the right quadrants in y so their ends meet across the centre line.
+        let (x, y) = match quadrant {
+            0 => (half - 1 - local[0], local[1]),
+            1 => (half - 1 - local[0], local[1] + half),
+            2 => (local[0] + half, 2 * half - 1 - local[1]),
+            _ => (local[0] + half, half - 1 - local[1]),
+        };
+        Point::new_with_dimension(2, smallvec![x, y])
+    }
+
+    fn index(&self, p: &Point) -> u32 {
+        debug_assert_eq!(p.len(), 2, "point dimension mismatch");
+        debug_assert!(
+            p.iter().all(|&coord| coord < self.size),
+            "point coordinate out of bounds"
+        );
+        let half = self.half();
+        let (x, y) = (p[0], p[1]);
+        let (quadrant, local) = match (x < half, y < half) {
+            (true, true) => (0, [half - 1 - x, y]),
+            (true, false) => (1, [half - 1 - x, y - half]),
+            (false, false) => (2, [x - half, 2 * half - 1 - y]),
+            (false, true) => (3, [x - half, half - 1 - y]),
+        };
+        quadrant * (self.length / 4) + hilbert2::hilbert_index(self.order - 1, &local) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smallest_curve_is_a_square_loop() {
+        let curve = Moore::from_dimensions(2, 2).unwrap();
+        let order: Vec<Vec<u32>> = (0..4).map(|i| curve.point(i).into()).collect();
+        assert_eq!(order, [[0, 0], [0, 1], [1, 1], [1, 0]]);
+    }
+
+    #[test]
+    fn round_trips_and_closes_the_loop() {
+        for size in [2, 4, 8, 32] {
+            let curve = Moore::from_dimensions(2, size).unwrap();
+            let last = curve.point(curve.length() - 1);
+            let mut prev = last.clone();
+            for i in 0..curve.length() {
+                let p = curve.point(i);
+                assert_eq!(curve.index(&p), i, "size {size} at {i}");
+                assert_eq!(p.distance(&prev), 1.0, "size {size} at {i}");
+                prev = p;
+            }
+            assert_eq!(curve.point(0), Point::new(vec![size / 2 - 1, 0]));
+            assert_eq!(last, Point::new(vec![size / 2, 0]));
+        }
+    }
+
+    #[test]
+    fn guards() {
+        assert!(Moore::from_dimensions(3, 4).is_err());
+        assert!(Moore::from_dimensions(2, 1).is_err());
+        assert!(Moore::from_dimensions(2, 6).is_err());
+        assert!(Moore::from_dimensions(2, 1 << 15).is_ok());
+        assert!(Moore::from_dimensions(2, 1 << 16).is_err());
+    }
+}
@@ -18,7 +18,14 @@
 /// The outer shell has 26 cells (even). The center cell is White, hence the shell
 /// must end on White; any continuous traversal into the next shell would need to
 /// enter a Black cell, contradiction.
-use crate::{error, point::Point, spacecurve::SpaceCurve, spec::GridSpec};
+use std::ops::Range;
+
+use crate::{
+    error,
+    point::Point,
+    spacecurve::SpaceCurve,
+    spec::{GridSpec, RectGridSpec},
+};
 
 /// Onion curve operating on L∞ shells in N‑D.
 #[derive(Debug)]
@@ -29,6 +36,8 @@ pub struct OnionCurve {
     side_length: u32,
     /// Total number of points (L^N).
     length: u32,
+    /// Side of each axis on a rectangular grid, or `None` for a cube of side `side_length`.
+    rect: Option<Vec<u32>>,
 }
 
 impl OnionCurve {
@@ -46,8 +55,99 @@ impl OnionCurve {
             dimensions: spec.dimension(),
             side_length: spec.size(),
             length: spec.length(),
+            rect: None,
+        })
+    }
+
+    /// Construct an Onion curve over a rectangular grid with the given side per axis.
+    ///
+    /// Shells are peeled from every axis at once, so axes shorter than the rest run out first
+    /// and the innermost shell is a slab rather than a single cell.
+    pub fn from_sides(sides: &[u32]) -> error::Result<Self> {
+        let spec = RectGridSpec::new(sides)?;
+        if let Some(side) = spec.cube_side() {
+            return Self::new(spec.dimension(), side);
+        }
+        Ok(Self {
+            dimensions: spec.dimension(),
+            side_length: spec.sides().iter().copied().max().unwrap_or(1),
+            length: spec.length(),
+            rect: Some(spec.sides().to_vec()),
         })
     }
+
+    /// Number of L∞ shells, from the outer surface to the centre.
+    pub fn shells(&self) -> u32 {
+        match &self.rect {
+            Some(sides) => sides.iter().copied().min().unwrap_or(0).div_ceil(2),
+            None => self.side_length.div_ceil(2),
+        }
+    }
+
+    /// Indices covered by shell `level`, counted from the outside. Each shell is traversed
+    /// completely before the next one starts, so the range is contiguous.
+    pub fn shell_range(&self, level: u32) -> Range<u32> {
+        debug_assert!(level < self.shells(), "shell out of bounds");
+        if let Some(sides) = &self.rect {
+            let mut sides = sides.clone();
+            let mut start = 0;
+            for _ in 0..level {
+                start += rect_shell_size(&sides);
+                sides
+                    .iter_mut()
+                    .for_each(|side| *side = side.saturating_sub(2));
+            }
+            return start..start + rect_shell_size(&sides);
+        }
+        let mut side = self.side_length;
+        let mut start = 0;
+        for _ in 0..level {
+            start += shell_size(self.dimensions, side);
+            side = side.saturating_sub(2);
+        }
+        start..start + shell_size(self.dimensions, side)
+    }
+
+    /// Shell holding the point at `index`, counted from the outside.
+    pub fn shell_of_index(&self, index: u32) -> u32 {
+        debug_assert!(index < self.length, "index out of bounds");
+        if self.rect.is_some() {
+            return (0..self.shells())
+                .find(|&level| index < self.shell_range(level).end)
+                .unwrap_or(0);
+        }
+        shell_for_index(self.dimensions, self.side_length, index).level
+    }
+
+    /// Shell holding `p`, counted from the outside.
+    pub fn shell_of_point(&self, p: &Point) -> u32 {
+        match &self.rect {
+            Some(sides) => p
+                .iter()
+                .zip(sides)
+                .map(|(&c, &side)| c.min(side - 1 - c))
+                .min()
+                .unwrap_or(0),
+            None => shell_of(p, self.side_length),
+        }
+    }
+
+    /// Points of shell `level` in curve order.
+    pub fn shell_points(&self, level: u32) -> impl Iterator<Item = Point> + '_ {
+        self.iter_range(self.shell_range(level))
+    }
+}
+
+/// L∞ shell holding `point` in a cube of side `side`, counted from the outside: 0 for points
+/// on the surface, up to `side.div_ceil(2) - 1` at the centre.
+///
+/// This depends only on the grid, so it applies to the points of any curve.
+pub fn shell_of(point: &[u32], side: u32) -> u32 {
+    point
+        .iter()
+        .map(|&c| c.min(side - 1 - c))
+        .min()
+        .unwrap_or(0)
 }
 
 impl SpaceCurve for OnionCurve {
@@ -67,12 +167,26 @@ impl SpaceCurve for OnionCurve {
         self.length
     }
 
+    fn sides(&self) -> Vec<u32> {
+        match &self.rect {
+            Some(sides) => sides.clone(),
+            None => vec![self.side_length; self.dimensions as usize],
+        }
+    }
+
     fn index(&self, p: &Point) -> u32 {
         debug_assert_eq!(
             p.len(),
             self.dimensions as usize,
             "point dimension mismatch"
         );
+        if let Some(sides) = &self.rect {
+            debug_assert!(
+                p.iter().zip(sides).all(|(&c, &side)| c < side),
+                "point coordinate out of bounds"
+            );
+            return onion_index_rect(sides, p);
+        }
         debug_assert!(
             p.iter().all(|&c| c < self.side_length),
             "point coordinate out of bounds"
@@ -82,6 +196,9 @@ impl SpaceCurve for OnionCurve {
 
     fn point(&self, index: u32) -> Point {
         debug_assert!(index < self.length, "index out of bounds");
+        if let Some(sides) = &self.rect {
+            return Point::new_with_dimension(self.dimensions, onion_point_rect(sides, index));
+        }
         let coords = onion_point_nd(self.dimensions, self.side_length, index % self.length);
         Point::new_with_dimension(self.dimensions, coords)
     }
@@ -115,6 +232,13 @@ fn shell_size(dimension: u32, side: u32) -> u32 {
     pow_u32(side, dimension) - pow_u32(inner, dimension)
 }
 
+/// Number of points on the outer shell of a rectangular grid with the given sides.
+fn rect_shell_size(sides: &[u32]) -> u32 {
+    let outer: u32 = sides.iter().product();
+    let inner: u32 = sides.iter().map(|side| side.saturating_sub(2)).product();
+    outer - inner
+}
+
 /// Locate the shell that contains `index`.
 fn shell_for_index(dimension: u32, side: u32, mut index: u32) -> Shell {
     let mut side_at_level = side;
@@ -139,11 +263,7 @@ fn shell_for_index(dimension: u32, side: u32, mut index: u32) -> Shell {
 
 /// Locate the shell and offset for a given point.
 fn shell_for_point(dimension: u32, side: u32, point: &[u32]) -> Shell {
-    let level = point
-        .iter()
-        .map(|&c| c.min(side - 1 - c))
-        .min()
-        .unwrap_or(0);
+    let level = shell_of(point, side);
     let mut side_at_level = side;
     let mut offset = 0;
     for _ in 0..level {
@@ -866,6 +986,37 @@ mod tests {
         assert_eq!(c.length(), 9);
     }
 
+    #[test]
+    fn shell_ranges_match_shell_of() {
+        for (dim, size) in [(2, 7), (2, 8), (3, 5), (4, 4)] {
+            let curve = OnionCurve::new(dim, size).unwrap();
+            assert_eq!(curve.shell_range(0).start, 0);
+            assert_eq!(curve.shell_range(curve.shells() - 1).end, curve.length());
+            for level in 0..curve.shells() {
+                for idx in curve.shell_range(level) {
+                    assert_eq!(shell_of(&curve.point(idx), size), level);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shell_queries_agree() {
+        for (dim, size) in [(2, 7), (3, 4), (3, 5)] {
+            let curve = OnionCurve::new(dim, size).unwrap();
+            for level in 0..curve.shells() {
+                let range = curve.shell_range(level);
+                let points: Vec<_> = curve.shell_points(level).collect();
+                assert_eq!(points.len(), range.len());
+                for (idx, p) in range.zip(&points) {
+                    assert_eq!(curve.point(idx), *p);
+                    assert_eq!(curve.shell_of_index(idx), level);
+                    assert_eq!(curve.shell_of_point(p), level);
+                }
+            }
+        }
+    }
+
     #[test]
     fn roundtrip_dims_2_to_4_sizes_upto_8() {
         for dim in 2..=4 {
@@ -882,4 +1033,37 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn rectangular_grids_peel_shells() {
+        for sides in [
+            vec![5, 3],
+            vec![8, 2],
+            vec![6, 4, 3],
+            vec![7, 1],
+            vec![4, 9],
+        ] {
+            let curve = OnionCurve::from_sides(&sides).unwrap();
+            assert_eq!(curve.sides(), sides);
+            assert_eq!(curve.length(), sides.iter().product::<u32>());
+            assert_eq!(
+                curve.shell_range(curve.shells() - 1).end,
+                curve.length(),
+                "{sides:?}"
+            );
+            for idx in 0..curve.length() {
+                let p = curve.point(idx);
+                assert!(p.iter().zip(&sides).all(|(&c, &side)| c < side));
+                assert_eq!(
+                    curve.index(&p),
+                    idx,
+                    "roundtrip failed for {sides:?} at {idx}"
+                );
+                assert_eq!(curve.shell_of_point(&p), curve.shell_of_index(idx));
+            }
+        }
+        // Equal sides give the usual cubic curve.
+        let cube = OnionCurve::from_sides(&[5, 5, 5]).unwrap();
+        assert_eq!(cube.point(60), OnionCurve::new(3, 5).unwrap().point(60));
+    }
 }
@@ -0,0 +1,151 @@
+use smallvec::{SmallVec, smallvec};
+
+use crate::{error, point::Point, spacecurve::SpaceCurve, spec::GridSpec};
+
+/// Peano's base-3 curve on grids whose side is a power of three.
+///
+/// An index is read as `order * dimension` base-3 digits, most significant first, dealt out
+/// to the axes in turn starting with axis 0. A digit is reflected (`d → 2 - d`) when the
+/// digits already dealt to the other axes sum to an odd number, which is what keeps every
+/// step between adjacent cells.
+#[derive(Debug)]
+pub struct Peano {
+    /// Number of dimensions in the grid.
+    dimension: u32,
+    /// Number of base-3 digits per coordinate (`size == 3^order`).
+    order: u32,
+    /// Side length per dimension.
+    size: u32,
+    /// Cached total number of points in the curve.
+    length: u32,
+}
+
+impl Peano {
+    /// Construct a `Peano` curve for the given dimensions and side length.
+    ///
+    /// The size must be a power of three and `size^dimension` must fit in a `u32`.
+    pub fn from_dimensions(dimension: u32, size: u32) -> error::Result<Self> {
+        let spec = GridSpec::power_of_three(dimension, size)?;
+        Ok(Self {
+            dimension: spec.dimension(),
+            order: spec.size().ilog(3),
+            size: spec.size(),
+            length: spec.length(),
+        })
+    }
+}
+
+impl SpaceCurve for Peano {
+    fn name(&self) -> &'static str {
+        "Peano"
+    }
+
+    fn info(&self) -> &'static str {
+        "The original space-filling curve (1890), recursing into 3x3 blocks.\n\
+        Continuous like Hilbert, with a serpentine path through each block;\n\
+        needs power-of-three sides rather than powers of two."
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+
+    fn dimensions(&self) -> u32 {
+        self.dimension
+    }
+
+    fn point(&self, index: u32) -> Point {
+        debug_assert!(index < self.length, "index out of range");
+        let digits = self.order * self.dimension;
+        let mut coords: SmallVec<[u32; 4]> = smallvec![0; self.dimension as usize];
+        // Parity of the digits dealt to each axis so far, and of all of them.
+        let mut parity: SmallVec<[bool; 4]> = smallvec![false; self.dimension as usize];
+        let mut total = false;
+        let mut place = self.length;
+        for position in 0..digits {
+            place /= 3;
+            let digit = index / place % 3;
+            let axis = (position % self.dimension) as usize;
+            let reflect = total != parity[axis];
+            coords[axis] = coords[axis] * 3 + if reflect { 2 - digit } else { digit };
+            parity[axis] ^= digit % 2 == 1;
+            total ^= digit % 2 == 1;
+        }
+        Point::new_with_dimension(self.dimension, coords)
+    }
+
+    fn index(&self, p: &Point) -> u32 {
+        debug_assert_eq!(p.len(), self.dimension as usize, "point dimension mismatch");
+        debug_assert!(
+            p.iter().all(|&coord| coord < self.size),
+            "point coordinate out of bounds"
+        );
+        let mut parity: SmallVec<[bool; 4]> = smallvec![false; self.dimension as usize];
+        let mut total = false;
+        let mut index = 0;
+        let mut place = self.size;
+        for _ in 0..self.order {
+            place /= 3;
+            for (axis, &coord) in p.iter().enumerate() {
+                let digit = coord / place % 3;
+                let reflect = total != parity[axis];
+                index = index * 3 + if reflect { 2 - digit } else { digit };
+                // Reflection keeps a digit's parity, so coordinates track it directly.
+                parity[axis] ^= digit % 2 == 1;
+                total ^= digit % 2 == 1;
+            }
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_block_snakes_through_columns() {
+        let curve = Peano::from_dimensions(2, 3).unwrap();
+        let order: Vec<Vec<u32>> = (0..9).map(|i| curve.point(i).into()).collect();
+        assert_eq!(
+            order,
+            [
+                [0, 0],
+                [0, 1],
+                [0, 2],
+                [1, 2],
+                [1, 1],
+                [1, 0],
+                [2, 0],
+                [2, 1],
+                [2, 2]
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips_and_stays_continuous() {
+        for (dimension, size) in [(1, 27), (2, 27), (3, 9), (4, 3)] {
+            let curve = Peano::from_dimensions(dimension, size).unwrap();
+            let mut prev = curve.point(0);
+            assert_eq!(prev, Point::new(vec![0; dimension as usize]));
+            for i in 0..curve.length() {
+                let p = curve.point(i);
+                assert_eq!(curve.index(&p), i, "{dimension}D size {size} at {i}");
+                if i > 0 {
+                    assert_eq!(p.distance(&prev), 1.0, "{dimension}D size {size} at {i}");
+                }
+                prev = p;
+            }
+            assert_eq!(prev, Point::new(vec![size - 1; dimension as usize]));
+        }
+    }
+
+    #[test]
+    fn guards() {
+        assert!(Peano::from_dimensions(2, 4).is_err());
+        assert!(Peano::from_dimensions(0, 3).is_err());
+        assert!(Peano::from_dimensions(2, 3u32.pow(10)).is_ok());
+        assert!(Peano::from_dimensions(2, 3u32.pow(11)).is_err());
+    }
+}
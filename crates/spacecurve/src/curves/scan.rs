@@ -1,16 +1,21 @@
 use std::iter::Iterator;
 
-use smallvec::smallvec;
+use smallvec::{SmallVec, smallvec};
 
-use crate::{error, point::Point, spacecurve::SpaceCurve, spec::GridSpec};
+use crate::{
+    error,
+    point::Point,
+    spacecurve::SpaceCurve,
+    spec::{GridSpec, RectGridSpec},
+};
 
 /// Serpentine row/column scan across an N‑D grid.
 #[derive(Debug)]
 pub struct Scan {
     /// Number of dimensions in the grid.
     dimension: u32,
-    /// Side length per dimension.
-    size: u32,
+    /// Side length of each dimension.
+    sides: SmallVec<[u32; 4]>,
     /// Cached total number of points in the scan.
     length: u32,
 }
@@ -21,10 +26,48 @@ impl Scan {
         let spec = GridSpec::new(dimension, size)?;
         Ok(Self {
             dimension: spec.dimension(),
-            size: spec.size(),
+            sides: smallvec![spec.size(); spec.dimension() as usize],
             length: spec.length(),
         })
     }
+
+    /// Construct a `Scan` curve over a rectangular grid with the given side per axis.
+    pub fn from_sides(sides: &[u32]) -> error::Result<Self> {
+        let spec = RectGridSpec::new(sides)?;
+        Ok(Self {
+            dimension: spec.dimension(),
+            sides: SmallVec::from_slice(spec.sides()),
+            length: spec.length(),
+        })
+    }
+
+    /// The point one step forward or back along the scan from `point`.
+    ///
+    /// An axis runs backwards when the coordinates above it sum to an odd number, so a step
+    /// moves the lowest axis that is not already at the end it is heading for.
+    fn step(&self, point: &Point, forward: bool) -> Option<Point> {
+        let mut coordinates = point.0.clone();
+        let mut odd_above = coordinates
+            .iter()
+            .skip(1)
+            .fold(false, |odd, &c| odd ^ (c % 2 != 0));
+        for axis in 0..coordinates.len() {
+            let ascending = forward != odd_above;
+            let coordinate = coordinates[axis];
+            if ascending && coordinate + 1 < self.sides[axis] {
+                coordinates[axis] += 1;
+                return Some(Point::new_with_dimension(self.dimension, coordinates));
+            }
+            if !ascending && coordinate > 0 {
+                coordinates[axis] -= 1;
+                return Some(Point::new_with_dimension(self.dimension, coordinates));
+            }
+            if let Some(&above) = coordinates.get(axis + 1) {
+                odd_above ^= above % 2 != 0;
+            }
+        }
+        None
+    }
 }
 
 impl SpaceCurve for Scan {
@@ -43,6 +86,9 @@ impl SpaceCurve for Scan {
     fn dimensions(&self) -> u32 {
         self.dimension
     }
+    fn sides(&self) -> Vec<u32> {
+        self.sides.to_vec()
+    }
 
     /// Convert a 1D index into N-dimensional coordinates.
     ///
@@ -55,15 +101,17 @@ impl SpaceCurve for Scan {
         let mut should_reverse_direction = false;
         let mut coordinates = smallvec![0; self.dimension as usize];
         let mut remaining_index = index;
+        let mut stride = self.length;
 
         // Iterate dimensions from highest to lowest (e.g., Z -> Y -> X)
         for dim_idx in (0..self.dimension).rev() {
-            let stride = self.size.pow(dim_idx);
+            let side = self.sides[dim_idx as usize];
+            stride /= side;
             let raw_coordinate = remaining_index / stride;
 
             // If we are in a reversed section, invert the coordinate
             coordinates[dim_idx as usize] = if should_reverse_direction {
-                self.size - raw_coordinate - 1
+                side - raw_coordinate - 1
             } else {
                 raw_coordinate
             };
@@ -88,18 +136,20 @@ impl SpaceCurve for Scan {
             "point dimension mismatch"
         );
         debug_assert!(
-            point.iter().all(|&c| c < self.size),
+            point.iter().zip(&self.sides).all(|(&c, &side)| c < side),
             "point coordinate out of bounds"
         );
         let mut should_reverse_direction = false;
         let mut index_accumulator = 0;
+        let mut stride = self.length;
 
         // Iterate dimensions from highest to lowest to reconstruct the index
         for (dim_idx, &coordinate) in point.iter().enumerate().rev() {
-            let stride = self.size.pow(dim_idx as u32);
+            let side = self.sides[dim_idx];
+            stride /= side;
 
             let actual_value = if should_reverse_direction {
-                self.size - coordinate - 1
+                side - coordinate - 1
             } else {
                 coordinate
             };
@@ -113,6 +163,14 @@ impl SpaceCurve for Scan {
         }
         index_accumulator
     }
+
+    fn next_point(&self, point: &Point) -> Option<Point> {
+        self.step(point, true)
+    }
+
+    fn prev_point(&self, point: &Point) -> Option<Point> {
+        self.step(point, false)
+    }
 }
 
 #[cfg(test)]
@@ -173,4 +231,30 @@ mod tests {
             assert_eq!(s.index(&p), idx, "roundtrip failed at {idx}");
         }
     }
+
+    #[test]
+    fn rectangular_grids_snake_continuously() {
+        for sides in [[4, 3, 1], [2, 5, 3], [1, 4, 2]] {
+            let s = Scan::from_sides(&sides).unwrap();
+            assert_eq!(s.sides(), sides);
+            assert_eq!(s.length(), sides.iter().product::<u32>());
+            let mut prev = s.point(0);
+            for idx in 0..s.length() {
+                let p = s.point(idx);
+                assert!(p.iter().zip(&sides).all(|(&c, &side)| c < side));
+                assert_eq!(s.index(&p), idx, "roundtrip failed for {sides:?} at {idx}");
+                if idx > 0 {
+                    assert_eq!(s.next_point(&prev), Some(p.clone()));
+                    assert_eq!(prev.distance(&p), 1.0);
+                }
+                prev = p;
+            }
+            assert_eq!(s.next_point(&prev), None);
+        }
+        assert_eq!(
+            Scan::from_sides(&[3, 2]).unwrap().point(3),
+            Point::new(vec![2, 1])
+        );
+        assert!(Scan::from_sides(&[3, 0]).is_err());
+    }
 }
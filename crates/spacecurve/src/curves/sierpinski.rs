@@ -0,0 +1,253 @@
+use smallvec::smallvec;
+
+use crate::{
+    error::{self, Error},
+    point::Point,
+    spacecurve::SpaceCurve,
+    spec::{GridSpec, INDEX_BITS},
+};
+
+/// Direction in which cell centres are nudged so each lies strictly inside one triangle.
+///
+/// Cell centres sit on the diagonal edges of the triangles; no diagonal is parallel to this.
+const NUDGE: [i64; 2] = [3, 1];
+
+/// A right isosceles triangle of the recursive subdivision, in grid units.
+///
+/// The curve enters at `a`, passes the right angle `r` and leaves at `b`.
+#[derive(Debug, Clone, Copy)]
+struct Triangle {
+    /// Entry corner, one end of the hypotenuse.
+    a: [i64; 2],
+    /// Right-angle corner.
+    r: [i64; 2],
+    /// Exit corner, the other end of the hypotenuse.
+    b: [i64; 2],
+}
+
+impl Triangle {
+    /// The two halves cut by the altitude from `r`, in curve order.
+    fn split(&self) -> (Self, Self) {
+        let m = [(self.a[0] + self.b[0]) / 2, (self.a[1] + self.b[1]) / 2];
+        (
+            Self {
+                a: self.a,
+                r: m,
+                b: self.r,
+            },
+            Self {
+                a: self.r,
+                r: m,
+                b: self.b,
+            },
+        )
+    }
+
+    /// Whether the triangle is half of a single grid cell.
+    fn is_half_cell(&self) -> bool {
+        (self.r[0] - self.a[0]).abs() + (self.r[1] - self.a[1]).abs() == 1
+    }
+
+    /// Number of nudged cell centres inside the triangle.
+    ///
+    /// Centres strictly inside are counted directly, and the centres on each diagonal edge
+    /// belong to the triangle when the nudge points inward across that edge.
+    fn cells(&self) -> u32 {
+        let inward = |from: [i64; 2], to: [i64; 2]| {
+            (to[0] - from[0]) * NUDGE[0] + (to[1] - from[1]) * NUDGE[1] > 0
+        };
+        if self.r[0] == self.a[0] || self.r[1] == self.a[1] {
+            // Legs along the axes; the hypotenuse is diagonal.
+            let leg = (self.r[0] - self.a[0]).abs() + (self.r[1] - self.a[1]).abs();
+            let midpoint = [self.a[0] + self.b[0], self.a[1] + self.b[1]];
+            let apex = [2 * self.r[0], 2 * self.r[1]];
+            let edge = if inward(midpoint, apex) { leg } else { 0 };
+            (leg * (leg - 1) / 2 + edge) as u32
+        } else {
+            // Hypotenuse along an axis; both legs are diagonal.
+            let hyp = (self.b[0] - self.a[0]).abs() + (self.b[1] - self.a[1]).abs();
+            // A leg's inward normal points along the other leg.
+            let edges = [self.a, self.b]
+                .into_iter()
+                .filter(|&end| inward(self.r, end))
+                .count() as i64;
+            (hyp * hyp / 4 - hyp / 2 + edges * hyp / 2) as u32
+        }
+    }
+}
+
+/// Whether the nudged cell centre `centre`, in doubled coordinates, lies on the same side
+/// of the line through `from` and `to` as `reference`.
+fn same_side(from: [i64; 2], to: [i64; 2], reference: [i64; 2], centre: [i64; 2]) -> bool {
+    let cross = |p: [i64; 2], scale: i64| {
+        (to[0] - from[0]) * (p[1] - scale * from[1]) - (to[1] - from[1]) * (p[0] - scale * from[0])
+    };
+    let side = match cross(centre, 2) {
+        0 => (to[0] - from[0]) * NUDGE[1] - (to[1] - from[1]) * NUDGE[0],
+        side => side,
+    };
+    (side > 0) == (cross(reference, 1) > 0)
+}
+
+/// The Sierpiński–Knopp curve on square grids whose side is a power of two.
+///
+/// The square is cut along its diagonal into two right isosceles triangles, and each
+/// triangle is cut recursively by the altitude from its right angle, the curve passing
+/// through both halves in turn. Grid cells are visited in the order the subdivision reaches
+/// them: every cell is split by a diagonal into two half-cell triangles, and is ordered by
+/// the half its centre falls into after a small fixed nudge. Consecutive cells touch along
+/// an edge or at a corner.
+#[derive(Debug)]
+pub struct Sierpinski {
+    /// Side length per dimension.
+    size: u32,
+    /// Cached total number of points in the curve.
+    length: u32,
+}
+
+impl Sierpinski {
+    /// Construct a 2D Sierpiński–Knopp curve with the given side length.
+    ///
+    /// The dimension must be 2 and the size a power of two with `size^2` fitting in a `u32`.
+    pub fn from_dimensions(dimension: u32, size: u32) -> error::Result<Self> {
+        if dimension != 2 {
+            return Err(Error::Shape(format!(
+                "Sierpinski curves are 2-dimensional, got dimension {dimension}"
+            )));
+        }
+        let spec = GridSpec::power_of_two(dimension, size)?;
+        spec.require_index_bits_lt(INDEX_BITS)?;
+        Ok(Self {
+            size,
+            length: spec.length(),
+        })
+    }
+
+    /// The two triangles covering the grid, in curve order.
+    fn roots(&self) -> [Triangle; 2] {
+        let s = i64::from(self.size);
+        [
+            Triangle {
+                a: [0, 0],
+                r: [0, s],
+                b: [s, s],
+            },
+            Triangle {
+                a: [s, s],
+                r: [s, 0],
+                b: [0, 0],
+            },
+        ]
+    }
+}
+
+impl SpaceCurve for Sierpinski {
+    fn name(&self) -> &'static str {
+        "Sierpinski"
+    }
+
+    fn info(&self) -> &'static str {
+        "Sierpinski-Knopp curve from recursive triangle bisection.\n\
+        Splits space into right triangles rather than squares, giving\n\
+        a different locality trade-off from Hilbert's quadrants."
+    }
+
+    fn length(&self) -> u32 {
+        self.length
+    }
+
+    fn dimensions(&self) -> u32 {
+        2
+    }
+
+    fn point(&self, index: u32) -> Point {
+        debug_assert!(index < self.length, "index out of range");
+        let [first, second] = self.roots();
+        let mut rank = index;
+        let mut triangle = if rank < first.cells() {
+            first
+        } else {
+            rank -= first.cells();
+            second
+        };
+        while !triangle.is_half_cell() {
+            let (head, tail) = triangle.split();
+            let cells = head.cells();
+            triangle = if rank < cells {
+                head
+            } else {
+                rank -= cells;
+                tail
+            };
+        }
+        let corner =
+            |axis: usize| triangle.a[axis].min(triangle.r[axis]).min(triangle.b[axis]) as u32;
+        Point::new_with_dimension(2, smallvec![corner(0), corner(1)])
+    }
+
+    fn index(&self, p: &Point) -> u32 {
+        debug_assert_eq!(p.len(), 2, "point dimension mismatch");
+        debug_assert!(
+            p.iter().all(|&coord| coord < self.size),
+            "point coordinate out of bounds"
+        );
+        let centre = [2 * i64::from(p[0]) + 1, 2 * i64::from(p[1]) + 1];
+        let [first, second] = self.roots();
+        let mut index = 0;
+        let mut triangle = if same_side(first.a, first.b, first.r, centre) {
+            first
+        } else {
+            index += first.cells();
+            second
+        };
+        while !triangle.is_half_cell() {
+            let (head, tail) = triangle.split();
+            triangle = if same_side(head.r, head.b, head.a, centre) {
+                head
+            } else {
+                index += head.cells();
+                tail
+            };
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_steps_to_touching_cells() {
+        for size in [1, 2, 4, 8, 32] {
+            let curve = Sierpinski::from_dimensions(2, size).unwrap();
+            let mut prev: Option<Point> = None;
+            for i in 0..curve.length() {
+                let p = curve.point(i);
+                assert_eq!(curve.index(&p), i, "size {size} at {i}");
+                if let Some(prev) = prev {
+                    let step = p.iter().zip(prev.iter()).map(|(&a, &b)| a.abs_diff(b));
+                    assert_eq!(step.max(), Some(1), "size {size} at {i}");
+                }
+                prev = Some(p);
+            }
+        }
+    }
+
+    #[test]
+    fn triangle_counts_cover_the_grid() {
+        let curve = Sierpinski::from_dimensions(2, 16).unwrap();
+        let [first, second] = curve.roots();
+        assert_eq!(first.cells() + second.cells(), 256);
+        let (head, tail) = first.split();
+        assert_eq!(head.cells() + tail.cells(), first.cells());
+    }
+
+    #[test]
+    fn guards() {
+        assert!(Sierpinski::from_dimensions(3, 4).is_err());
+        assert!(Sierpinski::from_dimensions(2, 6).is_err());
+        assert!(Sierpinski::from_dimensions(2, 1 << 15).is_ok());
+        assert!(Sierpinski::from_dimensions(2, 1 << 16).is_err());
+    }
+}
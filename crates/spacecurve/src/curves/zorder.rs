@@ -1,15 +1,28 @@
-use crate::{error, ops, point, spacecurve::SpaceCurve, spec::GridSpec};
+use smallvec::SmallVec;
+
+use crate::{
+    error, ops, point,
+    spacecurve::{SpaceCurve, out_of_range},
+    spec::{GridSpec, INDEX_BITS, RectGridSpec, WIDE_INDEX_BITS},
+    table::StateTable,
+};
 
 /// An implementation of the Z Order curve.
 #[derive(Debug)]
 pub struct ZOrder {
-    /// The bit width of each co-ordinate
+    /// The bit width of each co-ordinate on a cubic grid.
+    ///
+    /// On a rectangular grid the axes differ, and this is the width of the widest one; see
+    /// [`SpaceCurve::sides`] for every axis.
     pub bitwidth: u32,
     /// The number of dimensions
     pub dimension: u32,
     /// Cached total number of points (`2^(bitwidth * dimension)`), computed
     /// once at construction with checked math to avoid overflow.
-    length: u32,
+    length: u64,
+    /// Bit width of each axis on a rectangular grid, or `None` when every axis has
+    /// `bitwidth` bits.
+    widths: Option<SmallVec<[u32; 4]>>,
 }
 
 impl ZOrder {
@@ -18,12 +31,82 @@ impl ZOrder {
     /// a number 2**n, where n is an integer, or the result is an error.
     pub fn from_dimensions(dimension: u32, size: u32) -> error::Result<Self> {
         let spec = GridSpec::power_of_two(dimension, size)?;
-        spec.require_index_bits_lt(32)?;
-        let bitwidth = spec.bits_per_axis().unwrap();
+        spec.require_index_bits_lt(INDEX_BITS)?;
+        Ok(Self::from_spec(&spec))
+    }
+
+    /// Construct a Z Order curve over a rectangular grid with the given side per axis, each a
+    /// power of two.
+    ///
+    /// Coordinate bits are interleaved from the least significant up, and an axis drops out
+    /// once its bits run out, so the longer axes' high bits end up at the top of the index.
+    pub fn from_sides(sides: &[u32]) -> error::Result<Self> {
+        let spec = RectGridSpec::power_of_two(sides)?;
+        let widths: SmallVec<[u32; 4]> = spec
+            .sides()
+            .iter()
+            .map(|side| side.trailing_zeros())
+            .collect();
         Ok(Self {
             dimension: spec.dimension(),
-            bitwidth,
-            length: spec.length(),
+            bitwidth: widths.iter().copied().max().unwrap_or(0),
+            length: u64::from(spec.length()),
+            widths: spec.cube_side().is_none().then_some(widths),
+        })
+    }
+
+    /// Construct a Z Order curve addressed with 64-bit indices, allowing grids of up to 63
+    /// index bits, such as Morton keys over a 2^21 grid in three dimensions.
+    ///
+    /// Use [`SpaceCurve::index_u64`] and [`SpaceCurve::point_u64`] to reach the whole grid.
+    pub fn from_dimensions_u64(dimension: u32, size: u32) -> error::Result<Self> {
+        let spec = GridSpec::power_of_two_u64(dimension, size)?;
+        spec.require_index_bits_lt(WIDE_INDEX_BITS)?;
+        Ok(Self::from_spec(&spec))
+    }
+
+    /// Build the curve for a validated power-of-two `spec`.
+    fn from_spec(spec: &GridSpec) -> Self {
+        Self {
+            dimension: spec.dimension(),
+            bitwidth: spec.bits_per_axis().unwrap(),
+            length: spec.length_u64(),
+            widths: None,
+        }
+    }
+
+    /// Whether indices need more bits than the `u32` bit tricks handle.
+    fn is_wide(&self) -> bool {
+        self.length > u64::from(u32::MAX)
+    }
+
+    /// The point at `index`, which must be below the curve's length.
+    fn locate(&self, index: u64) -> point::Point {
+        let coords = match &self.widths {
+            Some(widths) => ops::deinterleave_widths(widths, index),
+            None => ops::deinterleave_lsb_u64(self.dimension, self.bitwidth, index),
+        };
+        point::Point::new_with_dimension(self.dimension, coords)
+    }
+
+    /// Check in debug builds that `p` lies on the grid.
+    fn check_point(&self, p: &point::Point) {
+        debug_assert_eq!(p.len(), self.dimension as usize, "point dimension mismatch");
+        debug_assert!(
+            p.iter()
+                .zip(self.sides())
+                .all(|(&coord, side)| coord < side),
+            "point coordinate out of bounds"
+        );
+    }
+
+    /// The state table walking this curve: one state whose digits name their cells directly.
+    ///
+    /// `None` on rectangular grids, where axes drop out of the interleaving as their bits run
+    /// out and the levels no longer share one digit width.
+    pub fn state_table(&self) -> Option<StateTable> {
+        self.widths.is_none().then(|| {
+            StateTable::explore(self.dimension, self.bitwidth, (), |(), digit| (digit, ()))
         })
     }
 }
@@ -39,31 +122,71 @@ impl SpaceCurve for ZOrder {
         neighborhood worse than Hilbert/H-curve and may exhibit long jumps."
     }
     fn length(&self) -> u32 {
-        self.length
+        u32::try_from(self.length).expect("curve has more than u32::MAX points; use length_u64")
     }
     fn dimensions(&self) -> u32 {
         self.dimension
     }
+    fn nested_blocks(&self) -> bool {
+        self.widths.is_none()
+    }
+    fn sides(&self) -> Vec<u32> {
+        match &self.widths {
+            Some(widths) => widths.iter().map(|width| 1 << width).collect(),
+            None => vec![1 << self.bitwidth; self.dimension as usize],
+        }
+    }
     fn point(&self, index: u32) -> point::Point {
-        debug_assert!(index < self.length, "index out of range");
+        debug_assert!(u64::from(index) < self.length, "index out of range");
+        if self.is_wide() || self.widths.is_some() {
+            return self.locate(u64::from(index));
+        }
         point::Point::new_with_dimension(
             self.dimension,
             ops::deinterleave_lsb(self.dimension, self.bitwidth, index),
         )
     }
     fn index(&self, p: &point::Point) -> u32 {
-        debug_assert_eq!(p.len(), self.dimension as usize, "point dimension mismatch");
-        let side = if self.bitwidth == 0 {
-            1
-        } else {
-            1u32 << self.bitwidth
-        };
-        debug_assert!(
-            p.iter().all(|&coord| coord < side),
-            "point coordinate out of bounds"
-        );
+        if self.is_wide() || self.widths.is_some() {
+            return u32::try_from(self.index_u64(p)).expect("index exceeds u32; use index_u64");
+        }
+        self.check_point(p);
         ops::interleave_lsb(&p[..], self.bitwidth)
     }
+    fn point_u64(&self, index: u64) -> error::Result<point::Point> {
+        if index >= self.length {
+            return Err(out_of_range(index, self.length));
+        }
+        Ok(self.locate(index))
+    }
+    fn index_u64(&self, p: &point::Point) -> u64 {
+        self.check_point(p);
+        match &self.widths {
+            Some(widths) => ops::interleave_widths(&p[..], widths),
+            None => ops::interleave_lsb_u64(&p[..], self.bitwidth),
+        }
+    }
+    fn length_u64(&self) -> u64 {
+        self.length
+    }
+    fn next_point(&self, p: &point::Point) -> Option<point::Point> {
+        if self.widths.is_some() {
+            let index = self.index_u64(p) + 1;
+            return (index < self.length).then(|| self.locate(index));
+        }
+        let mut next = p.clone();
+        ops::morton_step(&mut next.0, self.bitwidth, true).then_some(next)
+    }
+    fn prev_point(&self, p: &point::Point) -> Option<point::Point> {
+        if self.widths.is_some() {
+            return self
+                .index_u64(p)
+                .checked_sub(1)
+                .map(|index| self.locate(index));
+        }
+        let mut prev = p.clone();
+        ops::morton_step(&mut prev.0, self.bitwidth, false).then_some(prev)
+    }
 }
 
 #[cfg(test)]
@@ -131,4 +254,98 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn wide_keys_reach_beyond_u32() {
+        let curve = ZOrder::from_dimensions_u64(3, 1 << 21).unwrap();
+        assert_eq!(curve.length_u64(), 1 << 63);
+        let point = point::Point::new(vec![0x1f_ffff, 3, 0x10_0000]);
+        let key = curve.index_u64(&point);
+        assert_eq!(key >> 60, 0b101);
+        assert_eq!(curve.point(6), point::Point::new(vec![0, 1, 1]));
+        assert_eq!(curve.point_u64(key).unwrap(), point);
+        assert!(curve.point_u64(1 << 63).is_err());
+
+        let narrow = ZOrder::from_dimensions(2, 16).unwrap();
+        let wide = ZOrder::from_dimensions_u64(2, 16).unwrap();
+        for i in 0..narrow.length() {
+            let p = narrow.point(i);
+            assert_eq!(wide.index_u64(&p), u64::from(i));
+        }
+        assert!(ZOrder::from_dimensions_u64(4, 1 << 16).is_err());
+    }
+
+    #[test]
+    fn u32_boundary() {
+        let curve = ZOrder::from_dimensions_u64(3, 1 << 11).unwrap();
+        let last = curve.point(u32::MAX);
+        assert_eq!(curve.index(&last), u32::MAX);
+        let past = curve.point_u64(1 << 32).unwrap();
+        assert_eq!(past, point::Point::new(vec![0, 0, 1 << 10]));
+        assert_eq!(curve.index_u64(&past), 1 << 32);
+        assert_eq!(curve.next_point(&last), Some(past));
+    }
+
+    #[test]
+    #[should_panic(expected = "use length_u64")]
+    fn length_panics_beyond_u32() {
+        ZOrder::from_dimensions_u64(2, 1 << 16).unwrap().length();
+    }
+
+    #[test]
+    #[should_panic(expected = "use index_u64")]
+    fn index_panics_beyond_u32() {
+        let curve = ZOrder::from_dimensions_u64(3, 1 << 11).unwrap();
+        curve.index(&point::Point::new(vec![0, 0, 1 << 10]));
+    }
+
+    #[test]
+    fn rectangular_grids_interleave_until_axes_run_out() {
+        let curve = ZOrder::from_sides(&[4, 2]).unwrap();
+        assert_eq!(curve.sides(), [4, 2]);
+        let order: Vec<Vec<u32>> = (0..curve.length()).map(|i| curve.point(i).into()).collect();
+        assert_eq!(
+            order,
+            [
+                [0, 0],
+                [1, 0],
+                [0, 1],
+                [1, 1],
+                [2, 0],
+                [3, 0],
+                [2, 1],
+                [3, 1]
+            ]
+        );
+
+        let curve = ZOrder::from_sides(&[512, 64]).unwrap();
+        assert_eq!(curve.length(), 512 * 64);
+        for i in (0..curve.length()).step_by(97) {
+            let p = curve.point(i);
+            assert_eq!(curve.index(&p), i);
+            assert_eq!(curve.next_point(&p), Some(curve.point(i + 1)));
+        }
+        // Equal sides give the usual cubic curve.
+        let cube = ZOrder::from_sides(&[8, 8, 8]).unwrap();
+        assert_eq!(
+            cube.point(77),
+            ZOrder::from_dimensions(3, 8).unwrap().point(77)
+        );
+        assert!(ZOrder::from_sides(&[8, 6]).is_err());
+    }
+
+    #[test]
+    fn state_tables_only_walk_cubic_grids() {
+        assert!(
+            ZOrder::from_sides(&[16, 4])
+                .unwrap()
+                .state_table()
+                .is_none()
+        );
+        let cube = ZOrder::from_sides(&[8, 8]).unwrap();
+        let table = cube.state_table().unwrap();
+        let walked: Vec<_> = table.points(0..cube.length()).collect();
+        let looked_up: Vec<_> = (0..cube.length()).map(|i| cube.point(i)).collect();
+        assert_eq!(walked, looked_up);
+    }
 }
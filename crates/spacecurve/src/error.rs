@@ -13,6 +13,9 @@ pub enum Error {
     /// Errors where size exceeds limits or constraints.
     #[error("Size error: {0}")]
     Size(String),
+    /// A value tagged for one curve was used with another.
+    #[error("Curve mismatch: {0}")]
+    Mismatch(String),
     /// Unknown pattern or identifier error.
     #[error("Unknown: {0}")]
     Unknown(String),
@@ -0,0 +1,86 @@
+//! Stable hashes of a curve's full index-to-point mapping.
+//!
+//! A fingerprint changes whenever any index maps to a different point, so caches, lookup
+//! tables and golden images keyed on it notice when a curve's definition changes between
+//! revisions. The hash is 64-bit FNV-1a over little-endian `u32`s: the dimension, the length,
+//! then every coordinate of every point in index order. It depends on nothing but the mapping,
+//! so it is the same on every platform and build.
+
+use crate::{error, spacecurve::SpaceCurve};
+
+/// FNV-1a 64-bit offset basis.
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a 64-bit prime.
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hash of every point of `curve` in index order, computed one point at a time.
+///
+/// Fails when the curve has more than `u32::MAX` points, as its length has no `u32` encoding.
+pub fn fingerprint(curve: &dyn SpaceCurve) -> error::Result<u64> {
+    let length = curve.try_length()?;
+    let mut hash = OFFSET_BASIS;
+    let mut feed = |value: u32| {
+        for byte in value.to_le_bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+    feed(curve.dimensions());
+    feed(length);
+    for point in curve.iter() {
+        for &coordinate in point.iter() {
+            feed(coordinate);
+        }
+    }
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve_from_name;
+
+    fn of(name: &str, dimension: u32, size: u32) -> u64 {
+        fingerprint(curve_from_name(name, dimension, size).unwrap().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn repeatable() {
+        assert_eq!(of("hilbert", 2, 8), of("hilbert", 2, 8));
+    }
+
+    #[test]
+    fn distinguishes_curves_and_grids() {
+        let prints = [
+            of("hilbert", 2, 8),
+            of("zorder", 2, 8),
+            of("hilbert", 2, 4),
+            of("hilbert", 3, 4),
+            of("scan", 1, 64),
+        ];
+        for (i, a) in prints.iter().enumerate() {
+            for b in &prints[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn single_point_hashes_header_and_origin() {
+        // A single point at the origin of a 1D grid: dimension 1, length 1, coordinate 0.
+        let mut expected = OFFSET_BASIS;
+        for byte in [1u32, 1, 0].iter().flat_map(|v| v.to_le_bytes()) {
+            expected = (expected ^ u64::from(byte)).wrapping_mul(PRIME);
+        }
+        assert_eq!(of("scan", 1, 1), expected);
+    }
+
+    #[test]
+    fn rejects_wide_curves() {
+        let wide = curve_from_name("zorder", 2, 1 << 16).unwrap();
+        assert!(matches!(
+            fingerprint(wide.as_ref()),
+            Err(error::Error::Size(_))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+//! Table-driven 2D Hilbert conversions for tight loops.
+//!
+//! These free functions compute the same mapping as [`Hilbert`](crate::curves::hilbert::Hilbert)
+//! on a 2D grid of side `2^order`, but without a trait object, a [`Point`](crate::point::Point)
+//! or any allocation. Each level of the index costs one lookup in a transition table of the
+//! curve's four states. They are the fast path for code that converts single coordinates in a
+//! hot loop; use the [`SpaceCurve`](crate::SpaceCurve) API everywhere else.
+
+/// Child cell (`x` in bit 0, `y` in bit 1) and next state for each state and index digit.
+const POINT: [[(u8, u8); 4]; 4] = [
+    [(0, 1), (1, 0), (3, 0), (2, 2)],
+    [(0, 0), (2, 1), (3, 1), (1, 3)],
+    [(3, 3), (1, 2), (0, 2), (2, 0)],
+    [(3, 2), (2, 3), (0, 3), (1, 1)],
+];
+
+/// Index digit and next state for each state and child cell, inverting [`POINT`].
+const INDEX: [[(u8, u8); 4]; 4] = [
+    [(0, 1), (1, 0), (3, 2), (2, 0)],
+    [(0, 0), (3, 3), (1, 1), (2, 1)],
+    [(2, 2), (1, 2), (3, 0), (0, 3)],
+    [(2, 3), (3, 1), (1, 3), (0, 2)],
+];
+
+/// Hilbert index of `(x, y)` on a grid of side `2^order`.
+///
+/// `order` must be at most 32 and both coordinates must be below `2^order`.
+#[inline]
+pub fn index(x: u32, y: u32, order: u32) -> u64 {
+    debug_assert!(order <= 32, "order must be at most 32");
+    let mut index = 0;
+    let mut state = 0;
+    for level in (0..order).rev() {
+        let cell = ((x >> level) & 1) | (((y >> level) & 1) << 1);
+        let (digit, next) = INDEX[state][cell as usize];
+        index = (index << 2) | u64::from(digit);
+        state = usize::from(next);
+    }
+    index
+}
+
+/// Coordinates `[x, y]` of Hilbert index `index` on a grid of side `2^order`.
+///
+/// `order` must be at most 32 and `index` must be below `4^order`.
+#[inline]
+pub fn point(index: u64, order: u32) -> [u32; 2] {
+    debug_assert!(order <= 32, "order must be at most 32");
+    let mut coords = [0; 2];
+    let mut state = 0;
+    for level in (0..order).rev() {
+        let digit = (index >> (2 * level)) & 3;
+        let (cell, next) = POINT[state][digit as usize];
+        coords[0] |= u32::from(cell & 1) << level;
+        coords[1] |= u32::from(cell >> 1) << level;
+        state = usize::from(next);
+    }
+    coords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SpaceCurve, curves::hilbert::Hilbert, point::Point, table::state_table};
+
+    #[test]
+    fn tables_match_state_table() {
+        let table = state_table("hilbert", 2, 4).unwrap().unwrap();
+        let flat: Vec<_> = POINT
+            .iter()
+            .flatten()
+            .map(|&(cell, next)| (u32::from(cell), u32::from(next)))
+            .collect();
+        assert_eq!(flat, table.transitions);
+        for (state, row) in POINT.iter().enumerate() {
+            for (digit, &(cell, next)) in row.iter().enumerate() {
+                assert_eq!(INDEX[state][usize::from(cell)], (digit as u8, next));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_curve() {
+        for order in [0, 1, 3, 5] {
+            let curve = Hilbert::from_dimensions(2, 1 << order).unwrap();
+            for i in 0..curve.length() {
+                let p = curve.point(i);
+                assert_eq!(point(u64::from(i), order), [p[0], p[1]]);
+                assert_eq!(index(p[0], p[1], order), u64::from(i));
+            }
+        }
+        let curve = Hilbert::from_dimensions(2, 1 << 15).unwrap();
+        for (x, y) in [(12_345, 31_000), (32_767, 0), (1, 32_766)] {
+            let i = curve.index(&Point::new(vec![x, y]));
+            assert_eq!(index(x, y, 15), u64::from(i));
+        }
+    }
+
+    #[test]
+    fn round_trips_at_full_order() {
+        for i in [0, 1, u64::MAX, 0x0123_4567_89ab_cdef] {
+            let [x, y] = point(i, 32);
+            assert_eq!(index(x, y, 32), i);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Table-driven 3D Hilbert conversions for tight loops.
+//!
+//! The 3D counterpart of [`hilbert2d`](crate::hilbert2d): the same mapping as
+//! [`Hilbert`](crate::curves::hilbert::Hilbert) on a cube of side `2^order`, walked through a
+//! transition table of the curve's twelve states with no trait object or allocation.
+
+/// Child cell (`x` in bit 0, `y` in bit 1, `z` in bit 2) and next state for each state and
+/// index digit.
+const POINT: [[(u8, u8); 8]; 12] = [
+    [
+        (0, 1),
+        (2, 2),
+        (3, 2),
+        (1, 3),
+        (5, 3),
+        (7, 4),
+        (6, 4),
+        (4, 5),
+    ],
+    [
+        (0, 2),
+        (1, 0),
+        (5, 0),
+        (4, 6),
+        (6, 6),
+        (7, 7),
+        (3, 7),
+        (2, 8),
+    ],
+    [
+        (0, 0),
+        (4, 1),
+        (6, 1),
+        (2, 9),
+        (3, 9),
+        (7, 10),
+        (5, 10),
+        (1, 11),
+    ],
+    [
+        (3, 10),
+        (1, 8),
+        (0, 8),
+        (2, 0),
+        (6, 0),
+        (4, 9),
+        (5, 9),
+        (7, 6),
+    ],
+    [
+        (5, 11),
+        (1, 6),
+        (3, 6),
+        (7, 8),
+        (6, 8),
+        (2, 5),
+        (0, 5),
+        (4, 0),
+    ],
+    [
+        (6, 9),
+        (7, 7),
+        (3, 7),
+        (2, 10),
+        (0, 10),
+        (1, 0),
+        (5, 0),
+        (4, 4),
+    ],
+    [
+        (5, 4),
+        (4, 11),
+        (0, 11),
+        (1, 1),
+        (3, 1),
+        (2, 3),
+        (6, 3),
+        (7, 9),
+    ],
+    [
+        (6, 5),
+        (4, 9),
+        (5, 9),
+        (7, 11),
+        (3, 11),
+        (1, 8),
+        (0, 8),
+        (2, 1),
+    ],
+    [
+        (3, 3),
+        (7, 10),
+        (5, 10),
+        (1, 4),
+        (0, 4),
+        (4, 1),
+        (6, 1),
+        (2, 7),
+    ],
+    [
+        (6, 7),
+        (2, 5),
+        (0, 5),
+        (4, 2),
+        (5, 2),
+        (1, 6),
+        (3, 6),
+        (7, 3),
+    ],
+    [
+        (3, 8),
+        (2, 3),
+        (6, 3),
+        (7, 5),
+        (5, 5),
+        (4, 11),
+        (0, 11),
+        (1, 2),
+    ],
+    [
+        (5, 6),
+        (7, 4),
+        (6, 4),
+        (4, 7),
+        (0, 7),
+        (2, 2),
+        (3, 2),
+        (1, 10),
+    ],
+];
+
+/// Index digit and next state for each state and child cell, inverting [`POINT`].
+const INDEX: [[(u8, u8); 8]; 12] = [
+    [
+        (0, 1),
+        (3, 3),
+        (1, 2),
+        (2, 2),
+        (7, 5),
+        (4, 3),
+        (6, 4),
+        (5, 4),
+    ],
+    [
+        (0, 2),
+        (1, 0),
+        (7, 8),
+        (6, 7),
+        (3, 6),
+        (2, 0),
+        (4, 6),
+        (5, 7),
+    ],
+    [
+        (0, 0),
+        (7, 11),
+        (3, 9),
+        (4, 9),
+        (1, 1),
+        (6, 10),
+        (2, 1),
+        (5, 10),
+    ],
+    [
+        (2, 8),
+        (1, 8),
+        (3, 0),
+        (0, 10),
+        (5, 9),
+        (6, 9),
+        (4, 0),
+        (7, 6),
+    ],
+    [
+        (6, 5),
+        (1, 6),
+        (5, 5),
+        (2, 6),
+        (7, 0),
+        (0, 11),
+        (4, 8),
+        (3, 8),
+    ],
+    [
+        (4, 10),
+        (5, 0),
+        (3, 10),
+        (2, 7),
+        (7, 4),
+        (6, 0),
+        (0, 9),
+        (1, 7),
+    ],
+    [
+        (2, 11),
+        (3, 1),
+        (5, 3),
+        (4, 1),
+        (1, 11),
+        (0, 4),
+        (6, 3),
+        (7, 9),
+    ],
+    [
+        (6, 8),
+        (5, 8),
+        (7, 1),
+        (4, 11),
+        (1, 9),
+        (2, 9),
+        (0, 5),
+        (3, 11),
+    ],
+    [
+        (4, 4),
+        (3, 4),
+        (7, 7),
+        (0, 3),
+        (5, 1),
+        (2, 10),
+        (6, 1),
+        (1, 10),
+    ],
+    [
+        (2, 5),
+        (5, 6),
+        (1, 5),
+        (6, 6),
+        (3, 2),
+        (4, 2),
+        (0, 7),
+        (7, 3),
+    ],
+    [
+        (6, 11),
+        (7, 2),
+        (1, 3),
+        (0, 8),
+        (5, 11),
+        (4, 5),
+        (2, 3),
+        (3, 5),
+    ],
+    [
+        (4, 7),
+        (7, 10),
+        (5, 2),
+        (6, 2),
+        (3, 7),
+        (0, 6),
+        (2, 4),
+        (1, 4),
+    ],
+];
+
+/// Hilbert index of `(x, y, z)` on a cube of side `2^order`.
+///
+/// `order` must be at most 21 and every coordinate must be below `2^order`.
+#[inline]
+pub fn index(x: u32, y: u32, z: u32, order: u32) -> u64 {
+    debug_assert!(order <= 21, "order must be at most 21");
+    let mut index = 0;
+    let mut state = 0;
+    for level in (0..order).rev() {
+        let cell = ((x >> level) & 1) | (((y >> level) & 1) << 1) | (((z >> level) & 1) << 2);
+        let (digit, next) = INDEX[state][cell as usize];
+        index = (index << 3) | u64::from(digit);
+        state = usize::from(next);
+    }
+    index
+}
+
+/// Coordinates `[x, y, z]` of Hilbert index `index` on a cube of side `2^order`.
+///
+/// `order` must be at most 21 and `index` must be below `8^order`.
+#[inline]
+pub fn point(index: u64, order: u32) -> [u32; 3] {
+    debug_assert!(order <= 21, "order must be at most 21");
+    let mut coords = [0; 3];
+    let mut state = 0;
+    for level in (0..order).rev() {
+        let digit = (index >> (3 * level)) & 7;
+        let (cell, next) = POINT[state][digit as usize];
+        for (axis, coord) in coords.iter_mut().enumerate() {
+            *coord |= u32::from((cell >> axis) & 1) << level;
+        }
+        state = usize::from(next);
+    }
+    coords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SpaceCurve, curves::hilbert::Hilbert, table::state_table};
+
+    #[test]
+    fn tables_match_state_table() {
+        let table = state_table("hilbert", 3, 4).unwrap().unwrap();
+        let flat: Vec<_> = POINT
+            .iter()
+            .flatten()
+            .map(|&(cell, next)| (u32::from(cell), u32::from(next)))
+            .collect();
+        assert_eq!(flat, table.transitions);
+        for (state, row) in POINT.iter().enumerate() {
+            for (digit, &(cell, next)) in row.iter().enumerate() {
+                assert_eq!(INDEX[state][usize::from(cell)], (digit as u8, next));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_curve() {
+        for order in [0, 1, 2, 4] {
+            let curve = Hilbert::from_dimensions(3, 1 << order).unwrap();
+            for i in 0..curve.length() {
+                let p = curve.point(i);
+                assert_eq!(point(u64::from(i), order), [p[0], p[1], p[2]]);
+                assert_eq!(index(p[0], p[1], p[2], order), u64::from(i));
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_at_full_order() {
+        for i in [0, 1, (1 << 63) - 1, 0x0123_4567_89ab_cdef] {
+            let [x, y, z] = point(i, 21);
+            assert_eq!(index(x, y, z, 21), i);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Curve-ordered memory layouts for dense matrices.
+//!
+//! A [`MatrixLayout`] cuts a `rows × cols` matrix into square tiles of `block × block`
+//! elements, stores each tile contiguously in row-major order, and orders the tiles along a
+//! curve. With Morton or Hilbert order, tiles that are close in the matrix are close in memory
+//! at every scale, which is what makes these layouts cache-oblivious.
+//!
+//! Curves cover square grids, so the grid of tiles is padded to a square curve grid. Padded
+//! tiles are skipped when assigning memory, leaving offsets dense: the only padding in memory
+//! is the unused part of the tiles along the bottom and right edges.
+
+use crate::{curve_from_name, error};
+
+/// Mapping between matrix coordinates and offsets in a curve-ordered buffer.
+#[derive(Debug, Clone)]
+pub struct MatrixLayout {
+    /// Number of matrix rows.
+    rows: u32,
+    /// Number of matrix columns.
+    cols: u32,
+    /// Side of a tile, in elements.
+    block: u32,
+    /// Tiles per matrix row of tiles.
+    tile_cols: u32,
+    /// Memory slot of each tile, indexed row-major by tile row and column.
+    slots: Vec<u32>,
+    /// Row and column of the tile in each memory slot.
+    tiles: Vec<(u32, u32)>,
+}
+
+impl MatrixLayout {
+    /// Lay out a `rows × cols` matrix in `block × block` tiles ordered along the curve
+    /// registered as `curve`.
+    ///
+    /// The curve must accept two-dimensional power-of-two grids. Fails when any size is zero,
+    /// the curve is unknown, or the tile grid is too large for it.
+    pub fn new(curve: &str, rows: u32, cols: u32, block: u32) -> error::Result<Self> {
+        if rows == 0 || cols == 0 || block == 0 {
+            return Err(error::Error::Size(format!(
+                "matrix layout needs non-zero sizes, got {rows}x{cols} in blocks of {block}"
+            )));
+        }
+        let tile_rows = rows.div_ceil(block);
+        let tile_cols = cols.div_ceil(block);
+        let side = tile_rows.max(tile_cols).next_power_of_two().max(2);
+        let curve = curve_from_name(curve, 2, side)?;
+        curve.try_length()?;
+
+        let count = tile_rows as usize * tile_cols as usize;
+        let mut slots = vec![0; count];
+        let mut tiles = Vec::with_capacity(count);
+        // Curve points are (x, y): the column comes first.
+        for point in curve.iter() {
+            let (row, col) = (point[1], point[0]);
+            if row < tile_rows && col < tile_cols {
+                slots[(row * tile_cols + col) as usize] = tiles.len() as u32;
+                tiles.push((row, col));
+            }
+        }
+        Ok(Self {
+            rows,
+            cols,
+            block,
+            tile_cols,
+            slots,
+            tiles,
+        })
+    }
+
+    /// Tiles in Morton (Z-order) order.
+    pub fn morton(rows: u32, cols: u32, block: u32) -> error::Result<Self> {
+        Self::new("zorder", rows, cols, block)
+    }
+
+    /// Tiles in Hilbert order.
+    pub fn hilbert(rows: u32, cols: u32, block: u32) -> error::Result<Self> {
+        Self::new("hilbert", rows, cols, block)
+    }
+
+    /// Number of matrix rows.
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Number of matrix columns.
+    pub fn cols(&self) -> u32 {
+        self.cols
+    }
+
+    /// Side of a tile, in elements.
+    pub fn block(&self) -> u32 {
+        self.block
+    }
+
+    /// Number of elements a buffer needs to hold the matrix, including the unused parts of
+    /// edge tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len() * self.tile_area()
+    }
+
+    /// Whether the layout holds no elements; never true, as every dimension is non-zero.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Offset of element (`row`, `col`) in the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coordinates lie outside the matrix.
+    pub fn offset(&self, row: u32, col: u32) -> usize {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({row}, {col}) is outside the {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        let tile = (row / self.block) * self.tile_cols + col / self.block;
+        let block = self.block as usize;
+        self.slots[tile as usize] as usize * self.tile_area()
+            + (row % self.block) as usize * block
+            + (col % self.block) as usize
+    }
+
+    /// Element stored at `offset`, or `None` when the offset is past the end of the buffer or
+    /// falls in the unused part of an edge tile.
+    pub fn position(&self, offset: usize) -> Option<(u32, u32)> {
+        let &(tile_row, tile_col) = self.tiles.get(offset / self.tile_area())?;
+        let (within, block) = (offset % self.tile_area(), self.block as usize);
+        let row = tile_row * self.block + (within / block) as u32;
+        let col = tile_col * self.block + (within % block) as u32;
+        (row < self.rows && col < self.cols).then_some((row, col))
+    }
+
+    /// Tiles in memory order, as (tile row, tile column).
+    pub fn tiles(&self) -> &[(u32, u32)] {
+        &self.tiles
+    }
+
+    /// Elements per tile.
+    fn tile_area(&self) -> usize {
+        self.block as usize * self.block as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trips(layout: &MatrixLayout) {
+        let mut seen = vec![false; layout.len()];
+        for row in 0..layout.rows() {
+            for col in 0..layout.cols() {
+                let offset = layout.offset(row, col);
+                assert!(!seen[offset], "offset {offset} used twice");
+                seen[offset] = true;
+                assert_eq!(layout.position(offset), Some((row, col)));
+            }
+        }
+        for (offset, used) in seen.iter().enumerate() {
+            if !used {
+                assert_eq!(layout.position(offset), None);
+            }
+        }
+        assert_eq!(layout.position(layout.len()), None);
+    }
+
+    #[test]
+    fn round_trip_square_and_rectangular() {
+        for (rows, cols, block) in [(8, 8, 2), (5, 13, 4), (17, 3, 2), (1, 1, 1), (9, 9, 16)] {
+            round_trips(&MatrixLayout::morton(rows, cols, block).unwrap());
+            round_trips(&MatrixLayout::hilbert(rows, cols, block).unwrap());
+        }
+    }
+
+    #[test]
+    fn padded_tiles_take_no_memory() {
+        // A 3x10 matrix in 2x2 tiles needs 2x5 tiles, not the 8x8 curve grid.
+        let layout = MatrixLayout::hilbert(3, 10, 2).unwrap();
+        assert_eq!(layout.tiles().len(), 10);
+        assert_eq!(layout.len(), 40);
+    }
+
+    #[test]
+    fn tiles_wider_than_u16_do_not_overflow() {
+        let block = 1 << 17;
+        let layout = MatrixLayout::morton(3, 5, block).unwrap();
+        let area = block as usize * block as usize;
+        assert_eq!(layout.len(), area);
+        let offset = layout.offset(2, 4);
+        assert_eq!(offset, 2 * block as usize + 4);
+        assert_eq!(layout.position(offset), Some((2, 4)));
+        assert_eq!(layout.position(area - 1), None);
+    }
+
+    #[test]
+    fn morton_orders_tiles_in_z_order() {
+        let layout = MatrixLayout::morton(4, 4, 2).unwrap();
+        assert_eq!(layout.tiles(), &[(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(layout.offset(0, 2), 4);
+        assert_eq!(layout.offset(3, 1), 11);
+    }
+
+    #[test]
+    fn hilbert_tiles_are_adjacent() {
+        let layout = MatrixLayout::hilbert(16, 16, 4).unwrap();
+        for pair in layout.tiles().windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_sizes() {
+        assert!(MatrixLayout::morton(0, 4, 2).is_err());
+        assert!(MatrixLayout::morton(4, 4, 0).is_err());
+        assert!(MatrixLayout::new("nonesuch", 4, 4, 2).is_err());
+    }
+}
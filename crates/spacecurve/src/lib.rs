@@ -9,31 +9,61 @@
 //! - Z-order (Morton)
 //! - Gray Code
 //! - H-curve
+//! - Moore (closed 2D Hilbert loop)
+//! - Peano (power-of-three grids)
 //! - Scan (Boustrophedon)
+//! - Sierpinski-Knopp (triangle bisection, 2D)
 //! - Onion / Hairy Onion (experimental)
 
+/// Locality and continuity measures of curves.
+pub mod analysis;
+/// LRU caching of index↔point conversions for curves with costly mappings.
+pub mod cached;
+/// Curve indices tagged with the fingerprint of their curve.
+pub mod curve_index;
 /// Implementations of specific space‑filling curves.
 pub mod curves;
 /// Error types used across the crate.
 pub mod error;
+/// Stable hashes of curve mappings for detecting definition changes.
+pub mod fingerprint;
+/// Allocation-free 2D Hilbert conversions for hot loops.
+pub mod hilbert2d;
+/// Allocation-free 3D Hilbert conversions for hot loops.
+pub mod hilbert3d;
+/// Curve-ordered blocked layouts for dense matrices.
+pub mod layout;
 /// Internal bit operations shared by curve implementations.
 #[doc(hidden)]
 pub mod ops;
 /// N‑dimensional points and helpers.
 pub mod point;
+/// Covering axis-aligned boxes with runs of curve indices.
+pub mod query;
+/// Precomputed curves that can be cloned cheaply across threads.
+pub mod shared;
 /// The `SpaceCurve` trait and related utilities.
 mod spacecurve;
 /// Grid specification helpers shared across curves.
 pub mod spec;
+/// Z-order texel addressing for swizzled and block-linear GPU textures.
+pub mod swizzle;
+/// State machine tables for the recursive power‑of‑two curves.
+pub mod table;
+/// Rotations, reflections and axis permutations of existing curves.
+pub mod transform;
+/// Binding curves to world-coordinate extents.
+pub mod world;
 
-pub use crate::spacecurve::SpaceCurve;
+pub use crate::spacecurve::{Points, SpaceCurve, points};
 
 /// Central registry of curve metadata and constructors.
 pub mod registry;
 
 /// Construct a curve by name with the requested dimensionality and size.
 ///
-/// Returns an error if the combination is invalid or the name is unknown.
+/// Returns an error if the combination is invalid or the name is unknown. Hilbert and
+/// Z-order curves may have more than `u32::MAX` points; see [`SpaceCurve::try_length`].
 pub fn curve_from_name(
     name: &str,
     dimension: u32,
@@ -41,3 +71,14 @@ pub fn curve_from_name(
 ) -> error::Result<Box<dyn SpaceCurve + 'static>> {
     registry::construct(name, dimension, size)
 }
+
+/// Construct a curve by name over a rectangular grid with the given side per axis.
+///
+/// Scan, Z-order and Onion accept any sides their rules allow; other curves need every side
+/// to be equal.
+pub fn curve_from_name_rect(
+    name: &str,
+    sides: &[u32],
+) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    registry::construct_rect(name, sides)
+}
@@ -232,10 +232,179 @@ fn deinterleave_generic(dimension: u32, bits_per_axis: u32, value: u32) -> Small
     coords
 }
 
+/// 64-bit form of [`interleave_lsb`], for codes of up to 64 bits.
+pub fn interleave_lsb_u64(coords: &[u32], bits_per_axis: u32) -> u64 {
+    let dimension = coords.len() as u32;
+    let mut value = 0u64;
+    for bit in 0..bits_per_axis {
+        for (dim, coord) in coords.iter().enumerate() {
+            let bit_val = u64::from((coord >> bit) & 1);
+            value |= bit_val << (bit * dimension + dim as u32);
+        }
+    }
+    value
+}
+
+/// 64-bit form of [`deinterleave_lsb`], for codes of up to 64 bits.
+pub fn deinterleave_lsb_u64(dimension: u32, bits_per_axis: u32, value: u64) -> SmallVec<[u32; 4]> {
+    let mut coords = smallvec![0u32; dimension as usize];
+    for bit in 0..bits_per_axis {
+        for dim in 0..dimension {
+            let bit_val = ((value >> (bit * dimension + dim)) & 1) as u32;
+            coords[dim as usize] |= bit_val << bit;
+        }
+    }
+    coords
+}
+
+/// Interleave coordinates whose axes have different bit widths, least significant bits first.
+///
+/// Axis `a` contributes `widths[a]` bits; once an axis runs out of bits the remaining axes
+/// carry on without it.
+pub fn interleave_widths(coords: &[u32], widths: &[u32]) -> u64 {
+    let mut value = 0u64;
+    let mut position = 0;
+    for bit in 0..widths.iter().copied().max().unwrap_or(0) {
+        for (coord, &width) in coords.iter().zip(widths) {
+            if bit < width {
+                value |= u64::from((coord >> bit) & 1) << position;
+                position += 1;
+            }
+        }
+    }
+    value
+}
+
+/// Inverse of [`interleave_widths`].
+pub fn deinterleave_widths(widths: &[u32], value: u64) -> SmallVec<[u32; 4]> {
+    let mut coords: SmallVec<[u32; 4]> = smallvec![0; widths.len()];
+    let mut position = 0;
+    for bit in 0..widths.iter().copied().max().unwrap_or(0) {
+        for (coord, &width) in coords.iter_mut().zip(widths) {
+            if bit < width {
+                *coord |= (((value >> position) & 1) as u32) << bit;
+                position += 1;
+            }
+        }
+    }
+    coords
+}
+
+/// Step the Morton code whose coordinates are `coords` one code forward or back, in place.
+///
+/// An increment carries through the trailing one bits and a decrement borrows through the
+/// trailing zero bits, so only the coordinates' low bits are touched. Returns `false`, leaving
+/// `coords` unchanged, when the step would leave the `bits_per_axis`-bit range.
+pub fn morton_step(coords: &mut [u32], bits_per_axis: u32, forward: bool) -> bool {
+    let dimension = coords.len() as u32;
+    let mask = (1u32 << bits_per_axis) - 1;
+    let pivot = coords
+        .iter()
+        .enumerate()
+        .filter_map(|(axis, &coord)| {
+            let bits = if forward { !coord & mask } else { coord };
+            (bits != 0).then(|| bits.trailing_zeros() * dimension + axis as u32)
+        })
+        .min();
+    let Some(pivot) = pivot else {
+        return false;
+    };
+    let (level, pivot_axis) = (pivot / dimension, pivot % dimension);
+    for (axis, coord) in coords.iter_mut().enumerate() {
+        let mut below = (1 << level) - 1;
+        if (axis as u32) < pivot_axis {
+            below |= 1 << level;
+        }
+        if forward {
+            *coord &= !below;
+        } else {
+            *coord |= below;
+        }
+    }
+    coords[pivot_axis as usize] ^= 1 << level;
+    true
+}
+
+/// Step the Gray code whose coordinates are `coords` one code forward or back, in place.
+///
+/// Consecutive codes differ in one bit: the lowest when stepping away from an even index
+/// (forward from even parity, back from odd), and otherwise the bit above the lowest set
+/// bit. Returns `false`, leaving `coords` unchanged, when the step would leave the
+/// `bits_per_axis`-bit range.
+pub fn gray_step(coords: &mut [u32], bits_per_axis: u32, forward: bool) -> bool {
+    let dimension = coords.len() as u32;
+    let odd = coords.iter().map(|c| c.count_ones()).sum::<u32>() % 2 == 1;
+    let bit = if forward != odd {
+        0
+    } else {
+        let lowest = coords
+            .iter()
+            .enumerate()
+            .filter(|&(_, &coord)| coord != 0)
+            .map(|(axis, coord)| coord.trailing_zeros() * dimension + axis as u32)
+            .min();
+        match lowest {
+            Some(lowest) => lowest + 1,
+            None => return false,
+        }
+    };
+    if bit >= bits_per_axis * dimension {
+        return false;
+    }
+    coords[(bit % dimension) as usize] ^= 1 << (bit / dimension);
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn wide_interleave_matches_narrow_and_round_trips() {
+        assert_eq!(
+            interleave_lsb_u64(&[5, 3, 6], 3),
+            u64::from(interleave_lsb(&[5, 3, 6], 3))
+        );
+        let coords = [0xf_ffff, 0x1_2345, 0xa_bcde];
+        let code = interleave_lsb_u64(&coords, 20);
+        assert!(code > u64::from(u32::MAX));
+        assert_eq!(deinterleave_lsb_u64(3, 20, code).as_slice(), coords);
+    }
+
+    #[test]
+    fn steps_follow_codes() {
+        for (dimension, bits) in [(1, 4), (2, 3), (3, 2), (5, 1)] {
+            let length = 1u32 << (dimension * bits);
+            for code in 0..length {
+                let coords = deinterleave_lsb(dimension, bits, code);
+                for forward in [true, false] {
+                    let target = if forward {
+                        code.checked_add(1).filter(|&c| c < length)
+                    } else {
+                        code.checked_sub(1)
+                    };
+
+                    let mut stepped = coords.clone();
+                    let moved = morton_step(&mut stepped, bits, forward);
+                    match target {
+                        Some(t) => assert_eq!(stepped, deinterleave_lsb(dimension, bits, t)),
+                        None => assert!(!moved && stepped == coords),
+                    }
+
+                    let gray = deinterleave_lsb(dimension, bits, graycode(code));
+                    let mut stepped = gray.clone();
+                    let moved = gray_step(&mut stepped, bits, forward);
+                    match target {
+                        Some(t) => {
+                            assert_eq!(stepped, deinterleave_lsb(dimension, bits, graycode(t)));
+                        }
+                        None => assert!(!moved && stepped == gray),
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn interleave_roundtrip() {
         for dim in 1u32..=4 {
@@ -1,11 +1,23 @@
 //! Lightweight N‑dimensional point type used by curve implementations.
 
-use std::{ops::Deref, vec::Vec};
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    vec::Vec,
+};
 
 use smallvec::SmallVec;
 
+use crate::{error, spacecurve::SpaceCurve};
+
 /// Compact N‑dimensional point wrapper used by curves.
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// Points order lexicographically by coordinate, first axis first; wrap them in a
+/// [`CurveKey`] to order them along a curve instead.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Point(pub SmallVec<[u32; 4]>);
 
 impl Point {
@@ -80,10 +92,160 @@ impl Deref for Point {
     }
 }
 
+/// A point keyed by its index on a curve, so ordered maps and sets keep curve order.
+///
+/// The index is computed once when the key is made, and keys compare, hash and [`Borrow`] as
+/// that index alone: a `BTreeMap<CurveKey, V>` can be searched or ranged by plain `u32`
+/// indices. Keys made from different curves should not share a collection.
+#[derive(Clone)]
+pub struct CurveKey<'c> {
+    /// Curve the index was taken from.
+    curve: &'c dyn SpaceCurve,
+    /// Index of `point` on `curve`.
+    index: u32,
+    /// The keyed point.
+    point: Point,
+}
+
+impl<'c> CurveKey<'c> {
+    /// Key `point`, which must lie on `curve`, by its index on `curve`.
+    ///
+    /// Fails when the curve has more than `u32::MAX` points, as keys hold `u32` indices.
+    pub fn new(curve: &'c dyn SpaceCurve, point: Point) -> error::Result<Self> {
+        curve.try_length()?;
+        let index = curve.index(&point);
+        Ok(Self {
+            curve,
+            index,
+            point,
+        })
+    }
+
+    /// Key the point at `index` on `curve`.
+    pub fn at(curve: &'c dyn SpaceCurve, index: u32) -> Self {
+        Self {
+            curve,
+            index,
+            point: curve.point(index),
+        }
+    }
+
+    /// Index of the point on the curve.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The keyed point.
+    pub fn point(&self) -> &Point {
+        &self.point
+    }
+
+    /// Curve the key orders by.
+    pub fn curve(&self) -> &'c dyn SpaceCurve {
+        self.curve
+    }
+
+    /// Unwrap the keyed point.
+    pub fn into_point(self) -> Point {
+        self.point
+    }
+}
+
+impl fmt::Debug for CurveKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CurveKey")
+            .field("curve", &self.curve.name())
+            .field("index", &self.index)
+            .field("point", &self.point)
+            .finish()
+    }
+}
+
+impl PartialEq for CurveKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl Eq for CurveKey<'_> {}
+
+impl PartialOrd for CurveKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CurveKey<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl Hash for CurveKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl Borrow<u32> for CurveKey<'_> {
+    fn borrow(&self) -> &u32 {
+        &self.index
+    }
+}
+
+impl Deref for CurveKey<'_> {
+    type Target = Point;
+    fn deref(&self) -> &Point {
+        &self.point
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
     use super::*;
-    use crate::error;
+    use crate::{curve_from_name, error};
+
+    #[test]
+    fn points_order_lexicographically() {
+        let mut points = vec![
+            Point::new(vec![1, 0]),
+            Point::new(vec![0, 2]),
+            Point::new(vec![0, 1]),
+        ];
+        points.sort();
+        assert_eq!(
+            points,
+            [
+                Point::new(vec![0, 1]),
+                Point::new(vec![0, 2]),
+                Point::new(vec![1, 0])
+            ]
+        );
+    }
+
+    #[test]
+    fn curve_keys_follow_the_curve() -> error::Result<()> {
+        let curve = curve_from_name("hilbert", 2, 4)?;
+        let set: BTreeSet<_> = [[3, 0], [0, 0], [1, 1], [0, 1]]
+            .into_iter()
+            .map(|p| CurveKey::new(curve.as_ref(), Point::new(p.to_vec())))
+            .collect::<error::Result<_>>()?;
+        let indices: Vec<_> = set.iter().map(CurveKey::index).collect();
+        assert_eq!(indices, [0, 1, 2, 5]);
+
+        let map: BTreeMap<_, _> = (0..curve.length())
+            .map(|i| (CurveKey::at(curve.as_ref(), i), i * 10))
+            .collect();
+        assert_eq!(map.get(&5), Some(&50));
+        let ranged: Vec<_> = map.range(3..6).map(|(key, _)| key.index()).collect();
+        assert_eq!(ranged, [3, 4, 5]);
+        let key = map.keys().nth(2).unwrap();
+        assert_eq!(key.point(), &curve.point(2));
+        assert_eq!(key[0], curve.point(2)[0]);
+        Ok(())
+    }
 
     #[test]
     fn point() -> error::Result<()> {
@@ -112,4 +274,11 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn curve_keys_reject_wide_curves() {
+        let wide = curve_from_name("hilbert", 2, 1 << 16).unwrap();
+        let key = CurveKey::new(wide.as_ref(), Point::new(vec![0, 0]));
+        assert!(matches!(key, Err(error::Error::Size(_))));
+    }
 }
@@ -0,0 +1,252 @@
+//! Covering axis-aligned boxes with runs of curve indices.
+//!
+//! Using a curve as a spatial key means storing each cell under its index, so a box query
+//! becomes a set of index range scans. [`box_ranges`] finds the fewest contiguous index
+//! ranges holding exactly the cells of a box.
+//!
+//! Curves reporting [`SpaceCurve::nested_blocks`], such as Hilbert and Z-order, are split
+//! block by block: a block wholly inside the box is one range, and only blocks straddling
+//! its boundary are divided further, so the work grows with the box's surface rather than
+//! its volume. Every other curve has each cell of the box indexed and the indices merged.
+
+use std::ops::Range;
+
+use smallvec::SmallVec;
+
+use crate::{
+    error::{self, Error},
+    point::Point,
+    spacecurve::SpaceCurve,
+};
+
+/// The fewest contiguous index ranges of `curve` covering exactly the cells of a box.
+///
+/// `bounds` holds one half-open coordinate range per axis and is clipped to the grid. The
+/// ranges are returned in ascending order, and no two of them touch. Fails when the number
+/// of bounds differs from the curve's dimension, or the curve has more than `u32::MAX`
+/// points.
+pub fn box_ranges(curve: &dyn SpaceCurve, bounds: &[Range<u32>]) -> error::Result<Vec<Range<u32>>> {
+    let dimension = curve.dimensions() as usize;
+    if bounds.len() != dimension {
+        return Err(Error::Shape(format!(
+            "{} bounds given for a {dimension}-dimensional curve",
+            bounds.len()
+        )));
+    }
+    if curve.length_u64() > u64::from(u32::MAX) {
+        return Err(Error::Size(format!(
+            "box queries need u32 indices, but the curve has {} points",
+            curve.length_u64()
+        )));
+    }
+    let sides = curve.sides();
+    let bounds: Vec<Range<u32>> = bounds
+        .iter()
+        .zip(&sides)
+        .map(|(range, &side)| range.start.min(side)..range.end.min(side))
+        .collect();
+    if bounds.iter().any(Range::is_empty) {
+        return Ok(Vec::new());
+    }
+
+    let side = sides[0];
+    let mut ranges =
+        if curve.nested_blocks() && side.is_power_of_two() && sides.iter().all(|&s| s == side) {
+            block_ranges(curve, &bounds, side)
+        } else {
+            cell_ranges(curve, &bounds)
+        };
+    ranges.sort_unstable_by_key(|range| range.start);
+    Ok(merge(ranges))
+}
+
+/// Ranges of the largest aligned blocks inside `bounds`, for a curve with nested blocks on a
+/// cube of the given `side`.
+fn block_ranges(curve: &dyn SpaceCurve, bounds: &[Range<u32>], side: u32) -> Vec<Range<u32>> {
+    let dimension = bounds.len();
+    let mut ranges = Vec::new();
+    let mut stack = vec![(SmallVec::<[u32; 4]>::from_elem(0, dimension), side)];
+    while let Some((origin, block)) = stack.pop() {
+        let overlaps = origin
+            .iter()
+            .zip(bounds)
+            .all(|(&start, range)| start < range.end && range.start < start + block);
+        if !overlaps {
+            continue;
+        }
+        let inside = origin
+            .iter()
+            .zip(bounds)
+            .all(|(&start, range)| range.start <= start && start + block <= range.end);
+        if inside {
+            let cells = u64::from(block).pow(dimension as u32);
+            let base = u64::from(curve.index(&Point::new(origin))) / cells * cells;
+            ranges.push(base as u32..(base + cells) as u32);
+            continue;
+        }
+        let half = block / 2;
+        for corner in 0..1u32 << dimension {
+            let child = origin
+                .iter()
+                .enumerate()
+                .map(|(axis, &start)| start + (corner >> axis & 1) * half)
+                .collect();
+            stack.push((child, half));
+        }
+    }
+    ranges
+}
+
+/// Single-index ranges for every cell inside `bounds`.
+fn cell_ranges(curve: &dyn SpaceCurve, bounds: &[Range<u32>]) -> Vec<Range<u32>> {
+    let mut cells = Vec::new();
+    let mut cell: SmallVec<[u32; 4]> = bounds.iter().map(|range| range.start).collect();
+    'cells: loop {
+        cells.push(Point::new(cell.clone()));
+        for (coordinate, range) in cell.iter_mut().zip(bounds) {
+            *coordinate += 1;
+            if *coordinate < range.end {
+                continue 'cells;
+            }
+            *coordinate = range.start;
+        }
+        break;
+    }
+    curve
+        .index_many(&cells)
+        .into_iter()
+        .map(|index| index..index + 1)
+        .collect()
+}
+
+/// Join touching ranges of `ranges`, which must be sorted and disjoint.
+fn merge(ranges: Vec<Range<u32>>) -> Vec<Range<u32>> {
+    let mut merged: Vec<Range<u32>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        curve_from_name, curve_from_name_rect,
+        transform::{Transform, TransformedCurve},
+    };
+
+    /// Ranges found by indexing every cell of the box, sorting and merging.
+    fn brute_force(curve: &dyn SpaceCurve, bounds: &[Range<u32>]) -> Vec<Range<u32>> {
+        let mut indices: Vec<u32> = (0..curve.length())
+            .filter(|&index| {
+                let p = curve.point(index);
+                p.iter().zip(bounds).all(|(c, range)| range.contains(c))
+            })
+            .collect();
+        indices.sort_unstable();
+        merge(indices.into_iter().map(|index| index..index + 1).collect())
+    }
+
+    /// Every box whose corners on each axis are two of `coords`.
+    fn boxes(dimension: usize, coords: &[u32]) -> Vec<Vec<Range<u32>>> {
+        let mut spans = Vec::new();
+        for &start in coords {
+            for &end in coords {
+                if start < end {
+                    spans.push(start..end);
+                }
+            }
+        }
+        let mut all = vec![Vec::new()];
+        for _ in 0..dimension {
+            all = all
+                .into_iter()
+                .flat_map(|prefix: Vec<Range<u32>>| {
+                    spans.iter().map(move |span| {
+                        let mut next = prefix.clone();
+                        next.push(span.clone());
+                        next
+                    })
+                })
+                .collect();
+        }
+        all
+    }
+
+    #[test]
+    fn matches_brute_force_on_every_curve_shape() {
+        let curves: Vec<Box<dyn SpaceCurve>> = vec![
+            curve_from_name("hilbert", 2, 16).unwrap(),
+            curve_from_name("zorder", 2, 16).unwrap(),
+            curve_from_name("moore", 2, 16).unwrap(),
+            curve_from_name("onion", 2, 10).unwrap(),
+            curve_from_name_rect("zorder", &[16, 4]).unwrap(),
+            Box::new(
+                TransformedCurve::new(
+                    curve_from_name("hilbert", 2, 16).unwrap(),
+                    Transform::identity(2).rotate(0, 1).unwrap(),
+                )
+                .unwrap(),
+            ),
+        ];
+        for curve in &curves {
+            for bounds in boxes(2, &[0, 1, 3, 4, 7, 8, 13, 16]) {
+                assert_eq!(
+                    box_ranges(curve.as_ref(), &bounds).unwrap(),
+                    brute_force(curve.as_ref(), &bounds),
+                    "{} {bounds:?}",
+                    curve.name()
+                );
+            }
+        }
+        for name in ["hilbert", "zorder"] {
+            let curve = curve_from_name(name, 3, 8).unwrap();
+            for bounds in boxes(3, &[0, 2, 3, 5, 8]) {
+                assert_eq!(
+                    box_ranges(curve.as_ref(), &bounds).unwrap(),
+                    brute_force(curve.as_ref(), &bounds),
+                    "{name} {bounds:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn aligned_blocks_are_single_ranges() {
+        let curve = curve_from_name("hilbert", 2, 1 << 12).unwrap();
+        let whole = box_ranges(curve.as_ref(), &[0..4096, 0..4096]).unwrap();
+        assert_eq!(whole.len(), 1);
+        assert_eq!(whole[0], 0..1 << 24);
+        let block = box_ranges(curve.as_ref(), &[1024..2048, 3072..4096]).unwrap();
+        assert_eq!(block.len(), 1);
+        assert_eq!(block[0].len(), 1 << 20);
+        // A large unaligned box is still covered by a modest number of ranges.
+        let ranges = box_ranges(curve.as_ref(), &[100..3000, 7..2500]).unwrap();
+        let cells: u64 = ranges.iter().map(|range| range.len() as u64).sum();
+        assert_eq!(cells, 2900 * 2493);
+        assert!(ranges.len() < 10_000, "{} ranges", ranges.len());
+    }
+
+    #[test]
+    fn clips_and_validates_bounds() {
+        let curve = curve_from_name("zorder", 2, 8).unwrap();
+        let clipped = box_ranges(curve.as_ref(), &[0..100, 0..100]).unwrap();
+        assert_eq!(clipped.len(), 1);
+        assert_eq!(clipped[0], 0..64);
+        assert!(
+            box_ranges(curve.as_ref(), &[3..3, 0..8])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            box_ranges(curve.as_ref(), &[9..12, 0..8])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(box_ranges(curve.as_ref(), &[0..8, 0..8, 0..8]).is_err());
+    }
+}
@@ -1,37 +1,76 @@
 use crate::{
-    curves::{gray, hairyonion, hcurve, hilbert, onion, scan, zorder},
+    curves::{gray, hairyonion, hcurve, hilbert, moore, onion, peano, scan, sierpinski, zorder},
     error,
     spacecurve::SpaceCurve,
-    spec::GridSpec,
+    spec::{GridSpec, INDEX_BITS, RectGridSpec, WIDE_INDEX_BITS},
 };
 
+/// Which side lengths a curve can be built with, before its length limits apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeRule {
+    /// Any side length of at least one.
+    Any,
+    /// Side lengths that are powers of two.
+    PowerOfTwo,
+    /// Side lengths that are powers of three.
+    PowerOfThree,
+}
+
+impl SizeRule {
+    /// Side lengths of this form from `from` upwards, in increasing order.
+    fn candidates(self, from: u32) -> Box<dyn DoubleEndedIterator<Item = u32>> {
+        match self {
+            Self::Any => Box::new(from.max(1)..=u32::MAX),
+            Self::PowerOfTwo => Box::new(
+                (0..u32::BITS)
+                    .map(|order| 1 << order)
+                    .filter(move |&size| size >= from),
+            ),
+            Self::PowerOfThree => Box::new(
+                (0..=u32::MAX.ilog(3))
+                    .map(|order| 3u32.pow(order))
+                    .filter(move |&size| size >= from),
+            ),
+        }
+    }
+}
+
+/// Constructor building a curve over a validated rectangular grid.
+pub type RectCtor = fn(&RectGridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>>;
+
 /// Metadata and constructor for a curve type.
 pub struct CurveEntry {
     /// Canonical, lowercase key (as accepted by CLI/APIs).
     pub key: &'static str,
     /// Human-friendly display name.
     pub display: &'static str,
+    /// Alternate names accepted in place of `key`.
+    pub aliases: &'static [&'static str],
     /// Human-friendly constraints summary suitable for help text.
     pub constraints: &'static str,
     /// Whether this curve is experimental and should be hidden in stable UIs.
     pub experimental: bool,
+    /// Whether every step moves to an adjacent cell at every valid shape.
+    pub continuous: bool,
+    /// Form of the side lengths this curve accepts.
+    pub sizes: SizeRule,
     /// Build a validated grid specification for this curve.
     pub build_spec: fn(u32, u32) -> error::Result<GridSpec>,
     /// Construct the curve given a validated grid specification.
     pub ctor: fn(&GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>>,
+    /// Construct the curve over a rectangular grid, for curves that support one.
+    pub rect_ctor: Option<RectCtor>,
 }
 
 // --- Per-curve validators -----------------------------------------------------
 
-/// Hilbert pre-validation aligned with constructor invariants.
+/// Hilbert pre-validation aligned with constructor invariants, for 64-bit indices.
 fn v_hilbert(dim: u32, size: u32) -> error::Result<GridSpec> {
-    let spec = GridSpec::power_of_two(dim, size)?;
-    let total_bits = (spec.order().unwrap() as u64) * (dim as u64);
-    if total_bits >= 32 {
-        return Err(error::Error::Size(
-            "Hilbert requires order * dimension < 32 for u32 indices".to_string(),
-        ));
+    let spec = GridSpec::power_of_two_u64(dim, size)?;
+    if dim >= 32 {
+        return Err(error::Error::Shape("dimension must be < 32".to_string()));
     }
+    spec.require_index_bits_lt(WIDE_INDEX_BITS)?;
     Ok(spec)
 }
 
@@ -44,18 +83,14 @@ fn v_hcurve(dim: u32, size: u32) -> error::Result<GridSpec> {
     if dim >= 32 {
         return Err(error::Error::Shape("dimension must be < 32".to_string()));
     }
-    if (spec.order().unwrap() as u64) * (dim as u64) >= 32 {
-        return Err(error::Error::Size(
-            "Curve size exceeds u32 limits (D*O must be < 32)".to_string(),
-        ));
-    }
+    spec.require_index_bits_lt(INDEX_BITS)?;
     Ok(spec)
 }
 
-/// Z-order (Morton) pre-validation aligned with constructor invariants.
+/// Z-order (Morton) pre-validation aligned with constructor invariants, for 64-bit indices.
 fn v_zorder(dim: u32, size: u32) -> error::Result<GridSpec> {
-    let spec = GridSpec::power_of_two(dim, size)?;
-    spec.require_index_bits_lt(32)?;
+    let spec = GridSpec::power_of_two_u64(dim, size)?;
+    spec.require_index_bits_lt(WIDE_INDEX_BITS)?;
     Ok(spec)
 }
 
@@ -77,19 +112,43 @@ fn v_scan(dim: u32, size: u32) -> error::Result<GridSpec> {
 /// Gray pre-validation: generic shape/length checks.
 fn v_gray(dim: u32, size: u32) -> error::Result<GridSpec> {
     let spec = GridSpec::power_of_two(dim, size)?;
-    if (spec.bits_per_axis().unwrap() as u64) * (dim as u64) >= 32 {
-        return Err(error::Error::Size(
-            "Gray requires bitwidth * dimension < 32 for u32 indices".to_string(),
-        ));
+    spec.require_index_bits_lt(INDEX_BITS)?;
+    Ok(spec)
+}
+
+/// Moore pre-validation: 2D only, power-of-two sides of at least 2.
+fn v_moore(dim: u32, size: u32) -> error::Result<GridSpec> {
+    if dim != 2 {
+        return Err(error::Error::Shape("dimension must be 2".to_string()));
     }
+    if size < 2 {
+        return Err(error::Error::Size("size must be >= 2".to_string()));
+    }
+    let spec = GridSpec::power_of_two(dim, size)?;
+    spec.require_index_bits_lt(INDEX_BITS)?;
     Ok(spec)
 }
 
+/// Sierpinski pre-validation: 2D only, power-of-two sides.
+fn v_sierpinski(dim: u32, size: u32) -> error::Result<GridSpec> {
+    if dim != 2 {
+        return Err(error::Error::Shape("dimension must be 2".to_string()));
+    }
+    let spec = GridSpec::power_of_two(dim, size)?;
+    spec.require_index_bits_lt(INDEX_BITS)?;
+    Ok(spec)
+}
+
+/// Peano pre-validation: power-of-three sides.
+fn v_peano(dim: u32, size: u32) -> error::Result<GridSpec> {
+    GridSpec::power_of_three(dim, size)
+}
+
 // --- Per-curve constructors (boxed trait objects) ----------------------------
 
 /// Construct a boxed Hilbert instance.
 fn c_hilbert(spec: &GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
-    Ok(Box::new(hilbert::Hilbert::from_dimensions(
+    Ok(Box::new(hilbert::Hilbert::from_dimensions_u64(
         spec.dimension(),
         spec.size(),
     )?))
@@ -103,7 +162,7 @@ fn c_hcurve(spec: &GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
 }
 /// Construct a boxed Z-order instance.
 fn c_zorder(spec: &GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
-    Ok(Box::new(zorder::ZOrder::from_dimensions(
+    Ok(Box::new(zorder::ZOrder::from_dimensions_u64(
         spec.dimension(),
         spec.size(),
     )?))
@@ -136,6 +195,42 @@ fn c_gray(spec: &GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
         spec.size(),
     )?))
 }
+/// Construct a boxed Peano instance.
+fn c_peano(spec: &GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    Ok(Box::new(peano::Peano::from_dimensions(
+        spec.dimension(),
+        spec.size(),
+    )?))
+}
+/// Construct a boxed Moore instance.
+fn c_moore(spec: &GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    Ok(Box::new(moore::Moore::from_dimensions(
+        spec.dimension(),
+        spec.size(),
+    )?))
+}
+/// Construct a boxed Sierpinski instance.
+fn c_sierpinski(spec: &GridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    Ok(Box::new(sierpinski::Sierpinski::from_dimensions(
+        spec.dimension(),
+        spec.size(),
+    )?))
+}
+
+// --- Rectangular-grid constructors -------------------------------------------
+
+/// Construct a boxed Scan instance over a rectangular grid.
+fn r_scan(spec: &RectGridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    Ok(Box::new(scan::Scan::from_sides(spec.sides())?))
+}
+/// Construct a boxed Z-order instance over a rectangular grid.
+fn r_zorder(spec: &RectGridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    Ok(Box::new(zorder::ZOrder::from_sides(spec.sides())?))
+}
+/// Construct a boxed Onion instance over a rectangular grid.
+fn r_onion(spec: &RectGridSpec) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    Ok(Box::new(onion::OnionCurve::from_sides(spec.sides())?))
+}
 
 /// Generate the registry table and the ordered list of curve keys from one
 /// token list to avoid drift between the two.
@@ -144,10 +239,14 @@ macro_rules! define_registry {
         {
             $key:literal,
             $display:literal,
+            [ $( $alias:literal ),* ],
             $constraints:literal,
             $experimental:expr,
+            $continuous:expr,
+            $sizes:ident,
             $validate:ident,
-            $ctor:ident
+            $ctor:ident,
+            $rect:expr
         }
     ),+ $(,)? ) => {
         /// Public list of curve keys accepted by the library and CLI.
@@ -159,10 +258,14 @@ macro_rules! define_registry {
                 CurveEntry {
                     key: $key,
                     display: $display,
+                    aliases: &[ $( $alias ),* ],
                     constraints: $constraints,
                     experimental: $experimental,
+                    continuous: $continuous,
+                    sizes: SizeRule::$sizes,
                     build_spec: $validate,
                     ctor: $ctor,
+                    rect_ctor: $rect,
                 },
             )+
         ];
@@ -170,38 +273,192 @@ macro_rules! define_registry {
 }
 
 define_registry! {
-    { "hilbert", "Hilbert", "size=2^order; order*dimension < 32 (u32 indices)", false, v_hilbert, c_hilbert },
-    { "scan", "Scan", "any size>=1; any dimension>=1", false, v_scan, c_scan },
-    { "zorder", "Z-order (Morton)", "size=2^bitwidth; bitwidth*dimension < 32 (u32 indices)", false, v_zorder, c_zorder },
-    { "hcurve", "H-curve", "dimension>=2; size=2^order; order*dimension < 32", false, v_hcurve, c_hcurve },
-    { "onion", "Onion", "any size>=1; any dimension>=1; length=size^dimension fits u32", false, v_onion, c_onion },
-    { "hairyonion", "Hairy Onion", "any size>=1; any dimension>=1; length=size^dimension fits u32", true, v_hairyonion, c_hairyonion },
-    { "gray", "Gray (BRGC)", "size=2^bitwidth; bitwidth*dimension < 32 (u32 indices)", false, v_gray, c_gray },
+    { "hilbert", "Hilbert", [], "size=2^order; dimension < 32; order*dimension < 64 (u64 indices)", false, true, PowerOfTwo, v_hilbert, c_hilbert, None },
+    { "scan", "Scan", ["snake", "boustrophedon"], "any size>=1; any dimension>=1", false, true, Any, v_scan, c_scan, Some(r_scan) },
+    { "zorder", "Z-order (Morton)", ["morton", "z-order"], "size=2^bitwidth; bitwidth*dimension < 64 (u64 indices); < 32 on rectangular grids", false, false, PowerOfTwo, v_zorder, c_zorder, Some(r_zorder) },
+    { "hcurve", "H-curve", ["h-curve"], "dimension>=2; size=2^order; order*dimension < 32", false, false, PowerOfTwo, v_hcurve, c_hcurve, None },
+    { "onion", "Onion", [], "any size>=1; any dimension>=1; length=size^dimension fits u32", false, false, Any, v_onion, c_onion, Some(r_onion) },
+    { "hairyonion", "Hairy Onion", ["hairy-onion"], "any size>=1; any dimension>=1; length=size^dimension fits u32", true, true, Any, v_hairyonion, c_hairyonion, None },
+    { "gray", "Gray (BRGC)", ["brgc", "grey"], "size=2^bitwidth; bitwidth*dimension < 32 (u32 indices)", false, false, PowerOfTwo, v_gray, c_gray, None },
+    { "peano", "Peano", [], "size=3^order; length=size^dimension fits u32", false, true, PowerOfThree, v_peano, c_peano, None },
+    { "moore", "Moore", [], "dimension=2; size=2^order>=2; 2*order < 32", false, true, PowerOfTwo, v_moore, c_moore, None },
+    { "sierpinski", "Sierpinski-Knopp", ["sierpinski-knopp", "knopp"], "dimension=2; size=2^order; 2*order < 32", false, false, PowerOfTwo, v_sierpinski, c_sierpinski, None },
+}
+
+/// Capabilities a curve must have to pass [`filter`]. Unset fields accept every curve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CurveFilter {
+    /// Number of dimensions the curve must support.
+    pub dimension: Option<u32>,
+    /// Side length the curve must support, in `dimension` dimensions when that is set and in
+    /// any dimension otherwise.
+    pub size: Option<u32>,
+    /// Whether experimental curves pass.
+    pub include_experimental: bool,
+    /// Only pass curves marked [`CurveEntry::continuous`], which step between adjacent cells
+    /// at every shape.
+    pub continuous: bool,
+}
+
+impl CurveFilter {
+    /// Whether `entry` has every capability this filter asks for.
+    pub fn matches(&self, entry: &CurveEntry) -> bool {
+        if entry.experimental && !self.include_experimental {
+            return false;
+        }
+        if self.continuous && !entry.continuous {
+            return false;
+        }
+        match (self.dimension, self.size) {
+            (Some(dimension), Some(size)) => entry.accepts(dimension, size),
+            (Some(dimension), None) => entry.max_size(dimension).is_some(),
+            (None, Some(size)) => {
+                (1..WIDE_INDEX_BITS).any(|dimension| entry.accepts(dimension, size))
+            }
+            (None, None) => true,
+        }
+    }
+}
+
+/// Registry entries with every capability `filter` asks for, in registry order.
+pub fn filter(filter: CurveFilter) -> Vec<&'static CurveEntry> {
+    REGISTRY
+        .iter()
+        .filter(|entry| filter.matches(entry))
+        .collect()
 }
 
 /// Return curve keys, optionally filtering out experimental entries.
 pub fn curve_names(include_experimental: bool) -> Vec<&'static str> {
+    filter(CurveFilter {
+        include_experimental,
+        ..CurveFilter::default()
+    })
+    .into_iter()
+    .map(|entry| entry.key)
+    .collect()
+}
+
+/// Look up a registry entry by key or alias, ignoring case.
+pub fn find(name: &str) -> Option<&'static CurveEntry> {
+    REGISTRY.iter().find(|entry| {
+        entry
+            .names()
+            .any(|candidate| candidate.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Look up a registry entry by key or alias, failing with a did-you-mean suggestion for
+/// unknown names.
+pub fn lookup(name: &str) -> error::Result<&'static CurveEntry> {
+    find(name).ok_or_else(|| {
+        let mut message = format!("unknown pattern: \"{name}\"");
+        if let Some(suggestion) = suggest(name) {
+            message.push_str(&format!("; did you mean \"{suggestion}\"?"));
+        }
+        error::Error::Unknown(message)
+    })
+}
+
+/// The known name closest to `name` by edit distance, if any is close enough to be a typo.
+fn suggest(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
     REGISTRY
         .iter()
-        .filter(|entry| include_experimental || !entry.experimental)
-        .map(|entry| entry.key)
-        .collect()
+        .flat_map(CurveEntry::names)
+        .map(|candidate| (edit_distance(&name, candidate), candidate))
+        .filter(|&(distance, candidate)| distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
 }
 
-/// Look up a registry entry by key (case-sensitive).
-pub fn find(key: &str) -> Option<&'static CurveEntry> {
-    REGISTRY.iter().find(|e| e.key == key)
+/// Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
 }
 
-/// Validate a curve specification using the registry without constructing it.
-pub fn validate(key: &str, dimension: u32, size: u32) -> error::Result<()> {
-    match find(key) {
-        Some(entry) => {
-            (entry.build_spec)(dimension, size)?;
-            Ok(())
+impl CurveEntry {
+    /// The canonical key followed by the aliases.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        [self.key].into_iter().chain(self.aliases.iter().copied())
+    }
+
+    /// Whether this curve can be built with `dimension` axes of side `size`.
+    fn accepts(&self, dimension: u32, size: u32) -> bool {
+        (self.build_spec)(dimension, size).is_ok()
+    }
+
+    /// Largest side length this curve accepts in `dimension` dimensions.
+    ///
+    /// Lengths grow with the side, so past the smallest valid side every larger one is valid
+    /// up to a single cutoff, which is found by bisection.
+    fn max_size(&self, dimension: u32) -> Option<u32> {
+        match self.sizes {
+            SizeRule::PowerOfTwo | SizeRule::PowerOfThree => self
+                .sizes
+                .candidates(1)
+                .rev()
+                .find(|&size| self.accepts(dimension, size)),
+            SizeRule::Any => {
+                if !self.accepts(dimension, 1) {
+                    return None;
+                }
+                let (mut lo, mut hi) = (1, u32::MAX);
+                while lo < hi {
+                    let mid = lo + (hi - lo).div_ceil(2);
+                    if self.accepts(dimension, mid) {
+                        lo = mid;
+                    } else {
+                        hi = mid - 1;
+                    }
+                }
+                Some(lo)
+            }
         }
-        None => Err(error::Error::Unknown(format!("unknown pattern: \"{key}\""))),
     }
+
+    /// Valid side lengths of at least `from` and at most `limit`, in increasing order.
+    fn sizes_between(&self, dimension: u32, from: u32, limit: u32) -> impl Iterator<Item = u32> {
+        let max = self.max_size(dimension).map_or(0, |max| max.min(limit));
+        self.sizes
+            .candidates(from)
+            .take_while(move |&size| size <= max)
+            .filter(move |&size| self.accepts(dimension, size))
+    }
+}
+
+/// Largest side length `key` accepts in `dimension` dimensions, or `None` when it accepts
+/// none at all.
+pub fn max_size(key: &str, dimension: u32) -> error::Result<Option<u32>> {
+    Ok(lookup(key)?.max_size(dimension))
+}
+
+/// Side lengths up to `limit` that `key` accepts in `dimension` dimensions, in increasing
+/// order.
+pub fn valid_sizes(key: &str, dimension: u32, limit: u32) -> error::Result<Vec<u32>> {
+    Ok(lookup(key)?.sizes_between(dimension, 1, limit).collect())
+}
+
+/// Smallest side length of at least `size` that `key` accepts in `dimension` dimensions.
+pub fn size_at_least(key: &str, dimension: u32, size: u32) -> error::Result<Option<u32>> {
+    Ok(lookup(key)?.sizes_between(dimension, size, u32::MAX).next())
+}
+
+/// Validate a curve specification using the registry without constructing it.
+pub fn validate(key: &str, dimension: u32, size: u32) -> error::Result<()> {
+    (lookup(key)?.build_spec)(dimension, size)?;
+    Ok(())
 }
 
 /// Construct a curve by key after validating via the registry.
@@ -210,18 +467,39 @@ pub fn construct(
     dimension: u32,
     size: u32,
 ) -> error::Result<Box<dyn SpaceCurve + 'static>> {
-    match find(key) {
-        Some(entry) => {
-            let spec = (entry.build_spec)(dimension, size)?;
-            (entry.ctor)(&spec)
-        }
-        None => Err(error::Error::Unknown(format!("unknown pattern: \"{key}\""))),
+    let entry = lookup(key)?;
+    let spec = (entry.build_spec)(dimension, size)?;
+    (entry.ctor)(&spec)
+}
+
+/// Construct a curve by key over a rectangular grid with the given side per axis.
+///
+/// Grids whose sides are all equal are built as usual, so every curve accepts them. Other
+/// shapes need a curve that supports rectangular grids.
+pub fn construct_rect(key: &str, sides: &[u32]) -> error::Result<Box<dyn SpaceCurve + 'static>> {
+    let entry = lookup(key)?;
+    let spec = RectGridSpec::new(sides)?;
+    if let Some(side) = spec.cube_side() {
+        return construct(entry.key, spec.dimension(), side);
     }
+    let ctor = entry.rect_ctor.ok_or_else(|| {
+        error::Error::Shape(format!(
+            "{} needs equal sides on every axis, got {}",
+            entry.display,
+            sides
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join("x")
+        ))
+    })?;
+    ctor(&spec)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::point::Point;
 
     #[test]
     fn test_registry_consistency() {
@@ -247,4 +525,172 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn find_accepts_aliases_and_any_case() {
+        assert_eq!(find("morton").unwrap().key, "zorder");
+        assert_eq!(find("Snake").unwrap().key, "scan");
+        assert_eq!(find("BRGC").unwrap().key, "gray");
+        assert_eq!(find("Hilbert").unwrap().key, "hilbert");
+        assert_eq!(find("PEANO").unwrap().key, "peano");
+        assert!(find("dragon").is_none());
+    }
+
+    #[test]
+    fn aliases_are_unique() {
+        let mut names: Vec<&str> = REGISTRY.iter().flat_map(CurveEntry::names).collect();
+        let count = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn unknown_names_suggest_close_matches() {
+        let message = lookup("hilbret").err().unwrap().to_string();
+        assert!(message.contains("did you mean \"hilbert\""), "{message}");
+        let message = lookup("mortn").err().unwrap().to_string();
+        assert!(message.contains("did you mean \"morton\""), "{message}");
+        let message = lookup("dragon").err().unwrap().to_string();
+        assert!(!message.contains("did you mean"), "{message}");
+    }
+
+    /// Keys of the entries passing `f`.
+    fn keys(f: CurveFilter) -> Vec<&'static str> {
+        filter(f).into_iter().map(|entry| entry.key).collect()
+    }
+
+    #[test]
+    fn filter_by_capability() {
+        assert_eq!(keys(CurveFilter::default()), curve_names(false));
+        assert_eq!(
+            keys(CurveFilter {
+                include_experimental: true,
+                ..CurveFilter::default()
+            }),
+            CURVE_NAMES
+        );
+        assert_eq!(
+            keys(CurveFilter {
+                dimension: Some(2),
+                size: Some(5),
+                ..CurveFilter::default()
+            }),
+            ["scan", "onion"]
+        );
+        assert_eq!(
+            keys(CurveFilter {
+                dimension: Some(1),
+                ..CurveFilter::default()
+            }),
+            ["hilbert", "scan", "zorder", "onion", "gray", "peano"]
+        );
+        assert_eq!(
+            keys(CurveFilter {
+                continuous: true,
+                include_experimental: true,
+                ..CurveFilter::default()
+            }),
+            ["hilbert", "scan", "hairyonion", "peano", "moore"]
+        );
+        // Onion and the H-curve are continuous on some shapes only, so never pass.
+        assert_eq!(
+            keys(CurveFilter {
+                dimension: Some(2),
+                size: Some(4),
+                continuous: true,
+                ..CurveFilter::default()
+            }),
+            ["hilbert", "scan", "moore"]
+        );
+        assert_eq!(
+            keys(CurveFilter {
+                dimension: Some(3),
+                size: Some(4),
+                continuous: true,
+                ..CurveFilter::default()
+            }),
+            ["hilbert", "scan"]
+        );
+        assert_eq!(
+            keys(CurveFilter {
+                dimension: Some(2),
+                size: Some(9),
+                ..CurveFilter::default()
+            }),
+            ["scan", "onion", "peano"]
+        );
+    }
+
+    #[test]
+    fn size_queries_match_validate() {
+        for entry in REGISTRY {
+            for dimension in 1..=4 {
+                let sizes = valid_sizes(entry.key, dimension, 300).unwrap();
+                let expected: Vec<u32> = (1..=300)
+                    .filter(|&size| validate(entry.key, dimension, size).is_ok())
+                    .collect();
+                assert_eq!(sizes, expected, "{} {dimension}D", entry.key);
+
+                match max_size(entry.key, dimension).unwrap() {
+                    Some(max) => {
+                        assert!(validate(entry.key, dimension, max).is_ok());
+                        let above = entry.sizes.candidates(max).nth(1);
+                        assert!(
+                            above.is_none_or(|size| validate(entry.key, dimension, size).is_err())
+                        );
+                    }
+                    None => assert!(sizes.is_empty()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn size_limits() {
+        assert_eq!(max_size("hilbert", 2).unwrap(), Some(1 << 31));
+        assert_eq!(max_size("hilbert", 3).unwrap(), Some(1 << 21));
+        assert_eq!(max_size("gray", 2).unwrap(), Some(1 << 15));
+        assert_eq!(max_size("scan", 2).unwrap(), Some(65535));
+        assert_eq!(max_size("onion", 3).unwrap(), Some(1625));
+        assert_eq!(max_size("hcurve", 1).unwrap(), None);
+        assert_eq!(size_at_least("hilbert", 2, 5).unwrap(), Some(8));
+        assert_eq!(size_at_least("scan", 3, 5).unwrap(), Some(5));
+        assert_eq!(size_at_least("zorder", 2, 1 << 16).unwrap(), Some(1 << 16));
+        assert_eq!(size_at_least("zorder", 3, 1 << 22).unwrap(), None);
+        assert!(max_size("nope", 2).is_err());
+    }
+
+    #[test]
+    fn wide_curves_build_by_name() {
+        for (key, dimension, size) in [("hilbert", 2, 1 << 16), ("hilbert", 3, 1 << 20)] {
+            let curve = construct(key, dimension, size).unwrap();
+            let length = u64::from(size).pow(dimension);
+            assert_eq!(curve.length_u64(), length, "{key} {dimension}D");
+            assert!(curve.try_length().is_err());
+            let p = curve.point_u64(length - 1).unwrap();
+            assert_eq!(curve.index_u64(&p), length - 1);
+        }
+        let morton = construct("morton", 3, 1 << 21).unwrap();
+        let p = morton.point_u64(1 << 62).unwrap();
+        assert_eq!(p, Point::new(vec![0, 0, 1 << 20]));
+        assert_eq!(morton.index_u64(&p), 1 << 62);
+
+        assert!(construct("hilbert", 32, 2).is_err());
+        assert!(construct("zorder", 4, 1 << 16).is_err());
+        assert!(construct("gray", 2, 1 << 16).is_err());
+    }
+
+    #[test]
+    fn construct_rect_builds_rectangular_curves() {
+        for key in ["scan", "zorder", "onion"] {
+            let curve = construct_rect(key, &[512, 64]).unwrap();
+            assert_eq!(curve.sides(), [512, 64], "{key}");
+            assert_eq!(curve.length(), 512 * 64, "{key}");
+        }
+        assert_eq!(construct_rect("hilbert", &[8, 8]).unwrap().length(), 64);
+        assert!(construct_rect("hilbert", &[8, 4]).is_err());
+        assert!(construct_rect("zorder", &[8, 6]).is_err());
+        assert!(construct_rect("nonesuch", &[8, 4]).is_err());
+    }
 }
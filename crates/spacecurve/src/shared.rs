@@ -0,0 +1,192 @@
+//! Precomputed curves shared between threads.
+//!
+//! Curves such as the onion family do real work per lookup. A [`SharedCurve`] walks the
+//! curve once, stores both directions of the mapping in immutable tables, and keeps them
+//! behind an [`Arc`], so clones are cheap and every thread reads the same tables without
+//! locking. The tables hold `length × (dimensions + 1)` `u32`s.
+
+use std::sync::Arc;
+
+use crate::{curve_from_name, error, point::Point, spacecurve::SpaceCurve};
+
+/// Both directions of a curve's mapping, built once.
+#[derive(Debug)]
+struct Tables {
+    /// Curve the tables were built from, which supplies the name and description.
+    curve: Box<dyn SpaceCurve>,
+    /// Side length of each axis.
+    sides: Vec<u32>,
+    /// Coordinates of every point in index order, `dimensions` values per point.
+    points: Vec<u32>,
+    /// Curve index of every cell, in row-major order with the first axis varying fastest.
+    indices: Vec<u32>,
+}
+
+/// A cheaply cloneable, thread-safe curve answering lookups from precomputed tables.
+#[derive(Debug, Clone)]
+pub struct SharedCurve {
+    /// Tables shared by every clone.
+    tables: Arc<Tables>,
+}
+
+impl SharedCurve {
+    /// Precompute the tables for `curve`.
+    ///
+    /// Fails when the curve has more than `u32::MAX` points.
+    pub fn new(curve: Box<dyn SpaceCurve>) -> error::Result<Self> {
+        let dimensions = curve.dimensions() as usize;
+        let length = curve.try_length()?;
+        let mut points = Vec::with_capacity(length as usize * dimensions);
+        for index in 0..length {
+            points.extend_from_slice(curve.point(index).as_slice());
+        }
+        let sides = curve.sides();
+
+        let mut indices = vec![0; length as usize];
+        for (index, point) in points.chunks_exact(dimensions.max(1)).enumerate() {
+            indices[cell(&sides, point)] = index as u32;
+        }
+        Ok(Self {
+            tables: Arc::new(Tables {
+                curve,
+                sides,
+                points,
+                indices,
+            }),
+        })
+    }
+
+    /// Construct the curve registered as `name` and precompute its tables.
+    pub fn from_name(name: &str, dimension: u32, size: u32) -> error::Result<Self> {
+        Self::new(curve_from_name(name, dimension, size)?)
+    }
+
+    /// Side length of the grid; the longest side on a rectangular grid.
+    pub fn size(&self) -> u32 {
+        self.tables.sides.iter().copied().max().unwrap_or(1)
+    }
+
+    /// Coordinates of the point at `index`, borrowed from the table.
+    pub fn coordinates(&self, index: u32) -> &[u32] {
+        let dimensions = self.tables.curve.dimensions() as usize;
+        let start = index as usize * dimensions;
+        &self.tables.points[start..start + dimensions]
+    }
+}
+
+impl SpaceCurve for SharedCurve {
+    fn name(&self) -> &'static str {
+        self.tables.curve.name()
+    }
+
+    fn info(&self) -> &'static str {
+        self.tables.curve.info()
+    }
+
+    fn index(&self, p: &Point) -> u32 {
+        debug_assert_eq!(p.dimension(), self.dimensions(), "point dimension mismatch");
+        self.tables.indices[cell(&self.tables.sides, p.as_slice())]
+    }
+
+    fn point(&self, index: u32) -> Point {
+        debug_assert!(index < self.length(), "index out of bounds");
+        Point::new(self.coordinates(index))
+    }
+
+    fn length(&self) -> u32 {
+        self.tables.indices.len() as u32
+    }
+
+    fn dimensions(&self) -> u32 {
+        self.tables.curve.dimensions()
+    }
+
+    fn sides(&self) -> Vec<u32> {
+        self.tables.sides.clone()
+    }
+
+    fn nested_blocks(&self) -> bool {
+        self.tables.curve.nested_blocks()
+    }
+}
+
+/// Row-major position of `point` in a grid with the given `sides`, first axis fastest.
+fn cell(sides: &[u32], point: &[u32]) -> usize {
+    point
+        .iter()
+        .zip(sides)
+        .rev()
+        .fold(0, |acc, (&coordinate, &side)| {
+            acc * side as usize + coordinate as usize
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::{
+        curve_from_name, curve_from_name_rect,
+        registry::{self, CURVE_NAMES},
+    };
+
+    #[test]
+    fn matches_source_curve() {
+        for &name in CURVE_NAMES {
+            for (dimension, size) in [(2, 8), (3, 4)] {
+                let Some(size) = registry::size_at_least(name, dimension, size).unwrap() else {
+                    continue;
+                };
+                let curve = curve_from_name(name, dimension, size).unwrap();
+                let shared = SharedCurve::from_name(name, dimension, size).unwrap();
+                assert_eq!(shared.name(), curve.name());
+                assert_eq!(shared.length(), curve.length());
+                assert_eq!(shared.size(), size);
+                for index in 0..curve.length() {
+                    let point = curve.point(index);
+                    assert_eq!(shared.point(index), point);
+                    assert_eq!(shared.index(&point), index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handles_rectangular_grids() {
+        let curve = curve_from_name_rect("onion", &[6, 3]).unwrap();
+        let shared = SharedCurve::new(curve_from_name_rect("onion", &[6, 3]).unwrap()).unwrap();
+        assert_eq!(shared.sides(), [6, 3]);
+        for index in 0..curve.length() {
+            let point = curve.point(index);
+            assert_eq!(shared.point(index), point);
+            assert_eq!(shared.index(&point), index);
+        }
+    }
+
+    #[test]
+    fn clones_share_tables_across_threads() {
+        let shared = SharedCurve::from_name("hilbert", 2, 16).unwrap();
+        let handles: Vec<_> = (0..4)
+            .map(|offset| {
+                let curve = shared.clone();
+                thread::spawn(move || {
+                    (offset..curve.length())
+                        .step_by(4)
+                        .all(|index| curve.index(&curve.point(index)) == index)
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+        assert_eq!(Arc::strong_count(&shared.tables), 1);
+    }
+
+    #[test]
+    fn rejects_wide_curves() {
+        let wide = curve_from_name("hilbert", 2, 1 << 16).unwrap();
+        assert!(matches!(SharedCurve::new(wide), Err(error::Error::Size(_))));
+        assert!(SharedCurve::from_name("zorder", 2, 1 << 16).is_err());
+    }
+}
@@ -1,21 +1,33 @@
 //! The `SpaceCurve` trait describing a family of curves.
 
-use std::fmt;
+use std::{fmt, iter::FusedIterator, ops::Range};
 
-use crate::point;
+use smallvec::SmallVec;
+
+use crate::{
+    error::{self, Error},
+    point,
+};
 
 /// SpaceCurve is the core trait for space‑filling curves.
 ///
 /// Invariants and preconditions (apply to all implementations):
 /// - `dimensions()` is fixed at construction and defines the required point arity.
 /// - `index` expects a [`point::Point`] whose length matches `dimensions()` and whose
-///   coordinates lie in `[0, size-1]` for the curve.
+///   coordinates lie in `[0, side-1]` for each axis's side in `sides()`.
 /// - `point` expects `index < length()`.
 /// - Constructors are responsible for validating dimensionality and bounds (via
 ///   the shared [`spec::GridSpec`] helpers); callers should treat out‑of‑range
 ///   inputs as undefined behaviour. Implementations retain lightweight
 ///   `debug_assert!` guards for development builds.
-pub trait SpaceCurve: fmt::Debug {
+/// - Curves whose grids need more than 32 index bits (built with constructors such as
+///   [`crate::curves::hilbert::Hilbert::from_dimensions_u64`]) are addressed through
+///   [`SpaceCurve::index_u64`], [`SpaceCurve::point_u64`] and [`SpaceCurve::length_u64`].
+///   Their `length()` panics, as does `index()` on points past the first `u32::MAX`
+///   indices, so nothing walking `0..length()` silently covers only part of the curve.
+/// - Curves are immutable once built, so they must be `Send + Sync` and can be shared
+///   between threads; see [`crate::shared::SharedCurve`].
+pub trait SpaceCurve: fmt::Debug + Send + Sync {
     /// A short human-friendly name for this curve.
     ///
     /// This is intended for UI display and logs.
@@ -33,7 +45,409 @@ pub trait SpaceCurve: fmt::Debug {
     /// point will have a dimension matching that of the curve.
     fn point(&self, index: u32) -> point::Point;
     /// What is the maximum linear offset supported by this curve?
+    ///
+    /// Panics when the curve has more than `u32::MAX` points; use
+    /// [`SpaceCurve::length_u64`] for curves that can be built that large.
     fn length(&self) -> u32;
     /// How many dimensions does the curve have?
     fn dimensions(&self) -> u32;
+
+    /// Calculate the 64-bit linear index of an N-dimensional point.
+    ///
+    /// The default widens [`SpaceCurve::index`]; curves that can be built beyond 32 index
+    /// bits override it.
+    fn index_u64(&self, p: &point::Point) -> u64 {
+        u64::from(self.index(p))
+    }
+
+    /// Calculate the coordinates of a point from a 64-bit linear index.
+    ///
+    /// Fails when `index` is not below [`SpaceCurve::length_u64`]. The default narrows to
+    /// [`SpaceCurve::point`]; curves that can be built beyond 32 index bits override it.
+    fn point_u64(&self, index: u64) -> error::Result<point::Point> {
+        match u32::try_from(index) {
+            Ok(narrow) if index < self.length_u64() => Ok(self.point(narrow)),
+            _ => Err(out_of_range(index, self.length_u64())),
+        }
+    }
+
+    /// The number of points on the curve, as a 64-bit count.
+    fn length_u64(&self) -> u64 {
+        u64::from(self.length())
+    }
+
+    /// The number of points on the curve, failing when there are more than `u32::MAX`.
+    ///
+    /// Check this before handing a curve that may have been built with 64-bit indices to code
+    /// walking `0..length()`.
+    fn try_length(&self) -> error::Result<u32> {
+        u32::try_from(self.length_u64()).map_err(|_| {
+            Error::Size(format!(
+                "{} has {} points, more than u32 indices can address",
+                self.name(),
+                self.length_u64()
+            ))
+        })
+    }
+
+    /// Side length of the grid along each axis.
+    ///
+    /// The default assumes a cube holding [`SpaceCurve::length_u64`] points; curves built on
+    /// rectangular grids override it.
+    fn sides(&self) -> Vec<u32> {
+        let dimension = self.dimensions();
+        vec![cube_side(self.length_u64(), dimension); dimension as usize]
+    }
+
+    /// Whether every aligned block of `2^k` cells per side is visited as one contiguous run
+    /// of indices starting at a multiple of the block's cell count.
+    ///
+    /// Recursive curves such as Hilbert and Z-order have this property, which lets
+    /// [`crate::query::box_ranges`] cover a box block by block. The default is `false`.
+    fn nested_blocks(&self) -> bool {
+        false
+    }
+
+    /// The point after `p` on the curve, or `None` when `p` is the last point.
+    ///
+    /// `p` must lie on the curve. The default converts through the index; curves that can
+    /// step between neighbouring points directly override it.
+    fn next_point(&self, p: &point::Point) -> Option<point::Point> {
+        let index = self.index(p);
+        (index + 1 < self.length()).then(|| self.point(index + 1))
+    }
+
+    /// The point before `p` on the curve, or `None` when `p` is the first point.
+    ///
+    /// `p` must lie on the curve; see [`SpaceCurve::next_point`].
+    fn prev_point(&self, p: &point::Point) -> Option<point::Point> {
+        self.index(p).checked_sub(1).map(|index| self.point(index))
+    }
+
+    /// Indices of every point in `points`, in order.
+    ///
+    /// Converting a batch costs one dynamic call rather than one per point; see
+    /// [`SpaceCurve::index_many_into`].
+    fn index_many(&self, points: &[point::Point]) -> Vec<u32> {
+        let mut out = vec![0; points.len()];
+        self.index_many_into(points, &mut out);
+        out
+    }
+
+    /// Write the index of each point in `points` to the matching slot of `out`.
+    ///
+    /// Panics unless both slices have the same length.
+    fn index_many_into(&self, points: &[point::Point], out: &mut [u32]) {
+        assert_eq!(points.len(), out.len(), "output length mismatch");
+        for (slot, p) in out.iter_mut().zip(points) {
+            *slot = self.index(p);
+        }
+    }
+
+    /// Points at every index in `indices`, in order.
+    ///
+    /// See [`SpaceCurve::point_many_into`] to reuse an existing buffer.
+    fn point_many(&self, indices: &[u32]) -> Vec<point::Point> {
+        let mut out = vec![point::Point::new(SmallVec::new()); indices.len()];
+        self.point_many_into(indices, &mut out);
+        out
+    }
+
+    /// Write the point at each index in `indices` to the matching slot of `out`.
+    ///
+    /// Panics unless both slices have the same length.
+    fn point_many_into(&self, indices: &[u32], out: &mut [point::Point]) {
+        assert_eq!(indices.len(), out.len(), "output length mismatch");
+        for (slot, &index) in out.iter_mut().zip(indices) {
+            *slot = self.point(index);
+        }
+    }
+
+    /// Points at the indices in `range`, in index order, computed as they are consumed.
+    ///
+    /// `range` must lie within `0..length()`. The default looks every index up with
+    /// [`SpaceCurve::point`]; curves that can step from one point to the next more cheaply
+    /// override it.
+    fn iter_range(&self, range: Range<u32>) -> Box<dyn Iterator<Item = point::Point> + '_> {
+        Box::new(range.map(|index| self.point(index)))
+    }
+}
+
+/// The error for a 64-bit index not below a curve's `length`.
+pub fn out_of_range(index: u64, length: u64) -> Error {
+    Error::Size(format!(
+        "index {index} is out of range for a curve of {length} points"
+    ))
+}
+
+impl dyn SpaceCurve + '_ {
+    /// Every point of the curve, in index order; see [`points`].
+    ///
+    /// Panics when the curve has more than `u32::MAX` points; [`points`] reports that as an
+    /// error instead.
+    pub fn iter(&self) -> Points<'_> {
+        points(self).expect("curve has more than u32::MAX points; walk it with point_u64")
+    }
+}
+
+/// Every point of `curve`, in index order.
+///
+/// The iterator knows its exact length and runs from either end, so `rev`, `skip`, `step_by`
+/// and friends work without spelling out an index loop. Fails when the curve has more than
+/// `u32::MAX` points.
+pub fn points(curve: &dyn SpaceCurve) -> error::Result<Points<'_>> {
+    Ok(Points::new(curve, 0..curve.try_length()?))
+}
+
+/// Iterator over the points of a curve at a range of indices.
+///
+/// Walking forward streams points through [`SpaceCurve::iter_range`], so curves that step
+/// cheaply between neighbours stay fast. Skipping ahead or walking backward looks points up
+/// by index.
+pub struct Points<'a> {
+    /// The curve being walked.
+    curve: &'a dyn SpaceCurve,
+    /// Indices not yet yielded from either end.
+    range: Range<u32>,
+    /// Forward stream starting at `range.start`, opened on first use.
+    stream: Option<Box<dyn Iterator<Item = point::Point> + 'a>>,
+}
+
+impl<'a> Points<'a> {
+    /// The points of `curve` at the indices in `range`, which must lie within
+    /// `0..curve.length()`.
+    pub fn new(curve: &'a dyn SpaceCurve, range: Range<u32>) -> Self {
+        debug_assert!(range.end <= curve.length(), "range exceeds curve length");
+        Self {
+            curve,
+            range,
+            stream: None,
+        }
+    }
+
+    /// Indices not yet yielded, from the front and back.
+    pub fn remaining(&self) -> Range<u32> {
+        self.range.clone()
+    }
+}
+
+impl fmt::Debug for Points<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Points")
+            .field("curve", &self.curve.name())
+            .field("range", &self.range)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Iterator for Points<'_> {
+    type Item = point::Point;
+
+    fn next(&mut self) -> Option<point::Point> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let (curve, range) = (self.curve, self.range.clone());
+        let point = self
+            .stream
+            .get_or_insert_with(|| curve.iter_range(range))
+            .next();
+        self.range.start += 1;
+        point
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<point::Point> {
+        if n > 0 {
+            let skip = u32::try_from(n).unwrap_or(u32::MAX);
+            self.range.start = self.range.start.saturating_add(skip).min(self.range.end);
+            self.stream = None;
+        }
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for Points<'_> {
+    fn next_back(&mut self) -> Option<point::Point> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        Some(self.curve.point(self.range.end))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<point::Point> {
+        let skip = u32::try_from(n).unwrap_or(u32::MAX);
+        self.range.end = self.range.end.saturating_sub(skip).max(self.range.start);
+        self.next_back()
+    }
+}
+
+impl ExactSizeIterator for Points<'_> {}
+
+impl FusedIterator for Points<'_> {}
+
+/// Boxed curves, such as those built by the registry, are curves too, so they can be handed to
+/// wrappers that take a curve by value.
+impl<C: SpaceCurve + ?Sized> SpaceCurve for Box<C> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn info(&self) -> &'static str {
+        (**self).info()
+    }
+
+    fn index(&self, p: &point::Point) -> u32 {
+        (**self).index(p)
+    }
+
+    fn point(&self, index: u32) -> point::Point {
+        (**self).point(index)
+    }
+
+    fn length(&self) -> u32 {
+        (**self).length()
+    }
+
+    fn dimensions(&self) -> u32 {
+        (**self).dimensions()
+    }
+
+    fn index_u64(&self, p: &point::Point) -> u64 {
+        (**self).index_u64(p)
+    }
+
+    fn point_u64(&self, index: u64) -> error::Result<point::Point> {
+        (**self).point_u64(index)
+    }
+
+    fn length_u64(&self) -> u64 {
+        (**self).length_u64()
+    }
+
+    fn sides(&self) -> Vec<u32> {
+        (**self).sides()
+    }
+
+    fn nested_blocks(&self) -> bool {
+        (**self).nested_blocks()
+    }
+
+    fn next_point(&self, p: &point::Point) -> Option<point::Point> {
+        (**self).next_point(p)
+    }
+
+    fn prev_point(&self, p: &point::Point) -> Option<point::Point> {
+        (**self).prev_point(p)
+    }
+
+    fn index_many(&self, points: &[point::Point]) -> Vec<u32> {
+        (**self).index_many(points)
+    }
+
+    fn index_many_into(&self, points: &[point::Point], out: &mut [u32]) {
+        (**self).index_many_into(points, out);
+    }
+
+    fn point_many(&self, indices: &[u32]) -> Vec<point::Point> {
+        (**self).point_many(indices)
+    }
+
+    fn point_many_into(&self, indices: &[u32], out: &mut [point::Point]) {
+        (**self).point_many_into(indices, out);
+    }
+
+    fn iter_range(&self, range: Range<u32>) -> Box<dyn Iterator<Item = point::Point> + '_> {
+        (**self).iter_range(range)
+    }
+}
+
+/// Side of a cube holding `length` points in `dimension` axes.
+fn cube_side(length: u64, dimension: u32) -> u32 {
+    let estimate = (length as f64).powf(1.0 / f64::from(dimension)).round() as u32;
+    (estimate.saturating_sub(1)..=estimate.saturating_add(1))
+        .find(|&side| u64::from(side).checked_pow(dimension) == Some(length))
+        .unwrap_or(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve_from_name;
+
+    #[test]
+    fn points_run_from_both_ends() {
+        let curve = curve_from_name("hilbert", 2, 4).unwrap();
+        let all: Vec<_> = (0..curve.length()).map(|i| curve.point(i)).collect();
+        assert_eq!(curve.iter().len(), 16);
+        assert!(curve.iter().eq(all.iter().cloned()));
+        assert!(curve.iter().rev().eq(all.iter().rev().cloned()));
+        assert!(curve.iter().step_by(5).eq(all.iter().step_by(5).cloned()));
+
+        let mut points = curve.iter();
+        assert_eq!(points.next(), Some(all[0].clone()));
+        assert_eq!(points.next_back(), Some(all[15].clone()));
+        assert_eq!(points.nth(3), Some(all[4].clone()));
+        assert_eq!(points.nth_back(2), Some(all[12].clone()));
+        assert_eq!(points.remaining(), 5..12);
+        assert_eq!(points.len(), 7);
+        assert!(points.by_ref().eq(all[5..12].iter().cloned()));
+        assert_eq!(points.next(), None);
+        assert_eq!(points.nth(100), None);
+        assert_eq!(points.next_back(), None);
+    }
+
+    #[test]
+    fn batches_match_single_conversions() {
+        let curve = curve_from_name("zorder", 3, 8).unwrap();
+        let indices: Vec<u32> = (0..curve.length()).rev().step_by(7).collect();
+        let points = curve.point_many(&indices);
+        assert_eq!(
+            points,
+            indices.iter().map(|&i| curve.point(i)).collect::<Vec<_>>()
+        );
+        assert_eq!(curve.index_many(&points), indices);
+
+        let mut reused = vec![point::Point::new(vec![0; 3]); 2];
+        curve.point_many_into(&[5, 9], &mut reused);
+        assert_eq!(reused, [curve.point(5), curve.point(9)]);
+        let mut out = [0; 2];
+        curve.index_many_into(&reused, &mut out);
+        assert_eq!(out, [5, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "output length mismatch")]
+    fn batches_reject_short_outputs() {
+        let curve = curve_from_name("hilbert", 2, 4).unwrap();
+        curve.point_many_into(&[1, 2], &mut []);
+    }
+
+    #[test]
+    fn default_point_u64_checks_the_length() {
+        let curve = curve_from_name("onion", 2, 5).unwrap();
+        assert_eq!(curve.point_u64(24).unwrap(), curve.point(24));
+        assert!(curve.point_u64(25).is_err());
+        assert!(curve.point_u64(1 << 40).is_err());
+    }
+
+    #[test]
+    fn cube_sides_invert_lengths() {
+        assert_eq!(cube_side(64, 2), 8);
+        assert_eq!(cube_side(27, 3), 3);
+        assert_eq!(cube_side(1 << 30, 3), 1024);
+        assert_eq!(cube_side(1 << 60, 3), 1 << 20);
+        assert_eq!(cube_side(5, 1), 5);
+    }
+
+    #[test]
+    fn points_reject_wide_curves() {
+        let wide = curve_from_name("hilbert", 2, 1 << 16).unwrap();
+        assert!(matches!(points(wide.as_ref()), Err(Error::Size(_))));
+    }
 }
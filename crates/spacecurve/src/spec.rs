@@ -1,7 +1,20 @@
 //! Grid specification helpers used by curve constructors and registry validation.
 
+use smallvec::SmallVec;
+
 use crate::{error, error::Error};
 
+/// Width in bits of a curve index.
+///
+/// Index math in the power-of-two cores is 64-bit, but [`crate::SpaceCurve`] addresses points
+/// with `u32`, so grids needing `INDEX_BITS` or more index bits are rejected.
+pub const INDEX_BITS: u32 = u32::BITS;
+
+/// Width in bits of a 64-bit curve index, as used by [`crate::SpaceCurve::index_u64`].
+///
+/// Curves built for 64-bit indices reject grids needing `WIDE_INDEX_BITS` or more index bits.
+pub const WIDE_INDEX_BITS: u32 = u64::BITS;
+
 /// Describes the dimensionality and side length of a grid along with derived values.
 ///
 /// The helper centralizes guard logic (non‑zero sizes, power‑of‑two checks, overflow checks)
@@ -13,7 +26,7 @@ pub struct GridSpec {
     /// Side length per dimension.
     size: u32,
     /// Total number of points (`size^dimension`).
-    length: u32,
+    length: u64,
     /// Order (bits per axis) when `size` is a power of two.
     order: Option<u32>,
     /// Bit width per axis when `size` is a power of two.
@@ -27,6 +40,19 @@ impl GridSpec {
     /// - `size` must be >= 1
     /// - `size.pow(dimension)` must fit inside `u32`
     pub fn new(dimension: u32, size: u32) -> error::Result<Self> {
+        let spec = Self::new_u64(dimension, size)?;
+        if spec.length > u64::from(u32::MAX) {
+            return Err(Error::Size(
+                "curve length (size^dimension) exceeds u32 bounds".to_string(),
+            ));
+        }
+        Ok(spec)
+    }
+
+    /// Construct a spec for a grid addressed with 64-bit indices.
+    ///
+    /// As [`Self::new`], but `size.pow(dimension)` need only fit inside `u64`.
+    pub fn new_u64(dimension: u32, size: u32) -> error::Result<Self> {
         if dimension == 0 {
             return Err(Error::Shape("dimension must be >= 1".to_string()));
         }
@@ -34,8 +60,8 @@ impl GridSpec {
             return Err(Error::Size("size must be >= 1".to_string()));
         }
 
-        let length = size.checked_pow(dimension).ok_or_else(|| {
-            Error::Size("curve length (size^dimension) exceeds u32 bounds".to_string())
+        let length = u64::from(size).checked_pow(dimension).ok_or_else(|| {
+            Error::Size("curve length (size^dimension) exceeds u64 bounds".to_string())
         })?;
 
         Ok(Self {
@@ -51,28 +77,55 @@ impl GridSpec {
     ///
     /// Populates `order` and `bits_per_axis` with `size.trailing_zeros()`.
     pub fn power_of_two(dimension: u32, size: u32) -> error::Result<Self> {
+        Self::require_power_of_two(size)?;
+        Ok(Self::new(dimension, size)?.with_order())
+    }
+
+    /// As [`Self::power_of_two`], for a grid addressed with 64-bit indices.
+    pub fn power_of_two_u64(dimension: u32, size: u32) -> error::Result<Self> {
+        Self::require_power_of_two(size)?;
+        Ok(Self::new_u64(dimension, size)?.with_order())
+    }
+
+    /// Construct a spec requiring `size` to be a positive power of three, as base-3 curves
+    /// such as Peano need.
+    pub fn power_of_three(dimension: u32, size: u32) -> error::Result<Self> {
+        if size == 0 || 3u32.pow(size.ilog(3)) != size {
+            return Err(Error::Size(
+                "size must be a positive power of three".to_string(),
+            ));
+        }
+        Self::new(dimension, size)
+    }
+
+    /// Reject sizes that are not positive powers of two.
+    fn require_power_of_two(size: u32) -> error::Result<()> {
         if size == 0 || !size.is_power_of_two() {
             return Err(Error::Size(
                 "size must be a positive power of two".to_string(),
             ));
         }
+        Ok(())
+    }
 
-        let mut spec = Self::new(dimension, size)?;
-        let order = size.trailing_zeros();
-        spec.order = Some(order);
-        spec.bits_per_axis = Some(order);
-        Ok(spec)
+    /// Fill in the order of a power-of-two grid.
+    fn with_order(mut self) -> Self {
+        let order = self.size.trailing_zeros();
+        self.order = Some(order);
+        self.bits_per_axis = Some(order);
+        self
     }
 
     /// Require that the total number of index bits is strictly less than `limit`.
     ///
-    /// Useful for curves that encode indices into `u32` using `bits_per_axis * dimension`.
+    /// Useful for curves whose indices take `bits_per_axis * dimension` bits; pass
+    /// [`INDEX_BITS`] to bound them by the width of a curve index.
     pub fn require_index_bits_lt(&self, limit: u32) -> error::Result<()> {
         if let Some(bits) = self.bits_per_axis {
             let total_bits = (bits as u64) * (self.dimension as u64);
             if total_bits >= limit as u64 {
                 return Err(Error::Size(format!(
-                    "index requires {total_bits} bits; must be < {limit} for u32 indices"
+                    "index requires {total_bits} bits; must be < {limit}"
                 )));
             }
         }
@@ -90,7 +143,15 @@ impl GridSpec {
     }
 
     /// Total number of points in the grid (`size^dimension`).
+    ///
+    /// Panics when the grid has more than `u32::MAX` points, which only grids built with
+    /// [`Self::new_u64`] can; use [`Self::length_u64`] for those.
     pub fn length(&self) -> u32 {
+        u32::try_from(self.length).expect("grid has more than u32::MAX points; use length_u64")
+    }
+
+    /// Total number of points in the grid (`size^dimension`).
+    pub fn length_u64(&self) -> u64 {
         self.length
     }
 
@@ -103,4 +164,160 @@ impl GridSpec {
     pub fn bits_per_axis(&self) -> Option<u32> {
         self.bits_per_axis
     }
+
+    /// Side length of the sub-blocks at recursion `level` of a power‑of‑two grid.
+    ///
+    /// Level 0 is the whole grid and each further level halves every axis, giving the
+    /// quadrants (2D) or octants (3D) that self-similar curves fill one after another.
+    /// Returns `None` for grids that are not powers of two or levels beyond [`Self::order`].
+    pub fn block_side(&self, level: u32) -> Option<u32> {
+        let order = self.order?;
+        (level <= order).then(|| self.size >> level)
+    }
+
+    /// Block coordinates of the sub-block containing `point` at recursion `level`.
+    ///
+    /// Each coordinate lies in `[0, 2^level - 1]`. Returns `None` when
+    /// [`Self::block_side`] does, or when `point` does not match the grid's dimension.
+    pub fn block_of(&self, point: &[u32], level: u32) -> Option<Vec<u32>> {
+        let side = self.block_side(level)?;
+        (point.len() == self.dimension as usize).then(|| point.iter().map(|c| c / side).collect())
+    }
+}
+
+/// Describes a grid whose axes may have different side lengths, such as a 512×64 image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RectGridSpec {
+    /// Side length of each axis.
+    sides: SmallVec<[u32; 4]>,
+    /// Total number of points (the product of the sides).
+    length: u32,
+}
+
+impl RectGridSpec {
+    /// Construct a spec for a grid with the given side per axis.
+    ///
+    /// - there must be at least one side
+    /// - every side must be >= 1
+    /// - the product of the sides must fit inside `u32`
+    pub fn new(sides: &[u32]) -> error::Result<Self> {
+        if sides.is_empty() {
+            return Err(Error::Shape("dimension must be >= 1".to_string()));
+        }
+        if sides.contains(&0) {
+            return Err(Error::Size("every side must be >= 1".to_string()));
+        }
+        let length = sides
+            .iter()
+            .try_fold(1u32, |acc, &side| acc.checked_mul(side))
+            .ok_or_else(|| {
+                Error::Size("curve length (product of sides) exceeds u32 bounds".to_string())
+            })?;
+        Ok(Self {
+            sides: SmallVec::from_slice(sides),
+            length,
+        })
+    }
+
+    /// Construct a spec requiring every side to be a positive power of two.
+    pub fn power_of_two(sides: &[u32]) -> error::Result<Self> {
+        if !sides.iter().all(|side| side.is_power_of_two()) {
+            return Err(Error::Size(
+                "every side must be a positive power of two".to_string(),
+            ));
+        }
+        Self::new(sides)
+    }
+
+    /// Dimension count.
+    pub fn dimension(&self) -> u32 {
+        self.sides.len() as u32
+    }
+
+    /// Side length of each axis.
+    pub fn sides(&self) -> &[u32] {
+        &self.sides
+    }
+
+    /// Total number of points in the grid (the product of the sides).
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// The common side length when every axis has the same one.
+    pub fn cube_side(&self) -> Option<u32> {
+        let first = self.sides[0];
+        self.sides
+            .iter()
+            .all(|&side| side == first)
+            .then_some(first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_side_halves_per_level() -> error::Result<()> {
+        let spec = GridSpec::power_of_two(2, 8)?;
+        assert_eq!(spec.block_side(0), Some(8));
+        assert_eq!(spec.block_side(1), Some(4));
+        assert_eq!(spec.block_side(3), Some(1));
+        assert_eq!(spec.block_side(4), None);
+        assert_eq!(GridSpec::new(2, 6)?.block_side(1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn power_of_three_sizes() {
+        for size in [1, 3, 9, 27, 3u32.pow(20)] {
+            assert!(GridSpec::power_of_three(1, size).is_ok(), "{size}");
+        }
+        for size in [0, 2, 6, 10, u32::MAX] {
+            assert!(GridSpec::power_of_three(1, size).is_err(), "{size}");
+        }
+        assert!(GridSpec::power_of_three(3, 3u32.pow(7)).is_err());
+    }
+
+    #[test]
+    fn block_of_locates_quadrants() -> error::Result<()> {
+        let spec = GridSpec::power_of_two(3, 4)?;
+        assert_eq!(spec.block_of(&[0, 1, 3], 1), Some(vec![0, 0, 1]));
+        assert_eq!(spec.block_of(&[3, 2, 1], 2), Some(vec![3, 2, 1]));
+        assert_eq!(spec.block_of(&[1, 1], 1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn wide_specs_allow_lengths_beyond_u32() -> error::Result<()> {
+        assert!(GridSpec::power_of_two(3, 1 << 20).is_err());
+        let spec = GridSpec::power_of_two_u64(3, 1 << 20)?;
+        assert_eq!(spec.length_u64(), 1 << 60);
+        assert!(spec.require_index_bits_lt(WIDE_INDEX_BITS).is_ok());
+        assert!(GridSpec::power_of_two_u64(4, 1 << 16).is_err());
+        assert!(GridSpec::new_u64(5, u32::MAX).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "use length_u64")]
+    fn length_panics_beyond_u32() {
+        GridSpec::power_of_two_u64(2, 1 << 16).unwrap().length();
+    }
+
+    #[test]
+    fn rect_specs_take_a_side_per_axis() -> error::Result<()> {
+        let spec = RectGridSpec::new(&[512, 64])?;
+        assert_eq!(spec.dimension(), 2);
+        assert_eq!(spec.length(), 512 * 64);
+        assert_eq!(spec.cube_side(), None);
+        assert_eq!(RectGridSpec::new(&[4, 4, 4])?.cube_side(), Some(4));
+        assert!(RectGridSpec::new(&[]).is_err());
+        assert!(RectGridSpec::new(&[3, 0]).is_err());
+        assert!(RectGridSpec::new(&[1 << 16, 1 << 16]).is_err());
+        assert!(RectGridSpec::power_of_two(&[8, 2])?.length() == 16);
+        assert!(RectGridSpec::power_of_two(&[8, 3]).is_err());
+        Ok(())
+    }
 }
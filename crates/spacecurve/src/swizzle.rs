@@ -0,0 +1,249 @@
+//! Z-order ("swizzled") texel addressing as used by GPU texture tiling.
+//!
+//! A [`Swizzle`] cuts a texture into tiles whose sides are powers of two, lays the tiles out
+//! row by row, and orders the texels inside each tile along a Z curve. One tile covering the
+//! whole texture gives the classic fully swizzled layout; smaller tiles give the block-linear
+//! hybrids many consoles and GPUs use.
+//!
+//! Tiles need not be square. The low bits of `x` and `y` are interleaved until the shorter side
+//! runs out, and the remaining bits of the longer side follow unchanged, so a 4×2 tile holds
+//! two 2×2 Z blocks side by side.
+
+use crate::{
+    error::{self, Error},
+    ops::{deinterleave_lsb, interleave_lsb},
+};
+
+/// Texel addressing for a texture stored as a row of swizzled tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Swizzle {
+    /// Texture width in texels.
+    width: u32,
+    /// Texture height in texels.
+    height: u32,
+    /// Bits of `x` addressed within a tile.
+    tile_width_bits: u32,
+    /// Bits of `y` addressed within a tile.
+    tile_height_bits: u32,
+    /// Tiles per row of tiles.
+    tiles_across: u32,
+    /// Rows of tiles.
+    tiles_down: u32,
+}
+
+impl Swizzle {
+    /// A texture of `width × height` texels, fully swizzled as a single tile.
+    ///
+    /// Both sides must be powers of two.
+    pub fn morton(width: u32, height: u32) -> error::Result<Self> {
+        Self::block_linear(width, height, width, height)
+    }
+
+    /// A texture of `width × height` texels cut into `tile_width × tile_height` swizzled tiles
+    /// laid out in row-major order.
+    ///
+    /// Tile sides must be powers of two; the texture may be any size, with the last row and
+    /// column of tiles padded. Fails when the padded texture has more than `u32::MAX` texels.
+    pub fn block_linear(
+        width: u32,
+        height: u32,
+        tile_width: u32,
+        tile_height: u32,
+    ) -> error::Result<Self> {
+        if width == 0 || height == 0 {
+            return Err(Error::Size(format!(
+                "texture must not be empty, got {width}x{height}"
+            )));
+        }
+        if !tile_width.is_power_of_two() || !tile_height.is_power_of_two() {
+            return Err(Error::Size(format!(
+                "tile sides must be powers of two, got {tile_width}x{tile_height}"
+            )));
+        }
+        let swizzle = Self {
+            width,
+            height,
+            tile_width_bits: tile_width.trailing_zeros(),
+            tile_height_bits: tile_height.trailing_zeros(),
+            tiles_across: width.div_ceil(tile_width),
+            tiles_down: height.div_ceil(tile_height),
+        };
+        let padded = u64::from(swizzle.tiles_across)
+            * u64::from(swizzle.tiles_down)
+            * u64::from(tile_width)
+            * u64::from(tile_height);
+        if padded > u64::from(u32::MAX) {
+            return Err(Error::Size(format!(
+                "{width}x{height} texture in {tile_width}x{tile_height} tiles exceeds u32 bounds"
+            )));
+        }
+        Ok(swizzle)
+    }
+
+    /// Texture width in texels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Texture height in texels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Tile width and height in texels.
+    pub fn tile(&self) -> (u32, u32) {
+        (1 << self.tile_width_bits, 1 << self.tile_height_bits)
+    }
+
+    /// Number of texels a buffer needs, including padding in the edge tiles.
+    pub fn len(&self) -> u32 {
+        self.tiles_across * self.tiles_down * self.tile_area()
+    }
+
+    /// Whether the buffer holds no texels; never true, as both sides are non-zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Buffer offset of texel (`x`, `y`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the texel lies outside the texture.
+    pub fn offset(&self, x: u32, y: u32) -> u32 {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is outside the {}x{} texture",
+            self.width,
+            self.height
+        );
+        let tile = (y >> self.tile_height_bits) * self.tiles_across + (x >> self.tile_width_bits);
+        let (tile_width, tile_height) = self.tile();
+        tile * self.tile_area() + self.within(x & (tile_width - 1), y & (tile_height - 1))
+    }
+
+    /// Texel stored at `offset`, or `None` when the offset is past the end of the buffer or
+    /// falls in the padding of an edge tile.
+    pub fn position(&self, offset: u32) -> Option<(u32, u32)> {
+        if offset >= self.len() {
+            return None;
+        }
+        let tile = offset / self.tile_area();
+        let (x, y) = self.within_inverse(offset % self.tile_area());
+        let x = ((tile % self.tiles_across) << self.tile_width_bits) + x;
+        let y = ((tile / self.tiles_across) << self.tile_height_bits) + y;
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// Texels per tile.
+    fn tile_area(&self) -> u32 {
+        1 << (self.tile_width_bits + self.tile_height_bits)
+    }
+
+    /// Z-order offset of (`x`, `y`) within a tile.
+    fn within(&self, x: u32, y: u32) -> u32 {
+        let shared = self.tile_width_bits.min(self.tile_height_bits);
+        let mask = (1 << shared) - 1;
+        let low = interleave_lsb(&[x & mask, y & mask], shared);
+        let rest = if self.tile_width_bits > shared { x } else { y } >> shared;
+        low | rest << (2 * shared)
+    }
+
+    /// Position within a tile of Z-order offset `offset`; the inverse of [`Self::within`].
+    fn within_inverse(&self, offset: u32) -> (u32, u32) {
+        let shared = self.tile_width_bits.min(self.tile_height_bits);
+        let low = deinterleave_lsb(2, shared, offset & ((1 << (2 * shared)) - 1));
+        let rest = (offset >> (2 * shared)) << shared;
+        if self.tile_width_bits > shared {
+            (low[0] | rest, low[1])
+        } else {
+            (low[0], low[1] | rest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trips(swizzle: &Swizzle) {
+        let mut seen = vec![false; swizzle.len() as usize];
+        for y in 0..swizzle.height() {
+            for x in 0..swizzle.width() {
+                let offset = swizzle.offset(x, y);
+                assert!(!seen[offset as usize], "offset {offset} used twice");
+                seen[offset as usize] = true;
+                assert_eq!(swizzle.position(offset), Some((x, y)));
+            }
+        }
+        for (offset, used) in seen.iter().enumerate() {
+            if !used {
+                assert_eq!(swizzle.position(offset as u32), None);
+            }
+        }
+        assert_eq!(swizzle.position(swizzle.len()), None);
+    }
+
+    #[test]
+    fn square_morton_matches_interleave() {
+        let swizzle = Swizzle::morton(8, 8).unwrap();
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(swizzle.offset(x, y), interleave_lsb(&[x, y], 3));
+            }
+        }
+    }
+
+    #[test]
+    fn rectangular_morton_appends_long_axis() {
+        let wide = Swizzle::morton(4, 2).unwrap();
+        let order: Vec<_> = (0..8).map(|i| wide.position(i).unwrap()).collect();
+        assert_eq!(
+            order,
+            [
+                (0, 0),
+                (1, 0),
+                (0, 1),
+                (1, 1),
+                (2, 0),
+                (3, 0),
+                (2, 1),
+                (3, 1)
+            ]
+        );
+        let tall = Swizzle::morton(2, 4).unwrap();
+        assert_eq!(tall.offset(1, 3), 7);
+        assert_eq!(tall.offset(0, 2), 4);
+    }
+
+    #[test]
+    fn round_trip_layouts() {
+        for swizzle in [
+            Swizzle::morton(16, 16).unwrap(),
+            Swizzle::morton(32, 4).unwrap(),
+            Swizzle::morton(1, 8).unwrap(),
+            Swizzle::block_linear(16, 16, 4, 4).unwrap(),
+            Swizzle::block_linear(13, 7, 4, 2).unwrap(),
+            Swizzle::block_linear(5, 9, 1, 8).unwrap(),
+            Swizzle::block_linear(3, 3, 8, 8).unwrap(),
+        ] {
+            round_trips(&swizzle);
+        }
+    }
+
+    #[test]
+    fn block_linear_lays_tiles_in_rows() {
+        let swizzle = Swizzle::block_linear(8, 8, 4, 4).unwrap();
+        assert_eq!(swizzle.offset(4, 0), 16);
+        assert_eq!(swizzle.offset(0, 4), 32);
+        assert_eq!(swizzle.offset(5, 5), 48 + 3);
+    }
+
+    #[test]
+    fn rejects_bad_shapes() {
+        assert!(Swizzle::morton(6, 8).is_err());
+        assert!(Swizzle::block_linear(0, 8, 4, 4).is_err());
+        assert!(Swizzle::block_linear(8, 8, 3, 4).is_err());
+        assert!(Swizzle::block_linear(u32::MAX, 3, 1, 4).is_err());
+    }
+}
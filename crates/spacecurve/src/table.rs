@@ -0,0 +1,249 @@
+//! Finite state machine form of the recursive power‑of‑two curves.
+//!
+//! Hilbert and Z-order curves read their index one digit of `dimension` bits at a time,
+//! from the most significant end. Each digit picks a child cell of the current block and a
+//! state for the sub-curve inside it, so a curve is fully described by a table indexed by
+//! `(state, digit)`. Tables are small and branch-free to walk, which suits bulk point
+//! generation on hardware where the recursive definitions are awkward.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    ops::Range,
+};
+
+use smallvec::{SmallVec, smallvec};
+
+use crate::{
+    curves::{hilbert::Hilbert, zorder::ZOrder},
+    error,
+    point::Point,
+};
+
+/// A curve as a state machine over the digits of its index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateTable {
+    /// Number of dimensions; each digit has this many bits.
+    pub dimension: u32,
+    /// Bits per axis of the grid the table is walked over.
+    pub order: u32,
+    /// Transition for state `s` and digit `d` at `transitions[(s << dimension) | d]`.
+    ///
+    /// Each entry holds the child cell entered, with the bit of coordinate `c` at bit `c`, and
+    /// the state of the sub-curve inside that cell. The walk starts in state 0.
+    pub transitions: Vec<(u32, u32)>,
+}
+
+impl StateTable {
+    /// Tabulate a curve whose levels are produced by `step`, starting from `start`.
+    ///
+    /// States are discovered breadth-first and numbered in order of discovery.
+    pub(crate) fn explore<S>(
+        dimension: u32,
+        order: u32,
+        start: S,
+        step: impl Fn(S, u32) -> (u32, S),
+    ) -> Self
+    where
+        S: Copy + Eq + Hash,
+    {
+        let digits = 1u32 << dimension;
+        let mut ids = HashMap::from([(start, 0u32)]);
+        let mut queue = VecDeque::from([start]);
+        let mut transitions = Vec::new();
+        while let Some(state) = queue.pop_front() {
+            for digit in 0..digits {
+                let (cell, next) = step(state, digit);
+                let fresh = ids.len() as u32;
+                let id = *ids.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    fresh
+                });
+                transitions.push((cell, id));
+            }
+        }
+        Self {
+            dimension,
+            order,
+            transitions,
+        }
+    }
+
+    /// Number of states in the table.
+    pub fn states(&self) -> u32 {
+        (self.transitions.len() >> self.dimension) as u32
+    }
+
+    /// Coordinates of the point at `index`, walking the table level by level.
+    pub fn point(&self, index: u32) -> SmallVec<[u32; 4]> {
+        let mask = (1u32 << self.dimension) - 1;
+        let mut coords = smallvec![0; self.dimension as usize];
+        let mut state = 0;
+        for level in (0..self.order).rev() {
+            let digit = (index >> (level * self.dimension)) & mask;
+            let (cell, next) = self.transitions[((state << self.dimension) | digit) as usize];
+            for (axis, coord) in coords.iter_mut().enumerate() {
+                *coord |= ((cell >> axis) & 1) << level;
+            }
+            state = next;
+        }
+        coords
+    }
+
+    /// Points at the indices in `range`, stepping the table from one index to the next.
+    pub fn points(&self, range: Range<u32>) -> Walk<u32, impl Fn(u32, u32) -> (u32, u32) + '_> {
+        Walk::new(self.dimension, self.order, 0, range, |state, digit| {
+            self.transitions[((state << self.dimension) | digit) as usize]
+        })
+    }
+}
+
+/// Points over a range of indices, produced by walking a digit state machine.
+///
+/// `step` maps a state and digit to the child cell entered, with the bit of coordinate `c` at
+/// bit `c`, and the next state, as in [`StateTable::transitions`]. Moving to the next index
+/// only revisits the levels whose digits changed: the lowest level alone for all but one in
+/// `2^dimension` steps.
+#[derive(Debug, Clone)]
+pub struct Walk<S, F> {
+    /// Number of dimensions; each digit has this many bits.
+    dimension: u32,
+    /// Transition function of the state machine.
+    step: F,
+    /// Index of the point held in `coords`.
+    index: u32,
+    /// End of the range, exclusive.
+    end: u32,
+    /// State entering each level, indexed by level with the least significant first.
+    states: SmallVec<[S; 16]>,
+    /// Coordinates of the point at `index`.
+    coords: SmallVec<[u32; 4]>,
+}
+
+impl<S, F> Walk<S, F>
+where
+    S: Copy,
+    F: Fn(S, u32) -> (u32, S),
+{
+    /// Walk the indices in `range` of a curve with `order` levels, starting each index in `start`.
+    pub(crate) fn new(dimension: u32, order: u32, start: S, range: Range<u32>, step: F) -> Self {
+        let mut walk = Self {
+            dimension,
+            step,
+            index: range.start,
+            end: range.end,
+            states: smallvec![start; order as usize],
+            coords: smallvec![0; dimension as usize],
+        };
+        if walk.index < walk.end && order > 0 {
+            walk.descend(order - 1);
+        }
+        walk
+    }
+
+    /// Recompute the coordinate bits of levels `top` and below for the current index.
+    fn descend(&mut self, top: u32) {
+        let mask = (1u32 << self.dimension) - 1;
+        let mut state = self.states[top as usize];
+        for level in (0..=top).rev() {
+            let digit = (self.index >> (level * self.dimension)) & mask;
+            let (cell, next) = (self.step)(state, digit);
+            for (axis, coord) in self.coords.iter_mut().enumerate() {
+                *coord = (*coord & !(1 << level)) | (((cell >> axis) & 1) << level);
+            }
+            if level > 0 {
+                self.states[level as usize - 1] = next;
+            }
+            state = next;
+        }
+    }
+}
+
+impl<S, F> Iterator for Walk<S, F>
+where
+    S: Copy,
+    F: Fn(S, u32) -> (u32, S),
+{
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        if self.index >= self.end {
+            return None;
+        }
+        let point = Point::new(self.coords.clone());
+        self.index += 1;
+        if self.index < self.end {
+            let changed = self.index ^ (self.index - 1);
+            self.descend((31 - changed.leading_zeros()) / self.dimension);
+        }
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end.saturating_sub(self.index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<S, F> ExactSizeIterator for Walk<S, F>
+where
+    S: Copy,
+    F: Fn(S, u32) -> (u32, S),
+{
+}
+
+/// State table of the registered curve `name` on a `dimension`-D grid of side `size`.
+///
+/// Returns `Ok(None)` for curves that have no table form.
+pub fn state_table(name: &str, dimension: u32, size: u32) -> error::Result<Option<StateTable>> {
+    Ok(match name {
+        "hilbert" => Some(Hilbert::from_dimensions(dimension, size)?.state_table()),
+        "zorder" => ZOrder::from_dimensions(dimension, size)?.state_table(),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_walk_like_the_curves() {
+        for name in ["hilbert", "zorder"] {
+            for (dimension, size) in [(2, 16), (3, 8), (4, 4)] {
+                let table = state_table(name, dimension, size).unwrap().unwrap();
+                let curve = crate::curve_from_name(name, dimension, size).unwrap();
+                for index in 0..curve.length() {
+                    assert_eq!(
+                        table.point(index).as_slice(),
+                        &curve.point(index)[..],
+                        "{name} {dimension}D size {size} at {index}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn walks_match_lookups() {
+        for name in ["hilbert", "zorder"] {
+            for (dimension, size) in [(2, 16), (3, 8), (5, 2)] {
+                let table = state_table(name, dimension, size).unwrap().unwrap();
+                let length = size.pow(dimension);
+                for range in [0..length, 3..length - 2, 5..5] {
+                    let walked: Vec<_> = table.points(range.clone()).collect();
+                    let looked_up: Vec<_> = range.map(|i| Point::new(table.point(i))).collect();
+                    assert_eq!(walked, looked_up, "{name} {dimension}D size {size}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn table_sizes() {
+        assert_eq!(state_table("zorder", 3, 8).unwrap().unwrap().states(), 1);
+        assert_eq!(state_table("hilbert", 2, 8).unwrap().unwrap().states(), 4);
+        assert!(state_table("onion", 3, 8).unwrap().is_none());
+        assert!(state_table("hilbert", 3, 6).is_err());
+    }
+}
@@ -0,0 +1,406 @@
+//! Symmetries of existing curves.
+//!
+//! A grid can be mirrored along any axis and have its axes permuted without moving any cell
+//! off the grid. Applying such a symmetry to every point of a curve gives another curve over
+//! the same grid: a rotated or reflected Hilbert curve, say, or a Z-order curve interleaving
+//! its axes in a different order. [`Transform`] describes one symmetry, built from axis
+//! permutations, mirrors and quarter turns, and [`TransformedCurve`] applies it to any
+//! [`SpaceCurve`] while keeping the index↔point bijection intact.
+//!
+//! A transform sends the point `p` of the inner curve to `q`, where `q[i]` is
+//! `p[axes[i]]`, counted from the far end of its axis when axis `i` is mirrored.
+
+use std::ops::Range;
+
+use smallvec::SmallVec;
+
+use crate::{
+    error::{self, Error},
+    point::Point,
+    spacecurve::SpaceCurve,
+};
+
+/// A symmetry of a grid: an axis permutation followed by per-axis mirroring.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Transform {
+    /// Inner axis feeding each output axis.
+    axes: SmallVec<[usize; 4]>,
+    /// Whether each output axis is mirrored.
+    mirrored: SmallVec<[bool; 4]>,
+}
+
+impl Transform {
+    /// The transform leaving every point of a `dimension`-dimensional grid in place.
+    pub fn identity(dimension: u32) -> Self {
+        let dimension = dimension as usize;
+        Self {
+            axes: (0..dimension).collect(),
+            mirrored: SmallVec::from_elem(false, dimension),
+        }
+    }
+
+    /// The transform taking output axis `i` from inner axis `axes[i]`.
+    ///
+    /// Fails unless `axes` holds every axis index below its length exactly once.
+    pub fn permutation(axes: &[usize]) -> error::Result<Self> {
+        let mut seen = vec![false; axes.len()];
+        for &axis in axes {
+            if axis >= axes.len() || seen[axis] {
+                return Err(Error::Shape(format!(
+                    "{axes:?} is not a permutation of the axes 0..{}",
+                    axes.len()
+                )));
+            }
+            seen[axis] = true;
+        }
+        Ok(Self {
+            axes: axes.into(),
+            mirrored: SmallVec::from_elem(false, axes.len()),
+        })
+    }
+
+    /// Every symmetry of a `dimension`-dimensional grid, `2^dimension * dimension!` in all.
+    ///
+    /// Permutations come in lexicographic order, each with every combination of mirrored
+    /// axes; the identity is first. A square has 8 symmetries and a cube 48.
+    pub fn all(dimension: u32) -> Vec<Self> {
+        let mut permutations = Vec::new();
+        permute(
+            &mut (0..dimension as usize).collect::<Vec<_>>(),
+            0,
+            &mut permutations,
+        );
+        permutations.sort();
+        let mut all = Vec::with_capacity(permutations.len() << dimension);
+        for axes in permutations {
+            for mask in 0..1u32 << dimension {
+                all.push(Self {
+                    mirrored: (0..axes.len()).map(|axis| mask >> axis & 1 == 1).collect(),
+                    axes: axes.as_slice().into(),
+                });
+            }
+        }
+        all
+    }
+
+    /// Number of axes the transform acts on.
+    pub fn dimension(&self) -> u32 {
+        self.axes.len() as u32
+    }
+
+    /// This transform followed by `then`.
+    ///
+    /// Fails when the two act on different numbers of axes.
+    pub fn then(&self, then: &Self) -> error::Result<Self> {
+        if then.axes.len() != self.axes.len() {
+            return Err(Error::Shape(format!(
+                "cannot compose {}-dimensional and {}-dimensional transforms",
+                self.axes.len(),
+                then.axes.len()
+            )));
+        }
+        Ok(Self {
+            axes: then.axes.iter().map(|&axis| self.axes[axis]).collect(),
+            mirrored: then
+                .axes
+                .iter()
+                .zip(&then.mirrored)
+                .map(|(&axis, &mirror)| mirror != self.mirrored[axis])
+                .collect(),
+        })
+    }
+
+    /// This transform followed by mirroring along `axis`.
+    pub fn mirror(mut self, axis: usize) -> error::Result<Self> {
+        self.check_axis(axis)?;
+        self.mirrored[axis] = !self.mirrored[axis];
+        Ok(self)
+    }
+
+    /// This transform followed by a quarter turn in the plane of axes `from` and `to`, carrying
+    /// the positive direction of `from` onto that of `to`.
+    ///
+    /// In 2D, `rotate(0, 1)` turns the grid anticlockwise with `y` pointing up.
+    pub fn rotate(self, from: usize, to: usize) -> error::Result<Self> {
+        self.check_axis(from)?;
+        self.check_axis(to)?;
+        if from == to {
+            return Err(Error::Shape(format!(
+                "a rotation needs two distinct axes, got {from} twice"
+            )));
+        }
+        let mut turn = Self::identity(self.dimension());
+        turn.axes.swap(from, to);
+        turn.mirrored[from] = true;
+        self.then(&turn)
+    }
+
+    /// Fail unless `axis` is one of the transform's axes.
+    fn check_axis(&self, axis: usize) -> error::Result<()> {
+        if axis >= self.axes.len() {
+            return Err(Error::Shape(format!(
+                "axis {axis} is out of range for a {}-dimensional transform",
+                self.axes.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Side of each output axis, given the side of each inner axis.
+    fn sides(&self, inner: &[u32]) -> Vec<u32> {
+        self.axes.iter().map(|&axis| inner[axis]).collect()
+    }
+
+    /// The transformed position of the inner point `p` on a grid with `inner` sides.
+    fn forward(&self, inner: &[u32], p: &Point) -> Point {
+        Point::new(
+            self.axes
+                .iter()
+                .zip(&self.mirrored)
+                .map(|(&axis, &mirror)| flip(p[axis], inner[axis], mirror))
+                .collect::<SmallVec<[u32; 4]>>(),
+        )
+    }
+
+    /// The inner point whose transformed position is `q`, on a grid with `inner` sides.
+    fn inverse(&self, inner: &[u32], q: &Point) -> Point {
+        let mut p = SmallVec::<[u32; 4]>::from_elem(0, self.axes.len());
+        for (i, (&axis, &mirror)) in self.axes.iter().zip(&self.mirrored).enumerate() {
+            p[axis] = flip(q[i], inner[axis], mirror);
+        }
+        Point::new(p)
+    }
+}
+
+/// `coord` counted from the far end of an axis with `side` cells when `mirror` is set.
+fn flip(coord: u32, side: u32, mirror: bool) -> u32 {
+    if mirror { side - 1 - coord } else { coord }
+}
+
+/// Push every ordering of `axes[start..]` onto `out`.
+fn permute(axes: &mut [usize], start: usize, out: &mut Vec<Vec<usize>>) {
+    if start == axes.len() {
+        out.push(axes.to_vec());
+        return;
+    }
+    for i in start..axes.len() {
+        axes.swap(start, i);
+        permute(axes, start + 1, out);
+        axes.swap(start, i);
+    }
+}
+
+/// A curve with a [`Transform`] applied to every point.
+#[derive(Debug)]
+pub struct TransformedCurve<C> {
+    /// The wrapped curve.
+    curve: C,
+    /// The symmetry applied to the wrapped curve's points.
+    transform: Transform,
+    /// Side of each axis of the wrapped curve's grid.
+    inner_sides: Vec<u32>,
+}
+
+impl<C: SpaceCurve> TransformedCurve<C> {
+    /// Wrap `curve`, moving each of its points by `transform`.
+    ///
+    /// Fails when the transform acts on a different number of axes than the curve has.
+    pub fn new(curve: C, transform: Transform) -> error::Result<Self> {
+        if transform.dimension() != curve.dimensions() {
+            return Err(Error::Shape(format!(
+                "{}-dimensional transform for a {}-dimensional curve",
+                transform.dimension(),
+                curve.dimensions()
+            )));
+        }
+        Ok(Self {
+            inner_sides: curve.sides(),
+            curve,
+            transform,
+        })
+    }
+
+    /// The wrapped curve.
+    pub fn inner(&self) -> &C {
+        &self.curve
+    }
+
+    /// The symmetry applied to the wrapped curve.
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    /// Unwrap the curve, discarding the transform.
+    pub fn into_inner(self) -> C {
+        self.curve
+    }
+
+    /// The transformed position of the wrapped curve's point `p`.
+    fn forward(&self, p: &Point) -> Point {
+        self.transform.forward(&self.inner_sides, p)
+    }
+
+    /// The wrapped curve's point at the transformed position `q`.
+    fn inverse(&self, q: &Point) -> Point {
+        self.transform.inverse(&self.inner_sides, q)
+    }
+}
+
+impl<C: SpaceCurve> SpaceCurve for TransformedCurve<C> {
+    fn name(&self) -> &'static str {
+        self.curve.name()
+    }
+
+    fn info(&self) -> &'static str {
+        self.curve.info()
+    }
+
+    fn index(&self, p: &Point) -> u32 {
+        self.curve.index(&self.inverse(p))
+    }
+
+    fn point(&self, index: u32) -> Point {
+        self.forward(&self.curve.point(index))
+    }
+
+    fn length(&self) -> u32 {
+        self.curve.length()
+    }
+
+    fn dimensions(&self) -> u32 {
+        self.curve.dimensions()
+    }
+
+    fn index_u64(&self, p: &Point) -> u64 {
+        self.curve.index_u64(&self.inverse(p))
+    }
+
+    fn point_u64(&self, index: u64) -> error::Result<Point> {
+        Ok(self.forward(&self.curve.point_u64(index)?))
+    }
+
+    fn length_u64(&self) -> u64 {
+        self.curve.length_u64()
+    }
+
+    fn sides(&self) -> Vec<u32> {
+        self.transform.sides(&self.inner_sides)
+    }
+
+    // Mirrors and axis permutations carry aligned blocks onto aligned blocks.
+    fn nested_blocks(&self) -> bool {
+        self.curve.nested_blocks()
+    }
+
+    fn next_point(&self, p: &Point) -> Option<Point> {
+        self.curve
+            .next_point(&self.inverse(p))
+            .map(|next| self.forward(&next))
+    }
+
+    fn prev_point(&self, p: &Point) -> Option<Point> {
+        self.curve
+            .prev_point(&self.inverse(p))
+            .map(|prev| self.forward(&prev))
+    }
+
+    fn index_many_into(&self, points: &[Point], out: &mut [u32]) {
+        let inner: Vec<Point> = points.iter().map(|q| self.inverse(q)).collect();
+        self.curve.index_many_into(&inner, out);
+    }
+
+    fn point_many_into(&self, indices: &[u32], out: &mut [Point]) {
+        self.curve.point_many_into(indices, out);
+        for p in out {
+            *p = self.forward(p);
+        }
+    }
+
+    fn iter_range(&self, range: Range<u32>) -> Box<dyn Iterator<Item = Point> + '_> {
+        Box::new(self.curve.iter_range(range).map(|p| self.forward(&p)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{curve_from_name, curve_from_name_rect};
+
+    #[test]
+    fn square_has_eight_distinct_hilbert_symmetries() {
+        let transforms = Transform::all(2);
+        assert_eq!(transforms.len(), 8);
+        assert_eq!(transforms[0], Transform::identity(2));
+        assert_eq!(Transform::all(3).len(), 48);
+
+        let mut orders = HashSet::new();
+        for transform in transforms {
+            let curve = TransformedCurve::new(curve_from_name("hilbert", 2, 8).unwrap(), transform)
+                .unwrap();
+            let order: Vec<Point> = curve.iter_range(0..curve.length()).collect();
+            for (index, p) in order.iter().enumerate() {
+                assert_eq!(curve.point(index as u32), *p);
+                assert_eq!(curve.index(p), index as u32);
+            }
+            assert!(
+                order
+                    .windows(2)
+                    .all(|pair| pair[0].distance(&pair[1]) == 1.0)
+            );
+            orders.insert(order);
+        }
+        assert_eq!(orders.len(), 8);
+    }
+
+    #[test]
+    fn rotations_and_mirrors_move_points() {
+        let quarter = Transform::identity(2).rotate(0, 1).unwrap();
+        let curve =
+            TransformedCurve::new(curve_from_name("scan", 2, 4).unwrap(), quarter.clone()).unwrap();
+        // Scan starts along x from the origin; turned anticlockwise it starts up the y axis
+        // from the bottom-right corner.
+        assert_eq!(curve.point(0), Point::new(vec![3, 0]));
+        assert_eq!(curve.point(1), Point::new(vec![3, 1]));
+
+        let full = (0..3).fold(quarter.clone(), |t, _| t.then(&quarter).unwrap());
+        assert_eq!(full, Transform::identity(2));
+        let twice = Transform::identity(2).mirror(1).unwrap().mirror(1).unwrap();
+        assert_eq!(twice, Transform::identity(2));
+    }
+
+    #[test]
+    fn permutations_carry_rectangular_sides() {
+        let transform = Transform::permutation(&[1, 0]).unwrap().mirror(0).unwrap();
+        let curve =
+            TransformedCurve::new(curve_from_name_rect("scan", &[4, 2]).unwrap(), transform)
+                .unwrap();
+        assert_eq!(curve.sides(), [2, 4]);
+        let cells: HashSet<Point> = curve.iter_range(0..curve.length()).collect();
+        assert_eq!(cells.len(), 8);
+        assert!(cells.iter().all(|p| p[0] < 2 && p[1] < 4));
+        let p = curve.point(5);
+        assert_eq!(curve.point_many(&[5, 0]), [p.clone(), curve.point(0)]);
+        assert_eq!(curve.index_many(&[p.clone(), curve.point(7)]), [5, 7]);
+        assert_eq!(curve.next_point(&p), Some(curve.point(6)));
+        assert_eq!(curve.prev_point(&p), Some(curve.point(4)));
+    }
+
+    #[test]
+    fn rejects_bad_shapes() {
+        assert!(Transform::permutation(&[0, 0]).is_err());
+        assert!(Transform::permutation(&[0, 2]).is_err());
+        assert!(Transform::identity(2).mirror(2).is_err());
+        assert!(Transform::identity(2).rotate(1, 1).is_err());
+        assert!(matches!(
+            Transform::identity(2).then(&Transform::identity(3)),
+            Err(Error::Shape(_))
+        ));
+        let curve = curve_from_name("hilbert", 3, 4).unwrap();
+        assert!(matches!(
+            TransformedCurve::new(curve, Transform::identity(2)),
+            Err(Error::Shape(_))
+        ));
+    }
+}
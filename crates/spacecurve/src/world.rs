@@ -0,0 +1,317 @@
+//! Mapping between world coordinates and curve indices.
+//!
+//! Curves address integer grid cells, while applications work in their own units: degrees of
+//! latitude, metres across a sensor, or the bounds of a simulation domain. A [`WorldMap`]
+//! binds a curve to a rectangular region of that space, quantising world coordinates to grid
+//! cells and turning cells back into world coordinates.
+//!
+//! Two conventions for placing the grid are supported, as in raster georeferencing. With
+//! [`Registration::Cell`] each cell covers an equal share of the region and stands for its
+//! centre. With [`Registration::Node`] the grid points themselves sit evenly from the minimum
+//! to the maximum of each axis, both included. [`Rounding`] picks which grid point a
+//! coordinate between two of them is assigned to.
+
+use std::ops::Range;
+
+use crate::{
+    SpaceCurve,
+    error::{self, Error},
+    point::Point,
+};
+
+/// How a world coordinate between two grid points is assigned to one of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// The closest grid point, rounding halfway cases up. Under [`Registration::Cell`] this is
+    /// the cell containing the coordinate.
+    #[default]
+    Nearest,
+    /// The closest grid point at or below the coordinate.
+    Floor,
+    /// The closest grid point at or above the coordinate.
+    Ceil,
+}
+
+impl Rounding {
+    /// Round a continuous grid coordinate.
+    fn apply(self, t: f64) -> f64 {
+        match self {
+            Self::Nearest => (t + 0.5).floor(),
+            Self::Floor => t.floor(),
+            Self::Ceil => t.ceil(),
+        }
+    }
+}
+
+/// Where the grid points sit within the world region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Registration {
+    /// The region is split into equal cells, each represented by its centre.
+    #[default]
+    Cell,
+    /// Grid points are spaced evenly from the minimum to the maximum, both included.
+    Node,
+}
+
+/// A curve bound to a rectangular region of world space.
+pub struct WorldMap {
+    /// The curve ordering the grid.
+    curve: Box<dyn SpaceCurve>,
+    /// Grid points along each axis.
+    sides: Vec<u32>,
+    /// Minimum and maximum world coordinate of each axis.
+    extents: Vec<Range<f64>>,
+    /// World distance between neighbouring grid points along each axis.
+    resolution: Vec<f64>,
+    /// World coordinate of the first grid point along each axis.
+    origin: Vec<f64>,
+    /// How coordinates are quantised.
+    rounding: Rounding,
+    /// Whether coordinates outside the extents are moved onto the nearest edge.
+    clamp: bool,
+}
+
+impl WorldMap {
+    /// Bind `curve` to `extents`, one `min..max` range per axis, with cell registration.
+    ///
+    /// Fails when the number of extents differs from the curve's dimension, or an extent is
+    /// not finite or has its maximum at or below its minimum, or the curve has more than
+    /// `u32::MAX` points.
+    pub fn new(curve: Box<dyn SpaceCurve>, extents: &[Range<f64>]) -> error::Result<Self> {
+        Self::with_registration(curve, extents, Registration::default())
+    }
+
+    /// Bind `curve` to `extents` with the given grid registration.
+    pub fn with_registration(
+        curve: Box<dyn SpaceCurve>,
+        extents: &[Range<f64>],
+        registration: Registration,
+    ) -> error::Result<Self> {
+        let dimension = curve.dimensions();
+        if extents.len() != dimension as usize {
+            return Err(Error::Shape(format!(
+                "{} extents given for a {dimension}-dimensional curve",
+                extents.len()
+            )));
+        }
+        for (axis, extent) in extents.iter().enumerate() {
+            if !(extent.start.is_finite() && extent.end.is_finite() && extent.start < extent.end) {
+                return Err(Error::Size(format!(
+                    "extent {}..{} of axis {axis} is empty or not finite",
+                    extent.start, extent.end
+                )));
+            }
+        }
+        curve.try_length()?;
+        let sides = curve.sides();
+        let (resolution, origin) = extents
+            .iter()
+            .zip(&sides)
+            .map(|(extent, &side)| {
+                let span = extent.end - extent.start;
+                match registration {
+                    Registration::Cell => {
+                        let step = span / f64::from(side);
+                        (step, extent.start + step / 2.0)
+                    }
+                    Registration::Node if side == 1 => (span, extent.start),
+                    Registration::Node => (span / f64::from(side - 1), extent.start),
+                }
+            })
+            .unzip();
+        Ok(Self {
+            curve,
+            sides,
+            extents: extents.to_vec(),
+            resolution,
+            origin,
+            rounding: Rounding::default(),
+            clamp: false,
+        })
+    }
+
+    /// Quantise coordinates with `rounding`.
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Move coordinates outside the extents onto the nearest edge instead of rejecting them.
+    pub fn clamp(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// The curve ordering the grid.
+    pub fn curve(&self) -> &dyn SpaceCurve {
+        self.curve.as_ref()
+    }
+
+    /// Minimum and maximum world coordinate of each axis.
+    pub fn extents(&self) -> &[Range<f64>] {
+        &self.extents
+    }
+
+    /// World distance between neighbouring grid points along each axis.
+    pub fn resolution(&self) -> &[f64] {
+        &self.resolution
+    }
+
+    /// The grid cell holding the world position `coords`.
+    ///
+    /// Fails when the number of coordinates differs from the curve's dimension, or a
+    /// coordinate is not finite or, unless clamping, lies outside its extent.
+    pub fn cell_for_world(&self, coords: &[f64]) -> error::Result<Point> {
+        if coords.len() != self.extents.len() {
+            return Err(Error::Shape(format!(
+                "{} coordinates given for a {}-dimensional map",
+                coords.len(),
+                self.extents.len()
+            )));
+        }
+        let mut cell = Vec::with_capacity(coords.len());
+        for (axis, &x) in coords.iter().enumerate() {
+            let extent = &self.extents[axis];
+            if x.is_nan() || (!self.clamp && !(extent.start..=extent.end).contains(&x)) {
+                return Err(Error::Size(format!(
+                    "coordinate {x} of axis {axis} is outside {}..{}",
+                    extent.start, extent.end
+                )));
+            }
+            let t = (x - self.origin[axis]) / self.resolution[axis];
+            let max = f64::from(self.sides[axis] - 1);
+            cell.push(self.rounding.apply(t).clamp(0.0, max) as u32);
+        }
+        Ok(Point::new(cell))
+    }
+
+    /// The curve index of the grid cell holding the world position `coords`.
+    ///
+    /// Fails as [`Self::cell_for_world`] does.
+    pub fn index_for_world(&self, coords: &[f64]) -> error::Result<u32> {
+        Ok(self.curve.index(&self.cell_for_world(coords)?))
+    }
+
+    /// The world position of the grid point `cell`.
+    pub fn world_for_cell(&self, cell: &Point) -> Vec<f64> {
+        cell.iter()
+            .zip(self.origin.iter().zip(&self.resolution))
+            .map(|(&c, (&origin, &step))| origin + f64::from(c) * step)
+            .collect()
+    }
+
+    /// The world position of the grid point at curve index `index`.
+    pub fn world_for_index(&self, index: u32) -> Vec<f64> {
+        self.world_for_cell(&self.curve.point(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve_from_name;
+
+    fn hilbert(size: u32, extents: &[Range<f64>]) -> WorldMap {
+        WorldMap::new(curve_from_name("hilbert", 2, size).unwrap(), extents).unwrap()
+    }
+
+    #[test]
+    fn cells_cover_equal_shares_and_map_to_centres() {
+        let map = hilbert(4, &[0.0..8.0, -1.0..1.0]);
+        assert_eq!(map.resolution(), &[2.0, 0.5]);
+        let curve = curve_from_name("hilbert", 2, 4).unwrap();
+        assert_eq!(
+            map.cell_for_world(&[0.0, -1.0]).unwrap(),
+            Point::new(vec![0, 0])
+        );
+        assert_eq!(
+            map.cell_for_world(&[3.9, 0.1]).unwrap(),
+            Point::new(vec![1, 2])
+        );
+        // The maximum edge belongs to the last cell.
+        assert_eq!(
+            map.cell_for_world(&[8.0, 1.0]).unwrap(),
+            Point::new(vec![3, 3])
+        );
+        assert_eq!(
+            map.index_for_world(&[3.9, 0.1]).unwrap(),
+            curve.index(&Point::new(vec![1, 2]))
+        );
+        for index in 0..curve.length() {
+            let world = map.world_for_index(index);
+            let cell = curve.point(index);
+            assert_eq!(world[0], 1.0 + 2.0 * f64::from(cell[0]));
+            assert_eq!(map.index_for_world(&world).unwrap(), index);
+        }
+    }
+
+    #[test]
+    fn nodes_span_the_extents() {
+        let curve = curve_from_name("scan", 1, 5).unwrap();
+        let map = WorldMap::with_registration(curve, &[10.0..20.0], Registration::Node).unwrap();
+        assert_eq!(map.resolution(), &[2.5]);
+        assert_eq!(map.world_for_index(0), [10.0]);
+        assert_eq!(map.world_for_index(4), [20.0]);
+        assert_eq!(map.index_for_world(&[13.0]).unwrap(), 1);
+        assert_eq!(map.index_for_world(&[14.0]).unwrap(), 2);
+    }
+
+    #[test]
+    fn rounding_picks_neighbouring_points() {
+        let node = |rounding| {
+            let curve = curve_from_name("scan", 1, 5).unwrap();
+            WorldMap::with_registration(curve, &[0.0..4.0], Registration::Node)
+                .unwrap()
+                .rounding(rounding)
+        };
+        assert_eq!(node(Rounding::Nearest).index_for_world(&[1.5]).unwrap(), 2);
+        assert_eq!(node(Rounding::Floor).index_for_world(&[1.9]).unwrap(), 1);
+        assert_eq!(node(Rounding::Ceil).index_for_world(&[1.1]).unwrap(), 2);
+        // Cell edges stay inside the grid whichever way they round.
+        let cells = hilbert(4, &[0.0..4.0, 0.0..4.0]).rounding(Rounding::Floor);
+        assert_eq!(
+            cells.cell_for_world(&[0.2, 4.0]).unwrap(),
+            Point::new(vec![0, 3])
+        );
+    }
+
+    #[test]
+    fn rejects_or_clamps_outside_coordinates() {
+        let map = hilbert(4, &[0.0..1.0, 0.0..1.0]);
+        assert!(map.index_for_world(&[1.5, 0.5]).is_err());
+        assert!(map.index_for_world(&[f64::NAN, 0.5]).is_err());
+        assert!(map.index_for_world(&[0.5]).is_err());
+        let map = map.clamp(true);
+        assert_eq!(
+            map.cell_for_world(&[1.5, -3.0]).unwrap(),
+            Point::new(vec![3, 0])
+        );
+        assert!(map.index_for_world(&[f64::NAN, 0.5]).is_err());
+    }
+
+    #[test]
+    fn rectangular_grids_get_per_axis_resolution() {
+        let curve = crate::curve_from_name_rect("zorder", &[8, 2]).unwrap();
+        let map = WorldMap::new(curve, &[0.0..8.0, 0.0..8.0]).unwrap();
+        assert_eq!(map.resolution(), &[1.0, 4.0]);
+        assert_eq!(
+            map.cell_for_world(&[7.5, 7.5]).unwrap(),
+            Point::new(vec![7, 1])
+        );
+    }
+
+    #[test]
+    fn rejects_bad_extents() {
+        let curve = || curve_from_name("zorder", 2, 4).unwrap();
+        assert!(WorldMap::new(curve(), &[0.0..1.0]).is_err());
+        assert!(WorldMap::new(curve(), &[0.0..1.0, 1.0..1.0]).is_err());
+        assert!(WorldMap::new(curve(), &[0.0..f64::INFINITY, 0.0..1.0]).is_err());
+    }
+
+    #[test]
+    fn rejects_wide_curves() {
+        let wide = curve_from_name("hilbert", 2, 1 << 16).unwrap();
+        let map = WorldMap::new(wide, &[0.0..1.0, 0.0..1.0]);
+        assert!(matches!(map, Err(Error::Size(_))));
+    }
+}
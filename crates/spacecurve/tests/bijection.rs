@@ -200,6 +200,10 @@ fn exhaustive_bijection_small_curves() {
         ("onion", 2, 4),
         ("hairyonion", 2, 4),
         ("gray", 2, 4),
+        ("peano", 2, 9),
+        ("peano", 3, 3),
+        ("moore", 2, 8),
+        ("sierpinski", 2, 8),
     ];
 
     for (name, dim, size) in small_configs {
@@ -228,6 +232,7 @@ fn all_registered_curves_satisfy_bijection() {
                 "hilbert" | "zorder" | "gray" => (name, 2, 4),
                 "hcurve" => (name, 2, 4), // hcurve requires dim >= 2
                 "scan" | "onion" | "hairyonion" => (name, 2, 4),
+                "peano" => (name, 2, 9),
                 _ => (name, 2, 4), // fallback
             }
         })
@@ -31,6 +31,25 @@ mod tests {
         }
     }
 
+    fn pattern_steps(pattern_name: &str, p: &dyn SpaceCurve) {
+        let last = p.length() - 1;
+        for off in 0..=last {
+            let pt = p.point(off);
+            let next = (off < last).then(|| p.point(off + 1));
+            let prev = off.checked_sub(1).map(|i| p.point(i));
+            assert_eq!(
+                p.next_point(&pt),
+                next,
+                "Pattern {pattern_name} next of {off}"
+            );
+            assert_eq!(
+                p.prev_point(&pt),
+                prev,
+                "Pattern {pattern_name} prev of {off}"
+            );
+        }
+    }
+
     macro_rules! curve_tests {
         ($(($pattern:expr, $dims:expr, $size:expr, $reflection:expr, $continuous:expr)),* $(,)?) => {
             $(
@@ -52,6 +71,13 @@ mod tests {
                         }
                         Ok(())
                     }
+
+                    #[test]
+                    fn [<$pattern _steps_ $dims d_ $size>]() -> error::Result<()> {
+                        let curve = curve_from_name($pattern, $dims, $size)?;
+                        pattern_steps(&format!("{}({},{})", $pattern, $dims, $size), curve.as_ref());
+                        Ok(())
+                    }
                 }
             )*
         };
@@ -61,6 +87,7 @@ mod tests {
         ("hilbert", 2, 4, true, true),
         ("hilbert", 3, 4, true, true),
         ("hilbert", 4, 2, true, true),
+        ("hilbert", 2, 32, true, true),
         ("hcurve", 2, 4, true, true),
         // ("hcurve", 3, 4, true, true),
         // ("hcurve", 3, 8, true, true),
@@ -68,6 +95,7 @@ mod tests {
         ("scan", 2, 4, true, true),
         ("scan", 3, 4, true, true),
         ("scan", 4, 2, true, true),
+        ("scan", 3, 5, true, true),
         ("zorder", 2, 4, true, false),
         ("zorder", 3, 4, true, false),
         ("zorder", 4, 2, true, false),
@@ -80,6 +108,13 @@ mod tests {
         ("gray", 2, 4, true, false),
         ("gray", 3, 4, true, false),
         ("gray", 4, 2, true, false),
+        ("peano", 2, 9, true, true),
+        ("peano", 3, 3, true, true),
+        ("peano", 1, 27, true, true),
+        ("moore", 2, 2, true, true),
+        ("moore", 2, 16, true, true),
+        ("sierpinski", 2, 1, true, true),
+        ("sierpinski", 2, 16, true, false),
     }
 
     #[test]
@@ -0,0 +1,52 @@
+//! Golden fingerprints pinning every registered curve's mapping.
+//!
+//! A failure here means a curve now maps some index to a different point. If the change is
+//! intended, update the value and invalidate anything keyed on the old fingerprint.
+#[cfg(test)]
+mod tests {
+    use spacecurve::{curve_from_name, fingerprint::fingerprint, registry::CURVE_NAMES};
+
+    /// Curve key, dimension, size and the fingerprint of that grid.
+    const GOLDEN: &[(&str, u32, u32, u64)] = &[
+        ("hilbert", 2, 8, 0xa0c1_8dfd_473f_2c47),
+        ("hilbert", 3, 4, 0x7325_ebd3_ba90_0026),
+        ("scan", 2, 8, 0xd4fc_721b_ccbf_0447),
+        ("scan", 3, 4, 0x5dce_a713_e266_1c26),
+        ("zorder", 2, 8, 0x112b_f64f_548c_9447),
+        ("zorder", 3, 4, 0x1895_9bbb_3b7c_4326),
+        ("hcurve", 2, 8, 0xe563_9a0d_f24e_c447),
+        ("hcurve", 3, 4, 0xd462_0dbf_faaa_fc26),
+        ("onion", 2, 8, 0x2da5_ee93_b323_3c07),
+        ("onion", 3, 4, 0x1987_7b2d_908c_8b86),
+        ("hairyonion", 2, 8, 0x2da5_ee93_b323_3c07),
+        ("hairyonion", 3, 4, 0x7839_3e03_3af6_8aa6),
+        ("gray", 2, 8, 0xf6cb_3526_ccf0_7d47),
+        ("gray", 3, 4, 0xd954_ca57_7991_a326),
+        ("peano", 2, 9, 0x9108_e783_eeea_3de6),
+        ("peano", 3, 3, 0x1ac4_1439_c459_beae),
+        ("moore", 2, 8, 0x9924_a915_0638_4447),
+        ("sierpinski", 2, 8, 0x8cda_bc68_3732_f247),
+    ];
+
+    #[test]
+    fn fingerprints_match_golden_values() {
+        for &(name, dimension, size, expected) in GOLDEN {
+            let curve = curve_from_name(name, dimension, size).unwrap();
+            let actual = fingerprint(curve.as_ref()).unwrap();
+            assert_eq!(
+                actual, expected,
+                "{name} {dimension}D size {size} changed: fingerprint is now {actual:#018x}"
+            );
+        }
+    }
+
+    #[test]
+    fn every_curve_is_pinned() {
+        for name in CURVE_NAMES {
+            assert!(
+                GOLDEN.iter().any(|(key, ..)| key == name),
+                "{name} has no golden fingerprint"
+            );
+        }
+    }
+}